@@ -0,0 +1,125 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::storage_cost::{
+    removal::{Identifier, StorageRemovedBytes, UNKNOWN_EPOCH},
+    StorageCost,
+};
+
+/// Bytes added and removed by a batch, grouped by the owner and the epoch
+/// they are paid or refunded to.
+#[derive(Debug, Default, PartialEq, Clone, Eq)]
+pub struct StorageFeeReport {
+    /// Bytes added, by owner id and epoch
+    pub added_bytes: BTreeMap<(Identifier, u16), u32>,
+    /// Bytes removed, by owner id and epoch
+    pub removed_bytes: BTreeMap<(Identifier, u16), u32>,
+}
+
+impl StorageFeeReport {
+    /// Adds `bytes` paid by `owner_id` in `epoch`.
+    pub fn add_added_bytes(&mut self, owner_id: Identifier, epoch: u16, bytes: u32) {
+        if bytes > 0 {
+            let entry = self.added_bytes.entry((owner_id, epoch)).or_default();
+            *entry = entry.saturating_add(bytes);
+        }
+    }
+
+    /// Adds the sections of `removed_bytes`. Basic removals are refunded to
+    /// no one in particular and are listed under the default identifier and
+    /// [`UNKNOWN_EPOCH`], like when they are added to sectioned removals.
+    pub fn add_removed_bytes(&mut self, removed_bytes: &StorageRemovedBytes) {
+        let mut add = |owner_id: Identifier, epoch: u16, bytes: u32| {
+            if bytes > 0 {
+                let entry = self.removed_bytes.entry((owner_id, epoch)).or_default();
+                *entry = entry.saturating_add(bytes);
+            }
+        };
+        match removed_bytes {
+            StorageRemovedBytes::NoStorageRemoval => {}
+            StorageRemovedBytes::BasicStorageRemoval(bytes) => {
+                add(Identifier::default(), UNKNOWN_EPOCH, *bytes)
+            }
+            StorageRemovedBytes::SectionedStorageRemoval(map) => {
+                for (owner_id, bytes_per_epoch) in map {
+                    for (epoch, bytes) in bytes_per_epoch.iter() {
+                        add(*owner_id, epoch, *bytes);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Total bytes added
+    pub fn total_added_bytes(&self) -> u32 {
+        self.added_bytes
+            .values()
+            .fold(0, |total, bytes| total.saturating_add(*bytes))
+    }
+
+    /// Total bytes removed
+    pub fn total_removed_bytes(&self) -> u32 {
+        self.removed_bytes
+            .values()
+            .fold(0, |total, bytes| total.saturating_add(*bytes))
+    }
+}
+
+/// A scheme of element flags telling who pays for the bytes of an element
+/// and in which epoch, used to build a [`StorageFeeReport`].
+///
+/// The first two functions are the ones a batch is applied with when flags
+/// are updated just in time, the last one attributes the bytes a batch adds
+/// to an element with the given flags.
+pub trait StorageFeeScheme {
+    /// Error returned when flags can't be understood
+    type Error: fmt::Display;
+
+    /// Updates `new_flags` of an element replacing one with `old_flags`,
+    /// `cost` being the storage cost of the replacement. Returns whether
+    /// the flags were changed.
+    fn update_element_flags(
+        cost: &StorageCost,
+        old_flags: Option<Vec<u8>>,
+        new_flags: &mut Vec<u8>,
+    ) -> Result<bool, Self::Error>;
+
+    /// Splits the key and value bytes removed from an element with `flags`
+    /// into the sections they are refunded to.
+    fn split_removal_bytes(
+        flags: &mut Vec<u8>,
+        removed_key_bytes: u32,
+        removed_value_bytes: u32,
+    ) -> Result<(StorageRemovedBytes, StorageRemovedBytes), Self::Error>;
+
+    /// Owner id and epoch paying for bytes added to an element with
+    /// `flags`, or `None` if the flags don't tell.
+    fn added_bytes_owner_and_epoch(flags: &[u8]) -> Option<(Identifier, u16)>;
+}
@@ -33,6 +33,8 @@ use crate::{
     storage_cost::removal::{StorageRemovedBytes, StorageRemovedBytes::NoStorageRemoval},
 };
 
+/// Storage fee reports
+pub mod fee_report;
 /// Key Value Storage Costs
 pub mod key_value_cost;
 /// Storage Removal
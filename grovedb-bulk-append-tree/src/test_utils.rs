@@ -136,6 +136,10 @@ impl<'db> StorageContext<'db> for MemStorageContext {
     fn raw_iter(&self) -> Self::RawIterator {
         unimplemented!("MemStorageContext::raw_iter")
     }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        unimplemented!("MemStorageContext::raw_iter_meta")
+    }
 }
 
 // ── Batch and RawIterator stubs ───────────────────────────────────────
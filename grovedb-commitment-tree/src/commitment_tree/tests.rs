@@ -277,6 +277,10 @@ mod storage_tests {
         fn raw_iter(&self) -> Self::RawIterator {
             StubRawIterator
         }
+
+        fn raw_iter_meta(&self) -> Self::RawIterator {
+            StubRawIterator
+        }
     }
 
     // ── Failing mock for error paths ────────────────────────────────────
@@ -398,6 +402,10 @@ mod storage_tests {
         fn raw_iter(&self) -> Self::RawIterator {
             StubRawIterator
         }
+
+        fn raw_iter_meta(&self) -> Self::RawIterator {
+            StubRawIterator
+        }
     }
 
     // ── Helpers ─────────────────────────────────────────────────────────
@@ -136,6 +136,10 @@ impl<'db> StorageContext<'db> for MemStorageContext {
     fn raw_iter(&self) -> Self::RawIterator {
         unimplemented!("MemStorageContext::raw_iter")
     }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        unimplemented!("MemStorageContext::raw_iter_meta")
+    }
 }
 
 /// Storage context that can be configured to fail on specific positions.
@@ -285,6 +289,10 @@ impl<'db> StorageContext<'db> for FailingStorageContext {
     fn raw_iter(&self) -> Self::RawIterator {
         unimplemented!()
     }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        unimplemented!()
+    }
 }
 
 // ── Batch and RawIterator stubs ───────────────────────────────────────
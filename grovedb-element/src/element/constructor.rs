@@ -2,7 +2,10 @@
 //! Functions for setting an element's type
 
 use crate::{
-    element::{BigSumValue, CountValue, Element, ElementFlags, MaxReferenceHop, SumValue},
+    element::{
+        BigSumValue, CountValue, Element, ElementFlags, ExpiryValue, MaxReferenceHop, MinMaxValue,
+        SumValue,
+    },
     error::ElementError,
     reference_path::ReferencePathType,
 };
@@ -92,6 +95,20 @@ impl Element {
         Element::ItemWithSumItem(item_value, sum_value, flags)
     }
 
+    /// Set element to an item expiring at `expiry` (no flags)
+    pub fn new_item_with_expiry(item_value: Vec<u8>, expiry: ExpiryValue) -> Self {
+        Element::ItemWithExpiry(item_value, expiry, None)
+    }
+
+    /// Set element to an item expiring at `expiry` with flags
+    pub fn new_item_with_expiry_and_flags(
+        item_value: Vec<u8>,
+        expiry: ExpiryValue,
+        flags: Option<ElementFlags>,
+    ) -> Self {
+        Element::ItemWithExpiry(item_value, expiry, flags)
+    }
+
     /// Set element to a reference without flags
     pub fn new_reference(reference_path: ReferencePathType) -> Self {
         Element::Reference(reference_path, None, None)
@@ -383,4 +400,111 @@ impl Element {
     pub fn new_dense_tree(count: u16, height: u8, flags: Option<ElementFlags>) -> Self {
         Element::DenseAppendOnlyFixedSizeTree(count, height, flags)
     }
+
+    /// Set element to a min max item without flags
+    pub fn new_min_max_item(value: MinMaxValue) -> Self {
+        Element::MinMaxItem(value, None)
+    }
+
+    /// Set element to a min max item with flags
+    pub fn new_min_max_item_with_flags(value: MinMaxValue, flags: Option<ElementFlags>) -> Self {
+        Element::MinMaxItem(value, flags)
+    }
+
+    /// Set element to default empty min max tree without flags
+    pub fn empty_min_max_tree() -> Self {
+        Element::new_min_max_tree(Default::default())
+    }
+
+    /// Set element to default empty min max tree with flags
+    pub fn empty_min_max_tree_with_flags(flags: Option<ElementFlags>) -> Self {
+        Element::new_min_max_tree_with_flags(Default::default(), flags)
+    }
+
+    /// Set element to a min max tree without flags
+    pub fn new_min_max_tree(maybe_root_key: Option<Vec<u8>>) -> Self {
+        Element::MinMaxTree(maybe_root_key, MinMaxValue::MAX, MinMaxValue::MIN, None)
+    }
+
+    /// Set element to a min max tree with flags
+    pub fn new_min_max_tree_with_flags(
+        maybe_root_key: Option<Vec<u8>>,
+        flags: Option<ElementFlags>,
+    ) -> Self {
+        Element::MinMaxTree(maybe_root_key, MinMaxValue::MAX, MinMaxValue::MIN, flags)
+    }
+
+    /// Set element to a min max tree with flags and its minimum and maximum
+    pub fn new_min_max_tree_with_flags_and_min_max_value(
+        maybe_root_key: Option<Vec<u8>>,
+        min: MinMaxValue,
+        max: MinMaxValue,
+        flags: Option<ElementFlags>,
+    ) -> Self {
+        Element::MinMaxTree(maybe_root_key, min, max, flags)
+    }
+
+    /// Set element to default empty provable sum tree without flags
+    pub fn empty_provable_sum_tree() -> Self {
+        Element::new_provable_sum_tree(Default::default())
+    }
+
+    /// Set element to default empty provable sum tree with flags
+    pub fn empty_provable_sum_tree_with_flags(flags: Option<ElementFlags>) -> Self {
+        Element::new_provable_sum_tree_with_flags(Default::default(), flags)
+    }
+
+    /// Set element to a provable sum tree without flags
+    pub fn new_provable_sum_tree(maybe_root_key: Option<Vec<u8>>) -> Self {
+        Element::ProvableSumTree(maybe_root_key, 0, None)
+    }
+
+    /// Set element to a provable sum tree with flags
+    pub fn new_provable_sum_tree_with_flags(
+        maybe_root_key: Option<Vec<u8>>,
+        flags: Option<ElementFlags>,
+    ) -> Self {
+        Element::ProvableSumTree(maybe_root_key, 0, flags)
+    }
+
+    /// Set element to a provable sum tree with flags and sum value
+    pub fn new_provable_sum_tree_with_flags_and_sum_value(
+        maybe_root_key: Option<Vec<u8>>,
+        sum_value: SumValue,
+        flags: Option<ElementFlags>,
+    ) -> Self {
+        Element::ProvableSumTree(maybe_root_key, sum_value, flags)
+    }
+
+    /// Set element to default empty provable big sum tree without flags
+    pub fn empty_provable_big_sum_tree() -> Self {
+        Element::new_provable_big_sum_tree(Default::default())
+    }
+
+    /// Set element to default empty provable big sum tree with flags
+    pub fn empty_provable_big_sum_tree_with_flags(flags: Option<ElementFlags>) -> Self {
+        Element::new_provable_big_sum_tree_with_flags(Default::default(), flags)
+    }
+
+    /// Set element to a provable big sum tree without flags
+    pub fn new_provable_big_sum_tree(maybe_root_key: Option<Vec<u8>>) -> Self {
+        Element::ProvableBigSumTree(maybe_root_key, 0, None)
+    }
+
+    /// Set element to a provable big sum tree with flags
+    pub fn new_provable_big_sum_tree_with_flags(
+        maybe_root_key: Option<Vec<u8>>,
+        flags: Option<ElementFlags>,
+    ) -> Self {
+        Element::ProvableBigSumTree(maybe_root_key, 0, flags)
+    }
+
+    /// Set element to a provable big sum tree with flags and sum value
+    pub fn new_provable_big_sum_tree_with_flags_and_sum_value(
+        maybe_root_key: Option<Vec<u8>>,
+        sum_value: BigSumValue,
+        flags: Option<ElementFlags>,
+    ) -> Self {
+        Element::ProvableBigSumTree(maybe_root_key, sum_value, flags)
+    }
 }
@@ -5,7 +5,7 @@ use grovedb_version::{check_grovedb_v0, version::GroveVersion};
 use integer_encoding::VarInt;
 
 use crate::{
-    element::{Element, ElementFlags},
+    element::{Element, ElementFlags, ExpiryValue},
     error::ElementError,
     reference_path::{path_from_reference_path_type, ReferencePathType},
 };
@@ -19,7 +19,8 @@ impl Element {
             | Element::ItemWithSumItem(_, sum_value, _)
             | Element::SumTree(_, sum_value, _)
             | Element::CountSumTree(_, _, sum_value, _)
-            | Element::ProvableCountSumTree(_, _, sum_value, _) => *sum_value,
+            | Element::ProvableCountSumTree(_, _, sum_value, _)
+            | Element::ProvableSumTree(_, sum_value, _) => *sum_value,
             _ => 0,
         }
     }
@@ -42,7 +43,8 @@ impl Element {
         match self {
             Element::SumItem(sum_value, _)
             | Element::ItemWithSumItem(_, sum_value, _)
-            | Element::SumTree(_, sum_value, _) => (1, *sum_value),
+            | Element::SumTree(_, sum_value, _)
+            | Element::ProvableSumTree(_, sum_value, _) => (1, *sum_value),
             Element::CountTree(_, count_value, _) => (*count_value, 0),
             Element::CountSumTree(_, count_value, sum_value, _)
             | Element::ProvableCountSumTree(_, count_value, sum_value, _) => {
@@ -61,12 +63,43 @@ impl Element {
             | Element::ItemWithSumItem(_, sum_value, _)
             | Element::SumTree(_, sum_value, _)
             | Element::CountSumTree(_, _, sum_value, _)
-            | Element::ProvableCountSumTree(_, _, sum_value, _) => *sum_value as i128,
-            Element::BigSumTree(_, sum_value, _) => *sum_value,
+            | Element::ProvableCountSumTree(_, _, sum_value, _)
+            | Element::ProvableSumTree(_, sum_value, _) => *sum_value as i128,
+            Element::BigSumTree(_, sum_value, _) | Element::ProvableBigSumTree(_, sum_value, _) => {
+                *sum_value
+            }
             _ => 0,
         }
     }
 
+    /// Decoded the minimum and maximum in the MinMaxItem and MinMaxTree
+    /// element types, returns `(i64::MAX, i64::MIN)`, which is no extreme at
+    /// all, for everything else
+    pub fn min_max_value_or_default(&self) -> (i64, i64) {
+        match self {
+            Element::MinMaxItem(value, _) => (*value, *value),
+            Element::MinMaxTree(_, min, max, _) => (*min, *max),
+            _ => (i64::MAX, i64::MIN),
+        }
+    }
+
+    /// Decoded the integer value in the MinMaxItem element type
+    pub fn as_min_max_item_value(&self) -> Result<i64, ElementError> {
+        match self {
+            Element::MinMaxItem(value, _) => Ok(*value),
+            _ => Err(ElementError::WrongElementType("expected a min max item")),
+        }
+    }
+
+    /// Decoded the minimum and maximum in the MinMaxTree element type, `None`
+    /// if the tree holds no min max items
+    pub fn as_min_max_tree_value(&self) -> Result<Option<(i64, i64)>, ElementError> {
+        match self {
+            Element::MinMaxTree(_, min, max, _) => Ok((min <= max).then_some((*min, *max))),
+            _ => Err(ElementError::WrongElementType("expected a min max tree")),
+        }
+    }
+
     /// Decoded the integer value in the SumItem element type
     pub fn as_sum_item_value(&self) -> Result<i64, ElementError> {
         match self {
@@ -105,7 +138,7 @@ impl Element {
     pub fn as_item_bytes(&self) -> Result<&[u8], ElementError> {
         match self {
             Element::Item(value, _) => Ok(value),
-            Element::ItemWithSumItem(value, ..) => Ok(value),
+            Element::ItemWithSumItem(value, ..) | Element::ItemWithExpiry(value, ..) => Ok(value),
             _ => Err(ElementError::WrongElementType("expected an item")),
         }
     }
@@ -114,7 +147,7 @@ impl Element {
     pub fn into_item_bytes(self) -> Result<Vec<u8>, ElementError> {
         match self {
             Element::Item(value, _) => Ok(value),
-            Element::ItemWithSumItem(value, ..) => Ok(value),
+            Element::ItemWithSumItem(value, ..) | Element::ItemWithExpiry(value, ..) => Ok(value),
             _ => Err(ElementError::WrongElementType("expected an item")),
         }
     }
@@ -137,11 +170,24 @@ impl Element {
         matches!(self, Element::BigSumTree(..))
     }
 
+    /// Check if the element is a provable sum tree or a provable big sum tree
+    pub fn is_provable_sum_tree(&self) -> bool {
+        matches!(
+            self,
+            Element::ProvableSumTree(..) | Element::ProvableBigSumTree(..)
+        )
+    }
+
     /// Check if the element is a tree but not a sum tree
     pub fn is_basic_tree(&self) -> bool {
         matches!(self, Element::Tree(..))
     }
 
+    /// Check if the element is a min max tree
+    pub fn is_min_max_tree(&self) -> bool {
+        matches!(self, Element::MinMaxTree(..))
+    }
+
     /// Check if the element is a tree
     pub fn is_any_tree(&self) -> bool {
         matches!(
@@ -157,6 +203,9 @@ impl Element {
                 | Element::MmrTree(..)
                 | Element::BulkAppendTree(..)
                 | Element::DenseAppendOnlyFixedSizeTree(..)
+                | Element::MinMaxTree(..)
+                | Element::ProvableSumTree(..)
+                | Element::ProvableBigSumTree(..)
         )
     }
 
@@ -228,6 +277,9 @@ impl Element {
                 | Element::CountSumTree(Some(_), ..)
                 | Element::ProvableCountTree(Some(_), ..)
                 | Element::ProvableCountSumTree(Some(_), ..)
+                | Element::MinMaxTree(Some(_), ..)
+                | Element::ProvableSumTree(Some(_), ..)
+                | Element::ProvableBigSumTree(Some(_), ..)
                 | Element::CommitmentTree(..)
                 | Element::MmrTree(..)
                 | Element::BulkAppendTree(..)
@@ -238,9 +290,10 @@ impl Element {
     /// Check if the element is a non-empty Merk-backed tree.
     ///
     /// Returns true only for standard Merk trees (Tree, SumTree, BigSumTree,
-    /// CountTree, CountSumTree, ProvableCountTree, ProvableCountSumTree)
-    /// with a `Some(_)` root key. Excludes non-Merk tree types (MmrTree,
-    /// BulkAppendTree, CommitmentTree, DenseAppendOnlyFixedSizeTree).
+    /// CountTree, CountSumTree, ProvableCountTree, ProvableCountSumTree,
+    /// MinMaxTree, ProvableSumTree, ProvableBigSumTree) with a `Some(_)` root
+    /// key. Excludes non-Merk tree types (MmrTree, BulkAppendTree,
+    /// CommitmentTree, DenseAppendOnlyFixedSizeTree).
     pub fn is_non_empty_merk_tree(&self) -> bool {
         matches!(
             self,
@@ -251,6 +304,9 @@ impl Element {
                 | Element::CountSumTree(Some(_), ..)
                 | Element::ProvableCountTree(Some(_), ..)
                 | Element::ProvableCountSumTree(Some(_), ..)
+                | Element::MinMaxTree(Some(_), ..)
+                | Element::ProvableSumTree(Some(_), ..)
+                | Element::ProvableBigSumTree(Some(_), ..)
         )
     }
 
@@ -263,7 +319,11 @@ impl Element {
     pub fn is_any_item(&self) -> bool {
         matches!(
             self,
-            Element::Item(..) | Element::SumItem(..) | Element::ItemWithSumItem(..)
+            Element::Item(..)
+                | Element::SumItem(..)
+                | Element::ItemWithSumItem(..)
+                | Element::MinMaxItem(..)
+                | Element::ItemWithExpiry(..)
         )
     }
 
@@ -274,7 +334,10 @@ impl Element {
 
     /// Check if the element is an item
     pub fn has_basic_item(&self) -> bool {
-        matches!(self, Element::Item(..) | Element::ItemWithSumItem(..))
+        matches!(
+            self,
+            Element::Item(..) | Element::ItemWithSumItem(..) | Element::ItemWithExpiry(..)
+        )
     }
 
     /// Check if the element is a sum item
@@ -287,6 +350,24 @@ impl Element {
         matches!(self, Element::ItemWithSumItem(..))
     }
 
+    /// Check if the element is a min max item
+    pub fn is_min_max_item(&self) -> bool {
+        matches!(self, Element::MinMaxItem(..))
+    }
+
+    /// Check if the element is an item with an expiry
+    pub fn is_item_with_expiry(&self) -> bool {
+        matches!(self, Element::ItemWithExpiry(..))
+    }
+
+    /// Gives the expiry of an ItemWithExpiry, `None` for everything else
+    pub fn expiry(&self) -> Option<ExpiryValue> {
+        match self {
+            Element::ItemWithExpiry(_, expiry, _) => Some(*expiry),
+            _ => None,
+        }
+    }
+
     /// Grab the optional flag stored in an element
     pub fn get_flags(&self) -> &Option<ElementFlags> {
         match self {
@@ -304,7 +385,12 @@ impl Element {
             | Element::CommitmentTree(.., flags)
             | Element::MmrTree(.., flags)
             | Element::BulkAppendTree(.., flags)
-            | Element::DenseAppendOnlyFixedSizeTree(.., flags) => flags,
+            | Element::DenseAppendOnlyFixedSizeTree(.., flags)
+            | Element::MinMaxItem(_, flags)
+            | Element::MinMaxTree(.., flags)
+            | Element::ProvableSumTree(.., flags)
+            | Element::ProvableBigSumTree(.., flags)
+            | Element::ItemWithExpiry(.., flags) => flags,
         }
     }
 
@@ -325,7 +411,12 @@ impl Element {
             | Element::CommitmentTree(.., flags)
             | Element::MmrTree(.., flags)
             | Element::BulkAppendTree(.., flags)
-            | Element::DenseAppendOnlyFixedSizeTree(.., flags) => flags,
+            | Element::DenseAppendOnlyFixedSizeTree(.., flags)
+            | Element::MinMaxItem(_, flags)
+            | Element::MinMaxTree(.., flags)
+            | Element::ProvableSumTree(.., flags)
+            | Element::ProvableBigSumTree(.., flags)
+            | Element::ItemWithExpiry(.., flags) => flags,
         }
     }
 
@@ -346,7 +437,12 @@ impl Element {
             | Element::CommitmentTree(.., flags)
             | Element::MmrTree(.., flags)
             | Element::BulkAppendTree(.., flags)
-            | Element::DenseAppendOnlyFixedSizeTree(.., flags) => flags,
+            | Element::DenseAppendOnlyFixedSizeTree(.., flags)
+            | Element::MinMaxItem(_, flags)
+            | Element::MinMaxTree(.., flags)
+            | Element::ProvableSumTree(.., flags)
+            | Element::ProvableBigSumTree(.., flags)
+            | Element::ItemWithExpiry(.., flags) => flags,
         }
     }
 
@@ -367,7 +463,12 @@ impl Element {
             | Element::CommitmentTree(.., flags)
             | Element::MmrTree(.., flags)
             | Element::BulkAppendTree(.., flags)
-            | Element::DenseAppendOnlyFixedSizeTree(.., flags) => *flags = new_flags,
+            | Element::DenseAppendOnlyFixedSizeTree(.., flags)
+            | Element::MinMaxItem(_, flags)
+            | Element::MinMaxTree(.., flags)
+            | Element::ProvableSumTree(.., flags)
+            | Element::ProvableBigSumTree(.., flags)
+            | Element::ItemWithExpiry(.., flags) => *flags = new_flags,
         }
     }
 
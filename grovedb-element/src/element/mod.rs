@@ -33,6 +33,13 @@ pub type BigSumValue = i128;
 /// int 64 count value
 pub type CountValue = u64;
 
+/// int 64 value aggregated by its minimum and maximum in a min max tree
+pub type MinMaxValue = i64;
+
+/// Block height or timestamp at which an item expires, its unit is up to the
+/// application
+pub type ExpiryValue = u64;
+
 #[cfg(feature = "verify")]
 pub trait ElementCostSizeExtension {
     fn cost_size(&self) -> u32;
@@ -122,6 +129,28 @@ pub enum Element {
     /// - `height`: Tree height h; the tree has 2^h - 1 positions.
     /// - `flags`: Optional per-element metadata.
     DenseAppendOnlyFixedSizeTree(u16, u8, Option<ElementFlags>),
+    /// Signed integer value whose minimum and maximum are tracked by a min
+    /// max tree
+    MinMaxItem(MinMaxValue, Option<ElementFlags>),
+    /// Same as Element::Tree but underlying Merk keeps the minimum and maximum
+    /// of its min max items in cryptographic state
+    ///
+    /// Fields: `(root_key, min, max, flags)`. A tree without min max items
+    /// has `min == MinMaxValue::MAX` and `max == MinMaxValue::MIN`.
+    MinMaxTree(
+        Option<Vec<u8>>,
+        MinMaxValue,
+        MinMaxValue,
+        Option<ElementFlags>,
+    ),
+    /// Same as Element::SumTree but includes the sum in cryptographic state
+    ProvableSumTree(Option<Vec<u8>>, SumValue, Option<ElementFlags>),
+    /// Same as Element::BigSumTree but includes the sum in cryptographic
+    /// state
+    ProvableBigSumTree(Option<Vec<u8>>, BigSumValue, Option<ElementFlags>),
+    /// An ordinary value that expires once the chain reaches its expiry, see
+    /// `GroveDb::sweep_expired`
+    ItemWithExpiry(Vec<u8>, ExpiryValue, Option<ElementFlags>),
 }
 
 pub fn hex_to_ascii(hex_value: &[u8]) -> String {
@@ -307,6 +336,61 @@ impl fmt::Display for Element {
                         .map_or(String::new(), |f| format!(", flags: {:?}", f))
                 )
             }
+            Element::MinMaxItem(value, flags) => {
+                write!(
+                    f,
+                    "MinMaxItem({}{})",
+                    value,
+                    flags
+                        .as_ref()
+                        .map_or(String::new(), |f| format!(", flags: {:?}", f))
+                )
+            }
+            Element::MinMaxTree(root_key, min, max, flags) => {
+                write!(
+                    f,
+                    "MinMaxTree({}, {}, {}{})",
+                    root_key.as_ref().map_or("None".to_string(), hex::encode),
+                    min,
+                    max,
+                    flags
+                        .as_ref()
+                        .map_or(String::new(), |f| format!(", flags: {:?}", f))
+                )
+            }
+            Element::ProvableSumTree(root_key, sum_value, flags) => {
+                write!(
+                    f,
+                    "ProvableSumTree({}, {}{})",
+                    root_key.as_ref().map_or("None".to_string(), hex::encode),
+                    sum_value,
+                    flags
+                        .as_ref()
+                        .map_or(String::new(), |f| format!(", flags: {:?}", f))
+                )
+            }
+            Element::ProvableBigSumTree(root_key, sum_value, flags) => {
+                write!(
+                    f,
+                    "ProvableBigSumTree({}, {}{})",
+                    root_key.as_ref().map_or("None".to_string(), hex::encode),
+                    sum_value,
+                    flags
+                        .as_ref()
+                        .map_or(String::new(), |f| format!(", flags: {:?}", f))
+                )
+            }
+            Element::ItemWithExpiry(data, expiry, flags) => {
+                write!(
+                    f,
+                    "ItemWithExpiry({}, expires: {}{})",
+                    hex_to_ascii(data),
+                    expiry,
+                    flags
+                        .as_ref()
+                        .map_or(String::new(), |f| format!(", flags: {:?}", f))
+                )
+            }
         }
     }
 }
@@ -330,6 +414,11 @@ impl Element {
             Element::MmrTree(..) => ElementType::MmrTree,
             Element::BulkAppendTree(..) => ElementType::BulkAppendTree,
             Element::DenseAppendOnlyFixedSizeTree(..) => ElementType::DenseAppendOnlyFixedSizeTree,
+            Element::MinMaxItem(..) => ElementType::MinMaxItem,
+            Element::MinMaxTree(..) => ElementType::MinMaxTree,
+            Element::ProvableSumTree(..) => ElementType::ProvableSumTree,
+            Element::ProvableBigSumTree(..) => ElementType::ProvableBigSumTree,
+            Element::ItemWithExpiry(..) => ElementType::ItemWithExpiry,
         }
     }
 
@@ -170,6 +170,59 @@ impl Visualize for Element {
             Element::DenseAppendOnlyFixedSizeTree(count, height, flags) => {
                 drawer.write(format!("dense_tree: count: {count} height: {height}",).as_bytes())?;
 
+                if let Some(f) = flags
+                    && !f.is_empty()
+                {
+                    drawer = f.visualize(drawer)?;
+                }
+            }
+            Element::MinMaxItem(value, flags) => {
+                drawer.write(format!("min_max_item: {value}").as_bytes())?;
+
+                if let Some(f) = flags
+                    && !f.is_empty()
+                {
+                    drawer = f.visualize(drawer)?;
+                }
+            }
+            Element::MinMaxTree(root_key, min, max, flags) => {
+                drawer.write(b"min_max_tree: ")?;
+                drawer = root_key.as_deref().visualize(drawer)?;
+                drawer.write(format!(" min: {min}, max: {max}").as_bytes())?;
+
+                if let Some(f) = flags
+                    && !f.is_empty()
+                {
+                    drawer = f.visualize(drawer)?;
+                }
+            }
+            Element::ProvableSumTree(root_key, value, flags) => {
+                drawer.write(b"provable_sum_tree: ")?;
+                drawer = root_key.as_deref().visualize(drawer)?;
+                drawer.write(format!(" {value}").as_bytes())?;
+
+                if let Some(f) = flags
+                    && !f.is_empty()
+                {
+                    drawer = f.visualize(drawer)?;
+                }
+            }
+            Element::ProvableBigSumTree(root_key, value, flags) => {
+                drawer.write(b"provable_big_sum_tree: ")?;
+                drawer = root_key.as_deref().visualize(drawer)?;
+                drawer.write(format!(" {value}").as_bytes())?;
+
+                if let Some(f) = flags
+                    && !f.is_empty()
+                {
+                    drawer = f.visualize(drawer)?;
+                }
+            }
+            Element::ItemWithExpiry(value, expiry, flags) => {
+                drawer.write(b"item_with_expiry: ")?;
+                drawer = value.visualize(drawer)?;
+                drawer.write(format!(" expires: {expiry}").as_bytes())?;
+
                 if let Some(f) = flags
                     && !f.is_empty()
                 {
@@ -13,7 +13,8 @@ pub enum ProofNodeType {
     /// This is secure because any tampering with the value bytes will cause
     /// the computed hash to differ, failing verification.
     ///
-    /// Used for: Item, SumItem, ItemWithSumItem (in regular trees)
+    /// Used for: Item, SumItem, ItemWithSumItem, ItemWithExpiry (in regular
+    /// trees)
     Kv,
 
     /// Use `Node::KVValueHash` - the verifier trusts the provided value_hash.
@@ -76,6 +77,40 @@ pub enum ProofNodeType {
     ///
     /// Used for: Reference (inside ProvableCountTree)
     KvRefValueHashCount,
+
+    /// Use `Node::KVMinMax` - the verifier will compute `value_hash = H(value)`
+    /// and include the min and max in the node hash calculation.
+    ///
+    /// Used for: Item, SumItem, ItemWithSumItem, MinMaxItem (inside
+    /// MinMaxTree)
+    KvMinMax,
+
+    /// Use `Node::KVRefValueHashMinMax` - like KVRefValueHashCount but with
+    /// the min and max instead of the count.
+    ///
+    /// At the merk layer, this generates `KVValueHashFeatureType`. GroveDB
+    /// post-processes these nodes to `Node::KVRefValueHashMinMax` with the
+    /// dereferenced value.
+    ///
+    /// Used for: Reference (inside MinMaxTree)
+    KvRefValueHashMinMax,
+
+    /// Use `Node::KVSum` - the verifier will compute `value_hash = H(value)`
+    /// and include the aggregate sum in the node hash calculation.
+    ///
+    /// Used for: Item, SumItem, ItemWithSumItem (inside ProvableSumTree or
+    /// ProvableBigSumTree)
+    KvSum,
+
+    /// Use `Node::KVRefValueHashSum` - like KVRefValueHashCount but with the
+    /// aggregate sum instead of the count.
+    ///
+    /// At the merk layer, this generates `KVValueHashFeatureType`. GroveDB
+    /// post-processes these nodes to `Node::KVRefValueHashSum` with the
+    /// dereferenced value.
+    ///
+    /// Used for: Reference (inside ProvableSumTree or ProvableBigSumTree)
+    KvRefValueHashSum,
 }
 
 /// Element type discriminants matching the Element enum serialization order.
@@ -116,6 +151,16 @@ pub enum ElementType {
     BulkAppendTree = 13,
     /// Dense fixed-sized Merkle tree - discriminant 14
     DenseAppendOnlyFixedSizeTree = 14,
+    /// Value aggregated by a min max tree - discriminant 15
+    MinMaxItem = 15,
+    /// Min max tree - discriminant 16
+    MinMaxTree = 16,
+    /// Provable sum tree - discriminant 17
+    ProvableSumTree = 17,
+    /// Provable big sum tree (i128) - discriminant 18
+    ProvableBigSumTree = 18,
+    /// Item with an expiry - discriminant 19
+    ItemWithExpiry = 19,
 }
 
 impl ElementType {
@@ -170,6 +215,19 @@ impl ElementType {
     /// Non-queried and boundary nodes are handled separately in the merk
     /// proof generation code (`KVHash`/`KVHashCount`, `KVDigest`/`KVDigestCount`).
     ///
+    /// ## MinMaxTree
+    ///
+    /// Uses `node_hash_with_min_max(kv_hash, left, right, min, max)`, so the
+    /// same roles use `KvMinMax`, `KvValueHashFeatureType` and
+    /// `KvRefValueHashMinMax`, and non-queried and boundary nodes use
+    /// `KVHashMinMax` and `KVDigestMinMax`.
+    ///
+    /// ## ProvableSumTree / ProvableBigSumTree
+    ///
+    /// Use `node_hash_with_sum(kv_hash, left, right, sum)`, so the same roles
+    /// use `KvSum`, `KvValueHashFeatureType` and `KvRefValueHashSum`, and
+    /// non-queried and boundary nodes use `KVHashSum` and `KVDigestSum`.
+    ///
     /// See also: docs/book/src/proof-system.md "Proof Node Types by Tree Type"
     ///
     /// # Arguments
@@ -181,10 +239,19 @@ impl ElementType {
             parent_tree_type,
             Some(ElementType::ProvableCountTree) | Some(ElementType::ProvableCountSumTree)
         );
+        let is_min_max_tree = parent_tree_type == Some(ElementType::MinMaxTree);
+        let is_provable_sum_tree = matches!(
+            parent_tree_type,
+            Some(ElementType::ProvableSumTree) | Some(ElementType::ProvableBigSumTree)
+        );
 
         if self.has_simple_value_hash() {
-            // Items (Item, SumItem, ItemWithSumItem)
-            if is_provable_count_tree {
+            // Items (Item, SumItem, ItemWithSumItem, MinMaxItem, ItemWithExpiry)
+            if is_min_max_tree {
+                ProofNodeType::KvMinMax
+            } else if is_provable_sum_tree {
+                ProofNodeType::KvSum
+            } else if is_provable_count_tree {
                 ProofNodeType::KvCount
             } else {
                 ProofNodeType::Kv
@@ -193,7 +260,11 @@ impl ElementType {
             // References need combined hash (for reference resolution).
             // In ProvableCountTree, they also need the count in node_hash.
             // GroveDB post-processes these to KVRefValueHash/KVRefValueHashCount.
-            if is_provable_count_tree {
+            if is_min_max_tree {
+                ProofNodeType::KvRefValueHashMinMax
+            } else if is_provable_sum_tree {
+                ProofNodeType::KvRefValueHashSum
+            } else if is_provable_count_tree {
                 ProofNodeType::KvRefValueHashCount
             } else {
                 ProofNodeType::KvRefValueHash
@@ -201,7 +272,7 @@ impl ElementType {
         } else {
             // Subtrees (Tree, SumTree, BigSumTree, CountTree, CountSumTree,
             // ProvableCountTree)
-            if is_provable_count_tree {
+            if is_provable_count_tree || is_min_max_tree || is_provable_sum_tree {
                 ProofNodeType::KvValueHashFeatureType
             } else {
                 ProofNodeType::KvValueHash
@@ -218,7 +289,11 @@ impl ElementType {
     pub fn has_simple_value_hash(&self) -> bool {
         matches!(
             self,
-            ElementType::Item | ElementType::SumItem | ElementType::ItemWithSumItem
+            ElementType::Item
+                | ElementType::SumItem
+                | ElementType::ItemWithSumItem
+                | ElementType::MinMaxItem
+                | ElementType::ItemWithExpiry
         )
     }
 
@@ -252,6 +327,9 @@ impl ElementType {
                 | ElementType::MmrTree
                 | ElementType::BulkAppendTree
                 | ElementType::DenseAppendOnlyFixedSizeTree
+                | ElementType::MinMaxTree
+                | ElementType::ProvableSumTree
+                | ElementType::ProvableBigSumTree
         )
     }
 
@@ -267,7 +345,11 @@ impl ElementType {
     pub fn is_item(&self) -> bool {
         matches!(
             self,
-            ElementType::Item | ElementType::SumItem | ElementType::ItemWithSumItem
+            ElementType::Item
+                | ElementType::SumItem
+                | ElementType::ItemWithSumItem
+                | ElementType::MinMaxItem
+                | ElementType::ItemWithExpiry
         )
     }
 
@@ -289,6 +371,11 @@ impl ElementType {
             ElementType::MmrTree => "mmr tree",
             ElementType::BulkAppendTree => "bulk_append_tree",
             ElementType::DenseAppendOnlyFixedSizeTree => "dense_tree",
+            ElementType::MinMaxItem => "min max item",
+            ElementType::MinMaxTree => "min max tree",
+            ElementType::ProvableSumTree => "provable sum tree",
+            ElementType::ProvableBigSumTree => "provable big sum tree",
+            ElementType::ItemWithExpiry => "item with expiry",
         }
     }
 }
@@ -313,6 +400,11 @@ impl TryFrom<u8> for ElementType {
             12 => Ok(ElementType::MmrTree),
             13 => Ok(ElementType::BulkAppendTree),
             14 => Ok(ElementType::DenseAppendOnlyFixedSizeTree),
+            15 => Ok(ElementType::MinMaxItem),
+            16 => Ok(ElementType::MinMaxTree),
+            17 => Ok(ElementType::ProvableSumTree),
+            18 => Ok(ElementType::ProvableBigSumTree),
+            19 => Ok(ElementType::ItemWithExpiry),
             _ => Err(ElementError::CorruptedData(format!(
                 "Unknown element type discriminant: {}",
                 value
@@ -366,7 +458,21 @@ mod tests {
             ElementType::try_from(14).unwrap(),
             ElementType::DenseAppendOnlyFixedSizeTree
         );
-        assert!(ElementType::try_from(15).is_err());
+        assert_eq!(ElementType::try_from(15).unwrap(), ElementType::MinMaxItem);
+        assert_eq!(ElementType::try_from(16).unwrap(), ElementType::MinMaxTree);
+        assert_eq!(
+            ElementType::try_from(17).unwrap(),
+            ElementType::ProvableSumTree
+        );
+        assert_eq!(
+            ElementType::try_from(18).unwrap(),
+            ElementType::ProvableBigSumTree
+        );
+        assert_eq!(
+            ElementType::try_from(19).unwrap(),
+            ElementType::ItemWithExpiry
+        );
+        assert!(ElementType::try_from(20).is_err());
     }
 
     #[test]
@@ -514,6 +620,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_proof_node_type_min_max_tree() {
+        use super::ProofNodeType;
+
+        let mmt = Some(ElementType::MinMaxTree);
+
+        assert_eq!(
+            ElementType::Item.proof_node_type(mmt),
+            ProofNodeType::KvMinMax
+        );
+        assert_eq!(
+            ElementType::MinMaxItem.proof_node_type(mmt),
+            ProofNodeType::KvMinMax
+        );
+        assert_eq!(
+            ElementType::Reference.proof_node_type(mmt),
+            ProofNodeType::KvRefValueHashMinMax
+        );
+        assert_eq!(
+            ElementType::MinMaxTree.proof_node_type(mmt),
+            ProofNodeType::KvValueHashFeatureType
+        );
+
+        // Outside of a min max tree min max items are ordinary items
+        assert_eq!(
+            ElementType::MinMaxItem.proof_node_type(None),
+            ProofNodeType::Kv
+        );
+        assert_eq!(
+            ElementType::MinMaxTree.proof_node_type(None),
+            ProofNodeType::KvValueHash
+        );
+    }
+
+    #[test]
+    fn test_proof_node_type_provable_sum_tree() {
+        use super::ProofNodeType;
+
+        for pst in [
+            Some(ElementType::ProvableSumTree),
+            Some(ElementType::ProvableBigSumTree),
+        ] {
+            assert_eq!(
+                ElementType::SumItem.proof_node_type(pst),
+                ProofNodeType::KvSum
+            );
+            assert_eq!(ElementType::Item.proof_node_type(pst), ProofNodeType::KvSum);
+            assert_eq!(
+                ElementType::Reference.proof_node_type(pst),
+                ProofNodeType::KvRefValueHashSum
+            );
+            assert_eq!(
+                ElementType::SumTree.proof_node_type(pst),
+                ProofNodeType::KvValueHashFeatureType
+            );
+        }
+
+        assert_eq!(
+            ElementType::ProvableSumTree.proof_node_type(None),
+            ProofNodeType::KvValueHash
+        );
+    }
+
     #[test]
     fn test_from_serialized_value() {
         // Test with valid first bytes
@@ -549,6 +718,10 @@ mod tests {
         assert!(ElementType::MmrTree.is_tree());
         assert!(ElementType::BulkAppendTree.is_tree());
         assert!(ElementType::DenseAppendOnlyFixedSizeTree.is_tree());
+        assert!(!ElementType::MinMaxItem.is_tree());
+        assert!(ElementType::MinMaxTree.is_tree());
+        assert!(ElementType::ProvableSumTree.is_tree());
+        assert!(ElementType::ProvableBigSumTree.is_tree());
     }
 
     /// Verifies that serialized Element discriminants match ElementType
@@ -649,13 +822,43 @@ mod tests {
                 ElementType::DenseAppendOnlyFixedSizeTree,
                 "DenseAppendOnlyFixedSizeTree",
             ),
+            // discriminant 15
+            (
+                Element::MinMaxItem(-7, None),
+                ElementType::MinMaxItem,
+                "MinMaxItem",
+            ),
+            // discriminant 16
+            (
+                Element::MinMaxTree(None, i64::MAX, i64::MIN, None),
+                ElementType::MinMaxTree,
+                "MinMaxTree",
+            ),
+            // discriminant 17
+            (
+                Element::ProvableSumTree(None, -3, None),
+                ElementType::ProvableSumTree,
+                "ProvableSumTree",
+            ),
+            // discriminant 18
+            (
+                Element::ProvableBigSumTree(None, 3, None),
+                ElementType::ProvableBigSumTree,
+                "ProvableBigSumTree",
+            ),
+            // discriminant 19
+            (
+                Element::ItemWithExpiry(vec![1, 2, 3], 1000, None),
+                ElementType::ItemWithExpiry,
+                "ItemWithExpiry",
+            ),
         ];
 
-        // Verify we're testing all 15 discriminants (0-14)
+        // Verify we're testing all 20 discriminants (0-19)
         assert_eq!(
             test_cases.len(),
-            15,
-            "Expected 15 Element variants in test, got {}",
+            20,
+            "Expected 20 Element variants in test, got {}",
             test_cases.len()
         );
 
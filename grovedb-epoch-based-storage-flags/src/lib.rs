@@ -2,6 +2,7 @@
 
 pub mod error;
 mod split_removal_bytes;
+mod storage_fee_scheme;
 mod update_element_flags;
 
 use crate::{
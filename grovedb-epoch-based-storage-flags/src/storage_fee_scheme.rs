@@ -0,0 +1,75 @@
+use grovedb_costs::storage_cost::{
+    fee_report::StorageFeeScheme,
+    removal::{Identifier, StorageRemovedBytes},
+    StorageCost,
+};
+
+use crate::{error::StorageFlagsError, ElementFlags, StorageFlags};
+
+impl StorageFeeScheme for StorageFlags {
+    type Error = StorageFlagsError;
+
+    fn update_element_flags(
+        cost: &StorageCost,
+        old_flags: Option<ElementFlags>,
+        new_flags: &mut ElementFlags,
+    ) -> Result<bool, Self::Error> {
+        StorageFlags::update_element_flags(cost, old_flags, new_flags)
+    }
+
+    fn split_removal_bytes(
+        flags: &mut ElementFlags,
+        removed_key_bytes: u32,
+        removed_value_bytes: u32,
+    ) -> Result<(StorageRemovedBytes, StorageRemovedBytes), Self::Error> {
+        StorageFlags::split_removal_bytes(flags, removed_key_bytes, removed_value_bytes)
+    }
+
+    /// Added bytes are paid by the owner, or by the default identifier when
+    /// there is none, in the latest epoch the flags hold bytes for.
+    fn added_bytes_owner_and_epoch(flags: &[u8]) -> Option<(Identifier, u16)> {
+        let storage_flags = StorageFlags::from_slice(flags).ok()??;
+        let epoch = storage_flags
+            .epoch_index_map()
+            .and_then(|epochs| epochs.keys().next_back())
+            .unwrap_or(storage_flags.base_epoch());
+        let owner_id = storage_flags.owner_id().copied().unwrap_or_default();
+        Some((owner_id, *epoch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use grovedb_costs::storage_cost::fee_report::StorageFeeScheme;
+
+    use crate::StorageFlags;
+
+    #[test]
+    fn added_bytes_go_to_the_latest_epoch_of_the_owner() {
+        let owner_id = [7; 32];
+        let flags = StorageFlags::SingleEpochOwned(3, owner_id).serialize();
+        assert_eq!(
+            <StorageFlags as StorageFeeScheme>::added_bytes_owner_and_epoch(&flags),
+            Some((owner_id, 3))
+        );
+
+        let flags = StorageFlags::MultiEpochOwned(3, BTreeMap::from([(5, 10), (9, 4)]), owner_id)
+            .serialize();
+        assert_eq!(
+            <StorageFlags as StorageFeeScheme>::added_bytes_owner_and_epoch(&flags),
+            Some((owner_id, 9))
+        );
+
+        let flags = StorageFlags::SingleEpoch(2).serialize();
+        assert_eq!(
+            <StorageFlags as StorageFeeScheme>::added_bytes_owner_and_epoch(&flags),
+            Some(([0; 32], 2))
+        );
+        assert_eq!(
+            <StorageFlags as StorageFeeScheme>::added_bytes_owner_and_epoch(&[]),
+            None
+        );
+    }
+}
@@ -257,6 +257,10 @@ impl<'db> StorageContext<'db> for MockStorageContext {
     fn raw_iter(&self) -> Self::RawIterator {
         StubRawIterator
     }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        StubRawIterator
+    }
 }
 
 /// Mock StorageContext that returns errors for get and put.
@@ -373,6 +377,10 @@ impl<'db> StorageContext<'db> for FailingStorageContext {
     fn raw_iter(&self) -> Self::RawIterator {
         StubRawIterator
     }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        StubRawIterator
+    }
 }
 
 // ── Tests ───────────────────────────────────────────────────────────
@@ -54,6 +54,9 @@ pub struct ProofParams {
     /// Whether to traverse the tree from left to right (`true`) or right to
     /// left (`false`).
     pub left_to_right: bool,
+    /// Whether matched nodes are proved by their key and value hash only,
+    /// leaving their values out of the proof.
+    pub keys_only: bool,
 }
 
 /// Convert a byte slice to an ASCII string if all characters are allowed,
@@ -62,7 +62,10 @@ impl<'a> ProofItems<'a> {
             key_query_items,
             range_query_items,
         };
-        let params = ProofParams { left_to_right };
+        let params = ProofParams {
+            left_to_right,
+            keys_only: false,
+        };
         (status, params)
     }
 
@@ -3,6 +3,9 @@
 pub struct ProofStatus {
     /// The remaining number of results allowed, or `None` for unlimited.
     pub limit: Option<u16>,
+    /// The remaining number of matches to skip before results are counted.
+    /// Skipped matches are proved without their values.
+    pub offset: u16,
 }
 
 impl ProofStatus {
@@ -15,7 +18,13 @@ impl ProofStatus {
 impl ProofStatus {
     /// Creates a new `ProofStatus` with the given limit.
     pub fn new_with_limit(limit: Option<u16>) -> Self {
-        Self { limit }
+        Self { limit, offset: 0 }
+    }
+
+    /// Creates a new `ProofStatus` that skips the first `offset` matches and
+    /// then allows `limit` results.
+    pub fn new_with_limit_and_offset(limit: Option<u16>, offset: u16) -> Self {
+        Self { limit, offset }
     }
 
     /// Returns a new `ProofStatus` with the limit updated to `new_limit` if
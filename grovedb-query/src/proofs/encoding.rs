@@ -2,6 +2,7 @@
 
 use std::io::{Read, Write};
 
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use ed::{Decode, Encode, Error as EdError, Terminated};
 
 use super::{Node, Op};
@@ -15,6 +16,9 @@ use crate::{
 /// allocations.
 const MAX_VALUE_LEN: u32 = 64 * 1024 * 1024;
 
+/// Length of the big-endian i128 sum carried by provable sum nodes.
+const SUM_LENGTH: usize = 16;
+
 impl Encode for Op {
     // Note: `key.len() as u8` casts below are safe because GroveDB enforces a
     // 255-byte maximum key length at insertion time (both direct insert and
@@ -176,6 +180,107 @@ impl Encode for Op {
                     dest.write_all(child_hash)?;
                 }
             }
+            Op::Push(Node::KVMinMax(key, value, min, max)) => {
+                debug_assert!(key.len() < 256);
+                if value.len() < 65536 {
+                    dest.write_all(&[0x30, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u16).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    min.encode_into(dest)?;
+                    max.encode_into(dest)?;
+                } else {
+                    dest.write_all(&[0x31, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u32).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    min.encode_into(dest)?;
+                    max.encode_into(dest)?;
+                }
+            }
+            Op::Push(Node::KVHashMinMax(kv_hash, min, max)) => {
+                dest.write_all(&[0x32])?;
+                dest.write_all(kv_hash)?;
+                min.encode_into(dest)?;
+                max.encode_into(dest)?;
+            }
+            Op::Push(Node::KVRefValueHashMinMax(key, value, value_hash, min, max)) => {
+                debug_assert!(key.len() < 256);
+                if value.len() < 65536 {
+                    dest.write_all(&[0x33, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u16).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_all(value_hash)?;
+                    min.encode_into(dest)?;
+                    max.encode_into(dest)?;
+                } else {
+                    dest.write_all(&[0x34, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u32).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_all(value_hash)?;
+                    min.encode_into(dest)?;
+                    max.encode_into(dest)?;
+                }
+            }
+            Op::Push(Node::KVDigestMinMax(key, value_hash, min, max)) => {
+                debug_assert!(key.len() < 256);
+
+                dest.write_all(&[0x35, key.len() as u8])?;
+                dest.write_all(key)?;
+                dest.write_all(value_hash)?;
+                min.encode_into(dest)?;
+                max.encode_into(dest)?;
+            }
+
+            Op::Push(Node::KVSum(key, value, sum)) => {
+                debug_assert!(key.len() < 256);
+                if value.len() < 65536 {
+                    dest.write_all(&[0x40, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u16).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_i128::<BigEndian>(*sum)?;
+                } else {
+                    dest.write_all(&[0x41, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u32).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_i128::<BigEndian>(*sum)?;
+                }
+            }
+            Op::Push(Node::KVHashSum(kv_hash, sum)) => {
+                dest.write_all(&[0x42])?;
+                dest.write_all(kv_hash)?;
+                dest.write_i128::<BigEndian>(*sum)?;
+            }
+            Op::Push(Node::KVRefValueHashSum(key, value, value_hash, sum)) => {
+                debug_assert!(key.len() < 256);
+                if value.len() < 65536 {
+                    dest.write_all(&[0x43, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u16).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_all(value_hash)?;
+                    dest.write_i128::<BigEndian>(*sum)?;
+                } else {
+                    dest.write_all(&[0x44, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u32).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_all(value_hash)?;
+                    dest.write_i128::<BigEndian>(*sum)?;
+                }
+            }
+            Op::Push(Node::KVDigestSum(key, value_hash, sum)) => {
+                debug_assert!(key.len() < 256);
+
+                dest.write_all(&[0x45, key.len() as u8])?;
+                dest.write_all(key)?;
+                dest.write_all(value_hash)?;
+                dest.write_i128::<BigEndian>(*sum)?;
+            }
 
             // PushInverted
             Op::PushInverted(Node::Hash(hash)) => {
@@ -335,6 +440,107 @@ impl Encode for Op {
                     dest.write_all(child_hash)?;
                 }
             }
+            Op::PushInverted(Node::KVMinMax(key, value, min, max)) => {
+                debug_assert!(key.len() < 256);
+                if value.len() < 65536 {
+                    dest.write_all(&[0x38, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u16).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    min.encode_into(dest)?;
+                    max.encode_into(dest)?;
+                } else {
+                    dest.write_all(&[0x39, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u32).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    min.encode_into(dest)?;
+                    max.encode_into(dest)?;
+                }
+            }
+            Op::PushInverted(Node::KVHashMinMax(kv_hash, min, max)) => {
+                dest.write_all(&[0x3a])?;
+                dest.write_all(kv_hash)?;
+                min.encode_into(dest)?;
+                max.encode_into(dest)?;
+            }
+            Op::PushInverted(Node::KVRefValueHashMinMax(key, value, value_hash, min, max)) => {
+                debug_assert!(key.len() < 256);
+                if value.len() < 65536 {
+                    dest.write_all(&[0x3b, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u16).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_all(value_hash)?;
+                    min.encode_into(dest)?;
+                    max.encode_into(dest)?;
+                } else {
+                    dest.write_all(&[0x3c, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u32).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_all(value_hash)?;
+                    min.encode_into(dest)?;
+                    max.encode_into(dest)?;
+                }
+            }
+            Op::PushInverted(Node::KVDigestMinMax(key, value_hash, min, max)) => {
+                debug_assert!(key.len() < 256);
+
+                dest.write_all(&[0x3d, key.len() as u8])?;
+                dest.write_all(key)?;
+                dest.write_all(value_hash)?;
+                min.encode_into(dest)?;
+                max.encode_into(dest)?;
+            }
+
+            Op::PushInverted(Node::KVSum(key, value, sum)) => {
+                debug_assert!(key.len() < 256);
+                if value.len() < 65536 {
+                    dest.write_all(&[0x48, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u16).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_i128::<BigEndian>(*sum)?;
+                } else {
+                    dest.write_all(&[0x49, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u32).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_i128::<BigEndian>(*sum)?;
+                }
+            }
+            Op::PushInverted(Node::KVHashSum(kv_hash, sum)) => {
+                dest.write_all(&[0x4a])?;
+                dest.write_all(kv_hash)?;
+                dest.write_i128::<BigEndian>(*sum)?;
+            }
+            Op::PushInverted(Node::KVRefValueHashSum(key, value, value_hash, sum)) => {
+                debug_assert!(key.len() < 256);
+                if value.len() < 65536 {
+                    dest.write_all(&[0x4b, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u16).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_all(value_hash)?;
+                    dest.write_i128::<BigEndian>(*sum)?;
+                } else {
+                    dest.write_all(&[0x4c, key.len() as u8])?;
+                    dest.write_all(key)?;
+                    (value.len() as u32).encode_into(dest)?;
+                    dest.write_all(value)?;
+                    dest.write_all(value_hash)?;
+                    dest.write_i128::<BigEndian>(*sum)?;
+                }
+            }
+            Op::PushInverted(Node::KVDigestSum(key, value_hash, sum)) => {
+                debug_assert!(key.len() < 256);
+
+                dest.write_all(&[0x4d, key.len() as u8])?;
+                dest.write_all(key)?;
+                dest.write_all(value_hash)?;
+                dest.write_i128::<BigEndian>(*sum)?;
+            }
 
             Op::Parent => dest.write_all(&[0x10])?,
             Op::Child => dest.write_all(&[0x11])?,
@@ -386,6 +592,35 @@ impl Encode for Op {
                     + feature_type.encoding_length()?
                     + HASH_LENGTH
             }
+            Op::Push(Node::KVMinMax(key, value, min, max)) => {
+                let header = if value.len() < 65536 { 4 } else { 6 };
+                header + key.len() + value.len() + min.encoding_length()? + max.encoding_length()?
+            }
+            Op::Push(Node::KVHashMinMax(_, min, max)) => {
+                1 + HASH_LENGTH + min.encoding_length()? + max.encoding_length()?
+            }
+            Op::Push(Node::KVRefValueHashMinMax(key, value, _, min, max)) => {
+                let header = if value.len() < 65536 { 4 } else { 6 };
+                header
+                    + key.len()
+                    + value.len()
+                    + HASH_LENGTH
+                    + min.encoding_length()?
+                    + max.encoding_length()?
+            }
+            Op::Push(Node::KVDigestMinMax(key, _, min, max)) => {
+                2 + key.len() + HASH_LENGTH + min.encoding_length()? + max.encoding_length()?
+            }
+            Op::Push(Node::KVSum(key, value, _)) => {
+                let header = if value.len() < 65536 { 4 } else { 6 };
+                header + key.len() + value.len() + SUM_LENGTH
+            }
+            Op::Push(Node::KVHashSum(..)) => 1 + HASH_LENGTH + SUM_LENGTH,
+            Op::Push(Node::KVRefValueHashSum(key, value, ..)) => {
+                let header = if value.len() < 65536 { 4 } else { 6 };
+                header + key.len() + value.len() + HASH_LENGTH + SUM_LENGTH
+            }
+            Op::Push(Node::KVDigestSum(key, ..)) => 2 + key.len() + HASH_LENGTH + SUM_LENGTH,
             Op::PushInverted(Node::Hash(_)) => 1 + HASH_LENGTH,
             Op::PushInverted(Node::KVHash(_)) => 1 + HASH_LENGTH,
             Op::PushInverted(Node::KVDigest(key, _)) => 2 + key.len() + HASH_LENGTH,
@@ -434,6 +669,37 @@ impl Encode for Op {
                     + feature_type.encoding_length()?
                     + HASH_LENGTH
             }
+            Op::PushInverted(Node::KVMinMax(key, value, min, max)) => {
+                let header = if value.len() < 65536 { 4 } else { 6 };
+                header + key.len() + value.len() + min.encoding_length()? + max.encoding_length()?
+            }
+            Op::PushInverted(Node::KVHashMinMax(_, min, max)) => {
+                1 + HASH_LENGTH + min.encoding_length()? + max.encoding_length()?
+            }
+            Op::PushInverted(Node::KVRefValueHashMinMax(key, value, _, min, max)) => {
+                let header = if value.len() < 65536 { 4 } else { 6 };
+                header
+                    + key.len()
+                    + value.len()
+                    + HASH_LENGTH
+                    + min.encoding_length()?
+                    + max.encoding_length()?
+            }
+            Op::PushInverted(Node::KVDigestMinMax(key, _, min, max)) => {
+                2 + key.len() + HASH_LENGTH + min.encoding_length()? + max.encoding_length()?
+            }
+            Op::PushInverted(Node::KVSum(key, value, _)) => {
+                let header = if value.len() < 65536 { 4 } else { 6 };
+                header + key.len() + value.len() + SUM_LENGTH
+            }
+            Op::PushInverted(Node::KVHashSum(..)) => 1 + HASH_LENGTH + SUM_LENGTH,
+            Op::PushInverted(Node::KVRefValueHashSum(key, value, ..)) => {
+                let header = if value.len() < 65536 { 4 } else { 6 };
+                header + key.len() + value.len() + HASH_LENGTH + SUM_LENGTH
+            }
+            Op::PushInverted(Node::KVDigestSum(key, ..)) => {
+                2 + key.len() + HASH_LENGTH + SUM_LENGTH
+            }
             Op::Parent => 1,
             Op::Child => 1,
             Op::ParentInverted => 1,
@@ -1019,6 +1285,330 @@ impl Decode for Op {
                 ))
             }
 
+            // Min max variants: Push 0x30-0x35, PushInverted 0x38-0x3d
+            0x30 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u16 = Decode::decode(&mut input)?;
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::Push(Node::KVMinMax(key, value, min, max))
+            }
+            0x31 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u32 = Decode::decode(&mut input)?;
+                if value_len > MAX_VALUE_LEN {
+                    return Err(ed::Error::UnexpectedByte(0x31));
+                }
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::Push(Node::KVMinMax(key, value, min, max))
+            }
+            0x32 => {
+                let mut kv_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut kv_hash)?;
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::Push(Node::KVHashMinMax(kv_hash, min, max))
+            }
+            0x33 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u16 = Decode::decode(&mut input)?;
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::Push(Node::KVRefValueHashMinMax(key, value, value_hash, min, max))
+            }
+            0x34 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u32 = Decode::decode(&mut input)?;
+                if value_len > MAX_VALUE_LEN {
+                    return Err(ed::Error::UnexpectedByte(0x34));
+                }
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::Push(Node::KVRefValueHashMinMax(key, value, value_hash, min, max))
+            }
+            0x35 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::Push(Node::KVDigestMinMax(key, value_hash, min, max))
+            }
+            0x38 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u16 = Decode::decode(&mut input)?;
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::PushInverted(Node::KVMinMax(key, value, min, max))
+            }
+            0x39 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u32 = Decode::decode(&mut input)?;
+                if value_len > MAX_VALUE_LEN {
+                    return Err(ed::Error::UnexpectedByte(0x39));
+                }
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::PushInverted(Node::KVMinMax(key, value, min, max))
+            }
+            0x3a => {
+                let mut kv_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut kv_hash)?;
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::PushInverted(Node::KVHashMinMax(kv_hash, min, max))
+            }
+            0x3b => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u16 = Decode::decode(&mut input)?;
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::PushInverted(Node::KVRefValueHashMinMax(key, value, value_hash, min, max))
+            }
+            0x3c => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u32 = Decode::decode(&mut input)?;
+                if value_len > MAX_VALUE_LEN {
+                    return Err(ed::Error::UnexpectedByte(0x3c));
+                }
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::PushInverted(Node::KVRefValueHashMinMax(key, value, value_hash, min, max))
+            }
+            0x3d => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let min: i64 = Decode::decode(&mut input)?;
+                let max: i64 = Decode::decode(&mut input)?;
+                Self::PushInverted(Node::KVDigestMinMax(key, value_hash, min, max))
+            }
+
+            // Provable sum variants: Push 0x40-0x45, PushInverted 0x48-0x4d
+            0x40 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u16 = Decode::decode(&mut input)?;
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::Push(Node::KVSum(key, value, sum))
+            }
+            0x41 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u32 = Decode::decode(&mut input)?;
+                if value_len > MAX_VALUE_LEN {
+                    return Err(ed::Error::UnexpectedByte(0x41));
+                }
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::Push(Node::KVSum(key, value, sum))
+            }
+            0x42 => {
+                let mut kv_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut kv_hash)?;
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::Push(Node::KVHashSum(kv_hash, sum))
+            }
+            0x43 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u16 = Decode::decode(&mut input)?;
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::Push(Node::KVRefValueHashSum(key, value, value_hash, sum))
+            }
+            0x44 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u32 = Decode::decode(&mut input)?;
+                if value_len > MAX_VALUE_LEN {
+                    return Err(ed::Error::UnexpectedByte(0x44));
+                }
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::Push(Node::KVRefValueHashSum(key, value, value_hash, sum))
+            }
+            0x45 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::Push(Node::KVDigestSum(key, value_hash, sum))
+            }
+            0x48 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u16 = Decode::decode(&mut input)?;
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::PushInverted(Node::KVSum(key, value, sum))
+            }
+            0x49 => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u32 = Decode::decode(&mut input)?;
+                if value_len > MAX_VALUE_LEN {
+                    return Err(ed::Error::UnexpectedByte(0x49));
+                }
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::PushInverted(Node::KVSum(key, value, sum))
+            }
+            0x4a => {
+                let mut kv_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut kv_hash)?;
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::PushInverted(Node::KVHashSum(kv_hash, sum))
+            }
+            0x4b => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u16 = Decode::decode(&mut input)?;
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::PushInverted(Node::KVRefValueHashSum(key, value, value_hash, sum))
+            }
+            0x4c => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let value_len: u32 = Decode::decode(&mut input)?;
+                if value_len > MAX_VALUE_LEN {
+                    return Err(ed::Error::UnexpectedByte(0x4c));
+                }
+                let mut value = vec![0; value_len as usize];
+                input.read_exact(value.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::PushInverted(Node::KVRefValueHashSum(key, value, value_hash, sum))
+            }
+            0x4d => {
+                let key_len: u8 = Decode::decode(&mut input)?;
+                let mut key = vec![0; key_len as usize];
+                input.read_exact(key.as_mut_slice())?;
+
+                let mut value_hash = [0; HASH_LENGTH];
+                input.read_exact(&mut value_hash)?;
+
+                let sum = input.read_i128::<BigEndian>()?;
+                Self::PushInverted(Node::KVDigestSum(key, value_hash, sum))
+            }
+
             0x10 => Self::Parent,
             0x11 => Self::Child,
             0x12 => Self::ParentInverted,
@@ -1774,6 +2364,154 @@ mod test {
         assert_eq!(decoded_ops.unwrap(), ops);
     }
 
+    #[test]
+    fn encode_decode_min_max_nodes() {
+        // 1 opcode + 1 key_len + key + 2 value_len + value + 8 min + 8 max
+        let kv_length = 4 + 3 + 3 + 16;
+        for (op, opcode, expected_length) in [
+            (
+                Op::Push(Node::KVMinMax(vec![1, 2, 3], vec![4, 5, 6], -5, 42)),
+                0x30,
+                kv_length,
+            ),
+            (
+                Op::Push(Node::KVMinMax(vec![1, 2, 3], vec![4; 70_000], -5, 42)),
+                0x31,
+                6 + 3 + 70_000 + 16,
+            ),
+            (
+                Op::Push(Node::KVHashMinMax([123; HASH_LENGTH], i64::MIN, i64::MAX)),
+                0x32,
+                1 + HASH_LENGTH + 16,
+            ),
+            (
+                Op::Push(Node::KVRefValueHashMinMax(
+                    vec![1, 2, 3],
+                    vec![4, 5, 6],
+                    [7; HASH_LENGTH],
+                    i64::MAX,
+                    i64::MIN,
+                )),
+                0x33,
+                kv_length + HASH_LENGTH,
+            ),
+            (
+                Op::Push(Node::KVDigestMinMax(vec![1, 2, 3], [7; HASH_LENGTH], 0, 0)),
+                0x35,
+                2 + 3 + HASH_LENGTH + 16,
+            ),
+            (
+                Op::PushInverted(Node::KVMinMax(vec![1, 2, 3], vec![4, 5, 6], 1, 2)),
+                0x38,
+                kv_length,
+            ),
+            (
+                Op::PushInverted(Node::KVHashMinMax([123; HASH_LENGTH], -1, 1)),
+                0x3a,
+                1 + HASH_LENGTH + 16,
+            ),
+            (
+                Op::PushInverted(Node::KVRefValueHashMinMax(
+                    vec![1, 2, 3],
+                    vec![4; 70_000],
+                    [7; HASH_LENGTH],
+                    3,
+                    4,
+                )),
+                0x3c,
+                6 + 3 + 70_000 + HASH_LENGTH + 16,
+            ),
+            (
+                Op::PushInverted(Node::KVDigestMinMax(vec![1, 2, 3], [7; HASH_LENGTH], 5, 6)),
+                0x3d,
+                2 + 3 + HASH_LENGTH + 16,
+            ),
+        ] {
+            assert_eq!(op.encoding_length(), expected_length, "{:?}", op);
+
+            let mut bytes = vec![];
+            op.encode_into(&mut bytes).unwrap();
+            assert_eq!(bytes.len(), expected_length, "{:?}", op);
+            assert_eq!(bytes[0], opcode, "{:?}", op);
+
+            let decoded = Op::decode(&bytes[..]).expect("decode failed");
+            assert_eq!(decoded, op);
+        }
+    }
+
+    #[test]
+    fn encode_decode_sum_nodes() {
+        // 1 opcode + 1 key_len + key + 2 value_len + value + 16 sum
+        let kv_length = 4 + 3 + 3 + 16;
+        for (op, opcode, expected_length) in [
+            (
+                Op::Push(Node::KVSum(vec![1, 2, 3], vec![4, 5, 6], -5)),
+                0x40,
+                kv_length,
+            ),
+            (
+                Op::Push(Node::KVSum(vec![1, 2, 3], vec![4; 70_000], i128::MAX)),
+                0x41,
+                6 + 3 + 70_000 + 16,
+            ),
+            (
+                Op::Push(Node::KVHashSum([123; HASH_LENGTH], i128::MIN)),
+                0x42,
+                1 + HASH_LENGTH + 16,
+            ),
+            (
+                Op::Push(Node::KVRefValueHashSum(
+                    vec![1, 2, 3],
+                    vec![4, 5, 6],
+                    [7; HASH_LENGTH],
+                    i64::MAX as i128 + 1,
+                )),
+                0x43,
+                kv_length + HASH_LENGTH,
+            ),
+            (
+                Op::Push(Node::KVDigestSum(vec![1, 2, 3], [7; HASH_LENGTH], 0)),
+                0x45,
+                2 + 3 + HASH_LENGTH + 16,
+            ),
+            (
+                Op::PushInverted(Node::KVSum(vec![1, 2, 3], vec![4, 5, 6], 12)),
+                0x48,
+                kv_length,
+            ),
+            (
+                Op::PushInverted(Node::KVHashSum([123; HASH_LENGTH], -1)),
+                0x4a,
+                1 + HASH_LENGTH + 16,
+            ),
+            (
+                Op::PushInverted(Node::KVRefValueHashSum(
+                    vec![1, 2, 3],
+                    vec![4; 70_000],
+                    [7; HASH_LENGTH],
+                    3,
+                )),
+                0x4c,
+                6 + 3 + 70_000 + HASH_LENGTH + 16,
+            ),
+            (
+                Op::PushInverted(Node::KVDigestSum(vec![1, 2, 3], [7; HASH_LENGTH], 6)),
+                0x4d,
+                2 + 3 + HASH_LENGTH + 16,
+            ),
+        ] {
+            assert_eq!(op.encoding_length(), expected_length, "{:?}", op);
+
+            let mut bytes = vec![];
+            op.encode_into(&mut bytes).unwrap();
+            assert_eq!(bytes.len(), expected_length, "{:?}", op);
+            assert_eq!(bytes[0], opcode, "{:?}", op);
+
+            let decoded = Op::decode(&bytes[..]).expect("decode failed");
+            assert_eq!(decoded, op);
+        }
+    }
+
     #[test]
     fn encode_decode_push_kvrefvaluehash_count() {
         let op = Op::Push(Node::KVRefValueHashCount(
@@ -127,6 +127,51 @@ pub enum Node {
     ///
     /// Contains: `(key, value, value_hash, feature_type, child_hash)`
     KVValueHashFeatureTypeWithChildHash(Vec<u8>, Vec<u8>, CryptoHash, TreeFeatureType, CryptoHash),
+
+    /// Key, value, min, and max. For queried Items in MinMaxTree.
+    ///
+    /// Contains: `(key, value, min, max)`
+    KVMinMax(Vec<u8>, Vec<u8>, i64, i64),
+
+    /// KV hash, min, and max. For non-queried nodes in MinMaxTree.
+    ///
+    /// Contains: `(kv_hash, min, max)`
+    KVHashMinMax(CryptoHash, i64, i64),
+
+    /// Key, referenced value, reference element hash, min, and max.
+    /// For queried References in MinMaxTree.
+    ///
+    /// Contains: `(key, referenced_value, reference_element_hash, min, max)`
+    KVRefValueHashMinMax(Vec<u8>, Vec<u8>, CryptoHash, i64, i64),
+
+    /// Key, value_hash, min, and max. For proving absence in MinMaxTree.
+    ///
+    /// Contains: `(key, value_hash, min, max)`
+    KVDigestMinMax(Vec<u8>, CryptoHash, i64, i64),
+
+    /// Key, value, and aggregate sum. For queried Items in ProvableSumTree
+    /// and ProvableBigSumTree.
+    ///
+    /// Contains: `(key, value, sum)`
+    KVSum(Vec<u8>, Vec<u8>, i128),
+
+    /// KV hash and aggregate sum. For non-queried nodes in ProvableSumTree
+    /// and ProvableBigSumTree.
+    ///
+    /// Contains: `(kv_hash, sum)`
+    KVHashSum(CryptoHash, i128),
+
+    /// Key, referenced value, reference element hash, and aggregate sum.
+    /// For queried References in ProvableSumTree and ProvableBigSumTree.
+    ///
+    /// Contains: `(key, referenced_value, reference_element_hash, sum)`
+    KVRefValueHashSum(Vec<u8>, Vec<u8>, CryptoHash, i128),
+
+    /// Key, value_hash, and aggregate sum. For proving absence in
+    /// ProvableSumTree and ProvableBigSumTree.
+    ///
+    /// Contains: `(key, value_hash, sum)`
+    KVDigestSum(Vec<u8>, CryptoHash, i128),
 }
 
 use std::fmt;
@@ -199,6 +244,56 @@ impl fmt::Display for Node {
                 feature_type,
                 hex::encode(child_hash)
             ),
+            Node::KVMinMax(key, value, min, max) => format!(
+                "KVMinMax({}, {}, {}, {})",
+                hex_to_ascii(key),
+                hex_to_ascii(value),
+                min,
+                max
+            ),
+            Node::KVHashMinMax(kv_hash, min, max) => format!(
+                "KVHashMinMax(HASH[{}], {}, {})",
+                hex::encode(kv_hash),
+                min,
+                max
+            ),
+            Node::KVRefValueHashMinMax(key, value, value_hash, min, max) => format!(
+                "KVRefValueHashMinMax({}, {}, HASH[{}], {}, {})",
+                hex_to_ascii(key),
+                hex_to_ascii(value),
+                hex::encode(value_hash),
+                min,
+                max
+            ),
+            Node::KVDigestMinMax(key, value_hash, min, max) => format!(
+                "KVDigestMinMax({}, HASH[{}], {}, {})",
+                hex_to_ascii(key),
+                hex::encode(value_hash),
+                min,
+                max
+            ),
+            Node::KVSum(key, value, sum) => format!(
+                "KVSum({}, {}, {})",
+                hex_to_ascii(key),
+                hex_to_ascii(value),
+                sum
+            ),
+            Node::KVHashSum(kv_hash, sum) => {
+                format!("KVHashSum(HASH[{}], {})", hex::encode(kv_hash), sum)
+            }
+            Node::KVRefValueHashSum(key, value, value_hash, sum) => format!(
+                "KVRefValueHashSum({}, {}, HASH[{}], {})",
+                hex_to_ascii(key),
+                hex_to_ascii(value),
+                hex::encode(value_hash),
+                sum
+            ),
+            Node::KVDigestSum(key, value_hash, sum) => format!(
+                "KVDigestSum({}, HASH[{}], {})",
+                hex_to_ascii(key),
+                hex::encode(value_hash),
+                sum
+            ),
         };
         write!(f, "{}", node_string)
     }
@@ -9,8 +9,8 @@ use grovedb_costs::TreeCostType;
 use integer_encoding::{VarInt, VarIntReader, VarIntWriter};
 
 use self::TreeFeatureType::{
-    BasicMerkNode, BigSummedMerkNode, CountedMerkNode, CountedSummedMerkNode,
-    ProvableCountedMerkNode, SummedMerkNode,
+    BasicMerkNode, BigSummedMerkNode, CountedMerkNode, CountedSummedMerkNode, MinMaxMerkNode,
+    ProvableBigSummedMerkNode, ProvableCountedMerkNode, ProvableSummedMerkNode, SummedMerkNode,
 };
 use crate::proofs::TreeFeatureType::ProvableCountedSummedMerkNode;
 
@@ -32,6 +32,12 @@ pub enum NodeType {
     ProvableCountNode,
     /// Provable count + sum node (count included in hash)
     ProvableCountSumNode,
+    /// Min + max node (min and max included in hash)
+    MinMaxNode,
+    /// Provable sum node (i64 sum included in hash)
+    ProvableSumNode,
+    /// Provable big sum node (i128 sum included in hash)
+    ProvableBigSumNode,
 }
 
 impl NodeType {
@@ -45,6 +51,9 @@ impl NodeType {
             NodeType::CountSumNode => 17,
             NodeType::ProvableCountNode => 9,
             NodeType::ProvableCountSumNode => 17,
+            NodeType::MinMaxNode => 17,
+            NodeType::ProvableSumNode => 9,
+            NodeType::ProvableBigSumNode => 17,
         }
     }
 
@@ -58,6 +67,9 @@ impl NodeType {
             NodeType::CountSumNode => 16,
             NodeType::ProvableCountNode => 8,
             NodeType::ProvableCountSumNode => 16,
+            NodeType::MinMaxNode => 16,
+            NodeType::ProvableSumNode => 8,
+            NodeType::ProvableBigSumNode => 16,
         }
     }
 }
@@ -79,6 +91,13 @@ pub enum TreeFeatureType {
     ProvableCountedMerkNode(u64),
     /// Provable Counted and Summed Merk Tree Node (count in hash, sum tracked)
     ProvableCountedSummedMerkNode(u64, i64),
+    /// Min Max Merk Tree Node (min and max in hash), `(i64::MAX, i64::MIN)`
+    /// when there is no min max value
+    MinMaxMerkNode(i64, i64),
+    /// Provable Summed Merk Tree Node (sum in hash)
+    ProvableSummedMerkNode(i64),
+    /// Provable Big Summed Merk Tree Node (sum in hash)
+    ProvableBigSummedMerkNode(i128),
 }
 
 impl TreeFeatureType {
@@ -92,7 +111,33 @@ impl TreeFeatureType {
             | ProvableCountedMerkNode(count)
             | CountedSummedMerkNode(count, _)
             | ProvableCountedSummedMerkNode(count, _) => Some(*count),
-            BasicMerkNode | SummedMerkNode(_) | BigSummedMerkNode(_) => None,
+            BasicMerkNode
+            | SummedMerkNode(_)
+            | BigSummedMerkNode(_)
+            | MinMaxMerkNode(..)
+            | ProvableSummedMerkNode(_)
+            | ProvableBigSummedMerkNode(_) => None,
+        }
+    }
+
+    /// Returns the minimum and maximum of this subtree, if available.
+    /// Returns Some((min, max)) for MinMaxMerkNode, None for every other
+    /// variant.
+    pub fn min_max(&self) -> Option<(i64, i64)> {
+        match self {
+            MinMaxMerkNode(min, max) => Some((*min, *max)),
+            _ => None,
+        }
+    }
+
+    /// Returns the sum committed into the hash of this subtree, if any.
+    /// Returns Some(sum) for ProvableSummedMerkNode and
+    /// ProvableBigSummedMerkNode, None for every other variant.
+    pub fn provable_sum(&self) -> Option<i128> {
+        match self {
+            ProvableSummedMerkNode(sum) => Some(*sum as i128),
+            ProvableBigSummedMerkNode(sum) => Some(*sum),
+            _ => None,
         }
     }
 
@@ -106,6 +151,9 @@ impl TreeFeatureType {
             CountedSummedMerkNode(..) => NodeType::CountSumNode,
             ProvableCountedMerkNode(_) => NodeType::ProvableCountNode,
             ProvableCountedSummedMerkNode(..) => NodeType::ProvableCountSumNode,
+            MinMaxMerkNode(..) => NodeType::MinMaxNode,
+            ProvableSummedMerkNode(_) => NodeType::ProvableSumNode,
+            ProvableBigSummedMerkNode(_) => NodeType::ProvableBigSumNode,
         }
     }
 
@@ -120,6 +168,9 @@ impl TreeFeatureType {
             CountedSummedMerkNode(..) => 17,
             ProvableCountedMerkNode(_) => 9,
             ProvableCountedSummedMerkNode(..) => 17,
+            MinMaxMerkNode(..) => 17,
+            ProvableSummedMerkNode(_) => 9,
+            ProvableBigSummedMerkNode(_) => 17,
         }
     }
 }
@@ -153,6 +204,15 @@ impl TreeFeatureType {
                 TreeCostType::TreeFeatureUsesTwoVarIntsCostAs16Bytes,
                 count.encode_var_vec().len() as u32 + sum.encode_var_vec().len() as u32,
             )),
+            MinMaxMerkNode(min, max) => Some((
+                TreeCostType::TreeFeatureUsesTwoVarIntsCostAs16Bytes,
+                min.encode_var_vec().len() as u32 + max.encode_var_vec().len() as u32,
+            )),
+            ProvableSummedMerkNode(m) => Some((
+                TreeCostType::TreeFeatureUsesVarIntCostAs8Bytes,
+                m.encode_var_vec().len() as u32,
+            )),
+            ProvableBigSummedMerkNode(_) => Some((TreeCostType::TreeFeatureUses16Bytes, 16)),
         }
     }
 }
@@ -197,6 +257,22 @@ impl Encode for TreeFeatureType {
                 dest.write_varint(*sum)?;
                 Ok(())
             }
+            MinMaxMerkNode(min, max) => {
+                dest.write_all(&[7])?;
+                dest.write_varint(*min)?;
+                dest.write_varint(*max)?;
+                Ok(())
+            }
+            ProvableSummedMerkNode(sum) => {
+                dest.write_all(&[8])?;
+                dest.write_varint(*sum)?;
+                Ok(())
+            }
+            ProvableBigSummedMerkNode(sum) => {
+                dest.write_all(&[9])?;
+                dest.write_i128::<BigEndian>(*sum)?;
+                Ok(())
+            }
         }
     }
 
@@ -225,6 +301,15 @@ impl Encode for TreeFeatureType {
                 let encoded_lengths = count.encode_var_vec().len() + sum.encode_var_vec().len();
                 Ok(1 + encoded_lengths)
             }
+            MinMaxMerkNode(min, max) => {
+                let encoded_lengths = min.encode_var_vec().len() + max.encode_var_vec().len();
+                Ok(1 + encoded_lengths)
+            }
+            ProvableSummedMerkNode(sum) => {
+                let encoded_sum = sum.encode_var_vec();
+                Ok(1 + encoded_sum.len())
+            }
+            ProvableBigSummedMerkNode(_) => Ok(17),
         }
     }
 }
@@ -264,6 +349,19 @@ impl Decode for TreeFeatureType {
                 let encoded_sum: i64 = input.read_varint()?;
                 Ok(ProvableCountedSummedMerkNode(encoded_count, encoded_sum))
             }
+            [7] => {
+                let encoded_min: i64 = input.read_varint()?;
+                let encoded_max: i64 = input.read_varint()?;
+                Ok(MinMaxMerkNode(encoded_min, encoded_max))
+            }
+            [8] => {
+                let encoded_sum: i64 = input.read_varint()?;
+                Ok(ProvableSummedMerkNode(encoded_sum))
+            }
+            [9] => {
+                let encoded_sum: i128 = input.read_i128::<BigEndian>()?;
+                Ok(ProvableBigSummedMerkNode(encoded_sum))
+            }
             [b] => Err(ed::Error::UnexpectedByte(b)),
         }
     }
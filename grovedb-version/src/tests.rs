@@ -4,6 +4,7 @@ use crate::version::merk_versions::*;
 use crate::version::v1::GROVE_V1;
 use crate::version::v2::GROVE_V2;
 use crate::version::v3::GROVE_V3;
+use crate::version::v4::GROVE_V4;
 use crate::version::{GroveVersion, GROVE_VERSIONS};
 use crate::{TryFromVersioned, TryIntoVersioned};
 
@@ -64,14 +65,14 @@ fn grove_version_first_returns_v1() {
 }
 
 #[test]
-fn grove_version_latest_returns_v3() {
+fn grove_version_latest_returns_v4() {
     let latest = GroveVersion::latest();
-    assert_eq!(latest.protocol_version, GROVE_V3.protocol_version);
+    assert_eq!(latest.protocol_version, GROVE_V4.protocol_version);
 }
 
 #[test]
 fn grove_versions_count() {
-    assert_eq!(GROVE_VERSIONS.len(), 3);
+    assert_eq!(GROVE_VERSIONS.len(), 4);
 }
 
 #[test]
@@ -157,6 +158,32 @@ fn v2_has_updated_merk_average_case_costs() {
     );
 }
 
+#[test]
+fn v4_charges_subtree_registry_updates() {
+    assert_eq!(
+        GROVE_V3
+            .grovedb_versions
+            .operations
+            .subtree_registry
+            .apply_subtree_registry_update,
+        0
+    );
+    assert_eq!(
+        GROVE_V4
+            .grovedb_versions
+            .operations
+            .subtree_registry
+            .apply_subtree_registry_update,
+        1
+    );
+}
+
+#[test]
+fn v4_has_updated_path_query_push() {
+    assert_eq!(GROVE_V3.grovedb_versions.element.path_query_push, 0);
+    assert_eq!(GROVE_V4.grovedb_versions.element.path_query_push, 1);
+}
+
 // ── Default trait for version structs ─────────────────────────────────
 
 #[test]
@@ -190,6 +217,7 @@ fn grovedb_operations_all_sub_structs_default() {
     let _ = GroveDBOperationsProofVersions::default();
     let _ = GroveDBOperationsAverageCaseVersions::default();
     let _ = GroveDBOperationsWorstCaseVersions::default();
+    let _ = GroveDBOperationsSubtreeRegistryVersions::default();
     let _ = GroveDBPathQueryMethodVersions::default();
     let _ = GroveDBReplicationVersions::default();
     let _ = GroveDBApplyBatchVersions::default();
@@ -62,6 +62,7 @@ pub struct GroveDBOperationsVersions {
     pub proof: GroveDBOperationsProofVersions,
     pub average_case: GroveDBOperationsAverageCaseVersions,
     pub worst_case: GroveDBOperationsWorstCaseVersions,
+    pub subtree_registry: GroveDBOperationsSubtreeRegistryVersions,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -156,6 +157,11 @@ pub struct GroveDBOperationsWorstCaseVersions {
     pub add_worst_case_get_cost: FeatureVersion,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct GroveDBOperationsSubtreeRegistryVersions {
+    pub apply_subtree_registry_update: FeatureVersion,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct GroveDBOperationsInsertVersions {
     pub insert: FeatureVersion,
@@ -3,12 +3,13 @@ pub mod merk_versions;
 pub mod v1;
 pub mod v2;
 pub mod v3;
+pub mod v4;
 
 pub use versioned_feature_core::*;
 
-use crate::version::v3::GROVE_V3;
 use crate::version::{
     grovedb_versions::GroveDBVersions, merk_versions::MerkVersions, v1::GROVE_V1, v2::GROVE_V2,
+    v3::GROVE_V3, v4::GROVE_V4,
 };
 
 #[derive(Clone, Debug, Default)]
@@ -32,4 +33,4 @@ impl GroveVersion {
     }
 }
 
-pub const GROVE_VERSIONS: &[GroveVersion] = &[GROVE_V1, GROVE_V2, GROVE_V3];
+pub const GROVE_VERSIONS: &[GroveVersion] = &[GROVE_V1, GROVE_V2, GROVE_V3, GROVE_V4];
@@ -5,9 +5,10 @@ use crate::version::{
         GroveDBOperationsAverageCaseVersions, GroveDBOperationsDeleteUpTreeVersions,
         GroveDBOperationsDeleteVersions, GroveDBOperationsGetVersions,
         GroveDBOperationsInsertVersions, GroveDBOperationsProofVersions,
-        GroveDBOperationsQueryVersions, GroveDBOperationsVersions,
-        GroveDBOperationsWorstCaseVersions, GroveDBPathQueryMethodVersions, GroveDBQueryLimits,
-        GroveDBReplicationVersions, GroveDBVersions,
+        GroveDBOperationsQueryVersions, GroveDBOperationsSubtreeRegistryVersions,
+        GroveDBOperationsVersions, GroveDBOperationsWorstCaseVersions,
+        GroveDBPathQueryMethodVersions, GroveDBQueryLimits, GroveDBReplicationVersions,
+        GroveDBVersions,
     },
     merk_versions::{MerkAverageCaseCostsVersions, MerkBatchVersions, MerkVersions},
     GroveVersion,
@@ -187,6 +188,9 @@ pub const GROVE_V1: GroveVersion = GroveVersion {
                 add_worst_case_get_raw_cost: 0,
                 add_worst_case_get_cost: 0,
             },
+            subtree_registry: GroveDBOperationsSubtreeRegistryVersions {
+                apply_subtree_registry_update: 0,
+            },
         },
         aggregate_sum_path_query_methods: GroveDBAggregateSumPathQueryMethodVersions { merge: 0 },
         path_query_methods: GroveDBPathQueryMethodVersions {
@@ -561,9 +561,9 @@ mod tests {
         assert_eq!(
             average_case_cost,
             OperationCost {
-                seek_count: 3,
+                seek_count: 4,
                 storage_cost: StorageCost {
-                    added_bytes: 115,
+                    added_bytes: 203,
                     replaced_bytes: 0,
                     removed_bytes: NoStorageRemoval,
                 },
@@ -629,9 +629,9 @@ mod tests {
         assert_eq!(
             average_case_cost,
             OperationCost {
-                seek_count: 3,
+                seek_count: 4,
                 storage_cost: StorageCost {
-                    added_bytes: 119,
+                    added_bytes: 207,
                     replaced_bytes: 0,
                     removed_bytes: NoStorageRemoval,
                 },
@@ -779,9 +779,9 @@ mod tests {
         assert_eq!(
             average_case_cost,
             OperationCost {
-                seek_count: 5, // todo: why is this 5
+                seek_count: 6, // todo: why is this 5
                 storage_cost: StorageCost {
-                    added_bytes: 115,
+                    added_bytes: 203,
                     replaced_bytes: 75,
                     removed_bytes: NoStorageRemoval,
                 },
@@ -873,9 +873,9 @@ mod tests {
         assert_eq!(
             average_case_cost,
             OperationCost {
-                seek_count: 6,
+                seek_count: 7,
                 storage_cost: StorageCost {
-                    added_bytes: 115,
+                    added_bytes: 203,
                     replaced_bytes: 75,
                     removed_bytes: NoStorageRemoval,
                 },
@@ -514,9 +514,9 @@ mod tests {
         assert_eq!(
             worst_case_cost,
             OperationCost {
-                seek_count: 5,
+                seek_count: 6,
                 storage_cost: StorageCost {
-                    added_bytes: 115,
+                    added_bytes: 203,
                     replaced_bytes: 65535, // todo: verify
                     removed_bytes: NoStorageRemoval,
                 },
@@ -570,9 +570,9 @@ mod tests {
         assert_eq!(
             worst_case_cost,
             OperationCost {
-                seek_count: 4,
+                seek_count: 5,
                 storage_cost: StorageCost {
-                    added_bytes: 119,
+                    added_bytes: 207,
                     replaced_bytes: 0,
                     removed_bytes: NoStorageRemoval,
                 },
@@ -693,9 +693,9 @@ mod tests {
         assert_eq!(
             worst_case_cost,
             OperationCost {
-                seek_count: 38,
+                seek_count: 39,
                 storage_cost: StorageCost {
-                    added_bytes: 115,
+                    added_bytes: 203,
                     replaced_bytes: 2228190, // todo: verify
                     removed_bytes: NoStorageRemoval,
                 },
@@ -758,9 +758,9 @@ mod tests {
         assert_eq!(
             worst_case_cost,
             OperationCost {
-                seek_count: 7,
+                seek_count: 8,
                 storage_cost: StorageCost {
-                    added_bytes: 115,
+                    added_bytes: 203,
                     replaced_bytes: 81996,
                     removed_bytes: NoStorageRemoval,
                 },
@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, ops::DerefMut};
 
 use grovedb_costs::{
     cost_return_on_error_into_no_add, cost_return_on_error_no_add,
@@ -21,9 +21,10 @@ use crate::{
     Element, ElementFlags, Error,
 };
 
-impl<'db, S, F> TreeCacheMerkByPath<S, F>
+impl<'db, S, M, F> TreeCacheMerkByPath<M, F>
 where
-    F: FnMut(&[Vec<u8>], bool) -> CostResult<Merk<S>, Error>,
+    F: FnMut(&[Vec<u8>], bool) -> CostResult<M, Error>,
+    M: DerefMut<Target = Merk<S>>,
     S: StorageContext<'db>,
 {
     pub(crate) fn process_old_element_flags<G, SR>(
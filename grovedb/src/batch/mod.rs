@@ -16,6 +16,7 @@ mod just_in_time_cost_tests;
 /// Just-in-time reference update handling for batch operations.
 pub mod just_in_time_reference_update;
 mod options;
+mod simulation;
 #[cfg(test)]
 mod single_deletion_cost_tests;
 #[cfg(test)]
@@ -24,13 +25,14 @@ mod single_insert_cost_tests;
 mod single_sum_item_deletion_cost_tests;
 #[cfg(test)]
 mod single_sum_item_insert_cost_tests;
+mod storage_fee_report;
 
 use core::fmt;
 use std::{
     cmp::Ordering,
     collections::{btree_map::Entry, hash_map::Entry as HashMapEntry, BTreeMap, HashMap, HashSet},
     hash::{Hash, Hasher},
-    ops::{Add, AddAssign},
+    ops::{Add, AddAssign, DerefMut},
     slice::Iter,
     vec::IntoIter,
 };
@@ -62,9 +64,9 @@ use grovedb_merk::{
     tree_type::{CostSize, TreeType, SUM_ITEM_COST_SIZE},
     CryptoHash, Error as MerkError, Merk, MerkType, Op, RootHashKeyAndAggregateData,
 };
-use grovedb_path::SubtreePath;
+use grovedb_path::{SubtreePath, SubtreePathBuilder};
 use grovedb_storage::{
-    rocksdb_storage::PrefixedRocksDbTransactionContext, Storage, StorageBatch, StorageContext,
+    backend::PrefixedBackendTransactionContext, Storage, StorageBatch, StorageContext,
 };
 use grovedb_version::{check_grovedb_v0_with_cost, version::GroveVersion};
 use grovedb_visualize::{Drawer, Visualize};
@@ -72,6 +74,7 @@ use integer_encoding::VarInt;
 use itertools::Itertools;
 use key_info::{KeyInfo, KeyInfo::KnownKey};
 pub use options::BatchApplyOptions;
+pub use simulation::BatchSimulation;
 
 pub use crate::batch::batch_structure::{OpsByLevelPath, OpsByPath};
 #[cfg(feature = "estimated_costs")]
@@ -79,10 +82,13 @@ use crate::batch::estimated_costs::EstimatedCostsType;
 use crate::{
     batch::{batch_structure::BatchStructure, mode::BatchRunMode},
     element::MaxReferenceHop,
+    merk_cache::MerkCache,
     operations::{delete::DeleteOptions, get::MAX_REFERENCE_HOPS, proof::util::hex_to_ascii},
     reference_path::{
         path_from_reference_path_type, path_from_reference_qualified_path_type, ReferencePathType,
     },
+    secondary_index::check_items_with_expiry_are_indexed,
+    subtree_registry::SubtreeRegistryUpdate,
     util::TxRef,
     Element, ElementFlags, Error, GroveDb, Transaction, TransactionArg,
 };
@@ -1093,12 +1099,16 @@ impl GroveDbOpConsistencyResults {
 }
 
 /// Cache for Merk trees by their paths.
-struct TreeCacheMerkByPath<S, F> {
-    merks: HashMap<Vec<Vec<u8>>, Merk<S>>,
+///
+/// `M` is anything holding a Merk, such as the
+/// [`TakenMerk`](crate::merk_cache::TakenMerk)s borrowed from the
+/// [`MerkCache`] of `apply_batch`.
+struct TreeCacheMerkByPath<M, F> {
+    merks: HashMap<Vec<Vec<u8>>, M>,
     get_merk_fn: F,
 }
 
-impl<S, F> fmt::Debug for TreeCacheMerkByPath<S, F> {
+impl<M, F> fmt::Debug for TreeCacheMerkByPath<M, F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("TreeCacheMerkByPath").finish()
     }
@@ -1134,9 +1144,10 @@ trait TreeCache<G, SR> {
     ) -> CostResult<(), Error>;
 }
 
-impl<'db, S, F> TreeCacheMerkByPath<S, F>
+impl<'db, S, M, F> TreeCacheMerkByPath<M, F>
 where
-    F: FnMut(&[Vec<u8>], bool) -> CostResult<Merk<S>, Error>,
+    F: FnMut(&[Vec<u8>], bool) -> CostResult<M, Error>,
+    M: DerefMut<Target = Merk<S>>,
     S: StorageContext<'db>,
 {
     /// Processes a reference, determining whether it can be retrieved from a
@@ -1442,7 +1453,11 @@ where
         };
 
         match element {
-            Element::Item(..) | Element::SumItem(..) | Element::ItemWithSumItem(..) => {
+            Element::Item(..)
+            | Element::SumItem(..)
+            | Element::ItemWithSumItem(..)
+            | Element::MinMaxItem(..)
+            | Element::ItemWithExpiry(..) => {
                 let serialized =
                     cost_return_on_error_into_no_add!(cost, element.serialize(grove_version));
                 let val_hash = value_hash(&serialized).unwrap_add_cost(&mut cost);
@@ -1473,7 +1488,10 @@ where
             | Element::CommitmentTree(..)
             | Element::MmrTree(..)
             | Element::BulkAppendTree(..)
-            | Element::DenseAppendOnlyFixedSizeTree(..) => Err(Error::InvalidBatchOperation(
+            | Element::DenseAppendOnlyFixedSizeTree(..)
+            | Element::MinMaxTree(..)
+            | Element::ProvableSumTree(..)
+            | Element::ProvableBigSumTree(..) => Err(Error::InvalidBatchOperation(
                 "references can not point to trees being updated",
             ))
             .wrap_with_cost(cost),
@@ -1542,7 +1560,11 @@ where
                 | GroveOp::Replace { element }
                 | GroveOp::Patch { element, .. } => {
                     match element {
-                        Element::Item(..) | Element::SumItem(..) | Element::ItemWithSumItem(..) => {
+                        Element::Item(..)
+                        | Element::SumItem(..)
+                        | Element::ItemWithSumItem(..)
+                        | Element::MinMaxItem(..)
+                        | Element::ItemWithExpiry(..) => {
                             let serialized = cost_return_on_error_into_no_add!(
                                 cost,
                                 element.serialize(grove_version)
@@ -1619,17 +1641,22 @@ where
                         | Element::CommitmentTree(..)
                         | Element::MmrTree(..)
                         | Element::BulkAppendTree(..)
-                        | Element::DenseAppendOnlyFixedSizeTree(..) => {
-                            Err(Error::InvalidBatchOperation(
-                                "references can not point to trees being updated",
-                            ))
-                            .wrap_with_cost(cost)
-                        }
+                        | Element::DenseAppendOnlyFixedSizeTree(..)
+                        | Element::MinMaxTree(..)
+                        | Element::ProvableSumTree(..)
+                        | Element::ProvableBigSumTree(..) => Err(Error::InvalidBatchOperation(
+                            "references can not point to trees being updated",
+                        ))
+                        .wrap_with_cost(cost),
                     }
                 }
                 GroveOp::InsertWithKnownToNotAlreadyExist { element }
                 | GroveOp::InsertIfNotExists { element, .. } => match element {
-                    Element::Item(..) | Element::SumItem(..) | Element::ItemWithSumItem(..) => {
+                    Element::Item(..)
+                    | Element::SumItem(..)
+                    | Element::ItemWithSumItem(..)
+                    | Element::MinMaxItem(..)
+                    | Element::ItemWithExpiry(..) => {
                         let serialized = cost_return_on_error_into_no_add!(
                             cost,
                             element.serialize(grove_version)
@@ -1662,12 +1689,13 @@ where
                     | Element::CommitmentTree(..)
                     | Element::MmrTree(..)
                     | Element::BulkAppendTree(..)
-                    | Element::DenseAppendOnlyFixedSizeTree(..) => {
-                        Err(Error::InvalidBatchOperation(
-                            "references can not point to trees being updated",
-                        ))
-                        .wrap_with_cost(cost)
-                    }
+                    | Element::DenseAppendOnlyFixedSizeTree(..)
+                    | Element::MinMaxTree(..)
+                    | Element::ProvableSumTree(..)
+                    | Element::ProvableBigSumTree(..) => Err(Error::InvalidBatchOperation(
+                        "references can not point to trees being updated",
+                    ))
+                    .wrap_with_cost(cost),
                 },
                 GroveOp::RefreshReference {
                     reference_path_type,
@@ -1711,7 +1739,7 @@ where
     }
 }
 
-impl<'db, S, F, G, SR> TreeCache<G, SR> for TreeCacheMerkByPath<S, F>
+impl<'db, S, M, F, G, SR> TreeCache<G, SR> for TreeCacheMerkByPath<M, F>
 where
     G: FnMut(&StorageCost, Option<ElementFlags>, &mut ElementFlags) -> Result<bool, Error>,
     SR: FnMut(
@@ -1719,7 +1747,8 @@ where
         u32,
         u32,
     ) -> Result<(StorageRemovedBytes, StorageRemovedBytes), Error>,
-    F: FnMut(&[Vec<u8>], bool) -> CostResult<Merk<S>, Error>,
+    F: FnMut(&[Vec<u8>], bool) -> CostResult<M, Error>,
+    M: DerefMut<Target = Merk<S>>,
     S: StorageContext<'db>,
 {
     fn insert(
@@ -1934,7 +1963,10 @@ where
                         | Element::ProvableCountSumTree(..)
                         | Element::MmrTree(..)
                         | Element::BulkAppendTree(..)
-                        | Element::DenseAppendOnlyFixedSizeTree(..) => {
+                        | Element::DenseAppendOnlyFixedSizeTree(..)
+                        | Element::MinMaxTree(..)
+                        | Element::ProvableSumTree(..)
+                        | Element::ProvableBigSumTree(..) => {
                             // Check existence for InsertIfNotExists on subtrees
                             if is_insert_if_not_exists
                                 || batch_apply_options.validate_insertion_does_not_override
@@ -1998,7 +2030,11 @@ where
                                 )
                             );
                         }
-                        Element::Item(..) | Element::SumItem(..) | Element::ItemWithSumItem(..) => {
+                        Element::Item(..)
+                        | Element::SumItem(..)
+                        | Element::ItemWithSumItem(..)
+                        | Element::MinMaxItem(..)
+                        | Element::ItemWithExpiry(..) => {
                             let merk_feature_type = cost_return_on_error_into!(
                                 &mut cost,
                                 element
@@ -2246,6 +2282,21 @@ where
                         AggregateData::ProvableCountAndSum(count_value, sum_value) => {
                             Element::ProvableCountSumTree(root_key, count_value, sum_value, flags)
                         }
+                        AggregateData::MinMax(min_value, max_value) => {
+                            Element::new_min_max_tree_with_flags_and_min_max_value(
+                                root_key, min_value, max_value, flags,
+                            )
+                        }
+                        AggregateData::ProvableSum(sum_value) => {
+                            Element::new_provable_sum_tree_with_flags_and_sum_value(
+                                root_key, sum_value, flags,
+                            )
+                        }
+                        AggregateData::ProvableBigSum(sum_value) => {
+                            Element::new_provable_big_sum_tree_with_flags_and_sum_value(
+                                root_key, sum_value, flags,
+                            )
+                        }
                     };
                     let merk_feature_type = cost_return_on_error_into_no_add!(
                         cost,
@@ -2388,7 +2439,10 @@ where
                                     | Element::CommitmentTree(..)
                                     | Element::MmrTree(..)
                                     | Element::BulkAppendTree(..)
-                                    | Element::DenseAppendOnlyFixedSizeTree(..) => {
+                                    | Element::DenseAppendOnlyFixedSizeTree(..)
+                                    | Element::MinMaxTree(..)
+                                    | Element::ProvableSumTree(..)
+                                    | Element::ProvableBigSumTree(..) => {
                                         let tree_type = new_element
                                             .tree_type()
                                             .expect("tree_type guaranteed by match arm");
@@ -2662,6 +2716,36 @@ impl GroveDb {
                                                         ..,
                                                         flags,
                                                     ) = element
+                                                    {
+                                                        *mutable_occupied_entry =
+                                                            GroveOp::InsertTreeWithRootHash {
+                                                                hash: root_hash,
+                                                                root_key: calculated_root_key,
+                                                                flags: flags.clone(),
+                                                                aggregate_data,
+                                                            }
+                                                    } else if let Element::MinMaxTree(.., flags) =
+                                                        element
+                                                    {
+                                                        *mutable_occupied_entry =
+                                                            GroveOp::InsertTreeWithRootHash {
+                                                                hash: root_hash,
+                                                                root_key: calculated_root_key,
+                                                                flags: flags.clone(),
+                                                                aggregate_data,
+                                                            }
+                                                    } else if let Element::ProvableSumTree(.., flags) =
+                                                        element
+                                                    {
+                                                        *mutable_occupied_entry =
+                                                            GroveOp::InsertTreeWithRootHash {
+                                                                hash: root_hash,
+                                                                root_key: calculated_root_key,
+                                                                flags: flags.clone(),
+                                                                aggregate_data,
+                                                            }
+                                                    } else if let Element::ProvableBigSumTree(.., flags) =
+                                                        element
                                                     {
                                                         *mutable_occupied_entry =
                                                             GroveOp::InsertTreeWithRootHash {
@@ -2840,7 +2924,7 @@ impl GroveDb {
     /// Method to propagate updated subtree root hashes up to GroveDB root
     /// If the pause height is set in the batch apply options
     /// Then return the list of leftover operations
-    fn apply_body<'db, S: StorageContext<'db>>(
+    fn apply_body<'db, S: StorageContext<'db>, M: DerefMut<Target = Merk<S>>>(
         &self,
         ops: Vec<QualifiedGroveDbOp>,
         batch_apply_options: Option<BatchApplyOptions>,
@@ -2857,7 +2941,7 @@ impl GroveDb {
             (StorageRemovedBytes, StorageRemovedBytes),
             Error,
         >,
-        get_merk_fn: impl FnMut(&[Vec<u8>], bool) -> CostResult<Merk<S>, Error>,
+        get_merk_fn: impl FnMut(&[Vec<u8>], bool) -> CostResult<M, Error>,
         grove_version: &GroveVersion,
     ) -> CostResult<Option<OpsByLevelPath>, Error> {
         check_grovedb_v0_with_cost!(
@@ -2884,7 +2968,7 @@ impl GroveDb {
     /// Method to propagate updated subtree root hashes up to GroveDB root
     /// If the pause height is set in the batch apply options
     /// Then return the list of leftover operations
-    fn continue_partial_apply_body<'db, S: StorageContext<'db>>(
+    fn continue_partial_apply_body<'db, S: StorageContext<'db>, M: DerefMut<Target = Merk<S>>>(
         &self,
         previous_leftover_operations: Option<OpsByLevelPath>,
         additional_ops: Vec<QualifiedGroveDbOp>,
@@ -2902,7 +2986,7 @@ impl GroveDb {
             (StorageRemovedBytes, StorageRemovedBytes),
             Error,
         >,
-        get_merk_fn: impl FnMut(&[Vec<u8>], bool) -> CostResult<Merk<S>, Error>,
+        get_merk_fn: impl FnMut(&[Vec<u8>], bool) -> CostResult<M, Error>,
         grove_version: &GroveVersion,
     ) -> CostResult<Option<OpsByLevelPath>, Error> {
         check_grovedb_v0_with_cost!(
@@ -3292,7 +3376,7 @@ impl GroveDb {
         tx: &'db Transaction,
         new_merk: bool,
         grove_version: &GroveVersion,
-    ) -> CostResult<Merk<PrefixedRocksDbTransactionContext<'db>>, Error> {
+    ) -> CostResult<Merk<PrefixedBackendTransactionContext<'db>>, Error> {
         check_grovedb_v0_with_cost!(
             "open_batch_transactional_merk_at_path",
             grove_version
@@ -3405,9 +3489,106 @@ impl GroveDb {
                 .apply_batch
                 .apply_batch_with_element_flags_update
         );
+        self.apply_batch_inspecting_storage(
+            ops,
+            batch_apply_options,
+            update_element_flags_function,
+            split_removal_bytes_function,
+            |_| {},
+            transaction,
+            grove_version,
+        )
+    }
+
+    /// Applies batch of operations on GroveDB like
+    /// [`GroveDb::apply_batch_with_element_flags_update`], letting
+    /// `inspect_storage_batch` see the storage writes of the batch before
+    /// they are committed.
+    pub(crate) fn apply_batch_inspecting_storage(
+        &self,
+        ops: Vec<QualifiedGroveDbOp>,
+        batch_apply_options: Option<BatchApplyOptions>,
+        update_element_flags_function: impl FnMut(
+            &StorageCost,
+            Option<ElementFlags>,
+            &mut ElementFlags,
+        ) -> Result<bool, Error>,
+        split_removal_bytes_function: impl FnMut(
+            &mut ElementFlags,
+            u32, // key removed bytes
+            u32, // value removed bytes
+        ) -> Result<
+            (StorageRemovedBytes, StorageRemovedBytes),
+            Error,
+        >,
+        inspect_storage_batch: impl FnOnce(&StorageBatch),
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(), Error> {
         let mut cost = OperationCost::default();
         let tx = TxRef::new(&self.db, transaction);
 
+        let ops = cost_return_on_error!(
+            &mut cost,
+            self.add_secondary_index_ops(ops, tx.as_ref(), grove_version)
+        );
+
+        let changes = self.capture_changes(
+            || {
+                ops.iter().map(|op| {
+                    (
+                        op.path.to_path(),
+                        op.key.as_ref().map(KeyInfo::get_key_clone),
+                    )
+                })
+            },
+            tx.as_ref(),
+            grove_version,
+        );
+
+        cost_return_on_error!(
+            &mut cost,
+            self.apply_batch_on_transaction(
+                ops,
+                batch_apply_options,
+                update_element_flags_function,
+                split_removal_bytes_function,
+                inspect_storage_batch,
+                tx.as_ref(),
+                grove_version,
+            )
+        );
+
+        self.commit_local_with_changes(tx, changes, grove_version)
+            .wrap_with_cost(cost)
+    }
+
+    /// Applies batch of operations within `transaction` without committing
+    /// it. `inspect_storage_batch` sees the storage writes of the batch right
+    /// before they are committed to the transaction.
+    pub(crate) fn apply_batch_on_transaction(
+        &self,
+        ops: Vec<QualifiedGroveDbOp>,
+        batch_apply_options: Option<BatchApplyOptions>,
+        update_element_flags_function: impl FnMut(
+            &StorageCost,
+            Option<ElementFlags>,
+            &mut ElementFlags,
+        ) -> Result<bool, Error>,
+        split_removal_bytes_function: impl FnMut(
+            &mut ElementFlags,
+            u32, // key removed bytes
+            u32, // value removed bytes
+        ) -> Result<
+            (StorageRemovedBytes, StorageRemovedBytes),
+            Error,
+        >,
+        inspect_storage_batch: impl FnOnce(&StorageBatch),
+        transaction: &Transaction,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(), Error> {
+        let mut cost = OperationCost::default();
+
         if ops.is_empty() {
             return Ok(()).wrap_with_cost(cost);
         }
@@ -3438,28 +3619,28 @@ impl GroveDb {
         // then convert to ReplaceTreeRootKey ops
         let ops = cost_return_on_error!(
             &mut cost,
-            self.preprocess_commitment_tree_ops(ops, tx.as_ref(), &storage_batch, grove_version)
+            self.preprocess_commitment_tree_ops(ops, transaction, &storage_batch, grove_version)
         );
 
         // Preprocess MmrTreeAppend ops: execute MMR operations
         // then convert to ReplaceTreeRootKey ops
         let ops = cost_return_on_error!(
             &mut cost,
-            self.preprocess_mmr_tree_ops(ops, tx.as_ref(), &storage_batch, grove_version)
+            self.preprocess_mmr_tree_ops(ops, transaction, &storage_batch, grove_version)
         );
 
         // Preprocess BulkAppend ops: execute bulk append operations
         // then convert to ReplaceTreeRootKey ops
         let ops = cost_return_on_error!(
             &mut cost,
-            self.preprocess_bulk_append_ops(ops, tx.as_ref(), &storage_batch, grove_version)
+            self.preprocess_bulk_append_ops(ops, transaction, &storage_batch, grove_version)
         );
 
         // Preprocess DenseTreeInsert ops: execute dense tree operations
         // then convert to ReplaceTreeRootKey ops
         let ops = cost_return_on_error!(
             &mut cost,
-            self.preprocess_dense_tree_ops(ops, tx.as_ref(), &storage_batch, grove_version)
+            self.preprocess_dense_tree_ops(ops, transaction, &storage_batch, grove_version)
         );
 
         // Collect paths of subtrees being deleted, separated by type.
@@ -3479,6 +3660,10 @@ impl GroveDb {
         // nested subtrees.
         let mut non_merk_delete_paths: Vec<Vec<Vec<u8>>> = Vec::new();
         let mut merk_delete_paths: Vec<Vec<Vec<u8>>> = Vec::new();
+        // Merks opened by the emptiness checks, by apply_body and while
+        // resolving references are opened once for the whole batch.
+        let merk_cache: MerkCache<Vec<u8>> =
+            MerkCache::new_for_batch(self, transaction, &storage_batch, grove_version);
         // Track paths skipped due to SubelementsDeletionBehavior::Skip so we can
         // filter the corresponding ops out of the batch before apply_body.
         let mut skipped_delete_paths: HashSet<Vec<Vec<u8>>> = HashSet::new();
@@ -3512,7 +3697,7 @@ impl GroveDb {
                                 .get_transactional_storage_context(
                                     parent_path,
                                     Some(&storage_batch),
-                                    tx.as_ref(),
+                                    transaction,
                                 )
                                 .unwrap_add_cost(&mut cost);
                             let element = cost_return_on_error!(
@@ -3563,19 +3748,15 @@ impl GroveDb {
                             let batch_deleted_keys_refs: std::collections::BTreeSet<&[u8]> =
                                 batch_deleted_keys.iter().map(|k| k.as_slice()).collect();
 
-                            let child_merk = cost_return_on_error!(
+                            let mut child_merk = cost_return_on_error!(
                                 &mut cost,
-                                self.open_batch_transactional_merk_at_path(
-                                    &storage_batch,
-                                    child_path.as_slice().into(),
-                                    tx.as_ref(),
-                                    false,
-                                    grove_version,
-                                )
+                                merk_cache.get_merk(SubtreePathBuilder::owned_from_iter(
+                                    child_path.iter()
+                                ))
                             );
 
                             child_merk
-                                .is_empty_tree_except(batch_deleted_keys_refs)
+                                .for_merk(|merk| merk.is_empty_tree_except(batch_deleted_keys_refs))
                                 .unwrap_add_cost(&mut cost)
                         };
 
@@ -3625,6 +3806,13 @@ impl GroveDb {
         } else {
             ops
         };
+        let mut registry_update = SubtreeRegistryUpdate::default();
+        registry_update.record_ops(
+            &ops,
+            batch_apply_options
+                .as_ref()
+                .is_some_and(|options| options.validate_insertion_does_not_override_tree),
+        );
 
         // With the only one difference (if there is a transaction) do the following:
         // 2. If nothing left to do and we were on a non-leaf subtree or we're done with
@@ -3644,17 +3832,20 @@ impl GroveDb {
                 update_element_flags_function,
                 split_removal_bytes_function,
                 |path, new_merk| {
-                    self.open_batch_transactional_merk_at_path(
-                        &storage_batch,
-                        path.into(),
-                        tx.as_ref(),
-                        new_merk,
-                        grove_version,
-                    )
+                    let path = SubtreePathBuilder::owned_from_iter(path);
+                    if new_merk {
+                        merk_cache.get_new_merk(path)
+                    } else {
+                        merk_cache.get_merk(path)
+                    }
+                    .map_ok(|merk| merk.take())
                 },
                 grove_version
             )
         );
+        // Root hashes were propagated by `apply_body`, the cache only spared
+        // opening Merks twice.
+        drop(merk_cache);
 
         // Clean up data storage for deleted non-Merk trees.
         for child_path in &non_merk_delete_paths {
@@ -3665,7 +3856,7 @@ impl GroveDb {
                 .get_transactional_storage_context(
                     child_subtree_path,
                     Some(&storage_batch),
-                    tx.as_ref(),
+                    transaction,
                 )
                 .unwrap_add_cost(&mut cost);
             cost_return_on_error!(
@@ -3696,13 +3887,13 @@ impl GroveDb {
             let child_subtree_path: SubtreePath<Vec<u8>> = child_path.as_slice().into();
             let subtrees_paths = cost_return_on_error!(
                 &mut cost,
-                self.find_subtrees(&child_subtree_path, Some(tx.as_ref()), grove_version)
+                self.find_subtrees(&child_subtree_path, Some(transaction), grove_version)
             );
             for subtree_path in subtrees_paths {
                 let p: SubtreePath<_> = subtree_path.as_slice().into();
                 let mut storage = self
                     .db
-                    .get_transactional_storage_context(p, Some(&storage_batch), tx.as_ref())
+                    .get_transactional_storage_context(p, Some(&storage_batch), transaction)
                     .unwrap_add_cost(&mut cost);
                 cost_return_on_error!(
                     &mut cost,
@@ -3712,16 +3903,23 @@ impl GroveDb {
                         ))
                     })
                 );
+                registry_update.remove(subtree_path);
             }
         }
 
+        inspect_storage_batch(&storage_batch);
+
         // TODO: compute batch costs
         cost_return_on_error!(
             &mut cost,
             self.db
-                .commit_multi_context_batch(storage_batch, Some(tx.as_ref()))
+                .commit_multi_context_batch(storage_batch, Some(transaction))
                 .map_err(|e| e.into())
         );
+        cost_return_on_error!(
+            &mut cost,
+            self.apply_subtree_registry_update(registry_update, transaction, grove_version)
+        );
 
         // Keep this commented for easy debugging in the future.
         // let issues = self
@@ -3738,7 +3936,7 @@ impl GroveDb {
         //     );
         // }
 
-        tx.commit_local().wrap_with_cost(cost)
+        Ok(()).wrap_with_cost(cost)
     }
 
     /// Applies a partial batch of operations on GroveDB
@@ -3783,6 +3981,10 @@ impl GroveDb {
             return Ok(()).wrap_with_cost(cost);
         }
 
+        // Partial batches don't maintain secondary indexes, so items with an
+        // expiry written here would never be swept.
+        cost_return_on_error_no_add!(cost, check_items_with_expiry_are_indexed(&ops, &[]));
+
         // Check batch operation consistency BEFORE preprocessing so that
         // conflicting ops (e.g., CommitmentTreeInsert + Delete on the same
         // path/key) are caught before any work is done.
@@ -3834,6 +4036,8 @@ impl GroveDb {
         // emptiness checks are needed (H2).
         let mut non_merk_delete_paths: Vec<Vec<Vec<u8>>> = Vec::new();
         let mut merk_delete_paths: Vec<Vec<Vec<u8>>> = Vec::new();
+        let merk_cache: MerkCache<Vec<u8>> =
+            MerkCache::new_for_batch(self, tx.as_ref(), &storage_batch, grove_version);
 
         let mut batch_apply_options = batch_apply_options.unwrap_or_default();
         let mut skipped_delete_paths: HashSet<Vec<Vec<u8>>> = HashSet::new();
@@ -3912,19 +4116,15 @@ impl GroveDb {
                             let batch_deleted_keys_refs: std::collections::BTreeSet<&[u8]> =
                                 batch_deleted_keys.iter().map(|k| k.as_slice()).collect();
 
-                            let child_merk = cost_return_on_error!(
+                            let mut child_merk = cost_return_on_error!(
                                 &mut cost,
-                                self.open_batch_transactional_merk_at_path(
-                                    &storage_batch,
-                                    child_path.as_slice().into(),
-                                    tx.as_ref(),
-                                    false,
-                                    grove_version,
-                                )
+                                merk_cache.get_merk(SubtreePathBuilder::owned_from_iter(
+                                    child_path.iter()
+                                ))
                             );
 
                             child_merk
-                                .is_empty_tree_except(batch_deleted_keys_refs)
+                                .for_merk(|merk| merk.is_empty_tree_except(batch_deleted_keys_refs))
                                 .unwrap_add_cost(&mut cost)
                         };
 
@@ -3974,6 +4174,11 @@ impl GroveDb {
         } else {
             ops
         };
+        let mut registry_update = SubtreeRegistryUpdate::default();
+        registry_update.record_ops(
+            &ops,
+            batch_apply_options.validate_insertion_does_not_override_tree,
+        );
         if batch_apply_options.batch_pause_height.is_none() {
             // we default to pausing at the root tree, which is the most common case
             batch_apply_options.batch_pause_height = Some(1);
@@ -3997,17 +4202,18 @@ impl GroveDb {
                 &mut update_element_flags_function,
                 &mut split_removal_bytes_function,
                 |path, new_merk| {
-                    self.open_batch_transactional_merk_at_path(
-                        &storage_batch,
-                        path.into(),
-                        tx.as_ref(),
-                        new_merk,
-                        grove_version,
-                    )
+                    let path = SubtreePathBuilder::owned_from_iter(path);
+                    if new_merk {
+                        merk_cache.get_new_merk(path)
+                    } else {
+                        merk_cache.get_merk(path)
+                    }
+                    .map_ok(|merk| merk.take())
                 },
                 grove_version
             )
         );
+        drop(merk_cache);
         // if we paused at the root height, the left over operations would be to replace
         // a lot of leaf nodes in the root tree
 
@@ -4057,10 +4263,21 @@ impl GroveDb {
             }
         }
 
+        cost_return_on_error_no_add!(
+            cost,
+            check_items_with_expiry_are_indexed(&new_operations, &[])
+        );
+        registry_update.record_ops(
+            &new_operations,
+            batch_apply_options.validate_insertion_does_not_override_tree,
+        );
+
         // we are trying to finalize
         batch_apply_options.batch_pause_height = None;
 
         let continue_storage_batch = StorageBatch::new();
+        let continue_merk_cache: MerkCache<Vec<u8>> =
+            MerkCache::new_for_batch(self, tx.as_ref(), &continue_storage_batch, grove_version);
 
         cost_return_on_error!(
             &mut cost,
@@ -4071,17 +4288,18 @@ impl GroveDb {
                 update_element_flags_function,
                 split_removal_bytes_function,
                 |path, new_merk| {
-                    self.open_batch_transactional_merk_at_path(
-                        &continue_storage_batch,
-                        path.into(),
-                        tx.as_ref(),
-                        new_merk,
-                        grove_version,
-                    )
+                    let path = SubtreePathBuilder::owned_from_iter(path);
+                    if new_merk {
+                        continue_merk_cache.get_new_merk(path)
+                    } else {
+                        continue_merk_cache.get_merk(path)
+                    }
+                    .map_ok(|merk| merk.take())
                 },
                 grove_version
             )
         );
+        drop(continue_merk_cache);
 
         // Clean up data storage for deleted non-Merk trees.
         for child_path in &non_merk_delete_paths {
@@ -4139,6 +4357,7 @@ impl GroveDb {
                         ))
                     })
                 );
+                registry_update.remove(subtree_path);
             }
         }
 
@@ -4159,6 +4378,10 @@ impl GroveDb {
                 .commit_db_write_batch(write_batch, pending_costs, Some(tx.as_ref()))
                 .map_err(|e| e.into())
         );
+        cost_return_on_error!(
+            &mut cost,
+            self.apply_subtree_registry_update(registry_update, tx.as_ref(), grove_version)
+        );
 
         tx.commit_local().wrap_with_cost(cost)
     }
@@ -12,7 +12,7 @@ mod tests {
         },
         OperationCost,
     };
-    use grovedb_version::version::GroveVersion;
+    use grovedb_version::version::{v3::GROVE_V3, GroveVersion};
     use integer_encoding::VarInt;
 
     use crate::{
@@ -228,7 +228,7 @@ mod tests {
 
     #[test]
     fn test_batch_root_two_insert_tree_cost_same_level() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
@@ -293,7 +293,7 @@ mod tests {
 
     #[test]
     fn test_batch_root_two_insert_tree_cost_different_level() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
@@ -360,7 +360,7 @@ mod tests {
 
     #[test]
     fn test_batch_insert_item_in_also_inserted_sub_tree_with_reference() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
         db.insert(
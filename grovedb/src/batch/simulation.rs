@@ -0,0 +1,179 @@
+//! Dry runs of batches
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use grovedb_costs::{
+    cost_return_on_error,
+    storage_cost::{
+        removal::{StorageRemovedBytes, StorageRemovedBytes::BasicStorageRemoval},
+        StorageCost,
+    },
+    CostResult, CostsExt, OperationCost,
+};
+use grovedb_merk::tree::AggregateData;
+use grovedb_path::SubtreePath;
+use grovedb_version::version::GroveVersion;
+
+use super::{BatchApplyOptions, QualifiedGroveDbOp};
+use crate::{
+    util::TxRef, Element, ElementFlags, Error, GroveDb, Hash, Transaction, TransactionArg,
+};
+
+/// What applying a batch would result in, see [`GroveDb::simulate_batch`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSimulation {
+    /// Root hash the GroveDb would have
+    pub root_hash: Hash,
+    /// New aggregate data of every sum or count tree the batch touches, by
+    /// path of the tree
+    pub aggregates: BTreeMap<Vec<Vec<u8>>, AggregateData>,
+}
+
+impl GroveDb {
+    /// Runs the batch like [`GroveDb::apply_batch`] would without keeping
+    /// any of its writes.
+    ///
+    /// The batch is applied on top of `transaction`, or of the committed
+    /// state if there is none, and reverted to a savepoint afterwards, so the
+    /// transaction is left as it was. Returns the same errors and costs as
+    /// `apply_batch`; reading the resulting state is not part of the costs.
+    pub fn simulate_batch(
+        &self,
+        ops: Vec<QualifiedGroveDbOp>,
+        batch_apply_options: Option<BatchApplyOptions>,
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<BatchSimulation, Error> {
+        self.simulate_batch_with_element_flags_update(
+            ops,
+            batch_apply_options,
+            |_cost, _old_flags, _new_flags| Ok(false),
+            |_flags, key_bytes_to_remove, value_bytes_to_remove| {
+                Ok((
+                    BasicStorageRemoval(key_bytes_to_remove),
+                    BasicStorageRemoval(value_bytes_to_remove),
+                ))
+            },
+            transaction,
+            grove_version,
+        )
+    }
+
+    /// Runs the batch like [`GroveDb::apply_batch_with_element_flags_update`]
+    /// would without keeping any of its writes, see
+    /// [`GroveDb::simulate_batch`].
+    pub fn simulate_batch_with_element_flags_update(
+        &self,
+        ops: Vec<QualifiedGroveDbOp>,
+        batch_apply_options: Option<BatchApplyOptions>,
+        update_element_flags_function: impl FnMut(
+            &StorageCost,
+            Option<ElementFlags>,
+            &mut ElementFlags,
+        ) -> Result<bool, Error>,
+        split_removal_bytes_function: impl FnMut(
+            &mut ElementFlags,
+            u32, // key removed bytes
+            u32, // value removed bytes
+        ) -> Result<
+            (StorageRemovedBytes, StorageRemovedBytes),
+            Error,
+        >,
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<BatchSimulation, Error> {
+        let mut cost = OperationCost::default();
+        let tx = TxRef::new(&self.db, transaction);
+
+        let ops = cost_return_on_error!(
+            &mut cost,
+            self.add_secondary_index_ops(ops, tx.as_ref(), grove_version)
+        );
+        let touched_trees = Self::touched_trees(&ops);
+
+        tx.as_ref().set_savepoint();
+        let simulation = self
+            .apply_batch_on_transaction(
+                ops,
+                batch_apply_options,
+                update_element_flags_function,
+                split_removal_bytes_function,
+                |_| {},
+                tx.as_ref(),
+                grove_version,
+            )
+            .unwrap_add_cost(&mut cost)
+            .and_then(|_| self.read_simulation(touched_trees, tx.as_ref(), grove_version));
+        let reverted = tx.as_ref().rollback_to_savepoint().map_err(Into::into);
+
+        simulation
+            .and_then(|simulation| reverted.map(|_| simulation))
+            .wrap_with_cost(cost)
+    }
+
+    /// Paths of the trees holding the elements `ops` write, of their
+    /// ancestors and of the trees they insert.
+    fn touched_trees(ops: &[QualifiedGroveDbOp]) -> BTreeSet<Vec<Vec<u8>>> {
+        let mut trees = BTreeSet::new();
+        for op in ops {
+            let mut path = op.path.to_path();
+            if let Some(key) = &op.key {
+                trees.insert([path.as_slice(), &[key.get_key_clone()]].concat());
+            }
+            while !path.is_empty() {
+                trees.insert(path.clone());
+                path.pop();
+            }
+        }
+        trees
+    }
+
+    fn read_simulation(
+        &self,
+        touched_trees: BTreeSet<Vec<Vec<u8>>>,
+        transaction: &Transaction,
+        grove_version: &GroveVersion,
+    ) -> Result<BatchSimulation, Error> {
+        let root_hash = self.root_hash(Some(transaction), grove_version).unwrap()?;
+
+        let mut aggregates = BTreeMap::new();
+        for tree_path in touched_trees {
+            let Some((key, parent_path)) = tree_path.split_last() else {
+                continue;
+            };
+            let element = self
+                .get_raw_optional_on_transaction_caching_optional(
+                    SubtreePath::from(parent_path),
+                    key,
+                    false,
+                    transaction,
+                    grove_version,
+                )
+                .unwrap()?;
+            let aggregate_data = match element {
+                Some(Element::SumTree(_, sum, _)) => AggregateData::Sum(sum),
+                Some(Element::BigSumTree(_, sum, _)) => AggregateData::BigSum(sum),
+                Some(Element::CountTree(_, count, _)) => AggregateData::Count(count),
+                Some(Element::CountSumTree(_, count, sum, _)) => {
+                    AggregateData::CountAndSum(count, sum)
+                }
+                Some(Element::ProvableCountTree(_, count, _)) => {
+                    AggregateData::ProvableCount(count)
+                }
+                Some(Element::ProvableCountSumTree(_, count, sum, _)) => {
+                    AggregateData::ProvableCountAndSum(count, sum)
+                }
+                Some(Element::MinMaxTree(_, min, max, _)) => AggregateData::MinMax(min, max),
+                Some(Element::ProvableSumTree(_, sum, _)) => AggregateData::ProvableSum(sum),
+                Some(Element::ProvableBigSumTree(_, sum, _)) => AggregateData::ProvableBigSum(sum),
+                _ => continue,
+            };
+            aggregates.insert(tree_path, aggregate_data);
+        }
+
+        Ok(BatchSimulation {
+            root_hash,
+            aggregates,
+        })
+    }
+}
@@ -8,7 +8,7 @@ mod tests {
         StorageRemovedBytes::SectionedStorageRemoval,
     };
     use grovedb_merk::tree_type::TreeType;
-    use grovedb_version::version::GroveVersion;
+    use grovedb_version::version::{v3::GROVE_V3, v4::GROVE_V4, GroveVersion};
     use intmap::IntMap;
 
     use crate::{
@@ -309,7 +309,7 @@ mod tests {
 
     #[test]
     fn test_batch_one_deletion_tree_with_flags_costs_match_non_batch_on_transaction() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
 
         let insertion_cost = db
@@ -381,10 +381,90 @@ mod tests {
         assert_eq!(non_batch_cost.storage_cost, batch_cost.storage_cost);
     }
 
+    #[test]
+    fn test_batch_one_deletion_tree_with_subtree_registry_costs_match_non_batch() {
+        let grove_version = &GROVE_V4;
+        let db = make_empty_grovedb();
+
+        let insertion_cost = db
+            .insert(
+                EMPTY_PATH,
+                b"key1",
+                Element::empty_tree_with_flags(Some(b"dog".to_vec())),
+                None,
+                None,
+                grove_version,
+            )
+            .cost_as_result()
+            .expect("expected to insert successfully");
+
+        let tx = db.start_transaction();
+
+        let non_batch_cost = db
+            .delete(EMPTY_PATH, b"key1", None, Some(&tx), grove_version)
+            .cost_as_result()
+            .expect("expected to delete successfully");
+
+        // Explanation for 207 storage_written_bytes, the subtree registry entry
+        // being charged from GROVE_V4 on
+
+        // Key -> 37 bytes
+        // 32 bytes for the key prefix
+        // 4 bytes for the key
+        // 1 byte for key_size (required space for 36)
+
+        // Value -> 42
+        //   1 for the flag option (but no flags)
+        //   1 for the flags size
+        //   3 bytes for flags
+        //   1 for the enum type
+        //   1 for empty tree value
+        //   1 for Basic Merk
+        // 32 for node hash
+        // 0 for value hash
+        // 2 byte for the value_size (required space for 98 + up to 256 for child key)
+
+        // Parent Hook -> 40
+        // Key Bytes 4
+        // Hash Size 32
+        // Key Length 1
+        // Child Heights 2
+        // Sum 1
+
+        // Subtree registry entry -> 88
+        // 54 for the entry key behind the prefix of the parent, with its size
+        // 34 for the tree prefix and whether the tree may hold subtrees, with
+        //   their size
+
+        // Total 37 + 42 + 40 + 88 = 207
+
+        assert_eq!(insertion_cost.storage_cost.added_bytes, 207);
+        assert_eq!(
+            insertion_cost.storage_cost.added_bytes,
+            non_batch_cost
+                .storage_cost
+                .removed_bytes
+                .total_removed_bytes()
+        );
+
+        tx.rollback().expect("expected to rollback");
+        let ops = vec![QualifiedGroveDbOp::delete_tree_op(
+            vec![],
+            b"key1".to_vec(),
+            TreeType::NormalTree,
+            SubelementsDeletionBehavior::Error,
+        )];
+        let batch_cost = db
+            .apply_batch(ops, None, Some(&tx), grove_version)
+            .cost_as_result()
+            .expect("expected to delete successfully");
+        assert_eq!(non_batch_cost.storage_cost, batch_cost.storage_cost);
+    }
+
     #[test]
     fn test_batch_one_deletion_tree_with_identity_cost_flags_costs_match_non_batch_on_transaction()
     {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
 
         let insertion_cost = db
@@ -574,7 +654,7 @@ mod tests {
 
     #[test]
     fn test_batch_one_deletion_tree_with_flags_costs_match_non_batch_without_transaction() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
 
         let insertion_cost = db
@@ -15,7 +15,7 @@ mod tests {
         },
         OperationCost,
     };
-    use grovedb_version::version::GroveVersion;
+    use grovedb_version::version::{v3::GROVE_V3, v4::GROVE_V4, GroveVersion};
     use integer_encoding::VarInt;
     use intmap::IntMap;
 
@@ -54,7 +54,7 @@ mod tests {
 
     #[test]
     fn test_batch_root_one_insert_tree_cost() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
@@ -118,6 +118,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_batch_root_one_insert_tree_cost_with_subtree_registry() {
+        let grove_version = &GROVE_V4;
+        let db = make_empty_grovedb();
+        let tx = db.start_transaction();
+
+        let ops = vec![QualifiedGroveDbOp::insert_or_replace_op(
+            vec![],
+            b"key1".to_vec(),
+            Element::empty_tree(),
+        )];
+        let cost_result = db.apply_batch(ops, None, Some(&tx), grove_version);
+        cost_result.value.expect("expected to execute batch");
+        let cost = cost_result.cost;
+        // Explanation for 203 storage_written_bytes, the subtree registry entry
+        // being charged from GROVE_V4 on
+
+        // Key -> 37 bytes
+        // 32 bytes for the key prefix
+        // 4 bytes for the key
+        // 1 byte for key_size (required space for 36)
+
+        // Value -> 38
+        //   1 for the flag option (but no flags)
+        //   1 for the enum type
+        //   1 for empty tree value
+        //   1 for Basic Merk
+        // 32 for node hash
+        // 0 for value hash
+        // 2 byte for the value_size (required space for 98 + up to 256 for child key)
+
+        // Parent Hook -> 40
+        // Key Bytes 4
+        // Hash Size 32
+        // Key Length 1
+        // Child Heights 2
+        // Sum 1
+        // Subtree registry entry -> 88
+        // 54 for the entry key behind the prefix of the parent, with its size
+        // 34 for the tree prefix and whether the tree may hold subtrees, with
+        //   their size
+        // Total 37 + 38 + 40 + 88 = 203
+
+        // Hash node calls
+        // 1 for the tree insert
+        // 2 for the node hash
+        // 1 for the value hash
+        // 1 for the combine hash
+        // 1 kv_digest_to_kv_hash
+
+        // Seek Count
+        // 1 to load from root tree
+        // 1 to insert
+        // 1 to write the subtree registry entry
+        // 1 to update root tree
+
+        assert_eq!(
+            cost,
+            OperationCost {
+                seek_count: 4,
+                storage_cost: StorageCost {
+                    added_bytes: 203,
+                    replaced_bytes: 0,
+                    removed_bytes: NoStorageRemoval,
+                },
+                storage_loaded_bytes: 0,
+                hash_node_calls: 6,
+                sinsemilla_hash_calls: 0,
+            }
+        );
+    }
+
     #[test]
     fn test_batch_root_one_insert_item_cost() {
         let grove_version = GroveVersion::latest();
@@ -185,7 +257,7 @@ mod tests {
 
     #[test]
     fn test_batch_root_one_insert_tree_under_parent_item_in_same_merk_cost() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
@@ -282,7 +354,7 @@ mod tests {
 
     #[test]
     fn test_batch_root_one_insert_tree_under_parent_tree_in_same_merk_cost() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
@@ -365,7 +437,7 @@ mod tests {
 
     #[test]
     fn test_batch_root_one_insert_tree_under_parent_tree_in_different_merk_cost() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
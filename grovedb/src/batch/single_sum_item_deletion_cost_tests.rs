@@ -3,7 +3,7 @@
 #[cfg(feature = "minimal")]
 mod tests {
     use grovedb_merk::tree_type::TreeType;
-    use grovedb_version::version::GroveVersion;
+    use grovedb_version::version::{v3::GROVE_V3, GroveVersion};
 
     use crate::{
         batch::{QualifiedGroveDbOp, SubelementsDeletionBehavior},
@@ -120,7 +120,7 @@ mod tests {
 
     #[test]
     fn test_batch_one_deletion_sum_tree_with_flags_costs_match_non_batch_on_transaction() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
 
         let insertion_cost = db
@@ -6,7 +6,7 @@ mod tests {
         storage_cost::{removal::StorageRemovedBytes::NoStorageRemoval, StorageCost},
         OperationCost,
     };
-    use grovedb_version::version::GroveVersion;
+    use grovedb_version::version::{v3::GROVE_V3, GroveVersion};
 
     use crate::{
         batch::QualifiedGroveDbOp,
@@ -53,7 +53,7 @@ mod tests {
 
     #[test]
     fn test_batch_one_insert_sum_tree_cost() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
@@ -120,7 +120,7 @@ mod tests {
 
     #[test]
     fn test_batch_one_insert_sum_tree_under_parent_tree_in_same_merk_cost() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
@@ -207,7 +207,7 @@ mod tests {
 
     #[test]
     fn test_batch_one_insert_sum_tree_under_parent_sum_tree_in_same_merk_cost() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
@@ -294,7 +294,7 @@ mod tests {
 
     #[test]
     fn test_batch_one_insert_sum_tree_under_parent_tree_in_different_merk_cost() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
@@ -385,7 +385,7 @@ mod tests {
 
     #[test]
     fn test_batch_one_insert_sum_tree_under_parent_sum_tree_in_different_merk_cost() {
-        let grove_version = GroveVersion::latest();
+        let grove_version = &GROVE_V3;
         let db = make_empty_grovedb();
         let tx = db.start_transaction();
 
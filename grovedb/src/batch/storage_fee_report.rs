@@ -0,0 +1,114 @@
+//! Storage fees of batches by owner and epoch
+
+use grovedb_costs::{
+    cost_return_on_error,
+    storage_cost::{
+        fee_report::{StorageFeeReport, StorageFeeScheme},
+        removal::{Identifier, UNKNOWN_EPOCH},
+    },
+    CostResult, CostsExt, OperationCost,
+};
+use grovedb_merk::tree::{kv::ValueDefinedCostType, TreeNode};
+use grovedb_storage::StorageBatch;
+use grovedb_version::version::GroveVersion;
+
+use super::{BatchApplyOptions, QualifiedGroveDbOp};
+use crate::{Element, Error, GroveDb, SubtreePrefix, TransactionArg};
+
+/// Bytes added by the writes of `storage_batch`, with the owner id and epoch
+/// paying for them, for the writes of elements with flags understood by `S`.
+fn attributed_added_bytes<S: StorageFeeScheme>(
+    storage_batch: &StorageBatch,
+    grove_version: &GroveVersion,
+) -> Vec<(Identifier, u16, u32)> {
+    let mut added_bytes = Vec::new();
+    storage_batch.for_each_data_put(|prefixed_key, value, cost_info| {
+        let Some(cost_info) = cost_info else {
+            return;
+        };
+        let bytes = cost_info
+            .key_storage_cost
+            .added_bytes
+            .saturating_add(cost_info.value_storage_cost.added_bytes);
+        if bytes == 0 {
+            return;
+        }
+        let key = prefixed_key
+            .get(size_of::<SubtreePrefix>()..)
+            .unwrap_or_default()
+            .to_vec();
+        let Some((owner_id, epoch)) = TreeNode::decode_raw(
+            value,
+            key,
+            None::<fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+            grove_version,
+        )
+        .ok()
+        .and_then(|node| Element::deserialize(node.value_as_slice(), grove_version).ok())
+        .and_then(|element| element.get_flags().clone())
+        .and_then(|flags| S::added_bytes_owner_and_epoch(&flags)) else {
+            return;
+        };
+        added_bytes.push((owner_id, epoch, bytes));
+    });
+    added_bytes
+}
+
+impl GroveDb {
+    /// Applies batch of operations on GroveDB with the element flags of
+    /// scheme `S` updated just in time, and returns the bytes the batch
+    /// added and removed grouped by owner and epoch.
+    ///
+    /// Bytes added are paid for by the owner and epoch `S` reads from the
+    /// flags of the element they are written for, while bytes removed are
+    /// refunded to the sections `S` split them into. Bytes that aren't
+    /// attributed to an element, like those of elements without flags, are
+    /// listed under the default identifier and [`UNKNOWN_EPOCH`], so the
+    /// totals of the report are the storage costs of the batch.
+    pub fn apply_batch_with_storage_fee_report<S: StorageFeeScheme>(
+        &self,
+        ops: Vec<QualifiedGroveDbOp>,
+        batch_apply_options: Option<BatchApplyOptions>,
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<StorageFeeReport, Error> {
+        let mut cost = OperationCost::default();
+        let mut added_bytes = Vec::new();
+        cost_return_on_error!(
+            &mut cost,
+            self.apply_batch_inspecting_storage(
+                ops,
+                batch_apply_options,
+                |cost, old_flags, new_flags| {
+                    S::update_element_flags(cost, old_flags, new_flags)
+                        .map_err(|e| Error::JustInTimeElementFlagsClientError(e.to_string()))
+                },
+                |flags, removed_key_bytes, removed_value_bytes| {
+                    S::split_removal_bytes(flags, removed_key_bytes, removed_value_bytes)
+                        .map_err(|e| Error::SplitRemovalBytesClientError(e.to_string()))
+                },
+                |storage_batch| {
+                    added_bytes = attributed_added_bytes::<S>(storage_batch, grove_version)
+                },
+                transaction,
+                grove_version,
+            )
+        );
+
+        let mut report = StorageFeeReport::default();
+        let mut attributed_bytes = 0u32;
+        for (owner_id, epoch, bytes) in added_bytes {
+            report.add_added_bytes(owner_id, epoch, bytes);
+            attributed_bytes = attributed_bytes.saturating_add(bytes);
+        }
+        report.add_added_bytes(
+            Identifier::default(),
+            UNKNOWN_EPOCH,
+            cost.storage_cost
+                .added_bytes
+                .saturating_sub(attributed_bytes),
+        );
+        report.add_removed_bytes(&cost.storage_cost.removed_bytes);
+        Ok(report).wrap_with_cost(cost)
+    }
+}
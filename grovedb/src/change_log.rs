@@ -0,0 +1,342 @@
+//! Change data capture of committed element mutations, see
+//! [`GroveDb::enable_change_log`].
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use grovedb_path::SubtreePath;
+use grovedb_version::version::GroveVersion;
+
+use crate::{util::TxRef, Element, Error, GroveDb, Transaction};
+
+/// An element which was inserted, replaced or deleted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementChange {
+    /// Path of the subtree holding the element
+    pub path: Vec<Vec<u8>>,
+    /// Key of the element
+    pub key: Vec<u8>,
+    /// Element before the change, `None` if it was inserted
+    pub old: Option<Element>,
+    /// Element after the change, `None` if it was deleted
+    pub new: Option<Element>,
+}
+
+/// How much a change moved the aggregates of the tree holding the element.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AggregateDelta {
+    /// Change of the sum of a sum tree
+    pub sum: i128,
+    /// Change of the count of a count tree
+    pub count: i128,
+}
+
+impl ElementChange {
+    /// Returns how much this change moved the sum and count the parent
+    /// tree aggregates. Sum items and sum trees contribute their sum, count
+    /// trees their count and any other element a count of one; whether the
+    /// parent actually aggregates them depends on its tree type.
+    pub fn aggregate_delta(&self) -> AggregateDelta {
+        let (old_sum, old_count) = Self::aggregates(self.old.as_ref());
+        let (new_sum, new_count) = Self::aggregates(self.new.as_ref());
+        AggregateDelta {
+            sum: new_sum.saturating_sub(old_sum),
+            count: new_count - old_count,
+        }
+    }
+
+    fn aggregates(element: Option<&Element>) -> (i128, i128) {
+        element.map_or((0, 0), |element| {
+            (
+                element.big_sum_value_or_default(),
+                element.count_value_or_default() as i128,
+            )
+        })
+    }
+}
+
+/// Element changes of one commit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeSet {
+    /// Commit sequence, increasing by one with every change set
+    pub sequence: u64,
+    /// Changes in the order the operations of the commit made them, the
+    /// deepest elements of each operation first
+    pub changes: Vec<ElementChange>,
+}
+
+/// Handle returned by [`GroveDb::subscribe_changes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChangeSubscriptionId(u64);
+
+type Subscriber = Arc<dyn Fn(&ChangeSet) + Send + Sync>;
+
+#[derive(Default)]
+pub(crate) struct ChangeLog {
+    state: Mutex<ChangeLogState>,
+}
+
+#[derive(Default)]
+struct ChangeLogState {
+    /// `None` while the change log is disabled
+    retained_change_sets: Option<usize>,
+    last_sequence: u64,
+    /// Changes made under transactions which weren't committed yet, by
+    /// transaction id
+    pending: BTreeMap<u64, Vec<ElementChange>>,
+    retained: VecDeque<ChangeSet>,
+    subscribers: BTreeMap<ChangeSubscriptionId, Subscriber>,
+    next_subscription_id: u64,
+}
+
+impl ChangeLog {
+    fn state(&self) -> MutexGuard<'_, ChangeLogState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.state().retained_change_sets.is_some()
+    }
+
+    fn add_pending(&self, transaction_id: u64, changes: Vec<ElementChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        self.state()
+            .pending
+            .entry(transaction_id)
+            .or_default()
+            .extend(changes);
+    }
+
+    pub(crate) fn discard_pending(&self, transaction_id: u64) {
+        self.state().pending.remove(&transaction_id);
+    }
+
+    pub(crate) fn publish_pending(&self, transaction_id: u64) {
+        let pending = self.state().pending.remove(&transaction_id);
+        self.publish(pending.unwrap_or_default());
+    }
+
+    fn publish(&self, changes: Vec<ElementChange>) {
+        if changes.is_empty() {
+            return;
+        }
+
+        let (change_set, subscribers) = {
+            let mut state = self.state();
+            let Some(retained_change_sets) = state.retained_change_sets else {
+                return;
+            };
+            state.last_sequence += 1;
+            let change_set = ChangeSet {
+                sequence: state.last_sequence,
+                changes,
+            };
+            if retained_change_sets > 0 {
+                if state.retained.len() == retained_change_sets {
+                    state.retained.pop_front();
+                }
+                state.retained.push_back(change_set.clone());
+            }
+            let subscribers: Vec<Subscriber> = state.subscribers.values().cloned().collect();
+            (change_set, subscribers)
+        };
+
+        // Subscribers are called without holding the lock so they can use the
+        // change log API themselves.
+        for subscriber in subscribers {
+            subscriber(&change_set);
+        }
+    }
+}
+
+/// Elements an operation may change, read before it runs.
+pub(crate) struct ChangeCapture {
+    old: BTreeMap<(Vec<Vec<u8>>, Vec<u8>), Option<Element>>,
+}
+
+impl ChangeCapture {
+    /// Reads the elements again and returns those which differ.
+    fn finish(
+        self,
+        grove_db: &GroveDb,
+        transaction: &Transaction,
+        grove_version: &GroveVersion,
+    ) -> Result<Vec<ElementChange>, Error> {
+        let mut changes = Vec::new();
+        // Deepest elements first, so propagated tree updates come after the
+        // changes causing them.
+        let mut old: Vec<_> = self.old.into_iter().collect();
+        old.sort_by_key(|((path, _), _)| Reverse(path.len()));
+        for ((path, key), old) in old {
+            let new = grove_db
+                .get_raw_optional_on_transaction_caching_optional(
+                    SubtreePath::from(path.as_slice()),
+                    &key,
+                    false,
+                    transaction,
+                    grove_version,
+                )
+                .unwrap()?;
+            if old != new {
+                changes.push(ElementChange {
+                    path,
+                    key,
+                    old,
+                    new,
+                });
+            }
+        }
+        Ok(changes)
+    }
+}
+
+impl GroveDb {
+    /// Starts recording the elements changed by `insert`, `delete` and
+    /// `apply_batch`, including the tree elements updated by propagation.
+    ///
+    /// Every commit changing elements makes a [`ChangeSet`] with the next
+    /// commit sequence; changes made under a transaction make one when
+    /// [`GroveDb::commit_transaction`] succeeds and are dropped if it is
+    /// rolled back. The last `retained_change_sets` are kept for
+    /// [`GroveDb::changes_since`] and every change set is passed to the
+    /// subscribers registered with [`GroveDb::subscribe_changes`].
+    ///
+    /// The log is kept in memory, so sequences start over when the GroveDb is
+    /// reopened. Capturing reads every touched element and its ancestors
+    /// before and after the operation; these reads are not included in the
+    /// returned costs. Sub-elements removed along with a deleted tree are not
+    /// reported separately, nor are writes of non-Merk trees outside of
+    /// `apply_batch`. Changes of a transaction dropped without a commit or
+    /// rollback are kept until the change log is disabled.
+    pub fn enable_change_log(&self, retained_change_sets: usize) {
+        let mut state = self.change_log.state();
+        state.retained_change_sets = Some(retained_change_sets);
+        while state.retained.len() > retained_change_sets {
+            state.retained.pop_front();
+        }
+    }
+
+    /// Stops recording changes and drops the retained change sets.
+    /// Subscribers stay registered.
+    pub fn disable_change_log(&self) {
+        let mut state = self.change_log.state();
+        state.retained_change_sets = None;
+        state.pending.clear();
+        state.retained.clear();
+    }
+
+    /// Registers a callback called with every change set after its commit.
+    pub fn subscribe_changes(
+        &self,
+        subscriber: impl Fn(&ChangeSet) + Send + Sync + 'static,
+    ) -> ChangeSubscriptionId {
+        let mut state = self.change_log.state();
+        let id = ChangeSubscriptionId(state.next_subscription_id);
+        state.next_subscription_id += 1;
+        state.subscribers.insert(id, Arc::new(subscriber));
+        id
+    }
+
+    /// Removes a subscriber, returns `false` if it was already removed.
+    pub fn unsubscribe_changes(&self, id: ChangeSubscriptionId) -> bool {
+        self.change_log.state().subscribers.remove(&id).is_some()
+    }
+
+    /// Returns the sequence of the last change set, `0` if there is none.
+    pub fn last_change_sequence(&self) -> u64 {
+        self.change_log.state().last_sequence
+    }
+
+    /// Replays the retained change sets committed after `sequence`.
+    ///
+    /// Fails if some of them are no longer retained, in which case a
+    /// follower has to resynchronize from the current state.
+    pub fn changes_since(&self, sequence: u64) -> Result<std::vec::IntoIter<ChangeSet>, Error> {
+        let state = self.change_log.state();
+        let first_retained = state
+            .retained
+            .front()
+            .map_or(state.last_sequence + 1, |change_set| change_set.sequence);
+        if sequence + 1 < first_retained {
+            return Err(Error::InvalidInput(
+                "change sets after this sequence are no longer retained",
+            ));
+        }
+        Ok(state
+            .retained
+            .iter()
+            .filter(|change_set| change_set.sequence > sequence)
+            .cloned()
+            .collect::<Vec<_>>()
+            .into_iter())
+    }
+
+    /// Reads the elements at `touched` keys and at every ancestor of their
+    /// paths if the change log is enabled. A `None` key stands for the tree
+    /// at the end of the path.
+    pub(crate) fn capture_changes<I>(
+        &self,
+        touched: impl FnOnce() -> I,
+        transaction: &Transaction,
+        grove_version: &GroveVersion,
+    ) -> Option<ChangeCapture>
+    where
+        I: IntoIterator<Item = (Vec<Vec<u8>>, Option<Vec<u8>>)>,
+    {
+        if !self.change_log.is_enabled() {
+            return None;
+        }
+
+        let mut old = BTreeMap::new();
+        for (path, key) in touched() {
+            for depth in 0..path.len() {
+                old.entry((path[..depth].to_vec(), path[depth].clone()))
+                    .or_insert(None);
+            }
+            if let Some(key) = key {
+                old.entry((path, key)).or_insert(None);
+            }
+        }
+        for ((path, key), element) in old.iter_mut() {
+            // An unreadable path makes the operation itself fail.
+            *element = self
+                .get_raw_optional_on_transaction_caching_optional(
+                    SubtreePath::from(path.as_slice()),
+                    key,
+                    false,
+                    transaction,
+                    grove_version,
+                )
+                .unwrap()
+                .unwrap_or(None);
+        }
+        Some(ChangeCapture { old })
+    }
+
+    /// Commits the transaction if it wasn't received from outside, recording
+    /// the changes captured before the operation.
+    pub(crate) fn commit_local_with_changes(
+        &self,
+        tx: TxRef,
+        capture: Option<ChangeCapture>,
+        grove_version: &GroveVersion,
+    ) -> Result<(), Error> {
+        let Some(capture) = capture else {
+            return tx.commit_local();
+        };
+        let changes = capture.finish(self, tx.as_ref(), grove_version)?;
+        match tx {
+            TxRef::Owned(tx) => {
+                tx.commit()?;
+                self.change_log.publish(changes);
+            }
+            TxRef::Borrowed(tx) => self.change_log.add_pending(tx.id(), changes),
+        }
+        Ok(())
+    }
+}
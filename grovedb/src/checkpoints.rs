@@ -13,7 +13,11 @@ impl GroveDb {
     /// Opens a checkpoint
     pub fn open_checkpoint<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let db = RocksDbStorage::checkpoint_rocksdb_with_path(path)?;
-        Ok(GroveDb { db })
+        Ok(GroveDb {
+            db: db.into(),
+            change_log: Default::default(),
+            indexes: Default::default(),
+        })
     }
 
     /// Deletes a checkpoint directory.
@@ -478,6 +478,15 @@ fn merk_proof_node_to_grovedbg(node: Node) -> Result<MerkProofNode, crate::Error
                 TreeFeatureType::ProvableCountedSummedMerkNode(count, sum) => {
                     grovedbg_types::TreeFeatureType::ProvableCountedSummedMerkNode(count, sum)
                 }
+                TreeFeatureType::MinMaxMerkNode(min, max) => {
+                    grovedbg_types::TreeFeatureType::MinMaxMerkNode(min, max)
+                }
+                TreeFeatureType::ProvableSummedMerkNode(sum) => {
+                    grovedbg_types::TreeFeatureType::ProvableSummedMerkNode(sum)
+                }
+                TreeFeatureType::ProvableBigSummedMerkNode(sum) => {
+                    grovedbg_types::TreeFeatureType::ProvableBigSummedMerkNode(sum)
+                }
             };
             MerkProofNode::KVValueHashFeatureType(
                 key,
@@ -520,6 +529,80 @@ fn merk_proof_node_to_grovedbg(node: Node) -> Result<MerkProofNode, crate::Error
                 grovedbg_types::TreeFeatureType::ProvableCountedMerkNode(count),
             )
         }
+        Node::KVMinMax(key, value, min, max) => {
+            let element = crate::Element::deserialize(&value, GroveVersion::latest())?;
+            let val_hash = value_hash(&value).unwrap();
+            MerkProofNode::KVValueHashFeatureType(
+                key,
+                element_to_grovedbg(element),
+                val_hash,
+                grovedbg_types::TreeFeatureType::MinMaxMerkNode(min, max),
+            )
+        }
+        Node::KVHashMinMax(hash, min, max) => MerkProofNode::KVValueHashFeatureType(
+            vec![],
+            grovedbg_types::Element::Item {
+                value: vec![],
+                element_flags: None,
+            },
+            hash,
+            grovedbg_types::TreeFeatureType::MinMaxMerkNode(min, max),
+        ),
+        Node::KVRefValueHashMinMax(key, value, hash, min, max) => {
+            let element = crate::Element::deserialize(&value, GroveVersion::latest())?;
+            MerkProofNode::KVValueHashFeatureType(
+                key,
+                element_to_grovedbg(element),
+                hash,
+                grovedbg_types::TreeFeatureType::MinMaxMerkNode(min, max),
+            )
+        }
+        Node::KVDigestMinMax(key, value_hash, min, max) => MerkProofNode::KVValueHashFeatureType(
+            key,
+            grovedbg_types::Element::Item {
+                value: vec![],
+                element_flags: None,
+            },
+            value_hash,
+            grovedbg_types::TreeFeatureType::MinMaxMerkNode(min, max),
+        ),
+        Node::KVSum(key, value, sum) => {
+            let element = crate::Element::deserialize(&value, GroveVersion::latest())?;
+            let val_hash = value_hash(&value).unwrap();
+            MerkProofNode::KVValueHashFeatureType(
+                key,
+                element_to_grovedbg(element),
+                val_hash,
+                provable_sum_to_grovedbg(sum),
+            )
+        }
+        Node::KVHashSum(hash, sum) => MerkProofNode::KVValueHashFeatureType(
+            vec![],
+            grovedbg_types::Element::Item {
+                value: vec![],
+                element_flags: None,
+            },
+            hash,
+            provable_sum_to_grovedbg(sum),
+        ),
+        Node::KVRefValueHashSum(key, value, hash, sum) => {
+            let element = crate::Element::deserialize(&value, GroveVersion::latest())?;
+            MerkProofNode::KVValueHashFeatureType(
+                key,
+                element_to_grovedbg(element),
+                hash,
+                provable_sum_to_grovedbg(sum),
+            )
+        }
+        Node::KVDigestSum(key, value_hash, sum) => MerkProofNode::KVValueHashFeatureType(
+            key,
+            grovedbg_types::Element::Item {
+                value: vec![],
+                element_flags: None,
+            },
+            value_hash,
+            provable_sum_to_grovedbg(sum),
+        ),
         Node::KVValueHashFeatureTypeWithChildHash(key, value, hash, feature_type, _child_hash) => {
             let element = crate::Element::deserialize(&value, GroveVersion::latest())?;
             let node_feature_type = match feature_type {
@@ -542,6 +625,15 @@ fn merk_proof_node_to_grovedbg(node: Node) -> Result<MerkProofNode, crate::Error
                 TreeFeatureType::ProvableCountedSummedMerkNode(count, sum) => {
                     grovedbg_types::TreeFeatureType::ProvableCountedSummedMerkNode(count, sum)
                 }
+                TreeFeatureType::MinMaxMerkNode(min, max) => {
+                    grovedbg_types::TreeFeatureType::MinMaxMerkNode(min, max)
+                }
+                TreeFeatureType::ProvableSummedMerkNode(sum) => {
+                    grovedbg_types::TreeFeatureType::ProvableSummedMerkNode(sum)
+                }
+                TreeFeatureType::ProvableBigSummedMerkNode(sum) => {
+                    grovedbg_types::TreeFeatureType::ProvableBigSummedMerkNode(sum)
+                }
             };
             MerkProofNode::KVValueHashFeatureType(
                 key,
@@ -640,6 +732,16 @@ fn query_item_to_grovedb(item: QueryItem) -> crate::QueryItem {
     }
 }
 
+/// Proof nodes of provable sum trees carry the sum as `i128` whichever of the
+/// two trees they come from, so report it as the narrowest feature type that
+/// fits.
+fn provable_sum_to_grovedbg(sum: i128) -> grovedbg_types::TreeFeatureType {
+    match i64::try_from(sum) {
+        Ok(sum) => grovedbg_types::TreeFeatureType::ProvableSummedMerkNode(sum),
+        Err(_) => grovedbg_types::TreeFeatureType::ProvableBigSummedMerkNode(sum),
+    }
+}
+
 fn element_to_grovedbg(element: crate::Element) -> grovedbg_types::Element {
     match element {
         crate::Element::Item(value, element_flags) => grovedbg_types::Element::Item {
@@ -731,6 +833,13 @@ fn element_to_grovedbg(element: crate::Element) -> grovedbg_types::Element {
                 element_flags,
             }
         }
+        crate::Element::ItemWithExpiry(value, expiry, element_flags) => {
+            grovedbg_types::Element::ItemWithExpiry {
+                value,
+                expiry,
+                element_flags,
+            }
+        }
         crate::Element::SumTree(root_key, sum, element_flags) => grovedbg_types::Element::Sumtree {
             root_key,
             sum,
@@ -773,6 +882,32 @@ fn element_to_grovedbg(element: crate::Element) -> grovedbg_types::Element {
                 element_flags,
             }
         }
+        crate::Element::MinMaxItem(value, element_flags) => grovedbg_types::Element::MinMaxItem {
+            value,
+            element_flags,
+        },
+        crate::Element::MinMaxTree(root_key, min, max, element_flags) => {
+            grovedbg_types::Element::MinMaxTree {
+                root_key,
+                min,
+                max,
+                element_flags,
+            }
+        }
+        crate::Element::ProvableSumTree(root_key, sum, element_flags) => {
+            grovedbg_types::Element::ProvableSumTree {
+                root_key,
+                sum,
+                element_flags,
+            }
+        }
+        crate::Element::ProvableBigSumTree(root_key, sum, element_flags) => {
+            grovedbg_types::Element::ProvableBigSumTree {
+                root_key,
+                sum,
+                element_flags,
+            }
+        }
         crate::Element::CommitmentTree(_, _, element_flags) => grovedbg_types::Element::Subtree {
             root_key: None,
             element_flags,
@@ -840,6 +975,15 @@ fn node_to_update(
             TreeFeatureType::ProvableCountedSummedMerkNode(count, sum) => {
                 grovedbg_types::TreeFeatureType::ProvableCountedSummedMerkNode(count, sum)
             }
+            TreeFeatureType::MinMaxMerkNode(min, max) => {
+                grovedbg_types::TreeFeatureType::MinMaxMerkNode(min, max)
+            }
+            TreeFeatureType::ProvableSummedMerkNode(sum) => {
+                grovedbg_types::TreeFeatureType::ProvableSummedMerkNode(sum)
+            }
+            TreeFeatureType::ProvableBigSummedMerkNode(sum) => {
+                grovedbg_types::TreeFeatureType::ProvableBigSummedMerkNode(sum)
+            }
         },
         value_hash,
         kv_digest_hash,
@@ -30,7 +30,7 @@ use grovedb_merk::proofs::query::AggregateSumQuery;
 #[cfg(feature = "minimal")]
 use grovedb_path::SubtreePath;
 #[cfg(feature = "minimal")]
-use grovedb_storage::{rocksdb_storage::RocksDbStorage, RawIterator, StorageContext};
+use grovedb_storage::{backend::StorageBackend, RawIterator, StorageContext};
 #[cfg(feature = "minimal")]
 use grovedb_version::{check_grovedb_v0, check_grovedb_v0_with_cost, version::GroveVersion};
 
@@ -107,7 +107,7 @@ where
     'db: 'ctx,
 {
     /// The RocksDB storage instance.
-    pub storage: &'db RocksDbStorage,
+    pub storage: &'db StorageBackend,
     /// The optional transaction context for transactional reads.
     pub transaction: TransactionArg<'db, 'ctx>,
     /// The key of the current element being processed, if any.
@@ -178,7 +178,7 @@ where
 pub trait ElementAggregateSumQueryExtensions {
     /// Executes an aggregate sum path query and returns matching key-sum pairs.
     fn get_aggregate_sum_query(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         aggregate_sum_path_query: &AggregateSumPathQuery,
         query_options: AggregateSumQueryOptions,
         transaction: TransactionArg,
@@ -186,7 +186,7 @@ pub trait ElementAggregateSumQueryExtensions {
     ) -> CostResult<AggregateSumQueryResult, Error>;
     /// Executes an aggregate sum query using a custom element-processing function.
     fn get_aggregate_sum_query_apply_function(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         path: &[&[u8]],
         aggregate_sum_query: &AggregateSumQuery,
         query_options: AggregateSumQueryOptions,
@@ -204,7 +204,7 @@ pub trait ElementAggregateSumQueryExtensions {
     ) -> CostResult<(), Error>;
     /// Processes a single query item (key or range) within an aggregate sum query.
     fn aggregate_sum_query_item(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         item: &QueryItem,
         results: &mut Vec<KeySumValuePair>,
         path: &[&[u8]],
@@ -232,7 +232,7 @@ pub trait ElementAggregateSumQueryExtensions {
 impl ElementAggregateSumQueryExtensions for Element {
     /// Returns a vector of result elements based on given query
     fn get_aggregate_sum_query(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         aggregate_sum_path_query: &AggregateSumPathQuery,
         query_options: AggregateSumQueryOptions,
         transaction: TransactionArg,
@@ -265,7 +265,7 @@ impl ElementAggregateSumQueryExtensions for Element {
     /// Returns a vector of result sum items with keys
     /// based on given aggregate sum query
     fn get_aggregate_sum_query_apply_function(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         path: &[&[u8]],
         aggregate_sum_query: &AggregateSumQuery,
         query_options: AggregateSumQueryOptions,
@@ -528,7 +528,7 @@ impl ElementAggregateSumQueryExtensions for Element {
     /// not decrease and hence we would continue on the increasingly
     /// expensive query.
     fn aggregate_sum_query_item(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         item: &QueryItem,
         results: &mut Vec<KeySumValuePair>,
         path: &[&[u8]],
@@ -3,7 +3,7 @@ use grovedb_merk::proofs::query::AggregateSumQuery;
 use grovedb_merk::proofs::query::QueryItem;
 use grovedb_merk::tree::NULL_HASH;
 use grovedb_path::SubtreePath;
-use grovedb_storage::Storage;
+use grovedb_storage::{Storage, StorageBatch};
 use grovedb_version::version::GroveVersion;
 
 use crate::element::aggregate_sum_query::{
@@ -2982,7 +2982,8 @@ fn test_cyclic_reference_detected_in_aggregate_sum_query() {
     // Use MerkCache to insert cyclic references at the Merk level,
     // bypassing GroveDB-level validation that would reject them.
     {
-        let cache = MerkCache::new(&db, &tx, grove_version);
+        let batch = StorageBatch::new();
+        let cache = MerkCache::new(&db, &tx, &batch, grove_version);
         let path: SubtreePath<&[u8]> = SubtreePath::from(&[TEST_LEAF] as &[&[u8]]);
 
         // ref_a points to [TEST_LEAF, "ref_b"]
@@ -3019,9 +3020,12 @@ fn test_cyclic_reference_detected_in_aggregate_sum_query() {
         drop(merk);
 
         // Commit the batch to make the writes visible in the transaction
-        let batch = cache.into_batch().unwrap().expect("should produce batch");
+        cache
+            .flush()
+            .unwrap()
+            .expect("should propagate the changes");
         db.db
-            .commit_multi_context_batch(*batch, Some(&tx))
+            .commit_multi_context_batch(batch, Some(&tx))
             .unwrap()
             .expect("should commit batch");
     }
@@ -3059,7 +3063,8 @@ fn test_self_referencing_element_detected_in_aggregate_sum_query() {
     let tx = db.start_transaction();
 
     {
-        let cache = MerkCache::new(&db, &tx, grove_version);
+        let batch = StorageBatch::new();
+        let cache = MerkCache::new(&db, &tx, &batch, grove_version);
         let path: SubtreePath<&[u8]> = SubtreePath::from(&[TEST_LEAF] as &[&[u8]]);
 
         // ref_self points to itself: [TEST_LEAF, "ref_self"]
@@ -3082,9 +3087,12 @@ fn test_self_referencing_element_detected_in_aggregate_sum_query() {
 
         drop(merk);
 
-        let batch = cache.into_batch().unwrap().expect("should produce batch");
+        cache
+            .flush()
+            .unwrap()
+            .expect("should propagate the changes");
         db.db
-            .commit_multi_context_batch(*batch, Some(&tx))
+            .commit_multi_context_batch(batch, Some(&tx))
             .unwrap()
             .expect("should commit batch");
     }
@@ -12,6 +12,9 @@ mod path_query_push_args;
 #[cfg(feature = "minimal")]
 /// Query execution logic for elements, including path queries and sized queries.
 pub mod query;
+#[cfg(feature = "minimal")]
+/// Lazy iteration over the results of path queries.
+pub mod query_iterator;
 /// Options for controlling query behavior.
 pub mod query_options;
 
@@ -5,7 +5,7 @@ use grovedb_merk::proofs::{
     query::{Path, SubqueryBranch},
     Query,
 };
-use grovedb_storage::rocksdb_storage::RocksDbStorage;
+use grovedb_storage::backend::StorageBackend;
 
 use crate::{
     element::query_options::QueryOptions,
@@ -19,7 +19,7 @@ pub struct PathQueryPushArgs<'db, 'ctx, 'a>
 where
     'db: 'ctx,
 {
-    pub storage: &'db RocksDbStorage,
+    pub storage: &'db StorageBackend,
     pub transaction: TransactionArg<'db, 'ctx>,
     pub key: Option<&'a [u8]>,
     pub element: Element,
@@ -11,8 +11,10 @@ use grovedb_merk::{
     proofs::{query::query_item::QueryItem, Query},
 };
 use grovedb_path::SubtreePath;
-use grovedb_storage::{rocksdb_storage::RocksDbStorage, RawIterator, StorageContext};
-use grovedb_version::{check_grovedb_v0, check_grovedb_v0_with_cost, version::GroveVersion};
+use grovedb_storage::{backend::StorageBackend, RawIterator, StorageContext};
+use grovedb_version::{
+    check_grovedb_v0, check_grovedb_v0_with_cost, error::GroveVersionError, version::GroveVersion,
+};
 
 use crate::{
     element::{path_query_push_args::PathQueryPushArgs, query_options::QueryOptions},
@@ -31,7 +33,7 @@ use crate::{
 pub trait ElementQueryExtensions {
     /// Executes a query against a subtree and returns matching elements.
     fn get_query(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         merk_path: &[&[u8]],
         query: &Query,
         query_options: QueryOptions,
@@ -41,7 +43,7 @@ pub trait ElementQueryExtensions {
     ) -> CostResult<QueryResultElements, Error>;
     /// Executes a query and returns only the element values (no keys or paths).
     fn get_query_values(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         merk_path: &[&[u8]],
         query: &Query,
         query_options: QueryOptions,
@@ -50,7 +52,7 @@ pub trait ElementQueryExtensions {
     ) -> CostResult<Vec<Element>, Error>;
     /// Executes a sized query using a custom element-processing function.
     fn get_query_apply_function(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         path: &[&[u8]],
         sized_query: &SizedQuery,
         query_options: QueryOptions,
@@ -61,7 +63,7 @@ pub trait ElementQueryExtensions {
     ) -> CostResult<(QueryResultElements, u16), Error>;
     /// Executes a path query, resolving the path and running the sized query within it.
     fn get_path_query(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         path_query: &PathQuery,
         query_options: QueryOptions,
         result_type: QueryResultType,
@@ -70,7 +72,7 @@ pub trait ElementQueryExtensions {
     ) -> CostResult<(QueryResultElements, u16), Error>;
     /// Returns a vector of elements, and the number of skipped elements
     fn get_sized_query(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         path: &[&[u8]],
         sized_query: &SizedQuery,
         query_options: QueryOptions,
@@ -98,7 +100,7 @@ pub trait ElementQueryExtensions {
     /// expensive query.
     // TODO: refactor
     fn query_item(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         item: &QueryItem,
         results: &mut Vec<QueryResultElement>,
         path: &[&[u8]],
@@ -118,7 +120,7 @@ pub trait ElementQueryExtensions {
 impl ElementQueryExtensions for Element {
     /// Returns a vector of result elements based on given query
     fn get_query(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         merk_path: &[&[u8]],
         query: &Query,
         query_options: QueryOptions,
@@ -146,7 +148,7 @@ impl ElementQueryExtensions for Element {
 
     /// Get values of result elements coming from given query
     fn get_query_values(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         merk_path: &[&[u8]],
         query: &Query,
         query_options: QueryOptions,
@@ -184,7 +186,7 @@ impl ElementQueryExtensions for Element {
     /// Returns a vector of result elements and the number of skipped items
     /// based on given query
     fn get_query_apply_function(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         path: &[&[u8]],
         sized_query: &SizedQuery,
         query_options: QueryOptions,
@@ -268,7 +270,7 @@ impl ElementQueryExtensions for Element {
     /// Returns a vector of elements excluding trees, and the number of skipped
     /// elements
     fn get_path_query(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         path_query: &PathQuery,
         query_options: QueryOptions,
         result_type: QueryResultType,
@@ -299,7 +301,7 @@ impl ElementQueryExtensions for Element {
 
     /// Returns a vector of elements, and the number of skipped elements
     fn get_sized_query(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         path: &[&[u8]],
         sized_query: &SizedQuery,
         query_options: QueryOptions,
@@ -329,224 +331,18 @@ impl ElementQueryExtensions for Element {
         args: PathQueryPushArgs,
         grove_version: &GroveVersion,
     ) -> CostResult<(), Error> {
-        use crate::util::{compat, TxRef};
-
-        check_grovedb_v0_with_cost!(
-            "path_query_push",
-            grove_version.grovedb_versions.element.path_query_push
-        );
-
-        // println!("path_query_push {} \n", args);
-
-        let mut cost = OperationCost::default();
-
-        let PathQueryPushArgs {
-            storage,
-            transaction,
-            key,
-            element,
-            path,
-            subquery_path,
-            subquery,
-            left_to_right,
-            query_options,
-            result_type,
-            results,
-            limit,
-            offset,
-        } = args;
-
-        let tx = TxRef::new(storage, transaction);
-
-        let QueryOptions {
-            allow_get_raw,
-            allow_cache,
-            decrease_limit_on_range_with_no_sub_elements,
-            ..
-        } = query_options;
-        if element.is_any_tree() {
-            let mut path_vec = path.to_vec();
-            let key = cost_return_on_error_no_add!(
-                cost,
-                key.ok_or(Error::MissingParameter(
-                    "the key must be provided when using a subquery path",
-                ))
-            );
-            path_vec.push(key);
-
-            if let Some(subquery) = subquery {
-                if let Some(subquery_path) = &subquery_path {
-                    path_vec.extend(subquery_path.iter().map(|k| k.as_slice()));
-                }
-
-                let inner_query = SizedQuery::new(subquery, *limit, *offset);
-                let path_vec_owned = path_vec.iter().map(|x| x.to_vec()).collect();
-                let inner_path_query = PathQuery::new(path_vec_owned, inner_query);
-
-                let (mut sub_elements, skipped) = cost_return_on_error!(
-                    &mut cost,
-                    Element::get_path_query(
-                        storage,
-                        &inner_path_query,
-                        query_options,
-                        result_type,
-                        transaction,
-                        grove_version,
-                    )
-                );
-
-                if let Some(limit) = limit {
-                    if sub_elements.is_empty() && decrease_limit_on_range_with_no_sub_elements {
-                        // we should decrease by 1 in this case
-                        *limit = limit.saturating_sub(1);
-                    } else {
-                        *limit =
-                            limit.saturating_sub(sub_elements.len().min(u16::MAX as usize) as u16);
-                    }
-                }
-                if let Some(offset) = offset {
-                    *offset = offset.saturating_sub(skipped);
-                }
-                results.append(&mut sub_elements.elements);
-            } else if let Some(subquery_path) = subquery_path {
-                if offset.unwrap_or(0) == 0 {
-                    if let Some((subquery_path_last_key, subquery_path_front_keys)) =
-                        &subquery_path.split_last()
-                    {
-                        path_vec.extend(subquery_path_front_keys.iter().map(|k| k.as_slice()));
-
-                        let subtree_path: SubtreePath<_> = path_vec.as_slice().into();
-                        let subtree = cost_return_on_error!(
-                            &mut cost,
-                            compat::merk_optional_tx(
-                                storage,
-                                subtree_path,
-                                tx.as_ref(),
-                                None,
-                                grove_version
-                            )
-                        );
-
-                        match result_type {
-                            QueryElementResultType => {
-                                if let Some(element) = cost_return_on_error_into!(
-                                    &mut cost,
-                                    Element::get_optional_with_absolute_refs(
-                                        &subtree,
-                                        path_vec.as_slice(),
-                                        subquery_path_last_key.as_slice(),
-                                        allow_cache,
-                                        grove_version,
-                                    )
-                                ) {
-                                    results.push(QueryResultElement::ElementResultItem(element));
-                                }
-                            }
-                            QueryKeyElementPairResultType => {
-                                if let Some(element) = cost_return_on_error_into!(
-                                    &mut cost,
-                                    Element::get_optional_with_absolute_refs(
-                                        &subtree,
-                                        path_vec.as_slice(),
-                                        subquery_path_last_key.as_slice(),
-                                        allow_cache,
-                                        grove_version,
-                                    )
-                                ) {
-                                    results.push(QueryResultElement::KeyElementPairResultItem((
-                                        subquery_path_last_key.to_vec(),
-                                        element,
-                                    )));
-                                }
-                            }
-                            QueryPathKeyElementTrioResultType => {
-                                if let Some(element) = cost_return_on_error_into!(
-                                    &mut cost,
-                                    Element::get_optional_with_absolute_refs(
-                                        &subtree,
-                                        path_vec.as_slice(),
-                                        subquery_path_last_key.as_slice(),
-                                        allow_cache,
-                                        grove_version,
-                                    )
-                                ) {
-                                    results.push(QueryResultElement::PathKeyElementTrioResultItem(
-                                        (
-                                            path_vec.iter().map(|p| p.to_vec()).collect(),
-                                            subquery_path_last_key.to_vec(),
-                                            element,
-                                        ),
-                                    ));
-                                }
-                            }
-                        }
-                    } else {
-                        return Err(Error::CorruptedCodeExecution(
-                            "subquery_paths can not be empty",
-                        ))
-                        .wrap_with_cost(cost);
-                    };
-
-                    if let Some(limit) = limit {
-                        *limit = limit.saturating_sub(1);
-                    }
-                } else if let Some(offset) = offset {
-                    *offset = offset.saturating_sub(1);
-                }
-            } else if allow_get_raw {
-                cost_return_on_error_no_add!(
-                    cost,
-                    Element::basic_push(
-                        PathQueryPushArgs {
-                            storage,
-                            transaction,
-                            key: Some(key),
-                            element,
-                            path,
-                            subquery_path,
-                            subquery,
-                            left_to_right,
-                            query_options,
-                            result_type,
-                            results,
-                            limit,
-                            offset,
-                        },
-                        grove_version
-                    )
-                );
-            } else {
-                return Err(Error::InvalidPath(
-                    "you must provide a subquery or a subquery_path when interacting with a Tree \
-                     of trees"
-                        .to_owned(),
-                ))
-                .wrap_with_cost(cost);
-            }
-        } else {
-            cost_return_on_error_no_add!(
-                cost,
-                Element::basic_push(
-                    PathQueryPushArgs {
-                        storage,
-                        transaction,
-                        key,
-                        element,
-                        path,
-                        subquery_path,
-                        subquery,
-                        left_to_right,
-                        query_options,
-                        result_type,
-                        results,
-                        limit,
-                        offset,
-                    },
-                    grove_version
-                )
-            );
+        match grove_version.grovedb_versions.element.path_query_push {
+            0 => Self::path_query_push_v0(args, grove_version),
+            1 => Self::path_query_push_v1(args, grove_version),
+            version => Err(Error::VersionError(
+                GroveVersionError::UnknownVersionMismatch {
+                    method: "path_query_push".to_string(),
+                    known_versions: vec![0, 1],
+                    received: version,
+                },
+            ))
+            .wrap_with_cost(OperationCost::default()),
         }
-        Ok(()).wrap_with_cost(cost)
     }
 
     /// Takes a sized query and a key and returns subquery key and subquery as
@@ -590,7 +386,7 @@ impl ElementQueryExtensions for Element {
     /// not decrease and hence we would continue on the increasingly
     /// expensive query.
     fn query_item(
-        storage: &RocksDbStorage,
+        storage: &StorageBackend,
         item: &QueryItem,
         results: &mut Vec<QueryResultElement>,
         path: &[&[u8]],
@@ -835,6 +631,453 @@ impl ElementQueryExtensions for Element {
     }
 }
 
+trait ElementQueryPrivateExtensions {
+    fn path_query_push_v0(
+        args: PathQueryPushArgs,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(), Error>;
+
+    fn path_query_push_v1(
+        args: PathQueryPushArgs,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(), Error>;
+}
+
+impl ElementQueryPrivateExtensions for Element {
+    /// Push arguments to path query. A subquery path key takes one from the
+    /// limit, or from the offset, whether or not it is found.
+    fn path_query_push_v0(
+        args: PathQueryPushArgs,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(), Error> {
+        use crate::util::{compat, TxRef};
+
+        // println!("path_query_push {} \n", args);
+
+        let mut cost = OperationCost::default();
+
+        let PathQueryPushArgs {
+            storage,
+            transaction,
+            key,
+            element,
+            path,
+            subquery_path,
+            subquery,
+            left_to_right,
+            query_options,
+            result_type,
+            results,
+            limit,
+            offset,
+        } = args;
+
+        let tx = TxRef::new(storage, transaction);
+
+        let QueryOptions {
+            allow_get_raw,
+            allow_cache,
+            decrease_limit_on_range_with_no_sub_elements,
+            ..
+        } = query_options;
+        if element.is_any_tree() {
+            let mut path_vec = path.to_vec();
+            let key = cost_return_on_error_no_add!(
+                cost,
+                key.ok_or(Error::MissingParameter(
+                    "the key must be provided when using a subquery path",
+                ))
+            );
+            path_vec.push(key);
+
+            if let Some(subquery) = subquery {
+                if let Some(subquery_path) = &subquery_path {
+                    path_vec.extend(subquery_path.iter().map(|k| k.as_slice()));
+                }
+
+                let inner_query = SizedQuery::new(subquery, *limit, *offset);
+                let path_vec_owned = path_vec.iter().map(|x| x.to_vec()).collect();
+                let inner_path_query = PathQuery::new(path_vec_owned, inner_query);
+
+                let (mut sub_elements, skipped) = cost_return_on_error!(
+                    &mut cost,
+                    Element::get_path_query(
+                        storage,
+                        &inner_path_query,
+                        query_options,
+                        result_type,
+                        transaction,
+                        grove_version,
+                    )
+                );
+
+                if let Some(limit) = limit {
+                    if sub_elements.is_empty() && decrease_limit_on_range_with_no_sub_elements {
+                        // we should decrease by 1 in this case
+                        *limit = limit.saturating_sub(1);
+                    } else {
+                        *limit =
+                            limit.saturating_sub(sub_elements.len().min(u16::MAX as usize) as u16);
+                    }
+                }
+                if let Some(offset) = offset {
+                    *offset = offset.saturating_sub(skipped);
+                }
+                results.append(&mut sub_elements.elements);
+            } else if let Some(subquery_path) = subquery_path {
+                if offset.unwrap_or(0) == 0 {
+                    if let Some((subquery_path_last_key, subquery_path_front_keys)) =
+                        &subquery_path.split_last()
+                    {
+                        path_vec.extend(subquery_path_front_keys.iter().map(|k| k.as_slice()));
+
+                        let subtree_path: SubtreePath<_> = path_vec.as_slice().into();
+                        let subtree = cost_return_on_error!(
+                            &mut cost,
+                            compat::merk_optional_tx(
+                                storage,
+                                subtree_path,
+                                tx.as_ref(),
+                                None,
+                                grove_version
+                            )
+                        );
+
+                        match result_type {
+                            QueryElementResultType => {
+                                if let Some(element) = cost_return_on_error_into!(
+                                    &mut cost,
+                                    Element::get_optional_with_absolute_refs(
+                                        &subtree,
+                                        path_vec.as_slice(),
+                                        subquery_path_last_key.as_slice(),
+                                        allow_cache,
+                                        grove_version,
+                                    )
+                                ) {
+                                    results.push(QueryResultElement::ElementResultItem(element));
+                                }
+                            }
+                            QueryKeyElementPairResultType => {
+                                if let Some(element) = cost_return_on_error_into!(
+                                    &mut cost,
+                                    Element::get_optional_with_absolute_refs(
+                                        &subtree,
+                                        path_vec.as_slice(),
+                                        subquery_path_last_key.as_slice(),
+                                        allow_cache,
+                                        grove_version,
+                                    )
+                                ) {
+                                    results.push(QueryResultElement::KeyElementPairResultItem((
+                                        subquery_path_last_key.to_vec(),
+                                        element,
+                                    )));
+                                }
+                            }
+                            QueryPathKeyElementTrioResultType => {
+                                if let Some(element) = cost_return_on_error_into!(
+                                    &mut cost,
+                                    Element::get_optional_with_absolute_refs(
+                                        &subtree,
+                                        path_vec.as_slice(),
+                                        subquery_path_last_key.as_slice(),
+                                        allow_cache,
+                                        grove_version,
+                                    )
+                                ) {
+                                    results.push(QueryResultElement::PathKeyElementTrioResultItem(
+                                        (
+                                            path_vec.iter().map(|p| p.to_vec()).collect(),
+                                            subquery_path_last_key.to_vec(),
+                                            element,
+                                        ),
+                                    ));
+                                }
+                            }
+                        }
+                    } else {
+                        return Err(Error::CorruptedCodeExecution(
+                            "subquery_paths can not be empty",
+                        ))
+                        .wrap_with_cost(cost);
+                    };
+
+                    if let Some(limit) = limit {
+                        *limit = limit.saturating_sub(1);
+                    }
+                } else if let Some(offset) = offset {
+                    *offset = offset.saturating_sub(1);
+                }
+            } else if allow_get_raw {
+                cost_return_on_error_no_add!(
+                    cost,
+                    Element::basic_push(
+                        PathQueryPushArgs {
+                            storage,
+                            transaction,
+                            key: Some(key),
+                            element,
+                            path,
+                            subquery_path,
+                            subquery,
+                            left_to_right,
+                            query_options,
+                            result_type,
+                            results,
+                            limit,
+                            offset,
+                        },
+                        grove_version
+                    )
+                );
+            } else {
+                return Err(Error::InvalidPath(
+                    "you must provide a subquery or a subquery_path when interacting with a Tree \
+                     of trees"
+                        .to_owned(),
+                ))
+                .wrap_with_cost(cost);
+            }
+        } else {
+            cost_return_on_error_no_add!(
+                cost,
+                Element::basic_push(
+                    PathQueryPushArgs {
+                        storage,
+                        transaction,
+                        key,
+                        element,
+                        path,
+                        subquery_path,
+                        subquery,
+                        left_to_right,
+                        query_options,
+                        result_type,
+                        results,
+                        limit,
+                        offset,
+                    },
+                    grove_version
+                )
+            );
+        }
+        Ok(()).wrap_with_cost(cost)
+    }
+
+    /// Push arguments to path query. Only found subquery path keys are taken
+    /// from the offset, and matches skipped by the offset are never taken
+    /// from the limit.
+    fn path_query_push_v1(
+        args: PathQueryPushArgs,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(), Error> {
+        use crate::util::{compat, TxRef};
+
+        // println!("path_query_push {} \n", args);
+
+        let mut cost = OperationCost::default();
+
+        let PathQueryPushArgs {
+            storage,
+            transaction,
+            key,
+            element,
+            path,
+            subquery_path,
+            subquery,
+            left_to_right,
+            query_options,
+            result_type,
+            results,
+            limit,
+            offset,
+        } = args;
+
+        let tx = TxRef::new(storage, transaction);
+
+        let QueryOptions {
+            allow_get_raw,
+            allow_cache,
+            decrease_limit_on_range_with_no_sub_elements,
+            ..
+        } = query_options;
+        if element.is_any_tree() {
+            let mut path_vec = path.to_vec();
+            let key = cost_return_on_error_no_add!(
+                cost,
+                key.ok_or(Error::MissingParameter(
+                    "the key must be provided when using a subquery path",
+                ))
+            );
+            path_vec.push(key);
+
+            if let Some(subquery) = subquery {
+                if let Some(subquery_path) = &subquery_path {
+                    path_vec.extend(subquery_path.iter().map(|k| k.as_slice()));
+                }
+
+                let inner_query = SizedQuery::new(subquery, *limit, *offset);
+                let path_vec_owned = path_vec.iter().map(|x| x.to_vec()).collect();
+                let inner_path_query = PathQuery::new(path_vec_owned, inner_query);
+
+                let (mut sub_elements, skipped) = cost_return_on_error!(
+                    &mut cost,
+                    Element::get_path_query(
+                        storage,
+                        &inner_path_query,
+                        query_options,
+                        result_type,
+                        transaction,
+                        grove_version,
+                    )
+                );
+
+                if let Some(limit) = limit {
+                    if sub_elements.is_empty()
+                        && skipped == 0
+                        && decrease_limit_on_range_with_no_sub_elements
+                    {
+                        // we should decrease by 1 in this case
+                        *limit = limit.saturating_sub(1);
+                    } else {
+                        *limit =
+                            limit.saturating_sub(sub_elements.len().min(u16::MAX as usize) as u16);
+                    }
+                }
+                if let Some(offset) = offset {
+                    *offset = offset.saturating_sub(skipped);
+                }
+                results.append(&mut sub_elements.elements);
+            } else if let Some(subquery_path) = subquery_path {
+                let Some((subquery_path_last_key, subquery_path_front_keys)) =
+                    &subquery_path.split_last()
+                else {
+                    return Err(Error::CorruptedCodeExecution(
+                        "subquery_paths can not be empty",
+                    ))
+                    .wrap_with_cost(cost);
+                };
+                path_vec.extend(subquery_path_front_keys.iter().map(|k| k.as_slice()));
+
+                let subtree_path: SubtreePath<_> = path_vec.as_slice().into();
+                let subtree = cost_return_on_error!(
+                    &mut cost,
+                    compat::merk_optional_tx(
+                        storage,
+                        subtree_path,
+                        tx.as_ref(),
+                        None,
+                        grove_version
+                    )
+                );
+                let element = cost_return_on_error_into!(
+                    &mut cost,
+                    Element::get_optional_with_absolute_refs(
+                        &subtree,
+                        path_vec.as_slice(),
+                        subquery_path_last_key.as_slice(),
+                        allow_cache,
+                        grove_version,
+                    )
+                );
+
+                // The element at the end of the subquery path is the only
+                // result of a subquery: the offset only skips it when found,
+                // and the subquery is without results when it is not found,
+                // like in proofs.
+                match element {
+                    Some(element) if offset.unwrap_or(0) == 0 => {
+                        results.push(match result_type {
+                            QueryElementResultType => {
+                                QueryResultElement::ElementResultItem(element)
+                            }
+                            QueryKeyElementPairResultType => {
+                                QueryResultElement::KeyElementPairResultItem((
+                                    subquery_path_last_key.to_vec(),
+                                    element,
+                                ))
+                            }
+                            QueryPathKeyElementTrioResultType => {
+                                QueryResultElement::PathKeyElementTrioResultItem((
+                                    path_vec.iter().map(|p| p.to_vec()).collect(),
+                                    subquery_path_last_key.to_vec(),
+                                    element,
+                                ))
+                            }
+                        });
+                        if let Some(limit) = limit {
+                            *limit = limit.saturating_sub(1);
+                        }
+                    }
+                    Some(_) => {
+                        if let Some(offset) = offset {
+                            *offset = offset.saturating_sub(1);
+                        }
+                    }
+                    None => {
+                        if decrease_limit_on_range_with_no_sub_elements && let Some(limit) = limit {
+                            *limit = limit.saturating_sub(1);
+                        }
+                    }
+                }
+            } else if allow_get_raw {
+                cost_return_on_error_no_add!(
+                    cost,
+                    Element::basic_push(
+                        PathQueryPushArgs {
+                            storage,
+                            transaction,
+                            key: Some(key),
+                            element,
+                            path,
+                            subquery_path,
+                            subquery,
+                            left_to_right,
+                            query_options,
+                            result_type,
+                            results,
+                            limit,
+                            offset,
+                        },
+                        grove_version
+                    )
+                );
+            } else {
+                return Err(Error::InvalidPath(
+                    "you must provide a subquery or a subquery_path when interacting with a Tree \
+                     of trees"
+                        .to_owned(),
+                ))
+                .wrap_with_cost(cost);
+            }
+        } else {
+            cost_return_on_error_no_add!(
+                cost,
+                Element::basic_push(
+                    PathQueryPushArgs {
+                        storage,
+                        transaction,
+                        key,
+                        element,
+                        path,
+                        subquery_path,
+                        subquery,
+                        left_to_right,
+                        query_options,
+                        result_type,
+                        results,
+                        limit,
+                        offset,
+                    },
+                    grove_version
+                )
+            );
+        }
+        Ok(()).wrap_with_cost(cost)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use grovedb_element::Element;
@@ -0,0 +1,435 @@
+//! Lazy iteration over the results of path queries.
+//!
+//! [`QueryIterator`] walks a path query depth-first, subquery by subquery,
+//! and hands out its results one at a time. Every subtree on the way down
+//! keeps a small buffer of the elements read ahead of the results, so the
+//! memory used depends on the depth of the query and not on the number of
+//! its results.
+
+use std::collections::VecDeque;
+
+use grovedb_costs::{
+    cost_return_on_error, cost_return_on_error_into_no_add, cost_return_on_error_no_add,
+    CostResult, CostsExt, OperationCost,
+};
+use grovedb_element::Element;
+use grovedb_merk::{
+    element::{decode::ElementDecodeExtensions, get::ElementFetchFromStorageExtensions},
+    proofs::query::query_item::QueryItem,
+};
+use grovedb_storage::{backend::StorageBackend, RawIterator, Storage, StorageContext};
+use grovedb_version::{error::GroveVersionError, version::GroveVersion};
+
+use crate::{
+    element::query::ElementQueryExtensions,
+    query_result_type::{Key, KeyElementPair, Path, PathKeyElementTrio},
+    util::{compat, TxRef},
+    Error, PathQuery, SizedQuery, Transaction, TransactionArg,
+};
+
+/// Maximum number of elements read ahead from a subtree at once.
+const READ_AHEAD: usize = 64;
+
+/// Iterator over the results of a path query, see [`crate::GroveDb::query_iter`].
+///
+/// The results are the ones of [`crate::GroveDb::query_raw`] with the path,
+/// key and element of every result, in the same order. The first error ends
+/// the iteration.
+pub struct QueryIterator<'db> {
+    storage: &'db StorageBackend,
+    transaction: TxRef<'db, 'db>,
+    /// Subtrees being walked, from the path query path down to the deepest
+    /// subquery reached
+    layers: Vec<QueryLayer>,
+    cost: OperationCost,
+    grove_version: &'db GroveVersion,
+}
+
+/// A subtree being walked by a [`QueryIterator`].
+struct QueryLayer {
+    path: Path,
+    /// The query of the subtree, its limit and offset being what is left of
+    /// them
+    query: SizedQuery,
+    /// Query items not walked yet, in walking order
+    items: VecDeque<QueryItem>,
+    /// Last key read for the current query item
+    last_key: Option<Key>,
+    /// Whether every element of the current query item has been read
+    item_read: bool,
+    /// Elements read but not walked yet
+    read_ahead: VecDeque<KeyElementPair>,
+    /// Number of results found in the subtree and its subqueries
+    result_count: u16,
+}
+
+impl QueryLayer {
+    fn new(path: Path, query: SizedQuery) -> Self {
+        let items = if query.query.left_to_right {
+            query.query.iter().cloned().collect()
+        } else {
+            query.query.rev_iter().cloned().collect()
+        };
+        QueryLayer {
+            path,
+            query,
+            items,
+            last_key: None,
+            item_read: false,
+            read_ahead: VecDeque::new(),
+            result_count: 0,
+        }
+    }
+
+    /// Returns the next element of the subtree matching its query, or `None`
+    /// once the query items or the limit are exhausted.
+    fn next_element(
+        &mut self,
+        storage: &StorageBackend,
+        transaction: &Transaction,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Option<KeyElementPair>, Error> {
+        let mut cost = OperationCost::default();
+        loop {
+            let Some(item) = self.items.front() else {
+                return Ok(None).wrap_with_cost(cost);
+            };
+            // Like `query_raw`, key items are checked against the limit once
+            // the previous item is done, ranges before every element.
+            if item.is_range() && self.query.limit == Some(0) {
+                return Ok(None).wrap_with_cost(cost);
+            }
+            if let Some(element) = self.read_ahead.pop_front() {
+                return Ok(Some(element)).wrap_with_cost(cost);
+            }
+            if self.item_read {
+                self.items.pop_front();
+                self.last_key = None;
+                self.item_read = false;
+                if self.query.limit == Some(0) {
+                    return Ok(None).wrap_with_cost(cost);
+                }
+                continue;
+            }
+            cost_return_on_error!(
+                &mut cost,
+                self.read_item(storage, transaction, grove_version)
+            );
+        }
+    }
+
+    /// Reads the next elements of the current query item into the read ahead
+    /// buffer.
+    fn read_item(
+        &mut self,
+        storage: &StorageBackend,
+        transaction: &Transaction,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(), Error> {
+        let mut cost = OperationCost::default();
+        let item = self.items.front().expect("a query item is being read");
+        let path: Vec<&[u8]> = self.path.iter().map(|key| key.as_slice()).collect();
+
+        if let QueryItem::Key(key) = item {
+            let subtree = cost_return_on_error!(
+                &mut cost,
+                compat::merk_optional_tx(
+                    storage,
+                    path.as_slice().into(),
+                    transaction,
+                    None,
+                    grove_version
+                )
+            );
+            match Element::get(&subtree, key, true, grove_version)
+                .map_err(|e| e.into())
+                .unwrap_add_cost(&mut cost)
+            {
+                Ok(element) => self.read_ahead.push_back((key.clone(), element)),
+                Err(Error::PathKeyNotFound(_)) => {}
+                Err(e) => return Err(e).wrap_with_cost(cost),
+            }
+            self.item_read = true;
+            return Ok(()).wrap_with_cost(cost);
+        }
+
+        let left_to_right = self.query.query.left_to_right;
+        let ctx = storage
+            .get_transactional_storage_context(path.as_slice().into(), None, transaction)
+            .unwrap_add_cost(&mut cost);
+        let mut iter = ctx.raw_iter();
+        match &self.last_key {
+            None => item
+                .seek_for_iter(&mut iter, left_to_right)
+                .unwrap_add_cost(&mut cost),
+            Some(last_key) => {
+                if left_to_right {
+                    iter.seek(last_key).unwrap_add_cost(&mut cost);
+                } else {
+                    iter.seek_for_prev(last_key).unwrap_add_cost(&mut cost);
+                }
+                if iter.key().unwrap_add_cost(&mut cost) == Some(last_key.as_slice()) {
+                    if left_to_right {
+                        iter.next().unwrap_add_cost(&mut cost);
+                    } else {
+                        iter.prev().unwrap_add_cost(&mut cost);
+                    }
+                }
+            }
+        }
+
+        // Every element walked takes at least one from the limit or the offset
+        let wanted = self.query.limit.map_or(READ_AHEAD, |limit| {
+            (limit.saturating_add(self.query.offset.unwrap_or(0)) as usize).min(READ_AHEAD)
+        });
+        while self.read_ahead.len() < wanted {
+            if !item
+                .iter_is_valid_for_type(&iter, None, None, left_to_right)
+                .unwrap_add_cost(&mut cost)
+            {
+                self.item_read = true;
+                break;
+            }
+            let key = cost_return_on_error_no_add!(
+                cost,
+                iter.key()
+                    .unwrap_add_cost(&mut cost)
+                    .map(|key| key.to_vec())
+                    .ok_or(Error::CorruptedData(
+                        "expected iterator key but got None".to_string(),
+                    ))
+            );
+            let value = cost_return_on_error_no_add!(
+                cost,
+                iter.value()
+                    .unwrap_add_cost(&mut cost)
+                    .ok_or(Error::CorruptedData(
+                        "expected iterator value but got None".to_string(),
+                    ))
+            );
+            let element =
+                cost_return_on_error_into_no_add!(cost, Element::raw_decode(value, grove_version));
+            self.last_key = Some(key.clone());
+            self.read_ahead.push_back((key, element));
+            if left_to_right {
+                iter.next().unwrap_add_cost(&mut cost);
+            } else {
+                iter.prev().unwrap_add_cost(&mut cost);
+            }
+            cost.seek_count += 1;
+        }
+        Ok(()).wrap_with_cost(cost)
+    }
+
+    /// Takes a result of the subtree, or a match skipped by the offset, out
+    /// of its limit or offset. Returns whether the match is a result.
+    fn take_match(&mut self) -> bool {
+        match self.query.offset.as_mut() {
+            Some(offset) if *offset > 0 => {
+                *offset -= 1;
+                false
+            }
+            _ => {
+                if let Some(limit) = self.query.limit.as_mut() {
+                    *limit = limit.saturating_sub(1);
+                }
+                true
+            }
+        }
+    }
+}
+
+impl<'db> QueryIterator<'db> {
+    pub(crate) fn new(
+        storage: &'db StorageBackend,
+        path_query: &PathQuery,
+        transaction: TransactionArg<'db, 'db>,
+        grove_version: &'db GroveVersion,
+    ) -> Self {
+        QueryIterator {
+            storage,
+            transaction: TxRef::new(storage, transaction),
+            layers: vec![QueryLayer::new(
+                path_query.path.clone(),
+                path_query.query.clone(),
+            )],
+            cost: OperationCost::default(),
+            grove_version,
+        }
+    }
+
+    /// Returns the cost of the iteration so far.
+    pub fn cost(&self) -> &OperationCost {
+        &self.cost
+    }
+
+    /// Walks the query up to its next result.
+    fn next_result(&mut self) -> CostResult<Option<PathKeyElementTrio>, Error> {
+        let mut cost = OperationCost::default();
+        let grove_version = self.grove_version;
+        let found_keys_only =
+            cost_return_on_error_no_add!(cost, Self::offsets_skip_found_keys_only(grove_version));
+        loop {
+            let Some(layer) = self.layers.last_mut() else {
+                return Ok(None).wrap_with_cost(cost);
+            };
+            let next_element = cost_return_on_error!(
+                &mut cost,
+                layer.next_element(self.storage, self.transaction.as_ref(), grove_version)
+            );
+            let Some((key, element)) = next_element else {
+                let done = self.layers.pop().expect("the layer is walked");
+                if let Some(parent) = self.layers.last_mut() {
+                    // The results of a subquery without any also take one
+                    // from the limit, see `decrease_limit_on_range_with_no_sub_elements`,
+                    // unless the offset skipped them
+                    let skipped = found_keys_only && parent.query.offset != done.query.offset;
+                    if let Some(limit) = parent.query.limit.as_mut() {
+                        let taken = if done.result_count == 0 && skipped {
+                            0
+                        } else {
+                            done.result_count.max(1)
+                        };
+                        *limit = limit.saturating_sub(taken);
+                    }
+                    parent.query.offset = done.query.offset;
+                }
+                continue;
+            };
+
+            if !element.is_any_tree() {
+                let path = layer.path.clone();
+                if let Some(result) =
+                    cost_return_on_error_no_add!(cost, self.take_result(path, key, element))
+                {
+                    return Ok(Some(result)).wrap_with_cost(cost);
+                }
+                continue;
+            }
+
+            let (subquery_path, subquery) =
+                Element::subquery_paths_and_value_for_sized_query(&layer.query, &key);
+            let mut path = layer.path.clone();
+            match (subquery_path, subquery) {
+                (subquery_path, Some(subquery)) => {
+                    path.push(key);
+                    path.extend(subquery_path.unwrap_or_default());
+                    let query = SizedQuery::new(subquery, layer.query.limit, layer.query.offset);
+                    self.layers.push(QueryLayer::new(path, query));
+                }
+                (Some(subquery_path), None) => {
+                    let Some((last_key, front_keys)) = subquery_path.split_last() else {
+                        return Err(Error::CorruptedCodeExecution(
+                            "subquery_paths can not be empty",
+                        ))
+                        .wrap_with_cost(cost);
+                    };
+                    if !found_keys_only && !layer.take_match() {
+                        continue;
+                    }
+                    path.push(key);
+                    path.extend(front_keys.iter().cloned());
+                    let path_slices: Vec<&[u8]> = path.iter().map(|key| key.as_slice()).collect();
+                    let subtree = cost_return_on_error!(
+                        &mut cost,
+                        compat::merk_optional_tx(
+                            self.storage,
+                            path_slices.as_slice().into(),
+                            self.transaction.as_ref(),
+                            None,
+                            grove_version
+                        )
+                    );
+                    let element = cost_return_on_error!(
+                        &mut cost,
+                        Element::get_optional_with_absolute_refs(
+                            &subtree,
+                            path_slices.as_slice(),
+                            last_key,
+                            true,
+                            grove_version,
+                        )
+                        .map_err(|e| e.into())
+                    );
+                    // Like in `query_raw`, the offset only skips the element
+                    // when found, and a missing one takes one from the limit
+                    let layer = self.layers.last_mut().expect("a layer is being walked");
+                    match element {
+                        Some(element) => {
+                            if !found_keys_only || layer.take_match() {
+                                for layer in self.layers.iter_mut() {
+                                    layer.result_count = layer.result_count.saturating_add(1);
+                                }
+                                return Ok(Some((path, last_key.clone(), element)))
+                                    .wrap_with_cost(cost);
+                            }
+                        }
+                        None => {
+                            if found_keys_only && let Some(limit) = layer.query.limit.as_mut() {
+                                *limit = limit.saturating_sub(1);
+                            }
+                        }
+                    }
+                }
+                (None, None) => {
+                    if let Some(result) =
+                        cost_return_on_error_no_add!(cost, self.take_result(path, key, element))
+                    {
+                        return Ok(Some(result)).wrap_with_cost(cost);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether only the subquery path keys which are found are taken from the
+    /// offset, and matches skipped by the offset are never taken from the
+    /// limit, like in version 1 of `path_query_push`. Before, a subquery path
+    /// key is taken from the offset or the limit before it is read.
+    fn offsets_skip_found_keys_only(grove_version: &GroveVersion) -> Result<bool, Error> {
+        match grove_version.grovedb_versions.element.path_query_push {
+            0 => Ok(false),
+            1 => Ok(true),
+            version => Err(Error::VersionError(
+                GroveVersionError::UnknownVersionMismatch {
+                    method: "path_query_push".to_string(),
+                    known_versions: vec![0, 1],
+                    received: version,
+                },
+            )),
+        }
+    }
+
+    /// Takes an element of the deepest subtree being walked as a result,
+    /// unless it is skipped by the offset.
+    fn take_result(
+        &mut self,
+        path: Path,
+        key: Key,
+        element: Element,
+    ) -> Result<Option<PathKeyElementTrio>, Error> {
+        let path_slices: Vec<&[u8]> = path.iter().map(|key| key.as_slice()).collect();
+        let element =
+            element.convert_if_reference_to_absolute_reference(&path_slices, Some(&key))?;
+        let layer = self.layers.last_mut().expect("a layer is being walked");
+        if !layer.take_match() {
+            return Ok(None);
+        }
+        for layer in self.layers.iter_mut() {
+            layer.result_count = layer.result_count.saturating_add(1);
+        }
+        Ok(Some((path, key, element)))
+    }
+}
+
+impl Iterator for QueryIterator<'_> {
+    type Item = Result<PathKeyElementTrio, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next_result().unwrap_add_cost(&mut self.cost);
+        if result.is_err() {
+            self.layers.clear();
+        }
+        result.transpose()
+    }
+}
@@ -169,6 +169,11 @@ pub enum Error {
     #[error("commitment tree error: {0}")]
     /// Commitment tree operation error
     CommitmentTreeError(String),
+
+    #[cfg(feature = "minimal")]
+    #[error("invalid chunk envelope: {0}")]
+    /// A state sync chunk was rejected before being applied
+    InvalidChunkEnvelope(crate::replication::ChunkEnvelopeError),
 }
 
 impl Error {
@@ -28,6 +28,7 @@ use integer_encoding::VarInt;
 
 use crate::{
     batch::{key_info::KeyInfo, KeyInfoPath},
+    subtree_registry::{add_subtree_entry_delete_cost, add_subtree_entry_put_cost},
     Element, ElementFlags, Error, GroveDb,
 };
 
@@ -209,6 +210,7 @@ impl GroveDb {
         let tree_cost_size = tree_type.cost_size();
         let value_len = tree_cost_size + flags_len;
         add_cost_case_merk_insert_layered(&mut cost, key_len, value_len, in_parent_tree_type);
+        add_subtree_entry_put_cost(&mut cost, key_len, grove_version);
         if let Some(input) = propagate_if_input {
             add_average_case_merk_propagate(&mut cost, input, grove_version)
                 .map_err(Error::MerkError)
@@ -249,6 +251,7 @@ impl GroveDb {
         let tree_cost_size = tree_type.cost_size();
         let layer_extra_size = tree_cost_size + flags_size;
         add_average_case_merk_delete_layered(&mut cost, key_len, layer_extra_size);
+        add_subtree_entry_delete_cost(&mut cost, grove_version);
         if propagate {
             add_average_case_merk_propagate(&mut cost, estimated_layer_information, grove_version)
                 .map_err(Error::MerkError)
@@ -296,6 +299,9 @@ impl GroveDb {
                 in_tree_type,
             )
         }
+        if value.is_any_tree() {
+            add_subtree_entry_put_cost(&mut cost, key_len, grove_version);
+        }
         if let Some(level) = propagate_for_level {
             add_average_case_merk_propagate(&mut cost, level, grove_version)
                 .map_err(Error::MerkError)
@@ -25,6 +25,7 @@ use integer_encoding::VarInt;
 
 use crate::{
     batch::{key_info::KeyInfo, KeyInfoPath},
+    subtree_registry::{add_subtree_entry_delete_cost, add_subtree_entry_put_cost},
     Element, ElementFlags, Error, GroveDb,
 };
 
@@ -126,6 +127,7 @@ impl GroveDb {
         let tree_cost = tree_type.cost_size();
         let value_len = tree_cost + flags_len;
         add_cost_case_merk_insert_layered(&mut cost, key_len, value_len, in_parent_tree_type);
+        add_subtree_entry_put_cost(&mut cost, key_len, grove_version);
         if let Some(input) = propagate_if_input {
             add_worst_case_merk_propagate(&mut cost, input).map_err(Error::MerkError)
         } else {
@@ -156,6 +158,7 @@ impl GroveDb {
         let tree_cost = tree_type.cost_size();
         let layer_extra_size = tree_cost + WORST_CASE_FLAGS_LEN;
         add_worst_case_merk_delete_layered(&mut cost, key_len, layer_extra_size);
+        add_subtree_entry_delete_cost(&mut cost, grove_version);
         if propagate {
             add_worst_case_merk_propagate(&mut cost, worst_case_layer_information)
                 .map_err(Error::MerkError)
@@ -212,6 +215,9 @@ impl GroveDb {
                 in_parent_tree_type,
             ),
         };
+        if value.is_any_tree() {
+            add_subtree_entry_put_cost(&mut cost, key_len, grove_version);
+        }
         if let Some(level) = propagate_for_level {
             add_worst_case_merk_propagate(&mut cost, level).map_err(Error::MerkError)
         } else {
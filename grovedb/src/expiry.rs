@@ -0,0 +1,152 @@
+//! Sweeping of items past their expiry, see [`GroveDb::sweep_expired`].
+
+use std::collections::BTreeSet;
+
+use grovedb_costs::{cost_return_on_error, CostResult, CostsExt, OperationCost};
+use grovedb_merk::proofs::Query;
+use grovedb_version::version::GroveVersion;
+
+use crate::{
+    batch::QualifiedGroveDbOp, element::ExpiryValue, query_result_type::QueryResultType,
+    reference_path::ReferencePathType, Element, Error, GroveDb, PathQuery, SizedQuery,
+    TransactionArg,
+};
+
+/// Returns the key of the item an expiry index entry refers to.
+fn indexed_key(entry: Element) -> Option<Vec<u8>> {
+    match entry {
+        Element::Reference(ReferencePathType::SiblingReference(key), ..) => Some(key),
+        Element::Reference(ReferencePathType::AbsolutePathReference(mut path), ..) => path.pop(),
+        _ => None,
+    }
+}
+
+impl GroveDb {
+    /// Returns the deletes of the first `batch_size` items expiring at or
+    /// before `up_to`, found through the registered expiry indexes, see
+    /// [`SecondaryIndex::expiry`](crate::SecondaryIndex::expiry).
+    ///
+    /// Items are taken by their index entries in key order, so by expiry and
+    /// then by key, with ties between indexes broken by the index path. As
+    /// the indexes are subtrees like any other, nodes with the same state
+    /// and the same registered indexes get the same deletes. Applying them
+    /// with `apply_batch` also deletes the index entries.
+    ///
+    /// Only `apply_batch` writes items with an expiry, and only under a
+    /// subtree with a registered expiry index, so a node missing the index
+    /// fails the write rather than leaving the item unindexed. `delete` and
+    /// `insert` don't maintain indexes though: an entry whose item is gone,
+    /// or no longer has that expiry, gets a delete of the entry itself.
+    pub fn expired_delete_ops(
+        &self,
+        up_to: ExpiryValue,
+        batch_size: u16,
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<QualifiedGroveDbOp>, Error> {
+        let mut cost = OperationCost::default();
+        if batch_size == 0 {
+            return Ok(Vec::new()).wrap_with_cost(cost);
+        }
+
+        let mut indexes: Vec<_> = self
+            .secondary_indexes()
+            .into_iter()
+            .filter(|index| index.is_expiry_index())
+            .collect();
+        indexes.sort_by(|a, b| a.index_path().cmp(b.index_path()));
+
+        let mut query = Query::new();
+        match up_to.checked_add(1) {
+            Some(end) => query.insert_range_to(..end.to_be_bytes().to_vec()),
+            None => query.insert_all(),
+        }
+
+        let mut expired = Vec::new();
+        for index in indexes.iter().map(AsRef::as_ref) {
+            let path_query = PathQuery::new(
+                index.index_path().to_vec(),
+                SizedQuery::new(query.clone(), Some(batch_size), None),
+            );
+            let (entries, _) = cost_return_on_error!(
+                &mut cost,
+                self.query_raw(
+                    &path_query,
+                    true,
+                    true,
+                    true,
+                    QueryResultType::QueryKeyElementPairResultType,
+                    transaction,
+                    grove_version,
+                )
+            );
+            for (entry_key, entry) in entries.to_key_elements() {
+                let Some(key) = indexed_key(entry) else {
+                    return Err(Error::CorruptedData(
+                        "expiry index entry is not a reference to an item".to_string(),
+                    ))
+                    .wrap_with_cost(cost);
+                };
+                expired.push((entry_key, index, key));
+            }
+        }
+        expired.sort_by(|(a_entry_key, a_index, _), (b_entry_key, b_index, _)| {
+            (a_entry_key, a_index.index_path()).cmp(&(b_entry_key, b_index.index_path()))
+        });
+
+        let mut ops = Vec::new();
+        // Two indexes of the same items give the same item twice.
+        let mut taken = BTreeSet::new();
+        for (entry_key, index, key) in expired {
+            if ops.len() == batch_size as usize {
+                break;
+            }
+            let item = cost_return_on_error!(
+                &mut cost,
+                self.get_raw_optional(index.source_path().into(), &key, transaction, grove_version)
+            );
+            // An item deleted or replaced without `apply_batch` leaves its
+            // entry behind, which is deleted instead of the item.
+            let is_current =
+                item.is_some_and(|item| index.entry_key(&key, &item).as_ref() == Some(&entry_key));
+            let (path, key) = if is_current {
+                (index.source_path(), key)
+            } else {
+                (index.index_path(), entry_key)
+            };
+            if taken.insert((path, key.clone())) {
+                ops.push(QualifiedGroveDbOp::delete_op(path.to_vec(), key));
+            }
+        }
+        Ok(ops).wrap_with_cost(cost)
+    }
+
+    /// Deletes the first `batch_size` items expiring at or before `up_to`,
+    /// see [`GroveDb::expired_delete_ops`], and returns the deletes that were
+    /// applied. Costs are those of finding the items and of applying the
+    /// batch.
+    ///
+    /// Expired items stay readable until swept, calling this with the current
+    /// height or time at every block keeps them from lingering.
+    pub fn sweep_expired(
+        &self,
+        up_to: ExpiryValue,
+        batch_size: u16,
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<QualifiedGroveDbOp>, Error> {
+        let mut cost = OperationCost::default();
+
+        let ops = cost_return_on_error!(
+            &mut cost,
+            self.expired_delete_ops(up_to, batch_size, transaction, grove_version)
+        );
+        if !ops.is_empty() {
+            cost_return_on_error!(
+                &mut cost,
+                self.apply_batch(ops.clone(), None, transaction, grove_version)
+            );
+        }
+        Ok(ops).wrap_with_cost(cost)
+    }
+}
@@ -0,0 +1,75 @@
+//! Reading and proving past versions of a GroveDb opened with history, see
+//! [`GroveDbOptions::history_retention`](crate::GroveDbOptions::history_retention).
+
+use std::ops::RangeInclusive;
+
+use grovedb_costs::{
+    cost_return_on_error, cost_return_on_error_no_add, CostResult, CostsExt, OperationCost,
+};
+use grovedb_version::version::GroveVersion;
+
+use crate::{operations::proof::ProveOptions, Error, GroveDb, Hash, PathQuery};
+
+impl GroveDb {
+    /// Returns the versions which can currently be read, the last one being
+    /// the latest committed state. `None` if the GroveDb was opened without
+    /// history.
+    ///
+    /// Every commit changing data makes a new version, so a transaction
+    /// committed per block makes one version per block.
+    pub fn retained_versions(&self) -> Result<Option<RangeInclusive<u64>>, Error> {
+        Ok(self
+            .db
+            .history_version_range()?
+            .map(|(earliest, latest)| earliest..=latest))
+    }
+
+    /// Returns the root hash the GroveDb had at `version`.
+    pub fn root_hash_at_version(
+        &self,
+        version: u64,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Hash, Error> {
+        self.with_version(version, |past| past.root_hash(None, grove_version))
+    }
+
+    /// Generates a proof for a path query against the state at `version`. The
+    /// proof verifies to [`Self::root_hash_at_version`].
+    pub fn prove_query_at_version(
+        &self,
+        path_query: &PathQuery,
+        version: u64,
+        prove_options: Option<ProveOptions>,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error> {
+        self.with_version(version, |past| {
+            past.prove_query(path_query, prove_options, grove_version)
+        })
+    }
+
+    /// Runs `f` against a read-only GroveDb at `version`, failing if the
+    /// version got pruned by concurrent commits before `f` was done.
+    fn with_version<T>(
+        &self,
+        version: u64,
+        f: impl FnOnce(&GroveDb) -> CostResult<T, Error>,
+    ) -> CostResult<T, Error> {
+        let mut cost = OperationCost::default();
+
+        let past = GroveDb {
+            db: cost_return_on_error_no_add!(cost, self.db.at_version(version).map_err(Into::into)),
+            change_log: Default::default(),
+            indexes: Default::default(),
+        };
+        let value = cost_return_on_error!(&mut cost, f(&past));
+
+        let still_retained = cost_return_on_error_no_add!(cost, self.retained_versions())
+            .is_some_and(|versions| versions.contains(&version));
+        if !still_retained {
+            return Err(Error::InvalidInput("version was pruned while being read"))
+                .wrap_with_cost(cost);
+        }
+
+        Ok(value).wrap_with_cost(cost)
+    }
+}
@@ -136,6 +136,8 @@
 #[cfg(feature = "minimal")]
 pub mod batch;
 #[cfg(feature = "minimal")]
+mod change_log;
+#[cfg(feature = "minimal")]
 mod checkpoints;
 #[cfg(feature = "grovedbg")]
 pub mod debugger;
@@ -146,20 +148,30 @@ pub mod error;
 #[cfg(feature = "estimated_costs")]
 mod estimated_costs;
 #[cfg(feature = "minimal")]
-#[allow(dead_code)] // WIP module, will be used in future batch rework
+mod expiry;
+#[cfg(feature = "minimal")]
+mod history;
+#[cfg(feature = "minimal")]
 mod merk_cache;
 #[cfg(any(feature = "minimal", feature = "verify"))]
 pub mod operations;
+#[cfg(feature = "minimal")]
+mod options;
 #[cfg(any(feature = "minimal", feature = "verify"))]
 mod query;
 #[cfg(any(feature = "minimal", feature = "verify"))]
 pub mod query_result_type;
 #[cfg(feature = "minimal")]
-#[allow(dead_code)] // WIP module, will be used in future batch rework
+mod read_only;
+#[cfg(feature = "minimal")]
 pub mod reference_path;
 #[cfg(feature = "minimal")]
 /// State replication and synchronization support.
 pub mod replication;
+#[cfg(feature = "minimal")]
+mod secondary_index;
+#[cfg(feature = "minimal")]
+mod subtree_registry;
 #[cfg(all(test, feature = "minimal"))]
 mod tests;
 #[cfg(feature = "minimal")]
@@ -172,6 +184,10 @@ use std::sync::Arc;
 #[cfg(feature = "minimal")]
 use std::{collections::HashMap, option::Option::None, path::Path};
 
+#[cfg(feature = "minimal")]
+use change_log::ChangeLog;
+#[cfg(feature = "minimal")]
+pub use change_log::{AggregateDelta, ChangeSet, ChangeSubscriptionId, ElementChange};
 #[cfg(feature = "grovedbg")]
 use debugger::start_visualizer;
 #[cfg(any(feature = "minimal", feature = "verify"))]
@@ -183,9 +199,7 @@ pub use element::ElementFlags;
 #[cfg(feature = "minimal")]
 use grovedb_costs::cost_return_on_error_into;
 #[cfg(feature = "minimal")]
-use grovedb_costs::{
-    cost_return_on_error, cost_return_on_error_no_add, CostResult, CostsExt, OperationCost,
-};
+use grovedb_costs::{cost_return_on_error, CostResult, CostsExt, OperationCost};
 #[cfg(any(feature = "minimal", feature = "verify"))]
 pub use grovedb_merk::calculate_max_tree_depth_from_count;
 #[cfg(feature = "minimal")]
@@ -230,24 +244,38 @@ use grovedb_merk::{
 #[cfg(feature = "minimal")]
 use grovedb_path::SubtreePath;
 #[cfg(feature = "minimal")]
-use grovedb_storage::rocksdb_storage::PrefixedRocksDbImmediateStorageContext;
+use grovedb_storage::backend::PrefixedBackendImmediateStorageContext;
+#[cfg(feature = "minimal")]
+use grovedb_storage::backend::StorageBackend;
+#[cfg(feature = "minimal")]
+use grovedb_storage::memory_storage::MemoryStorage;
 #[cfg(feature = "minimal")]
 use grovedb_storage::rocksdb_storage::RocksDbStorage;
 #[cfg(feature = "minimal")]
-use grovedb_storage::{rocksdb_storage::PrefixedRocksDbTransactionContext, StorageBatch};
+use grovedb_storage::{backend::PrefixedBackendTransactionContext, StorageBatch};
 #[cfg(feature = "minimal")]
 use grovedb_storage::{Storage, StorageContext};
 #[cfg(feature = "minimal")]
 use grovedb_version::version::GroveVersion;
 #[cfg(feature = "minimal")]
 use grovedb_visualize::DebugByteVectors;
+#[cfg(feature = "minimal")]
+pub use options::{ColumnFamilyOptions, CompressionType, GroveDbOptions};
 #[cfg(any(feature = "minimal", feature = "verify"))]
 pub use query::{
     aggregate_sum_path_query::AggregateSumPathQuery, GroveBranchQueryResult, GroveTrunkQueryResult,
-    LeafInfo, PathBranchChunkQuery, PathQuery, PathTrunkChunkQuery, SizedQuery,
+    LeafInfo, PathBranchChunkQuery, PathQuery, PathTrunkChunkQuery, QueryCursor, SizedQuery,
 };
 #[cfg(feature = "minimal")]
+pub use read_only::ReadOnlyGroveDb;
+#[cfg(feature = "minimal")]
 use reference_path::path_from_reference_path_type;
+#[cfg(feature = "minimal")]
+use secondary_index::IndexRegistry;
+#[cfg(feature = "minimal")]
+pub use secondary_index::SecondaryIndex;
+#[cfg(feature = "minimal")]
+pub use subtree_registry::RegisteredSubtree;
 #[cfg(feature = "grovedbg")]
 use tokio::net::ToSocketAddrs;
 #[cfg(feature = "minimal")]
@@ -265,6 +293,10 @@ type Hash = [u8; 32];
 
 /// GroveDb is a hierarchical authenticated data structure database.
 ///
+/// Data is kept either in RocksDB ([`GroveDb::open`]) or in memory
+/// ([`GroveDb::open_in_memory`]); both backends produce the same root hashes
+/// and operation costs.
+///
 /// # Concurrency and Transaction Safety
 ///
 /// `GroveDb` is `Send + Sync` because the underlying RocksDB
@@ -287,7 +319,11 @@ type Hash = [u8; 32];
 /// sequential.
 pub struct GroveDb {
     #[cfg(feature = "minimal")]
-    db: RocksDbStorage,
+    db: StorageBackend,
+    #[cfg(feature = "minimal")]
+    change_log: ChangeLog,
+    #[cfg(feature = "minimal")]
+    indexes: IndexRegistry,
 }
 
 #[cfg(feature = "minimal")]
@@ -295,7 +331,7 @@ pub(crate) type SubtreePrefix = [u8; blake3::OUT_LEN];
 
 /// Transaction
 #[cfg(feature = "minimal")]
-pub type Transaction<'db> = <RocksDbStorage as Storage<'db>>::Transaction;
+pub type Transaction<'db> = <StorageBackend as Storage<'db>>::Transaction;
 /// TransactionArg
 #[cfg(feature = "minimal")]
 pub type TransactionArg<'db, 'a> = Option<&'a Transaction<'db>>;
@@ -309,12 +345,12 @@ type VerificationIssues = HashMap<Vec<Vec<u8>>, (CryptoHash, CryptoHash, CryptoH
 
 /// Type alias for the return type of the `open_merk_for_replication` function.
 /// It represents a tuple containing:
-/// - A `Merk` instance with a prefixed RocksDB immediate storage context.
+/// - A `Merk` instance with a prefixed immediate storage context.
 /// - An optional `root_key`, represented as a vector of bytes.
 /// - A boolean indicating whether the Merk is a sum tree.
 #[cfg(feature = "minimal")]
 type OpenedMerkForReplication<'tx> = (
-    Merk<PrefixedRocksDbImmediateStorageContext<'tx>>,
+    Merk<PrefixedBackendImmediateStorageContext<'tx>>,
     Option<Vec<u8>>,
     TreeType,
 );
@@ -324,7 +360,49 @@ impl GroveDb {
     /// Opens a given path
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let db = RocksDbStorage::default_rocksdb_with_path(path)?;
-        Ok(GroveDb { db })
+        let grove_db = GroveDb {
+            db: db.into(),
+            change_log: ChangeLog::default(),
+            indexes: IndexRegistry::default(),
+        };
+        grove_db.init_subtree_registry()?;
+        Ok(grove_db)
+    }
+
+    /// Opens a given path tuning RocksDB with `options`.
+    pub fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        options: GroveDbOptions,
+    ) -> Result<Self, Error> {
+        let db = RocksDbStorage::rocksdb_with_path_and_options(path, &options.storage)?;
+        let grove_db = GroveDb {
+            db: db.into(),
+            change_log: ChangeLog::default(),
+            indexes: IndexRegistry::default(),
+        };
+        grove_db.init_subtree_registry()?;
+        Ok(grove_db)
+    }
+
+    /// Opens an empty GroveDb kept entirely in memory.
+    ///
+    /// Nothing is persisted and checkpoints are unavailable, but prefixes,
+    /// costs and root hashes are identical to a RocksDB-backed instance.
+    pub fn open_in_memory() -> Self {
+        let grove_db = GroveDb {
+            db: MemoryStorage::new().into(),
+            change_log: ChangeLog::default(),
+            indexes: IndexRegistry::default(),
+        };
+        grove_db
+            .init_subtree_registry()
+            .expect("writes to in-memory storage cannot fail");
+        grove_db
+    }
+
+    /// Returns `true` if this instance is backed by in-memory storage.
+    pub fn is_in_memory(&self) -> bool {
+        self.db.is_in_memory()
     }
 
     /// Starts a visualizer server for the GroveDB instance.
@@ -340,7 +418,7 @@ impl GroveDb {
     /// Uses raw iter to delete GroveDB key values pairs from rocksdb
     pub fn wipe(&self) -> Result<(), Error> {
         self.db.wipe()?;
-        Ok(())
+        self.init_subtree_registry()
     }
 
     /// Opens the transactional Merk at the given path. Returns CostResult.
@@ -350,7 +428,7 @@ impl GroveDb {
         tx: &'db Transaction,
         batch: Option<&'db StorageBatch>,
         grove_version: &GroveVersion,
-    ) -> CostResult<Merk<PrefixedRocksDbTransactionContext<'db>>, Error>
+    ) -> CostResult<Merk<PrefixedBackendTransactionContext<'db>>, Error>
     where
         B: AsRef<[u8]> + 'b,
     {
@@ -390,7 +468,7 @@ impl GroveDb {
         tx: &'db Transaction,
         batch: Option<&'db StorageBatch>,
         grove_version: &GroveVersion,
-    ) -> CostResult<Merk<PrefixedRocksDbTransactionContext<'db>>, Error> {
+    ) -> CostResult<Merk<PrefixedBackendTransactionContext<'db>>, Error> {
         let mut cost = OperationCost::default();
         let storage = self
             .db
@@ -535,7 +613,7 @@ impl GroveDb {
         &'db self,
         tx: &'tx Transaction<'db>,
         grove_version: &GroveVersion,
-    ) -> CostResult<Merk<PrefixedRocksDbTransactionContext<'tx>>, Error> {
+    ) -> CostResult<Merk<PrefixedBackendTransactionContext<'tx>>, Error> {
         self.db
             .get_transactional_storage_context(SubtreePath::empty(), None, tx)
             .flat_map(|storage_ctx| {
@@ -553,117 +631,6 @@ impl GroveDb {
             })
     }
 
-    /// Method to propagate updated subtree key changes one level up inside a
-    /// transaction
-    fn propagate_changes_with_batch_transaction<'b, B: AsRef<[u8]>>(
-        &self,
-        storage_batch: &StorageBatch,
-        mut merk_cache: HashMap<SubtreePath<'b, B>, Merk<PrefixedRocksDbTransactionContext>>,
-        path: &SubtreePath<'b, B>,
-        transaction: &Transaction,
-        grove_version: &GroveVersion,
-    ) -> CostResult<(), Error> {
-        let mut cost = OperationCost::default();
-
-        let mut child_tree = cost_return_on_error_no_add!(
-            cost,
-            merk_cache.remove(path).ok_or(Error::CorruptedCodeExecution(
-                "Merk Cache should always contain the last path",
-            ))
-        );
-
-        let mut current_path = path.clone();
-
-        while let Some((parent_path, parent_key)) = current_path.derive_parent() {
-            let mut parent_tree = cost_return_on_error!(
-                &mut cost,
-                self.open_batch_transactional_merk_at_path(
-                    storage_batch,
-                    parent_path.clone(),
-                    transaction,
-                    false,
-                    grove_version,
-                )
-            );
-            let (root_hash, root_key, aggregate_data) = cost_return_on_error!(
-                &mut cost,
-                child_tree
-                    .root_hash_key_and_aggregate_data()
-                    .map_err(Error::MerkError)
-            );
-            cost_return_on_error!(
-                &mut cost,
-                Self::update_tree_item_preserve_flag(
-                    &mut parent_tree,
-                    parent_key,
-                    root_key,
-                    root_hash,
-                    aggregate_data,
-                    grove_version,
-                )
-            );
-            child_tree = parent_tree;
-            current_path = parent_path;
-        }
-        Ok(()).wrap_with_cost(cost)
-    }
-
-    /// Method to propagate updated subtree key changes one level up inside a
-    /// transaction
-    fn propagate_changes_with_transaction<'b, B: AsRef<[u8]>>(
-        &self,
-        mut merk_cache: HashMap<SubtreePath<'b, B>, Merk<PrefixedRocksDbTransactionContext>>,
-        path: SubtreePath<'b, B>,
-        transaction: &Transaction,
-        batch: &StorageBatch,
-        grove_version: &GroveVersion,
-    ) -> CostResult<(), Error> {
-        let mut cost = OperationCost::default();
-
-        let mut child_tree = cost_return_on_error_no_add!(
-            cost,
-            merk_cache
-                .remove(&path)
-                .ok_or(Error::CorruptedCodeExecution(
-                    "Merk Cache should always contain the last path",
-                ))
-        );
-
-        let mut current_path = path.clone();
-
-        while let Some((parent_path, parent_key)) = current_path.derive_parent() {
-            let mut parent_tree: Merk<PrefixedRocksDbTransactionContext> = cost_return_on_error!(
-                &mut cost,
-                self.open_transactional_merk_at_path(
-                    parent_path.clone(),
-                    transaction,
-                    Some(batch),
-                    grove_version
-                )
-            );
-            let (root_hash, root_key, aggregate_data) = cost_return_on_error!(
-                &mut cost,
-                child_tree
-                    .root_hash_key_and_aggregate_data()
-                    .map_err(Error::MerkError)
-            );
-            cost_return_on_error!(
-                &mut cost,
-                Self::update_tree_item_preserve_flag(
-                    &mut parent_tree,
-                    parent_key,
-                    root_key,
-                    root_hash,
-                    aggregate_data,
-                    grove_version,
-                )
-            );
-            child_tree = parent_tree;
-            current_path = parent_path;
-        }
-        Ok(()).wrap_with_cost(cost)
-    }
-
     /// Updates a tree item and preserves flags. Returns CostResult.
     pub(crate) fn update_tree_item_preserve_flag<'db, K: AsRef<[u8]>, S: StorageContext<'db>>(
         parent_tree: &mut Merk<S>,
@@ -774,7 +741,8 @@ impl GroveDb {
             .flatten()
     }
 
-    /// Flush memory table to disk.
+    /// Flush memory table to disk, along with the buffered WAL writes if
+    /// [`GroveDbOptions::manual_wal_flush`] is on.
     pub fn flush(&self) -> Result<(), Error> {
         Ok(self.db.flush()?)
     }
@@ -872,7 +840,17 @@ impl GroveDb {
     /// For more details on the transaction usage, please check
     /// [`GroveDb::start_transaction`].
     pub fn commit_transaction(&self, transaction: Transaction) -> CostResult<(), Error> {
-        self.db.commit_transaction(transaction).map_err(Into::into)
+        let transaction_id = transaction.id();
+        self.db
+            .commit_transaction(transaction)
+            .map_err(Into::into)
+            .map(|result| {
+                match result {
+                    Ok(()) => self.change_log.publish_pending(transaction_id),
+                    Err(_) => self.change_log.discard_pending(transaction_id),
+                }
+                result
+            })
     }
 
     /// Rolls back a previously started transaction to its initial state.
@@ -884,6 +862,7 @@ impl GroveDb {
     /// For more details on the transaction usage, please check
     /// [`GroveDb::start_transaction`].
     pub fn rollback_transaction(&self, transaction: &Transaction) -> Result<(), Error> {
+        self.change_log.discard_pending(transaction.id());
         Ok(self.db.rollback_transaction(transaction)?)
     }
 
@@ -968,7 +947,10 @@ impl GroveDb {
                 | Element::CommitmentTree(..)
                 | Element::MmrTree(..)
                 | Element::BulkAppendTree(..)
-                | Element::DenseAppendOnlyFixedSizeTree(..) => {
+                | Element::DenseAppendOnlyFixedSizeTree(..)
+                | Element::MinMaxTree(..)
+                | Element::ProvableSumTree(..)
+                | Element::ProvableBigSumTree(..) => {
                     let (kv_value, element_value_hash) = merk
                         .get_value_and_value_hash(
                             &key,
@@ -1033,7 +1015,11 @@ impl GroveDb {
                         )?);
                     }
                 }
-                Element::Item(..) | Element::SumItem(..) | Element::ItemWithSumItem(..) => {
+                Element::Item(..)
+                | Element::SumItem(..)
+                | Element::ItemWithSumItem(..)
+                | Element::MinMaxItem(..)
+                | Element::ItemWithExpiry(..) => {
                     let (kv_value, element_value_hash) = merk
                         .get_value_and_value_hash(
                             &key,
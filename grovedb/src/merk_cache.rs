@@ -3,44 +3,79 @@
 use std::{
     cell::{Cell, UnsafeCell},
     collections::{btree_map::Entry, BTreeMap},
+    ops::{Deref, DerefMut},
 };
 
 use grovedb_costs::{cost_return_on_error, CostResult, CostsExt};
-use grovedb_merk::Merk;
+use grovedb_merk::{CryptoHash, Merk};
 use grovedb_path::SubtreePathBuilder;
-use grovedb_storage::{rocksdb_storage::PrefixedRocksDbTransactionContext, StorageBatch};
+use grovedb_storage::{backend::PrefixedBackendTransactionContext, StorageBatch};
 use grovedb_version::version::GroveVersion;
 
 use crate::{Error, GroveDb, Transaction};
 
-type TxMerk<'db> = Merk<PrefixedRocksDbTransactionContext<'db>>;
+type TxMerk<'db> = Merk<PrefixedBackendTransactionContext<'db>>;
+
+/// A cached Merk with its borrow flag and the root hash it had when opened.
+struct CachedMerk<'db> {
+    taken: Cell<bool>,
+    opened_root_hash: CryptoHash,
+    merk: TxMerk<'db>,
+}
 
 /// We store Merk on heap to preserve its location as well as borrow flag
 /// alongside.
-type CachedMerkEntry<'db> = Box<(Cell<bool>, TxMerk<'db>)>;
+type CachedMerkEntry<'db> = Box<CachedMerk<'db>>;
 
 /// Structure to keep subtrees open in memory for repeated access.
+///
+/// Every Merk is opened at most once over `batch`, and changes to subtrees
+/// are propagated up to the root once, by [`MerkCache::flush`], for each
+/// subtree whose root hash changed.
+///
+/// A cache made with [`MerkCache::new_for_batch`] backs the Merks of
+/// `apply_batch`, which propagates root hashes level by level itself, so it
+/// is dropped rather than flushed.
 pub(crate) struct MerkCache<'db, 'b, B: AsRef<[u8]>> {
     db: &'db GroveDb,
     pub(crate) version: &'db GroveVersion,
-    batch: Box<StorageBatch>,
+    batch: &'db StorageBatch,
     tx: &'db Transaction<'db>,
+    for_batch: bool,
     merks: UnsafeCell<BTreeMap<SubtreePathBuilder<'b, B>, CachedMerkEntry<'db>>>,
 }
 
 impl<'db, 'b, B: AsRef<[u8]>> MerkCache<'db, 'b, B> {
-    /// Initialize a new `MerkCache` instance
+    /// Initialize a new `MerkCache` instance writing to `batch`
     pub(crate) fn new(
         db: &'db GroveDb,
         tx: &'db Transaction<'db>,
+        batch: &'db StorageBatch,
         version: &'db GroveVersion,
     ) -> Self {
         MerkCache {
             db,
             tx,
             version,
+            for_batch: false,
             merks: Default::default(),
-            batch: Default::default(),
+            batch,
+        }
+    }
+
+    /// Initialize a new `MerkCache` instance writing to `batch` for
+    /// `apply_batch`, opening Merks with
+    /// [`GroveDb::open_batch_transactional_merk_at_path`] so errors are the
+    /// ones of batches.
+    pub(crate) fn new_for_batch(
+        db: &'db GroveDb,
+        tx: &'db Transaction<'db>,
+        batch: &'db StorageBatch,
+        version: &'db GroveVersion,
+    ) -> Self {
+        MerkCache {
+            for_batch: true,
+            ..Self::new(db, tx, batch, version)
         }
     }
 
@@ -48,6 +83,46 @@ impl<'db, 'b, B: AsRef<[u8]>> MerkCache<'db, 'b, B> {
     pub(crate) fn get_merk<'c>(
         &'c self,
         path: SubtreePathBuilder<'b, B>,
+    ) -> CostResult<MerkHandle<'db, 'c>, Error> {
+        self.get_or_open_merk(path, false)
+    }
+
+    /// Gets a smart pointer to a cached Merk or to a new empty one, for a
+    /// subtree a batch inserts.
+    pub(crate) fn get_new_merk<'c>(
+        &'c self,
+        path: SubtreePathBuilder<'b, B>,
+    ) -> CostResult<MerkHandle<'db, 'c>, Error> {
+        self.get_or_open_merk(path, true)
+    }
+
+    fn open_merk(
+        &self,
+        path: &SubtreePathBuilder<'b, B>,
+        new_merk: bool,
+    ) -> CostResult<TxMerk<'db>, Error> {
+        if self.for_batch || new_merk {
+            self.db.open_batch_transactional_merk_at_path(
+                self.batch,
+                path.into(),
+                self.tx,
+                new_merk,
+                self.version,
+            )
+        } else {
+            self.db.open_transactional_merk_at_path(
+                path.into(),
+                self.tx,
+                Some(self.batch),
+                self.version,
+            )
+        }
+    }
+
+    fn get_or_open_merk<'c>(
+        &'c self,
+        path: SubtreePathBuilder<'b, B>,
+        new_merk: bool,
     ) -> CostResult<MerkHandle<'db, 'c>, Error> {
         let mut cost = Default::default();
 
@@ -56,7 +131,7 @@ impl<'db, 'b, B: AsRef<[u8]>> MerkCache<'db, 'b, B> {
         // outside of the scope of this function, this map (`merks`) has
         // indirect connection to them through `Box`, thus there are no overlapping
         // references, and that is requirement of `UnsafeCell` we have there.
-        let boxed_flag_merk = match unsafe {
+        let cached_merk = match unsafe {
             self.merks
                 .get()
                 .as_mut()
@@ -65,30 +140,21 @@ impl<'db, 'b, B: AsRef<[u8]>> MerkCache<'db, 'b, B> {
         .entry(path)
         {
             Entry::Vacant(e) => {
-                let merk = cost_return_on_error!(
-                    &mut cost,
-                    self.db.open_transactional_merk_at_path(
-                        e.key().into(),
-                        self.tx,
-                        // SAFETY: batch is allocated on the heap and we use only shared
-                        // references, so as long as the `Box` allocation
-                        // outlives those references we're safe,
-                        // and it will outlive because Merks are dropped first.
-                        Some(unsafe {
-                            (&*self.batch as *const StorageBatch)
-                                .as_ref()
-                                .expect("`Box` is never null")
-                        }),
-                        self.version
-                    )
-                );
-                e.insert(Box::new((false.into(), merk)))
+                let merk = cost_return_on_error!(&mut cost, self.open_merk(e.key(), new_merk));
+                // Telling if the subtree changed is bookkeeping of the cache,
+                // it isn't part of the costs of the operation using it.
+                let opened_root_hash = merk.root_hash().unwrap();
+                e.insert(Box::new(CachedMerk {
+                    taken: false.into(),
+                    opened_root_hash,
+                    merk,
+                }))
             }
             Entry::Occupied(e) => e.into_mut(),
         };
 
-        let taken_handle_ref: *const Cell<bool> = &boxed_flag_merk.0 as *const _;
-        let merk_ptr: *mut TxMerk<'db> = &mut boxed_flag_merk.1 as *mut _;
+        let taken_handle_ref: *const Cell<bool> = &cached_merk.taken as *const _;
+        let merk_ptr: *mut TxMerk<'db> = &mut cached_merk.merk as *mut _;
 
         // SAFETY: `MerkHandle` contains two references to the heap allocated memory,
         // and we want to be sure that the referenced data will outlive those
@@ -124,25 +190,27 @@ impl<'db, 'b, B: AsRef<[u8]>> MerkCache<'db, 'b, B> {
         .wrap_with_cost(cost)
     }
 
-    /// Consumes `MerkCache` into accumulated batch of uncommitted operations
-    /// with subtrees' root hash  propagation done.
-    pub(crate) fn into_batch(mut self) -> CostResult<Box<StorageBatch>, Error> {
-        let mut cost = Default::default();
-        cost_return_on_error!(&mut cost, self.propagate_subtrees());
-
-        // SAFETY: By this time all subtrees are taken and dropped during
-        // propagation, so there are no more references to the batch and in can be
-        // safely released into the world.
-        Ok(self.batch).wrap_with_cost(cost)
+    /// Consumes `MerkCache`, propagating the changes of its subtrees up to
+    /// the root. All writes end up in the batch the cache was made with.
+    pub(crate) fn flush(mut self) -> CostResult<(), Error> {
+        self.propagate_subtrees()
     }
 
     fn propagate_subtrees(&mut self) -> CostResult<(), Error> {
         let mut cost = Default::default();
 
         // This relies on [SubtreePath]'s ordering implementation to put the deepest
-        // path's first.
-        while let Some((path, flag_and_merk)) = self.merks.get_mut().pop_first() {
-            let merk = flag_and_merk.1;
+        // path's first, so a subtree is done with before its parent is, and each
+        // changed parent is propagated once after all of its children.
+        while let Some((path, cached_merk)) = self.merks.get_mut().pop_first() {
+            let CachedMerk {
+                opened_root_hash,
+                merk,
+                ..
+            } = *cached_merk;
+            if merk.root_hash().unwrap() == opened_root_hash {
+                continue;
+            }
             if let Some((parent_path, parent_key)) = path.derive_parent_owned() {
                 let mut parent_merk = cost_return_on_error!(&mut cost, self.get_merk(parent_path));
 
@@ -176,7 +244,22 @@ pub(crate) struct MerkHandle<'db, 'c> {
     taken_handle: &'c Cell<bool>,
 }
 
-impl<'db> MerkHandle<'db, '_> {
+impl<'db, 'c> MerkHandle<'db, 'c> {
+    /// Borrows the Merk until the returned guard is dropped, for users
+    /// holding on to it across many operations.
+    pub(crate) fn take(self) -> TakenMerk<'db, 'c> {
+        if self.taken_handle.get() {
+            panic!("Attempt to have double &mut borrow on Merk");
+        }
+
+        self.taken_handle.set(true);
+
+        TakenMerk {
+            merk: self.merk,
+            taken_handle: self.taken_handle,
+        }
+    }
+
     pub(crate) fn for_merk<T>(&mut self, f: impl FnOnce(&mut TxMerk<'db>) -> T) -> T {
         if self.taken_handle.get() {
             panic!("Attempt to have double &mut borrow on Merk");
@@ -197,11 +280,44 @@ impl<'db> MerkHandle<'db, '_> {
     }
 }
 
+/// Merk borrowed from a [`MerkCache`] until dropped, see
+/// [`MerkHandle::take`].
+pub(crate) struct TakenMerk<'db, 'c> {
+    merk: *mut TxMerk<'db>,
+    taken_handle: &'c Cell<bool>,
+}
+
+impl<'db> Deref for TakenMerk<'db, '_> {
+    type Target = TxMerk<'db>;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: same as for `MerkHandle::for_merk`, the borrow flag stays
+        // set for as long as the guard lives.
+        unsafe { self.merk.as_ref().expect("`Box` contents are never null") }
+    }
+}
+
+impl DerefMut for TakenMerk<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: same as for `MerkHandle::for_merk`, the borrow flag stays
+        // set for as long as the guard lives.
+        unsafe { self.merk.as_mut().expect("`Box` contents are never null") }
+    }
+}
+
+impl Drop for TakenMerk<'_, '_> {
+    fn drop(&mut self) {
+        self.taken_handle.set(false);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use grovedb_merk::element::insert::ElementInsertToStorageExtensions;
+    use grovedb_merk::element::{
+        get::ElementFetchFromStorageExtensions, insert::ElementInsertToStorageExtensions,
+    };
     use grovedb_path::SubtreePath;
-    use grovedb_storage::StorageBatch;
+    use grovedb_storage::{Storage, StorageBatch};
     use grovedb_version::version::GroveVersion;
 
     use super::MerkCache;
@@ -216,8 +332,9 @@ mod tests {
         let version = GroveVersion::latest();
         let db = make_test_grovedb(version);
         let tx = db.start_transaction();
+        let batch = StorageBatch::new();
 
-        let cache = MerkCache::new(&db, &tx, version);
+        let cache = MerkCache::new(&db, &tx, &batch, version);
 
         let mut merk1 = cache
             .get_merk(SubtreePath::empty().derive_owned())
@@ -235,6 +352,55 @@ mod tests {
         });
     }
 
+    #[test]
+    fn taken_merk_is_released_on_drop() {
+        let version = GroveVersion::latest();
+        let db = make_test_grovedb(version);
+        let tx = db.start_transaction();
+        let batch = StorageBatch::new();
+
+        let cache = MerkCache::new_for_batch(&db, &tx, &batch, version);
+
+        let taken = cache
+            .get_merk(SubtreePath::empty().derive_owned())
+            .unwrap()
+            .unwrap()
+            .take();
+        assert!(taken.root_key().is_some());
+        drop(taken);
+
+        let mut merk = cache
+            .get_merk(SubtreePath::empty().derive_owned())
+            .unwrap()
+            .unwrap();
+        assert!(merk.for_merk(|m| m.root_key().is_some()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn cant_use_a_taken_merk() {
+        let version = GroveVersion::latest();
+        let db = make_test_grovedb(version);
+        let tx = db.start_transaction();
+        let batch = StorageBatch::new();
+
+        let cache = MerkCache::new_for_batch(&db, &tx, &batch, version);
+
+        let _taken = cache
+            .get_merk(SubtreePath::empty().derive_owned())
+            .unwrap()
+            .unwrap()
+            .take();
+        let mut merk = cache
+            .get_merk(SubtreePath::empty().derive_owned())
+            .unwrap()
+            .unwrap();
+
+        merk.for_merk(|_m| {
+            // this shouldn't happen
+        });
+    }
+
     #[test]
     fn subtrees_are_propagated() {
         let version = GroveVersion::latest();
@@ -259,7 +425,8 @@ mod tests {
             batch.len()
         };
 
-        let cache = MerkCache::new(&db, &tx, version);
+        let batch = StorageBatch::new();
+        let cache = MerkCache::new(&db, &tx, &batch, version);
 
         let mut merk = cache.get_merk(path.derive_owned()).unwrap().unwrap();
 
@@ -267,6 +434,77 @@ mod tests {
 
         drop(merk);
 
-        assert!(cache.into_batch().unwrap().unwrap().len() > no_propagation_ops_count);
+        cache.flush().unwrap().unwrap();
+        assert!(batch.len() > no_propagation_ops_count);
+    }
+
+    #[test]
+    fn unchanged_subtrees_are_not_propagated() {
+        let version = GroveVersion::latest();
+        let db = make_deep_tree(version);
+        let tx = db.start_transaction();
+        let batch = StorageBatch::new();
+        let cache = MerkCache::new(&db, &tx, &batch, version);
+
+        for (path, key) in [
+            (SubtreePath::from(&[TEST_LEAF, b"innertree"]), b"key1"),
+            (SubtreePath::from(&[TEST_LEAF, b"innertree4"]), b"key4"),
+        ] {
+            let mut merk = cache.get_merk(path.derive_owned()).unwrap().unwrap();
+            merk.for_merk(|m| {
+                Element::get(m, key, true, version)
+                    .unwrap()
+                    .expect("expected an item")
+            });
+        }
+
+        cache.flush().unwrap().unwrap();
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn shared_ancestors_are_propagated_once() {
+        let version = GroveVersion::latest();
+        let db = make_deep_tree(version);
+        let tx = db.start_transaction();
+        let item = Element::new_item(b"hello".to_vec());
+        let paths = [
+            SubtreePath::from(&[TEST_LEAF, b"innertree"]),
+            SubtreePath::from(&[TEST_LEAF, b"innertree4"]),
+        ];
+
+        let insert_and_flush = |paths: &[SubtreePath<&[u8]>]| {
+            let batch = StorageBatch::new();
+            let cache = MerkCache::new(&db, &tx, &batch, version);
+            for path in paths {
+                let mut merk = cache.get_merk(path.derive_owned()).unwrap().unwrap();
+                merk.for_merk(|m| item.insert(m, b"k1", None, version).unwrap().unwrap());
+            }
+            let flush_cost = cache.flush().cost;
+            (batch, flush_cost)
+        };
+
+        let separate_seeks: u32 = paths
+            .iter()
+            .map(|path| insert_and_flush(std::slice::from_ref(path)).1.seek_count)
+            .sum();
+        let (batch, flush_cost) = insert_and_flush(&paths);
+        assert!(flush_cost.seek_count < separate_seeks);
+
+        db.db
+            .commit_multi_context_batch(batch, Some(&tx))
+            .unwrap()
+            .unwrap();
+        let other_db = make_deep_tree(version);
+        for path in paths {
+            other_db
+                .insert(path, b"k1", item.clone(), None, None, version)
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(
+            db.root_hash(Some(&tx), version).unwrap().unwrap(),
+            other_db.root_hash(None, version).unwrap().unwrap()
+        );
     }
 }
@@ -18,6 +18,7 @@ use grovedb_version::version::GroveVersion;
 
 use crate::{
     batch::{GroveOp, QualifiedGroveDbOp},
+    merk_cache::MerkCache,
     util::TxRef,
     Element, Error, GroveDb, Transaction, TransactionArg,
 };
@@ -112,44 +113,27 @@ impl GroveDb {
 
         // 4. Update element in parent Merk
         let batch = StorageBatch::new();
-        let mut parent_merk = cost_return_on_error!(
-            &mut cost,
-            self.open_transactional_merk_at_path(
-                path.clone(),
-                tx.as_ref(),
-                Some(&batch),
-                grove_version,
-            )
-        );
+        let merk_cache = MerkCache::new(self, tx.as_ref(), &batch, grove_version);
+        let mut parent_merk =
+            cost_return_on_error!(&mut cost, merk_cache.get_merk(path.derive_owned()));
 
         let updated_element =
             Element::new_bulk_append_tree(new_total_count, chunk_power, existing_flags);
 
         cost_return_on_error_into!(
             &mut cost,
-            updated_element.insert_subtree(
-                &mut parent_merk,
+            parent_merk.for_merk(|merk| updated_element.insert_subtree(
+                merk,
                 key,
                 new_state_root,
                 None,
                 grove_version,
-            )
+            ))
         );
 
         // 5. Propagate changes
-        let mut merk_cache = HashMap::new();
-        merk_cache.insert(path.clone(), parent_merk);
-
-        cost_return_on_error!(
-            &mut cost,
-            self.propagate_changes_with_transaction(
-                merk_cache,
-                path,
-                tx.as_ref(),
-                &batch,
-                grove_version,
-            )
-        );
+        drop(parent_merk);
+        cost_return_on_error!(&mut cost, merk_cache.flush());
 
         // 6. Commit
         cost_return_on_error!(
@@ -20,7 +20,7 @@ mod delete_up_tree;
 mod worst_case;
 
 #[cfg(feature = "minimal")]
-use std::collections::{BTreeSet, HashMap};
+use std::collections::BTreeSet;
 
 #[cfg(feature = "minimal")]
 pub use delete_up_tree::DeleteUpTreeOptions;
@@ -32,24 +32,24 @@ use grovedb_costs::{
     CostResult, CostsExt, OperationCost,
 };
 use grovedb_merk::element::{
-    costs::ElementCostExtensions, decode::ElementDecodeExtensions,
-    delete::ElementDeleteFromStorageExtensions, tree_type::ElementTreeTypeExtensions,
+    decode::ElementDecodeExtensions, delete::ElementDeleteFromStorageExtensions,
+    tree_type::ElementTreeTypeExtensions,
 };
 #[cfg(feature = "minimal")]
 use grovedb_merk::{proofs::Query, KVIterator, MaybeTree};
 #[cfg(feature = "minimal")]
-use grovedb_merk::{Error as MerkError, Merk, MerkOptions};
+use grovedb_merk::{Error as MerkError, MerkOptions};
 use grovedb_path::SubtreePath;
 #[cfg(feature = "minimal")]
-use grovedb_storage::{
-    rocksdb_storage::PrefixedRocksDbTransactionContext, Storage, StorageBatch, StorageContext,
-};
+use grovedb_storage::{Storage, StorageBatch, StorageContext};
 use grovedb_version::{check_grovedb_v0_with_cost, version::GroveVersion};
 
 use crate::util::{compat, TxRef};
 #[cfg(feature = "minimal")]
 use crate::{
     batch::{GroveOp, QualifiedGroveDbOp, SubelementsDeletionBehavior},
+    merk_cache::MerkCache,
+    subtree_registry::SubtreeRegistryUpdate,
     Element, ElementFlags, Error, GroveDb, Transaction, TransactionArg,
 };
 
@@ -144,15 +144,23 @@ impl GroveDb {
 
         let tx = TxRef::new(&self.db, transaction);
 
+        let path: SubtreePath<B> = path.into();
+        let changes = self.capture_changes(
+            || [(path.to_vec(), Some(key.to_vec()))],
+            tx.as_ref(),
+            grove_version,
+        );
+
         let options = options.unwrap_or_default();
         let batch = StorageBatch::new();
+        let mut registry_update = SubtreeRegistryUpdate::default();
 
         let mut cost = Default::default();
 
         cost_return_on_error!(
             &mut cost,
             self.delete_internal_on_transaction(
-                path.into(),
+                path,
                 key,
                 &options,
                 tx.as_ref(),
@@ -163,6 +171,7 @@ impl GroveDb {
                     ))
                 },
                 &batch,
+                &mut registry_update,
                 grove_version,
             )
             .map_ok(|_| ())
@@ -174,8 +183,13 @@ impl GroveDb {
                 .commit_multi_context_batch(batch, Some(tx.as_ref()))
                 .map_err(Into::into)
         );
+        cost_return_on_error!(
+            &mut cost,
+            self.apply_subtree_registry_update(registry_update, tx.as_ref(), grove_version)
+        );
 
-        tx.commit_local().wrap_with_cost(cost)
+        self.commit_local_with_changes(tx, changes, grove_version)
+            .wrap_with_cost(cost)
     }
 
     /// Delete all elements in a specified subtree.
@@ -234,20 +248,16 @@ impl GroveDb {
 
         let options = options.unwrap_or_default();
 
-        let mut merk_to_clear = cost_return_on_error!(
-            &mut cost,
-            self.open_transactional_merk_at_path(
-                subtree_path.clone(),
-                tx.as_ref(),
-                Some(&batch),
-                grove_version,
-            )
-        );
+        let merk_cache = MerkCache::new(self, tx.as_ref(), &batch, grove_version);
+        let mut merk_to_clear =
+            cost_return_on_error!(&mut cost, merk_cache.get_merk(subtree_path.derive_owned()));
 
         // Non-Merk data trees store data in the data namespace as non-Element
         // entries.  We cannot iterate them with Element::iterator, so just
         // clear the storage directly.
-        if merk_to_clear.tree_type.uses_non_merk_data_storage() {
+        if merk_to_clear.for_merk(|merk| merk.tree_type.uses_non_merk_data_storage()) {
+            drop(merk_to_clear);
+            drop(merk_cache);
             let mut storage = self
                 .db
                 .get_transactional_storage_context(subtree_path.clone(), Some(&batch), tx.as_ref())
@@ -275,67 +285,70 @@ impl GroveDb {
             let mut all_query = Query::new();
             all_query.insert_all();
 
-            let mut element_iterator =
-                KVIterator::new(merk_to_clear.storage.raw_iter(), &all_query).unwrap();
-
-            // delete all nested subtrees
-            while let Some((key, element_value)) =
-                element_iterator.next_kv().unwrap_add_cost(&mut cost)
-            {
-                let element = match Element::raw_decode(&element_value, grove_version) {
-                    Ok(e) => e,
-                    Err(e) => {
-                        return Err(Error::CorruptedData(format!(
-                            "unable to decode element while clearing subtree: {e}"
-                        )))
-                        .wrap_with_cost(cost);
+            let subtree_keys = cost_return_on_error!(
+                &mut cost,
+                merk_to_clear.for_merk(|merk| {
+                    let mut cost = OperationCost::default();
+                    let mut subtree_keys = Vec::new();
+                    let mut element_iterator =
+                        KVIterator::new(merk.storage.raw_iter(), &all_query).unwrap();
+                    while let Some((key, element_value)) =
+                        element_iterator.next_kv().unwrap_add_cost(&mut cost)
+                    {
+                        match Element::raw_decode(&element_value, grove_version) {
+                            Ok(element) if element.is_any_tree() => subtree_keys.push(key),
+                            Ok(_) => {}
+                            Err(e) => {
+                                return Err(Error::CorruptedData(format!(
+                                    "unable to decode element while clearing subtree: {e}"
+                                )))
+                                .wrap_with_cost(cost);
+                            }
+                        }
                     }
+                    Ok(subtree_keys).wrap_with_cost(cost)
+                })
+            );
+
+            if !subtree_keys.is_empty() && !options.allow_deleting_subtrees {
+                return if options.trying_to_clear_with_subtrees_returns_error {
+                    Err(Error::ClearingTreeWithSubtreesNotAllowed(
+                        "options do not allow to clear this merk tree as it contains subtrees",
+                    ))
+                    .wrap_with_cost(cost)
+                } else {
+                    Ok(false).wrap_with_cost(cost)
                 };
-                if element.is_any_tree() {
-                    if options.allow_deleting_subtrees {
-                        cost_return_on_error!(
-                            &mut cost,
-                            self.delete(
-                                subtree_path.clone(),
-                                key.as_slice(),
-                                Some(DeleteOptions {
-                                    allow_deleting_non_empty_trees: true,
-                                    deleting_non_empty_trees_returns_error: false,
-                                    ..Default::default()
-                                }),
-                                Some(tx.as_ref()),
-                                grove_version,
-                            )
-                        );
-                    } else if options.trying_to_clear_with_subtrees_returns_error {
-                        return Err(Error::ClearingTreeWithSubtreesNotAllowed(
-                            "options do not allow to clear this merk tree as it contains subtrees",
-                        ))
-                        .wrap_with_cost(cost);
-                    } else {
-                        return Ok(false).wrap_with_cost(cost);
-                    }
-                }
+            }
+
+            // delete all nested subtrees
+            for key in subtree_keys {
+                cost_return_on_error!(
+                    &mut cost,
+                    self.delete(
+                        subtree_path.clone(),
+                        key.as_slice(),
+                        Some(DeleteOptions {
+                            allow_deleting_non_empty_trees: true,
+                            deleting_non_empty_trees_returns_error: false,
+                            ..Default::default()
+                        }),
+                        Some(tx.as_ref()),
+                        grove_version,
+                    )
+                );
             }
         }
 
         // delete non subtree values
-        cost_return_on_error!(&mut cost, merk_to_clear.clear().map_err(Error::MerkError));
-
-        // propagate changes
-        let mut merk_cache: HashMap<SubtreePath<B>, Merk<PrefixedRocksDbTransactionContext>> =
-            HashMap::default();
-        merk_cache.insert(subtree_path.clone(), merk_to_clear);
         cost_return_on_error!(
             &mut cost,
-            self.propagate_changes_with_transaction(
-                merk_cache,
-                subtree_path.clone(),
-                tx.as_ref(),
-                &batch,
-                grove_version,
-            )
+            merk_to_clear.for_merk(|merk| merk.clear().map_err(Error::MerkError))
         );
+        drop(merk_to_clear);
+
+        // propagate changes
+        cost_return_on_error!(&mut cost, merk_cache.flush());
 
         cost_return_on_error!(
             &mut cost,
@@ -381,9 +394,15 @@ impl GroveDb {
         );
 
         let tx = TxRef::new(&self.db, transaction);
+        let changes = self.capture_changes(
+            || [(path.to_vec(), Some(key.to_vec()))],
+            tx.as_ref(),
+            grove_version,
+        );
 
         let options = options.unwrap_or_default();
         let batch = StorageBatch::new();
+        let mut registry_update = SubtreeRegistryUpdate::default();
 
         let mut cost = Default::default();
 
@@ -412,6 +431,7 @@ impl GroveDb {
                     }
                 },
                 &batch,
+                &mut registry_update,
                 grove_version,
             )
         );
@@ -422,8 +442,13 @@ impl GroveDb {
                 .commit_multi_context_batch(batch, Some(tx.as_ref()))
                 .map_err(Into::into)
         );
+        cost_return_on_error!(
+            &mut cost,
+            self.apply_subtree_registry_update(registry_update, tx.as_ref(), grove_version)
+        );
 
-        tx.commit_local().wrap_with_cost(cost)
+        self.commit_local_with_changes(tx, changes, grove_version)
+            .wrap_with_cost(cost)
     }
 
     /// Delete if an empty tree.
@@ -457,12 +482,20 @@ impl GroveDb {
         let mut cost = Default::default();
 
         let batch = StorageBatch::new();
+        let mut registry_update = SubtreeRegistryUpdate::default();
         let tx = TxRef::new(&self.db, transaction);
 
+        let path: SubtreePath<B> = path.into();
+        let changes = self.capture_changes(
+            || [(path.to_vec(), Some(key.to_vec()))],
+            tx.as_ref(),
+            grove_version,
+        );
+
         let result = cost_return_on_error!(
             &mut cost,
             self.delete_if_empty_tree_with_sectional_storage_function(
-                path.into(),
+                path,
                 key,
                 tx.as_ref(),
                 &mut |_, removed_key_bytes, removed_value_bytes| {
@@ -472,6 +505,7 @@ impl GroveDb {
                     ))
                 },
                 &batch,
+                &mut registry_update,
                 grove_version,
             )
         );
@@ -482,8 +516,14 @@ impl GroveDb {
                 .commit_multi_context_batch(batch, Some(tx.as_ref()))
                 .map_err(Into::into)
         );
+        cost_return_on_error!(
+            &mut cost,
+            self.apply_subtree_registry_update(registry_update, tx.as_ref(), grove_version)
+        );
 
-        tx.commit_local().map(|_| result).wrap_with_cost(cost)
+        self.commit_local_with_changes(tx, changes, grove_version)
+            .map(|_| result)
+            .wrap_with_cost(cost)
     }
 
     /// Delete if an empty tree with section storage function
@@ -501,6 +541,7 @@ impl GroveDb {
             Error,
         >,
         batch: &StorageBatch,
+        registry_update: &mut SubtreeRegistryUpdate,
         grove_version: &GroveVersion,
     ) -> CostResult<bool, Error> {
         check_grovedb_v0_with_cost!(
@@ -539,6 +580,7 @@ impl GroveDb {
                 }
             },
             batch,
+            registry_update,
             grove_version,
         )
     }
@@ -720,6 +762,7 @@ impl GroveDb {
             MerkError,
         >,
         batch: &StorageBatch,
+        registry_update: &mut SubtreeRegistryUpdate,
         grove_version: &GroveVersion,
     ) -> CostResult<bool, Error> {
         check_grovedb_v0_with_cost!(
@@ -737,17 +780,11 @@ impl GroveDb {
             &mut cost,
             self.get_raw(path.clone(), key.as_ref(), Some(transaction), grove_version)
         );
-        let mut subtree_to_delete_from = cost_return_on_error!(
-            &mut cost,
-            self.open_transactional_merk_at_path(
-                path.clone(),
-                transaction,
-                Some(batch),
-                grove_version
-            )
-        );
-        let uses_sum_tree = subtree_to_delete_from.tree_type;
-        if let Some(tree_type) = element.tree_type() {
+        let merk_cache = MerkCache::new(self, transaction, batch, grove_version);
+        let mut subtree_to_delete_from =
+            cost_return_on_error!(&mut cost, merk_cache.get_merk(path.derive_owned()));
+        let uses_sum_tree = subtree_to_delete_from.for_merk(|merk| merk.tree_type);
+        if element.tree_type().is_some() {
             let subtree_merk_path = path.derive_owned_with_child(key);
             let subtree_merk_path_ref = SubtreePath::from(&subtree_merk_path);
 
@@ -836,34 +873,17 @@ impl GroveDb {
                                 ))
                             })
                         );
+                        registry_update.remove(subtree_path);
                     }
                 }
-                // todo: verify why we need to open the same? merk again
-                let storage = self
-                    .db
-                    .get_transactional_storage_context(path.clone(), Some(batch), transaction)
-                    .unwrap_add_cost(&mut cost);
-
-                let mut merk_to_delete_tree_from = cost_return_on_error!(
-                    &mut cost,
-                    Merk::open_layered_with_root_key(
-                        storage,
-                        subtree_to_delete_from.root_key(),
-                        tree_type,
-                        Some(&Element::value_defined_cost_for_serialized_value),
-                        grove_version,
-                    )
-                    .map_err(|e| {
-                        Error::CorruptedData(format!(
-                            "cannot open a subtree with given root key: {e}"
-                        ))
-                    })
-                );
-                // We are deleting a tree, a tree uses 3 bytes
-                cost_return_on_error_into!(
-                    &mut cost,
+            }
+            registry_update.remove(subtree_merk_path_ref.to_vec());
+            // We are deleting a tree, a tree uses 3 bytes
+            cost_return_on_error_into!(
+                &mut cost,
+                subtree_to_delete_from.for_merk(|merk| {
                     Element::delete_with_sectioned_removal_bytes(
-                        &mut merk_to_delete_tree_from,
+                        merk,
                         key,
                         Some(options.as_merk_options()),
                         true,
@@ -871,79 +891,26 @@ impl GroveDb {
                         sectioned_removal,
                         grove_version,
                     )
-                );
-                let mut merk_cache: HashMap<
-                    SubtreePath<B>,
-                    Merk<PrefixedRocksDbTransactionContext>,
-                > = HashMap::default();
-                merk_cache.insert(path.clone(), merk_to_delete_tree_from);
-                cost_return_on_error!(
-                    &mut cost,
-                    self.propagate_changes_with_batch_transaction(
-                        batch,
-                        merk_cache,
-                        &path,
-                        transaction,
-                        grove_version,
-                    )
-                );
-            } else {
-                // We are deleting a tree, a tree uses 3 bytes
-                cost_return_on_error_into!(
-                    &mut cost,
+                })
+            );
+        } else {
+            cost_return_on_error_into!(
+                &mut cost,
+                subtree_to_delete_from.for_merk(|merk| {
                     Element::delete_with_sectioned_removal_bytes(
-                        &mut subtree_to_delete_from,
+                        merk,
                         key,
                         Some(options.as_merk_options()),
-                        true,
+                        false,
                         uses_sum_tree,
                         sectioned_removal,
                         grove_version,
                     )
-                );
-                let mut merk_cache: HashMap<
-                    SubtreePath<B>,
-                    Merk<PrefixedRocksDbTransactionContext>,
-                > = HashMap::default();
-                merk_cache.insert(path.clone(), subtree_to_delete_from);
-                cost_return_on_error!(
-                    &mut cost,
-                    self.propagate_changes_with_transaction(
-                        merk_cache,
-                        path,
-                        transaction,
-                        batch,
-                        grove_version
-                    )
-                );
-            }
-        } else {
-            cost_return_on_error_into!(
-                &mut cost,
-                Element::delete_with_sectioned_removal_bytes(
-                    &mut subtree_to_delete_from,
-                    key,
-                    Some(options.as_merk_options()),
-                    false,
-                    uses_sum_tree,
-                    sectioned_removal,
-                    grove_version,
-                )
-            );
-            let mut merk_cache: HashMap<SubtreePath<B>, Merk<PrefixedRocksDbTransactionContext>> =
-                HashMap::default();
-            merk_cache.insert(path.clone(), subtree_to_delete_from);
-            cost_return_on_error!(
-                &mut cost,
-                self.propagate_changes_with_transaction(
-                    merk_cache,
-                    path,
-                    transaction,
-                    batch,
-                    grove_version
-                )
+                })
             );
         }
+        drop(subtree_to_delete_from);
+        cost_return_on_error!(&mut cost, merk_cache.flush());
 
         Ok(true).wrap_with_cost(cost)
     }
@@ -956,10 +923,12 @@ mod tests {
         storage_cost::{removal::StorageRemovedBytes::BasicStorageRemoval, StorageCost},
         OperationCost,
     };
+    use grovedb_merk::tree_type::TreeType;
     use grovedb_version::version::GroveVersion;
     use pretty_assertions::assert_eq;
 
     use crate::{
+        batch::{QualifiedGroveDbOp, SubelementsDeletionBehavior},
         operations::delete::{delete_up_tree::DeleteUpTreeOptions, ClearOptions, DeleteOptions},
         reference_path::ReferencePathType,
         tests::{
@@ -1932,6 +1901,76 @@ mod tests {
     /// Documents known behavior: deleting a referenced element leaves a
     /// dangling reference.  Following the dangling reference must return
     /// `CorruptedReferencePathKeyNotFound` (safe failure), never wrong data.
+    #[test]
+    fn test_delete_non_empty_sum_tree_matches_batch_delete() {
+        let grove_version = GroveVersion::latest();
+        let dbs = [
+            make_test_grovedb(grove_version),
+            make_test_grovedb(grove_version),
+        ];
+        for db in &dbs {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                b"sums",
+                Element::empty_sum_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a sum tree");
+            db.insert(
+                [TEST_LEAF, b"sums"].as_ref(),
+                b"a",
+                Element::new_sum_item(7),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a sum item");
+        }
+
+        dbs[0]
+            .delete(
+                [TEST_LEAF].as_ref(),
+                b"sums",
+                Some(DeleteOptions {
+                    allow_deleting_non_empty_trees: true,
+                    ..Default::default()
+                }),
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot delete a non empty sum tree");
+        dbs[1]
+            .apply_batch(
+                vec![QualifiedGroveDbOp::delete_tree_op(
+                    vec![TEST_LEAF.to_vec()],
+                    b"sums".to_vec(),
+                    TreeType::SumTree,
+                    SubelementsDeletionBehavior::DeleteChildren,
+                )],
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot delete the sum tree in a batch");
+
+        assert_eq!(
+            dbs[0].root_hash(None, grove_version).unwrap().unwrap(),
+            dbs[1].root_hash(None, grove_version).unwrap().unwrap()
+        );
+        assert!(matches!(
+            dbs[0]
+                .get([TEST_LEAF, b"sums"].as_ref(), b"a", None, grove_version)
+                .unwrap(),
+            Err(Error::PathParentLayerNotFound(_))
+        ));
+    }
+
     #[test]
     fn test_delete_referenced_element_leaves_dangling_reference() {
         let grove_version = GroveVersion::latest();
@@ -17,15 +17,14 @@ use grovedb_costs::{
 use grovedb_dense_fixed_sized_merkle_tree::{position_key, DenseFixedSizedMerkleTree};
 use grovedb_merk::element::insert::ElementInsertToStorageExtensions;
 use grovedb_path::SubtreePath;
-use grovedb_storage::{
-    rocksdb_storage::PrefixedRocksDbTransactionContext, Storage, StorageBatch, StorageContext,
-};
+use grovedb_storage::{Storage, StorageBatch, StorageContext};
 use grovedb_version::version::GroveVersion;
 
 use crate::{
     batch::{GroveOp, QualifiedGroveDbOp},
+    merk_cache::MerkCache,
     util::TxRef,
-    Element, Error, GroveDb, Merk, Transaction, TransactionArg,
+    Element, Error, GroveDb, Transaction, TransactionArg,
 };
 
 impl GroveDb {
@@ -109,44 +108,26 @@ impl GroveDb {
 
         // 4. Update element and propagate
         let batch = StorageBatch::new();
-        let mut parent_merk = cost_return_on_error!(
-            &mut cost,
-            self.open_transactional_merk_at_path(
-                path.clone(),
-                tx.as_ref(),
-                Some(&batch),
-                grove_version,
-            )
-        );
+        let merk_cache = MerkCache::new(self, tx.as_ref(), &batch, grove_version);
+        let mut parent_merk =
+            cost_return_on_error!(&mut cost, merk_cache.get_merk(path.derive_owned()));
 
         let updated_element = Element::new_dense_tree(new_count, height, existing_flags);
 
         cost_return_on_error_into!(
             &mut cost,
-            updated_element.insert_subtree(
-                &mut parent_merk,
+            parent_merk.for_merk(|merk| updated_element.insert_subtree(
+                merk,
                 key,
                 new_root_hash,
                 None,
                 grove_version,
-            )
+            ))
         );
 
         // 5. Propagate changes
-        let mut merk_cache: HashMap<SubtreePath<B>, Merk<PrefixedRocksDbTransactionContext>> =
-            HashMap::new();
-        merk_cache.insert(path.clone(), parent_merk);
-
-        cost_return_on_error!(
-            &mut cost,
-            self.propagate_changes_with_transaction(
-                merk_cache,
-                path,
-                tx.as_ref(),
-                &batch,
-                grove_version,
-            )
-        );
+        drop(parent_merk);
+        cost_return_on_error!(&mut cost, merk_cache.flush());
 
         // 6. Commit
         cost_return_on_error!(
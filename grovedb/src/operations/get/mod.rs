@@ -4,8 +4,10 @@
 mod average_case;
 
 mod query;
+mod rank;
 use grovedb_storage::Storage;
 pub use query::QueryItemOrSumReturnType;
+pub(crate) use query::StoredAggregateSource;
 #[cfg(feature = "estimated_costs")]
 mod worst_case;
 
@@ -392,6 +394,9 @@ impl GroveDb {
                 | Ok(Element::CountSumTree(..))
                 | Ok(Element::ProvableCountTree(..))
                 | Ok(Element::ProvableCountSumTree(..))
+                | Ok(Element::MinMaxTree(..))
+                | Ok(Element::ProvableSumTree(..))
+                | Ok(Element::ProvableBigSumTree(..))
                 | Ok(Element::CommitmentTree(..))
                 | Ok(Element::MmrTree(..))
                 | Ok(Element::BulkAppendTree(..))
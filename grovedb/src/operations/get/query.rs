@@ -2,6 +2,18 @@
 
 #[cfg(feature = "minimal")]
 use crate::element::SumValue;
+#[cfg(feature = "minimal")]
+use crate::{
+    element::{query_iterator::QueryIterator, ElementType},
+    operations::subtree_aggregate::{aggregate_subtree_results, layer_path_query, AggregateSource},
+    query_result_type::{
+        Key, Path, PathKeyElementTrio, ProjectedResult, QueryProjection, QueryResultElement,
+        QueryResultElements, QueryResultType, SubtreeAggregate,
+    },
+    reference_path::ReferencePathType,
+    util::TxRef,
+    Element, Error, GroveDb, PathQuery, Query, QueryCursor, TransactionArg,
+};
 use crate::{
     element::{
         aggregate_sum_query::{
@@ -9,25 +21,25 @@ use crate::{
         },
         query::ElementQueryExtensions,
         query_options::QueryOptions,
-        BigSumValue, CountValue,
+        BigSumValue, CountValue, MinMaxValue,
     },
     operations::proof::ProveOptions,
     query_result_type::PathKeyOptionalElementTrio,
     AggregateSumPathQuery,
 };
-#[cfg(feature = "minimal")]
-use crate::{
-    query_result_type::{QueryResultElement, QueryResultElements, QueryResultType},
-    reference_path::ReferencePathType,
-    Element, Error, GroveDb, PathQuery, TransactionArg,
-};
 use grovedb_costs::cost_return_on_error_default;
 #[cfg(feature = "minimal")]
 use grovedb_costs::{
     cost_return_on_error, cost_return_on_error_no_add, CostResult, CostsExt, OperationCost,
 };
+#[cfg(feature = "minimal")]
+use grovedb_merk::tree::{kv::ValueDefinedCostType, TreeNode};
+#[cfg(feature = "minimal")]
+use grovedb_storage::{RawIterator, Storage, StorageContext};
 use grovedb_version::{check_grovedb_v0, check_grovedb_v0_with_cost, version::GroveVersion};
 #[cfg(feature = "minimal")]
+use indexmap::IndexMap;
+#[cfg(feature = "minimal")]
 use integer_encoding::VarInt;
 
 #[cfg(feature = "minimal")]
@@ -46,6 +58,8 @@ pub enum QueryItemOrSumReturnType {
     CountSumValue(CountValue, SumValue),
     /// an Item in serialized form with a Sum Value
     ItemDataWithSumValue(Vec<u8>, SumValue),
+    /// A minimum and maximum value (equal for a min max item)
+    MinMaxValue(MinMaxValue, MinMaxValue),
 }
 
 #[cfg(feature = "minimal")]
@@ -105,7 +119,8 @@ impl GroveDb {
 
                             match maybe_item {
                                 Element::Item(item, _) => Ok(item),
-                                Element::ItemWithSumItem(item, ..) => Ok(item),
+                                Element::ItemWithSumItem(item, ..)
+                                | Element::ItemWithExpiry(item, ..) => Ok(item),
                                 Element::SumItem(value, _) => Ok(value.encode_var_vec()),
                                 _ => {
                                     Err(Error::InvalidQuery("the reference must result in an item"))
@@ -241,12 +256,17 @@ where {
             Element::Item(..)
             | Element::SumItem(..)
             | Element::ItemWithSumItem(..)
+            | Element::MinMaxItem(..)
+            | Element::ItemWithExpiry(..)
             | Element::SumTree(..)
             | Element::BigSumTree(..)
             | Element::CountTree(..)
             | Element::CountSumTree(..)
             | Element::ProvableCountTree(..)
-            | Element::ProvableCountSumTree(..) => Ok(element),
+            | Element::ProvableCountSumTree(..)
+            | Element::MinMaxTree(..)
+            | Element::ProvableSumTree(..)
+            | Element::ProvableBigSumTree(..) => Ok(element),
             Element::Tree(..)
             | Element::CommitmentTree(..)
             | Element::MmrTree(..)
@@ -300,6 +320,202 @@ where {
         Ok((QueryResultElements { elements: results }, skipped)).wrap_with_cost(cost)
     }
 
+    /// Returns one page of at most `page_size` results of `path_query`,
+    /// starting after `cursor` or at the first result if there is none, and
+    /// the cursor of the next page. References are followed like in
+    /// [`GroveDb::query`].
+    ///
+    /// The cursor is `None` once a page holds fewer than `page_size` results,
+    /// subqueries without results not counting against the page size.
+    pub fn query_page(
+        &self,
+        path_query: &PathQuery,
+        cursor: Option<&QueryCursor>,
+        page_size: u16,
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(Vec<PathKeyElementTrio>, Option<QueryCursor>), Error> {
+        let mut cost = OperationCost::default();
+
+        let page_query = cost_return_on_error_no_add!(cost, path_query.page(cursor, page_size));
+        let (elements, _) = cost_return_on_error!(
+            &mut cost,
+            self.query(
+                &page_query,
+                true,
+                false,
+                true,
+                QueryResultType::QueryPathKeyElementTrioResultType,
+                transaction,
+                grove_version,
+            )
+        );
+        let results = elements.to_path_key_elements();
+
+        let next_cursor = cost_return_on_error_no_add!(
+            cost,
+            QueryCursor::after_page(path_query, page_size, &results)
+        );
+        Ok((results, next_cursor)).wrap_with_cost(cost)
+    }
+
+    /// Queries a single subtree and returns only the part of the matching
+    /// elements given by `projection`. Keys are read without decoding the
+    /// nodes, value hashes and element types without deserializing the
+    /// elements. References are not followed.
+    ///
+    /// The path query must not have subqueries, its limit and offset are
+    /// applied as usual. Keys and value hashes can be proved with
+    /// [`GroveDb::prove_query_keys`].
+    pub fn query_projected(
+        &self,
+        path_query: &PathQuery,
+        projection: QueryProjection,
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<ProjectedResult>, Error> {
+        let mut cost = OperationCost::default();
+
+        let query = &path_query.query.query;
+        if query.has_subquery() {
+            return Err(Error::InvalidQuery(
+                "projected queries can not have subqueries",
+            ))
+            .wrap_with_cost(cost);
+        }
+
+        let tx = TxRef::new(&self.db, transaction);
+        let path: Vec<&[u8]> = path_query.path.iter().map(|key| key.as_slice()).collect();
+        let subtree = cost_return_on_error!(
+            &mut cost,
+            self.open_transactional_merk_at_path(
+                path.as_slice().into(),
+                tx.as_ref(),
+                None,
+                grove_version
+            )
+        );
+        if subtree.tree_type.uses_non_merk_data_storage() {
+            return Err(Error::InvalidQuery(
+                "projected queries only apply to merk trees",
+            ))
+            .wrap_with_cost(cost);
+        }
+
+        let mut limit = path_query.query.limit;
+        let mut offset = path_query.query.offset;
+        let mut results = Vec::new();
+        for item in query.directional_iter(query.left_to_right) {
+            let mut iter = subtree.storage.raw_iter();
+            item.seek_for_iter(&mut iter, query.left_to_right)
+                .unwrap_add_cost(&mut cost);
+            while item
+                .iter_is_valid_for_type(&iter, limit, None, query.left_to_right)
+                .unwrap_add_cost(&mut cost)
+            {
+                if let Some(skipped) = offset.as_mut().filter(|skipped| **skipped > 0) {
+                    *skipped -= 1;
+                } else {
+                    let key = cost_return_on_error_no_add!(
+                        cost,
+                        iter.key()
+                            .unwrap_add_cost(&mut cost)
+                            .map(|key| key.to_vec())
+                            .ok_or(Error::CorruptedData(
+                                "expected iterator key but got None".to_string(),
+                            ))
+                    );
+                    let result = if projection == QueryProjection::Keys {
+                        ProjectedResult::Key(key)
+                    } else {
+                        let value = cost_return_on_error_no_add!(
+                            cost,
+                            iter.value()
+                                .unwrap_add_cost(&mut cost)
+                                .ok_or(Error::CorruptedData(
+                                    "expected iterator value but got None".to_string(),
+                                ))
+                        );
+                        let node = cost_return_on_error_no_add!(
+                            cost,
+                            TreeNode::decode_raw(
+                                value,
+                                key.clone(),
+                                None::<fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+                                grove_version,
+                            )
+                            .map_err(|e| Error::CorruptedData(e.to_string()))
+                        );
+                        if projection == QueryProjection::ValueHashes {
+                            ProjectedResult::KeyValueHash(key, *node.value_hash())
+                        } else {
+                            let element_type = cost_return_on_error_no_add!(
+                                cost,
+                                ElementType::from_serialized_value(node.value_as_slice())
+                                    .map_err(Error::from)
+                            );
+                            ProjectedResult::KeyElementType(key, element_type)
+                        }
+                    };
+                    results.push(result);
+                    if let Some(limit) = limit.as_mut() {
+                        *limit -= 1;
+                    }
+                }
+                if query.left_to_right {
+                    iter.next().unwrap_add_cost(&mut cost);
+                } else {
+                    iter.prev().unwrap_add_cost(&mut cost);
+                }
+                cost.seek_count += 1;
+            }
+        }
+        Ok(results).wrap_with_cost(cost)
+    }
+
+    /// Returns the aggregates of the results of a path query in every
+    /// subtree they are found in: their count, sum, and smallest and largest
+    /// keys, see [`SubtreeAggregate`]. The results are the ones of
+    /// [`GroveDb::query_raw`], subtrees without results have no aggregates.
+    ///
+    /// Subqueries over the whole of a count sum tree take its count and sum
+    /// from the tree element and its smallest and largest keys from the ends
+    /// of the tree, without reading its elements.
+    ///
+    /// The path query must not have a limit or an offset. The aggregates are
+    /// proven by [`GroveDb::prove_subtree_aggregates`].
+    pub fn query_subtree_aggregates(
+        &self,
+        path_query: &PathQuery,
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<SubtreeAggregate>, Error> {
+        let mut cost = OperationCost::default();
+
+        if path_query.query.limit.is_some() || path_query.query.offset.is_some() {
+            return Err(Error::InvalidQuery(
+                "subtree aggregates can not be limited or offset",
+            ))
+            .wrap_with_cost(cost);
+        }
+        let mut source = StoredAggregateSource {
+            db: self,
+            transaction,
+            grove_version,
+        };
+        let mut aggregates = IndexMap::new();
+        cost_return_on_error!(
+            &mut cost,
+            aggregate_subtree_results(
+                &mut source,
+                path_query.path.clone(),
+                &path_query.query.query,
+                &mut aggregates,
+            )
+        );
+        Ok(aggregates.into_values().collect()).wrap_with_cost(cost)
+    }
+
     /// Queries the backing store and returns element items by their value,
     /// Sum Items are encoded as var vec
     pub fn query_item_value(
@@ -358,8 +574,10 @@ where {
 
                                     match maybe_item {
                                         Element::Item(item, _)
-                                        | Element::ItemWithSumItem(item, ..) => Ok(item),
-                                        Element::SumItem(item, _) => Ok(item.encode_var_vec()),
+                                        | Element::ItemWithSumItem(item, ..)
+                                        | Element::ItemWithExpiry(item, ..) => Ok(item),
+                                        Element::SumItem(item, _)
+                                        | Element::MinMaxItem(item, _) => Ok(item.encode_var_vec()),
                                         _ => Err(Error::InvalidQuery(
                                             "the reference must result in an item",
                                         )),
@@ -370,8 +588,12 @@ where {
                                 )),
                             }
                         }
-                        Element::Item(item, _) | Element::ItemWithSumItem(item, ..) => Ok(item),
-                        Element::SumItem(item, _) => Ok(item.encode_var_vec()),
+                        Element::Item(item, _)
+                        | Element::ItemWithSumItem(item, ..)
+                        | Element::ItemWithExpiry(item, ..) => Ok(item),
+                        Element::SumItem(item, _) | Element::MinMaxItem(item, _) => {
+                            Ok(item.encode_var_vec())
+                        }
                         Element::Tree(..)
                         | Element::SumTree(..)
                         | Element::BigSumTree(..)
@@ -382,7 +604,10 @@ where {
                         | Element::CommitmentTree(..)
                         | Element::MmrTree(..)
                         | Element::BulkAppendTree(..)
-                        | Element::DenseAppendOnlyFixedSizeTree(..) => Err(Error::InvalidQuery(
+                        | Element::DenseAppendOnlyFixedSizeTree(..)
+                        | Element::MinMaxTree(..)
+                        | Element::ProvableSumTree(..)
+                        | Element::ProvableBigSumTree(..) => Err(Error::InvalidQuery(
                             "path_queries can only refer to items and references",
                         )),
                     }
@@ -454,7 +679,8 @@ where {
                                         .unwrap_add_cost(&mut cost)?;
 
                                     match maybe_item {
-                                        Element::Item(item, _) => {
+                                        Element::Item(item, _)
+                                        | Element::ItemWithExpiry(item, ..) => {
                                             Ok(QueryItemOrSumReturnType::ItemData(item))
                                         }
                                         Element::SumItem(sum_value, _) => {
@@ -492,6 +718,18 @@ where {
                                             count_value,
                                             sum_value,
                                         )),
+                                        Element::MinMaxItem(value, _) => {
+                                            Ok(QueryItemOrSumReturnType::MinMaxValue(value, value))
+                                        }
+                                        Element::MinMaxTree(_, min, max, _) => {
+                                            Ok(QueryItemOrSumReturnType::MinMaxValue(min, max))
+                                        }
+                                        Element::ProvableSumTree(_, sum_value, _) => {
+                                            Ok(QueryItemOrSumReturnType::SumValue(sum_value))
+                                        }
+                                        Element::ProvableBigSumTree(_, big_sum_value, _) => {
+                                            Ok(QueryItemOrSumReturnType::BigSumValue(big_sum_value))
+                                        }
                                         _ => Err(Error::InvalidQuery(
                                             "the reference must result in an item",
                                         )),
@@ -502,7 +740,9 @@ where {
                                 )),
                             }
                         }
-                        Element::Item(item, _) => Ok(QueryItemOrSumReturnType::ItemData(item)),
+                        Element::Item(item, _) | Element::ItemWithExpiry(item, ..) => {
+                            Ok(QueryItemOrSumReturnType::ItemData(item))
+                        }
                         Element::SumItem(sum_value, _) => {
                             Ok(QueryItemOrSumReturnType::SumValue(sum_value))
                         }
@@ -527,6 +767,18 @@ where {
                         Element::ProvableCountSumTree(_, count_value, sum_value, _) => Ok(
                             QueryItemOrSumReturnType::CountSumValue(count_value, sum_value),
                         ),
+                        Element::MinMaxItem(value, _) => {
+                            Ok(QueryItemOrSumReturnType::MinMaxValue(value, value))
+                        }
+                        Element::MinMaxTree(_, min, max, _) => {
+                            Ok(QueryItemOrSumReturnType::MinMaxValue(min, max))
+                        }
+                        Element::ProvableSumTree(_, sum_value, _) => {
+                            Ok(QueryItemOrSumReturnType::SumValue(sum_value))
+                        }
+                        Element::ProvableBigSumTree(_, big_sum_value, _) => {
+                            Ok(QueryItemOrSumReturnType::BigSumValue(big_sum_value))
+                        }
                         Element::Tree(..)
                         | Element::CommitmentTree(..)
                         | Element::MmrTree(..)
@@ -560,7 +812,9 @@ where {
     ///
     /// **See also:** [`query_sums`](Self::query_sums) for a simpler API that
     /// uses a regular [`PathQuery`] and returns raw `Vec<i64>` values without
-    /// aggregate scanning limits.
+    /// aggregate scanning limits. When only the total of a key range of a
+    /// provable sum tree is needed, [`prove_sum_in_range`](Self::prove_sum_in_range)
+    /// proves it without revealing the individual sum items.
     pub fn query_aggregate_sums(
         &self,
         aggregate_sum_path_query: &AggregateSumPathQuery,
@@ -710,6 +964,11 @@ where {
                         | Element::MmrTree(..)
                         | Element::BulkAppendTree(..)
                         | Element::DenseAppendOnlyFixedSizeTree(..)
+                        | Element::MinMaxTree(..)
+                        | Element::ProvableSumTree(..)
+                        | Element::ProvableBigSumTree(..)
+                        | Element::MinMaxItem(..)
+                        | Element::ItemWithExpiry(..)
                         | Element::Item(..) => Err(Error::InvalidQuery(
                             "path_queries over sum items can only refer to sum items and \
                              references",
@@ -757,6 +1016,24 @@ where {
         )
     }
 
+    /// Returns an iterator over the results of a path query, walking its
+    /// subqueries lazily. The results are the ones of [`GroveDb::query_raw`]
+    /// with limits on ranges without sub elements and errors on missing
+    /// intermediate trees, but only a few elements per subtree on the way
+    /// down to the current result are kept in memory. The cost of the
+    /// results read so far is given by [`QueryIterator::cost`].
+    ///
+    /// Without a transaction the iterator reads from a transaction of its
+    /// own, so it sees the same state from its first result to its last.
+    pub fn query_iter<'db>(
+        &'db self,
+        path_query: &PathQuery,
+        transaction: TransactionArg<'db, 'db>,
+        grove_version: &'db GroveVersion,
+    ) -> QueryIterator<'db> {
+        QueryIterator::new(&self.db, path_query, transaction, grove_version)
+    }
+
     /// Splits the result set of a path query by query path.
     /// If max_results is exceeded we return an error.
     pub fn query_keys_optional(
@@ -877,6 +1154,77 @@ where {
     }
 }
 
+/// Reads the aggregates of the results of a path query from the database.
+#[cfg(feature = "minimal")]
+pub(crate) struct StoredAggregateSource<'a> {
+    pub(crate) db: &'a GroveDb,
+    pub(crate) transaction: TransactionArg<'a, 'a>,
+    pub(crate) grove_version: &'a GroveVersion,
+}
+
+#[cfg(feature = "minimal")]
+impl AggregateSource for StoredAggregateSource<'_> {
+    fn layer_elements(
+        &mut self,
+        path: &Path,
+        query: &Query,
+    ) -> CostResult<Vec<(Key, Element)>, Error> {
+        self.db
+            .query_raw(
+                &layer_path_query(path, query),
+                true,
+                true,
+                true,
+                QueryResultType::QueryKeyElementPairResultType,
+                self.transaction,
+                self.grove_version,
+            )
+            .map_ok(|(elements, _)| elements.to_key_elements())
+    }
+
+    fn element(&mut self, path: &Path, key: &[u8]) -> CostResult<Option<Element>, Error> {
+        let path: Vec<&[u8]> = path.iter().map(|key| key.as_slice()).collect();
+        self.db.get_raw_optional(
+            path.as_slice().into(),
+            key,
+            self.transaction,
+            self.grove_version,
+        )
+    }
+
+    fn tree_ends(&mut self, path: &Path) -> CostResult<Option<(Key, Key)>, Error> {
+        let mut cost = OperationCost::default();
+
+        let tx = TxRef::new(&self.db.db, self.transaction);
+        let path: Vec<&[u8]> = path.iter().map(|key| key.as_slice()).collect();
+        let ctx = self
+            .db
+            .db
+            .get_transactional_storage_context(path.as_slice().into(), None, tx.as_ref())
+            .unwrap_add_cost(&mut cost);
+        let mut iter = ctx.raw_iter();
+        iter.seek_to_first().unwrap_add_cost(&mut cost);
+        let Some(min_key) = iter
+            .key()
+            .unwrap_add_cost(&mut cost)
+            .map(|key| key.to_vec())
+        else {
+            return Ok(None).wrap_with_cost(cost);
+        };
+        iter.seek_to_last().unwrap_add_cost(&mut cost);
+        let max_key = cost_return_on_error_no_add!(
+            cost,
+            iter.key()
+                .unwrap_add_cost(&mut cost)
+                .map(|key| key.to_vec())
+                .ok_or(Error::CorruptedData(
+                    "expected a last key in a non empty tree".to_string(),
+                ))
+        );
+        Ok(Some((min_key, max_key))).wrap_with_cost(cost)
+    }
+}
+
 #[cfg(feature = "minimal")]
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,81 @@
+//! Rank and select queries on provable count trees
+
+use grovedb_costs::{cost_return_on_error, cost_return_on_error_no_add, CostResult, CostsExt};
+use grovedb_path::SubtreePath;
+use grovedb_version::version::GroveVersion;
+
+use crate::{util::TxRef, Element, Error, GroveDb, TransactionArg};
+
+impl GroveDb {
+    /// Gets the key and element at `rank` in the provable count tree at
+    /// `path`, or `None` if the tree counts no more than `rank`. References
+    /// are not followed.
+    ///
+    /// Ranks start at zero with the first key of the tree. An element holds
+    /// as many ranks as it counts for in the tree: in a tree of items the
+    /// rank of a key is its position, while a count tree nested in the tree
+    /// holds one rank for each element it counts. Finding the key takes one
+    /// walk from the root of the tree to it, using the counts stored along
+    /// the way.
+    ///
+    /// The key can be proved with [`GroveDb::prove_by_rank`].
+    pub fn get_by_rank<'b, B, P>(
+        &self,
+        path: P,
+        rank: u64,
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Option<(Vec<u8>, Element)>, Error>
+    where
+        B: AsRef<[u8]> + 'b,
+        P: Into<SubtreePath<'b, B>>,
+    {
+        let tx = TxRef::new(&self.db, transaction);
+        let mut cost = Default::default();
+
+        let merk = cost_return_on_error!(
+            &mut cost,
+            self.open_transactional_merk_at_path(path.into(), tx.as_ref(), None, grove_version)
+        );
+        let key_value = cost_return_on_error!(
+            &mut cost,
+            merk.get_by_rank(rank, grove_version).map_err(Error::from)
+        );
+        let Some((key, value)) = key_value else {
+            return Ok(None).wrap_with_cost(cost);
+        };
+        let element = cost_return_on_error_no_add!(
+            cost,
+            Element::deserialize(&value, grove_version).map_err(Error::from)
+        );
+        Ok(Some((key, element))).wrap_with_cost(cost)
+    }
+
+    /// Gets the first rank held by `key` in the provable count tree at
+    /// `path`, or `None` if the key is not in the tree. See
+    /// [`GroveDb::get_by_rank`] for how ranks are held.
+    ///
+    /// The rank can be proved with [`GroveDb::prove_rank_of_key`].
+    pub fn rank_of_key<'b, B, P>(
+        &self,
+        path: P,
+        key: &[u8],
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Option<u64>, Error>
+    where
+        B: AsRef<[u8]> + 'b,
+        P: Into<SubtreePath<'b, B>>,
+    {
+        let tx = TxRef::new(&self.db, transaction);
+        let mut cost = Default::default();
+
+        let merk = cost_return_on_error!(
+            &mut cost,
+            self.open_transactional_merk_at_path(path.into(), tx.as_ref(), None, grove_version)
+        );
+        merk.rank_of_key(key, grove_version)
+            .map_err(Error::from)
+            .add_cost(cost)
+    }
+}
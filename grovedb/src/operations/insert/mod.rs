@@ -1,22 +1,25 @@
 //! Insert operations
 
-use std::{collections::HashMap, option::Option::None};
+use std::option::Option::None;
 
 use grovedb_costs::{
     cost_return_on_error, cost_return_on_error_into, cost_return_on_error_no_add, CostResult,
     CostsExt, OperationCost,
 };
-use grovedb_element::reference_path::path_from_reference_path_type;
 use grovedb_merk::{
     element::{costs::ElementCostExtensions, insert::ElementInsertToStorageExtensions, ElementExt},
     tree::NULL_HASH,
-    Merk, MerkOptions,
+    MerkOptions,
 };
-use grovedb_path::SubtreePath;
-use grovedb_storage::{rocksdb_storage::PrefixedRocksDbTransactionContext, Storage, StorageBatch};
+use grovedb_path::{SubtreePath, SubtreePathBuilder};
+use grovedb_storage::{Storage, StorageBatch};
 use grovedb_version::{check_grovedb_v0_with_cost, version::GroveVersion};
 
-use crate::{util::TxRef, Element, Error, GroveDb, Transaction, TransactionArg};
+use crate::{
+    merk_cache::MerkCache, reference_path::follow_reference,
+    subtree_registry::SubtreeRegistryUpdate, util::TxRef, Element, Error, GroveDb, Transaction,
+    TransactionArg,
+};
 
 #[derive(Clone)]
 /// Insert options
@@ -86,11 +89,33 @@ impl GroveDb {
 
         let mut cost = OperationCost::default();
         cost_return_on_error!(&mut cost, validate_key_length(key));
+        if element.is_item_with_expiry() {
+            return Err(Error::InvalidInput(
+                "items with an expiry can only be written with apply_batch, which indexes them",
+            ))
+            .wrap_with_cost(cost);
+        }
 
         let subtree_path: SubtreePath<B> = path.into();
         let batch = StorageBatch::new();
 
         let tx = TxRef::new(&self.db, transaction);
+        let changes = self.capture_changes(
+            || [(subtree_path.to_vec(), Some(key.to_vec()))],
+            tx.as_ref(),
+            grove_version,
+        );
+
+        let options = options.unwrap_or_default();
+        let mut registry_update = SubtreeRegistryUpdate::default();
+        let mut tree_path = subtree_path.to_vec();
+        tree_path.push(key.to_vec());
+        if element.is_any_tree() {
+            registry_update.insert(tree_path, &element);
+        } else if !options.validate_insertion_does_not_override_tree {
+            // The replaced element is only read when overriding trees is checked
+            registry_update.overwrite(tree_path);
+        }
 
         cost_return_on_error!(
             &mut cost,
@@ -98,7 +123,7 @@ impl GroveDb {
                 subtree_path,
                 key,
                 element,
-                options.unwrap_or_default(),
+                options,
                 tx.as_ref(),
                 &batch,
                 grove_version,
@@ -111,17 +136,22 @@ impl GroveDb {
                 .commit_multi_context_batch(batch, Some(tx.as_ref()))
                 .map_err(Into::into)
         );
+        cost_return_on_error!(
+            &mut cost,
+            self.apply_subtree_registry_update(registry_update, tx.as_ref(), grove_version)
+        );
 
-        tx.commit_local().wrap_with_cost(cost)
+        self.commit_local_with_changes(tx, changes, grove_version)
+            .wrap_with_cost(cost)
     }
 
-    fn insert_on_transaction<'db, 'b, B: AsRef<[u8]>>(
+    fn insert_on_transaction<'b, B: AsRef<[u8]>>(
         &self,
         path: SubtreePath<'b, B>,
         key: &[u8],
         element: Element,
         options: InsertOptions,
-        transaction: &'db Transaction,
+        transaction: &Transaction,
         batch: &StorageBatch,
         grove_version: &GroveVersion,
     ) -> CostResult<(), Error> {
@@ -136,34 +166,20 @@ impl GroveDb {
 
         let mut cost = OperationCost::default();
 
-        let mut merk_cache: HashMap<SubtreePath<'b, B>, Merk<PrefixedRocksDbTransactionContext>> =
-            HashMap::default();
-
-        let merk = cost_return_on_error!(
+        let merk_cache = MerkCache::new(self, transaction, batch, grove_version);
+        cost_return_on_error!(
             &mut cost,
             self.add_element_on_transaction(
-                path.clone(),
+                &merk_cache,
+                path.derive_owned(),
                 key,
                 element,
                 options,
-                transaction,
-                batch,
-                grove_version
-            )
-        );
-        merk_cache.insert(path.clone(), merk);
-        cost_return_on_error!(
-            &mut cost,
-            self.propagate_changes_with_transaction(
-                merk_cache,
-                path,
-                transaction,
-                batch,
                 grove_version
             )
         );
 
-        Ok(()).wrap_with_cost(cost)
+        merk_cache.flush().add_cost(cost)
     }
 
     /// Add subtree to another subtree.
@@ -171,16 +187,15 @@ impl GroveDb {
     /// first make sure other merk exist
     /// if it exists, then create merk to be inserted, and get root hash
     /// we only care about root hash of merk to be inserted
-    fn add_element_on_transaction<'db, B: AsRef<[u8]>>(
-        &'db self,
-        path: SubtreePath<B>,
+    fn add_element_on_transaction<'b, B: AsRef<[u8]>>(
+        &self,
+        merk_cache: &MerkCache<'_, 'b, B>,
+        path: SubtreePathBuilder<'b, B>,
         key: &[u8],
         element: Element,
         options: InsertOptions,
-        transaction: &'db Transaction,
-        batch: &'db StorageBatch,
         grove_version: &GroveVersion,
-    ) -> CostResult<Merk<PrefixedRocksDbTransactionContext<'db>>, Error> {
+    ) -> CostResult<(), Error> {
         check_grovedb_v0_with_cost!(
             "add_element_on_transaction",
             grove_version
@@ -192,27 +207,20 @@ impl GroveDb {
 
         let mut cost = OperationCost::default();
 
-        let mut subtree_to_insert_into = cost_return_on_error!(
-            &mut cost,
-            self.open_transactional_merk_at_path(
-                path.clone(),
-                transaction,
-                Some(batch),
-                grove_version
-            )
-        );
+        let mut subtree_to_insert_into =
+            cost_return_on_error!(&mut cost, merk_cache.get_merk(path.clone()));
         // if we don't allow a tree override then we should check
 
         if options.checks_for_override() {
             let maybe_element_bytes = cost_return_on_error!(
                 &mut cost,
                 subtree_to_insert_into
-                    .get(
+                    .for_merk(|merk| merk.get(
                         key,
                         true,
                         Some(&Element::value_defined_cost_for_serialized_value),
                         grove_version,
-                    )
+                    ))
                     .map_err(|e| Error::CorruptedData(e.to_string()))
             );
             if let Some(element_bytes) = maybe_element_bytes {
@@ -243,21 +251,9 @@ impl GroveDb {
 
         match element {
             Element::Reference(ref reference_path, ..) => {
-                let path = path.to_vec(); // TODO: need for support for references in path library
-                let reference_path = cost_return_on_error_into!(
-                    &mut cost,
-                    path_from_reference_path_type(reference_path.clone(), &path, Some(key))
-                        .wrap_with_cost(OperationCost::default())
-                );
-
                 let referenced_item = cost_return_on_error!(
                     &mut cost,
-                    self.follow_reference(
-                        reference_path.as_slice().into(),
-                        false,
-                        Some(transaction),
-                        grove_version
-                    )
+                    follow_reference(merk_cache, path, key, reference_path.clone())
                 );
 
                 let referenced_element_value_hash = cost_return_on_error_into!(
@@ -267,13 +263,13 @@ impl GroveDb {
 
                 cost_return_on_error_into!(
                     &mut cost,
-                    element.insert_reference(
-                        &mut subtree_to_insert_into,
+                    subtree_to_insert_into.for_merk(|merk| element.insert_reference(
+                        merk,
                         key,
                         referenced_element_value_hash,
                         Some(options.as_merk_options()),
                         grove_version,
-                    )
+                    ))
                 );
             }
             Element::Tree(ref value, _)
@@ -282,7 +278,10 @@ impl GroveDb {
             | Element::CountTree(ref value, ..)
             | Element::CountSumTree(ref value, ..)
             | Element::ProvableCountTree(ref value, ..)
-            | Element::ProvableCountSumTree(ref value, ..) => {
+            | Element::ProvableCountSumTree(ref value, ..)
+            | Element::MinMaxTree(ref value, ..)
+            | Element::ProvableSumTree(ref value, ..)
+            | Element::ProvableBigSumTree(ref value, ..) => {
                 if value.is_some() {
                     return Err(Error::InvalidCodeExecution(
                         "a tree should be empty at the moment of insertion when not using batches",
@@ -291,13 +290,13 @@ impl GroveDb {
                 } else {
                     cost_return_on_error_into!(
                         &mut cost,
-                        element.insert_subtree(
-                            &mut subtree_to_insert_into,
+                        subtree_to_insert_into.for_merk(|merk| element.insert_subtree(
+                            merk,
                             key,
                             NULL_HASH,
                             Some(options.as_merk_options()),
                             grove_version
-                        )
+                        ))
                     );
                 }
             }
@@ -307,13 +306,13 @@ impl GroveDb {
             Element::CommitmentTree(..) => {
                 cost_return_on_error_into!(
                     &mut cost,
-                    element.insert_subtree(
-                        &mut subtree_to_insert_into,
+                    subtree_to_insert_into.for_merk(|merk| element.insert_subtree(
+                        merk,
                         key,
                         grovedb_commitment_tree::EMPTY_COMMITMENT_TREE_STATE_ROOT,
                         Some(options.as_merk_options()),
                         grove_version
-                    )
+                    ))
                 );
             }
             // MmrTree, BulkAppendTree, DenseAppendOnlyFixedSizeTree: initial
@@ -323,29 +322,33 @@ impl GroveDb {
             | Element::DenseAppendOnlyFixedSizeTree(..) => {
                 cost_return_on_error_into!(
                     &mut cost,
-                    element.insert_subtree(
-                        &mut subtree_to_insert_into,
+                    subtree_to_insert_into.for_merk(|merk| element.insert_subtree(
+                        merk,
                         key,
                         NULL_HASH,
                         Some(options.as_merk_options()),
                         grove_version
-                    )
+                    ))
                 );
             }
-            Element::Item(..) | Element::SumItem(..) | Element::ItemWithSumItem(..) => {
+            Element::Item(..)
+            | Element::SumItem(..)
+            | Element::ItemWithSumItem(..)
+            | Element::MinMaxItem(..)
+            | Element::ItemWithExpiry(..) => {
                 cost_return_on_error_into!(
                     &mut cost,
-                    element.insert(
-                        &mut subtree_to_insert_into,
+                    subtree_to_insert_into.for_merk(|merk| element.insert(
+                        merk,
                         key,
                         Some(options.as_merk_options()),
                         grove_version
-                    )
+                    ))
                 );
             }
         }
 
-        Ok(subtree_to_insert_into).wrap_with_cost(cost)
+        Ok(()).wrap_with_cost(cost)
     }
 
     /// Insert if not exists
@@ -1238,7 +1241,11 @@ mod tests {
         // Child Heights 2
         // Sum 1
 
-        // Total 37 + 38 + 40 = 115
+        // Subtree registry entry -> 88
+        // 54 for the entry key behind the prefix of the parent, with its size
+        // 34 for the tree prefix and whether the tree may hold subtrees, with
+        //   their size
+        // Total 37 + 38 + 40 + 88 = 203
 
         // Hash node calls
         // 1 for the node hash
@@ -1246,9 +1253,11 @@ mod tests {
         assert_eq!(
             cost,
             OperationCost {
-                seek_count: 3, // 1 to get tree, 1 to insert, 1 to insert into root tree
+                // 1 to get tree, 1 to insert, 1 to insert into root tree, 1 to
+                // register the tree
+                seek_count: 4,
                 storage_cost: StorageCost {
-                    added_bytes: 115,
+                    added_bytes: 203,
                     replaced_bytes: 0,
                     removed_bytes: NoStorageRemoval
                 },
@@ -1300,7 +1309,11 @@ mod tests {
         // Child Heights 2
         // Sum 1
 
-        // Total 37 + 47 + 40 = 124
+        // Subtree registry entry -> 88
+        // 54 for the entry key behind the prefix of the parent, with its size
+        // 34 for the tree prefix and whether the tree may hold subtrees, with
+        //   their size
+        // Total 37 + 47 + 40 + 88 = 212
 
         // Hash node calls
         // 1 for the node hash
@@ -1308,9 +1321,11 @@ mod tests {
         assert_eq!(
             cost,
             OperationCost {
-                seek_count: 3, // 1 to get tree, 1 to insert, 1 to insert into root tree
+                // 1 to get tree, 1 to insert, 1 to insert into root tree, 1 to
+                // register the tree
+                seek_count: 4,
                 storage_cost: StorageCost {
-                    added_bytes: 124,
+                    added_bytes: 212,
                     replaced_bytes: 0,
                     removed_bytes: NoStorageRemoval
                 },
@@ -1363,7 +1378,11 @@ mod tests {
         // Child Heights 2
         // Sum 1
 
-        // Total 37 + 42 + 40 = 119
+        // Subtree registry entry -> 88
+        // 54 for the entry key behind the prefix of the parent, with its size
+        // 34 for the tree prefix and whether the tree may hold subtrees, with
+        //   their size
+        // Total 37 + 42 + 40 + 88 = 207
 
         // Hash node calls
         // 1 for the kv_digest_to_kv_hash hash
@@ -1373,9 +1392,11 @@ mod tests {
         assert_eq!(
             cost,
             OperationCost {
-                seek_count: 3, // 1 to get tree, 1 to insert, 1 to insert into root tree
+                // 1 to get tree, 1 to insert, 1 to insert into root tree, 1 to
+                // register the tree
+                seek_count: 4,
                 storage_cost: StorageCost {
-                    added_bytes: 119,
+                    added_bytes: 207,
                     replaced_bytes: 0,
                     removed_bytes: NoStorageRemoval
                 },
@@ -15,13 +15,14 @@ use grovedb_merkle_mountain_range::{
     hash_count_for_push, mmr_size_to_leaf_count, MmrNode, MmrStore, MMR,
 };
 use grovedb_path::SubtreePath;
-use grovedb_storage::{rocksdb_storage::PrefixedRocksDbTransactionContext, Storage, StorageBatch};
+use grovedb_storage::{Storage, StorageBatch};
 use grovedb_version::version::GroveVersion;
 
 use crate::{
     batch::{GroveOp, QualifiedGroveDbOp},
+    merk_cache::MerkCache,
     util::TxRef,
-    Element, Error, GroveDb, Merk, Transaction, TransactionArg,
+    Element, Error, GroveDb, Transaction, TransactionArg,
 };
 
 impl GroveDb {
@@ -126,41 +127,23 @@ impl GroveDb {
 
         // 4. Open parent Merk and update the MmrTree element
         let batch = StorageBatch::new();
-        let mut parent_merk = cost_return_on_error!(
-            &mut cost,
-            self.open_transactional_merk_at_path(
-                path.clone(),
-                tx.as_ref(),
-                Some(&batch),
-                grove_version,
-            )
-        );
+        let merk_cache = MerkCache::new(self, tx.as_ref(), &batch, grove_version);
+        let mut parent_merk =
+            cost_return_on_error!(&mut cost, merk_cache.get_merk(path.derive_owned()));
 
         let updated_element = Element::new_mmr_tree(new_mmr_size, existing_flags);
 
         // MMR root hash flows as the Merk child hash
         cost_return_on_error!(
             &mut cost,
-            updated_element
-                .insert_subtree(&mut parent_merk, key, new_mmr_root, None, grove_version,)
-                .map_err(|e| e.into())
+            parent_merk.for_merk(|merk| updated_element
+                .insert_subtree(merk, key, new_mmr_root, None, grove_version,)
+                .map_err(|e| e.into()))
         );
 
         // 5. Propagate changes from parent upward
-        let mut merk_cache: HashMap<SubtreePath<B>, Merk<PrefixedRocksDbTransactionContext>> =
-            HashMap::new();
-        merk_cache.insert(path.clone(), parent_merk);
-
-        cost_return_on_error!(
-            &mut cost,
-            self.propagate_changes_with_transaction(
-                merk_cache,
-                path,
-                tx.as_ref(),
-                &batch,
-                grove_version,
-            )
-        );
+        drop(parent_merk);
+        cost_return_on_error!(&mut cost, merk_cache.flush());
 
         // 6. Commit batch and transaction
         cost_return_on_error!(
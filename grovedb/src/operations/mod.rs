@@ -14,6 +14,9 @@ pub(crate) mod is_empty_tree;
 #[cfg(any(feature = "minimal", feature = "verify"))]
 pub mod proof;
 
+#[cfg(any(feature = "minimal", feature = "verify"))]
+pub(crate) mod subtree_aggregate;
+
 #[cfg(feature = "minimal")]
 pub mod commitment_tree;
 
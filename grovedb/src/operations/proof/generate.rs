@@ -1,6 +1,6 @@
 //! Generate proof operations
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, LinkedList};
 
 use grovedb_bulk_append_tree::BulkAppendTreeProof;
 use grovedb_commitment_tree::COMMITMENT_TREE_DATA_KEY;
@@ -12,24 +12,33 @@ use grovedb_dense_fixed_sized_merkle_tree::DenseTreeProof;
 use grovedb_merk::{
     proofs::{encode_into, query::QueryItem, Node, Op},
     tree::{combine_hash, value_hash},
-    Merk, ProofWithoutEncodingResult, TreeFeatureType,
+    Merk, ProofWithoutEncodingResult, TreeFeatureType, TreeType,
 };
 use grovedb_merkle_mountain_range::MmrTreeProof;
-use grovedb_storage::{Storage, StorageContext};
+use grovedb_storage::{backend::PrefixedBackendTransactionContext, Storage, StorageContext};
 use grovedb_version::{
     check_grovedb_v0_or_v1_with_cost, check_grovedb_v0_with_cost, version::GroveVersion,
 };
+use indexmap::IndexMap;
 
 #[cfg(feature = "proof_debug")]
 use crate::query_result_type::QueryResultType;
 use crate::{
-    operations::proof::{
-        util::hex_to_ascii, GroveDBProof, GroveDBProofV0, GroveDBProofV1, LayerProof,
-        MerkOnlyLayerProof, ProofBytes, ProveOptions,
+    operations::{
+        get::StoredAggregateSource,
+        proof::{
+            util::hex_to_ascii, GroveDBProof, GroveDBProofV0, GroveDBProofV1, LayerProof,
+            MerkOnlyLayerProof, ProofBytes, ProveOptions,
+        },
+        subtree_aggregate::{
+            aggregate_subtree_results, element_path_query, layer_path_query, tree_end_path_query,
+            AggregateSource,
+        },
     },
     query::PathTrunkChunkQuery,
+    query_result_type::{Key, Path},
     reference_path::path_from_reference_path_type,
-    Element, Error, GroveDb, PathQuery,
+    Element, Error, GroveDb, PathQuery, Query, QueryCursor, Transaction,
 };
 
 impl GroveDb {
@@ -70,6 +79,11 @@ impl GroveDb {
     /// to generate them.
     ///
     /// Version dispatch happens in `prove_query_non_serialized`.
+    ///
+    /// V1 proofs support query offsets: the matches skipped by the offset are
+    /// proved by their key and value hash only. Offsets skip matches of the
+    /// deepest layers of the query, the layers without subqueries, so that
+    /// the skipped results can be verified without their values.
     pub fn prove_query(
         &self,
         path_query: &PathQuery,
@@ -100,6 +114,345 @@ impl GroveDb {
         Ok(encoded_proof).wrap_with_cost(cost)
     }
 
+    /// Proves the page of at most `page_size` results of `path_query`
+    /// following `cursor`, see [`GroveDb::query_page`]. The proof is
+    /// checked with [`GroveDb::verify_query_page`] given the same path
+    /// query, cursor and page size.
+    ///
+    /// Pages are always proved with V1 proofs, whose verifier and not prover
+    /// chooses not to count subqueries without results.
+    pub fn prove_query_page(
+        &self,
+        path_query: &PathQuery,
+        cursor: Option<&QueryCursor>,
+        page_size: u16,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error> {
+        let mut cost = OperationCost::default();
+
+        let page_query = cost_return_on_error_no_add!(cost, path_query.page(cursor, page_size));
+        let proof = cost_return_on_error!(
+            &mut cost,
+            self.prove_query_non_serialized_v1(
+                &page_query,
+                Some(PathQuery::PAGE_PROVE_OPTIONS),
+                grove_version
+            )
+        );
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+        let encoded_proof = cost_return_on_error_no_add!(
+            cost,
+            bincode::encode_to_vec(proof, config)
+                .map_err(|e| Error::CorruptedData(format!("unable to encode proof {}", e)))
+        );
+        Ok(encoded_proof).wrap_with_cost(cost)
+    }
+
+    /// Proves the aggregates of the results of a path query, see
+    /// [`GroveDb::query_subtree_aggregates`], for
+    /// [`GroveDb::verify_subtree_aggregates`].
+    ///
+    /// The proof holds a proof of every read the aggregates are made of.
+    /// Stored aggregates are proven by the count sum tree elements the parent
+    /// Merks commit to and by the first and last keys of the trees, so the
+    /// proof doesn't hold their elements.
+    ///
+    /// The path query must not have a limit or an offset.
+    pub fn prove_subtree_aggregates(
+        &self,
+        path_query: &PathQuery,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error> {
+        let mut cost = OperationCost::default();
+
+        if path_query.query.limit.is_some() || path_query.query.offset.is_some() {
+            return Err(Error::InvalidQuery(
+                "subtree aggregates can not be limited or offset",
+            ))
+            .wrap_with_cost(cost);
+        }
+        let mut source = ProvedAggregateSource {
+            stored: StoredAggregateSource {
+                db: self,
+                transaction: None,
+                grove_version,
+            },
+            proofs: Vec::new(),
+        };
+        cost_return_on_error!(
+            &mut cost,
+            aggregate_subtree_results(
+                &mut source,
+                path_query.path.clone(),
+                &path_query.query.query,
+                &mut IndexMap::new(),
+            )
+        );
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+        let encoded_proof = cost_return_on_error_no_add!(
+            cost,
+            bincode::encode_to_vec(source.proofs, config)
+                .map_err(|e| Error::CorruptedData(format!("unable to encode proof {}", e)))
+        );
+        Ok(encoded_proof).wrap_with_cost(cost)
+    }
+
+    /// Proves the keys matching a path query without subqueries, along with
+    /// their value hashes but not their values, see
+    /// [`GroveDb::query_projected`]. Each key found takes a `KVDigest` node
+    /// in the proof, which is checked with [`GroveDb::verify_query_keys`].
+    ///
+    /// The path query may have a limit but no offset.
+    pub fn prove_query_keys(
+        &self,
+        path_query: &PathQuery,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error> {
+        let mut cost = OperationCost::default();
+
+        let query = &path_query.query.query;
+        if query.has_subquery() || path_query.query.offset.is_some() {
+            return Err(Error::InvalidQuery(
+                "key proofs can not have subqueries or an offset",
+            ))
+            .wrap_with_cost(cost);
+        }
+
+        let tx = self.start_transaction();
+        let path_slices: Vec<&[u8]> = path_query.path.iter().map(|p| p.as_slice()).collect();
+        let target_tree = cost_return_on_error!(
+            &mut cost,
+            self.open_transactional_merk_at_path(
+                path_slices.as_slice().into(),
+                &tx,
+                None,
+                grove_version
+            )
+        );
+        if target_tree.tree_type.uses_non_merk_data_storage() {
+            return Err(Error::InvalidQuery("key proofs only apply to merk trees"))
+                .wrap_with_cost(cost);
+        }
+
+        // An empty tree is proved by the empty root key of its element.
+        let mut keys_proof = Vec::new();
+        if target_tree.has_root_key() {
+            let (proof, _) = cost_return_on_error!(
+                &mut cost,
+                target_tree
+                    .prove_keys_unchecked_query_items(
+                        &query.items,
+                        path_query.query.limit,
+                        query.left_to_right,
+                        grove_version
+                    )
+                    .map_err(Error::MerkError)
+            );
+            encode_into(proof.iter(), &mut keys_proof);
+        }
+        let target_layer = LayerProof {
+            merk_proof: ProofBytes::Merk(keys_proof),
+            lower_layers: BTreeMap::new(),
+        };
+
+        let root_layer = cost_return_on_error!(
+            &mut cost,
+            self.prove_path_to_layer(&path_query.path, target_layer, &tx, grove_version)
+        );
+
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+        let encoded_proof = cost_return_on_error_no_add!(
+            cost,
+            bincode::encode_to_vec(GroveDBProof::V1(GroveDBProofV1 { root_layer }), config)
+                .map_err(|e| Error::CorruptedData(format!("unable to encode proof {}", e)))
+        );
+        Ok(encoded_proof).wrap_with_cost(cost)
+    }
+
+    /// Proves the aggregate count of the keys of the tree at `path` that are
+    /// in `range`, without proving the keys themselves.
+    ///
+    /// The tree must be a provable count tree, whose node hashes commit to
+    /// the aggregate count of their subtrees. Only the nodes on the way to
+    /// the two ends of the range are in the proof, so its size grows with the
+    /// height of the tree rather than with the size of the range. For a tree
+    /// of items the count is the number of keys in the range; count trees
+    /// nested in it add their own counts instead of one.
+    ///
+    /// Verify it with [`GroveDb::verify_count_in_range`].
+    pub fn prove_count_in_range(
+        &self,
+        path: Vec<Vec<u8>>,
+        range: QueryItem,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error> {
+        self.prove_in_single_tree(
+            path,
+            |tree_type| {
+                matches!(
+                    tree_type,
+                    TreeType::ProvableCountTree | TreeType::ProvableCountSumTree
+                )
+            },
+            "range counts can only be proved in provable count trees",
+            |merk| merk.prove_count_in_range(&range, grove_version),
+            grove_version,
+        )
+    }
+
+    /// Proves the aggregate sum of the elements of the tree at `path` whose
+    /// keys are in `range`, without proving the elements themselves.
+    ///
+    /// The tree must be a provable sum tree or provable big sum tree, whose
+    /// node hashes commit to the aggregate sum of their subtrees. As with
+    /// [`GroveDb::prove_count_in_range`], only the nodes bounding the range
+    /// are in the proof. Sum trees nested in the tree contribute their own
+    /// sums, and elements that carry no sum contribute zero.
+    ///
+    /// Verify it with [`GroveDb::verify_sum_in_range`].
+    pub fn prove_sum_in_range(
+        &self,
+        path: Vec<Vec<u8>>,
+        range: QueryItem,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error> {
+        self.prove_in_single_tree(
+            path,
+            |tree_type| {
+                matches!(
+                    tree_type,
+                    TreeType::ProvableSumTree | TreeType::ProvableBigSumTree
+                )
+            },
+            "range sums can only be proved in provable sum trees",
+            |merk| merk.prove_sum_in_range(&range, grove_version),
+            grove_version,
+        )
+    }
+
+    /// Proves the key at `rank` in the provable count tree at `path`, see
+    /// [`GroveDb::get_by_rank`] for how ranks are held. The proof holds the
+    /// nodes on the way from the root of the tree to the key, with the counts
+    /// of the subtrees beside them, so it stays logarithmic in the size of
+    /// the tree.
+    ///
+    /// Verify it with [`GroveDb::verify_by_rank`]. The element at the key can
+    /// then be proved with a regular path query.
+    pub fn prove_by_rank(
+        &self,
+        path: Vec<Vec<u8>>,
+        rank: u64,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error> {
+        self.prove_in_single_tree(
+            path,
+            |tree_type| {
+                matches!(
+                    tree_type,
+                    TreeType::ProvableCountTree | TreeType::ProvableCountSumTree
+                )
+            },
+            "ranks can only be proved in provable count trees",
+            |merk| merk.prove_key_at_rank(rank, grove_version),
+            grove_version,
+        )
+    }
+
+    /// Proves the rank of `key` in the provable count tree at `path`, or that
+    /// the key is not in the tree. This is the range count proof of the keys
+    /// before `key`, whose walk ends at the key when it is in the tree.
+    ///
+    /// Verify it with [`GroveDb::verify_rank_of_key`].
+    pub fn prove_rank_of_key(
+        &self,
+        path: Vec<Vec<u8>>,
+        key: &[u8],
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error> {
+        self.prove_in_single_tree(
+            path,
+            |tree_type| {
+                matches!(
+                    tree_type,
+                    TreeType::ProvableCountTree | TreeType::ProvableCountSumTree
+                )
+            },
+            "ranks can only be proved in provable count trees",
+            |merk| merk.prove_rank_of_key(key, grove_version),
+            grove_version,
+        )
+    }
+
+    /// Proves the tree at `path` with the Merk proof made by `prove_tree`,
+    /// along with the path leading to it. Trees whose type is not accepted by
+    /// `accepts_tree_type` are rejected with `wrong_tree_type`. As with key
+    /// proofs, an empty tree is proved by its element alone.
+    fn prove_in_single_tree<F>(
+        &self,
+        path: Vec<Vec<u8>>,
+        accepts_tree_type: fn(TreeType) -> bool,
+        wrong_tree_type: &'static str,
+        prove_tree: F,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error>
+    where
+        F: for<'db> FnOnce(
+            &Merk<PrefixedBackendTransactionContext<'db>>,
+        ) -> CostResult<LinkedList<Op>, grovedb_merk::Error>,
+    {
+        let mut cost = OperationCost::default();
+
+        let tx = self.start_transaction();
+        let path_slices: Vec<&[u8]> = path.iter().map(|p| p.as_slice()).collect();
+        let target_tree = cost_return_on_error!(
+            &mut cost,
+            self.open_transactional_merk_at_path(
+                path_slices.as_slice().into(),
+                &tx,
+                None,
+                grove_version
+            )
+        );
+
+        if !accepts_tree_type(target_tree.tree_type) {
+            return Err(Error::InvalidInput(wrong_tree_type)).wrap_with_cost(cost);
+        }
+
+        let mut target_proof = Vec::new();
+        if target_tree.has_root_key() {
+            let proof = cost_return_on_error!(
+                &mut cost,
+                prove_tree(&target_tree).map_err(Error::MerkError)
+            );
+            encode_into(proof.iter(), &mut target_proof);
+        }
+        let target_layer = LayerProof {
+            merk_proof: ProofBytes::Merk(target_proof),
+            lower_layers: BTreeMap::new(),
+        };
+
+        let root_layer = cost_return_on_error!(
+            &mut cost,
+            self.prove_path_to_layer(&path, target_layer, &tx, grove_version)
+        );
+
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+        let encoded_proof = cost_return_on_error_no_add!(
+            cost,
+            bincode::encode_to_vec(GroveDBProof::V1(GroveDBProofV1 { root_layer }), config)
+                .map_err(|e| Error::CorruptedData(format!("unable to encode proof {}", e)))
+        );
+        Ok(encoded_proof).wrap_with_cost(cost)
+    }
+
     /// Generates a proof and does not serialize the result.
     ///
     /// Dispatches to v0 or v1 based on the version.
@@ -128,6 +481,45 @@ impl GroveDb {
         }
     }
 
+    /// Generates a Merk proof like `generate_merk_proof`, in which the first
+    /// `offset` matches are skipped and proved by their key and value hash
+    /// only. Also returns how many of the matches to skip were not found.
+    fn generate_merk_proof_with_offset<'a, S>(
+        &self,
+        subtree: &'a Merk<S>,
+        query_items: &[QueryItem],
+        left_to_right: bool,
+        limit: Option<u16>,
+        offset: u16,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(ProofWithoutEncodingResult, u16), Error>
+    where
+        S: StorageContext<'a> + 'a,
+    {
+        subtree
+            .prove_unchecked_query_items_with_offset(
+                query_items,
+                limit,
+                offset,
+                left_to_right,
+                grove_version,
+            )
+            .map_ok(|((proof, limit), offset)| {
+                (ProofWithoutEncodingResult::new(proof, limit), offset)
+            })
+            .map_err(|e| {
+                Error::InternalError(format!(
+                    "failed to generate proof for query_items [{}] error is : {}",
+                    query_items
+                        .iter()
+                        .map(|e| e.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    e
+                ))
+            })
+    }
+
     /// V0: Generates a Merk-only proof without serialization.
     pub(crate) fn prove_query_non_serialized_v0(
         &self,
@@ -314,6 +706,10 @@ impl GroveDb {
                     | Op::PushInverted(Node::KVValueHashFeatureType(..))
                     | Op::Push(Node::KVCount(..))
                     | Op::PushInverted(Node::KVCount(..))
+                    | Op::Push(Node::KVMinMax(..))
+                    | Op::PushInverted(Node::KVMinMax(..))
+                    | Op::Push(Node::KVSum(..))
+                    | Op::PushInverted(Node::KVSum(..))
             );
             // Extract count if present for ProvableCountTree references
             let count_for_ref = match op {
@@ -324,11 +720,25 @@ impl GroveDb {
                 },
                 _ => None,
             };
+            // Extract min and max if present for MinMaxTree references
+            let min_max_for_ref = match op {
+                Op::Push(Node::KVValueHashFeatureType(_, _, _, ft))
+                | Op::PushInverted(Node::KVValueHashFeatureType(_, _, _, ft)) => ft.min_max(),
+                _ => None,
+            };
+            // Extract sum if present for ProvableSumTree references
+            let sum_for_ref = match op {
+                Op::Push(Node::KVValueHashFeatureType(_, _, _, ft))
+                | Op::PushInverted(Node::KVValueHashFeatureType(_, _, _, ft)) => ft.provable_sum(),
+                _ => None,
+            };
             match op {
                 Op::Push(node) | Op::PushInverted(node) => match node {
                     Node::KV(key, value)
                     | Node::KVValueHash(key, value, ..)
                     | Node::KVCount(key, value, _)
+                    | Node::KVMinMax(key, value, ..)
+                    | Node::KVSum(key, value, _)
                     | Node::KVValueHashFeatureType(key, value, ..)
                         if !done_with_results =>
                     {
@@ -373,6 +783,21 @@ impl GroveDb {
                                         value_hash(value).unwrap_add_cost(&mut cost),
                                         count,
                                     )
+                                } else if let Some((min, max)) = min_max_for_ref {
+                                    Node::KVRefValueHashMinMax(
+                                        key.to_owned(),
+                                        serialized_referenced_elem.expect("confirmed ok above"),
+                                        value_hash(value).unwrap_add_cost(&mut cost),
+                                        min,
+                                        max,
+                                    )
+                                } else if let Some(sum) = sum_for_ref {
+                                    Node::KVRefValueHashSum(
+                                        key.to_owned(),
+                                        serialized_referenced_elem.expect("confirmed ok above"),
+                                        value_hash(value).unwrap_add_cost(&mut cost),
+                                        sum,
+                                    )
                                 } else {
                                     Node::KVRefValueHash(
                                         key.to_owned(),
@@ -388,6 +813,8 @@ impl GroveDb {
                             Ok(Element::Item(..))
                             | Ok(Element::SumItem(..))
                             | Ok(Element::ItemWithSumItem(..))
+                            | Ok(Element::MinMaxItem(..))
+                            | Ok(Element::ItemWithExpiry(..))
                                 if !done_with_results =>
                             {
                                 #[cfg(feature = "proof_debug")]
@@ -412,6 +839,9 @@ impl GroveDb {
                             | Ok(Element::CountSumTree(Some(_), ..))
                             | Ok(Element::ProvableCountTree(Some(_), ..))
                             | Ok(Element::ProvableCountSumTree(Some(_), ..))
+                            | Ok(Element::MinMaxTree(Some(_), ..))
+                            | Ok(Element::ProvableSumTree(Some(_), ..))
+                            | Ok(Element::ProvableBigSumTree(Some(_), ..))
                             | Ok(Element::CommitmentTree(..))
                                 if !done_with_results
                                     && query.has_subquery_or_matching_in_path_on_key(key) =>
@@ -470,6 +900,22 @@ impl GroveDb {
                                 .wrap_with_cost(cost);
                             }
 
+                            // An empty tree with a subquery is a subquery
+                            // without results, only counted if asked to
+                            Ok(Element::Tree(None, _))
+                            | Ok(Element::SumTree(None, ..))
+                            | Ok(Element::BigSumTree(None, ..))
+                            | Ok(Element::CountTree(None, ..))
+                            | Ok(Element::ProvableCountTree(None, ..))
+                            | Ok(Element::CountSumTree(None, ..))
+                            | Ok(Element::ProvableCountSumTree(None, ..))
+                            | Ok(Element::MinMaxTree(None, ..))
+                            | Ok(Element::ProvableSumTree(None, ..))
+                            | Ok(Element::ProvableBigSumTree(None, ..))
+                                if !done_with_results
+                                    && !prove_options.decrease_limit_on_empty_sub_query_result
+                                    && query.has_subquery_or_matching_in_path_on_key(key) => {}
+
                             // V0 proofs do not inject child hashes for
                             // non-empty trees without subqueries.  The node
                             // stays as-is (KVValueHashFeatureType etc.) and
@@ -481,6 +927,9 @@ impl GroveDb {
                             | Ok(Element::ProvableCountTree(..))
                             | Ok(Element::CountSumTree(..))
                             | Ok(Element::ProvableCountSumTree(..))
+                            | Ok(Element::MinMaxTree(..))
+                            | Ok(Element::ProvableSumTree(..))
+                            | Ok(Element::ProvableBigSumTree(..))
                             | Ok(Element::CommitmentTree(..))
                             | Ok(Element::MmrTree(..))
                             | Ok(Element::BulkAppendTree(..))
@@ -523,7 +972,12 @@ impl GroveDb {
                             | Ok(Element::CommitmentTree(..))
                             | Ok(Element::MmrTree(..))
                             | Ok(Element::BulkAppendTree(..))
-                            | Ok(Element::DenseAppendOnlyFixedSizeTree(..)) => continue,
+                            | Ok(Element::DenseAppendOnlyFixedSizeTree(..))
+                            | Ok(Element::MinMaxItem(..))
+                            | Ok(Element::ItemWithExpiry(..))
+                            | Ok(Element::MinMaxTree(..))
+                            | Ok(Element::ProvableSumTree(..))
+                            | Ok(Element::ProvableBigSumTree(..)) => continue,
                             Err(e) => {
                                 return Err(Error::CorruptedData(format!(
                                     "failed to deserialize element during proof generation: {e}"
@@ -792,21 +1246,43 @@ impl GroveDb {
         encode_into(trunk_result.proof.iter(), &mut trunk_proof_encoded);
 
         // Start with the innermost LayerProof using ProofBytes::Merk
-        let mut current_layer = LayerProof {
+        let target_layer = LayerProof {
             merk_proof: ProofBytes::Merk(trunk_proof_encoded),
             lower_layers: BTreeMap::new(),
         };
 
+        let root_layer = cost_return_on_error!(
+            &mut cost,
+            self.prove_path_to_layer(&query.path, target_layer, &tx, grove_version)
+        );
+
+        Ok(GroveDBProof::V1(GroveDBProofV1 { root_layer })).wrap_with_cost(cost)
+    }
+
+    /// Nests `target_layer`, the proof of the tree at `path`, in the layers
+    /// proving each key of `path` from the root tree down.
+    fn prove_path_to_layer(
+        &self,
+        path: &[Vec<u8>],
+        target_layer: LayerProof,
+        tx: &Transaction,
+        grove_version: &GroveVersion,
+    ) -> CostResult<LayerProof, Error> {
+        let mut cost = OperationCost::default();
+
+        let path_slices: Vec<&[u8]> = path.iter().map(|p| p.as_slice()).collect();
+        let mut current_layer = target_layer;
+
         // Build nested LayerProofs from inside out (target -> root)
-        for i in (0..query.path.len()).rev() {
+        for i in (0..path.len()).rev() {
             let current_path: Vec<&[u8]> = path_slices[..i].to_vec();
-            let key = query.path[i].clone();
+            let key = path[i].clone();
 
             let subtree = cost_return_on_error!(
                 &mut cost,
                 self.open_transactional_merk_at_path(
                     current_path.as_slice().into(),
-                    &tx,
+                    tx,
                     None,
                     grove_version
                 )
@@ -830,10 +1306,7 @@ impl GroveDb {
             };
         }
 
-        Ok(GroveDBProof::V1(GroveDBProofV1 {
-            root_layer: current_layer,
-        }))
-        .wrap_with_cost(cost)
+        Ok(current_layer).wrap_with_cost(cost)
     }
 
     /// Generate a serialized branch chunk proof.
@@ -927,12 +1400,17 @@ impl GroveDb {
         let mut cost = OperationCost::default();
         let prove_options = prove_options.unwrap_or_default();
 
-        if path_query.query.offset.is_some() && path_query.query.offset != Some(0) {
+        // Offsets are proved with the accounting of version 1 of
+        // `path_query_push`, which `query_raw` only follows from then on
+        if path_query.query.offset.unwrap_or(0) > 0
+            && grove_version.grovedb_versions.element.path_query_push == 0
+        {
             return Err(Error::InvalidQuery(
-                "proved path queries can not have offsets",
+                "proved path queries can only have offsets from GroveVersion v4 on",
             ))
             .wrap_with_cost(cost);
         }
+
         if path_query.query.limit == Some(0) {
             return Err(Error::InvalidQuery(
                 "proved path queries can not be for limit 0",
@@ -941,6 +1419,7 @@ impl GroveDb {
         }
 
         let mut limit = path_query.query.limit;
+        let mut offset = path_query.query.offset.unwrap_or(0);
 
         let root_layer = cost_return_on_error!(
             &mut cost,
@@ -948,6 +1427,7 @@ impl GroveDb {
                 vec![],
                 path_query,
                 &mut limit,
+                &mut offset,
                 &prove_options,
                 0,
                 grove_version
@@ -959,11 +1439,15 @@ impl GroveDb {
 
     /// V1 version of prove_subqueries that returns `LayerProof` and handles
     /// MmrTree/BulkAppendTree elements with type-specific proofs.
+    ///
+    /// `overall_offset` counts the results still to skip. They are proven by
+    /// their key and value hash only.
     pub(crate) fn prove_subqueries_v1(
         &self,
         path: Vec<&[u8]>,
         path_query: &PathQuery,
         overall_limit: &mut Option<u16>,
+        overall_offset: &mut u16,
         prove_options: &ProveOptions,
         current_depth: usize,
         grove_version: &GroveVersion,
@@ -1000,25 +1484,33 @@ impl GroveDb {
             self.open_transactional_merk_at_path(path.as_slice().into(), &tx, None, grove_version)
         );
 
+        let (limit, offset) =
+            query.merk_limit_and_offset(*overall_limit, *overall_offset, prove_options);
         let limit = if path.len() < path_query.path.len() {
             None
         } else {
-            *overall_limit
+            limit
         };
 
-        let mut merk_proof = cost_return_on_error!(
+        let (mut merk_proof, offset_left) = cost_return_on_error!(
             &mut cost,
-            self.generate_merk_proof(
+            self.generate_merk_proof_with_offset(
                 &subtree,
                 &query.items,
                 query.left_to_right,
                 limit,
+                offset,
                 grove_version
             )
         );
+        if offset > 0 {
+            *overall_offset = offset_left;
+        }
 
         let mut lower_layers = BTreeMap::new();
-        let mut has_a_result_at_level = false;
+        // Matches skipped by the offset are taken from it and not from the
+        // limit, like results
+        let mut has_a_result_at_level = offset_left < offset;
         let mut done_with_results = false;
 
         for op in merk_proof.proof.iter_mut() {
@@ -1029,6 +1521,10 @@ impl GroveDb {
                     | Op::PushInverted(Node::KVValueHashFeatureType(..))
                     | Op::Push(Node::KVCount(..))
                     | Op::PushInverted(Node::KVCount(..))
+                    | Op::Push(Node::KVMinMax(..))
+                    | Op::PushInverted(Node::KVMinMax(..))
+                    | Op::Push(Node::KVSum(..))
+                    | Op::PushInverted(Node::KVSum(..))
             );
             let count_for_ref = match op {
                 Op::Push(Node::KVValueHashFeatureType(_, _, _, ft))
@@ -1038,16 +1534,45 @@ impl GroveDb {
                 },
                 _ => None,
             };
+            // Extract min and max if present for MinMaxTree references
+            let min_max_for_ref = match op {
+                Op::Push(Node::KVValueHashFeatureType(_, _, _, ft))
+                | Op::PushInverted(Node::KVValueHashFeatureType(_, _, _, ft)) => ft.min_max(),
+                _ => None,
+            };
+            // Extract sum if present for ProvableSumTree references
+            let sum_for_ref = match op {
+                Op::Push(Node::KVValueHashFeatureType(_, _, _, ft))
+                | Op::PushInverted(Node::KVValueHashFeatureType(_, _, _, ft)) => ft.provable_sum(),
+                _ => None,
+            };
 
             match op {
                 Op::Push(node) | Op::PushInverted(node) => match node {
                     Node::KV(key, value)
                     | Node::KVValueHash(key, value, ..)
                     | Node::KVCount(key, value, _)
+                    | Node::KVMinMax(key, value, ..)
+                    | Node::KVSum(key, value, _)
                     | Node::KVValueHashFeatureType(key, value, ..)
                         if !done_with_results =>
                     {
                         let elem = Element::deserialize(value, grove_version);
+                        if *overall_offset > 0
+                            && matches!(
+                                elem,
+                                Ok(Element::MmrTree(..)
+                                    | Element::BulkAppendTree(..)
+                                    | Element::DenseAppendOnlyFixedSizeTree(..)
+                                    | Element::CommitmentTree(..))
+                            )
+                            && query.has_subquery_or_matching_in_path_on_key(key)
+                        {
+                            return Err(Error::NotSupported(
+                                "proved offsets can not skip results of non-Merk trees".to_string(),
+                            ))
+                            .wrap_with_cost(cost);
+                        }
                         match elem {
                             Ok(Element::Reference(reference_path, ..)) => {
                                 let absolute_path = cost_return_on_error_into!(
@@ -1086,6 +1611,21 @@ impl GroveDb {
                                         value_hash(value).unwrap_add_cost(&mut cost),
                                         count,
                                     )
+                                } else if let Some((min, max)) = min_max_for_ref {
+                                    Node::KVRefValueHashMinMax(
+                                        key.to_owned(),
+                                        serialized_referenced_elem.expect("confirmed ok above"),
+                                        value_hash(value).unwrap_add_cost(&mut cost),
+                                        min,
+                                        max,
+                                    )
+                                } else if let Some(sum) = sum_for_ref {
+                                    Node::KVRefValueHashSum(
+                                        key.to_owned(),
+                                        serialized_referenced_elem.expect("confirmed ok above"),
+                                        value_hash(value).unwrap_add_cost(&mut cost),
+                                        sum,
+                                    )
                                 } else {
                                     Node::KVRefValueHash(
                                         key.to_owned(),
@@ -1101,6 +1641,8 @@ impl GroveDb {
                             Ok(Element::Item(..))
                             | Ok(Element::SumItem(..))
                             | Ok(Element::ItemWithSumItem(..))
+                            | Ok(Element::MinMaxItem(..))
+                            | Ok(Element::ItemWithExpiry(..))
                                 if !done_with_results =>
                             {
                                 if !should_preserve_node_type {
@@ -1227,6 +1769,9 @@ impl GroveDb {
                             | Ok(Element::CountSumTree(Some(_), ..))
                             | Ok(Element::ProvableCountTree(Some(_), ..))
                             | Ok(Element::ProvableCountSumTree(Some(_), ..))
+                            | Ok(Element::MinMaxTree(Some(_), ..))
+                            | Ok(Element::ProvableSumTree(Some(_), ..))
+                            | Ok(Element::ProvableBigSumTree(Some(_), ..))
                                 if !done_with_results
                                     && query.has_subquery_or_matching_in_path_on_key(key) =>
                             {
@@ -1234,6 +1779,7 @@ impl GroveDb {
                                 lower_path.push(key.as_slice());
 
                                 let previous_limit = *overall_limit;
+                                let previous_offset = *overall_offset;
 
                                 let layer_proof = cost_return_on_error!(
                                     &mut cost,
@@ -1241,13 +1787,16 @@ impl GroveDb {
                                         lower_path,
                                         path_query,
                                         overall_limit,
+                                        overall_offset,
                                         prove_options,
                                         current_depth + 1,
                                         grove_version,
                                     )
                                 );
 
-                                if previous_limit != *overall_limit {
+                                if previous_limit != *overall_limit
+                                    || previous_offset != *overall_offset
+                                {
                                     has_a_result_at_level |= true;
                                 }
                                 lower_layers.insert(key.clone(), layer_proof);
@@ -1273,6 +1822,9 @@ impl GroveDb {
                             | Ok(Element::ProvableCountTree(Some(_), ..))
                             | Ok(Element::CountSumTree(Some(_), ..))
                             | Ok(Element::ProvableCountSumTree(Some(_), ..))
+                            | Ok(Element::MinMaxTree(Some(_), ..))
+                            | Ok(Element::ProvableSumTree(Some(_), ..))
+                            | Ok(Element::ProvableBigSumTree(Some(_), ..))
                                 if !done_with_results =>
                             {
                                 // Non-empty tree without subquery: inject child
@@ -1319,6 +1871,22 @@ impl GroveDb {
                                 }
                                 has_a_result_at_level |= true;
                             }
+                            // An empty tree with a subquery is a subquery
+                            // without results, only counted if asked to
+                            Ok(Element::Tree(None, _))
+                            | Ok(Element::SumTree(None, ..))
+                            | Ok(Element::BigSumTree(None, ..))
+                            | Ok(Element::CountTree(None, ..))
+                            | Ok(Element::ProvableCountTree(None, ..))
+                            | Ok(Element::CountSumTree(None, ..))
+                            | Ok(Element::ProvableCountSumTree(None, ..))
+                            | Ok(Element::MinMaxTree(None, ..))
+                            | Ok(Element::ProvableSumTree(None, ..))
+                            | Ok(Element::ProvableBigSumTree(None, ..))
+                                if !done_with_results
+                                    && !prove_options.decrease_limit_on_empty_sub_query_result
+                                    && query.has_subquery_or_matching_in_path_on_key(key) => {}
+
                             // Empty trees and CommitmentTree without subquery
                             Ok(Element::Tree(None, _))
                             | Ok(Element::SumTree(None, ..))
@@ -1327,6 +1895,9 @@ impl GroveDb {
                             | Ok(Element::ProvableCountTree(None, ..))
                             | Ok(Element::CountSumTree(None, ..))
                             | Ok(Element::ProvableCountSumTree(None, ..))
+                            | Ok(Element::MinMaxTree(None, ..))
+                            | Ok(Element::ProvableSumTree(None, ..))
+                            | Ok(Element::ProvableBigSumTree(None, ..))
                             | Ok(Element::CommitmentTree(..))
                                 if !done_with_results =>
                             {
@@ -1352,7 +1923,12 @@ impl GroveDb {
                             | Ok(Element::CommitmentTree(..))
                             | Ok(Element::MmrTree(..))
                             | Ok(Element::BulkAppendTree(..))
-                            | Ok(Element::DenseAppendOnlyFixedSizeTree(..)) => continue,
+                            | Ok(Element::DenseAppendOnlyFixedSizeTree(..))
+                            | Ok(Element::MinMaxItem(..))
+                            | Ok(Element::ItemWithExpiry(..))
+                            | Ok(Element::MinMaxTree(..))
+                            | Ok(Element::ProvableSumTree(..))
+                            | Ok(Element::ProvableBigSumTree(..)) => continue,
                             Err(e) => {
                                 return Err(Error::CorruptedData(format!(
                                     "failed to deserialize element during proof generation: {e}"
@@ -2071,6 +2647,47 @@ impl GroveDb {
     }
 }
 
+/// Reads the aggregates of the results of a path query from the database,
+/// proving every read.
+struct ProvedAggregateSource<'a> {
+    stored: StoredAggregateSource<'a>,
+    proofs: Vec<Vec<u8>>,
+}
+
+impl ProvedAggregateSource<'_> {
+    fn prove(&mut self, path_query: &PathQuery) -> CostResult<(), Error> {
+        self.stored
+            .db
+            .prove_query(path_query, None, self.stored.grove_version)
+            .map_ok(|proof| self.proofs.push(proof))
+    }
+}
+
+impl AggregateSource for ProvedAggregateSource<'_> {
+    fn layer_elements(
+        &mut self,
+        path: &Path,
+        query: &Query,
+    ) -> CostResult<Vec<(Key, Element)>, Error> {
+        let mut cost = OperationCost::default();
+        cost_return_on_error!(&mut cost, self.prove(&layer_path_query(path, query)));
+        self.stored.layer_elements(path, query).add_cost(cost)
+    }
+
+    fn element(&mut self, path: &Path, key: &[u8]) -> CostResult<Option<Element>, Error> {
+        let mut cost = OperationCost::default();
+        cost_return_on_error!(&mut cost, self.prove(&element_path_query(path, key)));
+        self.stored.element(path, key).add_cost(cost)
+    }
+
+    fn tree_ends(&mut self, path: &Path) -> CostResult<Option<(Key, Key)>, Error> {
+        let mut cost = OperationCost::default();
+        cost_return_on_error!(&mut cost, self.prove(&tree_end_path_query(path, true)));
+        cost_return_on_error!(&mut cost, self.prove(&tree_end_path_query(path, false)));
+        self.stored.tree_ends(path).add_cost(cost)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use grovedb_merk::proofs::query::QueryItem;
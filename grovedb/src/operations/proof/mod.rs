@@ -53,7 +53,9 @@ use crate::{
 /// results than actually exist.
 ///
 /// [`GroveDBProofV1`] does **not** embed `ProveOptions`. The verifier uses
-/// [`ProveOptions::default()`] instead, closing this attack vector.
+/// [`ProveOptions::default()`] instead, or the options pages are always
+/// proved with for [`GroveDb::verify_query_page`](crate::GroveDb::verify_query_page),
+/// closing this attack vector.
 #[derive(Debug, Clone, Copy, Encode, Decode)]
 pub struct ProveOptions {
     /// This tells the proof system to decrease the available limit of the query
@@ -724,6 +726,56 @@ fn node_to_string(node: &Node) -> Result<String, fmt::Error> {
             hex::encode(value_hash),
             count
         ),
+        Node::KVMinMax(key, value, min, max) => format!(
+            "KVMinMax({}, {}, {}, {})",
+            hex_to_ascii(key),
+            element_hex_to_ascii(value)?,
+            min,
+            max
+        ),
+        Node::KVHashMinMax(kv_hash, min, max) => format!(
+            "KVHashMinMax(HASH[{}], {}, {})",
+            hex::encode(kv_hash),
+            min,
+            max
+        ),
+        Node::KVRefValueHashMinMax(key, value, value_hash, min, max) => format!(
+            "KVRefValueHashMinMax({}, {}, HASH[{}], {}, {})",
+            hex_to_ascii(key),
+            element_hex_to_ascii(value)?,
+            hex::encode(value_hash),
+            min,
+            max
+        ),
+        Node::KVDigestMinMax(key, value_hash, min, max) => format!(
+            "KVDigestMinMax({}, HASH[{}], {}, {})",
+            hex_to_ascii(key),
+            hex::encode(value_hash),
+            min,
+            max
+        ),
+        Node::KVSum(key, value, sum) => format!(
+            "KVSum({}, {}, {})",
+            hex_to_ascii(key),
+            element_hex_to_ascii(value)?,
+            sum
+        ),
+        Node::KVHashSum(kv_hash, sum) => {
+            format!("KVHashSum(HASH[{}], {})", hex::encode(kv_hash), sum)
+        }
+        Node::KVRefValueHashSum(key, value, value_hash, sum) => format!(
+            "KVRefValueHashSum({}, {}, HASH[{}], {})",
+            hex_to_ascii(key),
+            element_hex_to_ascii(value)?,
+            hex::encode(value_hash),
+            sum
+        ),
+        Node::KVDigestSum(key, value_hash, sum) => format!(
+            "KVDigestSum({}, HASH[{}], {})",
+            hex_to_ascii(key),
+            hex::encode(value_hash),
+            sum
+        ),
         Node::KVValueHashFeatureTypeWithChildHash(
             key,
             value,
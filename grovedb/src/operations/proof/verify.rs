@@ -1,11 +1,16 @@
 use std::collections::{BTreeMap, BTreeSet};
 
+use grovedb_costs::{CostResult, CostsExt, OperationCost};
 use grovedb_merk::{
     calculate_chunk_depths, calculate_max_tree_depth_from_count,
     element::tree_type::ElementTreeTypeExtensions,
     proofs::{
         execute,
-        query::{PathKey, QueryProofVerify, VerifyOptions, PROOF_VERSION_LATEST},
+        query::{
+            verify_key_at_rank_proof, verify_range_count_proof, verify_range_sum_proof,
+            verify_rank_of_key_proof, PathKey, QueryItem, QueryProofVerify, VerifyOptions,
+            PROOF_VERSION_LATEST,
+        },
         Decoder, Node, Op, Query,
     },
     tree::{combine_hash, value_hash, NULL_HASH},
@@ -14,22 +19,33 @@ use grovedb_merk::{
 use grovedb_version::{
     check_grovedb_v0, version::GroveVersion, TryFromVersioned, TryIntoVersioned,
 };
+use indexmap::IndexMap;
 
 #[cfg(feature = "proof_debug")]
 use crate::operations::proof::util::{
     hex_to_ascii, path_as_slices_hex_to_ascii, path_hex_to_ascii,
 };
 use crate::{
-    operations::proof::{
-        util::{ProvedPathKeyOptionalValue, ProvedPathKeyValues},
-        GroveDBProof, GroveDBProofV0, GroveDBProofV1, LayerProof, MerkOnlyLayerProof, ProofBytes,
-        ProveOptions,
+    operations::{
+        proof::{
+            util::{ProvedPathKeyOptionalValue, ProvedPathKeyValues},
+            GroveDBProof, GroveDBProofV0, GroveDBProofV1, LayerProof, MerkOnlyLayerProof,
+            ProofBytes, ProveOptions,
+        },
+        subtree_aggregate::{
+            aggregate_subtree_results, element_path_query, layer_path_query, tree_end_path_query,
+            AggregateSource,
+        },
     },
     query::{GroveTrunkQueryResult, PathTrunkChunkQuery},
-    query_result_type::PathKeyOptionalElementTrio,
-    Element, Error, GroveDb, PathQuery,
+    query_result_type::{Key, Path, PathKeyOptionalElementTrio, SubtreeAggregate},
+    Element, Error, GroveDb, PathQuery, QueryCursor,
 };
 
+/// A layer of a proved path: its root hash, the serialized tree element
+/// found in it and the proved value hash of that element.
+type ProvedPathLayer = (CryptoHash, Vec<u8>, CryptoHash);
+
 impl GroveDb {
     /// Verifies a proof against a path query with the given options, returning
     /// the root hash and deserialized results.
@@ -52,13 +68,12 @@ impl GroveDb {
             query.query.limit.ok_or(Error::NotSupported(
                 "limits must be set in verify_query_with_absence_proof".to_string(),
             ))?;
-        }
-
-        // must have no offset
-        if query.query.offset.is_some() {
-            return Err(Error::NotSupported(
-                "offsets in path queries are not supported for proofs".to_string(),
-            ));
+            // and no offset, absence proofs are for the first terminal keys
+            if query.query.offset.is_some() {
+                return Err(Error::NotSupported(
+                    "offsets are not supported with absence proofs".to_string(),
+                ));
+            }
         }
 
         let config = bincode::config::standard()
@@ -101,13 +116,12 @@ impl GroveDb {
             query.query.limit.ok_or(Error::NotSupported(
                 "limits must be set in verify_query_with_absence_proof".to_string(),
             ))?;
-        }
-
-        // must have no offset
-        if query.query.offset.is_some() {
-            return Err(Error::NotSupported(
-                "offsets in path queries are not supported for proofs".to_string(),
-            ));
+            // and no offset, absence proofs are for the first terminal keys
+            if query.query.offset.is_some() {
+                return Err(Error::NotSupported(
+                    "offsets are not supported with absence proofs".to_string(),
+                ));
+            }
         }
 
         let config = bincode::config::standard()
@@ -181,9 +195,13 @@ impl GroveDb {
             GroveDBProof::V0(proof_v0) => {
                 Self::verify_proof_v0_internal(proof_v0, query, options, grove_version)
             }
-            GroveDBProof::V1(proof_v1) => {
-                Self::verify_proof_v1_internal(proof_v1, query, options, grove_version)
-            }
+            GroveDBProof::V1(proof_v1) => Self::verify_proof_v1_internal(
+                proof_v1,
+                query,
+                options,
+                ProveOptions::default(),
+                grove_version,
+            ),
         }
     }
 
@@ -200,6 +218,12 @@ impl GroveDb {
         ),
         Error,
     > {
+        if query.query.offset.unwrap_or(0) > 0 {
+            return Err(Error::NotSupported(
+                "offsets are only supported by V1 proofs".to_string(),
+            ));
+        }
+
         let mut result = Vec::new();
         let mut limit = query.query.limit;
         let mut last_tree_feature_type = None;
@@ -296,6 +320,12 @@ impl GroveDb {
         options: VerifyOptions,
         grove_version: &GroveVersion,
     ) -> Result<(CryptoHash, Option<TreeFeatureType>, ProvedPathKeyValues), Error> {
+        if query.query.offset.unwrap_or(0) > 0 {
+            return Err(Error::NotSupported(
+                "offsets are only supported by V1 proofs".to_string(),
+            ));
+        }
+
         let mut result = Vec::new();
         let mut limit = query.query.limit;
         let mut last_tree_feature_type = None;
@@ -318,6 +348,7 @@ impl GroveDb {
         proof: &GroveDBProofV1,
         query: &PathQuery,
         options: VerifyOptions,
+        prove_options: ProveOptions,
         grove_version: &GroveVersion,
     ) -> Result<
         (
@@ -327,20 +358,29 @@ impl GroveDb {
         ),
         Error,
     > {
-        // V1 proofs do not embed ProveOptions — the verifier uses the default,
-        // which closes the result-truncation attack where a malicious prover
-        // could set decrease_limit_on_empty_sub_query_result to manipulate
-        // how many results the verifier returns.
-        let prove_options = ProveOptions::default();
+        // V1 proofs do not embed ProveOptions — the verifier chooses them,
+        // the default unless it knows better, which closes the
+        // result-truncation attack where a malicious prover could set
+        // decrease_limit_on_empty_sub_query_result to manipulate how many
+        // results the verifier returns.
+        if query.query.offset.unwrap_or(0) > 0
+            && grove_version.grovedb_versions.element.path_query_push == 0
+        {
+            return Err(Error::NotSupported(
+                "proved path queries can only have offsets from GroveVersion v4 on".to_string(),
+            ));
+        }
 
         let mut result = Vec::new();
         let mut limit = query.query.limit;
+        let mut offset = query.query.offset.unwrap_or(0);
         let mut last_tree_feature_type = None;
         let root_hash = Self::verify_layer_proof_v1(
             &proof.root_layer,
             &prove_options,
             query,
             &mut limit,
+            &mut offset,
             &[],
             &mut result,
             &mut last_tree_feature_type,
@@ -381,14 +421,24 @@ impl GroveDb {
     ) -> Result<(CryptoHash, Option<TreeFeatureType>, ProvedPathKeyValues), Error> {
         let prove_options = ProveOptions::default();
 
+        if query.query.offset.unwrap_or(0) > 0
+            && grove_version.grovedb_versions.element.path_query_push == 0
+        {
+            return Err(Error::NotSupported(
+                "proved path queries can only have offsets from GroveVersion v4 on".to_string(),
+            ));
+        }
+
         let mut result = Vec::new();
         let mut limit = query.query.limit;
+        let mut offset = query.query.offset.unwrap_or(0);
         let mut last_tree_feature_type = None;
         let root_hash = Self::verify_layer_proof_v1(
             &proof.root_layer,
             &prove_options,
             query,
             &mut limit,
+            &mut offset,
             &[],
             &mut result,
             &mut last_tree_feature_type,
@@ -404,6 +454,7 @@ impl GroveDb {
         prove_options: &ProveOptions,
         query: &PathQuery,
         limit_left: &mut Option<u16>,
+        offset_left: &mut u16,
         current_path: &[&[u8]],
         result: &mut Vec<T>,
         last_parent_tree_type: &mut Option<TreeFeatureType>,
@@ -453,10 +504,13 @@ impl GroveDb {
             ..Default::default()
         };
 
+        let (merk_limit, merk_offset) =
+            internal_query.merk_limit_and_offset(*limit_left, *offset_left, prove_options);
         let (root_hash, merk_result) = level_query
-            .execute_proof(
+            .execute_proof_with_offset(
                 merk_proof_bytes,
-                *limit_left,
+                merk_limit,
+                merk_offset,
                 internal_query.left_to_right,
                 PROOF_VERSION_LATEST, // V1 proof: strict mode rejects items in value hash nodes
             )
@@ -467,10 +521,15 @@ impl GroveDb {
                     format!("Invalid V1 proof verification parameters: {}", e),
                 )
             })?;
+        if merk_offset > 0 {
+            *offset_left = merk_result.offset;
+        }
 
         let mut verified_keys = BTreeSet::new();
 
-        if merk_result.result_set.is_empty() {
+        // Matches skipped by the offset are taken from it and not from the
+        // limit, like results
+        if merk_result.result_set.is_empty() && merk_result.offset == merk_offset {
             if prove_options.decrease_limit_on_empty_sub_query_result {
                 limit_left
                     .iter_mut()
@@ -497,6 +556,9 @@ impl GroveDb {
                             | Element::CountSumTree(Some(_), ..)
                             | Element::ProvableCountTree(Some(_), ..)
                             | Element::ProvableCountSumTree(Some(_), ..)
+                            | Element::MinMaxTree(Some(_), ..)
+                            | Element::ProvableSumTree(Some(_), ..)
+                            | Element::ProvableBigSumTree(Some(_), ..)
                             | Element::CommitmentTree(..)
                             | Element::MmrTree(..)
                             | Element::BulkAppendTree(..)
@@ -540,6 +602,16 @@ impl GroveDb {
                                         );
                                     }
 
+                                    if *offset_left > 0
+                                        && !matches!(lower_layer.merk_proof, ProofBytes::Merk(_))
+                                    {
+                                        return Err(Error::NotSupported(
+                                            "proved offsets can not skip results of non-Merk \
+                                             trees"
+                                                .to_string(),
+                                        ));
+                                    }
+
                                     // Dispatch based on lower layer proof type
                                     let lower_hash = match &lower_layer.merk_proof {
                                         ProofBytes::Merk(_) => {
@@ -549,6 +621,7 @@ impl GroveDb {
                                                 prove_options,
                                                 query,
                                                 limit_left,
+                                                offset_left,
                                                 &path,
                                                 result,
                                                 last_parent_tree_type,
@@ -630,9 +703,14 @@ impl GroveDb {
                             | Element::CountSumTree(None, ..)
                             | Element::ProvableCountTree(None, ..)
                             | Element::ProvableCountSumTree(None, ..)
+                            | Element::MinMaxTree(None, ..)
+                            | Element::ProvableSumTree(None, ..)
+                            | Element::ProvableBigSumTree(None, ..)
                             | Element::SumItem(..)
                             | Element::Item(..)
                             | Element::ItemWithSumItem(..)
+                            | Element::MinMaxItem(..)
+                            | Element::ItemWithExpiry(..)
                             | Element::Reference(..) => {
                                 return Err(Error::InvalidProof(
                                     query.clone(),
@@ -1462,7 +1540,10 @@ impl GroveDb {
                             | Element::CountTree(Some(_), ..)
                             | Element::CountSumTree(Some(_), ..)
                             | Element::ProvableCountTree(Some(_), ..)
-                            | Element::ProvableCountSumTree(Some(_), ..) => {
+                            | Element::ProvableCountSumTree(Some(_), ..)
+                            | Element::MinMaxTree(Some(_), ..)
+                            | Element::ProvableSumTree(Some(_), ..)
+                            | Element::ProvableBigSumTree(Some(_), ..) => {
                                 path.push(key);
                                 *last_parent_tree_type = element.tree_feature_type();
                                 if query.query_items_at_path(&path, grove_version)?.is_none() {
@@ -1589,9 +1670,14 @@ impl GroveDb {
                             | Element::MmrTree(..)
                             | Element::BulkAppendTree(..)
                             | Element::DenseAppendOnlyFixedSizeTree(..)
+                            | Element::MinMaxTree(None, ..)
+                            | Element::ProvableSumTree(None, ..)
+                            | Element::ProvableBigSumTree(None, ..)
                             | Element::SumItem(..)
                             | Element::Item(..)
                             | Element::ItemWithSumItem(..)
+                            | Element::MinMaxItem(..)
+                            | Element::ItemWithExpiry(..)
                             | Element::Reference(..) => {
                                 return Err(Error::InvalidProof(
                                     query.clone(),
@@ -1711,6 +1797,387 @@ impl GroveDb {
         )
     }
 
+    /// Verifies a proof made by [`GroveDb::prove_query_page`] with the same
+    /// path query, cursor and page size, returning the root hash, the results
+    /// of the page and the cursor of the next one.
+    ///
+    /// Subqueries without results are not counted against the page size, as
+    /// when proving the page.
+    pub fn verify_query_page(
+        proof: &[u8],
+        query: &PathQuery,
+        cursor: Option<&QueryCursor>,
+        page_size: u16,
+        grove_version: &GroveVersion,
+    ) -> Result<
+        (
+            CryptoHash,
+            Vec<PathKeyOptionalElementTrio>,
+            Option<QueryCursor>,
+        ),
+        Error,
+    > {
+        let page_query = query.page(cursor, page_size)?;
+
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_limit::<{ 256 * 1024 * 1024 }>();
+        let grovedb_proof: GroveDBProof = bincode::decode_from_slice(proof, config)
+            .map_err(|e| Error::CorruptedData(format!("unable to decode proof: {}", e)))?
+            .0;
+        let options = VerifyOptions {
+            absence_proofs_for_non_existing_searched_keys: false,
+            verify_proof_succinctness: true,
+            include_empty_trees_in_result: false,
+        };
+        let GroveDBProof::V1(proof_v1) = &grovedb_proof else {
+            return Err(Error::NotSupported(
+                "query pages are only proved by V1 proofs".to_string(),
+            ));
+        };
+        let (root_hash, _, results) = Self::verify_proof_v1_internal(
+            proof_v1,
+            &page_query,
+            options,
+            PathQuery::PAGE_PROVE_OPTIONS,
+            grove_version,
+        )?;
+        let next_cursor = QueryCursor::after_page(query, page_size, &results)?;
+        Ok((root_hash, results, next_cursor))
+    }
+
+    /// Verifies a proof made by [`GroveDb::prove_subtree_aggregates`] with
+    /// the same path query, returning the root hash and the aggregates of
+    /// the results in every subtree they are found in.
+    pub fn verify_subtree_aggregates(
+        proof: &[u8],
+        query: &PathQuery,
+        grove_version: &GroveVersion,
+    ) -> Result<(CryptoHash, Vec<SubtreeAggregate>), Error> {
+        if query.query.limit.is_some() || query.query.offset.is_some() {
+            return Err(Error::InvalidQuery(
+                "subtree aggregates can not be limited or offset",
+            ));
+        }
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+        let proofs: Vec<Vec<u8>> = bincode::decode_from_slice(proof, config)
+            .map_err(|e| Error::CorruptedData(format!("unable to decode proof: {}", e)))?
+            .0;
+
+        let mut source = VerifiedAggregateSource {
+            query,
+            proofs: proofs.iter(),
+            root_hash: None,
+            grove_version,
+        };
+        let mut aggregates = IndexMap::new();
+        aggregate_subtree_results(
+            &mut source,
+            query.path.clone(),
+            &query.query.query,
+            &mut aggregates,
+        )
+        .unwrap()?;
+        if source.proofs.next().is_some() {
+            return Err(Error::InvalidProof(
+                query.clone(),
+                "the proof holds more reads than the aggregates are made of".to_string(),
+            ));
+        }
+        let root_hash = source.root_hash.ok_or(Error::CorruptedCodeExecution(
+            "aggregates always read the queried subtree",
+        ))?;
+        Ok((root_hash, aggregates.into_values().collect()))
+    }
+
+    /// Verifies a proof made by [`GroveDb::prove_query_keys`] with the same
+    /// path query, returning the root hash and the keys found along with
+    /// their value hashes.
+    pub fn verify_query_keys(
+        proof: &[u8],
+        query: &PathQuery,
+        grove_version: &GroveVersion,
+    ) -> Result<(CryptoHash, Vec<(Key, CryptoHash)>), Error> {
+        if query.query.query.has_subquery() || query.query.offset.is_some() {
+            return Err(Error::InvalidQuery(
+                "key proofs can not have subqueries or an offset",
+            ));
+        }
+        let root_layer = Self::decode_v1_root_layer(proof, query)?;
+        let (path_layers, _, target_layer) =
+            Self::verify_proved_path(&root_layer, query, grove_version)?;
+
+        // An empty tree has no keys proof, the hash chain checks it is empty.
+        let keys_proof = Self::merk_layer_bytes(target_layer, query)?;
+        let (target_hash, keys) = if keys_proof.is_empty() {
+            (NULL_HASH, Vec::new())
+        } else {
+            let (target_hash, result) = query
+                .query
+                .query
+                .execute_keys_proof(
+                    keys_proof,
+                    query.query.limit,
+                    query.query.query.left_to_right,
+                )
+                .unwrap()
+                .map_err(|e| Error::InvalidProof(query.clone(), e.to_string()))?;
+            let keys = result
+                .result_set
+                .into_iter()
+                .map(|proved| (proved.key, proved.proof))
+                .collect();
+            (target_hash, keys)
+        };
+
+        let root_hash = Self::chain_proved_path(query, path_layers, target_hash)?;
+        Ok((root_hash, keys))
+    }
+
+    /// Verifies a proof made by [`GroveDb::prove_count_in_range`] for the
+    /// same path and range, returning the root hash and the aggregate count
+    /// of the keys in the range.
+    pub fn verify_count_in_range(
+        proof: &[u8],
+        path: Vec<Vec<u8>>,
+        range: &QueryItem,
+        grove_version: &GroveVersion,
+    ) -> Result<(CryptoHash, u64), Error> {
+        Self::verify_single_tree_proof(
+            proof,
+            PathQuery::new_single_query_item(path, range.clone()),
+            |element| {
+                matches!(
+                    element,
+                    Element::ProvableCountTree(..) | Element::ProvableCountSumTree(..)
+                )
+            },
+            "range counts can only be proved in provable count trees",
+            |bytes| verify_range_count_proof(bytes, range),
+            0,
+            grove_version,
+        )
+    }
+
+    /// Verifies a proof made by [`GroveDb::prove_sum_in_range`] for the same
+    /// path and range, returning the root hash and the aggregate sum of the
+    /// elements in the range.
+    pub fn verify_sum_in_range(
+        proof: &[u8],
+        path: Vec<Vec<u8>>,
+        range: &QueryItem,
+        grove_version: &GroveVersion,
+    ) -> Result<(CryptoHash, i128), Error> {
+        Self::verify_single_tree_proof(
+            proof,
+            PathQuery::new_single_query_item(path, range.clone()),
+            |element| {
+                matches!(
+                    element,
+                    Element::ProvableSumTree(..) | Element::ProvableBigSumTree(..)
+                )
+            },
+            "range sums can only be proved in provable sum trees",
+            |bytes| verify_range_sum_proof(bytes, range),
+            0,
+            grove_version,
+        )
+    }
+
+    /// Verifies a proof made by [`GroveDb::prove_by_rank`] for the same path
+    /// and rank, returning the root hash and the key at the rank, or `None`
+    /// if the tree counts no more than `rank`.
+    pub fn verify_by_rank(
+        proof: &[u8],
+        path: Vec<Vec<u8>>,
+        rank: u64,
+        grove_version: &GroveVersion,
+    ) -> Result<(CryptoHash, Option<Vec<u8>>), Error> {
+        Self::verify_single_tree_proof(
+            proof,
+            PathQuery::new_single_query_item(path, QueryItem::RangeFull(..)),
+            |element| {
+                matches!(
+                    element,
+                    Element::ProvableCountTree(..) | Element::ProvableCountSumTree(..)
+                )
+            },
+            "ranks can only be proved in provable count trees",
+            |bytes| verify_key_at_rank_proof(bytes, rank),
+            None,
+            grove_version,
+        )
+    }
+
+    /// Verifies a proof made by [`GroveDb::prove_rank_of_key`] for the same
+    /// path and key, returning the root hash and the rank of the key, or
+    /// `None` if the key is not in the tree.
+    pub fn verify_rank_of_key(
+        proof: &[u8],
+        path: Vec<Vec<u8>>,
+        key: &[u8],
+        grove_version: &GroveVersion,
+    ) -> Result<(CryptoHash, Option<u64>), Error> {
+        Self::verify_single_tree_proof(
+            proof,
+            PathQuery::new_single_query_item(path, QueryItem::Key(key.to_vec())),
+            |element| {
+                matches!(
+                    element,
+                    Element::ProvableCountTree(..) | Element::ProvableCountSumTree(..)
+                )
+            },
+            "ranks can only be proved in provable count trees",
+            |bytes| verify_rank_of_key_proof(bytes, key),
+            None,
+            grove_version,
+        )
+    }
+
+    /// Verifies a proof made by `GroveDb::prove_in_single_tree` for the tree
+    /// at the path of `query`, checking the proof of the tree itself with
+    /// `verify_tree`. Trees whose element is not accepted by
+    /// `accepts_element` are rejected with `wrong_tree_type`, and empty trees
+    /// give `empty_result`.
+    fn verify_single_tree_proof<T>(
+        proof: &[u8],
+        query: PathQuery,
+        accepts_element: fn(&Element) -> bool,
+        wrong_tree_type: &str,
+        verify_tree: impl FnOnce(&[u8]) -> CostResult<(CryptoHash, T), grovedb_merk::Error>,
+        empty_result: T,
+        grove_version: &GroveVersion,
+    ) -> Result<(CryptoHash, T), Error> {
+        let root_layer = Self::decode_v1_root_layer(proof, &query)?;
+        let (path_layers, target_element, target_layer) =
+            Self::verify_proved_path(&root_layer, &query, grove_version)?;
+        if !target_element.as_ref().is_some_and(accepts_element) {
+            return Err(Error::InvalidProof(query, wrong_tree_type.to_string()));
+        }
+
+        let tree_proof = Self::merk_layer_bytes(target_layer, &query)?;
+        let (target_hash, result) = if tree_proof.is_empty() {
+            (NULL_HASH, empty_result)
+        } else {
+            verify_tree(tree_proof)
+                .unwrap()
+                .map_err(|e| Error::InvalidProof(query.clone(), e.to_string()))?
+        };
+
+        let root_hash = Self::chain_proved_path(&query, path_layers, target_hash)?;
+        Ok((root_hash, result))
+    }
+
+    /// Decodes a proof that must be a V1 proof, returning its root layer.
+    fn decode_v1_root_layer(proof: &[u8], query: &PathQuery) -> Result<LayerProof, Error> {
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_limit::<{ 256 * 1024 * 1024 }>();
+        let grovedb_proof: GroveDBProof = bincode::decode_from_slice(proof, config)
+            .map_err(|e| Error::CorruptedData(format!("unable to decode proof: {}", e)))?
+            .0;
+        let GroveDBProof::V1(GroveDBProofV1 { root_layer }) = grovedb_proof else {
+            return Err(Error::InvalidProof(
+                query.clone(),
+                "this proof must be a V1 proof".to_string(),
+            ));
+        };
+        Ok(root_layer)
+    }
+
+    fn merk_layer_bytes<'a>(layer: &'a LayerProof, query: &PathQuery) -> Result<&'a [u8], Error> {
+        match &layer.merk_proof {
+            ProofBytes::Merk(bytes) => Ok(bytes),
+            _ => Err(Error::InvalidProof(
+                query.clone(),
+                "the proof layers must be Merk proofs".to_string(),
+            )),
+        }
+    }
+
+    /// Verifies the single key proofs leading down the path of `query`.
+    ///
+    /// Returns, for each layer of the path, its root hash along with the tree
+    /// element found in it and that element's proved value hash, then the
+    /// last of these elements and the layer proving the target tree.
+    fn verify_proved_path<'a>(
+        root_layer: &'a LayerProof,
+        query: &PathQuery,
+        grove_version: &GroveVersion,
+    ) -> Result<(Vec<ProvedPathLayer>, Option<Element>, &'a LayerProof), Error> {
+        let mut path_layers = Vec::with_capacity(query.path.len());
+        let mut target_element = None;
+        let mut current_layer = root_layer;
+        for path_key in &query.path {
+            let key_query = Query::new_single_key(path_key.clone());
+            let (layer_root_hash, result) = key_query
+                .execute_proof(
+                    Self::merk_layer_bytes(current_layer, query)?,
+                    None,
+                    true,
+                    PROOF_VERSION_LATEST,
+                )
+                .unwrap()
+                .map_err(|e| Error::InvalidProof(query.clone(), e.to_string()))?;
+            let (value_bytes, expected_hash) = result
+                .result_set
+                .into_iter()
+                .find(|proved| &proved.key == path_key)
+                .and_then(|proved| Some((proved.value?, proved.proof)))
+                .ok_or_else(|| {
+                    Error::InvalidProof(
+                        query.clone(),
+                        format!("path key {} is not in the proof", hex::encode(path_key)),
+                    )
+                })?;
+            let element = Element::deserialize(&value_bytes, grove_version)?;
+            if !element.is_any_tree() || element.uses_non_merk_data_storage() {
+                return Err(Error::InvalidProof(
+                    query.clone(),
+                    format!("path key {} is not a merk tree", hex::encode(path_key)),
+                ));
+            }
+            path_layers.push((layer_root_hash, value_bytes, expected_hash));
+            target_element = Some(element);
+            current_layer = current_layer.lower_layers.get(path_key).ok_or_else(|| {
+                Error::InvalidProof(
+                    query.clone(),
+                    format!("missing lower layer for path key {}", hex::encode(path_key)),
+                )
+            })?;
+        }
+        Ok((path_layers, target_element, current_layer))
+    }
+
+    /// Chains the root hash of the target tree up through the proved path,
+    /// returning the root hash of the whole grove.
+    fn chain_proved_path(
+        query: &PathQuery,
+        path_layers: Vec<ProvedPathLayer>,
+        target_hash: CryptoHash,
+    ) -> Result<CryptoHash, Error> {
+        let mut lower_hash = target_hash;
+        for (layer_root_hash, value_bytes, expected_hash) in path_layers.into_iter().rev() {
+            let combined_hash = combine_hash(value_hash(&value_bytes).value(), &lower_hash)
+                .value()
+                .to_owned();
+            if combined_hash != expected_hash {
+                return Err(Error::InvalidProof(
+                    query.clone(),
+                    format!(
+                        "path proof hash mismatch: expected {}, got {}",
+                        hex::encode(expected_hash),
+                        hex::encode(combined_hash)
+                    ),
+                ));
+            }
+            lower_hash = layer_root_hash;
+        }
+        Ok(lower_hash)
+    }
+
     /// Verifies a proof against a path query without succinctness checks,
     /// allowing the proof to contain extra data beyond what the query requires.
     pub fn verify_subset_query(
@@ -2565,7 +3032,10 @@ impl GroveDb {
                     ));
                 }
             }
-            Node::KVRefValueHash(..) | Node::KVRefValueHashCount(..) => {
+            Node::KVRefValueHash(..)
+            | Node::KVRefValueHashCount(..)
+            | Node::KVRefValueHashMinMax(..)
+            | Node::KVRefValueHashSum(..) => {
                 // KVRefValueHash carries an opaque node_value_hash that cannot
                 // be recomputed from the value bytes alone. These node types
                 // should never appear in trunk/branch chunk proofs.
@@ -2650,13 +3120,21 @@ impl GroveDb {
             | Node::KVValueHashFeatureTypeWithChildHash(key, value, ..)
             | Node::KVCount(key, value, ..)
             | Node::KVRefValueHash(key, value, ..)
-            | Node::KVRefValueHashCount(key, value, ..) => Some((key.clone(), value.clone())),
+            | Node::KVRefValueHashCount(key, value, ..)
+            | Node::KVMinMax(key, value, ..)
+            | Node::KVRefValueHashMinMax(key, value, ..)
+            | Node::KVSum(key, value, _)
+            | Node::KVRefValueHashSum(key, value, ..) => Some((key.clone(), value.clone())),
             // These nodes don't have values, only key+hash or just hash
             Node::KVDigest(..)
             | Node::KVDigestCount(..)
+            | Node::KVDigestMinMax(..)
+            | Node::KVDigestSum(..)
             | Node::Hash(_)
             | Node::KVHash(_)
-            | Node::KVHashCount(..) => None,
+            | Node::KVHashCount(..)
+            | Node::KVHashMinMax(..)
+            | Node::KVHashSum(..) => None,
         }
     }
 
@@ -2751,3 +3229,77 @@ impl GroveDb {
         })
     }
 }
+
+/// Reads the aggregates of the results of a path query from a proof made by
+/// [`GroveDb::prove_subtree_aggregates`], checking every read proves the
+/// same root hash.
+struct VerifiedAggregateSource<'a> {
+    query: &'a PathQuery,
+    proofs: std::slice::Iter<'a, Vec<u8>>,
+    root_hash: Option<CryptoHash>,
+    grove_version: &'a GroveVersion,
+}
+
+impl VerifiedAggregateSource<'_> {
+    fn verify(&mut self, path_query: &PathQuery) -> Result<Vec<PathKeyOptionalElementTrio>, Error> {
+        let proof = self.proofs.next().ok_or_else(|| {
+            Error::InvalidProof(
+                self.query.clone(),
+                "the proof misses reads the aggregates are made of".to_string(),
+            )
+        })?;
+        let (root_hash, results) = GroveDb::verify_query(proof, path_query, self.grove_version)?;
+        if *self.root_hash.get_or_insert(root_hash) != root_hash {
+            return Err(Error::InvalidProof(
+                self.query.clone(),
+                "reads of the aggregates prove different root hashes".to_string(),
+            ));
+        }
+        Ok(results)
+    }
+}
+
+impl AggregateSource for VerifiedAggregateSource<'_> {
+    fn layer_elements(
+        &mut self,
+        path: &Path,
+        query: &Query,
+    ) -> CostResult<Vec<(Key, Element)>, Error> {
+        self.verify(&layer_path_query(path, query))
+            .map(|results| {
+                results
+                    .into_iter()
+                    .filter_map(|(_, key, element)| element.map(|element| (key, element)))
+                    .collect()
+            })
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn element(&mut self, path: &Path, key: &[u8]) -> CostResult<Option<Element>, Error> {
+        self.verify(&element_path_query(path, key))
+            .map(|results| {
+                results
+                    .into_iter()
+                    .find(|(_, result_key, _)| result_key == key)
+                    .and_then(|(_, _, element)| element)
+            })
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn tree_ends(&mut self, path: &Path) -> CostResult<Option<(Key, Key)>, Error> {
+        let mut first_key = |left_to_right| {
+            self.verify(&tree_end_path_query(path, left_to_right))
+                .map(|results| results.into_iter().next().map(|(_, key, _)| key))
+        };
+        match (first_key(true), first_key(false)) {
+            (Ok(Some(min_key)), Ok(Some(max_key))) => Ok(Some((min_key, max_key))),
+            (Ok(None), Ok(None)) => Ok(None),
+            (Err(e), _) | (_, Err(e)) => Err(e),
+            _ => Err(Error::InvalidProof(
+                self.query.clone(),
+                "a subtree has a first key but no last key".to_string(),
+            )),
+        }
+        .wrap_with_cost(OperationCost::default())
+    }
+}
@@ -0,0 +1,155 @@
+//! Aggregating the results of path queries by subtree.
+//!
+//! The same walk runs over the database, over the database while proving
+//! what it reads, and over such a proof, the three only differing in how
+//! they read elements, see [`AggregateSource`].
+
+use grovedb_costs::{cost_return_on_error, CostResult, CostsExt, OperationCost};
+use indexmap::IndexMap;
+
+use crate::{
+    query_result_type::{Key, Path, SubtreeAggregate},
+    Element, Error, PathQuery, Query, QueryItem, SizedQuery, SubqueryBranch,
+};
+
+/// Reads what the aggregates of the results of a path query are made of.
+/// Each read is the result of one of the path queries below.
+pub(crate) trait AggregateSource {
+    /// Returns the elements of [`layer_path_query`], in query order.
+    fn layer_elements(
+        &mut self,
+        path: &Path,
+        query: &Query,
+    ) -> CostResult<Vec<(Key, Element)>, Error>;
+
+    /// Returns the element of [`element_path_query`], if any.
+    fn element(&mut self, path: &Path, key: &[u8]) -> CostResult<Option<Element>, Error>;
+
+    /// Returns the smallest and the largest keys of the subtree at `path`,
+    /// which are the results of [`tree_end_path_query`], or `None` if the
+    /// subtree is empty.
+    fn tree_ends(&mut self, path: &Path) -> CostResult<Option<(Key, Key)>, Error>;
+}
+
+/// Path query of the elements of the subtree at `path` matching `query`,
+/// without its subqueries.
+pub(crate) fn layer_path_query(path: &Path, query: &Query) -> PathQuery {
+    let mut layer_query = query.clone();
+    layer_query.default_subquery_branch = SubqueryBranch::default();
+    layer_query.conditional_subquery_branches = None;
+    PathQuery::new_unsized(path.clone(), layer_query)
+}
+
+/// Path query of the element at `key` in the subtree at `path`.
+pub(crate) fn element_path_query(path: &Path, key: &[u8]) -> PathQuery {
+    PathQuery::new_single_key(path.clone(), key.to_vec())
+}
+
+/// Path query of the first element of the subtree at `path` in the given
+/// direction.
+pub(crate) fn tree_end_path_query(path: &Path, left_to_right: bool) -> PathQuery {
+    let mut query = Query::new_range_full();
+    query.left_to_right = left_to_right;
+    PathQuery::new(path.clone(), SizedQuery::new(query, Some(1), None))
+}
+
+/// Returns the subquery path and the subquery `query` applies to the tree at
+/// `key`.
+fn subquery_branch(query: &Query, key: &[u8]) -> (Option<Path>, Option<Query>) {
+    let branch = query
+        .conditional_subquery_branches
+        .iter()
+        .flatten()
+        .find(|(query_item, _)| query_item.contains(key))
+        .map(|(_, branch)| branch)
+        .unwrap_or(&query.default_subquery_branch);
+    (
+        branch.subquery_path.clone(),
+        branch.subquery.as_ref().map(|subquery| *subquery.clone()),
+    )
+}
+
+/// Adds the aggregates of the results of `query` in the subtree at `path`
+/// and its subqueries to `aggregates`.
+///
+/// Subqueries over the whole of a count sum tree take its count and sum
+/// from the tree element and its smallest and largest keys from the ends of
+/// the tree, without reading its elements.
+pub(crate) fn aggregate_subtree_results<S: AggregateSource>(
+    source: &mut S,
+    path: Path,
+    query: &Query,
+    aggregates: &mut IndexMap<Path, SubtreeAggregate>,
+) -> CostResult<(), Error> {
+    let mut cost = OperationCost::default();
+
+    let elements = cost_return_on_error!(&mut cost, source.layer_elements(&path, query));
+    for (key, element) in elements {
+        let (subquery_path, subquery) = if element.is_any_tree() {
+            subquery_branch(query, &key)
+        } else {
+            (None, None)
+        };
+        let mut subtree_path = path.clone();
+        subtree_path.push(key.clone());
+        match (subquery_path, subquery) {
+            (subquery_path, Some(subquery)) => {
+                let mut subtree_element = Some(element);
+                if let Some((last_key, front_keys)) =
+                    subquery_path.as_ref().and_then(|keys| keys.split_last())
+                {
+                    subtree_path.extend(front_keys.iter().cloned());
+                    subtree_element =
+                        cost_return_on_error!(&mut cost, source.element(&subtree_path, last_key));
+                    subtree_path.push(last_key.clone());
+                }
+                let whole_tree =
+                    subquery.items == [QueryItem::RangeFull(..)] && !subquery.has_subquery();
+                match subtree_element {
+                    Some(
+                        tree @ (Element::CountSumTree(..) | Element::ProvableCountSumTree(..)),
+                    ) if whole_tree => {
+                        if let Some((min_key, max_key)) =
+                            cost_return_on_error!(&mut cost, source.tree_ends(&subtree_path))
+                        {
+                            let (count, sum) = SubtreeAggregate::count_and_sum(&tree);
+                            SubtreeAggregate {
+                                path: subtree_path,
+                                count,
+                                sum,
+                                min_key,
+                                max_key,
+                            }
+                            .add_to(aggregates);
+                        }
+                    }
+                    _ => {
+                        cost_return_on_error!(
+                            &mut cost,
+                            aggregate_subtree_results(source, subtree_path, &subquery, aggregates)
+                        );
+                    }
+                }
+            }
+            (Some(subquery_path), None) => {
+                let Some((last_key, front_keys)) = subquery_path.split_last() else {
+                    return Err(Error::CorruptedCodeExecution(
+                        "subquery_paths can not be empty",
+                    ))
+                    .wrap_with_cost(cost);
+                };
+                subtree_path.extend(front_keys.iter().cloned());
+                if let Some(element) =
+                    cost_return_on_error!(&mut cost, source.element(&subtree_path, last_key))
+                {
+                    SubtreeAggregate::of_result(subtree_path, last_key.clone(), &element)
+                        .add_to(aggregates);
+                }
+            }
+            (None, None) => {
+                SubtreeAggregate::of_result(path.clone(), key, &element).add_to(aggregates)
+            }
+        }
+    }
+    Ok(()).wrap_with_cost(cost)
+}
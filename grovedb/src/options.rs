@@ -0,0 +1,108 @@
+//! Options used to open a GroveDb
+
+use grovedb_storage::rocksdb_storage::RocksDbOptions;
+pub use grovedb_storage::rocksdb_storage::{ColumnFamilyOptions, CompressionType};
+
+/// Options used to open a GroveDb with [`GroveDb::open_with_options`].
+///
+/// ```ignore
+/// let options = GroveDbOptions::default()
+///     .block_cache_size(512 * 1024 * 1024)
+///     .compression(CompressionType::Zstd)
+///     .bloom_filter_bits_per_key(10.0);
+/// let db = GroveDb::open_with_options(path, options)?;
+/// ```
+///
+/// [`GroveDb::open_with_options`]: crate::GroveDb::open_with_options
+#[derive(Debug, Clone, Default)]
+pub struct GroveDbOptions {
+    pub(crate) storage: RocksDbOptions,
+}
+
+impl GroveDbOptions {
+    /// Replaces all RocksDB options at once.
+    pub fn storage_options(mut self, storage: RocksDbOptions) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Sets the number of background threads for flushes and compactions.
+    pub fn parallelism(mut self, threads: i32) -> Self {
+        self.storage = self.storage.parallelism(threads);
+        self
+    }
+
+    /// Sets the size in bytes of the block cache shared by all column
+    /// families.
+    pub fn block_cache_size(mut self, size: usize) -> Self {
+        self.storage = self.storage.block_cache_size(size);
+        self
+    }
+
+    /// Sets the compression of every column family which doesn't override
+    /// it.
+    pub fn compression(mut self, compression: CompressionType) -> Self {
+        self.storage = self.storage.compression(compression);
+        self
+    }
+
+    /// Enables bloom filters with the given bits per key in every column
+    /// family which doesn't override them.
+    pub fn bloom_filter_bits_per_key(mut self, bits_per_key: f64) -> Self {
+        self.storage = self.storage.bloom_filter_bits_per_key(bits_per_key);
+        self
+    }
+
+    /// Sets the total size of WAL files after which column families are
+    /// flushed.
+    pub fn max_total_wal_size(mut self, size: u64) -> Self {
+        self.storage = self.storage.max_total_wal_size(size);
+        self
+    }
+
+    /// Buffers WAL writes until [`GroveDb::flush`](crate::GroveDb::flush)
+    /// instead of writing them on every commit.
+    pub fn manual_wal_flush(mut self, manual: bool) -> Self {
+        self.storage = self.storage.manual_wal_flush(manual);
+        self
+    }
+
+    /// Keeps the last `versions` committed states, the latest one included,
+    /// available to [`GroveDb::root_hash_at_version`] and
+    /// [`GroveDb::prove_query_at_version`].
+    ///
+    /// Only changed keys are saved for each version. State sync writes aren't
+    /// versioned, and opening the GroveDb without history drops the history
+    /// kept so far.
+    ///
+    /// [`GroveDb::root_hash_at_version`]: crate::GroveDb::root_hash_at_version
+    /// [`GroveDb::prove_query_at_version`]: crate::GroveDb::prove_query_at_version
+    pub fn history_retention(mut self, versions: u64) -> Self {
+        self.storage = self.storage.history_retention(versions);
+        self
+    }
+
+    /// Sets the options of the column family holding subtrees data.
+    pub fn default_cf(mut self, options: ColumnFamilyOptions) -> Self {
+        self.storage = self.storage.default_cf(options);
+        self
+    }
+
+    /// Sets the options of the auxiliary data column family.
+    pub fn aux_cf(mut self, options: ColumnFamilyOptions) -> Self {
+        self.storage = self.storage.aux_cf(options);
+        self
+    }
+
+    /// Sets the options of the subtree roots column family.
+    pub fn roots_cf(mut self, options: ColumnFamilyOptions) -> Self {
+        self.storage = self.storage.roots_cf(options);
+        self
+    }
+
+    /// Sets the options of the metadata column family.
+    pub fn meta_cf(mut self, options: ColumnFamilyOptions) -> Self {
+        self.storage = self.storage.meta_cf(options);
+        self
+    }
+}
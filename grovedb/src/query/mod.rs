@@ -5,6 +5,7 @@ mod grove_branch_query_result;
 mod grove_trunk_query_result;
 mod path_branch_chunk_query;
 mod path_trunk_chunk_query;
+mod query_cursor;
 
 use std::{
     borrow::{Cow, Cow::Borrowed},
@@ -28,8 +29,9 @@ use indexmap::IndexMap;
 pub use path_branch_chunk_query::PathBranchChunkQuery;
 #[cfg(any(feature = "minimal", feature = "verify"))]
 pub use path_trunk_chunk_query::PathTrunkChunkQuery;
+pub use query_cursor::QueryCursor;
 
-use crate::operations::proof::util::hex_to_ascii;
+use crate::operations::proof::{util::hex_to_ascii, ProveOptions};
 
 use crate::query_result_type::PathKey;
 
@@ -671,6 +673,29 @@ impl<'a> SinglePathSubquery<'a> {
         }
     }
 
+    /// Splits the limit and offset left to a proved query into the ones of
+    /// the merk proof at this level.
+    ///
+    /// An offset only skips matches at levels where no key leads further
+    /// down, so that every match is a result. Above those levels, the merk
+    /// proof makes room for the subtrees whose results the offset skips, and
+    /// is not limited at all when subqueries without results are not counted
+    /// against the limit, as any number of keys may then add no results.
+    pub fn merk_limit_and_offset(
+        &self,
+        limit: Option<u16>,
+        offset: u16,
+        prove_options: &ProveOptions,
+    ) -> (Option<u16>, u16) {
+        if self.in_path.is_none() && self.has_subquery == HasSubquery::NoSubquery {
+            (limit, offset)
+        } else if prove_options.decrease_limit_on_empty_sub_query_result {
+            (limit.map(|limit| limit.saturating_add(offset)), 0)
+        } else {
+            (None, 0)
+        }
+    }
+
     pub fn from_key_when_in_path(
         key: &'a Vec<u8>,
         subquery_is_last_path_item: bool,
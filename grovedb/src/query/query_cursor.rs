@@ -0,0 +1,181 @@
+//! Continuation cursors for paging through the results of a path query.
+//!
+//! A cursor records the position of the last result of a page: the key it
+//! was found under at every depth of the query below the path query path,
+//! down to its own key. The query of the next page is the original query
+//! restricted to the results after that position, so it can be proved and
+//! verified like any other path query.
+
+use bincode::{Decode, Encode};
+use grovedb_merk::proofs::{
+    query::{query_item::QueryItem, SubqueryBranch},
+    Query,
+};
+use indexmap::IndexMap;
+
+use crate::{operations::proof::ProveOptions, Error, PathQuery, SizedQuery};
+
+/// Position after which the next page of a path query starts, see
+/// [`PathQuery::page`].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueryCursor {
+    /// Path of the last result below the path query path, followed by its key
+    position: Vec<Vec<u8>>,
+}
+
+impl QueryCursor {
+    /// Returns the cursor after the result at `path` and `key` of
+    /// `path_query`.
+    pub fn after_result(
+        path_query: &PathQuery,
+        path: &[Vec<u8>],
+        key: &[u8],
+    ) -> Result<Self, Error> {
+        let Some(relative_path) = path.strip_prefix(path_query.path.as_slice()) else {
+            return Err(Error::InvalidInput(
+                "result path is not below the path query path",
+            ));
+        };
+        let mut position = relative_path.to_vec();
+        position.push(key.to_vec());
+        Ok(QueryCursor { position })
+    }
+
+    /// Returns the cursor of the page after the `results` of a page of
+    /// `path_query`, or `None` if the page is not full and therefore the
+    /// last one.
+    pub(crate) fn after_page<T>(
+        path_query: &PathQuery,
+        page_size: u16,
+        results: &[(Vec<Vec<u8>>, Vec<u8>, T)],
+    ) -> Result<Option<Self>, Error> {
+        match results.last() {
+            Some((path, key, _)) if results.len() >= page_size as usize => {
+                Self::after_result(path_query, path, key).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Serializes the cursor to hand it to a client.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+        bincode::encode_to_vec(self, config)
+            .map_err(|e| Error::CorruptedData(format!("unable to encode query cursor {}", e)))
+    }
+
+    /// Deserializes a cursor made by [`QueryCursor::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_limit::<{ 64 * 1024 }>();
+        let (cursor, _): (Self, _) = bincode::decode_from_slice(bytes, config)
+            .map_err(|_| Error::InvalidInput("unable to decode query cursor"))?;
+        if cursor.position.is_empty() {
+            return Err(Error::InvalidInput("query cursor has no position"));
+        }
+        Ok(cursor)
+    }
+}
+
+impl PathQuery {
+    /// Options pages are proved with: subqueries without results are not
+    /// counted against the page size, so that a page only holds fewer
+    /// results than its size when it is the last one.
+    pub(crate) const PAGE_PROVE_OPTIONS: ProveOptions = ProveOptions {
+        decrease_limit_on_empty_sub_query_result: false,
+    };
+
+    /// Returns the path query of the page of at most `page_size` results
+    /// following `cursor`, or of the first page if there is none.
+    ///
+    /// Paging replaces the limit of the query, which therefore must not have
+    /// a limit or an offset of its own. Pages are queried and proved without
+    /// counting subqueries without results against their size, so paging is
+    /// over once a page holds fewer than `page_size` results. A page goes
+    /// through every subtree without results until it is full, which must be
+    /// kept in mind for queries that may meet many of them.
+    ///
+    /// When the query adds parent trees of subqueries to its results, the
+    /// parent tree of the subquery a page resumes in is returned again.
+    pub fn page(&self, cursor: Option<&QueryCursor>, page_size: u16) -> Result<PathQuery, Error> {
+        if self.query.limit.is_some() || self.query.offset.is_some() {
+            return Err(Error::InvalidQuery(
+                "paged queries can not have a limit or an offset",
+            ));
+        }
+        if page_size == 0 {
+            return Err(Error::InvalidQuery("page size must be greater than zero"));
+        }
+        let query = match cursor {
+            Some(cursor) => resume_after(&self.query.query, &cursor.position)?,
+            None => self.query.query.clone(),
+        };
+        Ok(PathQuery::new(
+            self.path.clone(),
+            SizedQuery::new(query, Some(page_size), None),
+        ))
+    }
+}
+
+/// Restricts `query` to the results after `position`, the path below the
+/// queried tree and key of a result.
+fn resume_after(query: &Query, position: &[Vec<u8>]) -> Result<Query, Error> {
+    let Some((key, deeper)) = position.split_first() else {
+        return Err(Error::InvalidInput("query cursor has no position"));
+    };
+
+    let branch = subquery_branch_for_key(query, key);
+    let subquery_path_len = branch.subquery_path.as_ref().map_or(0, Vec::len);
+    // The last result was found below `key` if it is deeper than the
+    // subquery path of its branch, in which case the results left under
+    // `key` come first.
+    let resumed_branch = match &branch.subquery {
+        Some(subquery) if deeper.len() > subquery_path_len => Some(SubqueryBranch {
+            subquery_path: branch.subquery_path.clone(),
+            subquery: Some(Box::new(resume_after(
+                subquery,
+                &deeper[subquery_path_len..],
+            )?)),
+        }),
+        _ => None,
+    };
+
+    let bound = match (resumed_branch.is_some(), query.left_to_right) {
+        (true, true) => QueryItem::RangeFrom(key.clone()..),
+        (false, true) => QueryItem::RangeAfter(key.clone()..),
+        (true, false) => QueryItem::RangeToInclusive(..=key.clone()),
+        (false, false) => QueryItem::RangeTo(..key.clone()),
+    };
+    let mut resumed = query.clone();
+    resumed.items = query
+        .items
+        .iter()
+        .filter_map(|item| item.intersect(&bound).in_both)
+        .collect();
+
+    if let Some(resumed_branch) = resumed_branch {
+        // Branches are matched in order, so the one for `key` goes first.
+        let branches = resumed
+            .conditional_subquery_branches
+            .get_or_insert_with(IndexMap::new);
+        let key_item = QueryItem::Key(key.clone());
+        branches.shift_remove(&key_item);
+        branches.shift_insert(0, key_item, resumed_branch);
+    }
+    Ok(resumed)
+}
+
+/// Returns the subquery branch applied under `key`, the first conditional
+/// branch containing it or else the default one.
+fn subquery_branch_for_key<'a>(query: &'a Query, key: &[u8]) -> &'a SubqueryBranch {
+    query
+        .conditional_subquery_branches
+        .iter()
+        .flatten()
+        .find(|(query_item, _)| query_item.contains(key))
+        .map_or(&query.default_subquery_branch, |(_, branch)| branch)
+}
@@ -7,9 +7,11 @@ use std::{
 };
 
 pub use grovedb_merk::proofs::query::{Key, Path, PathKey};
+use grovedb_merk::CryptoHash;
 use grovedb_version::{version::GroveVersion, TryFromVersioned};
+use indexmap::IndexMap;
 
-use crate::element::SumValue;
+use crate::element::{ElementType, SumValue};
 use crate::{
     operations::proof::util::{
         hex_to_ascii, path_hex_to_ascii, ProvedPathKeyOptionalValue, ProvedPathKeyValue,
@@ -42,6 +44,100 @@ impl fmt::Display for QueryResultType {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// Part of the elements returned by `GroveDb::query_projected`, which reads
+/// no more of them than that
+pub enum QueryProjection {
+    /// Only the keys, without reading the elements
+    Keys,
+    /// The keys and value hashes, without deserializing the elements
+    ValueHashes,
+    /// The keys and element types, without deserializing the elements
+    ElementTypes,
+}
+
+/// Result of a projected query
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ProjectedResult {
+    /// Key, for `QueryProjection::Keys`
+    Key(Key),
+    /// Key and value hash, for `QueryProjection::ValueHashes`
+    KeyValueHash(Key, CryptoHash),
+    /// Key and element type, for `QueryProjection::ElementTypes`
+    KeyElementType(Key, ElementType),
+}
+
+impl ProjectedResult {
+    /// Returns the key of the result
+    pub fn key(&self) -> &Key {
+        match self {
+            ProjectedResult::Key(key)
+            | ProjectedResult::KeyValueHash(key, _)
+            | ProjectedResult::KeyElementType(key, _) => key,
+        }
+    }
+}
+
+/// Aggregates of the results of a path query in one subtree, see
+/// `GroveDb::query_subtree_aggregates`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubtreeAggregate {
+    /// Path of the subtree
+    pub path: Path,
+    /// Number of results, count trees counting for their own count like in
+    /// a count tree
+    pub count: u64,
+    /// Sum of the sum values of the results, big sum trees not adding to it
+    /// like in a count sum tree
+    pub sum: i128,
+    /// Smallest key of the results
+    pub min_key: Key,
+    /// Largest key of the results
+    pub max_key: Key,
+}
+
+impl SubtreeAggregate {
+    /// Returns the aggregates of a single result
+    pub(crate) fn of_result(path: Path, key: Key, element: &Element) -> Self {
+        let (count, sum) = Self::count_and_sum(element);
+        SubtreeAggregate {
+            path,
+            count,
+            sum,
+            min_key: key.clone(),
+            max_key: key,
+        }
+    }
+
+    /// Returns the count and sum `element` adds to aggregates, which are the
+    /// ones it adds to a count sum tree holding it. The count and sum of a
+    /// count sum tree are then the aggregates of its elements.
+    pub(crate) fn count_and_sum(element: &Element) -> (u64, i128) {
+        let (count, sum) = element.count_sum_value_or_default();
+        (count, sum as i128)
+    }
+
+    /// Adds `aggregate` to the aggregates of its subtree in `aggregates`,
+    /// which are kept in the order of the first results of the subtrees
+    pub(crate) fn add_to(self, aggregates: &mut IndexMap<Path, SubtreeAggregate>) {
+        match aggregates.get_mut(&self.path) {
+            Some(existing) => {
+                existing.count = existing.count.saturating_add(self.count);
+                existing.sum = existing.sum.saturating_add(self.sum);
+                if self.min_key < existing.min_key {
+                    existing.min_key = self.min_key;
+                }
+                if self.max_key > existing.max_key {
+                    existing.max_key = self.max_key;
+                }
+            }
+            None => {
+                aggregates.insert(self.path.clone(), self);
+            }
+        }
+    }
+}
+
 /// Query result elements
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct QueryResultElements {
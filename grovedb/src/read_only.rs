@@ -0,0 +1,278 @@
+//! Read-only access to a GroveDb whose data directory is owned by another
+//! process.
+//!
+//! [`ReadOnlyGroveDb`] only exposes reads, queries and proofs, so writing
+//! through it is rejected at compile time rather than failing at runtime.
+
+use std::path::Path;
+
+use grovedb_costs::CostResult;
+use grovedb_path::SubtreePath;
+use grovedb_storage::{backend::StorageBackend, rocksdb_storage::ReadOnlyRocksDbStorage};
+use grovedb_version::version::GroveVersion;
+
+use crate::{
+    operations::proof::ProveOptions,
+    query_result_type::{PathKeyOptionalElementTrio, QueryResultElements, QueryResultType},
+    Element, Error, GroveDb, Hash, PathQuery,
+};
+
+/// GroveDb opened without write access, either read-only or as a secondary
+/// instance following a primary one.
+///
+/// A read-only instance sees the data as it was when opened. A secondary
+/// instance moves forward each time
+/// [`try_catch_up_with_primary`](Self::try_catch_up_with_primary) is called,
+/// which takes `&mut self` so that no query or proof can observe a catch-up
+/// half way through.
+pub struct ReadOnlyGroveDb {
+    grove_db: GroveDb,
+}
+
+impl GroveDb {
+    /// Opens the GroveDb at `path` read-only.
+    ///
+    /// Changes made afterwards by the process owning `path` are not visible,
+    /// use [`GroveDb::open_as_secondary`] to follow them.
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<ReadOnlyGroveDb, Error> {
+        let db = ReadOnlyRocksDbStorage::read_only_rocksdb_with_path(path)?;
+        Ok(ReadOnlyGroveDb {
+            grove_db: GroveDb {
+                db: db.into(),
+                change_log: Default::default(),
+                indexes: Default::default(),
+            },
+        })
+    }
+
+    /// Opens the GroveDb at `primary_path` as a secondary instance which can
+    /// tail the primary one while it keeps writing. `secondary_path` is a
+    /// directory of its own where the secondary instance keeps its logs.
+    pub fn open_as_secondary<P: AsRef<Path>, S: AsRef<Path>>(
+        primary_path: P,
+        secondary_path: S,
+    ) -> Result<ReadOnlyGroveDb, Error> {
+        let db =
+            ReadOnlyRocksDbStorage::secondary_rocksdb_with_paths(primary_path, secondary_path)?;
+        Ok(ReadOnlyGroveDb {
+            grove_db: GroveDb {
+                db: db.into(),
+                change_log: Default::default(),
+                indexes: Default::default(),
+            },
+        })
+    }
+}
+
+impl ReadOnlyGroveDb {
+    fn storage(&self) -> &ReadOnlyRocksDbStorage {
+        match &self.grove_db.db {
+            StorageBackend::ReadOnlyRocksDb(storage) => storage,
+            _ => unreachable!("read-only GroveDb is always opened on read-only storage"),
+        }
+    }
+
+    /// Returns `true` if opened with [`GroveDb::open_as_secondary`].
+    pub fn is_secondary(&self) -> bool {
+        self.storage().is_secondary()
+    }
+
+    /// Catches up with the changes committed by the primary instance since
+    /// opening or the previous call. Fails for read-only instances.
+    pub fn try_catch_up_with_primary(&mut self) -> Result<(), Error> {
+        Ok(self.storage().try_catch_up_with_primary()?)
+    }
+
+    /// Returns the root hash of the GroveDb.
+    pub fn root_hash(&self, grove_version: &GroveVersion) -> CostResult<Hash, Error> {
+        self.grove_db.root_hash(None, grove_version)
+    }
+
+    /// Get an element from the GroveDb, following references.
+    pub fn get<'b, B, P>(
+        &self,
+        path: P,
+        key: &[u8],
+        grove_version: &GroveVersion,
+    ) -> CostResult<Element, Error>
+    where
+        B: AsRef<[u8]> + 'b,
+        P: Into<SubtreePath<'b, B>>,
+    {
+        self.grove_db.get(path, key, None, grove_version)
+    }
+
+    /// Get an element from the GroveDb without following references.
+    pub fn get_raw<B: AsRef<[u8]>>(
+        &self,
+        path: SubtreePath<B>,
+        key: &[u8],
+        grove_version: &GroveVersion,
+    ) -> CostResult<Element, Error> {
+        self.grove_db.get_raw(path, key, None, grove_version)
+    }
+
+    /// Get an element from the GroveDb without following references, `None`
+    /// if it doesn't exist.
+    pub fn get_raw_optional<B: AsRef<[u8]>>(
+        &self,
+        path: SubtreePath<B>,
+        key: &[u8],
+        grove_version: &GroveVersion,
+    ) -> CostResult<Option<Element>, Error> {
+        self.grove_db
+            .get_raw_optional(path, key, None, grove_version)
+    }
+
+    /// Does an element exist at the given path and key.
+    pub fn has_raw<'b, B, P>(
+        &self,
+        path: P,
+        key: &[u8],
+        grove_version: &GroveVersion,
+    ) -> CostResult<bool, Error>
+    where
+        B: AsRef<[u8]> + 'b,
+        P: Into<SubtreePath<'b, B>>,
+    {
+        self.grove_db.has_raw(path, key, None, grove_version)
+    }
+
+    /// Is the subtree at the given path empty.
+    pub fn is_empty_tree<'b, B, P>(
+        &self,
+        path: P,
+        grove_version: &GroveVersion,
+    ) -> CostResult<bool, Error>
+    where
+        B: AsRef<[u8]> + 'b,
+        P: Into<SubtreePath<'b, B>>,
+    {
+        self.grove_db.is_empty_tree(path, None, grove_version)
+    }
+
+    /// Queries the GroveDb, following references.
+    /// See [`GroveDb::query`].
+    pub fn query(
+        &self,
+        path_query: &PathQuery,
+        allow_cache: bool,
+        decrease_limit_on_range_with_no_sub_elements: bool,
+        error_if_intermediate_path_tree_not_present: bool,
+        result_type: QueryResultType,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(QueryResultElements, u16), Error> {
+        self.grove_db.query(
+            path_query,
+            allow_cache,
+            decrease_limit_on_range_with_no_sub_elements,
+            error_if_intermediate_path_tree_not_present,
+            result_type,
+            None,
+            grove_version,
+        )
+    }
+
+    /// Queries the GroveDb without following references.
+    /// See [`GroveDb::query_raw`].
+    pub fn query_raw(
+        &self,
+        path_query: &PathQuery,
+        allow_cache: bool,
+        decrease_limit_on_range_with_no_sub_elements: bool,
+        error_if_intermediate_path_tree_not_present: bool,
+        result_type: QueryResultType,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(QueryResultElements, u16), Error> {
+        self.grove_db.query_raw(
+            path_query,
+            allow_cache,
+            decrease_limit_on_range_with_no_sub_elements,
+            error_if_intermediate_path_tree_not_present,
+            result_type,
+            None,
+            grove_version,
+        )
+    }
+
+    /// Queries the values of items. See [`GroveDb::query_item_value`].
+    pub fn query_item_value(
+        &self,
+        path_query: &PathQuery,
+        allow_cache: bool,
+        decrease_limit_on_range_with_no_sub_elements: bool,
+        error_if_intermediate_path_tree_not_present: bool,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(Vec<Vec<u8>>, u16), Error> {
+        self.grove_db.query_item_value(
+            path_query,
+            allow_cache,
+            decrease_limit_on_range_with_no_sub_elements,
+            error_if_intermediate_path_tree_not_present,
+            None,
+            grove_version,
+        )
+    }
+
+    /// Queries the values of sum items. See [`GroveDb::query_sums`].
+    pub fn query_sums(
+        &self,
+        path_query: &PathQuery,
+        allow_cache: bool,
+        decrease_limit_on_range_with_no_sub_elements: bool,
+        error_if_intermediate_path_tree_not_present: bool,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(Vec<i64>, u16), Error> {
+        self.grove_db.query_sums(
+            path_query,
+            allow_cache,
+            decrease_limit_on_range_with_no_sub_elements,
+            error_if_intermediate_path_tree_not_present,
+            None,
+            grove_version,
+        )
+    }
+
+    /// Queries keys, including those without an element.
+    /// See [`GroveDb::query_keys_optional`].
+    pub fn query_keys_optional(
+        &self,
+        path_query: &PathQuery,
+        allow_cache: bool,
+        decrease_limit_on_range_with_no_sub_elements: bool,
+        error_if_intermediate_path_tree_not_present: bool,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<PathKeyOptionalElementTrio>, Error> {
+        self.grove_db.query_keys_optional(
+            path_query,
+            allow_cache,
+            decrease_limit_on_range_with_no_sub_elements,
+            error_if_intermediate_path_tree_not_present,
+            None,
+            grove_version,
+        )
+    }
+
+    /// Generates a proof for a path query. See [`GroveDb::prove_query`].
+    pub fn prove_query(
+        &self,
+        path_query: &PathQuery,
+        prove_options: Option<ProveOptions>,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error> {
+        self.grove_db
+            .prove_query(path_query, prove_options, grove_version)
+    }
+
+    /// Generates a single proof for several path queries.
+    /// See [`GroveDb::prove_query_many`].
+    pub fn prove_query_many(
+        &self,
+        query: Vec<&PathQuery>,
+        prove_options: Option<ProveOptions>,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<u8>, Error> {
+        self.grove_db
+            .prove_query_many(query, prove_options, grove_version)
+    }
+}
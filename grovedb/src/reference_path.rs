@@ -4,32 +4,20 @@ use std::collections::HashSet;
 
 use grovedb_costs::{cost_return_on_error, cost_return_on_error_into_no_add, CostResult, CostsExt};
 pub use grovedb_element::reference_path::*;
-use grovedb_merk::{element::get::ElementFetchFromStorageExtensions, CryptoHash};
+use grovedb_merk::element::get::ElementFetchFromStorageExtensions;
 use grovedb_path::SubtreePathBuilder;
 use grovedb_version::check_grovedb_v0_with_cost;
 
-use crate::{
-    merk_cache::{MerkCache, MerkHandle},
-    operations::MAX_REFERENCE_HOPS,
-    Element, Error,
-};
+use crate::{merk_cache::MerkCache, operations::MAX_REFERENCE_HOPS, Element, Error};
 
-pub(crate) struct ResolvedReference<'db, 'b, 'c, B> {
-    pub target_merk: MerkHandle<'db, 'c>,
-    pub target_path: SubtreePathBuilder<'b, B>,
-    pub target_key: Vec<u8>,
-    pub target_element: Element,
-    pub target_node_value_hash: CryptoHash,
-}
-
-pub(crate) fn follow_reference<'db, 'b, 'c, B: AsRef<[u8]>>(
-    merk_cache: &'c MerkCache<'db, 'b, B>,
+/// Follows references from the one at `key` under `path` until an element
+/// that isn't a reference, and returns that element.
+pub(crate) fn follow_reference<'b, B: AsRef<[u8]>>(
+    merk_cache: &MerkCache<'_, 'b, B>,
     path: SubtreePathBuilder<'b, B>,
     key: &[u8],
     ref_path: ReferencePathType,
-) -> CostResult<ResolvedReference<'db, 'b, 'c, B>, Error> {
-    // TODO: this is a new version of follow reference
-
+) -> CostResult<Element, Error> {
     check_grovedb_v0_with_cost!(
         "follow_reference",
         merk_cache
@@ -69,9 +57,19 @@ pub(crate) fn follow_reference<'db, 'b, 'c, B: AsRef<[u8]>>(
             return Err(Error::InvalidCodeExecution("empty reference")).wrap_with_cost(cost);
         };
 
-        let mut referred_merk =
-            cost_return_on_error!(&mut cost, merk_cache.get_merk(referred_path.clone()));
-        let (element, value_hash) = cost_return_on_error!(
+        let mut referred_merk = cost_return_on_error!(
+            &mut cost,
+            merk_cache
+                .get_merk(referred_path.clone())
+                .map_err(|e| match e {
+                    Error::InvalidParentLayerPath(s) | Error::PathParentLayerNotFound(s) => {
+                        Error::CorruptedReferencePathParentLayerNotFound(s)
+                    }
+                    Error::PathNotFound(s) => Error::CorruptedReferencePathNotFound(s),
+                    e => e,
+                })
+        );
+        let (element, _) = cost_return_on_error!(
             &mut cost,
             referred_merk
                 .for_merk(|m| {
@@ -91,16 +89,7 @@ pub(crate) fn follow_reference<'db, 'b, 'c, B: AsRef<[u8]>>(
                 current_ref = ref_path;
                 hops_left -= 1;
             }
-            e => {
-                return Ok(ResolvedReference {
-                    target_merk: referred_merk,
-                    target_path: referred_path,
-                    target_key: referred_key,
-                    target_element: e,
-                    target_node_value_hash: value_hash,
-                })
-                .wrap_with_cost(cost);
-            }
+            e => return Ok(e).wrap_with_cost(cost),
         }
     }
 
@@ -109,12 +98,16 @@ pub(crate) fn follow_reference<'db, 'b, 'c, B: AsRef<[u8]>>(
 
 /// Follow references stopping at the immediate element without following
 /// further.
-pub(crate) fn follow_reference_once<'db, 'b, 'c, B: AsRef<[u8]>>(
-    merk_cache: &'c MerkCache<'db, 'b, B>,
+///
+/// Insertions need the end of the reference chain and batches resolve
+/// references over their own Merks, so only tests take a single hop.
+#[cfg(test)]
+pub(crate) fn follow_reference_once<'b, B: AsRef<[u8]>>(
+    merk_cache: &MerkCache<'_, 'b, B>,
     path: SubtreePathBuilder<'b, B>,
     key: &[u8],
     ref_path: ReferencePathType,
-) -> CostResult<ResolvedReference<'db, 'b, 'c, B>, Error> {
+) -> CostResult<Element, Error> {
     check_grovedb_v0_with_cost!(
         "follow_reference_once",
         merk_cache
@@ -142,7 +135,7 @@ pub(crate) fn follow_reference_once<'db, 'b, 'c, B: AsRef<[u8]>>(
 
     let mut referred_merk =
         cost_return_on_error!(&mut cost, merk_cache.get_merk(referred_path.clone()));
-    let (element, value_hash) = cost_return_on_error!(
+    let (element, _) = cost_return_on_error!(
         &mut cost,
         referred_merk
             .for_merk(|m| {
@@ -155,14 +148,7 @@ pub(crate) fn follow_reference_once<'db, 'b, 'c, B: AsRef<[u8]>>(
             })
     );
 
-    Ok(ResolvedReference {
-        target_merk: referred_merk,
-        target_path: referred_path,
-        target_key: referred_key,
-        target_element: element,
-        target_node_value_hash: value_hash,
-    })
-    .wrap_with_cost(cost)
+    Ok(element).wrap_with_cost(cost)
 }
 
 #[cfg(test)]
@@ -1,3 +1,6 @@
+mod chunk_envelope;
+mod non_merk_chunks;
+mod session_checkpoint;
 mod state_sync_session;
 
 use std::pin::Pin;
@@ -6,7 +9,10 @@ use grovedb_merk::{tree::hash::CryptoHash, tree_type::TreeType, ChunkProducer};
 use grovedb_path::SubtreePath;
 use grovedb_version::{check_grovedb_v0, version::GroveVersion};
 
-pub use self::state_sync_session::MultiStateSyncSession;
+pub use self::{
+    chunk_envelope::{ChunkEnvelope, ChunkEnvelopeError},
+    state_sync_session::MultiStateSyncSession,
+};
 use crate::{
     replication::utils::{pack_nested_bytes, unpack_nested_bytes},
     util::TxRef,
@@ -29,7 +35,9 @@ pub type ChunkIdentifier = (
 );
 
 /// Current version of the state sync protocol.
-pub const CURRENT_STATE_SYNC_VERSION: u16 = 1;
+///
+/// Version 2 sends every global chunk in a [`ChunkEnvelope`].
+pub const CURRENT_STATE_SYNC_VERSION: u16 = 2;
 
 #[cfg(feature = "minimal")]
 impl GroveDb {
@@ -42,6 +50,21 @@ impl GroveDb {
         MultiStateSyncSession::new(self, app_hash, subtrees_batch_size)
     }
 
+    /// Resumes the state synchronization session of the snapshot with
+    /// `app_hash` from its last checkpoint, see
+    /// [`MultiStateSyncSession::checkpoint`]. The chunks to request next are
+    /// given by [`MultiStateSyncSession::pending_chunk_ids`].
+    ///
+    /// Returns `None` if no interrupted session was syncing this snapshot, in
+    /// which case syncing starts over with [`GroveDb::start_snapshot_syncing`].
+    pub fn resume_syncing_session(
+        &self,
+        app_hash: [u8; 32],
+        grove_version: &GroveVersion,
+    ) -> Result<Option<Pin<Box<MultiStateSyncSession<'_>>>>, Error> {
+        MultiStateSyncSession::resume(self, app_hash, grove_version)
+    }
+
     /// Commits a completed state synchronization session.
     ///
     /// Verifies the final GroveDB root hash matches the expected `app_hash`
@@ -73,8 +96,8 @@ impl GroveDb {
     ///
     /// # Returns
     ///
-    /// - `Ok(Vec<u8>)`: A packed byte vector containing the requested chunk
-    ///   data.
+    /// - `Ok(Vec<u8>)`: A packed byte vector containing a [`ChunkEnvelope`]
+    ///   for every requested global chunk id.
     /// - `Err(Error)`: An error if the fetch operation fails.
     ///
     /// # Errors
@@ -91,7 +114,9 @@ impl GroveDb {
     ///   is treated as a single ID.
     /// - Otherwise, it is unpacked into multiple nested chunk IDs.
     /// - The function opens a `Merk` tree for each chunk and retrieves the
-    ///   associated data.
+    ///   associated data. Chunks of MmrTree, BulkAppendTree,
+    ///   DenseAppendOnlyFixedSizeTree and CommitmentTree subtrees are read
+    ///   from their data namespace instead.
     /// - Empty trees return an empty byte vector.
     pub fn fetch_chunk(
         &self,
@@ -128,6 +153,27 @@ impl GroveDb {
                 utils::decode_global_chunk_id(global_chunk_id.as_slice(), &root_app_hash)?;
 
             let mut local_chunk_bytes: Vec<Vec<u8>> = vec![];
+            let mut op_count = 0;
+
+            if tree_type.uses_non_merk_data_storage() {
+                for chunk_id in nested_chunk_ids
+                    .is_empty()
+                    .then(Vec::new)
+                    .into_iter()
+                    .chain(nested_chunk_ids)
+                {
+                    local_chunk_bytes.push(self.fetch_non_merk_chunk(
+                        chunk_prefix,
+                        &chunk_id,
+                        tx.as_ref(),
+                    )?);
+                }
+                global_chunk_bytes.push(
+                    ChunkEnvelope::new(global_chunk_id, tree_type, 0, local_chunk_bytes)?
+                        .encode()?,
+                );
+                continue;
+            }
 
             let merk = self
                 .open_transactional_merk_by_prefix(
@@ -172,6 +218,7 @@ impl GroveDb {
                                     e
                                 ))
                             })?;
+                    op_count += chunk.len();
                     let op_bytes = utils::encode_vec_ops(chunk).map_err(|e| {
                         Error::CorruptedData(format!(
                             "failed to encode chunk ops:{} with:{}",
@@ -182,7 +229,10 @@ impl GroveDb {
                     local_chunk_bytes.push(op_bytes);
                 }
             }
-            global_chunk_bytes.push(pack_nested_bytes(local_chunk_bytes)?);
+            global_chunk_bytes.push(
+                ChunkEnvelope::new(global_chunk_id, tree_type, op_count, local_chunk_bytes)?
+                    .encode()?,
+            );
         }
         pack_nested_bytes(global_chunk_bytes)
     }
@@ -212,6 +262,8 @@ impl GroveDb {
     ///   available sources.
     /// - A new sync session is created and managed internally, facilitating
     ///   further synchronization.
+    /// - The checkpoint of an interrupted session is discarded once the new
+    ///   session commits its first batch of subtrees.
     ///
     /// # Usage
     /// This method is typically called as part of the ABCI `OfferSnapshot`
@@ -238,6 +290,77 @@ impl GroveDb {
                 .replication
                 .start_snapshot_syncing
         );
+        self.start_snapshot_syncing_session(
+            self.start_syncing_session(app_hash, subtrees_batch_size),
+            app_hash,
+            subtrees_batch_size,
+            version,
+            grove_version,
+        )
+    }
+
+    /// Starts a state synchronization process for a snapshot with the given
+    /// `app_hash` root hash over the older state this database already
+    /// holds, for a node catching up after being offline for a while. It
+    /// takes the same parameters as [`GroveDb::start_snapshot_syncing`] and
+    /// the session is driven the same way.
+    ///
+    /// # Behavior
+    /// - Before requesting the chunks of a subtree discovered in a restored
+    ///   parent, the session compares the hash committed for it in the
+    ///   parent with the root hash of the subtree stored at the same path.
+    ///   Subtrees that match are kept as they are with every subtree below
+    ///   them, and no chunk of them is requested.
+    /// - Within a Merk that differs, chunks are requested top-down: the
+    ///   subtree behind a hash node of an applied chunk is kept when the
+    ///   stored node with the key of its parent links to a subtree with the
+    ///   same hash, so only chunks of the changed regions are fetched.
+    /// - Stored nodes and subtrees which aren't part of the snapshot are
+    ///   deleted, and the session is committed only if the root hash
+    ///   matches `app_hash`, see [`GroveDb::commit_session`].
+    ///
+    /// # Notes
+    /// - The wire protocol is unchanged: chunks are requested and served
+    ///   with [`GroveDb::fetch_chunk`] as for any other session.
+    /// - Rather than sending the root hashes of the subtrees it holds to a
+    ///   source node which would skip the identical ones, the syncing node
+    ///   compares the hashes of the snapshot with its own stored state and
+    ///   only requests the chunks that differ. Source nodes don't know the
+    ///   session is incremental.
+    pub fn start_incremental_syncing(
+        &self,
+        app_hash: CryptoHash,
+        subtrees_batch_size: usize,
+        version: u16,
+        grove_version: &GroveVersion,
+    ) -> Result<Pin<Box<MultiStateSyncSession<'_>>>, Error> {
+        check_grovedb_v0!(
+            "start_incremental_syncing",
+            grove_version
+                .grovedb_versions
+                .replication
+                .start_snapshot_syncing
+        );
+
+        self.start_snapshot_syncing_session(
+            MultiStateSyncSession::new_incremental(self, app_hash, subtrees_batch_size),
+            app_hash,
+            subtrees_batch_size,
+            version,
+            grove_version,
+        )
+    }
+
+    /// Checks the arguments of a new sync session and adds the root tree
+    /// to it.
+    fn start_snapshot_syncing_session<'db>(
+        &'db self,
+        mut session: Pin<Box<MultiStateSyncSession<'db>>>,
+        app_hash: CryptoHash,
+        subtrees_batch_size: usize,
+        version: u16,
+        grove_version: &GroveVersion,
+    ) -> Result<Pin<Box<MultiStateSyncSession<'db>>>, Error> {
         // For now, only CURRENT_STATE_SYNC_VERSION is supported
         if version != CURRENT_STATE_SYNC_VERSION {
             return Err(Error::CorruptedData(
@@ -253,7 +376,7 @@ impl GroveDb {
 
         let root_prefix = [0u8; 32];
 
-        let mut session = self.start_syncing_session(app_hash, subtrees_batch_size);
+        session.discard_interrupted_session()?;
 
         session.add_subtree_sync_info(
             SubtreePath::empty(),
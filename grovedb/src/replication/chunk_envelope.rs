@@ -0,0 +1,282 @@
+//! Wire format of the chunks served by [`GroveDb::fetch_chunk`].
+//!
+//! Every global chunk is sent in an envelope describing what it holds, so a
+//! syncing node can reject a corrupted chunk, or one served for another
+//! request, before any of it reaches a restorer. All integers are
+//! big-endian:
+//!
+//! | Field      | Size              | Content                                     |
+//! |------------|-------------------|---------------------------------------------|
+//! | version    | 2                 | State sync protocol version                 |
+//! | chunk id   | 4 + length        | Global chunk id the chunk was fetched with  |
+//! | tree type  | 1                 | Discriminant of the subtree's `TreeType`    |
+//! | op count   | 4                 | Merk proof operators in the payload         |
+//! | payload    | 4 + length        | Local chunks, packed with `pack_nested_bytes` |
+//! | checksum   | 32                | blake3 hash of every preceding byte         |
+//!
+//! The op count is 0 for subtrees keeping their data outside of a Merk, as
+//! their local chunks hold values rather than proof operators.
+//!
+//! [`GroveDb::fetch_chunk`]: crate::GroveDb::fetch_chunk
+
+use grovedb_merk::{proofs::Decoder, tree_type::TreeType};
+
+use super::{
+    utils::{pack_nested_bytes, unpack_nested_bytes},
+    CURRENT_STATE_SYNC_VERSION,
+};
+use crate::Error;
+
+/// Length of the checksum closing an envelope.
+const CHECKSUM_LENGTH: usize = blake3::OUT_LEN;
+
+/// Reasons for a chunk envelope to be rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ChunkEnvelopeError {
+    /// The envelope ends before the given field
+    #[error("chunk envelope truncated while reading its {0}")]
+    Truncated(&'static str),
+    /// The envelope was built for another state sync protocol version
+    #[error("unsupported chunk envelope version {0}")]
+    UnsupportedVersion(u16),
+    /// The tree type discriminant is unknown
+    #[error("unknown tree type {0} in chunk envelope")]
+    UnknownTreeType(u8),
+    /// Bytes follow the checksum
+    #[error("{0} unexpected bytes after the chunk envelope checksum")]
+    TrailingBytes(usize),
+    /// The checksum doesn't match the content of the envelope
+    #[error("chunk envelope checksum doesn't match its content")]
+    ChecksumMismatch,
+    /// The payload can't be split into local chunks or decoded
+    #[error("invalid chunk envelope payload: {0}")]
+    InvalidPayload(String),
+    /// The payload holds another number of proof operators than declared
+    #[error("chunk envelope declares {declared} proof operators but holds {actual}")]
+    OpCountMismatch {
+        /// Op count of the envelope
+        declared: u32,
+        /// Proof operators found in the payload
+        actual: usize,
+    },
+    /// The envelope holds another chunk than the one requested
+    #[error("chunk envelope holds chunk {received} instead of {expected}")]
+    ChunkIdMismatch {
+        /// Hex of the requested global chunk id
+        expected: String,
+        /// Hex of the global chunk id of the envelope
+        received: String,
+    },
+    /// The envelope holds a chunk of another tree type than requested
+    #[error("chunk envelope holds a chunk of a {received} instead of a {expected}")]
+    TreeTypeMismatch {
+        /// Tree type of the requested subtree
+        expected: TreeType,
+        /// Tree type of the envelope
+        received: TreeType,
+    },
+    /// A field is too long to be framed
+    #[error("chunk envelope {0} of {1} bytes is too long")]
+    FieldTooLong(&'static str, usize),
+}
+
+impl From<ChunkEnvelopeError> for Error {
+    fn from(value: ChunkEnvelopeError) -> Self {
+        Error::InvalidChunkEnvelope(value)
+    }
+}
+
+/// Global chunk with the metadata sent along with it, see the
+/// [module documentation](self) for its encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkEnvelope {
+    /// State sync protocol version the chunk was produced with
+    pub version: u16,
+    /// Global chunk id the chunk was fetched with
+    pub chunk_id: Vec<u8>,
+    /// Type of the subtree the chunk belongs to. Parameters of tree types
+    /// aren't encoded, so they are 0 in decoded envelopes.
+    pub tree_type: TreeType,
+    /// Number of Merk proof operators in the payload
+    pub op_count: u32,
+    /// Local chunks, packed with `pack_nested_bytes`
+    pub payload: Vec<u8>,
+}
+
+impl ChunkEnvelope {
+    /// Wraps the local chunks fetched for `chunk_id` into an envelope of the
+    /// current protocol version.
+    pub(crate) fn new(
+        chunk_id: Vec<u8>,
+        tree_type: TreeType,
+        op_count: usize,
+        local_chunks: Vec<Vec<u8>>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            version: CURRENT_STATE_SYNC_VERSION,
+            chunk_id,
+            tree_type,
+            op_count: op_count
+                .try_into()
+                .map_err(|_| ChunkEnvelopeError::FieldTooLong("op count", op_count))?,
+            payload: pack_nested_bytes(local_chunks)?,
+        })
+    }
+
+    /// Encodes the envelope, closing it with its checksum.
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        let length_of = |field, bytes: &[u8]| {
+            u32::try_from(bytes.len())
+                .map_err(|_| ChunkEnvelopeError::FieldTooLong(field, bytes.len()))
+        };
+        let chunk_id_length = length_of("chunk id", &self.chunk_id)?;
+        let payload_length = length_of("payload", &self.payload)?;
+
+        let mut bytes = Vec::with_capacity(
+            2 + 4 + self.chunk_id.len() + 1 + 4 + 4 + self.payload.len() + CHECKSUM_LENGTH,
+        );
+        bytes.extend_from_slice(&self.version.to_be_bytes());
+        bytes.extend_from_slice(&chunk_id_length.to_be_bytes());
+        bytes.extend_from_slice(&self.chunk_id);
+        bytes.push(self.tree_type.discriminant());
+        bytes.extend_from_slice(&self.op_count.to_be_bytes());
+        bytes.extend_from_slice(&payload_length.to_be_bytes());
+        bytes.extend_from_slice(&self.payload);
+        let checksum = blake3::hash(&bytes);
+        bytes.extend_from_slice(checksum.as_bytes());
+        Ok(bytes)
+    }
+
+    /// Decodes an envelope, checking its version and checksum, and that its
+    /// payload holds `op_count` proof operators.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ChunkEnvelopeError> {
+        let mut reader = EnvelopeReader { bytes };
+        let version = u16::from_be_bytes(reader.take_array("version")?);
+        if version != CURRENT_STATE_SYNC_VERSION {
+            return Err(ChunkEnvelopeError::UnsupportedVersion(version));
+        }
+        let chunk_id = reader.take_framed("chunk id")?;
+        let [tree_type_byte] = reader.take_array("tree type")?;
+        let tree_type = TreeType::try_from(tree_type_byte)
+            .map_err(|_| ChunkEnvelopeError::UnknownTreeType(tree_type_byte))?;
+        let op_count = u32::from_be_bytes(reader.take_array("op count")?);
+        let payload = reader.take_framed("payload")?;
+
+        let content_length = bytes.len() - reader.bytes.len();
+        let checksum: [u8; CHECKSUM_LENGTH] = reader.take_array("checksum")?;
+        if !reader.bytes.is_empty() {
+            return Err(ChunkEnvelopeError::TrailingBytes(reader.bytes.len()));
+        }
+        if blake3::hash(&bytes[..content_length]) != checksum {
+            return Err(ChunkEnvelopeError::ChecksumMismatch);
+        }
+
+        let envelope = Self {
+            version,
+            chunk_id: chunk_id.to_vec(),
+            tree_type,
+            op_count,
+            payload: payload.to_vec(),
+        };
+        let actual = envelope.count_ops()?;
+        if actual != op_count as usize {
+            return Err(ChunkEnvelopeError::OpCountMismatch {
+                declared: op_count,
+                actual,
+            });
+        }
+        Ok(envelope)
+    }
+
+    /// Decodes the envelopes returned by [`GroveDb::fetch_chunk`].
+    ///
+    /// [`GroveDb::fetch_chunk`]: crate::GroveDb::fetch_chunk
+    pub fn decode_packed(packed_envelopes: &[u8]) -> Result<Vec<Self>, Error> {
+        unpack_nested_bytes(packed_envelopes)?
+            .iter()
+            .map(|bytes| Self::decode(bytes).map_err(Into::into))
+            .collect()
+    }
+
+    /// Checks that the envelope holds the chunk requested with `chunk_id`
+    /// for a subtree of `tree_type`.
+    pub fn check_request(
+        &self,
+        chunk_id: &[u8],
+        tree_type: TreeType,
+    ) -> Result<(), ChunkEnvelopeError> {
+        if self.chunk_id != chunk_id {
+            return Err(ChunkEnvelopeError::ChunkIdMismatch {
+                expected: hex::encode(chunk_id),
+                received: hex::encode(&self.chunk_id),
+            });
+        }
+        if self.tree_type.discriminant() != tree_type.discriminant() {
+            return Err(ChunkEnvelopeError::TreeTypeMismatch {
+                expected: tree_type,
+                received: self.tree_type,
+            });
+        }
+        Ok(())
+    }
+
+    /// Splits the payload into its local chunks.
+    pub fn local_chunks(&self) -> Result<Vec<Vec<u8>>, ChunkEnvelopeError> {
+        unpack_nested_bytes(&self.payload)
+            .map_err(|e| ChunkEnvelopeError::InvalidPayload(e.to_string()))
+    }
+
+    /// Counts the proof operators in the payload.
+    fn count_ops(&self) -> Result<usize, ChunkEnvelopeError> {
+        let local_chunks = self.local_chunks()?;
+        if self.tree_type.uses_non_merk_data_storage() {
+            return Ok(0);
+        }
+        let mut op_count = 0;
+        for local_chunk in local_chunks {
+            let mut decoder = Decoder::new(&local_chunk);
+            for op in decoder.by_ref() {
+                op.map_err(|e| ChunkEnvelopeError::InvalidPayload(e.to_string()))?;
+                op_count += 1;
+            }
+            if decoder.remaining_bytes() > 0 {
+                return Err(ChunkEnvelopeError::InvalidPayload(format!(
+                    "local chunk has {} unconsumed trailing bytes",
+                    decoder.remaining_bytes()
+                )));
+            }
+        }
+        Ok(op_count)
+    }
+}
+
+/// Reads the fields of an envelope from the front of `bytes`.
+struct EnvelopeReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> EnvelopeReader<'a> {
+    fn take(&mut self, length: usize, field: &'static str) -> Result<&'a [u8], ChunkEnvelopeError> {
+        if self.bytes.len() < length {
+            return Err(ChunkEnvelopeError::Truncated(field));
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(
+        &mut self,
+        field: &'static str,
+    ) -> Result<[u8; N], ChunkEnvelopeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N, field)?);
+        Ok(array)
+    }
+
+    /// Takes a field prefixed with its length.
+    fn take_framed(&mut self, field: &'static str) -> Result<&'a [u8], ChunkEnvelopeError> {
+        let length = u32::from_be_bytes(self.take_array(field)?);
+        self.take(length as usize, field)
+    }
+}
@@ -0,0 +1,495 @@
+//! Chunks of the subtrees that keep their data outside of a Merk.
+//!
+//! MmrTree, BulkAppendTree, DenseAppendOnlyFixedSizeTree and CommitmentTree
+//! subtrees store their data in the data namespace of their prefix, so they
+//! can't be restored with Merk chunks. Their chunks carry the values the
+//! tree is built from instead: leaves of an MMR, compacted chunk blobs of a
+//! bulk append tree, values at positions of a dense tree or of a buffer, and
+//! the Sinsemilla frontier of a commitment tree.
+//!
+//! Leaves and chunk blobs are pushed into a new MMR in order, so every
+//! internal node is computed by the restoring node rather than received, and
+//! the next range is only requested once the previous one is applied. When
+//! all chunks of a tree are applied its root is checked against the child
+//! hash committed in the parent Merk.
+
+use std::collections::{BTreeSet, VecDeque};
+
+use grovedb_commitment_tree::{CommitmentFrontier, COMMITMENT_TREE_DATA_KEY};
+use grovedb_dense_fixed_sized_merkle_tree::position_key;
+use grovedb_merk::tree::{combine_hash, hash::NULL_HASH, CryptoHash};
+use grovedb_merkle_mountain_range::{
+    leaf_index_to_mmr_size, leaf_index_to_pos, mmr_size_to_leaf_count, MMRStoreReadOps, MmrKeySize,
+    MmrNode, MmrStore, MMR,
+};
+use grovedb_storage::{backend::PrefixedBackendImmediateStorageContext, Storage, StorageContext};
+
+use super::utils::{pack_nested_bytes, unpack_nested_bytes};
+use crate::{Element, Error, GroveDb, SubtreePrefix, Transaction};
+
+/// Maximum number of MMR leaves in a chunk.
+const MMR_LEAVES_PER_CHUNK: u64 = 256;
+
+/// Maximum number of compacted chunk blobs in a chunk, each of them holding
+/// up to `2^16` values.
+const CHUNK_BLOBS_PER_CHUNK: u64 = 4;
+
+/// Maximum number of dense tree positions in a chunk.
+const DENSE_POSITIONS_PER_CHUNK: u16 = 256;
+
+const MMR_LEAVES_TAG: u8 = 0;
+const CHUNK_BLOBS_TAG: u8 = 1;
+const DENSE_POSITIONS_TAG: u8 = 2;
+const COMMITMENT_FRONTIER_TAG: u8 = 3;
+
+/// Local chunk id of a subtree that isn't a Merk.
+///
+/// Ids carry everything needed to read the chunk from the prefix of the
+/// subtree, as the source only learns the prefix and tree type from the
+/// global chunk id. The empty local id stands for an empty tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NonMerkChunkId {
+    /// Leaves `start..start + count` of the MMR of an MmrTree
+    MmrLeaves { start: u64, count: u64 },
+    /// Compacted chunk blobs `start..start + count` of a BulkAppendTree or
+    /// CommitmentTree, which are the leaves of its chunk MMR
+    ChunkBlobs { start: u64, count: u64 },
+    /// Values at positions `start..start + count` of a dense tree, or of the
+    /// buffer of a BulkAppendTree or CommitmentTree
+    DensePositions { start: u16, count: u16 },
+    /// Sinsemilla frontier of a CommitmentTree
+    CommitmentFrontier,
+}
+
+impl NonMerkChunkId {
+    fn encode(&self) -> Vec<u8> {
+        match *self {
+            NonMerkChunkId::MmrLeaves { start, count } => {
+                let mut bytes = vec![MMR_LEAVES_TAG];
+                bytes.extend_from_slice(&start.to_be_bytes());
+                bytes.extend_from_slice(&count.to_be_bytes());
+                bytes
+            }
+            NonMerkChunkId::ChunkBlobs { start, count } => {
+                let mut bytes = vec![CHUNK_BLOBS_TAG];
+                bytes.extend_from_slice(&start.to_be_bytes());
+                bytes.extend_from_slice(&count.to_be_bytes());
+                bytes
+            }
+            NonMerkChunkId::DensePositions { start, count } => {
+                let mut bytes = vec![DENSE_POSITIONS_TAG];
+                bytes.extend_from_slice(&start.to_be_bytes());
+                bytes.extend_from_slice(&count.to_be_bytes());
+                bytes
+            }
+            NonMerkChunkId::CommitmentFrontier => vec![COMMITMENT_FRONTIER_TAG],
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = || Error::CorruptedData(format!("invalid chunk id {}", hex::encode(bytes)));
+        let (tag, rest) = bytes.split_first().ok_or_else(invalid)?;
+        let chunk_id = match (*tag, rest.len()) {
+            (MMR_LEAVES_TAG | CHUNK_BLOBS_TAG, 16) => {
+                let start = u64::from_be_bytes(rest[..8].try_into().map_err(|_| invalid())?);
+                let count = u64::from_be_bytes(rest[8..].try_into().map_err(|_| invalid())?);
+                let max_count = if *tag == MMR_LEAVES_TAG {
+                    MMR_LEAVES_PER_CHUNK
+                } else {
+                    CHUNK_BLOBS_PER_CHUNK
+                };
+                if count == 0 || count > max_count || start.checked_add(count).is_none() {
+                    return Err(invalid());
+                }
+                if *tag == MMR_LEAVES_TAG {
+                    NonMerkChunkId::MmrLeaves { start, count }
+                } else {
+                    NonMerkChunkId::ChunkBlobs { start, count }
+                }
+            }
+            (DENSE_POSITIONS_TAG, 4) => {
+                let start = u16::from_be_bytes([rest[0], rest[1]]);
+                let count = u16::from_be_bytes([rest[2], rest[3]]);
+                if count == 0
+                    || count > DENSE_POSITIONS_PER_CHUNK
+                    || start.checked_add(count).is_none()
+                {
+                    return Err(invalid());
+                }
+                NonMerkChunkId::DensePositions { start, count }
+            }
+            (COMMITMENT_FRONTIER_TAG, 0) => NonMerkChunkId::CommitmentFrontier,
+            _ => return Err(invalid()),
+        };
+        Ok(chunk_id)
+    }
+
+    /// Number of values the chunk is made of.
+    fn value_count(&self) -> u64 {
+        match *self {
+            NonMerkChunkId::MmrLeaves { count, .. } | NonMerkChunkId::ChunkBlobs { count, .. } => {
+                count
+            }
+            NonMerkChunkId::DensePositions { count, .. } => count as u64,
+            NonMerkChunkId::CommitmentFrontier => 1,
+        }
+    }
+}
+
+/// Splits `0..len` into ranges of at most `max` values.
+fn ranges(len: u64, max: u64) -> impl Iterator<Item = (u64, u64)> {
+    (0..len)
+        .step_by(max as usize)
+        .map(move |start| (start, max.min(len - start)))
+}
+
+/// Ids of the chunks the data of a subtree with `element` is split into, as
+/// the chunks that must be applied in order and the chunks that can be
+/// applied in any order.
+fn chunk_ids(element: &Element) -> Result<(VecDeque<NonMerkChunkId>, Vec<NonMerkChunkId>), Error> {
+    let dense_positions = |count: u16| {
+        ranges(count as u64, DENSE_POSITIONS_PER_CHUNK as u64)
+            .map(|(start, count)| NonMerkChunkId::DensePositions {
+                start: start as u16,
+                count: count as u16,
+            })
+            .collect::<Vec<_>>()
+    };
+    match element {
+        Element::MmrTree(mmr_size, _) => Ok((
+            ranges(mmr_size_to_leaf_count(*mmr_size), MMR_LEAVES_PER_CHUNK)
+                .map(|(start, count)| NonMerkChunkId::MmrLeaves { start, count })
+                .collect(),
+            vec![],
+        )),
+        Element::DenseAppendOnlyFixedSizeTree(count, ..) => {
+            Ok((VecDeque::new(), dense_positions(*count)))
+        }
+        Element::BulkAppendTree(total_count, chunk_power, _)
+        | Element::CommitmentTree(total_count, chunk_power, _) => {
+            if !(1..=16).contains(chunk_power) {
+                return Err(Error::CorruptedData(format!(
+                    "invalid chunk power {chunk_power} of {}",
+                    element.type_str()
+                )));
+            }
+            let epoch_size = 1u64 << chunk_power;
+            let mut unordered = dense_positions((total_count % epoch_size) as u16);
+            if matches!(element, Element::CommitmentTree(..)) && *total_count > 0 {
+                unordered.push(NonMerkChunkId::CommitmentFrontier);
+            }
+            Ok((
+                ranges(total_count / epoch_size, CHUNK_BLOBS_PER_CHUNK)
+                    .map(|(start, count)| NonMerkChunkId::ChunkBlobs { start, count })
+                    .collect(),
+                unordered,
+            ))
+        }
+        _ => Err(Error::CorruptedData(format!(
+            "{} doesn't keep its data outside of a merk",
+            element.type_str()
+        ))),
+    }
+}
+
+/// Restores a subtree that isn't a Merk from its chunks.
+pub(crate) struct NonMerkRestorer<'db> {
+    storage: PrefixedBackendImmediateStorageContext<'db>,
+    /// Element of the subtree in its parent Merk
+    element: Element,
+    /// Size of the MMR restored so far
+    mmr_size: u64,
+    /// Chunks to apply in order which weren't requested yet
+    ordered_chunk_ids: VecDeque<NonMerkChunkId>,
+    /// Expected value hash of the element in the parent Merk
+    expected_value_hash: CryptoHash,
+    /// Hash of the serialized element
+    actual_value_hash: CryptoHash,
+}
+
+impl<'db> NonMerkRestorer<'db> {
+    /// Makes a restorer of the subtree of `element` writing to `storage`,
+    /// with the ids of the first chunks to request. No ids are returned for
+    /// an empty tree.
+    pub(crate) fn new(
+        storage: PrefixedBackendImmediateStorageContext<'db>,
+        element: Element,
+        expected_value_hash: CryptoHash,
+        actual_value_hash: CryptoHash,
+    ) -> Result<(Self, Vec<Vec<u8>>), Error> {
+        let (mut ordered_chunk_ids, unordered_chunk_ids) = chunk_ids(&element)?;
+        let first_chunk_ids = ordered_chunk_ids
+            .pop_front()
+            .into_iter()
+            .chain(unordered_chunk_ids)
+            .map(|chunk_id| chunk_id.encode())
+            .collect();
+        Ok((
+            NonMerkRestorer {
+                storage,
+                element,
+                mmr_size: 0,
+                ordered_chunk_ids,
+                expected_value_hash,
+                actual_value_hash,
+            },
+            first_chunk_ids,
+        ))
+    }
+
+    /// Makes a restorer of the subtree of `element` carrying on from the
+    /// chunks written by an interrupted restorer, which was waiting for
+    /// `pending_chunk_ids`.
+    pub(crate) fn resume(
+        storage: PrefixedBackendImmediateStorageContext<'db>,
+        element: Element,
+        expected_value_hash: CryptoHash,
+        actual_value_hash: CryptoHash,
+        pending_chunk_ids: &BTreeSet<Vec<u8>>,
+    ) -> Result<Self, Error> {
+        let (mut ordered_chunk_ids, _) = chunk_ids(&element)?;
+        // Ordered chunks before the pending one were applied, and the ones
+        // after it weren't requested yet
+        let mut leaf_count = 0;
+        while let Some(chunk_id) = ordered_chunk_ids.pop_front() {
+            if pending_chunk_ids.contains(&chunk_id.encode()) {
+                break;
+            }
+            leaf_count += chunk_id.value_count();
+        }
+        let mmr_size = leaf_count.checked_sub(1).map_or(0, leaf_index_to_mmr_size);
+        Ok(NonMerkRestorer {
+            storage,
+            element,
+            mmr_size,
+            ordered_chunk_ids,
+            expected_value_hash,
+            actual_value_hash,
+        })
+    }
+
+    /// Writes the values of a chunk to storage and returns the ids of the
+    /// chunks that can be requested next.
+    pub(crate) fn process_chunk(
+        &mut self,
+        chunk_id: &[u8],
+        chunk: &[u8],
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        if chunk_id.is_empty() {
+            return if chunk.is_empty() {
+                Ok(vec![])
+            } else {
+                Err(Error::CorruptedData(
+                    "expected an empty chunk for an empty tree".to_string(),
+                ))
+            };
+        }
+        let chunk_id = NonMerkChunkId::decode(chunk_id)?;
+        let values = unpack_nested_bytes(chunk)?;
+        if values.len() as u64 != chunk_id.value_count() {
+            return Err(Error::CorruptedData(format!(
+                "expected {} values in chunk, got {}",
+                chunk_id.value_count(),
+                values.len()
+            )));
+        }
+
+        match chunk_id {
+            NonMerkChunkId::MmrLeaves { start, .. } => {
+                self.push_leaves(start, values, MmrKeySize::U64)?
+            }
+            NonMerkChunkId::ChunkBlobs { start, .. } => {
+                self.push_leaves(start, values, MmrKeySize::U32)?
+            }
+            NonMerkChunkId::DensePositions { start, .. } => {
+                for (position, value) in (start..).zip(values) {
+                    self.storage
+                        .put(position_key(position), &value, None, None)
+                        .unwrap()?;
+                }
+            }
+            NonMerkChunkId::CommitmentFrontier => {
+                let frontier = &values[0];
+                CommitmentFrontier::deserialize(frontier).map_err(|e| {
+                    Error::CorruptedData(format!("invalid commitment frontier: {e}"))
+                })?;
+                self.storage
+                    .put(COMMITMENT_TREE_DATA_KEY, frontier, None, None)
+                    .unwrap()?;
+            }
+        }
+
+        let next_chunk_id = matches!(
+            chunk_id,
+            NonMerkChunkId::MmrLeaves { .. } | NonMerkChunkId::ChunkBlobs { .. }
+        )
+        .then(|| self.ordered_chunk_ids.pop_front())
+        .flatten();
+        Ok(next_chunk_id
+            .map(|chunk_id| chunk_id.encode())
+            .into_iter()
+            .collect())
+    }
+
+    /// Pushes leaves to the MMR restored so far, which must hold `start`
+    /// leaves.
+    fn push_leaves(
+        &mut self,
+        start: u64,
+        leaves: Vec<Vec<u8>>,
+        key_size: MmrKeySize,
+    ) -> Result<(), Error> {
+        if start != mmr_size_to_leaf_count(self.mmr_size) {
+            return Err(Error::CorruptedData(format!(
+                "expected leaves from {}, got leaves from {start}",
+                mmr_size_to_leaf_count(self.mmr_size)
+            )));
+        }
+        let store = MmrStore::with_key_size(&self.storage, key_size);
+        let mut mmr = MMR::new(self.mmr_size, &store);
+        for leaf in leaves {
+            mmr.push(MmrNode::leaf(leaf))
+                .unwrap()
+                .map_err(|e| Error::CorruptedData(format!("MMR push failed: {e}")))?;
+        }
+        mmr.commit()
+            .unwrap()
+            .map_err(|e| Error::CorruptedData(format!("MMR commit failed: {e}")))?;
+        self.mmr_size = mmr.mmr_size;
+        Ok(())
+    }
+
+    /// Checks the root of the restored tree against the hash of its element
+    /// in the parent Merk.
+    pub(crate) fn finalize(
+        self,
+        db: &GroveDb,
+        path: &[Vec<u8>],
+        transaction: &Transaction,
+    ) -> Result<(), Error> {
+        let NonMerkRestorer {
+            storage,
+            element,
+            expected_value_hash,
+            actual_value_hash,
+            ..
+        } = self;
+        drop(storage);
+
+        let root_hash =
+            db.compute_non_merk_child_hash(&element, path.into(), transaction, NULL_HASH);
+        if combine_hash(&actual_value_hash, &root_hash).unwrap() != expected_value_hash {
+            return Err(Error::CorruptedData(format!(
+                "restored {} doesn't match the hash committed in its parent",
+                element.type_str()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl GroveDb {
+    /// Reads a chunk of a subtree that isn't a Merk from its prefix.
+    pub(crate) fn fetch_non_merk_chunk(
+        &self,
+        prefix: SubtreePrefix,
+        chunk_id: &[u8],
+        transaction: &Transaction,
+    ) -> Result<Vec<u8>, Error> {
+        if chunk_id.is_empty() {
+            return Ok(vec![]);
+        }
+        let storage = self
+            .db
+            .get_transactional_storage_context_by_subtree_prefix(prefix, None, transaction)
+            .unwrap();
+        let get = |key: &[u8]| -> Result<Vec<u8>, Error> {
+            storage.get(key).unwrap()?.ok_or_else(|| {
+                Error::CorruptedData(format!(
+                    "missing value at {} of subtree {}",
+                    hex::encode(key),
+                    hex::encode(prefix)
+                ))
+            })
+        };
+        let get_leaves = |start: u64, count: u64, key_size: MmrKeySize| {
+            let store = MmrStore::with_key_size(&storage, key_size);
+            (start..start + count)
+                .map(|leaf_index| {
+                    (&store)
+                        .element_at_position(leaf_index_to_pos(leaf_index))
+                        .unwrap()
+                        .map_err(|e| Error::CorruptedData(format!("cannot read MMR leaf: {e}")))?
+                        .and_then(MmrNode::into_value)
+                        .ok_or_else(|| {
+                            Error::CorruptedData(format!(
+                                "missing MMR leaf {leaf_index} of subtree {}",
+                                hex::encode(prefix)
+                            ))
+                        })
+                })
+                .collect::<Result<Vec<_>, Error>>()
+        };
+
+        let values = match NonMerkChunkId::decode(chunk_id)? {
+            NonMerkChunkId::MmrLeaves { start, count } => {
+                get_leaves(start, count, MmrKeySize::U64)?
+            }
+            NonMerkChunkId::ChunkBlobs { start, count } => {
+                get_leaves(start, count, MmrKeySize::U32)?
+            }
+            NonMerkChunkId::DensePositions { start, count } => (start..start + count)
+                .map(|position| get(&position_key(position)))
+                .collect::<Result<_, _>>()?,
+            NonMerkChunkId::CommitmentFrontier => vec![get(COMMITMENT_TREE_DATA_KEY)?],
+        };
+        pack_nested_bytes(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NonMerkChunkId;
+
+    #[test]
+    fn chunk_ids_round_trip() {
+        for chunk_id in [
+            NonMerkChunkId::MmrLeaves {
+                start: 512,
+                count: 256,
+            },
+            NonMerkChunkId::ChunkBlobs { start: 8, count: 3 },
+            NonMerkChunkId::DensePositions {
+                start: 256,
+                count: 17,
+            },
+            NonMerkChunkId::CommitmentFrontier,
+        ] {
+            assert_eq!(
+                NonMerkChunkId::decode(&chunk_id.encode()).unwrap(),
+                chunk_id
+            );
+        }
+    }
+
+    #[test]
+    fn oversized_chunk_ids_are_rejected() {
+        for chunk_id in [
+            NonMerkChunkId::MmrLeaves {
+                start: 0,
+                count: 257,
+            },
+            NonMerkChunkId::ChunkBlobs { start: 0, count: 0 },
+            NonMerkChunkId::DensePositions {
+                start: u16::MAX,
+                count: 2,
+            },
+        ] {
+            assert!(NonMerkChunkId::decode(&chunk_id.encode()).is_err());
+        }
+        assert!(NonMerkChunkId::decode(
+            &[NonMerkChunkId::CommitmentFrontier.encode(), vec![0]].concat()
+        )
+        .is_err());
+    }
+}
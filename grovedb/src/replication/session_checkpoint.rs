@@ -0,0 +1,176 @@
+//! Progress of a state sync session kept in the metadata of the root tree,
+//! so a session interrupted by a restart can be resumed with
+//! [`GroveDb::resume_syncing_session`].
+//!
+//! The session record is written whenever the session commits its
+//! transaction, next to the data restored so far, so what is on disk always
+//! matches the record. Completed subtrees get an entry of their own as soon
+//! as they are done, as a snapshot can hold far more of them than would fit
+//! a record rewritten on every commit.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use bincode::{Decode, Encode};
+use grovedb_merk::CryptoHash;
+use grovedb_path::SubtreePath;
+use grovedb_storage::{RawIterator, Storage, StorageContext};
+
+use crate::{Error, GroveDb, SubtreePrefix, Transaction};
+
+/// Key of the session record in the metadata of the root tree.
+const SESSION_RECORD_KEY: &[u8] = b"\0grovedb_state_sync_session";
+
+/// Prefix of the keys of completed subtree entries in the metadata of the
+/// root tree.
+const PROCESSED_PREFIX_KEY_PREFIX: &[u8] = b"\0grovedb_state_sync_processed/";
+
+/// State of a sync session when its transaction was last committed.
+#[derive(Debug, Encode, Decode)]
+pub(crate) struct SessionRecord {
+    /// Root hash of the snapshot being synced
+    pub(crate) app_hash: [u8; 32],
+    /// Version of the state sync protocol
+    pub(crate) version: u16,
+    pub(crate) subtrees_batch_size: u64,
+    pub(crate) num_processed_subtrees_in_batch: u64,
+    /// Whether the session syncs over an older state
+    pub(crate) incremental: bool,
+    /// Subtrees being restored
+    pub(crate) subtrees: Vec<SubtreeRecord>,
+    /// Subtrees discovered but not started yet, as in `SubtreesMetadata`
+    pub(crate) pending_discovered_subtrees:
+        Option<BTreeMap<SubtreePrefix, (Vec<Vec<u8>>, CryptoHash, CryptoHash)>>,
+}
+
+/// Subtree being restored by a sync session.
+#[derive(Debug, Clone, Encode, Decode)]
+pub(crate) struct SubtreeRecord {
+    pub(crate) prefix: SubtreePrefix,
+    pub(crate) path: Vec<Vec<u8>>,
+    /// Value hash of the subtree element in the parent Merk
+    pub(crate) expected_hash: CryptoHash,
+    /// Hash of the serialized subtree element, `None` for the root tree
+    pub(crate) actual_hash: Option<CryptoHash>,
+    /// Local ids of the chunks requested and not applied yet
+    pub(crate) pending_chunks: BTreeSet<Vec<u8>>,
+    pub(crate) num_processed_chunks: u64,
+}
+
+impl SessionRecord {
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+        bincode::encode_to_vec(self, config).map_err(|e| {
+            Error::CorruptedData(format!("unable to encode state sync session record {}", e))
+        })
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+        let (record, _) = bincode::decode_from_slice(bytes, config).map_err(|e| {
+            Error::CorruptedData(format!("unable to decode state sync session record {}", e))
+        })?;
+        Ok(record)
+    }
+}
+
+fn processed_prefix_key(prefix: &SubtreePrefix) -> Vec<u8> {
+    [PROCESSED_PREFIX_KEY_PREFIX, prefix].concat()
+}
+
+impl GroveDb {
+    /// Writes the record of a sync session to `transaction`.
+    pub(crate) fn write_sync_session_record(
+        &self,
+        record: &SessionRecord,
+        transaction: &Transaction,
+    ) -> Result<(), Error> {
+        let bytes = record.encode()?;
+        self.db
+            .get_immediate_storage_context(SubtreePath::empty(), transaction)
+            .unwrap()
+            .put_meta(SESSION_RECORD_KEY, &bytes, None)
+            .unwrap()
+            .map_err(Into::into)
+    }
+
+    /// Reads the record of the last sync session, if it wasn't committed.
+    pub(crate) fn sync_session_record(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Option<SessionRecord>, Error> {
+        self.db
+            .get_immediate_storage_context(SubtreePath::empty(), transaction)
+            .unwrap()
+            .get_meta(SESSION_RECORD_KEY)
+            .unwrap()?
+            .map(|bytes| SessionRecord::decode(&bytes))
+            .transpose()
+    }
+
+    /// Records that the subtree with `prefix` was completely restored.
+    pub(crate) fn mark_sync_subtree_processed(
+        &self,
+        prefix: &SubtreePrefix,
+        transaction: &Transaction,
+    ) -> Result<(), Error> {
+        self.db
+            .get_immediate_storage_context(SubtreePath::empty(), transaction)
+            .unwrap()
+            .put_meta(processed_prefix_key(prefix), &[], None)
+            .unwrap()
+            .map_err(Into::into)
+    }
+
+    /// Prefixes of the subtrees the last sync session completely restored.
+    pub(crate) fn sync_processed_prefixes(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<BTreeSet<SubtreePrefix>, Error> {
+        let storage = self
+            .db
+            .get_immediate_storage_context(SubtreePath::empty(), transaction)
+            .unwrap();
+        let mut raw_iter = storage.raw_iter_meta();
+        raw_iter.seek(PROCESSED_PREFIX_KEY_PREFIX).unwrap();
+
+        let mut prefixes = BTreeSet::new();
+        while raw_iter.valid().unwrap() {
+            let Some(prefix) = raw_iter
+                .key()
+                .unwrap()
+                .and_then(|key| key.strip_prefix(PROCESSED_PREFIX_KEY_PREFIX))
+                .map(SubtreePrefix::try_from)
+            else {
+                break;
+            };
+            prefixes.insert(prefix.map_err(|_| {
+                Error::CorruptedData("invalid prefix of a synced subtree".to_string())
+            })?);
+            raw_iter.next().unwrap();
+        }
+        Ok(prefixes)
+    }
+
+    /// Removes the record and the completed subtrees of the last sync
+    /// session.
+    pub(crate) fn clear_sync_session_record(&self, transaction: &Transaction) -> Result<(), Error> {
+        let processed_prefixes = self.sync_processed_prefixes(transaction)?;
+        let storage = self
+            .db
+            .get_immediate_storage_context(SubtreePath::empty(), transaction)
+            .unwrap();
+        for prefix in &processed_prefixes {
+            storage
+                .delete_meta(processed_prefix_key(prefix), None)
+                .unwrap()?;
+        }
+        storage
+            .delete_meta(SESSION_RECORD_KEY, None)
+            .unwrap()
+            .map_err(Into::into)
+    }
+}
@@ -1,24 +1,31 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
-    fmt,
+    fmt, iter,
     marker::PhantomPinned,
     mem,
+    num::NonZeroUsize,
     pin::Pin,
+    thread,
 };
 
 use grovedb_merk::{
-    tree::{kv::ValueDefinedCostType, value_hash},
+    element::{get::ElementFetchFromStorageExtensions, tree_type::ElementTreeTypeExtensions},
+    proofs::Op,
+    tree::{combine_hash, hash::NULL_HASH, kv::ValueDefinedCostType, value_hash},
     tree_type::TreeType,
-    CryptoHash, Restorer,
+    ChunkExpectation, CryptoHash, Restorer, VerifiedChunk,
 };
 use grovedb_path::SubtreePath;
 use grovedb_storage::{
-    rocksdb_storage::{PrefixedRocksDbImmediateStorageContext, RocksDbStorage},
-    StorageContext,
+    backend::{PrefixedBackendImmediateStorageContext, StorageBackend},
+    Storage, StorageBatch, StorageContext,
 };
 use grovedb_version::version::GroveVersion;
 
 use super::{
+    chunk_envelope::ChunkEnvelope,
+    non_merk_chunks::NonMerkRestorer,
+    session_checkpoint::{SessionRecord, SubtreeRecord},
     utils::{decode_vec_ops, encode_global_chunk_id, path_to_string},
     CURRENT_STATE_SYNC_VERSION,
 };
@@ -26,7 +33,8 @@ use crate::{
     element::elements_iterator::ElementIteratorExtensions,
     replication,
     replication::utils::{pack_nested_bytes, unpack_nested_bytes},
-    Element, Error, GroveDb, Transaction,
+    subtree_registry::SubtreeRegistryUpdate,
+    Element, Error, GroveDb, OpenedMerkForReplication, Transaction,
 };
 
 /// Number of elements packed together
@@ -34,10 +42,19 @@ pub const CONST_GROUP_PACKING_SIZE: usize = 32;
 
 pub(crate) type SubtreePrefix = [u8; 32];
 
+/// Restorer of a subtree, depending on where the subtree keeps its data.
+enum SubtreeRestorer<'db> {
+    /// Restorer of a Merk from chunk proofs
+    Merk(Restorer<PrefixedBackendImmediateStorageContext<'db>>),
+    /// Restorer of an MmrTree, BulkAppendTree, DenseAppendOnlyFixedSizeTree
+    /// or CommitmentTree from the values it is built from
+    NonMerk(NonMerkRestorer<'db>),
+}
+
 /// Struct governing the state synchronization of one subtree.
 struct SubtreeStateSyncInfo<'db> {
     /// Current Chunk restorer
-    restorer: Restorer<PrefixedRocksDbImmediateStorageContext<'db>>,
+    restorer: SubtreeRestorer<'db>,
 
     /// Set of global chunk ids requested to be fetched and pending for
     /// processing. For the description of global chunk id check
@@ -55,6 +72,12 @@ struct SubtreeStateSyncInfo<'db> {
 
     /// Number of processed chunks in current prefix (Path digest)
     num_processed_chunks: usize,
+
+    /// Expected value hash of the subtree element in the parent Merk
+    expected_hash: CryptoHash,
+
+    /// Hash of the serialized subtree element, `None` for the root tree
+    actual_hash: Option<CryptoHash>,
 }
 
 impl SubtreeStateSyncInfo<'_> {
@@ -65,6 +88,8 @@ impl SubtreeStateSyncInfo<'_> {
     ///   applied.
     /// - `chunk_data`: A byte slice containing the chunk proof operators,
     ///   encoded as bytes.
+    /// - `verified_chunk`: The chunk proof of a Merk subtree if it was already
+    ///   decoded and verified, see `MultiStateSyncSession::apply_chunks`.
     /// - `grove_version`: A reference to the `GroveVersion` being used for
     ///   synchronization.
     ///
@@ -95,6 +120,7 @@ impl SubtreeStateSyncInfo<'_> {
         &mut self,
         chunk_id: &[u8],
         chunk_data: &[u8],
+        verified_chunk: Option<Result<VerifiedChunk, Error>>,
         grove_version: &GroveVersion,
     ) -> Result<Vec<Vec<u8>>, Error> {
         let mut res = vec![];
@@ -105,30 +131,30 @@ impl SubtreeStateSyncInfo<'_> {
             ));
         }
         self.pending_chunks.remove(chunk_id);
+        let restorer = match &mut self.restorer {
+            SubtreeRestorer::Merk(restorer) => restorer,
+            SubtreeRestorer::NonMerk(restorer) => {
+                let next_chunk_ids = restorer.process_chunk(chunk_id, chunk_data)?;
+                self.num_processed_chunks += 1;
+                self.pending_chunks.extend(next_chunk_ids.iter().cloned());
+                return Ok(next_chunk_ids);
+            }
+        };
         if !chunk_data.is_empty() {
-            match decode_vec_ops(chunk_data) {
-                Ok(ops) => {
-                    match self.restorer.process_chunk(chunk_id, ops, grove_version) {
-                        Ok(next_chunk_ids) => {
-                            self.num_processed_chunks += 1;
-                            for next_chunk_id in next_chunk_ids {
-                                self.pending_chunks.insert(next_chunk_id.clone());
-                                res.push(next_chunk_id);
-                            }
-                        }
-                        Err(e) => {
-                            return Err(Error::InternalError(format!(
-                                "Unable to process incoming chunk: {e}"
-                            )));
-                        }
-                    };
+            let next_chunk_ids = match verified_chunk {
+                Some(verified_chunk) => {
+                    restorer.apply_verified_chunk(verified_chunk?, grove_version)
                 }
-                Err(e) => {
-                    return Err(Error::CorruptedData(format!(
-                        "Unable to decode incoming chunk: {e}"
-                    )));
+                None => {
+                    restorer.process_chunk(chunk_id, decode_merk_chunk(chunk_data)?, grove_version)
                 }
             }
+            .map_err(|e| Error::InternalError(format!("Unable to process incoming chunk: {e}")))?;
+            self.num_processed_chunks += 1;
+            for next_chunk_id in next_chunk_ids {
+                self.pending_chunks.insert(next_chunk_id.clone());
+                res.push(next_chunk_id);
+            }
         }
 
         Ok(res)
@@ -136,7 +162,11 @@ impl SubtreeStateSyncInfo<'_> {
 }
 
 impl<'tx> SubtreeStateSyncInfo<'tx> {
-    pub fn new(restorer: Restorer<PrefixedRocksDbImmediateStorageContext<'tx>>) -> Self {
+    pub fn new(
+        restorer: SubtreeRestorer<'tx>,
+        expected_hash: CryptoHash,
+        actual_hash: Option<CryptoHash>,
+    ) -> Self {
         SubtreeStateSyncInfo {
             restorer,
             root_key: None,
@@ -144,10 +174,47 @@ impl<'tx> SubtreeStateSyncInfo<'tx> {
             pending_chunks: Default::default(),
             current_path: vec![],
             num_processed_chunks: 0,
+            expected_hash,
+            actual_hash,
+        }
+    }
+
+    /// Returns what is needed to resume syncing the subtree with `prefix`.
+    fn record(&self, prefix: SubtreePrefix) -> SubtreeRecord {
+        SubtreeRecord {
+            prefix,
+            path: self.current_path.clone(),
+            expected_hash: self.expected_hash,
+            actual_hash: self.actual_hash,
+            pending_chunks: self.pending_chunks.clone(),
+            num_processed_chunks: self.num_processed_chunks as u64,
         }
     }
 }
 
+/// Chunks of one subtree received together, with their local chunk ids.
+struct SubtreeChunks {
+    prefix: SubtreePrefix,
+    chunk_ids: Vec<Vec<u8>>,
+    chunks: Vec<Vec<u8>>,
+}
+
+/// Decodes the chunk proof operators of a Merk subtree.
+fn decode_merk_chunk(chunk_data: &[u8]) -> Result<Vec<Op>, Error> {
+    decode_vec_ops(chunk_data)
+        .map_err(|e| Error::CorruptedData(format!("Unable to decode incoming chunk: {e}")))
+}
+
+/// Decodes a chunk of a Merk subtree and verifies it against `expectation`.
+fn verify_merk_chunk(
+    expectation: ChunkExpectation,
+    chunk_data: &[u8],
+) -> Result<VerifiedChunk, Error> {
+    expectation
+        .verify(decode_merk_chunk(chunk_data)?)
+        .map_err(|e| Error::InternalError(format!("Unable to process incoming chunk: {e}")))
+}
+
 /// Struct governing the state synchronization process.
 pub struct MultiStateSyncSession<'db> {
     /// GroveDb instance to apply changes to
@@ -177,6 +244,10 @@ pub struct MultiStateSyncSession<'db> {
     /// Metadata for newly discovered subtrees that are pending processing.
     pending_discovered_subtrees: Option<SubtreesMetadata>,
 
+    /// Whether the database holds an older state the snapshot is synced
+    /// over, see [`GroveDb::start_incremental_syncing`].
+    incremental: bool,
+
     /// Transaction used for the synchronization process.
     /// This is placed last to ensure it is dropped last.
     transaction: Transaction<'db>,
@@ -198,10 +269,24 @@ impl<'db> MultiStateSyncSession<'db> {
             subtrees_batch_size,
             num_processed_subtrees_in_batch: 0,
             pending_discovered_subtrees: None,
+            incremental: false,
             _pin: PhantomPinned,
         })
     }
 
+    /// Initializes a new state sync session over the older state held by
+    /// `db`, which only restores the parts of the snapshot that differ.
+    pub(crate) fn new_incremental(
+        db: &'db GroveDb,
+        app_hash: [u8; 32],
+        subtrees_batch_size: usize,
+    ) -> Pin<Box<Self>> {
+        let mut session = Self::new(db, app_hash, subtrees_batch_size);
+        // SAFETY: no field is moved and the transaction isn't touched
+        unsafe { Pin::as_mut(&mut session).get_unchecked_mut() }.incremental = true;
+        session
+    }
+
     /// Returns true if there are no prefixes currently being synced.
     pub fn is_empty(&self) -> bool {
         self.current_prefixes.is_empty()
@@ -268,6 +353,7 @@ impl<'db> MultiStateSyncSession<'db> {
             )));
         }
 
+        session.db.clear_sync_session_record(&session.transaction)?;
         session
             .db
             .commit_transaction(session.transaction)
@@ -294,6 +380,144 @@ impl<'db> MultiStateSyncSession<'db> {
         Ok(())
     }
 
+    /// Resumes the session interrupted after its last checkpoint if it was
+    /// syncing the snapshot with `app_hash`.
+    pub(crate) fn resume(
+        db: &'db GroveDb,
+        app_hash: [u8; 32],
+        grove_version: &GroveVersion,
+    ) -> Result<Option<Pin<Box<Self>>>, Error> {
+        let mut session = Self::new(db, app_hash, 0);
+        let Some(record) = db.sync_session_record(&session.transaction)? else {
+            return Ok(None);
+        };
+        // Chunks of another protocol version can't be applied to the session
+        if record.app_hash != app_hash || record.version != CURRENT_STATE_SYNC_VERSION {
+            return Ok(None);
+        }
+        let processed_prefixes = db.sync_processed_prefixes(&session.transaction)?;
+
+        // SAFETY: no field is moved and the transaction isn't touched
+        let this = unsafe { Pin::as_mut(&mut session).get_unchecked_mut() };
+        this.version = record.version;
+        this.subtrees_batch_size = record.subtrees_batch_size as usize;
+        this.num_processed_subtrees_in_batch = record.num_processed_subtrees_in_batch as usize;
+        this.incremental = record.incremental;
+        this.processed_prefixes = processed_prefixes;
+        this.pending_discovered_subtrees = record
+            .pending_discovered_subtrees
+            .map(|data| SubtreesMetadata { data });
+
+        for subtree in record.subtrees {
+            session.resume_subtree_sync_info(subtree, grove_version)?;
+        }
+
+        // The last commit was the one between two batches of subtrees
+        if session.current_prefixes.is_empty()
+            && let Some(subtrees_metadata) = session.as_mut().pending_discovered_subtrees().take()
+        {
+            *session.as_mut().num_processed_subtrees_in_batch() = 0;
+            session.prepare_sync_state_sessions(subtrees_metadata, grove_version)?;
+        }
+
+        Ok(Some(session))
+    }
+
+    /// Removes the checkpoint of an interrupted session, so it can't be
+    /// resumed once this session commits.
+    pub(crate) fn discard_interrupted_session(&self) -> Result<(), Error> {
+        self.db.clear_sync_session_record(&self.transaction)
+    }
+
+    /// Writes the state of the session to its transaction, with `subtrees`
+    /// being restored.
+    fn write_session_record(&self, subtrees: Vec<SubtreeRecord>) -> Result<(), Error> {
+        let record = SessionRecord {
+            app_hash: self.app_hash,
+            version: self.version,
+            subtrees_batch_size: self.subtrees_batch_size as u64,
+            num_processed_subtrees_in_batch: self.num_processed_subtrees_in_batch as u64,
+            incremental: self.incremental,
+            subtrees,
+            pending_discovered_subtrees: self
+                .pending_discovered_subtrees
+                .as_ref()
+                .map(|subtrees_metadata| subtrees_metadata.data.clone()),
+        };
+        self.db
+            .write_sync_session_record(&record, &self.transaction)
+    }
+
+    /// Persists the progress of the session, including the chunks applied to
+    /// subtrees that are only partially restored, so that it can be picked
+    /// up with [`GroveDb::resume_syncing_session`] after a restart. Chunks
+    /// applied after the last checkpoint are requested again by the resumed
+    /// session.
+    ///
+    /// This commits the session transaction, after which the restorers of
+    /// the subtrees being restored are rebuilt from the data written so far.
+    pub fn checkpoint(
+        self: &mut Pin<Box<MultiStateSyncSession<'db>>>,
+        grove_version: &GroveVersion,
+    ) -> Result<(), Error> {
+        let subtrees: Vec<SubtreeRecord> = self
+            .current_prefixes
+            .iter()
+            .map(|(prefix, sync_info)| sync_info.record(*prefix))
+            .collect();
+        self.write_session_record(subtrees.clone())?;
+
+        // Restorers hold storage contexts of the transaction to commit
+        self.as_mut().current_prefixes().clear();
+        // SAFETY: `self.current_prefixes` was just cleared so there are no
+        // references to the transaction we're about to replace
+        unsafe {
+            self.set_new_transaction()?;
+        }
+
+        for subtree in subtrees {
+            self.resume_subtree_sync_info(subtree, grove_version)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the packed global chunk ids the session waits for, as
+    /// returned by [`Self::apply_chunk`], which must be requested again once
+    /// a session is resumed.
+    pub fn pending_chunk_ids(&self) -> Result<Vec<Vec<u8>>, Error> {
+        let whole_subtree_chunk_id: &[u8] = &[];
+        let mut global_chunk_ids = vec![];
+        for (prefix, sync_info) in &self.current_prefixes {
+            if sync_info.pending_chunks.contains(whole_subtree_chunk_id) {
+                // The chunk of the root tree is requested with the app hash
+                if sync_info.current_path.is_empty() {
+                    return Ok(vec![self.app_hash.to_vec()]);
+                }
+                global_chunk_ids.push(encode_global_chunk_id(
+                    *prefix,
+                    sync_info.root_key.clone(),
+                    sync_info.tree_type,
+                    vec![],
+                )?);
+                continue;
+            }
+            let local_chunk_ids: Vec<Vec<u8>> = sync_info.pending_chunks.iter().cloned().collect();
+            for grouped_ids in local_chunk_ids.chunks(CONST_GROUP_PACKING_SIZE) {
+                global_chunk_ids.push(encode_global_chunk_id(
+                    *prefix,
+                    sync_info.root_key.clone(),
+                    sync_info.tree_type,
+                    grouped_ids.to_vec(),
+                )?);
+            }
+        }
+
+        global_chunk_ids
+            .chunks(CONST_GROUP_PACKING_SIZE)
+            .map(|grouped_global_chunk_ids| pack_nested_bytes(grouped_global_chunk_ids.to_vec()))
+            .collect()
+    }
+
     /// Adds synchronization information for a subtree into the current
     /// synchronization session.
     ///
@@ -344,11 +568,15 @@ impl<'db> MultiStateSyncSession<'db> {
         };
 
         if let Ok((merk, root_key, tree_type)) =
-            self.db
-                .open_merk_for_replication(path.clone(), transaction_ref, grove_version)
+            self.open_merk_for_restorer(path.clone(), transaction_ref, grove_version)
         {
-            let restorer = Restorer::new(merk, hash, actual_hash);
-            let mut sync_info = SubtreeStateSyncInfo::new(restorer);
+            let restorer = if self.incremental {
+                Restorer::new_incremental(merk, hash, actual_hash)
+            } else {
+                Restorer::new(merk, hash, actual_hash)
+            };
+            let mut sync_info =
+                SubtreeStateSyncInfo::new(SubtreeRestorer::Merk(restorer), hash, actual_hash);
             sync_info.pending_chunks.insert(vec![]);
             sync_info.root_key = root_key.clone();
             sync_info.tree_type = tree_type;
@@ -364,6 +592,300 @@ impl<'db> MultiStateSyncSession<'db> {
         }
     }
 
+    /// Adds synchronization information for a subtree which keeps its data
+    /// outside of a Merk, whose `element` in the parent Merk has the value
+    /// hash `hash` and the serialized form hashing to `actual_hash`.
+    ///
+    /// Returns the global chunk ids of the first chunks of the subtree.
+    fn add_non_merk_subtree_sync_info(
+        self: &mut Pin<Box<MultiStateSyncSession<'db>>>,
+        path: &[Vec<u8>],
+        element: Element,
+        hash: CryptoHash,
+        actual_hash: CryptoHash,
+        chunk_prefix: [u8; 32],
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let transaction_ref: &'db Transaction<'db> = unsafe {
+            let tx: &Transaction<'db> = &self.as_ref().transaction;
+            &*(tx as *const _)
+        };
+
+        let tree_type = element
+            .tree_type()
+            .ok_or_else(|| Error::CorruptedData(format!("{} is not a tree", element.type_str())))?;
+        let storage = self
+            .db
+            .db
+            .get_immediate_storage_context_by_subtree_prefix(chunk_prefix, transaction_ref)
+            .unwrap();
+        let (restorer, first_chunk_ids) =
+            NonMerkRestorer::new(storage, element, hash, actual_hash)?;
+
+        let mut sync_info =
+            SubtreeStateSyncInfo::new(SubtreeRestorer::NonMerk(restorer), hash, Some(actual_hash));
+        sync_info.tree_type = tree_type;
+        sync_info.current_path = path.to_vec();
+        let global_chunk_ids = if first_chunk_ids.is_empty() {
+            sync_info.pending_chunks.insert(vec![]);
+            vec![encode_global_chunk_id(
+                chunk_prefix,
+                None,
+                tree_type,
+                vec![],
+            )?]
+        } else {
+            sync_info
+                .pending_chunks
+                .extend(first_chunk_ids.iter().cloned());
+            first_chunk_ids
+                .chunks(CONST_GROUP_PACKING_SIZE)
+                .map(|grouped_ids| {
+                    encode_global_chunk_id(chunk_prefix, None, tree_type, grouped_ids.to_vec())
+                })
+                .collect::<Result<_, _>>()?
+        };
+        self.as_mut()
+            .current_prefixes()
+            .insert(chunk_prefix, sync_info);
+        Ok(global_chunk_ids)
+    }
+
+    /// Adds synchronization information for a subtree recorded by a
+    /// checkpoint, with a restorer carrying on from the chunks written so
+    /// far.
+    fn resume_subtree_sync_info(
+        self: &mut Pin<Box<MultiStateSyncSession<'db>>>,
+        record: SubtreeRecord,
+        grove_version: &GroveVersion,
+    ) -> Result<(), Error> {
+        let transaction_ref: &'db Transaction<'db> = unsafe {
+            let tx: &Transaction<'db> = &self.as_ref().transaction;
+            &*(tx as *const _)
+        };
+
+        let SubtreeRecord {
+            prefix,
+            path,
+            expected_hash,
+            actual_hash,
+            pending_chunks,
+            num_processed_chunks,
+        } = record;
+
+        let mut sync_info = if let Some(element) =
+            self.non_merk_subtree_element(&path, grove_version)?
+        {
+            let tree_type = element.tree_type().ok_or_else(|| {
+                Error::CorruptedData(format!("{} is not a tree", element.type_str()))
+            })?;
+            let actual_value_hash = actual_hash.ok_or_else(|| {
+                Error::CorruptedData("checkpoint of a subtree lacks its element hash".to_string())
+            })?;
+            let storage = self
+                .db
+                .db
+                .get_immediate_storage_context_by_subtree_prefix(prefix, transaction_ref)
+                .unwrap();
+            let restorer = NonMerkRestorer::resume(
+                storage,
+                element,
+                expected_hash,
+                actual_value_hash,
+                &pending_chunks,
+            )?;
+            let mut sync_info = SubtreeStateSyncInfo::new(
+                SubtreeRestorer::NonMerk(restorer),
+                expected_hash,
+                actual_hash,
+            );
+            sync_info.tree_type = tree_type;
+            sync_info
+        } else {
+            let (merk, root_key, tree_type) = self.open_merk_for_restorer(
+                path.as_slice().into(),
+                transaction_ref,
+                grove_version,
+            )?;
+            let mut restorer = if self.incremental {
+                Restorer::new_incremental(merk, expected_hash, actual_hash)
+            } else {
+                Restorer::new(merk, expected_hash, actual_hash)
+            };
+            // Until the root chunk is applied, whatever is stored under the
+            // root key belongs to an older state
+            let whole_subtree_chunk_id: &[u8] = &[];
+            let root_chunk_pending =
+                pending_chunks.len() == 1 && pending_chunks.contains(whole_subtree_chunk_id);
+            let expected_chunks = if root_chunk_pending {
+                vec![vec![]]
+            } else {
+                restorer
+                    .attempt_state_recovery(grove_version)
+                    .map_err(|e| {
+                        Error::CorruptedData(format!("unable to recover restoration state: {e}"))
+                    })?
+            };
+            if expected_chunks.into_iter().collect::<BTreeSet<_>>() != pending_chunks {
+                return Err(Error::CorruptedData(format!(
+                    "restored data of subtree {:?} doesn't match its checkpoint",
+                    path_to_string(&path)
+                )));
+            }
+            let mut sync_info = SubtreeStateSyncInfo::new(
+                SubtreeRestorer::Merk(restorer),
+                expected_hash,
+                actual_hash,
+            );
+            sync_info.root_key = root_key;
+            sync_info.tree_type = tree_type;
+            sync_info
+        };
+        sync_info.current_path = path;
+        sync_info.pending_chunks = pending_chunks;
+        sync_info.num_processed_chunks = num_processed_chunks as usize;
+        self.as_mut().current_prefixes().insert(prefix, sync_info);
+        Ok(())
+    }
+
+    /// Returns the element of the subtree at `path` in its parent Merk if the
+    /// subtree keeps its data outside of a Merk.
+    fn non_merk_subtree_element(
+        &self,
+        path: &[Vec<u8>],
+        grove_version: &GroveVersion,
+    ) -> Result<Option<Element>, Error> {
+        let Some((parent_path, parent_key)) = SubtreePath::from(path).derive_parent() else {
+            return Ok(None);
+        };
+        let parent_storage = self
+            .db
+            .db
+            .get_immediate_storage_context(parent_path, &self.transaction)
+            .unwrap();
+        let element =
+            Element::get_from_storage(&parent_storage, parent_key, grove_version).unwrap()?;
+        Ok(element.uses_non_merk_data_storage().then_some(element))
+    }
+
+    /// Opens the Merk of the subtree at `path` to restore it. The storage of
+    /// an incremental session may hold another kind of tree at `path`, which
+    /// is cleared if the Merk can't be opened over it.
+    fn open_merk_for_restorer<'b, B: AsRef<[u8]>>(
+        &self,
+        path: SubtreePath<'b, B>,
+        transaction: &'db Transaction<'db>,
+        grove_version: &GroveVersion,
+    ) -> Result<OpenedMerkForReplication<'db>, Error> {
+        match self
+            .db
+            .open_merk_for_replication(path.clone(), transaction, grove_version)
+        {
+            Err(_) if self.incremental => {
+                self.clear_subtree_storage(&path.to_vec())?;
+                self.db
+                    .open_merk_for_replication(path, transaction, grove_version)
+            }
+            opened => opened,
+        }
+    }
+
+    /// Returns `true` if the subtree at `path` is already stored as it is in
+    /// the snapshot, its element in the restored parent Merk having the
+    /// value hash `expected_hash` and the serialized form hashing to
+    /// `actual_hash`. The root hash of a subtree covers every subtree below
+    /// it, so none of them needs to be synced either.
+    fn is_subtree_stored(
+        &self,
+        path: &[Vec<u8>],
+        non_merk_element: Option<&Element>,
+        actual_hash: CryptoHash,
+        expected_hash: CryptoHash,
+        grove_version: &GroveVersion,
+    ) -> Result<bool, Error> {
+        let root_hash = if let Some(element) = non_merk_element {
+            self.db
+                .compute_non_merk_child_hash(element, path.into(), &self.transaction, NULL_HASH)
+        } else {
+            match self
+                .db
+                .open_merk_for_replication(path.into(), &self.transaction, grove_version)
+            {
+                Ok((merk, ..)) => merk.root_hash().unwrap(),
+                Err(_) => return Ok(false),
+            }
+        };
+        Ok(combine_hash(&actual_hash, &root_hash).unwrap() == expected_hash)
+    }
+
+    /// Marks the subtree with `prefix` as synced without restoring it.
+    fn skip_stored_subtree(
+        self: &mut Pin<Box<MultiStateSyncSession<'db>>>,
+        prefix: SubtreePrefix,
+    ) -> Result<(), Error> {
+        self.as_mut().processed_prefixes().insert(prefix);
+        self.db
+            .mark_sync_subtree_processed(&prefix, &self.transaction)
+    }
+
+    /// Deletes the data of the subtree at `path`, leaving its metadata.
+    fn clear_subtree_storage(&self, path: &[Vec<u8>]) -> Result<(), Error> {
+        let batch = StorageBatch::new();
+        self.db
+            .db
+            .get_transactional_storage_context(path.into(), Some(&batch), &self.transaction)
+            .unwrap()
+            .clear()
+            .unwrap()
+            .map_err(|e| Error::CorruptedData(format!("unable to clear subtree storage: {e}")))?;
+        self.db
+            .db
+            .commit_multi_context_batch(batch, Some(&self.transaction))
+            .unwrap()
+            .map_err(Into::into)
+    }
+
+    /// Deletes the subtrees of an older state registered below the restored
+    /// subtree at `path` which aren't among its `subtrees_metadata`, with
+    /// every subtree below them.
+    fn delete_stale_subtrees(
+        &self,
+        path: &[Vec<u8>],
+        subtrees_metadata: &SubtreesMetadata,
+        grove_version: &GroveVersion,
+    ) -> Result<(), Error> {
+        let mut registry_update = SubtreeRegistryUpdate::default();
+        let registered_subtrees = self
+            .db
+            .list_subtrees(
+                SubtreePath::from(path),
+                Some(1),
+                Some(&self.transaction),
+                grove_version,
+            )
+            .unwrap()?;
+        for stale_subtree in registered_subtrees
+            .into_iter()
+            .filter(|subtree| !subtrees_metadata.data.contains_key(&subtree.prefix))
+        {
+            let nested_subtrees = self
+                .db
+                .list_subtrees(
+                    SubtreePath::from(stale_subtree.path.as_slice()),
+                    None,
+                    Some(&self.transaction),
+                    grove_version,
+                )
+                .unwrap()?;
+            for subtree in iter::once(stale_subtree).chain(nested_subtrees) {
+                self.clear_subtree_storage(&subtree.path)?;
+                registry_update.remove(subtree.path);
+            }
+        }
+        self.db
+            .apply_subtree_registry_update(registry_update, &self.transaction, grove_version)
+            .unwrap()
+    }
+
     fn current_prefixes(
         self: Pin<&mut MultiStateSyncSession<'db>>,
     ) -> &mut BTreeMap<SubtreePrefix, SubtreeStateSyncInfo<'db>> {
@@ -403,8 +925,9 @@ impl<'db> MultiStateSyncSession<'db> {
     /// - `db`: A reference to the `GroveDb` instance used for synchronization.
     /// - `packed_global_chunk_ids`: A byte slice representing the packed global
     ///   chunk IDs being applied.
-    /// - `packed_global_chunks`: A byte slice containing packed encoded proof
-    ///   for the chunk.
+    /// - `packed_global_chunks`: The bytes returned by
+    ///   [`GroveDb::fetch_chunk`] for `packed_global_chunk_ids`, a
+    ///   [`ChunkEnvelope`] for each of them.
     /// - `version`: The state synchronization protocol version being used.
     /// - `grove_version`: A reference to the `GroveVersion` specifying the
     ///   GroveDB version.
@@ -417,6 +940,9 @@ impl<'db> MultiStateSyncSession<'db> {
     ///   proof is invalid.
     ///
     /// # Behavior
+    /// - Envelopes that are corrupted or don't hold the requested chunks are
+    ///   rejected with [`Error::InvalidChunkEnvelope`] before anything is
+    ///   applied.
     /// - This method applies the given chunk using the provided
     ///   `global_chunk_id` and its corresponding proof data (`chunk`).
     /// - Once the chunk is applied successfully, it calculates and returns the
@@ -435,6 +961,31 @@ impl<'db> MultiStateSyncSession<'db> {
         packed_global_chunks: &[u8],
         version: u16,
         grove_version: &GroveVersion,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.apply_chunks(
+            &[(packed_global_chunk_ids, packed_global_chunks)],
+            version,
+            grove_version,
+        )
+    }
+
+    /// Applies many chunks at once, given as pairs of packed global chunk ids
+    /// and the packed chunks fetched for them, and returns the packed global
+    /// chunk ids to fetch next like [`Self::apply_chunk`] does.
+    ///
+    /// Chunk proofs of Merk subtrees only depend on hashes already known to
+    /// the session, so they are decoded and verified on several threads
+    /// before any of them is written. They are then written one after the
+    /// other to the session transaction, in the order they were given, so
+    /// the outcome is the same as applying every pair with
+    /// [`Self::apply_chunk`], and the transaction is still committed and
+    /// checked against the app hash by [`Self::commit`]. Chunks of subtrees
+    /// that keep their data outside of a Merk are applied in order.
+    pub fn apply_chunks(
+        self: &mut Pin<Box<MultiStateSyncSession<'db>>>,
+        chunks: &[(&[u8], &[u8])],
+        version: u16,
+        grove_version: &GroveVersion,
     ) -> Result<Vec<Vec<u8>>, Error> {
         // For now, only CURRENT_STATE_SYNC_VERSION is supported
         if version != CURRENT_STATE_SYNC_VERSION {
@@ -449,13 +1000,14 @@ impl<'db> MultiStateSyncSession<'db> {
         }
 
         let mut nested_global_chunk_ids: Vec<Vec<u8>> = vec![];
-        let mut nested_global_chunks: Vec<Vec<u8>> = vec![];
-        if self.app_hash == packed_global_chunk_ids {
-            nested_global_chunk_ids = vec![packed_global_chunk_ids.to_vec()];
-            nested_global_chunks = unpack_nested_bytes(packed_global_chunks)?;
-        } else {
-            nested_global_chunk_ids.extend(unpack_nested_bytes(packed_global_chunk_ids)?);
-            nested_global_chunks.extend(unpack_nested_bytes(packed_global_chunks)?);
+        let mut nested_global_chunks: Vec<ChunkEnvelope> = vec![];
+        for (packed_global_chunk_ids, packed_global_chunks) in chunks {
+            if self.app_hash == *packed_global_chunk_ids {
+                nested_global_chunk_ids.push(packed_global_chunk_ids.to_vec());
+            } else {
+                nested_global_chunk_ids.extend(unpack_nested_bytes(packed_global_chunk_ids)?);
+            }
+            nested_global_chunks.extend(ChunkEnvelope::decode_packed(packed_global_chunks)?);
         }
 
         if nested_global_chunk_ids.len() != nested_global_chunks.len() {
@@ -469,19 +1021,17 @@ impl<'db> MultiStateSyncSession<'db> {
             ));
         }
 
-        let mut next_global_chunk_ids: Vec<Vec<u8>> = vec![];
-
-        for (iter_global_chunk_id, iter_packed_chunks) in nested_global_chunk_ids
+        let mut subtree_chunks = vec![];
+        for (iter_global_chunk_id, iter_envelope) in nested_global_chunk_ids
             .iter()
             .zip(nested_global_chunks.iter())
         {
-            let mut next_chunk_ids = vec![];
-
-            let (chunk_prefix, _, _, nested_local_chunk_ids) =
+            let (chunk_prefix, _, tree_type, nested_local_chunk_ids) =
                 replication::utils::decode_global_chunk_id(
                     iter_global_chunk_id.as_slice(),
                     &self.app_hash,
                 )?;
+            iter_envelope.check_request(iter_global_chunk_id, tree_type)?;
 
             let it_chunk_ids = if nested_local_chunk_ids.is_empty() {
                 vec![vec![]]
@@ -489,7 +1039,7 @@ impl<'db> MultiStateSyncSession<'db> {
                 nested_local_chunk_ids
             };
 
-            let current_nested_chunk_data = unpack_nested_bytes(iter_packed_chunks.as_slice())?;
+            let current_nested_chunk_data = iter_envelope.local_chunks()?;
 
             if it_chunk_ids.len() != current_nested_chunk_data.len() {
                 return Err(Error::InternalError(
@@ -497,6 +1047,28 @@ impl<'db> MultiStateSyncSession<'db> {
                 ));
             }
 
+            subtree_chunks.push(SubtreeChunks {
+                prefix: chunk_prefix,
+                chunk_ids: it_chunk_ids,
+                chunks: current_nested_chunk_data,
+            });
+        }
+
+        let verified_chunks = self.verify_merk_chunks(&subtree_chunks);
+
+        let mut next_global_chunk_ids: Vec<Vec<u8>> = vec![];
+
+        for (
+            SubtreeChunks {
+                prefix: chunk_prefix,
+                chunk_ids: it_chunk_ids,
+                chunks: current_nested_chunk_data,
+            },
+            verified_subtree_chunks,
+        ) in subtree_chunks.into_iter().zip(verified_chunks)
+        {
+            let mut next_chunk_ids = vec![];
+
             let current_prefixes = self.as_mut().current_prefixes();
             let Some(subtree_state_sync) = current_prefixes.get_mut(&chunk_prefix) else {
                 return Err(Error::InternalError(
@@ -505,12 +1077,15 @@ impl<'db> MultiStateSyncSession<'db> {
             };
 
             let mut next_local_chunk_ids = vec![];
-            for (current_local_chunk_id, current_local_chunks) in
-                it_chunk_ids.iter().zip(current_nested_chunk_data.iter())
+            for ((current_local_chunk_id, current_local_chunks), verified_chunk) in it_chunk_ids
+                .iter()
+                .zip(current_nested_chunk_data.iter())
+                .zip(verified_subtree_chunks)
             {
                 next_local_chunk_ids.extend(subtree_state_sync.apply_inner_chunk(
                     current_local_chunk_id.as_slice(),
                     current_local_chunks.as_slice(),
+                    verified_chunk,
                     grove_version,
                 )?);
             }
@@ -527,27 +1102,40 @@ impl<'db> MultiStateSyncSession<'db> {
                 next_global_chunk_ids.extend(next_chunk_ids);
             } else if subtree_state_sync.pending_chunks.is_empty() {
                 let completed_path = subtree_state_sync.current_path.clone();
+                let completed_tree_type = subtree_state_sync.tree_type;
 
                 // Subtree is finished. We can save it.
                 let is_subtree_empty = subtree_state_sync.num_processed_chunks == 0;
                 if let Some(prefix_data) = current_prefixes.remove(&chunk_prefix) {
-                    if is_subtree_empty {
-                        // For empty subtrees, verify the restorer's underlying merk has a
-                        // NULL root hash. A malicious peer that sends empty data for a
-                        // non-empty subtree will be caught here (and also at commit time
-                        // via H3 root hash verification).
-                        let merk = prefix_data.restorer.into_merk();
-                        let merk_root = merk.root_hash().unwrap();
-                        if merk_root != grovedb_merk::tree::hash::NULL_HASH {
-                            return Err(Error::InternalError(
-                                "empty subtree has non-null root hash".to_string(),
-                            ));
+                    match prefix_data.restorer {
+                        SubtreeRestorer::Merk(restorer) if is_subtree_empty => {
+                            // For empty subtrees, verify the restorer's underlying merk has a
+                            // NULL root hash. A malicious peer that sends empty data for a
+                            // non-empty subtree will be caught here (and also at commit time
+                            // via H3 root hash verification).
+                            let merk = restorer.into_merk();
+                            let merk_root = merk.root_hash().unwrap();
+                            if merk_root != grovedb_merk::tree::hash::NULL_HASH {
+                                return Err(Error::InternalError(
+                                    "empty subtree has non-null root hash".to_string(),
+                                ));
+                            }
+                            drop(merk);
+                            if self.incremental {
+                                self.clear_subtree_storage(&completed_path)?;
+                            }
+                        }
+                        SubtreeRestorer::Merk(restorer) => {
+                            if let Err(err) = restorer.finalize(grove_version) {
+                                return Err(Error::InternalError(format!(
+                                    "Unable to finalize Merk: {:?}",
+                                    err
+                                )));
+                            }
+                        }
+                        SubtreeRestorer::NonMerk(restorer) => {
+                            restorer.finalize(self.db, &completed_path, &self.transaction)?
                         }
-                    } else if let Err(err) = prefix_data.restorer.finalize(grove_version) {
-                        return Err(Error::InternalError(format!(
-                            "Unable to finalize Merk: {:?}",
-                            err
-                        )));
                     }
                 } else {
                     return Err(Error::InternalError(format!(
@@ -557,11 +1145,24 @@ impl<'db> MultiStateSyncSession<'db> {
                 }
 
                 self.as_mut().processed_prefixes().insert(chunk_prefix);
+                self.db
+                    .mark_sync_subtree_processed(&chunk_prefix, &self.transaction)?;
 
                 *self.as_mut().num_processed_subtrees_in_batch() += 1;
 
-                let new_subtrees_metadata =
-                    self.discover_new_subtrees_metadata(&completed_path, grove_version)?;
+                // Trees keeping their data outside of a Merk have no subtrees
+                let new_subtrees_metadata = if completed_tree_type.uses_non_merk_data_storage() {
+                    SubtreesMetadata::default()
+                } else {
+                    self.discover_new_subtrees_metadata(&completed_path, grove_version)?
+                };
+                if self.incremental {
+                    self.delete_stale_subtrees(
+                        &completed_path,
+                        &new_subtrees_metadata,
+                        grove_version,
+                    )?;
+                }
 
                 if self.num_processed_subtrees_in_batch >= self.subtrees_batch_size {
                     match self.as_mut().pending_discovered_subtrees() {
@@ -590,6 +1191,7 @@ impl<'db> MultiStateSyncSession<'db> {
         if self.num_processed_subtrees_in_batch >= self.subtrees_batch_size
             && self.current_prefixes.is_empty()
         {
+            self.write_session_record(vec![])?;
             // SAFETY: we made sure `self.current_prefixes` is empty so there are no
             // references to the transaction we're about to replace
             unsafe {
@@ -623,6 +1225,95 @@ impl<'db> MultiStateSyncSession<'db> {
         Ok(res)
     }
 
+    /// Decodes and verifies the chunks of Merk subtrees in `subtree_chunks`
+    /// on as many threads as there are cores, returning the outcome for
+    /// every chunk in the same layout. Chunks the session doesn't expect yet,
+    /// empty chunks and chunks of non-Merk subtrees are left for
+    /// `SubtreeStateSyncInfo::apply_inner_chunk`.
+    fn verify_merk_chunks(
+        &self,
+        subtree_chunks: &[SubtreeChunks],
+    ) -> Vec<Vec<Option<Result<VerifiedChunk, Error>>>> {
+        let mut verified_chunks: Vec<Vec<Option<Result<VerifiedChunk, Error>>>> = subtree_chunks
+            .iter()
+            .map(|subtree_chunks| subtree_chunks.chunks.iter().map(|_| None).collect())
+            .collect();
+
+        // Restorers can't be shared between threads, so what each chunk is
+        // verified against is taken from them here
+        let mut jobs = vec![];
+        for (subtree_index, subtree_chunks) in subtree_chunks.iter().enumerate() {
+            let Some(SubtreeStateSyncInfo {
+                restorer: SubtreeRestorer::Merk(restorer),
+                pending_chunks,
+                ..
+            }) = self.current_prefixes.get(&subtree_chunks.prefix)
+            else {
+                continue;
+            };
+            for (chunk_index, (chunk_id, chunk_data)) in subtree_chunks
+                .chunk_ids
+                .iter()
+                .zip(subtree_chunks.chunks.iter())
+                .enumerate()
+            {
+                if chunk_data.is_empty() || !pending_chunks.contains(chunk_id) {
+                    continue;
+                }
+                if let Ok(expectation) = restorer.chunk_expectation(chunk_id) {
+                    jobs.push((
+                        subtree_index,
+                        chunk_index,
+                        expectation,
+                        chunk_data.as_slice(),
+                    ));
+                }
+            }
+        }
+
+        let num_threads = thread::available_parallelism()
+            .map_or(1, NonZeroUsize::get)
+            .min(jobs.len());
+        let verify_jobs = |jobs: Vec<(usize, usize, ChunkExpectation, &[u8])>| {
+            jobs.into_iter()
+                .map(|(subtree_index, chunk_index, expectation, chunk_data)| {
+                    (
+                        subtree_index,
+                        chunk_index,
+                        verify_merk_chunk(expectation, chunk_data),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let results = if num_threads <= 1 {
+            verify_jobs(jobs)
+        } else {
+            let jobs_per_thread = jobs.len().div_ceil(num_threads);
+            let mut jobs = jobs.into_iter();
+            thread::scope(|scope| {
+                let handles: Vec<_> = (0..num_threads)
+                    .map(|_| {
+                        let thread_jobs: Vec<_> = jobs.by_ref().take(jobs_per_thread).collect();
+                        scope.spawn(move || verify_jobs(thread_jobs))
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                    })
+                    .collect()
+            })
+        };
+
+        for (subtree_index, chunk_index, result) in results {
+            verified_chunks[subtree_index][chunk_index] = Some(result);
+        }
+        verified_chunks
+    }
+
     /// Discovers new subtrees at the given path that need to be synchronized.
     ///
     /// # Parameters
@@ -672,17 +1363,18 @@ impl<'db> MultiStateSyncSession<'db> {
         if merk.is_empty_tree().unwrap() {
             return Ok(SubtreesMetadata::default());
         }
-        let mut subtree_keys = BTreeSet::new();
+        let mut subtree_keys = BTreeMap::new();
 
         let mut raw_iter = Element::iterator(merk.storage.raw_iter()).unwrap();
         while let Some((key, value)) = raw_iter.next_element(grove_version).unwrap()? {
             if value.is_any_tree() {
-                subtree_keys.insert(key.to_vec());
+                subtree_keys.insert(key.to_vec(), value);
             }
         }
 
         let mut subtrees_metadata = SubtreesMetadata::new();
-        for subtree_key in &subtree_keys {
+        let mut registry_update = SubtreeRegistryUpdate::default();
+        for (subtree_key, tree) in &subtree_keys {
             let (elem_value, elem_value_hash) = merk
                 .get_value_and_value_hash(
                     subtree_key.as_slice(),
@@ -708,13 +1400,17 @@ impl<'db> MultiStateSyncSession<'db> {
 
             let subtree_path: Vec<&[u8]> = new_path.iter().map(|vec| vec.as_slice()).collect();
             let path: &[&[u8]] = &subtree_path;
-            let prefix = RocksDbStorage::build_prefix(path.as_ref().into()).unwrap();
+            let prefix = StorageBackend::build_prefix(path.as_ref().into()).unwrap();
 
+            registry_update.insert(new_path.clone(), tree);
             subtrees_metadata.data.insert(
                 prefix,
                 (new_path.to_vec(), actual_value_hash, elem_value_hash),
             );
         }
+        self.db
+            .apply_subtree_registry_update(registry_update, transaction_ref, grove_version)
+            .unwrap()?;
 
         Ok(subtrees_metadata)
     }
@@ -764,6 +1460,37 @@ impl<'db> MultiStateSyncSession<'db> {
                 && !self.current_prefixes.contains_key(prefix)
             {
                 let (current_path, actual_value_hash, elem_value_hash) = &prefix_metadata;
+                let non_merk_element =
+                    self.non_merk_subtree_element(current_path, grove_version)?;
+
+                if self.incremental {
+                    if self.is_subtree_stored(
+                        current_path,
+                        non_merk_element.as_ref(),
+                        *actual_value_hash,
+                        *elem_value_hash,
+                        grove_version,
+                    )? {
+                        self.skip_stored_subtree(*prefix)?;
+                        continue;
+                    }
+                    if non_merk_element.is_some() {
+                        // Values are pushed from the start, so nothing stored
+                        // is kept
+                        self.clear_subtree_storage(current_path)?;
+                    }
+                }
+
+                if let Some(element) = non_merk_element {
+                    res.extend(self.add_non_merk_subtree_sync_info(
+                        current_path,
+                        element,
+                        *elem_value_hash,
+                        *actual_value_hash,
+                        *prefix,
+                    )?);
+                    continue;
+                }
 
                 let subtree_path: Vec<&[u8]> =
                     current_path.iter().map(|vec| vec.as_slice()).collect();
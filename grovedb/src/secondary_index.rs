@@ -0,0 +1,386 @@
+//! Secondary indexes kept in step with their items by
+//! [`GroveDb::apply_batch`], see [`GroveDb::register_index`].
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use grovedb_costs::{
+    cost_return_on_error, cost_return_on_error_no_add, CostResult, CostsExt, OperationCost,
+};
+use grovedb_path::SubtreePath;
+use grovedb_version::version::GroveVersion;
+
+use crate::{
+    batch::{GroveOp, QualifiedGroveDbOp},
+    reference_path::ReferencePathType,
+    Element, Error, GroveDb, Transaction,
+};
+
+const UNIQUE_ENTRY_TAKEN_ERROR_MESSAGE: &str =
+    "unique secondary index key is already used by another item";
+
+/// Rejects the items with an expiry `ops` write under a subtree none of the
+/// expiry indexes of `indexes` covers, as nothing would ever sweep them.
+pub(crate) fn check_items_with_expiry_are_indexed(
+    ops: &[QualifiedGroveDbOp],
+    indexes: &[Arc<SecondaryIndex>],
+) -> Result<(), Error> {
+    for op in ops {
+        let element = match &op.op {
+            GroveOp::InsertOrReplace { element }
+            | GroveOp::Replace { element }
+            | GroveOp::Patch { element, .. }
+            | GroveOp::InsertWithKnownToNotAlreadyExist { element }
+            | GroveOp::InsertIfNotExists { element, .. } => element,
+            _ => continue,
+        };
+        if element.is_item_with_expiry()
+            && !indexes
+                .iter()
+                .any(|index| index.is_expiry_index() && op.path == index.source_path)
+        {
+            return Err(Error::InvalidBatchOperation(
+                "an item with an expiry needs an expiry index maintained by the batch",
+            ));
+        }
+    }
+    Ok(())
+}
+
+type KeyExtractor = Arc<dyn Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync>;
+
+#[derive(Clone)]
+enum IndexedBy {
+    ExtractedKey(KeyExtractor),
+    Expiry,
+}
+
+/// Index of the items of one subtree, kept as references in another subtree.
+///
+/// Entries are keyed by what the key extractor returns for the item value.
+/// Unless the index is [`unique`](Self::unique), the extracted key is
+/// prefixed with its length as one byte and followed by the item key, so
+/// entries are ordered by the length of the extracted key, then by the
+/// extracted key, and those of one extracted key share the prefix
+/// [`entry_key_prefix`](Self::entry_key_prefix) returns. Each entry
+/// is an `AbsolutePathReference` to its item, or a `SiblingReference` if the
+/// index lives in the subtree of the items.
+///
+/// An [`expiry`](Self::expiry) index keys entries by the big-endian expiry
+/// of `ItemWithExpiry` elements instead, which is what
+/// [`GroveDb::sweep_expired`] walks.
+#[derive(Clone)]
+pub struct SecondaryIndex {
+    source_path: Vec<Vec<u8>>,
+    index_path: Vec<Vec<u8>>,
+    indexed_by: IndexedBy,
+    unique: bool,
+}
+
+impl fmt::Debug for SecondaryIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecondaryIndex")
+            .field("source_path", &self.source_path)
+            .field("index_path", &self.index_path)
+            .field("unique", &self.unique)
+            .field("expiry", &self.is_expiry_index())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SecondaryIndex {
+    /// Indexes the items of the subtree at `source_path` in the subtree at
+    /// `index_path` by the key `key_extractor` returns for their value.
+    /// Items it returns `None` for, and elements other than items, are not
+    /// indexed.
+    pub fn new(
+        source_path: Vec<Vec<u8>>,
+        index_path: Vec<Vec<u8>>,
+        key_extractor: impl Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync + 'static,
+    ) -> Self {
+        SecondaryIndex {
+            source_path,
+            index_path,
+            indexed_by: IndexedBy::ExtractedKey(Arc::new(key_extractor)),
+            unique: false,
+        }
+    }
+
+    /// Indexes the items with an expiry of the subtree at `source_path` in
+    /// the subtree at `index_path` by their expiry. Other elements are not
+    /// indexed, and the index can't be kept in the subtree of its items.
+    ///
+    /// Batches writing items with an expiry under a subtree without a
+    /// registered expiry index are rejected, as are `insert`s of them.
+    pub fn expiry(source_path: Vec<Vec<u8>>, index_path: Vec<Vec<u8>>) -> Self {
+        SecondaryIndex {
+            source_path,
+            index_path,
+            indexed_by: IndexedBy::Expiry,
+            unique: false,
+        }
+    }
+
+    /// Keys entries by the extracted key alone, so a batch giving a second
+    /// item the same key fails.
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    /// Path of the subtree whose items are indexed.
+    pub fn source_path(&self) -> &[Vec<u8>] {
+        &self.source_path
+    }
+
+    /// Path of the subtree holding the index entries.
+    pub fn index_path(&self) -> &[Vec<u8>] {
+        &self.index_path
+    }
+
+    /// Whether entries are keyed by the expiry of the items.
+    pub fn is_expiry_index(&self) -> bool {
+        matches!(self.indexed_by, IndexedBy::Expiry)
+    }
+
+    /// Returns the key of the entry of the item `element` at `key`, `None`
+    /// if it is not indexed. Extracted keys longer than 255 bytes can't be
+    /// length prefixed, items with such keys are not indexed by non-unique
+    /// indexes.
+    pub fn entry_key(&self, key: &[u8], element: &Element) -> Option<Vec<u8>> {
+        let mut entry_key = match &self.indexed_by {
+            IndexedBy::ExtractedKey(key_extractor) => match element {
+                Element::Item(value, _)
+                | Element::ItemWithSumItem(value, ..)
+                | Element::ItemWithExpiry(value, ..) => {
+                    self.entry_key_prefix(&key_extractor(value)?)?
+                }
+                _ => return None,
+            },
+            // Expiries are fixed width, so they aren't length prefixed
+            IndexedBy::Expiry => element.expiry()?.to_be_bytes().to_vec(),
+        };
+        if !self.unique {
+            entry_key.extend_from_slice(key);
+        }
+        Some(entry_key)
+    }
+
+    /// Returns what the keys of the entries of items with the extracted key
+    /// `extracted_key` start with, the whole entry key for a unique index.
+    /// `None` if the extracted key is too long to be indexed.
+    pub fn entry_key_prefix(&self, extracted_key: &[u8]) -> Option<Vec<u8>> {
+        if self.unique {
+            return Some(extracted_key.to_vec());
+        }
+        let length = u8::try_from(extracted_key.len()).ok()?;
+        let mut prefix = Vec::with_capacity(1 + extracted_key.len());
+        prefix.push(length);
+        prefix.extend_from_slice(extracted_key);
+        Some(prefix)
+    }
+
+    fn entry(&self, key: &[u8]) -> Element {
+        if self.index_path == self.source_path {
+            Element::new_reference(ReferencePathType::SiblingReference(key.to_vec()))
+        } else {
+            let mut item_path = self.source_path.clone();
+            item_path.push(key.to_vec());
+            Element::new_reference(ReferencePathType::AbsolutePathReference(item_path))
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct IndexRegistry {
+    indexes: RwLock<Vec<Arc<SecondaryIndex>>>,
+}
+
+impl IndexRegistry {
+    fn indexes(&self) -> Vec<Arc<SecondaryIndex>> {
+        self.indexes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl GroveDb {
+    /// Registers a secondary index maintained by `apply_batch`, which adds
+    /// the index entry inserts and deletes matching the item writes of every
+    /// batch. Both subtrees have to exist; items already in the source
+    /// subtree are not indexed retroactively.
+    ///
+    /// Only `apply_batch` and `simulate_batch` maintain indexes, items of an
+    /// indexed subtree must not be written with `insert` or `delete`. The
+    /// registry is kept in memory and has to be filled again on every open.
+    pub fn register_index(&self, index: SecondaryIndex) -> Result<(), Error> {
+        let mut indexes = self
+            .indexes
+            .indexes
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if indexes
+            .iter()
+            .any(|registered| registered.index_path == index.index_path)
+        {
+            return Err(Error::InvalidInput(
+                "a secondary index is already kept at this path",
+            ));
+        }
+        if index.is_expiry_index() && index.index_path == index.source_path {
+            return Err(Error::InvalidInput(
+                "an expiry index can't be kept in the subtree of its items",
+            ));
+        }
+        indexes.push(Arc::new(index));
+        Ok(())
+    }
+
+    /// Stops maintaining the index kept at `index_path`, returns `false` if
+    /// there is none. Its entries are left as they are.
+    pub fn unregister_index(&self, index_path: &[Vec<u8>]) -> bool {
+        let mut indexes = self
+            .indexes
+            .indexes
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let count = indexes.len();
+        indexes.retain(|index| index.index_path != index_path);
+        indexes.len() != count
+    }
+
+    /// Returns the registered secondary indexes.
+    pub fn secondary_indexes(&self) -> Vec<Arc<SecondaryIndex>> {
+        self.indexes.indexes()
+    }
+
+    /// Appends to `ops` the index entry writes matching their item writes.
+    pub(crate) fn add_secondary_index_ops(
+        &self,
+        mut ops: Vec<QualifiedGroveDbOp>,
+        transaction: &Transaction,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<QualifiedGroveDbOp>, Error> {
+        let mut cost = OperationCost::default();
+
+        let indexes = self.indexes.indexes();
+        cost_return_on_error_no_add!(cost, check_items_with_expiry_are_indexed(&ops, &indexes));
+        if indexes.is_empty() {
+            return Ok(ops).wrap_with_cost(cost);
+        }
+
+        let mut removed_entries = HashSet::new();
+        let mut added_entries = BTreeMap::new();
+        let mut added_unique_entries = Vec::new();
+        for op in &ops {
+            let Some(key) = op.key.as_ref().map(|key| key.as_slice()) else {
+                continue;
+            };
+            let (new, only_if_absent) = match &op.op {
+                GroveOp::InsertOrReplace { element }
+                | GroveOp::Replace { element }
+                | GroveOp::Patch { element, .. }
+                | GroveOp::InsertWithKnownToNotAlreadyExist { element } => (Some(element), false),
+                GroveOp::InsertIfNotExists { element, .. } => (Some(element), true),
+                GroveOp::Delete | GroveOp::DeleteTree(..) => (None, false),
+                _ => continue,
+            };
+            let matching: Vec<_> = indexes
+                .iter()
+                .filter(|index| op.path == index.source_path)
+                .collect();
+            if matching.is_empty() {
+                continue;
+            }
+
+            let path = op.path.to_path();
+            let old = cost_return_on_error!(
+                &mut cost,
+                self.get_raw_optional_on_transaction_caching_optional(
+                    SubtreePath::from(path.as_slice()),
+                    key,
+                    true,
+                    transaction,
+                    grove_version,
+                )
+            );
+            if only_if_absent && old.is_some() {
+                continue;
+            }
+
+            for index in matching {
+                let old_entry_key = old.as_ref().and_then(|old| index.entry_key(key, old));
+                let new_entry_key = new.and_then(|new| index.entry_key(key, new));
+                if let Some(old_entry_key) = old_entry_key
+                    .as_ref()
+                    .filter(|old_entry_key| Some(*old_entry_key) != new_entry_key.as_ref())
+                {
+                    removed_entries.insert((index.index_path.clone(), old_entry_key.clone()));
+                }
+                if let Some(new_entry_key) = new_entry_key {
+                    if index.unique && old_entry_key.as_ref() != Some(&new_entry_key) {
+                        added_unique_entries
+                            .push((index.index_path.clone(), new_entry_key.clone()));
+                    }
+                    // Replaced even if the key is unchanged, as the reference
+                    // hash covers the item value.
+                    let replaced = added_entries
+                        .insert((index.index_path.clone(), new_entry_key), index.entry(key));
+                    // Ops of a batch have distinct keys, so another item of
+                    // the batch took this entry.
+                    if index.unique && replaced.is_some() {
+                        return Err(Error::InvalidBatchOperation(
+                            UNIQUE_ENTRY_TAKEN_ERROR_MESSAGE,
+                        ))
+                        .wrap_with_cost(cost);
+                    }
+                }
+            }
+        }
+
+        for entry in added_unique_entries {
+            if removed_entries.contains(&entry) {
+                continue;
+            }
+            let (index_path, entry_key) = entry;
+            let existing = cost_return_on_error!(
+                &mut cost,
+                self.get_raw_optional_on_transaction_caching_optional(
+                    SubtreePath::from(index_path.as_slice()),
+                    &entry_key,
+                    true,
+                    transaction,
+                    grove_version,
+                )
+            );
+            if existing.is_some() {
+                return Err(Error::InvalidBatchOperation(
+                    UNIQUE_ENTRY_TAKEN_ERROR_MESSAGE,
+                ))
+                .wrap_with_cost(cost);
+            }
+        }
+
+        // An entry another item of the batch takes over is replaced rather
+        // than deleted, a batch can't have two ops on the same key.
+        ops.extend(
+            removed_entries
+                .into_iter()
+                .filter(|entry| !added_entries.contains_key(entry))
+                .map(|(index_path, entry_key)| QualifiedGroveDbOp::delete_op(index_path, entry_key))
+                .collect::<Vec<_>>(),
+        );
+        ops.extend(
+            added_entries
+                .into_iter()
+                .map(|((index_path, entry_key), entry)| {
+                    QualifiedGroveDbOp::insert_or_replace_op(index_path, entry_key, entry)
+                }),
+        );
+        Ok(ops).wrap_with_cost(cost)
+    }
+}
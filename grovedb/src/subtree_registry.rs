@@ -0,0 +1,512 @@
+//! Registry of the subtrees of a GroveDb, see [`GroveDb::list_subtrees`].
+//!
+//! Every subtree has an entry in the metadata storage of its parent, keyed by
+//! [`SUBTREE_ENTRY_KEY_PREFIX`] followed by the subtree key and holding the
+//! subtree prefix and whether the subtree may hold subtrees of its own, so the
+//! subtrees below a path are found without reading any element. Inserts,
+//! deletes and batches update the registry once their own writes are
+//! committed to the transaction. Since `GROVE_V4` they pay for its writes
+//! like for their own, before that the registry isn't charged.
+//!
+//! Databases created before the registry have no [`REGISTRY_MARKER_KEY`],
+//! their registry is built when they are opened.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use grovedb_costs::{
+    cost_return_on_error, cost_return_on_error_no_add, CostResult, CostsExt, OperationCost,
+};
+use grovedb_path::SubtreePath;
+use grovedb_storage::{RawIterator, Storage, StorageBatch, StorageContext};
+use grovedb_version::{error::GroveVersionError, version::GroveVersion};
+use integer_encoding::VarInt;
+
+use crate::{
+    batch::{GroveOp, QualifiedGroveDbOp},
+    element::elements_iterator::ElementIteratorExtensions,
+    util::TxRef,
+    Element, Error, GroveDb, StorageBackend, SubtreePrefix, Transaction, TransactionArg,
+};
+
+/// Prefix of the keys of subtree entries in the metadata of their parent.
+const SUBTREE_ENTRY_KEY_PREFIX: &[u8] = b"\0grovedb_subtree/";
+
+/// Key in the metadata of the root tree telling that the registry holds
+/// every subtree.
+const REGISTRY_MARKER_KEY: &[u8] = b"\0grovedb_subtree_registry";
+
+fn subtree_entry_key(key: &[u8]) -> Vec<u8> {
+    [SUBTREE_ENTRY_KEY_PREFIX, key].concat()
+}
+
+/// Value of the registry entry of a subtree.
+fn subtree_entry_value(prefix: SubtreePrefix, may_have_subtrees: bool) -> Vec<u8> {
+    [prefix.as_slice(), &[may_have_subtrees as u8]].concat()
+}
+
+/// Length of the key of a subtree entry as stored, behind the prefix of the
+/// parent of the subtree.
+fn stored_subtree_entry_key_len(key_len: u32) -> u32 {
+    (size_of::<SubtreePrefix>() + SUBTREE_ENTRY_KEY_PREFIX.len()) as u32 + key_len
+}
+
+/// Length of the value of a subtree entry.
+const SUBTREE_ENTRY_VALUE_LEN: u32 = size_of::<SubtreePrefix>() as u32 + 1;
+
+/// Whether registry writes are charged to the operations making them.
+fn charges_subtree_registry(grove_version: &GroveVersion) -> bool {
+    grove_version
+        .grovedb_versions
+        .operations
+        .subtree_registry
+        .apply_subtree_registry_update
+        > 0
+}
+
+/// Adds the cost of registering a subtree with a key of `key_len` bytes.
+pub(crate) fn add_subtree_entry_put_cost(
+    cost: &mut OperationCost,
+    key_len: u32,
+    grove_version: &GroveVersion,
+) {
+    if !charges_subtree_registry(grove_version) {
+        return;
+    }
+    let entry_key_len = stored_subtree_entry_key_len(key_len);
+    cost.seek_count += 1;
+    cost.storage_cost.added_bytes += entry_key_len
+        + entry_key_len.required_space() as u32
+        + SUBTREE_ENTRY_VALUE_LEN
+        + SUBTREE_ENTRY_VALUE_LEN.required_space() as u32;
+}
+
+/// Adds the cost of unregistering a subtree, whose entry is read to know
+/// the freed size.
+pub(crate) fn add_subtree_entry_delete_cost(
+    cost: &mut OperationCost,
+    grove_version: &GroveVersion,
+) {
+    if !charges_subtree_registry(grove_version) {
+        return;
+    }
+    cost.seek_count += 2;
+    cost.storage_loaded_bytes += SUBTREE_ENTRY_VALUE_LEN as u64;
+}
+
+/// Subtree in the subtree of a path.
+struct ChildTree {
+    key: Vec<u8>,
+    prefix: SubtreePrefix,
+    /// `false` for trees keeping non-Merk data, which never hold subtrees
+    may_have_subtrees: bool,
+}
+
+/// Subtree found by [`GroveDb::list_subtrees`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredSubtree {
+    /// Path of the subtree
+    pub path: Vec<Vec<u8>>,
+    /// Prefix of the storage keys of the subtree
+    pub prefix: SubtreePrefix,
+}
+
+/// Subtrees an operation added and removed, written to the registry with
+/// [`GroveDb::apply_subtree_registry_update`].
+#[derive(Debug, Default)]
+pub(crate) struct SubtreeRegistryUpdate {
+    /// Added subtrees, with whether they may hold subtrees of their own
+    added: BTreeMap<Vec<Vec<u8>>, bool>,
+    removed: BTreeSet<Vec<Vec<u8>>>,
+    /// Keys written with an element that isn't a tree, which may have been
+    /// one before
+    overwritten: BTreeSet<Vec<Vec<u8>>>,
+}
+
+impl SubtreeRegistryUpdate {
+    /// Registers the subtree at `path` of `tree`.
+    pub(crate) fn insert(&mut self, path: Vec<Vec<u8>>, tree: &Element) {
+        self.insert_with(path, !tree.uses_non_merk_data_storage());
+    }
+
+    fn insert_with(&mut self, path: Vec<Vec<u8>>, may_have_subtrees: bool) {
+        self.removed.remove(&path);
+        self.overwritten.remove(&path);
+        self.added.insert(path, may_have_subtrees);
+    }
+
+    /// Unregisters the subtree at `path`.
+    pub(crate) fn remove(&mut self, path: Vec<Vec<u8>>) {
+        self.added.remove(&path);
+        self.overwritten.remove(&path);
+        self.removed.insert(path);
+    }
+
+    /// Unregisters the subtree at `path` if there is one, for a key written
+    /// with an element that isn't a tree and that may have replaced one.
+    pub(crate) fn overwrite(&mut self, path: Vec<Vec<u8>>) {
+        self.added.remove(&path);
+        if !self.removed.contains(&path) {
+            self.overwritten.insert(path);
+        }
+    }
+
+    /// Records the trees inserted, deleted and replaced by batch operations.
+    /// Elements other than references can only overwrite a tree when
+    /// `validate_insertion_does_not_override_tree` is off.
+    pub(crate) fn record_ops(
+        &mut self,
+        ops: &[QualifiedGroveDbOp],
+        validate_insertion_does_not_override_tree: bool,
+    ) {
+        for op in ops {
+            let Some(key) = op.key.as_ref() else {
+                continue;
+            };
+            let mut path = op.path.to_path();
+            path.push(key.get_key_clone());
+            match &op.op {
+                GroveOp::InsertWithKnownToNotAlreadyExist { element }
+                | GroveOp::InsertIfNotExists { element, .. }
+                | GroveOp::InsertOrReplace { element }
+                | GroveOp::Replace { element }
+                | GroveOp::Patch { element, .. }
+                    if element.is_any_tree() =>
+                {
+                    self.insert(path, element)
+                }
+                // References are never checked against overwriting trees
+                GroveOp::InsertOrReplace { element }
+                | GroveOp::Replace { element }
+                | GroveOp::Patch { element, .. }
+                    if !validate_insertion_does_not_override_tree
+                        || matches!(element, Element::Reference(..)) =>
+                {
+                    self.overwrite(path)
+                }
+                // A tree deleted without `DeleteTree` leaves its entry too
+                GroveOp::Delete => self.overwrite(path),
+                GroveOp::DeleteTree(..) => self.remove(path),
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns `true` if nothing was added or removed.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.overwritten.is_empty()
+    }
+}
+
+impl GroveDb {
+    /// Returns the subtrees below `path`, breadth first, down to `depth`
+    /// levels below it, `Some(1)` giving only its direct children and `None`
+    /// every subtree. `path` itself is not part of the result.
+    ///
+    /// Subtrees are read from the registry, so the cost is in the number of
+    /// subtrees and not of elements.
+    pub fn list_subtrees<B: AsRef<[u8]>>(
+        &self,
+        path: SubtreePath<B>,
+        depth: Option<u16>,
+        transaction: TransactionArg,
+        _grove_version: &GroveVersion,
+    ) -> CostResult<Vec<RegisteredSubtree>, Error> {
+        let mut cost = OperationCost::default();
+        let tx = TxRef::new(&self.db, transaction);
+
+        let mut subtrees = Vec::new();
+        let mut queue = VecDeque::from([(path.to_vec(), 0u16)]);
+        while let Some((parent_path, parent_depth)) = queue.pop_front() {
+            if depth.is_some_and(|depth| parent_depth >= depth) {
+                continue;
+            }
+            let children = cost_return_on_error!(
+                &mut cost,
+                self.registered_children(&parent_path, tx.as_ref())
+            );
+            for child in children {
+                let mut child_path = parent_path.clone();
+                child_path.push(child.key);
+                if child.may_have_subtrees {
+                    queue.push_back((child_path.clone(), parent_depth + 1));
+                }
+                subtrees.push(RegisteredSubtree {
+                    path: child_path,
+                    prefix: child.prefix,
+                });
+            }
+        }
+        Ok(subtrees).wrap_with_cost(cost)
+    }
+
+    /// Registers every subtree by iterating over elements and drops entries
+    /// of subtrees that no longer exist, then marks the registry as complete.
+    ///
+    /// Opening a database runs it when the registry was never built, calling
+    /// it again repairs a registry written to outside of GroveDb.
+    pub fn build_subtree_registry(
+        &self,
+        transaction: TransactionArg,
+        grove_version: &GroveVersion,
+    ) -> Result<(), Error> {
+        let tx = TxRef::new(&self.db, transaction);
+
+        let mut update = SubtreeRegistryUpdate::default();
+        let mut queue = vec![Vec::new()];
+        while let Some(path) = queue.pop() {
+            let children = self
+                .child_trees(&path, tx.as_ref(), grove_version)
+                .unwrap()?;
+            for registered in self.registered_children(&path, tx.as_ref()).unwrap()? {
+                if !children.iter().any(|child| child.key == registered.key) {
+                    update.remove([path.as_slice(), &[registered.key]].concat());
+                }
+            }
+            for child in children {
+                let child_path = [path.as_slice(), &[child.key]].concat();
+                update.insert_with(child_path.clone(), child.may_have_subtrees);
+                if child.may_have_subtrees {
+                    queue.push(child_path);
+                }
+            }
+        }
+        self.apply_subtree_registry_update(update, tx.as_ref(), grove_version)
+            .unwrap()?;
+        self.mark_subtree_registry_complete(tx.as_ref())?;
+
+        tx.commit_local()
+    }
+
+    /// Writes `update` to the registry of `transaction`, see
+    /// [`add_subtree_entry_put_cost`] and [`add_subtree_entry_delete_cost`]
+    /// for what it costs. Version 0 doesn't charge the writes.
+    pub(crate) fn apply_subtree_registry_update(
+        &self,
+        update: SubtreeRegistryUpdate,
+        transaction: &Transaction,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(), Error> {
+        match grove_version
+            .grovedb_versions
+            .operations
+            .subtree_registry
+            .apply_subtree_registry_update
+        {
+            0 => self
+                .write_subtree_registry_update(update, transaction)
+                .unwrap()
+                .wrap_with_cost(OperationCost::default()),
+            1 => self.write_subtree_registry_update(update, transaction),
+            version => Err(Error::VersionError(
+                GroveVersionError::UnknownVersionMismatch {
+                    method: "apply_subtree_registry_update".to_string(),
+                    known_versions: vec![0, 1],
+                    received: version,
+                },
+            ))
+            .wrap_with_cost(OperationCost::default()),
+        }
+    }
+
+    /// Writes `update` to the registry of `transaction`. Keys overwritten
+    /// with something else than a tree are looked up in the registry to
+    /// unregister the trees they replaced.
+    fn write_subtree_registry_update(
+        &self,
+        update: SubtreeRegistryUpdate,
+        transaction: &Transaction,
+    ) -> CostResult<(), Error> {
+        let mut cost = OperationCost::default();
+        if update.is_empty() {
+            return Ok(()).wrap_with_cost(cost);
+        }
+
+        let mut removed = update.removed;
+        for path in update.overwritten {
+            let Some((key, parent_path)) = path.split_last() else {
+                continue;
+            };
+            let storage = self
+                .db
+                .get_transactional_storage_context(parent_path.into(), None, transaction)
+                .unwrap();
+            // Not charged, so writing an element costs the same whether or
+            // not it replaces a tree, only the deletion of the entry is
+            let entry = cost_return_on_error_no_add!(
+                cost,
+                storage
+                    .get_meta(subtree_entry_key(key))
+                    .unwrap()
+                    .map_err(Into::into)
+            );
+            if entry.is_some() {
+                removed.insert(path);
+            }
+        }
+
+        let batch = StorageBatch::new();
+        let entries = removed.iter().map(|path| (path, None)).chain(
+            update
+                .added
+                .iter()
+                .map(|(path, may_have_subtrees)| (path, Some(*may_have_subtrees))),
+        );
+        for (path, added) in entries {
+            let Some((key, parent_path)) = path.split_last() else {
+                continue;
+            };
+            let storage = self
+                .db
+                .get_transactional_storage_context(parent_path.into(), Some(&batch), transaction)
+                .unwrap();
+            let written = if let Some(may_have_subtrees) = added {
+                let prefix = StorageBackend::build_prefix(path.as_slice().into()).unwrap();
+                let value = subtree_entry_value(prefix, may_have_subtrees);
+                // Replacing a tree with a tree doesn't pay for its entry again
+                let entry = cost_return_on_error_no_add!(
+                    cost,
+                    storage
+                        .get_meta(subtree_entry_key(key))
+                        .unwrap()
+                        .map_err(Into::into)
+                );
+                if entry.as_ref() == Some(&value) {
+                    continue;
+                }
+                storage.put_meta(subtree_entry_key(key), &value, None)
+            } else {
+                storage.delete_meta(subtree_entry_key(key), None)
+            };
+            cost_return_on_error!(&mut cost, written.map_err(Into::into));
+        }
+
+        self.db
+            .commit_multi_context_batch(batch, Some(transaction))
+            .map_err(Into::into)
+            .add_cost(cost)
+    }
+
+    /// Builds the registry of a database that doesn't have one yet, which
+    /// for a new database only marks it as complete.
+    pub(crate) fn init_subtree_registry(&self) -> Result<(), Error> {
+        let tx = TxRef::new(&self.db, None);
+        if self.has_subtree_registry(tx.as_ref()).unwrap()? {
+            return Ok(());
+        }
+        drop(tx);
+        self.build_subtree_registry(None, GroveVersion::latest())
+    }
+
+    /// Writes the marker straight to the transaction, so it doesn't make a
+    /// version of its own when history is kept.
+    fn mark_subtree_registry_complete(&self, transaction: &Transaction) -> Result<(), Error> {
+        self.db
+            .get_immediate_storage_context(SubtreePath::empty(), transaction)
+            .unwrap()
+            .put_meta(REGISTRY_MARKER_KEY, &[], None)
+            .unwrap()
+            .map_err(Into::into)
+    }
+
+    pub(crate) fn has_subtree_registry(
+        &self,
+        transaction: &Transaction,
+    ) -> CostResult<bool, Error> {
+        let mut cost = OperationCost::default();
+        let storage = self
+            .db
+            .get_transactional_storage_context(SubtreePath::empty(), None, transaction)
+            .unwrap_add_cost(&mut cost);
+        storage
+            .get_meta(REGISTRY_MARKER_KEY)
+            .map_ok(|marker| marker.is_some())
+            .map_err(Into::into)
+            .add_cost(cost)
+    }
+
+    /// Keys and prefixes of the subtrees registered in the subtree at `path`.
+    fn registered_children(
+        &self,
+        path: &[Vec<u8>],
+        transaction: &Transaction,
+    ) -> CostResult<Vec<ChildTree>, Error> {
+        let mut cost = OperationCost::default();
+        let storage = self
+            .db
+            .get_transactional_storage_context(path.into(), None, transaction)
+            .unwrap_add_cost(&mut cost);
+        let mut raw_iter = storage.raw_iter_meta();
+        raw_iter
+            .seek(SUBTREE_ENTRY_KEY_PREFIX)
+            .unwrap_add_cost(&mut cost);
+
+        let mut children = Vec::new();
+        while raw_iter.valid().unwrap_add_cost(&mut cost) {
+            let Some(child_key) = raw_iter
+                .key()
+                .unwrap_add_cost(&mut cost)
+                .and_then(|key| key.strip_prefix(SUBTREE_ENTRY_KEY_PREFIX))
+                .map(<[u8]>::to_vec)
+            else {
+                break;
+            };
+            let Some((prefix, may_have_subtrees)) = raw_iter
+                .value()
+                .unwrap_add_cost(&mut cost)
+                .and_then(|value| match value.split_last() {
+                    Some((&may_have_subtrees, prefix)) if may_have_subtrees <= 1 => Some((
+                        SubtreePrefix::try_from(prefix).ok()?,
+                        may_have_subtrees == 1,
+                    )),
+                    _ => None,
+                })
+            else {
+                return Err(Error::CorruptedData(format!(
+                    "registry entry of subtree {} is invalid",
+                    hex::encode(&child_key)
+                )))
+                .wrap_with_cost(cost);
+            };
+            children.push(ChildTree {
+                key: child_key,
+                prefix,
+                may_have_subtrees,
+            });
+            raw_iter.next().unwrap_add_cost(&mut cost);
+        }
+        Ok(children).wrap_with_cost(cost)
+    }
+
+    /// Keys and prefixes of the trees among the elements of the subtree at
+    /// `path`.
+    fn child_trees(
+        &self,
+        path: &[Vec<u8>],
+        transaction: &Transaction,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Vec<ChildTree>, Error> {
+        let mut cost = OperationCost::default();
+        let storage = self
+            .db
+            .get_transactional_storage_context(path.into(), None, transaction)
+            .unwrap_add_cost(&mut cost);
+
+        let mut children = Vec::new();
+        let mut raw_iter = Element::iterator(storage.raw_iter()).unwrap_add_cost(&mut cost);
+        while let Some((key, element)) =
+            cost_return_on_error!(&mut cost, raw_iter.next_element(grove_version))
+        {
+            if element.is_any_tree() {
+                let child_path = [path, std::slice::from_ref(&key)].concat();
+                let prefix = StorageBackend::build_prefix(child_path.as_slice().into())
+                    .unwrap_add_cost(&mut cost);
+                children.push(ChildTree {
+                    key,
+                    prefix,
+                    may_have_subtrees: !element.uses_non_merk_data_storage(),
+                });
+            }
+        }
+        Ok(children).wrap_with_cost(cost)
+    }
+}
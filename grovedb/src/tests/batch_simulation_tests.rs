@@ -0,0 +1,139 @@
+//! Tests for simulating batches without applying them.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::tree::AggregateData;
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        batch::QualifiedGroveDbOp,
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element,
+    };
+
+    fn make_db_with_sum_tree(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"sums",
+            Element::empty_sum_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a sum tree");
+        db
+    }
+
+    fn sum_item_ops() -> Vec<QualifiedGroveDbOp> {
+        let sum_tree_path = vec![TEST_LEAF.to_vec(), b"sums".to_vec()];
+        vec![
+            QualifiedGroveDbOp::insert_or_replace_op(
+                sum_tree_path.clone(),
+                b"a".to_vec(),
+                Element::new_sum_item(5),
+            ),
+            QualifiedGroveDbOp::insert_or_replace_op(
+                sum_tree_path,
+                b"b".to_vec(),
+                Element::new_sum_item(7),
+            ),
+            QualifiedGroveDbOp::insert_or_replace_op(
+                vec![TEST_LEAF.to_vec()],
+                b"item".to_vec(),
+                Element::new_item(b"value".to_vec()),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_simulation_matches_applying_the_batch() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_sum_tree(grove_version);
+        let root_hash_before = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        let simulated = db.simulate_batch(sum_item_ops(), None, None, grove_version);
+        let simulated_cost = simulated.cost;
+        let simulation = simulated.value.expect("cannot simulate the batch");
+        assert_eq!(
+            db.root_hash(None, grove_version).unwrap().unwrap(),
+            root_hash_before
+        );
+        assert_eq!(
+            simulation
+                .aggregates
+                .get(&vec![TEST_LEAF.to_vec(), b"sums".to_vec()]),
+            Some(&AggregateData::Sum(12))
+        );
+        assert_eq!(simulation.aggregates.len(), 1);
+
+        let applied = db.apply_batch(sum_item_ops(), None, None, grove_version);
+        applied.value.expect("cannot apply the batch");
+        assert_eq!(applied.cost, simulated_cost);
+        assert_eq!(
+            db.root_hash(None, grove_version).unwrap().unwrap(),
+            simulation.root_hash
+        );
+    }
+
+    #[test]
+    fn test_simulation_leaves_the_transaction_untouched() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_sum_tree(grove_version);
+        let tx = db.start_transaction();
+        db.insert(
+            [TEST_LEAF, b"sums"].as_ref(),
+            b"c",
+            Element::new_sum_item(1),
+            None,
+            Some(&tx),
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a sum item");
+        let root_hash_in_tx = db.root_hash(Some(&tx), grove_version).unwrap().unwrap();
+
+        let simulation = db
+            .simulate_batch(sum_item_ops(), None, Some(&tx), grove_version)
+            .unwrap()
+            .expect("cannot simulate the batch");
+        assert_eq!(
+            simulation
+                .aggregates
+                .get(&vec![TEST_LEAF.to_vec(), b"sums".to_vec()]),
+            Some(&AggregateData::Sum(13))
+        );
+
+        let failing = vec![QualifiedGroveDbOp::insert_or_replace_op(
+            vec![b"missing".to_vec()],
+            b"key".to_vec(),
+            Element::new_item(b"value".to_vec()),
+        )];
+        assert!(db
+            .simulate_batch(failing, None, Some(&tx), grove_version)
+            .unwrap()
+            .is_err());
+
+        assert_eq!(
+            db.root_hash(Some(&tx), grove_version).unwrap().unwrap(),
+            root_hash_in_tx
+        );
+        assert!(db
+            .get(
+                [TEST_LEAF, b"sums"].as_ref(),
+                b"a",
+                Some(&tx),
+                grove_version
+            )
+            .unwrap()
+            .is_err());
+        db.commit_transaction(tx)
+            .unwrap()
+            .expect("cannot commit the transaction");
+        assert_eq!(
+            db.root_hash(None, grove_version).unwrap().unwrap(),
+            root_hash_in_tx
+        );
+    }
+}
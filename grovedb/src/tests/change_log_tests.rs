@@ -0,0 +1,267 @@
+//! Tests for the change log of committed element mutations.
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        batch::QualifiedGroveDbOp,
+        tests::{common::EMPTY_PATH, make_test_grovedb, TEST_LEAF},
+        AggregateDelta, ChangeSet, Element, GroveDb, PathQuery, Query,
+    };
+
+    fn collect_change_sets(db: &GroveDb) -> Arc<Mutex<Vec<ChangeSet>>> {
+        let change_sets = Arc::new(Mutex::new(Vec::new()));
+        let sink = change_sets.clone();
+        db.subscribe_changes(move |change_set| sink.lock().unwrap().push(change_set.clone()));
+        change_sets
+    }
+
+    fn insert_item(db: &GroveDb, key: &[u8], grove_version: &GroveVersion) {
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            key,
+            Element::new_item(b"value".to_vec()),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert an item");
+    }
+
+    #[test]
+    fn test_insert_and_delete_report_changes_with_propagation() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        db.enable_change_log(8);
+        let change_sets = collect_change_sets(&db);
+
+        insert_item(&db, b"key1", grove_version);
+        db.delete([TEST_LEAF].as_ref(), b"key1", None, None, grove_version)
+            .unwrap()
+            .expect("cannot delete the item");
+
+        let change_sets = change_sets.lock().unwrap();
+        assert_eq!(change_sets.len(), 2);
+        assert_eq!(change_sets[0].sequence, 1);
+        assert_eq!(change_sets[1].sequence, 2);
+
+        let inserted = &change_sets[0].changes;
+        assert_eq!(inserted.len(), 2);
+        assert_eq!(inserted[0].path, vec![TEST_LEAF.to_vec()]);
+        assert_eq!(inserted[0].key, b"key1".to_vec());
+        assert_eq!(inserted[0].old, None);
+        assert_eq!(inserted[0].new, Some(Element::new_item(b"value".to_vec())));
+        assert_eq!(inserted[1].path, Vec::<Vec<u8>>::new());
+        assert_eq!(inserted[1].key, TEST_LEAF.to_vec());
+        assert_eq!(inserted[1].old, Some(Element::empty_tree()));
+        assert_eq!(
+            inserted[1].new,
+            Some(Element::new_tree(Some(b"key1".to_vec())))
+        );
+
+        let deleted = &change_sets[1].changes;
+        assert_eq!(deleted[0].key, b"key1".to_vec());
+        assert_eq!(deleted[0].new, None);
+        assert_eq!(deleted[1].new, Some(Element::empty_tree()));
+        assert_eq!(db.last_change_sequence(), 2);
+    }
+
+    #[test]
+    fn test_transaction_changes_are_reported_on_commit_with_aggregate_deltas() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"sums",
+            Element::empty_sum_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a sum tree");
+        db.enable_change_log(8);
+        let change_sets = collect_change_sets(&db);
+
+        let sum_tree_path = vec![TEST_LEAF.to_vec(), b"sums".to_vec()];
+        let tx = db.start_transaction();
+        db.apply_batch(
+            vec![
+                QualifiedGroveDbOp::insert_or_replace_op(
+                    sum_tree_path.clone(),
+                    b"a".to_vec(),
+                    Element::new_sum_item(5),
+                ),
+                QualifiedGroveDbOp::insert_or_replace_op(
+                    sum_tree_path.clone(),
+                    b"b".to_vec(),
+                    Element::new_sum_item(7),
+                ),
+            ],
+            None,
+            Some(&tx),
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot apply the batch");
+        db.insert(
+            sum_tree_path.as_slice(),
+            b"a",
+            Element::new_sum_item(2),
+            None,
+            Some(&tx),
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot replace a sum item");
+        assert!(change_sets.lock().unwrap().is_empty());
+
+        db.commit_transaction(tx)
+            .unwrap()
+            .expect("cannot commit the transaction");
+
+        let change_sets = change_sets.lock().unwrap();
+        assert_eq!(change_sets.len(), 1);
+        let sum_tree_deltas: Vec<AggregateDelta> = change_sets[0]
+            .changes
+            .iter()
+            .filter(|change| change.key == b"sums")
+            .map(|change| change.aggregate_delta())
+            .collect();
+        assert_eq!(
+            sum_tree_deltas,
+            vec![
+                AggregateDelta { sum: 12, count: 0 },
+                AggregateDelta { sum: -3, count: 0 }
+            ]
+        );
+        let replaced = change_sets[0]
+            .changes
+            .iter()
+            .find(|change| change.key == b"a" && change.old.is_some())
+            .expect("expected the replaced sum item");
+        assert_eq!(replaced.new, Some(Element::new_sum_item(2)));
+    }
+
+    #[test]
+    fn test_rolled_back_changes_are_not_reported() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        db.enable_change_log(8);
+
+        let tx = db.start_transaction();
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"key1",
+            Element::new_item(b"value".to_vec()),
+            None,
+            Some(&tx),
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert an item");
+        db.rollback_transaction(&tx)
+            .expect("cannot roll back the transaction");
+        db.insert(
+            EMPTY_PATH,
+            b"leaf",
+            Element::empty_tree(),
+            None,
+            Some(&tx),
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a tree");
+        db.commit_transaction(tx)
+            .unwrap()
+            .expect("cannot commit the transaction");
+
+        let change_sets: Vec<ChangeSet> = db
+            .changes_since(0)
+            .expect("change sets should be retained")
+            .collect();
+        assert_eq!(change_sets.len(), 1);
+        assert_eq!(change_sets[0].changes.len(), 1);
+        assert_eq!(change_sets[0].changes[0].key, b"leaf".to_vec());
+    }
+
+    #[test]
+    fn test_transaction_changes_are_kept_apart_from_other_transactions() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        db.enable_change_log(8);
+        let change_sets = collect_change_sets(&db);
+
+        let insert_in = |tx, key: &[u8]| {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                key,
+                Element::new_item(b"value".to_vec()),
+                None,
+                Some(tx),
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert an item");
+        };
+
+        let tx = db.start_transaction();
+        insert_in(&tx, b"key1");
+
+        // Proving starts a transaction of its own
+        let mut query = Query::new();
+        query.insert_all();
+        let path_query = PathQuery::new_unsized(vec![TEST_LEAF.to_vec()], query);
+        db.prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("cannot prove the query");
+
+        let other_tx = db.start_transaction();
+        insert_in(&other_tx, b"key2");
+        db.rollback_transaction(&other_tx)
+            .expect("cannot roll back the transaction");
+
+        db.commit_transaction(tx)
+            .unwrap()
+            .expect("cannot commit the transaction");
+
+        let change_sets = change_sets.lock().unwrap();
+        assert_eq!(change_sets.len(), 1);
+        let keys: Vec<&[u8]> = change_sets[0]
+            .changes
+            .iter()
+            .map(|change| change.key.as_slice())
+            .collect();
+        assert_eq!(keys, vec![b"key1".as_slice(), TEST_LEAF]);
+    }
+
+    #[test]
+    fn test_changes_since_replays_retained_change_sets() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        insert_item(&db, b"before", grove_version);
+        assert_eq!(db.last_change_sequence(), 0);
+
+        db.enable_change_log(2);
+        insert_item(&db, b"key1", grove_version);
+        insert_item(&db, b"key2", grove_version);
+        insert_item(&db, b"key3", grove_version);
+
+        let sequences: Vec<u64> = db
+            .changes_since(1)
+            .expect("change sets after 1 should be retained")
+            .map(|change_set| change_set.sequence)
+            .collect();
+        assert_eq!(sequences, vec![2, 3]);
+        assert_eq!(db.changes_since(3).expect("nothing to replay").count(), 0);
+        assert!(db.changes_since(0).is_err());
+
+        db.disable_change_log();
+        insert_item(&db, b"key4", grove_version);
+        assert_eq!(db.last_change_sequence(), 3);
+    }
+}
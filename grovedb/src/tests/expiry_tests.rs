@@ -0,0 +1,359 @@
+//! Tests for items with an expiry and for sweeping them through expiry
+//! indexes.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::Query;
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        batch::QualifiedGroveDbOp,
+        element::ExpiryValue,
+        query_result_type::QueryResultType,
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, Error, GroveDb, PathQuery, SecondaryIndex,
+    };
+
+    fn sessions_path() -> Vec<Vec<u8>> {
+        vec![TEST_LEAF.to_vec(), b"sessions".to_vec()]
+    }
+
+    fn expiry_index_path() -> Vec<Vec<u8>> {
+        vec![TEST_LEAF.to_vec(), b"by_expiry".to_vec()]
+    }
+
+    /// Makes `TEST_LEAF/sessions`, whose items are indexed by expiry in
+    /// `TEST_LEAF/by_expiry`.
+    fn make_db_with_expiry_index(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        for key in [b"sessions".as_slice(), b"by_expiry"] {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                key,
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a tree");
+        }
+        db.register_index(SecondaryIndex::expiry(sessions_path(), expiry_index_path()))
+            .expect("cannot register the expiry index");
+        db
+    }
+
+    fn put_session(key: &[u8], expiry: ExpiryValue) -> QualifiedGroveDbOp {
+        QualifiedGroveDbOp::insert_or_replace_op(
+            sessions_path(),
+            key.to_vec(),
+            Element::new_item_with_expiry(key.to_vec(), expiry),
+        )
+    }
+
+    fn apply(db: &GroveDb, ops: Vec<QualifiedGroveDbOp>, grove_version: &GroveVersion) {
+        db.apply_batch(ops, None, None, grove_version)
+            .unwrap()
+            .expect("cannot apply the batch");
+    }
+
+    fn keys_at(db: &GroveDb, path: Vec<Vec<u8>>, grove_version: &GroveVersion) -> Vec<Vec<u8>> {
+        let path_query = PathQuery::new_unsized(path, Query::new_range_full());
+        let (elements, _) = db
+            .query_raw(
+                &path_query,
+                true,
+                true,
+                true,
+                QueryResultType::QueryKeyElementPairResultType,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot query the subtree");
+        elements.to_keys()
+    }
+
+    fn deletes(keys: &[&[u8]]) -> Vec<QualifiedGroveDbOp> {
+        keys.iter()
+            .map(|key| QualifiedGroveDbOp::delete_op(sessions_path(), key.to_vec()))
+            .collect()
+    }
+
+    fn entry_key(expiry: ExpiryValue, key: &[u8]) -> Vec<u8> {
+        let mut entry_key = expiry.to_be_bytes().to_vec();
+        entry_key.extend_from_slice(key);
+        entry_key
+    }
+
+    #[test]
+    fn test_item_with_expiry_reads_like_an_item() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_expiry_index(grove_version);
+        apply(&db, vec![put_session(b"alice", 42)], grove_version);
+
+        let element = db
+            .get(sessions_path().as_slice(), b"alice", None, grove_version)
+            .unwrap()
+            .expect("cannot get the item");
+        assert!(element.is_item_with_expiry());
+        assert!(element.is_any_item());
+        assert_eq!(element.expiry(), Some(42));
+        assert_eq!(element.as_item_bytes().expect("expected an item"), b"alice");
+        assert_eq!(Element::new_item(b"alice".to_vec()).expiry(), None);
+
+        let mut query = Query::new();
+        query.insert_key(b"alice".to_vec());
+        let path_query = PathQuery::new_unsized(sessions_path(), query);
+        let proof = db
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("cannot prove the query");
+        let (root_hash, result_set) = GroveDb::verify_query(&proof, &path_query, grove_version)
+            .expect("cannot verify the proof");
+        assert_eq!(
+            root_hash,
+            db.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        assert_eq!(result_set.len(), 1);
+        assert_eq!(result_set[0].2, Some(element));
+    }
+
+    #[test]
+    fn test_sweep_deletes_expired_items_by_expiry_then_key() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_expiry_index(grove_version);
+        apply(
+            &db,
+            vec![
+                put_session(b"carol", 10),
+                put_session(b"bob", 5),
+                put_session(b"dave", 20),
+                put_session(b"alice", 5),
+                QualifiedGroveDbOp::insert_or_replace_op(
+                    sessions_path(),
+                    b"erin".to_vec(),
+                    Element::new_item(b"erin".to_vec()),
+                ),
+            ],
+            grove_version,
+        );
+        assert_eq!(
+            keys_at(&db, expiry_index_path(), grove_version),
+            vec![
+                entry_key(5, b"alice"),
+                entry_key(5, b"bob"),
+                entry_key(10, b"carol"),
+                entry_key(20, b"dave"),
+            ]
+        );
+
+        let swept = db
+            .sweep_expired(4, 10, None, grove_version)
+            .unwrap()
+            .expect("cannot sweep");
+        assert!(swept.is_empty());
+
+        let swept = db
+            .sweep_expired(10, 2, None, grove_version)
+            .unwrap()
+            .expect("cannot sweep");
+        assert_eq!(swept, deletes(&[b"alice", b"bob"]));
+
+        let swept = db
+            .sweep_expired(10, 2, None, grove_version)
+            .unwrap()
+            .expect("cannot sweep");
+        assert_eq!(swept, deletes(&[b"carol"]));
+
+        assert_eq!(
+            keys_at(&db, sessions_path(), grove_version),
+            vec![b"dave".to_vec(), b"erin".to_vec()]
+        );
+        assert_eq!(
+            keys_at(&db, expiry_index_path(), grove_version),
+            vec![entry_key(20, b"dave")]
+        );
+
+        let swept = db
+            .sweep_expired(ExpiryValue::MAX, 10, None, grove_version)
+            .unwrap()
+            .expect("cannot sweep");
+        assert_eq!(swept, deletes(&[b"dave"]));
+        assert!(keys_at(&db, expiry_index_path(), grove_version).is_empty());
+    }
+
+    #[test]
+    fn test_replacing_an_item_moves_its_expiry() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_expiry_index(grove_version);
+        apply(
+            &db,
+            vec![put_session(b"alice", 5), put_session(b"bob", 5)],
+            grove_version,
+        );
+        apply(
+            &db,
+            vec![
+                put_session(b"alice", 100),
+                QualifiedGroveDbOp::insert_or_replace_op(
+                    sessions_path(),
+                    b"bob".to_vec(),
+                    Element::new_item(b"bob".to_vec()),
+                ),
+            ],
+            grove_version,
+        );
+        assert_eq!(
+            keys_at(&db, expiry_index_path(), grove_version),
+            vec![entry_key(100, b"alice")]
+        );
+
+        let swept = db
+            .sweep_expired(50, 10, None, grove_version)
+            .unwrap()
+            .expect("cannot sweep");
+        assert!(swept.is_empty());
+        assert_eq!(
+            keys_at(&db, sessions_path(), grove_version),
+            vec![b"alice".to_vec(), b"bob".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_sweeps_of_the_same_state_agree() {
+        let grove_version = GroveVersion::latest();
+        let ops: Vec<_> = (0u8..40)
+            .map(|n| put_session(&[b'k', n], (n as u64 * 7) % 13))
+            .collect();
+
+        let dbs = [
+            make_db_with_expiry_index(grove_version),
+            make_db_with_expiry_index(grove_version),
+        ];
+        let mut sweeps = Vec::new();
+        for db in &dbs {
+            apply(db, ops.clone(), grove_version);
+            let transaction = db.start_transaction();
+            let swept = db
+                .sweep_expired(6, 8, Some(&transaction), grove_version)
+                .unwrap()
+                .expect("cannot sweep");
+            db.commit_transaction(transaction)
+                .unwrap()
+                .expect("cannot commit the sweep");
+            sweeps.push((swept, db.root_hash(None, grove_version).unwrap().unwrap()));
+        }
+        assert_eq!(sweeps[0], sweeps[1]);
+        assert_eq!(sweeps[0].0.len(), 8);
+    }
+
+    #[test]
+    fn test_items_with_expiry_are_only_written_where_indexed() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_expiry_index(grove_version);
+
+        assert!(matches!(
+            db.insert(
+                sessions_path().as_slice(),
+                b"alice",
+                Element::new_item_with_expiry(b"alice".to_vec(), 5),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap(),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            db.apply_partial_batch(
+                vec![put_session(b"alice", 5)],
+                None,
+                |_, _| Ok(Vec::new()),
+                None,
+                grove_version,
+            )
+            .unwrap(),
+            Err(Error::InvalidBatchOperation(_))
+        ));
+
+        assert!(db.unregister_index(&expiry_index_path()));
+        assert!(matches!(
+            db.apply_batch(vec![put_session(b"alice", 5)], None, None, grove_version)
+                .unwrap(),
+            Err(Error::InvalidBatchOperation(
+                "an item with an expiry needs an expiry index maintained by the batch"
+            ))
+        ));
+        assert!(keys_at(&db, sessions_path(), grove_version).is_empty());
+    }
+
+    #[test]
+    fn test_sweep_deletes_entries_left_by_delete_and_insert() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_expiry_index(grove_version);
+        apply(
+            &db,
+            vec![
+                put_session(b"alice", 5),
+                put_session(b"bob", 5),
+                put_session(b"carol", 5),
+            ],
+            grove_version,
+        );
+        db.delete(
+            sessions_path().as_slice(),
+            b"alice",
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot delete the item");
+        db.insert(
+            sessions_path().as_slice(),
+            b"bob",
+            Element::new_item(b"bob".to_vec()),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot replace the item");
+
+        let swept = db
+            .sweep_expired(10, 10, None, grove_version)
+            .unwrap()
+            .expect("cannot sweep");
+        assert_eq!(
+            swept,
+            vec![
+                QualifiedGroveDbOp::delete_op(expiry_index_path(), entry_key(5, b"alice")),
+                QualifiedGroveDbOp::delete_op(expiry_index_path(), entry_key(5, b"bob")),
+                QualifiedGroveDbOp::delete_op(sessions_path(), b"carol".to_vec()),
+            ]
+        );
+        assert_eq!(
+            keys_at(&db, sessions_path(), grove_version),
+            vec![b"bob".to_vec()]
+        );
+        assert!(keys_at(&db, expiry_index_path(), grove_version).is_empty());
+    }
+
+    #[test]
+    fn test_expiry_index_is_not_kept_with_its_items() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        assert!(matches!(
+            db.register_index(SecondaryIndex::expiry(sessions_path(), sessions_path())),
+            Err(Error::InvalidInput(
+                "an expiry index can't be kept in the subtree of its items"
+            ))
+        ));
+        assert!(db
+            .sweep_expired(10, 10, None, grove_version)
+            .unwrap()
+            .expect("cannot sweep without indexes")
+            .is_empty());
+    }
+}
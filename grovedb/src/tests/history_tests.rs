@@ -0,0 +1,170 @@
+//! Tests for root hashes and proofs at past versions.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::Query;
+    use grovedb_version::version::GroveVersion;
+    use tempfile::TempDir;
+
+    use crate::{
+        tests::{common::EMPTY_PATH, TEST_LEAF},
+        Element, GroveDb, GroveDbOptions, PathQuery,
+    };
+
+    fn open_with_history(dir: &TempDir, retention: u64) -> GroveDb {
+        GroveDb::open_with_options(
+            dir.path(),
+            GroveDbOptions::default().history_retention(retention),
+        )
+        .expect("cannot open grovedb with history")
+    }
+
+    fn insert_item(db: &GroveDb, key: &[u8], value: &[u8], grove_version: &GroveVersion) {
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            key,
+            Element::new_item(value.to_vec()),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert an item");
+    }
+
+    fn key1_at_version(
+        db: &GroveDb,
+        version: u64,
+        grove_version: &GroveVersion,
+    ) -> Option<Element> {
+        let mut query = Query::new();
+        query.insert_key(b"key1".to_vec());
+        let path_query = PathQuery::new_unsized(vec![TEST_LEAF.to_vec()], query);
+
+        let proof = db
+            .prove_query_at_version(&path_query, version, None, grove_version)
+            .unwrap()
+            .expect("cannot prove at version");
+        let (root_hash, result_set) = GroveDb::verify_query(&proof, &path_query, grove_version)
+            .expect("cannot verify the proof");
+        assert_eq!(
+            root_hash,
+            db.root_hash_at_version(version, grove_version)
+                .unwrap()
+                .expect("cannot get root hash at version")
+        );
+        result_set
+            .into_iter()
+            .next()
+            .and_then(|(_, _, element)| element)
+    }
+
+    #[test]
+    fn test_root_hashes_and_proofs_at_past_versions() {
+        let grove_version = GroveVersion::latest();
+        let tmp_dir = TempDir::new().unwrap();
+        let db = open_with_history(&tmp_dir, 4);
+        assert_eq!(db.retained_versions().unwrap(), Some(0..=0));
+        let mut root_hashes = vec![db.root_hash(None, grove_version).unwrap().unwrap()];
+
+        db.insert(
+            EMPTY_PATH,
+            TEST_LEAF,
+            Element::empty_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a subtree");
+        root_hashes.push(db.root_hash(None, grove_version).unwrap().unwrap());
+
+        insert_item(&db, b"key1", b"a", grove_version);
+        root_hashes.push(db.root_hash(None, grove_version).unwrap().unwrap());
+
+        // A transaction is a single version
+        let transaction = db.start_transaction();
+        for (key, value) in [(b"key1", b"b"), (b"key2", b"c")] {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                key,
+                Element::new_item(value.to_vec()),
+                None,
+                Some(&transaction),
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert an item");
+        }
+        db.commit_transaction(transaction)
+            .unwrap()
+            .expect("cannot commit transaction");
+        root_hashes.push(db.root_hash(None, grove_version).unwrap().unwrap());
+        assert_eq!(db.retained_versions().unwrap(), Some(0..=3));
+
+        for (version, root_hash) in root_hashes.iter().enumerate() {
+            assert_eq!(
+                &db.root_hash_at_version(version as u64, grove_version)
+                    .unwrap()
+                    .expect("cannot get root hash at version"),
+                root_hash
+            );
+        }
+        assert_eq!(
+            key1_at_version(&db, 2, grove_version),
+            Some(Element::new_item(b"a".to_vec()))
+        );
+        assert_eq!(
+            key1_at_version(&db, 3, grove_version),
+            Some(Element::new_item(b"b".to_vec()))
+        );
+        assert!(db.root_hash_at_version(4, grove_version).unwrap().is_err());
+
+        db.delete([TEST_LEAF].as_ref(), b"key1", None, None, grove_version)
+            .unwrap()
+            .expect("cannot delete an item");
+        insert_item(&db, b"key3", b"d", grove_version);
+        assert_eq!(db.retained_versions().unwrap(), Some(2..=5));
+        assert!(db.root_hash_at_version(1, grove_version).unwrap().is_err());
+        assert_eq!(key1_at_version(&db, 4, grove_version), None);
+        assert_eq!(
+            key1_at_version(&db, 2, grove_version),
+            Some(Element::new_item(b"a".to_vec()))
+        );
+        assert_eq!(
+            db.root_hash_at_version(3, grove_version).unwrap().unwrap(),
+            root_hashes[3]
+        );
+    }
+
+    #[test]
+    fn test_history_is_dropped_when_opened_without_it() {
+        let grove_version = GroveVersion::latest();
+        let tmp_dir = TempDir::new().unwrap();
+        let db = open_with_history(&tmp_dir, 10);
+        db.insert(
+            EMPTY_PATH,
+            TEST_LEAF,
+            Element::empty_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a subtree");
+        assert_eq!(db.retained_versions().unwrap(), Some(0..=1));
+        drop(db);
+
+        let db = GroveDb::open(tmp_dir.path()).expect("cannot reopen grovedb");
+        assert_eq!(db.retained_versions().unwrap(), None);
+        assert!(db.root_hash_at_version(0, grove_version).unwrap().is_err());
+        drop(db);
+
+        let db = open_with_history(&tmp_dir, 10);
+        assert_eq!(db.retained_versions().unwrap(), Some(0..=0));
+        assert_eq!(
+            db.root_hash_at_version(0, grove_version).unwrap().unwrap(),
+            db.root_hash(None, grove_version).unwrap().unwrap()
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Tests for GroveDb running on in-memory storage.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_costs::OperationCost;
+    use grovedb_merk::proofs::Query;
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        batch::QualifiedGroveDbOp,
+        tests::{common::EMPTY_PATH, make_empty_grovedb, ANOTHER_TEST_LEAF, TEST_LEAF},
+        Element, GroveDb, PathQuery,
+    };
+
+    /// Applies the same mix of inserts, batches and deletions to `db`,
+    /// returning the cost of every operation.
+    fn run_workload(db: &GroveDb, grove_version: &GroveVersion) -> Vec<OperationCost> {
+        let mut costs = Vec::new();
+
+        for leaf in [TEST_LEAF, ANOTHER_TEST_LEAF] {
+            let result = db.insert(
+                EMPTY_PATH,
+                leaf,
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            );
+            costs.push(result.cost);
+            result.value.expect("cannot insert a subtree");
+        }
+
+        let result = db.insert(
+            [TEST_LEAF].as_ref(),
+            b"sum",
+            Element::empty_sum_tree(),
+            None,
+            None,
+            grove_version,
+        );
+        costs.push(result.cost);
+        result.value.expect("cannot insert a sum tree");
+
+        let ops = (0u8..10)
+            .map(|i| {
+                QualifiedGroveDbOp::insert_or_replace_op(
+                    vec![TEST_LEAF.to_vec(), b"sum".to_vec()],
+                    vec![i],
+                    Element::new_sum_item(i as i64),
+                )
+            })
+            .chain((0u8..10).map(|i| {
+                QualifiedGroveDbOp::insert_or_replace_op(
+                    vec![ANOTHER_TEST_LEAF.to_vec()],
+                    vec![i],
+                    Element::new_item(vec![i; 20]),
+                )
+            }))
+            .collect();
+        let result = db.apply_batch(ops, None, None, grove_version);
+        costs.push(result.cost);
+        result.value.expect("cannot apply batch");
+
+        let result = db.delete(
+            [ANOTHER_TEST_LEAF].as_ref(),
+            &[3],
+            None,
+            None,
+            grove_version,
+        );
+        costs.push(result.cost);
+        result.value.expect("cannot delete an item");
+
+        let result = db.get([TEST_LEAF, b"sum"].as_ref(), &[5], None, grove_version);
+        costs.push(result.cost);
+        result.value.expect("cannot get an item");
+
+        costs
+    }
+
+    #[test]
+    fn test_in_memory_matches_rocksdb() {
+        let grove_version = GroveVersion::latest();
+        let rocksdb = make_empty_grovedb();
+        let memory = GroveDb::open_in_memory();
+        assert!(memory.is_in_memory());
+        assert!(!rocksdb.is_in_memory());
+
+        assert_eq!(
+            run_workload(&rocksdb, grove_version),
+            run_workload(&memory, grove_version)
+        );
+        assert_eq!(
+            rocksdb.root_hash(None, grove_version).unwrap().unwrap(),
+            memory.root_hash(None, grove_version).unwrap().unwrap()
+        );
+
+        let mut query = Query::new();
+        query.insert_all();
+        let path_query = PathQuery::new_unsized(vec![ANOTHER_TEST_LEAF.to_vec()], query);
+        let proof = memory
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("cannot prove query");
+        assert_eq!(
+            proof,
+            rocksdb
+                .prove_query(&path_query, None, grove_version)
+                .unwrap()
+                .expect("cannot prove query")
+        );
+
+        let (hash, result_set) =
+            GroveDb::verify_query(&proof, &path_query, grove_version).expect("cannot verify proof");
+        assert_eq!(
+            hash,
+            memory.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        assert_eq!(result_set.len(), 9);
+    }
+
+    #[test]
+    fn test_in_memory_transactions() {
+        let grove_version = GroveVersion::latest();
+        let db = GroveDb::open_in_memory();
+        db.insert(
+            EMPTY_PATH,
+            TEST_LEAF,
+            Element::empty_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a subtree");
+
+        let transaction = db.start_transaction();
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"key",
+            Element::new_item(b"value".to_vec()),
+            None,
+            Some(&transaction),
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert an item");
+
+        assert!(db
+            .get([TEST_LEAF].as_ref(), b"key", None, grove_version)
+            .unwrap()
+            .is_err());
+        assert_eq!(
+            db.get(
+                [TEST_LEAF].as_ref(),
+                b"key",
+                Some(&transaction),
+                grove_version
+            )
+            .unwrap()
+            .expect("item should be visible inside the transaction"),
+            Element::new_item(b"value".to_vec())
+        );
+
+        db.rollback_transaction(&transaction)
+            .expect("cannot rollback transaction");
+        assert!(db
+            .get(
+                [TEST_LEAF].as_ref(),
+                b"key",
+                Some(&transaction),
+                grove_version
+            )
+            .unwrap()
+            .is_err());
+
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"key",
+            Element::new_item(b"value".to_vec()),
+            None,
+            Some(&transaction),
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert an item");
+        db.commit_transaction(transaction)
+            .unwrap()
+            .expect("cannot commit transaction");
+        assert_eq!(
+            db.get([TEST_LEAF].as_ref(), b"key", None, grove_version)
+                .unwrap()
+                .expect("item should be visible after commit"),
+            Element::new_item(b"value".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_in_memory_checkpoint_is_rejected() {
+        let db = GroveDb::open_in_memory();
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        assert!(db
+            .create_checkpoint(tmp_dir.path().join("checkpoint"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_transaction_of_another_backend_is_rejected() {
+        let grove_version = GroveVersion::latest();
+        let memory = GroveDb::open_in_memory();
+        let rocksdb = make_empty_grovedb();
+        let transaction = memory.start_transaction();
+
+        assert!(rocksdb
+            .insert(
+                EMPTY_PATH,
+                TEST_LEAF,
+                Element::empty_tree(),
+                None,
+                Some(&transaction),
+                grove_version,
+            )
+            .unwrap()
+            .is_err());
+        assert!(rocksdb
+            .get(EMPTY_PATH, TEST_LEAF, Some(&transaction), grove_version)
+            .unwrap()
+            .is_err());
+    }
+}
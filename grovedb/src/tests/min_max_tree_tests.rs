@@ -0,0 +1,268 @@
+//! MinMaxTree tests
+//!
+//! A MinMaxTree tracks the minimum and maximum of the MinMaxItems stored
+//! directly below it. Both values are part of every node hash, so they can be
+//! proven along with the queried elements.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::{encoding::Decoder, Node, Op, Query};
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        batch::QualifiedGroveDbOp,
+        operations::proof::{GroveDBProof, ProofBytes},
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, Error, GroveDb, PathQuery,
+    };
+
+    fn insert_min_max_tree_with_items(db: &TempGroveDb, values: &[(&[u8], i64)]) {
+        let grove_version = GroveVersion::latest();
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"prices",
+            Element::empty_min_max_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("should insert min max tree");
+
+        for (key, value) in values {
+            db.insert(
+                [TEST_LEAF, b"prices"].as_ref(),
+                key,
+                Element::new_min_max_item(*value),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should insert min max item");
+        }
+    }
+
+    fn get_min_max(db: &TempGroveDb) -> (i64, i64) {
+        db.get(
+            [TEST_LEAF].as_ref(),
+            b"prices",
+            None,
+            GroveVersion::latest(),
+        )
+        .unwrap()
+        .expect("should get min max tree")
+        .min_max_value_or_default()
+    }
+
+    #[test]
+    fn test_empty_min_max_tree_has_identity_bounds() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        insert_min_max_tree_with_items(&db, &[]);
+
+        let element = db
+            .get([TEST_LEAF].as_ref(), b"prices", None, grove_version)
+            .unwrap()
+            .expect("should get min max tree");
+        assert!(element.is_min_max_tree());
+        assert_eq!(element.min_max_value_or_default(), (i64::MAX, i64::MIN));
+    }
+
+    #[test]
+    fn test_min_max_tree_tracks_bounds_on_insert_and_delete() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        insert_min_max_tree_with_items(&db, &[(b"a", 50), (b"b", -20), (b"c", 300)]);
+        assert_eq!(get_min_max(&db), (-20, 300));
+
+        // Items that aren't min max items don't move the bounds
+        db.insert(
+            [TEST_LEAF, b"prices"].as_ref(),
+            b"d",
+            Element::new_item(b"note".to_vec()),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("should insert plain item");
+        assert_eq!(get_min_max(&db), (-20, 300));
+
+        db.delete(
+            [TEST_LEAF, b"prices"].as_ref(),
+            b"c",
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("should delete max");
+        assert_eq!(get_min_max(&db), (-20, 50));
+
+        db.delete(
+            [TEST_LEAF, b"prices"].as_ref(),
+            b"b",
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("should delete min");
+        assert_eq!(get_min_max(&db), (50, 50));
+
+        // Replacing an item updates the bounds
+        db.insert(
+            [TEST_LEAF, b"prices"].as_ref(),
+            b"a",
+            Element::new_min_max_item(7),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("should replace min max item");
+        assert_eq!(get_min_max(&db), (7, 7));
+    }
+
+    #[test]
+    fn test_min_max_tree_with_batch() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+
+        let ops = vec![
+            QualifiedGroveDbOp::insert_or_replace_op(
+                vec![TEST_LEAF.to_vec()],
+                b"prices".to_vec(),
+                Element::empty_min_max_tree(),
+            ),
+            QualifiedGroveDbOp::insert_or_replace_op(
+                vec![TEST_LEAF.to_vec(), b"prices".to_vec()],
+                b"a".to_vec(),
+                Element::new_min_max_item(11),
+            ),
+            QualifiedGroveDbOp::insert_or_replace_op(
+                vec![TEST_LEAF.to_vec(), b"prices".to_vec()],
+                b"b".to_vec(),
+                Element::new_min_max_item(-4),
+            ),
+        ];
+        db.apply_batch(ops, None, None, grove_version)
+            .unwrap()
+            .expect("should apply batch");
+
+        assert_eq!(get_min_max(&db), (-4, 11));
+    }
+
+    #[test]
+    fn test_min_max_item_rejected_outside_min_max_tree() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+
+        let result = db
+            .insert(
+                [TEST_LEAF].as_ref(),
+                b"price",
+                Element::new_min_max_item(5),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap();
+        assert!(matches!(
+            result,
+            Err(Error::InvalidInput(
+                "cannot add min max item to non min max tree"
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_min_max_tree_proof_carries_bounds() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        insert_min_max_tree_with_items(
+            &db,
+            &[(b"a", 10), (b"b", 3), (b"c", 99), (b"d", -1), (b"e", 42)],
+        );
+
+        let mut query = Query::new();
+        query.insert_key(b"c".to_vec());
+        let path_query =
+            PathQuery::new_unsized(vec![TEST_LEAF.to_vec(), b"prices".to_vec()], query);
+
+        let proof = db
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("should generate proof");
+
+        let (root_hash, proved_values) =
+            GroveDb::verify_query_raw(&proof, &path_query, grove_version)
+                .expect("should verify proof");
+        assert_eq!(
+            root_hash,
+            db.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        assert_eq!(proved_values.len(), 1);
+        assert_eq!(proved_values[0].key, b"c");
+
+        // Every node on the queried path carries its subtree's bounds, so the
+        // widest bounds in the lowest layer are those of the root node.
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+        let grovedb_proof: GroveDBProof = bincode::decode_from_slice(&proof, config)
+            .expect("should decode proof")
+            .0;
+        let GroveDBProof::V1(proof_v1) = grovedb_proof else {
+            panic!("expected a V1 proof");
+        };
+        let prices_layer =
+            &proof_v1.root_layer.lower_layers[TEST_LEAF].lower_layers[b"prices".as_slice()];
+        let ProofBytes::Merk(merk_proof) = &prices_layer.merk_proof else {
+            panic!("expected a merk proof");
+        };
+
+        let mut bounds = (i64::MAX, i64::MIN);
+        for op in Decoder::new(merk_proof) {
+            let node = match op.expect("should decode op") {
+                Op::Push(node) | Op::PushInverted(node) => node,
+                _ => continue,
+            };
+            let (min, max) = match node {
+                Node::KVMinMax(_, _, min, max)
+                | Node::KVHashMinMax(_, min, max)
+                | Node::KVDigestMinMax(_, _, min, max) => (min, max),
+                Node::Hash(_) => continue,
+                other => panic!("unexpected node in min max tree proof: {:?}", other),
+            };
+            bounds = (bounds.0.min(min), bounds.1.max(max));
+        }
+        assert_eq!(bounds, (-1, 99));
+    }
+
+    #[test]
+    fn test_min_max_tree_bounds_are_provable_from_parent() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        insert_min_max_tree_with_items(&db, &[(b"a", 8), (b"b", 21)]);
+
+        let mut query = Query::new();
+        query.insert_key(b"prices".to_vec());
+        let path_query = PathQuery::new_unsized(vec![TEST_LEAF.to_vec()], query);
+
+        let proof = db
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("should generate proof");
+        let (root_hash, proved_values) =
+            GroveDb::verify_query(&proof, &path_query, grove_version).expect("should verify proof");
+        assert_eq!(
+            root_hash,
+            db.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        assert_eq!(proved_values.len(), 1);
+        let element = proved_values[0].2.as_ref().expect("tree should be proven");
+        assert_eq!(element.min_max_value_or_default(), (8, 21));
+    }
+}
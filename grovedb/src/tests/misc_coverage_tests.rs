@@ -895,9 +895,9 @@ fn batch_average_case_insert_tree_cost_actual_comparison() {
 
 #[test]
 fn batch_operations_exercise_merk_cache() {
-    // MerkCache is used internally by batch processing. By running a
-    // multi-path batch operation, we exercise the cache's get_merk, into_batch,
-    // and propagation logic.
+    // MerkCache is used internally by inserts and deletes. Inserting a
+    // subtree exercises the cache's get_merk, flush and propagation logic,
+    // and the batch across several paths checks the result stays consistent.
     let grove_version = GroveVersion::latest();
     let db = make_test_grovedb(grove_version);
     let tx = db.start_transaction();
@@ -9,8 +9,10 @@ mod sum_tree_tests;
 mod batch_coverage_tests;
 mod batch_delete_tree_tests;
 mod batch_rejection_tests;
+mod batch_simulation_tests;
 mod batch_unit_tests;
 mod bulk_append_tree_tests;
+mod change_log_tests;
 mod checkpoint_tests;
 mod chunk_branch_proof_tests;
 mod commitment_tree_tests;
@@ -22,9 +24,13 @@ mod dense_tree_tests;
 mod error_display_tests;
 mod estimated_costs_average_case_tests;
 mod estimated_costs_worst_case_tests;
+mod expiry_tests;
 mod get_cost_estimator_tests;
 mod grove_query_result_tests;
+mod history_tests;
 mod is_empty_tree_tests;
+mod memory_storage_tests;
+mod min_max_tree_tests;
 mod misc_coverage_tests;
 mod mmr_tree_tests;
 mod operations_coverage_tests;
@@ -32,14 +38,26 @@ mod partial_batch_consistency_tests;
 mod proof_advanced_tests;
 mod proof_coverage_tests;
 mod proof_depth_limit_tests;
+mod proof_offset_tests;
 mod provable_count_sum_tree_tests;
 mod provable_count_tree_comprehensive_test;
 mod provable_count_tree_structure_test;
 mod provable_count_tree_test;
+mod provable_sum_tree_tests;
+mod query_iter_tests;
+mod query_page_tests;
+mod query_projection_tests;
 mod query_result_type_tests;
+mod range_count_proof_tests;
+mod rank_tests;
+mod read_only_tests;
 mod reference_path_tests;
 mod replication_session_tests;
 mod replication_utils_tests;
+mod secondary_index_tests;
+mod storage_fee_report_tests;
+mod subtree_aggregate_tests;
+mod subtree_registry_tests;
 mod succinctness_gap_test;
 mod test_compaction_sizes;
 mod test_provable_count_fresh;
@@ -1074,6 +1092,8 @@ pub fn make_deep_tree_with_sum_trees_mixed_with_items(grove_version: &GroveVersi
 }
 
 mod general_tests {
+    use std::collections::HashMap;
+
     use batch::QualifiedGroveDbOp;
     use grovedb_merk::{
         element::get::ElementFetchFromStorageExtensions, proofs::query::SubqueryBranch,
@@ -1088,6 +1108,119 @@ mod general_tests {
         GroveDb::open(tmp_dir).expect("empty tree is ok");
     }
 
+    /// Returns the options RocksDB persisted along with the database at
+    /// `path`, by section of its latest options file and by name.
+    fn persisted_rocksdb_options(
+        path: &std::path::Path,
+    ) -> HashMap<String, HashMap<String, String>> {
+        let options_file = std::fs::read_dir(path)
+            .expect("cannot read the database directory")
+            .map(|entry| entry.expect("cannot read a directory entry").path())
+            .filter(|file| {
+                file.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("OPTIONS-"))
+            })
+            .max()
+            .expect("RocksDB keeps an options file");
+
+        let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut section = String::new();
+        for line in std::fs::read_to_string(options_file)
+            .expect("cannot read the options file")
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+        {
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                section = name.to_string();
+            } else if let Some((name, value)) = line.split_once('=') {
+                sections
+                    .entry(section.clone())
+                    .or_default()
+                    .insert(name.to_string(), value.to_string());
+            }
+        }
+        sections
+    }
+
+    #[test]
+    fn test_open_with_options() {
+        let grove_version = GroveVersion::latest();
+        let tmp_dir = TempDir::new().unwrap();
+        let options = GroveDbOptions::default()
+            .parallelism(2)
+            .block_cache_size(8 * 1024 * 1024)
+            .compression(CompressionType::Lz4)
+            .bloom_filter_bits_per_key(10.0)
+            .max_total_wal_size(64 * 1024 * 1024)
+            .manual_wal_flush(true)
+            .aux_cf(ColumnFamilyOptions::default().compression(CompressionType::None))
+            .meta_cf(ColumnFamilyOptions::default().write_buffer_size(1024 * 1024));
+
+        let db = GroveDb::open_with_options(tmp_dir.path(), options)
+            .expect("cannot open grovedb with options");
+        let StorageBackend::RocksDb(storage) = &db.db else {
+            panic!("expected a RocksDB backed GroveDb");
+        };
+        assert_eq!(
+            storage
+                .property_int_value("rocksdb.block-cache-capacity")
+                .expect("cannot read the block cache capacity"),
+            Some(8 * 1024 * 1024)
+        );
+        let persisted = persisted_rocksdb_options(tmp_dir.path());
+        let option = |section: &str, name: &str| persisted[section][name].as_str();
+        assert_eq!(option("DBOptions", "max_background_jobs"), "2");
+        assert_eq!(option("DBOptions", "max_total_wal_size"), "67108864");
+        assert_eq!(option("DBOptions", "manual_wal_flush"), "true");
+        assert_eq!(
+            option("CFOptions \"default\"", "compression"),
+            "kLZ4Compression"
+        );
+        assert_eq!(
+            option("CFOptions \"roots\"", "compression"),
+            "kLZ4Compression"
+        );
+        assert_eq!(option("CFOptions \"aux\"", "compression"), "kNoCompression");
+        assert_eq!(option("CFOptions \"meta\"", "write_buffer_size"), "1048576");
+        assert!(
+            option("TableOptions/BlockBasedTable \"meta\"", "filter_policy")
+                .starts_with("bloomfilter:10")
+        );
+
+        db.insert(
+            EMPTY_PATH,
+            TEST_LEAF,
+            Element::empty_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a subtree");
+        db.put_aux(b"aux_key", b"aux_value", None, None)
+            .unwrap()
+            .expect("cannot put aux");
+        // Writes the buffered WAL writes
+        db.flush().expect("cannot flush");
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+        drop(db);
+
+        let db = GroveDb::open(tmp_dir.path()).expect("cannot reopen grovedb");
+        assert_eq!(
+            db.root_hash(None, grove_version).unwrap().unwrap(),
+            root_hash
+        );
+        assert_eq!(
+            db.get_aux(b"aux_key", None).unwrap().unwrap(),
+            Some(b"aux_value".to_vec())
+        );
+    }
+
     #[test]
     fn test_element_with_flags() {
         let grove_version = GroveVersion::latest();
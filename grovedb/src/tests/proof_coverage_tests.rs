@@ -1352,9 +1352,10 @@ mod tests {
     }
 
     #[test]
-    fn prove_query_with_nonzero_offset_errors() {
-        // prove_query with a non-zero offset should return InvalidQuery error
-        let grove_version = GroveVersion::latest();
+    fn prove_query_v0_with_nonzero_offset_errors() {
+        // V0 proofs do not support offsets, prove_query should return an
+        // InvalidQuery error
+        let grove_version = &GROVE_V2;
         let db = make_test_grovedb(grove_version);
 
         let mut query = Query::new();
@@ -1389,7 +1390,7 @@ mod tests {
     }
 
     #[test]
-    fn prove_query_v1_with_nonzero_offset_errors() {
+    fn prove_query_v1_with_nonzero_offset_succeeds() {
         let grove_version = GroveVersion::latest();
         let db = make_test_grovedb(grove_version);
 
@@ -1400,11 +1401,13 @@ mod tests {
             SizedQuery::new(query, None, Some(3)),
         );
 
-        let result = db.prove_query(&path_query, None, grove_version).unwrap();
-        assert!(
-            result.is_err(),
-            "prove_query_v1 with non-zero offset should error"
-        );
+        let proof = db
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("prove_query_v1 with non-zero offset should succeed");
+        let (_, results) = GroveDb::verify_query(&proof, &path_query, grove_version)
+            .expect("should verify the proof with its offset");
+        assert!(results.is_empty());
     }
 
     #[test]
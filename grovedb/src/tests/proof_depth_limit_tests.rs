@@ -232,6 +232,7 @@ mod tests {
                 vec![b"deep".as_slice()],
                 &path_query,
                 &mut limit,
+                &mut 0,
                 &prove_options,
                 MAX_PROOF_DEPTH + 1,
                 grove_version,
@@ -325,6 +326,7 @@ mod tests {
             &prove_options,
             &path_query,
             &mut limit,
+            &mut 0,
             &[],
             &mut result,
             &mut last_tree_type,
@@ -717,6 +719,7 @@ mod tests {
             &prove_options,
             &path_query,
             &mut limit,
+            &mut 0,
             &[b"deep"],
             &mut result,
             &mut last_tree_type,
@@ -0,0 +1,352 @@
+//! Tests for proofs of path queries with offsets.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::{query::QueryItem, Query};
+    use grovedb_version::version::{v2::GROVE_V2, v3::GROVE_V3, GroveVersion};
+
+    use crate::{
+        query_result_type::QueryResultType,
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, Error, GroveDb, PathQuery, SizedQuery,
+    };
+
+    /// Makes `TEST_LEAF/things` holding 20 items, and `TEST_LEAF/groups`
+    /// holding the trees `g0` to `g3` of 5 items each.
+    fn make_db_with_things(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        for key in [b"things".as_slice(), b"groups"] {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                key,
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a tree");
+        }
+        for n in 0u8..20 {
+            db.insert(
+                [TEST_LEAF, b"things"].as_ref(),
+                &[n],
+                Element::new_item(vec![n; 200]),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert an item");
+        }
+        for group in 0u8..4 {
+            let group_key = format!("g{}", group).into_bytes();
+            db.insert(
+                [TEST_LEAF, b"groups"].as_ref(),
+                &group_key,
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a group");
+            for n in 0u8..5 {
+                db.insert(
+                    [TEST_LEAF, b"groups", group_key.as_slice()].as_ref(),
+                    &[n],
+                    Element::new_item(vec![group, n]),
+                    None,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("cannot insert an item");
+            }
+        }
+        db
+    }
+
+    fn things_query(query: Query, limit: Option<u16>, offset: Option<u16>) -> PathQuery {
+        PathQuery::new(
+            vec![TEST_LEAF.to_vec(), b"things".to_vec()],
+            SizedQuery::new(query, limit, offset),
+        )
+    }
+
+    fn groups_query(limit: Option<u16>, offset: Option<u16>) -> PathQuery {
+        let mut query = Query::new_range_full();
+        query.set_subquery(Query::new_range_full());
+        PathQuery::new(
+            vec![TEST_LEAF.to_vec(), b"groups".to_vec()],
+            SizedQuery::new(query, limit, offset),
+        )
+    }
+
+    /// Checks that the proof of `path_query` verifies to the results of the
+    /// query itself.
+    fn assert_proof_matches_query(db: &TempGroveDb, path_query: &PathQuery) {
+        let grove_version = GroveVersion::latest();
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+        let expected: Vec<_> = db
+            .query_raw(
+                path_query,
+                true,
+                true,
+                true,
+                QueryResultType::QueryPathKeyElementTrioResultType,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot run the query")
+            .0
+            .to_path_key_elements()
+            .into_iter()
+            .map(|(path, key, element)| (path, key, Some(element)))
+            .collect();
+
+        let proof = db
+            .prove_query(path_query, None, grove_version)
+            .unwrap()
+            .expect("cannot prove the query");
+        let (proved_root_hash, results) = GroveDb::verify_query(&proof, path_query, grove_version)
+            .expect("cannot verify the proof");
+        assert_eq!(proved_root_hash, root_hash);
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_offset_proofs_match_the_query_results() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_things(grove_version);
+
+        for (limit, offset) in [
+            (Some(5), Some(3)),
+            (Some(5), Some(17)),
+            (Some(5), Some(25)),
+            (None, Some(1)),
+            (Some(1), Some(19)),
+        ] {
+            assert_proof_matches_query(&db, &things_query(Query::new_range_full(), limit, offset));
+
+            let mut descending = Query::new_range_full();
+            descending.left_to_right = false;
+            assert_proof_matches_query(&db, &things_query(descending, limit, offset));
+        }
+
+        let mut range = Query::new();
+        range.insert_item(QueryItem::RangeInclusive(vec![4]..=vec![14]));
+        assert_proof_matches_query(&db, &things_query(range, Some(3), Some(6)));
+    }
+
+    #[test]
+    fn test_offset_proofs_skip_results_of_subqueries() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_things(grove_version);
+
+        // Skipping all of `g0` takes nothing from the limit.
+        let (values, _) = db
+            .query_item_value(
+                &groups_query(Some(5), Some(5)),
+                true,
+                true,
+                true,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot run the query");
+        assert_eq!(values, (0..5).map(|n| vec![1, n]).collect::<Vec<_>>());
+
+        for (limit, offset) in [
+            (Some(5), Some(5)),
+            (Some(4), Some(3)),
+            (Some(4), Some(7)),
+            (Some(6), Some(12)),
+            (None, Some(18)),
+            (Some(2), Some(30)),
+        ] {
+            assert_proof_matches_query(&db, &groups_query(limit, offset));
+        }
+    }
+
+    #[test]
+    fn test_offsets_only_skip_found_subquery_path_keys() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_things(grove_version);
+        // Item `2` is missing in `g1` and `g4`.
+        db.delete(
+            [TEST_LEAF, b"groups", b"g1"].as_ref(),
+            &[2],
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot delete an item");
+        db.insert(
+            [TEST_LEAF, b"groups"].as_ref(),
+            b"g4",
+            Element::empty_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a group");
+
+        let path_query = |limit, offset| {
+            let mut query = Query::new_range_full();
+            query.set_subquery_path(vec![vec![2]]);
+            PathQuery::new(
+                vec![TEST_LEAF.to_vec(), b"groups".to_vec()],
+                SizedQuery::new(query, limit, offset),
+            )
+        };
+
+        let (values, _) = db
+            .query_item_value(
+                &path_query(None, Some(1)),
+                true,
+                true,
+                true,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot run the query");
+        assert_eq!(values, vec![vec![2, 2], vec![3, 2]]);
+        // The missing item of `g1` takes one from the limit, the skipped one
+        // of `g0` does not.
+        let (values, _) = db
+            .query_item_value(
+                &path_query(Some(2), Some(1)),
+                true,
+                true,
+                true,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot run the query");
+        assert_eq!(values, vec![vec![2, 2]]);
+
+        for (limit, offset) in [
+            (None, Some(1)),
+            (None, Some(2)),
+            (Some(2), Some(1)),
+            (Some(3), Some(1)),
+            (Some(1), Some(3)),
+            (Some(5), Some(4)),
+        ] {
+            assert_proof_matches_query(&db, &path_query(limit, offset));
+        }
+    }
+
+    #[test]
+    fn test_subquery_path_keys_are_taken_whether_found_or_not_before_grove_v4() {
+        let grove_version = &GROVE_V3;
+        let db = make_db_with_things(grove_version);
+        db.delete(
+            [TEST_LEAF, b"groups", b"g1"].as_ref(),
+            &[2],
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot delete an item");
+
+        let path_query = |limit, offset| {
+            let mut query = Query::new_range_full();
+            query.set_subquery_path(vec![vec![2]]);
+            PathQuery::new(
+                vec![TEST_LEAF.to_vec(), b"groups".to_vec()],
+                SizedQuery::new(query, limit, offset),
+            )
+        };
+        let query_values = |path_query: &PathQuery, decrease_limit| {
+            let (values, _) = db
+                .query_item_value(path_query, true, decrease_limit, true, None, grove_version)
+                .unwrap()
+                .expect("cannot run the query");
+            let iterated: Vec<_> = db
+                .query_iter(path_query, None, grove_version)
+                .map(|result| {
+                    let (_, _, element) = result.expect("cannot iterate the query");
+                    element
+                })
+                .collect();
+            if decrease_limit {
+                let elements: Vec<_> = values.iter().cloned().map(Element::new_item).collect();
+                assert_eq!(iterated, elements);
+            }
+            values
+        };
+
+        // The missing item of `g1` takes one from the limit even when ranges
+        // without sub elements don't
+        assert_eq!(
+            query_values(&path_query(Some(2), None), false),
+            vec![vec![0, 2]]
+        );
+        // and one from the offset
+        assert_eq!(
+            query_values(&path_query(None, Some(2)), true),
+            vec![vec![2, 2], vec![3, 2]]
+        );
+
+        assert!(matches!(
+            db.prove_query(&path_query(None, Some(1)), None, grove_version)
+                .unwrap(),
+            Err(Error::InvalidQuery(_))
+        ));
+    }
+
+    #[test]
+    fn test_offset_proofs_leave_out_the_skipped_values() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_things(grove_version);
+
+        let path_query = things_query(Query::new_range_full(), Some(2), Some(10));
+        let proof = db
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("cannot prove the query");
+        let unskipped_query = things_query(Query::new_range_full(), Some(12), None);
+        let unskipped_proof = db
+            .prove_query(&unskipped_query, None, grove_version)
+            .unwrap()
+            .expect("cannot prove the query");
+        // Each skipped item is proved by a 32 byte value hash instead of its
+        // 200 byte value.
+        assert!(proof.len() + 10 * 150 <= unskipped_proof.len());
+
+        // The proof only verifies the offset it was made for.
+        for other_offset in [Some(9), Some(11), None] {
+            let other_query = things_query(Query::new_range_full(), Some(2), other_offset);
+            assert!(GroveDb::verify_query(&proof, &other_query, grove_version).is_err());
+        }
+    }
+
+    #[test]
+    fn test_offsets_are_not_supported_by_v0_proofs() {
+        let grove_version = &GROVE_V2;
+        let db = make_db_with_things(grove_version);
+
+        let path_query = things_query(Query::new_range_full(), Some(2), Some(10));
+        assert!(db
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .is_err());
+
+        // A V0 proof of the query without its offset is not accepted either.
+        let unskipped_query = things_query(Query::new_range_full(), Some(12), None);
+        let proof = db
+            .prove_query(&unskipped_query, None, grove_version)
+            .unwrap()
+            .expect("cannot prove the query");
+        assert!(GroveDb::verify_query(&proof, &path_query, grove_version).is_err());
+    }
+}
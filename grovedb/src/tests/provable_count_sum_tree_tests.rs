@@ -78,7 +78,13 @@ mod tests {
                 Node::KVDigestCount(k, ..) => k.clone(),
                 Node::KVRefValueHash(k, ..) => k.clone(),
                 Node::KVRefValueHashCount(k, ..) => k.clone(),
-                Node::KVHashCount(..) => vec![],
+                Node::KVMinMax(k, ..) => k.clone(),
+                Node::KVDigestMinMax(k, ..) => k.clone(),
+                Node::KVRefValueHashMinMax(k, ..) => k.clone(),
+                Node::KVSum(k, ..) => k.clone(),
+                Node::KVDigestSum(k, ..) => k.clone(),
+                Node::KVRefValueHashSum(k, ..) => k.clone(),
+                Node::KVHashCount(..) | Node::KVHashMinMax(..) | Node::KVHashSum(..) => vec![],
                 Node::Hash(_) | Node::KVHash(_) => vec![],
             };
             results.push((key, count));
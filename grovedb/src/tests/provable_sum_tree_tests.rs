@@ -0,0 +1,381 @@
+//! ProvableSumTree tests
+//!
+//! Provable sum trees hash the aggregate sum of every node's subtree into the
+//! node hash, so the total of a key range can be proven from the nodes
+//! bounding the range instead of from every sum item in it.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::{encode_into, query::QueryItem, Decoder, Node, Op, Query};
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        batch::QualifiedGroveDbOp,
+        operations::proof::{GroveDBProof, ProofBytes},
+        query_result_type::QueryResultType,
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, Error, GroveDb, PathQuery,
+    };
+
+    fn balances_path() -> Vec<Vec<u8>> {
+        vec![TEST_LEAF.to_vec(), b"balances".to_vec()]
+    }
+
+    fn key(n: u16) -> Vec<u8> {
+        n.to_be_bytes().to_vec()
+    }
+
+    /// Makes `TEST_LEAF/balances`, a provable sum tree holding sum items
+    /// keyed by the big-endian encodings of the even numbers below 400, each
+    /// worth its key minus 150, and an empty provable sum tree
+    /// `TEST_LEAF/empty`.
+    fn make_db_with_balances(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        for tree_key in [b"balances".as_slice(), b"empty"] {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                tree_key,
+                Element::empty_provable_sum_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a provable sum tree");
+        }
+        for n in (0u16..400).step_by(2) {
+            db.insert(
+                [TEST_LEAF, b"balances"].as_ref(),
+                &key(n),
+                Element::new_sum_item(n as i64 - 150),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a sum item");
+        }
+        db
+    }
+
+    fn get_tree(db: &TempGroveDb, tree_key: &[u8]) -> Element {
+        db.get([TEST_LEAF].as_ref(), tree_key, None, GroveVersion::latest())
+            .unwrap()
+            .expect("cannot get the tree")
+    }
+
+    fn ranges() -> Vec<QueryItem> {
+        vec![
+            QueryItem::RangeFull(..),
+            QueryItem::Key(key(100)),
+            QueryItem::Key(key(101)),
+            QueryItem::Range(key(10)..key(20)),
+            QueryItem::Range(key(11)..key(21)),
+            QueryItem::RangeInclusive(key(0)..=key(398)),
+            QueryItem::RangeFrom(key(333)..),
+            QueryItem::RangeTo(..key(57)),
+            QueryItem::RangeToInclusive(..=key(56)),
+            QueryItem::RangeAfter(key(200)..),
+            QueryItem::RangeAfterTo(key(200)..key(300)),
+            QueryItem::RangeAfterToInclusive(key(199)..=key(300)),
+            QueryItem::Range(key(500)..key(600)),
+        ]
+    }
+
+    #[test]
+    fn test_provable_sum_tree_tracks_sum_on_insert_and_delete() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_balances(grove_version);
+        let expected: i64 = (0i64..400).step_by(2).map(|n| n - 150).sum();
+        let balances = get_tree(&db, b"balances");
+        assert!(balances.is_provable_sum_tree());
+        assert_eq!(balances.sum_value_or_default(), expected);
+
+        // Items without a sum don't move it
+        db.insert(
+            [TEST_LEAF, b"balances"].as_ref(),
+            b"note",
+            Element::new_item(b"memo".to_vec()),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a plain item");
+        assert_eq!(get_tree(&db, b"balances").sum_value_or_default(), expected);
+
+        db.delete(
+            [TEST_LEAF, b"balances"].as_ref(),
+            &key(398),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot delete a sum item");
+        assert_eq!(
+            get_tree(&db, b"balances").sum_value_or_default(),
+            expected - 248
+        );
+
+        db.insert(
+            [TEST_LEAF, b"balances"].as_ref(),
+            &key(0),
+            Element::new_sum_item(1000),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot replace a sum item");
+        assert_eq!(
+            get_tree(&db, b"balances").sum_value_or_default(),
+            expected - 248 + 1150
+        );
+    }
+
+    #[test]
+    fn test_provable_big_sum_tree_with_batch() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+
+        let ops = vec![
+            QualifiedGroveDbOp::insert_or_replace_op(
+                vec![TEST_LEAF.to_vec()],
+                b"supply".to_vec(),
+                Element::empty_provable_big_sum_tree(),
+            ),
+            QualifiedGroveDbOp::insert_or_replace_op(
+                vec![TEST_LEAF.to_vec(), b"supply".to_vec()],
+                b"a".to_vec(),
+                Element::new_sum_item(i64::MAX),
+            ),
+            QualifiedGroveDbOp::insert_or_replace_op(
+                vec![TEST_LEAF.to_vec(), b"supply".to_vec()],
+                b"b".to_vec(),
+                Element::new_sum_item(i64::MAX),
+            ),
+            QualifiedGroveDbOp::insert_or_replace_op(
+                vec![TEST_LEAF.to_vec(), b"supply".to_vec()],
+                b"c".to_vec(),
+                Element::new_sum_item(-5),
+            ),
+        ];
+        db.apply_batch(ops, None, None, grove_version)
+            .unwrap()
+            .expect("cannot apply the batch");
+
+        let supply = get_tree(&db, b"supply");
+        assert!(supply.is_provable_sum_tree());
+        let expected = 2 * i64::MAX as i128 - 5;
+        assert_eq!(supply.big_sum_value_or_default(), expected);
+
+        let path = vec![TEST_LEAF.to_vec(), b"supply".to_vec()];
+        let proof = db
+            .prove_sum_in_range(path.clone(), QueryItem::RangeFull(..), grove_version)
+            .unwrap()
+            .expect("cannot prove the range sum");
+        let (root_hash, sum) =
+            GroveDb::verify_sum_in_range(&proof, path, &QueryItem::RangeFull(..), grove_version)
+                .expect("cannot verify the range sum proof");
+        assert_eq!(
+            root_hash,
+            db.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        assert_eq!(sum, expected);
+    }
+
+    #[test]
+    fn test_provable_sum_tree_proves_its_sum_from_parent() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_balances(grove_version);
+
+        let mut query = Query::new();
+        query.insert_key(b"balances".to_vec());
+        let path_query = PathQuery::new_unsized(vec![TEST_LEAF.to_vec()], query);
+        let proof = db
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("cannot generate the proof");
+        let (root_hash, proved_values) = GroveDb::verify_query(&proof, &path_query, grove_version)
+            .expect("cannot verify the proof");
+        assert_eq!(
+            root_hash,
+            db.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        assert_eq!(proved_values.len(), 1);
+        let element = proved_values[0].2.as_ref().expect("tree should be proven");
+        assert_eq!(
+            element.sum_value_or_default(),
+            get_tree(&db, b"balances").sum_value_or_default()
+        );
+    }
+
+    #[test]
+    fn test_range_sum_proofs_match_the_items_in_range() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_balances(grove_version);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        for range in ranges() {
+            let expected: i128 = db
+                .query_raw(
+                    &PathQuery::new_single_query_item(balances_path(), range.clone()),
+                    true,
+                    true,
+                    true,
+                    QueryResultType::QueryElementResultType,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("cannot query the range")
+                .0
+                .to_elements()
+                .iter()
+                .map(|element| element.sum_value_or_default() as i128)
+                .sum();
+
+            let proof = db
+                .prove_sum_in_range(balances_path(), range.clone(), grove_version)
+                .unwrap()
+                .expect("cannot prove the range sum");
+            let (proved_root_hash, sum) =
+                GroveDb::verify_sum_in_range(&proof, balances_path(), &range, grove_version)
+                    .expect("cannot verify the range sum proof");
+            assert_eq!(proved_root_hash, root_hash, "root hash for {}", range);
+            assert_eq!(sum, expected, "sum for {}", range);
+        }
+    }
+
+    #[test]
+    fn test_range_sum_proof_is_smaller_than_the_range() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_balances(grove_version);
+
+        let range = QueryItem::Range(key(11)..key(389));
+        let proof = db
+            .prove_sum_in_range(balances_path(), range.clone(), grove_version)
+            .unwrap()
+            .expect("cannot prove the range sum");
+        let mut query = Query::new();
+        query.insert_item(range.clone());
+        let full_proof = db
+            .prove_query(
+                &PathQuery::new_unsized(balances_path(), query),
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot prove the range");
+        assert!(proof.len() * 2 < full_proof.len());
+
+        let (_, sum) = GroveDb::verify_sum_in_range(&proof, balances_path(), &range, grove_version)
+            .expect("cannot verify the range sum proof");
+        assert_eq!(sum, (12i128..389).step_by(2).map(|n| n - 150).sum());
+    }
+
+    #[test]
+    fn test_tampered_range_sum_proof_is_rejected() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_balances(grove_version);
+
+        let range = QueryItem::Range(key(11)..key(389));
+        let proof = db
+            .prove_sum_in_range(balances_path(), range.clone(), grove_version)
+            .unwrap()
+            .expect("cannot prove the range sum");
+
+        let config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+        let mut grovedb_proof: GroveDBProof = bincode::decode_from_slice(&proof, config)
+            .expect("cannot decode the proof")
+            .0;
+        let GroveDBProof::V1(ref mut proof_v1) = grovedb_proof else {
+            panic!("expected a V1 proof");
+        };
+        let balances_layer = proof_v1
+            .root_layer
+            .lower_layers
+            .get_mut(TEST_LEAF)
+            .and_then(|layer| layer.lower_layers.get_mut(b"balances".as_slice()))
+            .expect("expected the balances layer");
+        let ProofBytes::Merk(merk_proof) = &mut balances_layer.merk_proof else {
+            panic!("expected a merk proof");
+        };
+
+        // Inflate the sum claimed for one of the subtrees inside the range
+        let mut ops: Vec<Op> = Decoder::new(merk_proof)
+            .map(|op| op.expect("cannot decode op"))
+            .collect();
+        let tampered = ops.iter_mut().any(|op| match op {
+            Op::Push(Node::KVHashSum(_, sum)) | Op::PushInverted(Node::KVHashSum(_, sum)) => {
+                *sum += 1_000_000;
+                true
+            }
+            _ => false,
+        });
+        assert!(tampered, "expected a subtree sum in the proof");
+        let mut tampered_merk_proof = Vec::new();
+        encode_into(ops.iter(), &mut tampered_merk_proof);
+        *merk_proof = tampered_merk_proof;
+
+        let tampered_proof =
+            bincode::encode_to_vec(&grovedb_proof, config).expect("cannot encode the proof");
+        assert!(GroveDb::verify_sum_in_range(
+            &tampered_proof,
+            balances_path(),
+            &range,
+            grove_version
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_range_sum_proofs_of_empty_and_other_trees() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_balances(grove_version);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        let empty_path = vec![TEST_LEAF.to_vec(), b"empty".to_vec()];
+        let proof = db
+            .prove_sum_in_range(empty_path.clone(), QueryItem::RangeFull(..), grove_version)
+            .unwrap()
+            .expect("cannot prove the sum of an empty tree");
+        let (proved_root_hash, sum) = GroveDb::verify_sum_in_range(
+            &proof,
+            empty_path,
+            &QueryItem::RangeFull(..),
+            grove_version,
+        )
+        .expect("cannot verify the range sum proof");
+        assert_eq!(proved_root_hash, root_hash);
+        assert_eq!(sum, 0);
+
+        // Plain sum trees do not commit to their sums.
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"plain",
+            Element::empty_sum_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a sum tree");
+        let result = db
+            .prove_sum_in_range(
+                vec![TEST_LEAF.to_vec(), b"plain".to_vec()],
+                QueryItem::RangeFull(..),
+                grove_version,
+            )
+            .unwrap();
+        assert!(matches!(
+            result,
+            Err(Error::InvalidInput(
+                "range sums can only be proved in provable sum trees"
+            ))
+        ));
+    }
+}
@@ -0,0 +1,212 @@
+//! Tests for lazily iterated path queries.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::{query::QueryItem, Query};
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        query_result_type::{PathKeyElementTrio, QueryResultType},
+        reference_path::ReferencePathType,
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, PathQuery, SizedQuery,
+    };
+
+    /// Makes `TEST_LEAF/groups` holding the trees `g0` to `g3`: every group
+    /// holds a tree `items` of 150 items, a tree `empty` and an item `name`.
+    /// `g1/items` also holds a reference to its first item.
+    fn make_db_with_groups(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"groups",
+            Element::empty_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a tree");
+        for group in 0u8..4 {
+            let group_key = format!("g{}", group).into_bytes();
+            db.insert(
+                [TEST_LEAF, b"groups"].as_ref(),
+                &group_key,
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a group");
+            let group_path = [TEST_LEAF, b"groups", group_key.as_slice()];
+            for (key, element) in [
+                (b"items".as_slice(), Element::empty_tree()),
+                (b"empty", Element::empty_tree()),
+                (b"name", Element::new_item(group_key.clone())),
+            ] {
+                db.insert(group_path.as_ref(), key, element, None, None, grove_version)
+                    .unwrap()
+                    .expect("cannot insert into a group");
+            }
+            for n in 0u8..150 {
+                db.insert(
+                    [TEST_LEAF, b"groups", group_key.as_slice(), b"items"].as_ref(),
+                    &[n],
+                    Element::new_item(vec![group, n]),
+                    None,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("cannot insert an item");
+            }
+        }
+        db.insert(
+            [TEST_LEAF, b"groups", b"g1", b"items"].as_ref(),
+            b"ref",
+            Element::new_reference(ReferencePathType::SiblingReference(vec![0])),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a reference");
+        db
+    }
+
+    fn groups_query(query: Query, limit: Option<u16>, offset: Option<u16>) -> PathQuery {
+        PathQuery::new(
+            vec![TEST_LEAF.to_vec(), b"groups".to_vec()],
+            SizedQuery::new(query, limit, offset),
+        )
+    }
+
+    /// Checks that iterating `path_query` yields the results of `query_raw`.
+    fn assert_iter_matches_query(db: &TempGroveDb, path_query: &PathQuery) {
+        let grove_version = GroveVersion::latest();
+        let (expected, _) = db
+            .query_raw(
+                path_query,
+                true,
+                true,
+                true,
+                QueryResultType::QueryPathKeyElementTrioResultType,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot run the query");
+        let results = db
+            .query_iter(path_query, None, grove_version)
+            .collect::<Result<Vec<PathKeyElementTrio>, _>>()
+            .expect("cannot iterate the query");
+        assert_eq!(results, expected.to_path_key_elements(), "{}", path_query);
+    }
+
+    /// Query of the `items` of every group, and their `name` in the groups
+    /// from `g2` on.
+    fn items_and_names_query(left_to_right: bool) -> Query {
+        let mut items = Query::new_with_direction(left_to_right);
+        items.insert_all();
+        let mut query = Query::new_with_direction(left_to_right);
+        query.insert_all();
+        query.set_subquery_key(b"items".to_vec());
+        query.set_subquery(items);
+
+        let mut items_and_name = Query::new_with_direction(left_to_right);
+        items_and_name.insert_key(b"name".to_vec());
+        items_and_name.insert_key(b"items".to_vec());
+        let mut items = Query::new_with_direction(left_to_right);
+        items.insert_range_to(..vec![10]);
+        items_and_name.set_subquery(items);
+        query.add_conditional_subquery(
+            QueryItem::RangeFrom(b"g2".to_vec()..),
+            None,
+            Some(items_and_name),
+        );
+        query
+    }
+
+    #[test]
+    fn test_query_iter_matches_query_raw() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_groups(grove_version);
+
+        for left_to_right in [true, false] {
+            for (limit, offset) in [
+                (None, None),
+                (Some(1), None),
+                (Some(70), None),
+                (Some(200), Some(100)),
+                (None, Some(155)),
+                (Some(5), Some(1000)),
+            ] {
+                let query = items_and_names_query(left_to_right);
+                assert_iter_matches_query(&db, &groups_query(query, limit, offset));
+            }
+        }
+
+        // Subquery paths without subqueries, and subqueries of empty trees
+        let mut names = Query::new_range_full();
+        names.set_subquery_key(b"name".to_vec());
+        assert_iter_matches_query(&db, &groups_query(names, Some(3), Some(1)));
+        let mut empty = Query::new();
+        empty.insert_keys(vec![b"g0".to_vec(), b"g3".to_vec(), b"g9".to_vec()]);
+        empty.set_subquery_key(b"empty".to_vec());
+        empty.set_subquery(Query::new_range_full());
+        assert_iter_matches_query(&db, &groups_query(empty, Some(1), None));
+
+        // Trees are results when there is no subquery for them
+        let mut groups = Query::new();
+        groups.insert_range_after(b"g1".to_vec()..);
+        assert_iter_matches_query(&db, &groups_query(groups, None, None));
+    }
+
+    #[test]
+    fn test_query_iter_is_lazy() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_groups(grove_version);
+        let path_query = groups_query(items_and_names_query(true), None, None);
+
+        let mut iter = db.query_iter(&path_query, None, grove_version);
+        let (path, key, element) = iter
+            .next()
+            .expect("the query has results")
+            .expect("cannot read the first result");
+        assert_eq!(
+            path,
+            vec![
+                TEST_LEAF.to_vec(),
+                b"groups".to_vec(),
+                b"g0".to_vec(),
+                b"items".to_vec()
+            ]
+        );
+        assert_eq!(key, vec![0]);
+        assert_eq!(element, Element::new_item(vec![0, 0]));
+        let first_cost = iter.cost().clone();
+        assert!(first_cost.seek_count > 0);
+
+        // Later results are only read when they are asked for
+        assert_eq!(iter.by_ref().take(299).count(), 299);
+        assert!(iter.cost().seek_count > first_cost.seek_count);
+        assert!(iter.cost().storage_loaded_bytes > first_cost.storage_loaded_bytes);
+        // The reference in g1, then the ten items and the name of g2 and g3
+        assert_eq!(iter.count(), 1 + 2 * 11);
+    }
+
+    #[test]
+    fn test_query_iter_stops_at_the_first_error() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_groups(grove_version);
+
+        // The items are not trees the subquery can apply to
+        let mut query = Query::new_range_full();
+        query.set_subquery_path(vec![b"items".to_vec(), vec![0]]);
+        query.set_subquery(Query::new_single_key(vec![0]));
+        let mut iter = db.query_iter(&groups_query(query, None, None), None, grove_version);
+        assert!(matches!(iter.next(), Some(Err(_))));
+        assert!(iter.next().is_none());
+    }
+}
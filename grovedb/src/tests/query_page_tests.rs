@@ -0,0 +1,234 @@
+//! Tests for paging through path query results with cursors.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::{query::QueryItem, Query};
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, GroveDb, PathQuery, QueryCursor,
+    };
+
+    /// Makes trees `a`, `b` and `c` under `TEST_LEAF`, each holding items
+    /// `1` to `4`.
+    fn make_db_with_nested_items(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        for tree in [b"a", b"b", b"c"] {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                tree,
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a tree");
+            for item in [b"1", b"2", b"3", b"4"] {
+                db.insert(
+                    [TEST_LEAF, tree].as_ref(),
+                    item,
+                    Element::new_item([tree.as_slice(), item].concat()),
+                    None,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("cannot insert an item");
+            }
+        }
+        db
+    }
+
+    /// Every item of every tree, except for tree `b` of which only items
+    /// from `2` on.
+    fn nested_query(left_to_right: bool) -> PathQuery {
+        let mut subquery = Query::new_range_full();
+        subquery.left_to_right = left_to_right;
+        let mut query = Query::new_range_full();
+        query.left_to_right = left_to_right;
+        query.set_subquery(subquery.clone());
+        let mut b_subquery = Query::new_single_query_item(QueryItem::RangeFrom(b"2".to_vec()..));
+        b_subquery.left_to_right = left_to_right;
+        query.add_conditional_subquery(QueryItem::Key(b"b".to_vec()), None, Some(b_subquery));
+        PathQuery::new_unsized(vec![TEST_LEAF.to_vec()], query)
+    }
+
+    fn all_values(
+        db: &GroveDb,
+        path_query: &PathQuery,
+        grove_version: &GroveVersion,
+    ) -> Vec<Vec<u8>> {
+        let (elements, _) = db
+            .query_item_value(path_query, true, true, true, None, grove_version)
+            .unwrap()
+            .expect("cannot query all results");
+        elements
+    }
+
+    fn item_value(element: &Element) -> Vec<u8> {
+        match element {
+            Element::Item(value, _) => value.clone(),
+            _ => panic!("expected an item"),
+        }
+    }
+
+    #[test]
+    fn test_pages_cover_the_query_results_in_order() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_nested_items(grove_version);
+
+        for left_to_right in [true, false] {
+            let path_query = nested_query(left_to_right);
+            let expected = all_values(&db, &path_query, grove_version);
+            assert_eq!(expected.len(), 11);
+
+            let mut paged = Vec::new();
+            let mut cursor = None;
+            loop {
+                let (results, next_cursor) = db
+                    .query_page(&path_query, cursor.as_ref(), 3, None, grove_version)
+                    .unwrap()
+                    .expect("cannot query a page");
+                assert!(results.len() <= 3);
+                paged.extend(results.iter().map(|(_, _, element)| item_value(element)));
+                match next_cursor {
+                    Some(next_cursor) => {
+                        let bytes = next_cursor.to_bytes().expect("cannot encode the cursor");
+                        cursor = Some(
+                            QueryCursor::from_bytes(&bytes).expect("cannot decode the cursor"),
+                        );
+                    }
+                    None => break,
+                }
+            }
+            assert_eq!(paged, expected);
+        }
+    }
+
+    #[test]
+    fn test_proved_pages_verify_with_the_same_cursor() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_nested_items(grove_version);
+        let path_query = nested_query(true);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        let mut paged = Vec::new();
+        let mut cursor = None;
+        loop {
+            let proof = db
+                .prove_query_page(&path_query, cursor.as_ref(), 4, grove_version)
+                .unwrap()
+                .expect("cannot prove a page");
+            let (proved_root_hash, results, next_cursor) =
+                GroveDb::verify_query_page(&proof, &path_query, cursor.as_ref(), 4, grove_version)
+                    .expect("cannot verify a page");
+            assert_eq!(proved_root_hash, root_hash);
+
+            let (queried, queried_cursor) = db
+                .query_page(&path_query, cursor.as_ref(), 4, None, grove_version)
+                .unwrap()
+                .expect("cannot query a page");
+            assert_eq!(next_cursor, queried_cursor);
+            assert_eq!(
+                results
+                    .iter()
+                    .map(|(path, key, element)| (path.clone(), key.clone(), element.clone()))
+                    .collect::<Vec<_>>(),
+                queried
+                    .into_iter()
+                    .map(|(path, key, element)| (path, key, Some(element)))
+                    .collect::<Vec<_>>()
+            );
+
+            paged.extend(
+                results
+                    .iter()
+                    .map(|(_, _, element)| item_value(element.as_ref().expect("expected an item"))),
+            );
+            match next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+        assert_eq!(paged, all_values(&db, &path_query, grove_version));
+
+        // A proof only verifies with the cursor it was made for.
+        let first_page = db
+            .prove_query_page(&path_query, None, 4, grove_version)
+            .unwrap()
+            .expect("cannot prove the first page");
+        assert!(GroveDb::verify_query_page(
+            &first_page,
+            &path_query,
+            cursor.as_ref(),
+            4,
+            grove_version
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_empty_subtrees_do_not_shorten_pages() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_nested_items(grove_version);
+        for tree in [b"a0", b"a1", b"a2", b"a3", b"a4"] {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                tree,
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a tree");
+        }
+        let path_query = nested_query(true);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        let mut page_sizes = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (results, next_cursor) = db
+                .query_page(&path_query, cursor.as_ref(), 4, None, grove_version)
+                .unwrap()
+                .expect("cannot query a page");
+            let proof = db
+                .prove_query_page(&path_query, cursor.as_ref(), 4, grove_version)
+                .unwrap()
+                .expect("cannot prove a page");
+            let (proved_root_hash, proved, proved_cursor) =
+                GroveDb::verify_query_page(&proof, &path_query, cursor.as_ref(), 4, grove_version)
+                    .expect("cannot verify a page");
+            assert_eq!(proved_root_hash, root_hash);
+            assert_eq!(proved.len(), results.len());
+            assert_eq!(proved_cursor, next_cursor);
+
+            page_sizes.push(results.len());
+            match next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+        // The empty trees after `a` don't count, and the last page is known
+        // to be the last one as it isn't full.
+        assert_eq!(page_sizes, vec![4, 4, 3]);
+    }
+
+    #[test]
+    fn test_paged_queries_can_not_be_sized() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_nested_items(grove_version);
+        let mut path_query = nested_query(true);
+        path_query.query.limit = Some(5);
+
+        assert!(db
+            .query_page(&path_query, None, 3, None, grove_version)
+            .unwrap()
+            .is_err());
+        assert!(nested_query(true).page(None, 0).is_err());
+        assert!(QueryCursor::from_bytes(&[0xff, 0xff]).is_err());
+    }
+}
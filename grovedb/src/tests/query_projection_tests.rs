@@ -0,0 +1,212 @@
+//! Tests for projected queries and key proofs.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::{
+        proofs::{query::QueryItem, Query},
+        tree::value_hash,
+    };
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        element::ElementType,
+        query_result_type::{ProjectedResult, QueryProjection},
+        reference_path::ReferencePathType,
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, GroveDb, PathQuery, SizedQuery,
+    };
+
+    /// Makes `TEST_LEAF/things` holding items `a` to `e`, an empty tree `f`
+    /// and a reference `g` to item `a`, and an empty tree `TEST_LEAF/none`.
+    fn make_db_with_things(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        for key in [b"things".as_slice(), b"none"] {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                key,
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a tree");
+        }
+        let mut elements: Vec<(&[u8], Element)> = [b"a", b"b", b"c", b"d", b"e"]
+            .into_iter()
+            .map(|key| (key.as_slice(), Element::new_item(key.repeat(100))))
+            .collect();
+        elements.push((b"f", Element::empty_tree()));
+        elements.push((
+            b"g",
+            Element::new_reference(ReferencePathType::SiblingReference(b"a".to_vec())),
+        ));
+        for (key, element) in elements {
+            db.insert(
+                [TEST_LEAF, b"things"].as_ref(),
+                key,
+                element,
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert an element");
+        }
+        db
+    }
+
+    fn things_query(query: Query, limit: Option<u16>) -> PathQuery {
+        PathQuery::new(
+            vec![TEST_LEAF.to_vec(), b"things".to_vec()],
+            SizedQuery::new(query, limit, None),
+        )
+    }
+
+    fn b_to_g() -> Query {
+        let mut query = Query::new();
+        query.insert_item(QueryItem::RangeInclusive(b"b".to_vec()..=b"g".to_vec()));
+        query
+    }
+
+    #[test]
+    fn test_projections_read_the_parts_asked_for() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_things(grove_version);
+
+        let mut path_query = things_query(b_to_g(), Some(5));
+        path_query.query.offset = Some(1);
+        let keys = db
+            .query_projected(&path_query, QueryProjection::Keys, None, grove_version)
+            .unwrap()
+            .expect("cannot query keys");
+        assert_eq!(
+            keys,
+            [b"c", b"d", b"e", b"f", b"g"]
+                .map(|key| ProjectedResult::Key(key.to_vec()))
+                .to_vec()
+        );
+
+        let mut descending = b_to_g();
+        descending.left_to_right = false;
+        let types = db
+            .query_projected(
+                &things_query(descending, Some(3)),
+                QueryProjection::ElementTypes,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot query element types");
+        assert_eq!(
+            types,
+            vec![
+                ProjectedResult::KeyElementType(b"g".to_vec(), ElementType::Reference),
+                ProjectedResult::KeyElementType(b"f".to_vec(), ElementType::Tree),
+                ProjectedResult::KeyElementType(b"e".to_vec(), ElementType::Item),
+            ]
+        );
+
+        let value_hashes = db
+            .query_projected(
+                &things_query(Query::new_single_key(b"c".to_vec()), None),
+                QueryProjection::ValueHashes,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot query value hashes");
+        let item = Element::new_item(b"c".repeat(100))
+            .serialize(grove_version)
+            .expect("cannot serialize the item");
+        assert_eq!(
+            value_hashes,
+            vec![ProjectedResult::KeyValueHash(
+                b"c".to_vec(),
+                value_hash(&item).unwrap()
+            )]
+        );
+
+        let mut with_subquery = Query::new_range_full();
+        with_subquery.set_subquery(Query::new_range_full());
+        assert!(db
+            .query_projected(
+                &things_query(with_subquery, None),
+                QueryProjection::Keys,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn test_keys_proof_proves_keys_and_value_hashes_without_values() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_things(grove_version);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        let path_query = things_query(b_to_g(), Some(4));
+        let keys_proof = db
+            .prove_query_keys(&path_query, grove_version)
+            .unwrap()
+            .expect("cannot prove the keys");
+        let (proved_root_hash, keys) =
+            GroveDb::verify_query_keys(&keys_proof, &path_query, grove_version)
+                .expect("cannot verify the keys proof");
+        assert_eq!(proved_root_hash, root_hash);
+
+        let expected: Vec<_> = db
+            .query_projected(
+                &path_query,
+                QueryProjection::ValueHashes,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot query value hashes")
+            .into_iter()
+            .map(|result| match result {
+                ProjectedResult::KeyValueHash(key, hash) => (key, hash),
+                _ => panic!("expected a value hash"),
+            })
+            .collect();
+        assert_eq!(keys, expected);
+        assert_eq!(keys.len(), 4);
+
+        let full_proof = db
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("cannot prove the query");
+        assert!(keys_proof.len() < full_proof.len());
+
+        // The proof only verifies the query it was made for.
+        let other_query = things_query(b_to_g(), Some(5));
+        assert!(GroveDb::verify_query_keys(&keys_proof, &other_query, grove_version).is_err());
+    }
+
+    #[test]
+    fn test_keys_proof_of_an_empty_tree() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_things(grove_version);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        let empty_query = PathQuery::new_unsized(
+            vec![TEST_LEAF.to_vec(), b"none".to_vec()],
+            Query::new_range_full(),
+        );
+        let proof = db
+            .prove_query_keys(&empty_query, grove_version)
+            .unwrap()
+            .expect("cannot prove the keys of an empty tree");
+        let (proved_root_hash, keys) =
+            GroveDb::verify_query_keys(&proof, &empty_query, grove_version)
+                .expect("cannot verify the keys proof");
+        assert_eq!(proved_root_hash, root_hash);
+        assert!(keys.is_empty());
+
+        // The empty proof of a tree with keys fails the hash chain.
+        let things_query = things_query(Query::new_range_full(), None);
+        assert!(GroveDb::verify_query_keys(&proof, &things_query, grove_version).is_err());
+    }
+}
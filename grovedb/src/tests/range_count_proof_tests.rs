@@ -0,0 +1,184 @@
+//! Tests for proofs of the number of keys in a range of a provable count tree.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::{query::QueryItem, Query};
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        query_result_type::QueryResultType,
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, GroveDb, PathQuery,
+    };
+
+    fn counted_path() -> Vec<Vec<u8>> {
+        vec![TEST_LEAF.to_vec(), b"counted".to_vec()]
+    }
+
+    /// Makes `TEST_LEAF/counted`, a provable count tree holding items keyed
+    /// by the big-endian encodings of the even numbers below 400, and an
+    /// empty provable count tree `TEST_LEAF/empty`.
+    fn make_db_with_counted_items(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        for key in [b"counted".as_slice(), b"empty"] {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                key,
+                Element::empty_provable_count_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a provable count tree");
+        }
+        for n in (0u16..400).step_by(2) {
+            db.insert(
+                [TEST_LEAF, b"counted"].as_ref(),
+                &n.to_be_bytes(),
+                Element::new_item(vec![1; 32]),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert an item");
+        }
+        db
+    }
+
+    fn key(n: u16) -> Vec<u8> {
+        n.to_be_bytes().to_vec()
+    }
+
+    fn ranges() -> Vec<QueryItem> {
+        vec![
+            QueryItem::RangeFull(..),
+            QueryItem::Key(key(100)),
+            QueryItem::Key(key(101)),
+            QueryItem::Range(key(10)..key(20)),
+            QueryItem::Range(key(11)..key(21)),
+            QueryItem::RangeInclusive(key(10)..=key(20)),
+            QueryItem::RangeInclusive(key(0)..=key(398)),
+            QueryItem::RangeFrom(key(333)..),
+            QueryItem::RangeTo(..key(57)),
+            QueryItem::RangeToInclusive(..=key(56)),
+            QueryItem::RangeAfter(key(200)..),
+            QueryItem::RangeAfterTo(key(200)..key(300)),
+            QueryItem::RangeAfterToInclusive(key(199)..=key(300)),
+            QueryItem::Range(key(500)..key(600)),
+        ]
+    }
+
+    #[test]
+    fn test_range_count_proofs_match_the_keys_in_range() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_counted_items(grove_version);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        for range in ranges() {
+            let expected = db
+                .query_raw(
+                    &PathQuery::new_single_query_item(counted_path(), range.clone()),
+                    true,
+                    true,
+                    true,
+                    QueryResultType::QueryKeyElementPairResultType,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("cannot query the range")
+                .0
+                .len() as u64;
+
+            let proof = db
+                .prove_count_in_range(counted_path(), range.clone(), grove_version)
+                .unwrap()
+                .expect("cannot prove the range count");
+            let (proved_root_hash, count) =
+                GroveDb::verify_count_in_range(&proof, counted_path(), &range, grove_version)
+                    .expect("cannot verify the range count proof");
+            assert_eq!(proved_root_hash, root_hash, "root hash for {}", range);
+            assert_eq!(count, expected, "count for {}", range);
+        }
+    }
+
+    #[test]
+    fn test_range_count_proof_is_smaller_than_the_range() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_counted_items(grove_version);
+
+        let range = QueryItem::Range(key(11)..key(389));
+        let proof = db
+            .prove_count_in_range(counted_path(), range.clone(), grove_version)
+            .unwrap()
+            .expect("cannot prove the range count");
+        let mut query = Query::new();
+        query.insert_item(range.clone());
+        let full_proof = db
+            .prove_query(
+                &PathQuery::new_unsized(counted_path(), query),
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot prove the range");
+        assert!(proof.len() * 4 < full_proof.len());
+
+        // The proof stops above the nodes another range needs.
+        let other_range = QueryItem::Range(key(11)..key(201));
+        assert!(GroveDb::verify_count_in_range(
+            &proof,
+            counted_path(),
+            &other_range,
+            grove_version
+        )
+        .is_err());
+        let (_, count) =
+            GroveDb::verify_count_in_range(&proof, counted_path(), &range, grove_version)
+                .expect("cannot verify the range count proof");
+        assert_eq!(count, 189);
+    }
+
+    #[test]
+    fn test_range_count_proofs_of_empty_and_other_trees() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_counted_items(grove_version);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        let empty_path = vec![TEST_LEAF.to_vec(), b"empty".to_vec()];
+        let proof = db
+            .prove_count_in_range(empty_path.clone(), QueryItem::RangeFull(..), grove_version)
+            .unwrap()
+            .expect("cannot prove the count of an empty tree");
+        let (proved_root_hash, count) = GroveDb::verify_count_in_range(
+            &proof,
+            empty_path,
+            &QueryItem::RangeFull(..),
+            grove_version,
+        )
+        .expect("cannot verify the range count proof");
+        assert_eq!(proved_root_hash, root_hash);
+        assert_eq!(count, 0);
+
+        // The empty proof of a tree with keys fails the hash chain.
+        assert!(GroveDb::verify_count_in_range(
+            &proof,
+            counted_path(),
+            &QueryItem::RangeFull(..),
+            grove_version
+        )
+        .is_err());
+
+        // Normal trees do not commit to their counts.
+        assert!(db
+            .prove_count_in_range(
+                vec![TEST_LEAF.to_vec()],
+                QueryItem::RangeFull(..),
+                grove_version
+            )
+            .unwrap()
+            .is_err());
+    }
+}
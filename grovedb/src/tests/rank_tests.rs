@@ -0,0 +1,297 @@
+//! Tests for rank and select queries on provable count trees, and for their
+//! proofs.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, Error, GroveDb,
+    };
+
+    fn ranked_path() -> Vec<Vec<u8>> {
+        vec![TEST_LEAF.to_vec(), b"ranked".to_vec()]
+    }
+
+    fn key(n: u16) -> Vec<u8> {
+        n.to_be_bytes().to_vec()
+    }
+
+    /// Makes `TEST_LEAF/ranked`, a provable count tree holding items keyed
+    /// by the big-endian encodings of the multiples of three below 600, and
+    /// an empty provable count tree `TEST_LEAF/empty`.
+    fn make_db_with_ranked_items(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        for tree_key in [b"ranked".as_slice(), b"empty"] {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                tree_key,
+                Element::empty_provable_count_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a provable count tree");
+        }
+        for n in (0u16..600).step_by(3) {
+            db.insert(
+                [TEST_LEAF, b"ranked"].as_ref(),
+                &key(n),
+                Element::new_item(n.to_be_bytes().to_vec()),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert an item");
+        }
+        db
+    }
+
+    #[test]
+    fn test_get_by_rank_and_rank_of_key_follow_key_order() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_ranked_items(grove_version);
+
+        for (rank, n) in (0u16..600).step_by(3).enumerate() {
+            let (found_key, element) = db
+                .get_by_rank(ranked_path().as_slice(), rank as u64, None, grove_version)
+                .unwrap()
+                .expect("cannot get by rank")
+                .expect("expected a key at the rank");
+            assert_eq!(found_key, key(n));
+            assert_eq!(element, Element::new_item(n.to_be_bytes().to_vec()));
+
+            let found_rank = db
+                .rank_of_key(ranked_path().as_slice(), &key(n), None, grove_version)
+                .unwrap()
+                .expect("cannot get the rank of a key");
+            assert_eq!(found_rank, Some(rank as u64));
+        }
+
+        assert!(db
+            .get_by_rank(ranked_path().as_slice(), 200, None, grove_version)
+            .unwrap()
+            .expect("cannot get by rank")
+            .is_none());
+        for absent in [1, 302, 1000] {
+            assert_eq!(
+                db.rank_of_key(ranked_path().as_slice(), &key(absent), None, grove_version)
+                    .unwrap()
+                    .expect("cannot get the rank of a key"),
+                None
+            );
+        }
+
+        // Deleting a key moves the keys after it down one rank.
+        db.delete(
+            [TEST_LEAF, b"ranked"].as_ref(),
+            &key(30),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot delete an item");
+        let (found_key, _) = db
+            .get_by_rank(ranked_path().as_slice(), 10, None, grove_version)
+            .unwrap()
+            .expect("cannot get by rank")
+            .expect("expected a key at the rank");
+        assert_eq!(found_key, key(33));
+    }
+
+    #[test]
+    fn test_nested_count_trees_hold_a_rank_per_counted_element() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"ranked",
+            Element::empty_provable_count_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a provable count tree");
+        db.insert(
+            [TEST_LEAF, b"ranked"].as_ref(),
+            b"a",
+            Element::new_item(b"first".to_vec()),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert an item");
+        db.insert(
+            [TEST_LEAF, b"ranked"].as_ref(),
+            b"b",
+            Element::empty_count_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a count tree");
+        for inner in [b"x", b"y", b"z"] {
+            db.insert(
+                [TEST_LEAF, b"ranked", b"b"].as_ref(),
+                inner,
+                Element::new_item(b"inner".to_vec()),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert an item in the count tree");
+        }
+        db.insert(
+            [TEST_LEAF, b"ranked"].as_ref(),
+            b"c",
+            Element::new_item(b"last".to_vec()),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert an item");
+
+        let keys_by_rank: Vec<Vec<u8>> = (0..5)
+            .map(|rank| {
+                db.get_by_rank(ranked_path().as_slice(), rank, None, grove_version)
+                    .unwrap()
+                    .expect("cannot get by rank")
+                    .expect("expected a key at the rank")
+                    .0
+            })
+            .collect();
+        assert_eq!(
+            keys_by_rank,
+            vec![
+                b"a".to_vec(),
+                b"b".to_vec(),
+                b"b".to_vec(),
+                b"b".to_vec(),
+                b"c".to_vec()
+            ]
+        );
+        assert_eq!(
+            db.rank_of_key(ranked_path().as_slice(), b"c", None, grove_version)
+                .unwrap()
+                .expect("cannot get the rank of a key"),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_rank_proofs_match_the_unproved_queries() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_ranked_items(grove_version);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        for rank in [0, 1, 57, 100, 150, 199, 200, 5000] {
+            let expected = db
+                .get_by_rank(ranked_path().as_slice(), rank, None, grove_version)
+                .unwrap()
+                .expect("cannot get by rank")
+                .map(|(key, _)| key);
+            let proof = db
+                .prove_by_rank(ranked_path(), rank, grove_version)
+                .unwrap()
+                .expect("cannot prove the key at a rank");
+            let (proved_root_hash, proved_key) =
+                GroveDb::verify_by_rank(&proof, ranked_path(), rank, grove_version)
+                    .expect("cannot verify the rank proof");
+            assert_eq!(proved_root_hash, root_hash, "root hash for rank {}", rank);
+            assert_eq!(proved_key, expected, "key at rank {}", rank);
+        }
+
+        for n in [0, 1, 3, 299, 300, 597, 598, 1000] {
+            let expected = db
+                .rank_of_key(ranked_path().as_slice(), &key(n), None, grove_version)
+                .unwrap()
+                .expect("cannot get the rank of a key");
+            let proof = db
+                .prove_rank_of_key(ranked_path(), &key(n), grove_version)
+                .unwrap()
+                .expect("cannot prove the rank of a key");
+            let (proved_root_hash, proved_rank) =
+                GroveDb::verify_rank_of_key(&proof, ranked_path(), &key(n), grove_version)
+                    .expect("cannot verify the rank proof");
+            assert_eq!(proved_root_hash, root_hash, "root hash for key {}", n);
+            assert_eq!(proved_rank, expected, "rank of key {}", n);
+        }
+    }
+
+    #[test]
+    fn test_rank_proof_does_not_prove_other_ranks() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_ranked_items(grove_version);
+
+        let proof = db
+            .prove_by_rank(ranked_path(), 20, grove_version)
+            .unwrap()
+            .expect("cannot prove the key at a rank");
+        assert!(GroveDb::verify_by_rank(&proof, ranked_path(), 150, grove_version).is_err());
+
+        let proof = db
+            .prove_rank_of_key(ranked_path(), &key(60), grove_version)
+            .unwrap()
+            .expect("cannot prove the rank of a key");
+        assert!(
+            GroveDb::verify_rank_of_key(&proof, ranked_path(), &key(450), grove_version).is_err()
+        );
+    }
+
+    #[test]
+    fn test_ranks_of_empty_and_other_trees() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_ranked_items(grove_version);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+        let empty_path = vec![TEST_LEAF.to_vec(), b"empty".to_vec()];
+
+        assert!(db
+            .get_by_rank(empty_path.as_slice(), 0, None, grove_version)
+            .unwrap()
+            .expect("cannot get by rank")
+            .is_none());
+        let proof = db
+            .prove_by_rank(empty_path.clone(), 0, grove_version)
+            .unwrap()
+            .expect("cannot prove a rank in an empty tree");
+        let (proved_root_hash, proved_key) =
+            GroveDb::verify_by_rank(&proof, empty_path.clone(), 0, grove_version)
+                .expect("cannot verify the rank proof");
+        assert_eq!(proved_root_hash, root_hash);
+        assert_eq!(proved_key, None);
+        let proof = db
+            .prove_rank_of_key(empty_path.clone(), b"a", grove_version)
+            .unwrap()
+            .expect("cannot prove a rank in an empty tree");
+        let (proved_root_hash, proved_rank) =
+            GroveDb::verify_rank_of_key(&proof, empty_path, b"a", grove_version)
+                .expect("cannot verify the rank proof");
+        assert_eq!(proved_root_hash, root_hash);
+        assert_eq!(proved_rank, None);
+
+        // Normal trees do not count their elements.
+        assert!(matches!(
+            db.get_by_rank([TEST_LEAF].as_ref(), 0, None, grove_version)
+                .unwrap(),
+            Err(Error::InvalidInput(
+                "ranks can only be resolved in provable count trees"
+            ))
+        ));
+        assert!(matches!(
+            db.prove_rank_of_key(vec![TEST_LEAF.to_vec()], b"ranked", grove_version)
+                .unwrap(),
+            Err(Error::InvalidInput(
+                "ranks can only be proved in provable count trees"
+            ))
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+//! Tests for read-only and secondary GroveDb instances.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::Query;
+    use grovedb_version::version::GroveVersion;
+    use tempfile::TempDir;
+
+    use crate::{
+        query_result_type::QueryResultType,
+        tests::{make_test_grovedb, TEST_LEAF},
+        Element, GroveDb, PathQuery,
+    };
+
+    fn insert_item(db: &GroveDb, key: &[u8], grove_version: &GroveVersion) {
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            key,
+            Element::new_item(key.to_vec()),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert an item");
+    }
+
+    #[test]
+    fn test_read_only_serves_queries_and_proofs() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        insert_item(&db, b"key1", grove_version);
+        insert_item(&db, b"key2", grove_version);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        let read_only = GroveDb::open_read_only(db._tmp_dir.path()).expect("cannot open read-only");
+        assert!(!read_only.is_secondary());
+        assert_eq!(
+            read_only.root_hash(grove_version).unwrap().unwrap(),
+            root_hash
+        );
+        assert_eq!(
+            read_only
+                .get([TEST_LEAF].as_ref(), b"key1", grove_version)
+                .unwrap()
+                .expect("cannot get an item"),
+            Element::new_item(b"key1".to_vec())
+        );
+
+        let path_query = PathQuery::new_unsized(vec![TEST_LEAF.to_vec()], Query::new_range_full());
+        let (elements, _) = read_only
+            .query_item_value(&path_query, true, true, true, grove_version)
+            .unwrap()
+            .expect("cannot query");
+        assert_eq!(elements, vec![b"key1".to_vec(), b"key2".to_vec()]);
+
+        let proof = read_only
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("cannot prove");
+        let (hash, result_set) = GroveDb::verify_query(&proof, &path_query, grove_version)
+            .expect("cannot verify the proof");
+        assert_eq!(hash, root_hash);
+        assert_eq!(result_set.len(), 2);
+    }
+
+    #[test]
+    fn test_read_only_cannot_catch_up() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+
+        let mut read_only =
+            GroveDb::open_read_only(db._tmp_dir.path()).expect("cannot open read-only");
+        assert!(read_only.try_catch_up_with_primary().is_err());
+    }
+
+    #[test]
+    fn test_secondary_follows_primary() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        insert_item(&db, b"key1", grove_version);
+
+        let secondary_dir = TempDir::new().unwrap();
+        let mut secondary = GroveDb::open_as_secondary(db._tmp_dir.path(), secondary_dir.path())
+            .expect("cannot open secondary");
+        assert!(secondary.is_secondary());
+
+        insert_item(&db, b"key2", grove_version);
+        db.flush().expect("cannot flush");
+        assert!(secondary
+            .get_raw_optional([TEST_LEAF].as_ref().into(), b"key2", grove_version)
+            .unwrap()
+            .expect("cannot get")
+            .is_none());
+
+        secondary
+            .try_catch_up_with_primary()
+            .expect("cannot catch up");
+        assert_eq!(
+            secondary.root_hash(grove_version).unwrap().unwrap(),
+            db.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        let (elements, _) = secondary
+            .query(
+                &PathQuery::new_unsized(vec![TEST_LEAF.to_vec()], Query::new_range_full()),
+                true,
+                true,
+                true,
+                QueryResultType::QueryKeyElementPairResultType,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot query");
+        assert_eq!(elements.len(), 2);
+    }
+}
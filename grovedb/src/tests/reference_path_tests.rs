@@ -5,6 +5,7 @@
 mod tests {
     use grovedb_merk::{element::insert::ElementInsertToStorageExtensions, tree::NULL_HASH};
     use grovedb_path::SubtreePath;
+    use grovedb_storage::StorageBatch;
     use grovedb_version::version::GroveVersion;
 
     use crate::{
@@ -52,7 +53,8 @@ mod tests {
         // Use MerkCache to insert raw reference elements at the Merk level,
         // which skips GroveDb-level validation.
         {
-            let cache = MerkCache::new(&db, &tx, grove_version);
+            let batch = StorageBatch::new();
+            let cache = MerkCache::new(&db, &tx, &batch, grove_version);
             let path: SubtreePath<&[u8]> = SubtreePath::from(&[TEST_LEAF, b"refs"] as &[&[u8]]);
 
             // ref_a points to [TEST_LEAF, "refs", "b"]
@@ -143,7 +145,8 @@ mod tests {
         let tx = db.start_transaction();
 
         {
-            let cache = MerkCache::new(&db, &tx, grove_version);
+            let batch = StorageBatch::new();
+            let cache = MerkCache::new(&db, &tx, &batch, grove_version);
             let path: SubtreePath<&[u8]> = SubtreePath::from(&[TEST_LEAF, b"chain"] as &[&[u8]]);
 
             let keygen = |i: usize| format!("ref{}", i).into_bytes();
@@ -229,7 +232,8 @@ mod tests {
         let tx = db.start_transaction();
 
         {
-            let cache = MerkCache::new(&db, &tx, grove_version);
+            let batch = StorageBatch::new();
+            let cache = MerkCache::new(&db, &tx, &batch, grove_version);
             let path: SubtreePath<&[u8]> =
                 SubtreePath::from(&[TEST_LEAF, b"container"] as &[&[u8]]);
 
@@ -302,7 +306,8 @@ mod tests {
         let tx = db.start_transaction();
 
         {
-            let cache = MerkCache::new(&db, &tx, grove_version);
+            let batch = StorageBatch::new();
+            let cache = MerkCache::new(&db, &tx, &batch, grove_version);
             let path: SubtreePath<&[u8]> = SubtreePath::from(&[TEST_LEAF, b"self_ref"] as &[&[u8]]);
 
             // Call follow_reference_once with a reference that resolves to the
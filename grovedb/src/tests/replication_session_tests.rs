@@ -4,13 +4,20 @@
 mod tests {
     use std::collections::VecDeque;
 
+    use grovedb_path::SubtreePath;
+    use grovedb_storage::{RawIterator, Storage, StorageContext};
     use grovedb_version::version::GroveVersion;
     use tempfile::TempDir;
 
+    use grovedb_commitment_tree::{ciphertext_payload_size, DashMemo};
+
     use crate::{
-        replication::CURRENT_STATE_SYNC_VERSION,
+        operations::delete::DeleteOptions,
+        replication::{
+            utils::pack_nested_bytes, ChunkEnvelope, ChunkEnvelopeError, CURRENT_STATE_SYNC_VERSION,
+        },
         tests::{make_empty_grovedb, make_test_grovedb, TempGroveDb, ANOTHER_TEST_LEAF, TEST_LEAF},
-        Element, GroveDb,
+        Element, Error, GroveDb,
     };
 
     /// Helper: perform a full state sync from source to destination using
@@ -21,6 +28,32 @@ mod tests {
         source: &TempGroveDb,
         grove_version: &GroveVersion,
     ) -> TempGroveDb {
+        sync_tampered_source_to_destination(source, |chunk| chunk, grove_version)
+            .expect("should sync source to destination")
+    }
+
+    /// Helper: passes the payload of every envelope in `chunk_data` through
+    /// `tamper` and seals the envelopes again, as a peer serving forged
+    /// chunks would.
+    fn tamper_payloads(chunk_data: &[u8], mut tamper: impl FnMut(&mut Vec<u8>)) -> Vec<u8> {
+        let envelopes = ChunkEnvelope::decode_packed(chunk_data)
+            .expect("should decode envelopes")
+            .into_iter()
+            .map(|mut envelope| {
+                tamper(&mut envelope.payload);
+                envelope.encode().expect("should encode envelope")
+            })
+            .collect();
+        pack_nested_bytes(envelopes).expect("should pack envelopes")
+    }
+
+    /// Helper: like `sync_source_to_destination`, with every fetched chunk
+    /// passed through `tamper` before it is applied.
+    fn sync_tampered_source_to_destination(
+        source: &TempGroveDb,
+        tamper: impl Fn(Vec<u8>) -> Vec<u8>,
+        grove_version: &GroveVersion,
+    ) -> Result<TempGroveDb, Error> {
         // Create a checkpoint from the source -- this is the standard pattern
         // for replication (the tutorial does the same).
         let checkpoint_dir = TempDir::new().expect("should create temp dir for checkpoint");
@@ -55,14 +88,12 @@ mod tests {
                 )
                 .expect("should fetch chunk from checkpoint");
 
-            let more_ids = session
-                .apply_chunk(
-                    chunk_id.as_slice(),
-                    &chunk_data,
-                    CURRENT_STATE_SYNC_VERSION,
-                    grove_version,
-                )
-                .expect("should apply chunk to destination");
+            let more_ids = session.apply_chunk(
+                chunk_id.as_slice(),
+                &tamper(chunk_data),
+                CURRENT_STATE_SYNC_VERSION,
+                grove_version,
+            )?;
 
             chunk_queue.extend(more_ids);
         }
@@ -72,10 +103,9 @@ mod tests {
             "sync should be completed after all chunks are applied"
         );
 
-        dest.commit_session(session, grove_version)
-            .expect("should commit sync session");
+        dest.commit_session(session, grove_version)?;
 
-        dest
+        Ok(dest)
     }
 
     #[test]
@@ -320,6 +350,15 @@ mod tests {
             Element::new_item(b"other_value".to_vec()),
             "other_key value should match"
         );
+
+        // Synced subtrees are registered as they are discovered
+        let list_subtrees = |db: &GroveDb| {
+            db.list_subtrees(SubtreePath::empty(), None, None, grove_version)
+                .unwrap()
+                .expect("should list subtrees")
+        };
+        assert_eq!(list_subtrees(&dest), list_subtrees(&source));
+        assert_eq!(list_subtrees(&dest).len(), 3);
     }
 
     #[test]
@@ -353,7 +392,7 @@ mod tests {
             .fetch_chunk(&app_hash, None, CURRENT_STATE_SYNC_VERSION, grove_version)
             .expect("should fetch root chunk");
 
-        // Apply chunk with wrong version (version 0 instead of 1)
+        // Apply chunk with wrong version (version 0 instead of the current one)
         let result = session.apply_chunk(
             &app_hash,
             &root_chunk_data,
@@ -689,4 +728,931 @@ mod tests {
             other => panic!("expected CorruptedData, got: {:?}", other),
         }
     }
+
+    fn value(n: u64) -> Vec<u8> {
+        format!("value-{n}").into_bytes()
+    }
+
+    fn note(n: u8) -> ([u8; 32], [u8; 32], Vec<u8>) {
+        let mut cmx = [0u8; 32];
+        cmx[0] = n;
+        let mut rho = [0xAA; 32];
+        rho[0] = n;
+        (cmx, rho, vec![n; ciphertext_payload_size::<DashMemo>()])
+    }
+
+    /// Makes `TEST_LEAF/mmr`, `TEST_LEAF/bulk`, `TEST_LEAF/dense` and
+    /// `TEST_LEAF/notes` with values that span several chunks, and empty
+    /// trees of the same types under `ANOTHER_TEST_LEAF`.
+    fn make_source_with_non_merk_trees(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        for path in [TEST_LEAF, ANOTHER_TEST_LEAF] {
+            for (key, element) in [
+                (b"mmr".as_slice(), Element::empty_mmr_tree()),
+                (b"bulk", Element::empty_bulk_append_tree(2).unwrap()),
+                (b"dense", Element::empty_dense_tree(9)),
+                (b"notes", Element::empty_commitment_tree(2).unwrap()),
+            ] {
+                db.insert([path].as_ref(), key, element, None, None, grove_version)
+                    .unwrap()
+                    .expect("should insert tree");
+            }
+        }
+
+        for n in 0..300 {
+            db.mmr_tree_append([TEST_LEAF].as_ref(), b"mmr", value(n), None, grove_version)
+                .unwrap()
+                .expect("should append to mmr tree");
+            db.dense_tree_insert(
+                [TEST_LEAF].as_ref(),
+                b"dense",
+                value(n),
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should insert into dense tree");
+        }
+        // 11 compacted chunk blobs of 4 values and one value in the buffer
+        for n in 0..45 {
+            db.bulk_append([TEST_LEAF].as_ref(), b"bulk", value(n), None, grove_version)
+                .unwrap()
+                .expect("should append to bulk append tree");
+        }
+        for n in 0..6 {
+            let (cmx, rho, payload) = note(n);
+            db.commitment_tree_insert_raw(
+                [TEST_LEAF].as_ref(),
+                b"notes",
+                cmx,
+                rho,
+                payload,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should insert into commitment tree");
+        }
+        db
+    }
+
+    #[test]
+    fn full_round_trip_non_merk_trees() {
+        let grove_version = GroveVersion::latest();
+        let source = make_source_with_non_merk_trees(grove_version);
+        let dest = sync_source_to_destination(&source, grove_version);
+
+        assert!(dest
+            .verify_grovedb(None, true, false, grove_version)
+            .expect("should verify destination")
+            .is_empty());
+        let path = [TEST_LEAF].as_ref();
+        assert_eq!(
+            dest.mmr_tree_get_value(path, b"mmr", 299, None, grove_version)
+                .unwrap()
+                .expect("should get mmr value"),
+            Some(value(299))
+        );
+        assert_eq!(
+            dest.dense_tree_get(path, b"dense", 299, None, grove_version)
+                .unwrap()
+                .expect("should get dense tree value"),
+            Some(value(299))
+        );
+        for position in [0, 44] {
+            assert_eq!(
+                dest.bulk_get_value(path, b"bulk", position, None, grove_version)
+                    .unwrap()
+                    .expect("should get bulk value"),
+                Some(value(position))
+            );
+        }
+        assert_eq!(
+            dest.commitment_tree_anchor(path, b"notes", None, grove_version)
+                .unwrap()
+                .expect("should get destination anchor"),
+            source
+                .commitment_tree_anchor(path, b"notes", None, grove_version)
+                .unwrap()
+                .expect("should get source anchor")
+        );
+
+        // Trees keep growing the same way, so internal MMR nodes, buffers
+        // and the frontier were restored and not only the roots
+        for db in [&source, &dest] {
+            for path in [TEST_LEAF, ANOTHER_TEST_LEAF] {
+                let path = &[path];
+                db.mmr_tree_append(path, b"mmr", value(300), None, grove_version)
+                    .unwrap()
+                    .expect("should append to mmr tree");
+                db.dense_tree_insert(path, b"dense", value(300), None, grove_version)
+                    .unwrap()
+                    .expect("should insert into dense tree");
+                for n in 45..48 {
+                    db.bulk_append(path, b"bulk", value(n), None, grove_version)
+                        .unwrap()
+                        .expect("should append to bulk append tree");
+                }
+                let (cmx, rho, payload) = note(6);
+                db.commitment_tree_insert_raw(
+                    path,
+                    b"notes",
+                    cmx,
+                    rho,
+                    payload,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("should insert into commitment tree");
+            }
+        }
+        assert_eq!(
+            dest.root_hash(None, grove_version).unwrap().unwrap(),
+            source.root_hash(None, grove_version).unwrap().unwrap()
+        );
+    }
+
+    #[test]
+    fn tampered_non_merk_chunk_is_rejected() {
+        let grove_version = GroveVersion::latest();
+        let source = make_source_with_non_merk_trees(grove_version);
+
+        // Values only appear in chunks of the non-Merk trees
+        let result = sync_tampered_source_to_destination(
+            &source,
+            |chunk| {
+                tamper_payloads(&chunk, |payload| {
+                    if let Some(start) = payload.windows(8).position(|bytes| bytes == b"value-17") {
+                        payload[start] = b'V';
+                    }
+                })
+            },
+            grove_version,
+        );
+        match result {
+            Err(Error::CorruptedData(message)) => assert!(
+                message.contains("doesn't match the hash committed in its parent"),
+                "unexpected error: {message}"
+            ),
+            other => panic!(
+                "expected CorruptedData, got: {:?}",
+                other.map(|_| "a synced destination")
+            ),
+        }
+    }
+
+    /// Helper: like `sync_source_to_destination`, in batches of two subtrees,
+    /// checkpointing the session every `checkpoint_every` applied chunks and
+    /// dropping it after the next chunk, as if the node restarted, before
+    /// resuming it.
+    fn sync_source_to_destination_with_restarts(
+        source: &TempGroveDb,
+        checkpoint_every: usize,
+        grove_version: &GroveVersion,
+    ) -> TempGroveDb {
+        let checkpoint_dir = TempDir::new().expect("should create temp dir for checkpoint");
+        let checkpoint_path = checkpoint_dir.path().join("checkpoint");
+        source
+            .create_checkpoint(&checkpoint_path)
+            .expect("should create checkpoint");
+        let checkpoint_db = GroveDb::open(&checkpoint_path).expect("should open checkpoint db");
+        let app_hash = checkpoint_db
+            .root_hash(None, grove_version)
+            .unwrap()
+            .expect("checkpoint root hash should be available");
+
+        let dest = make_empty_grovedb();
+        let mut session = dest
+            .start_snapshot_syncing(app_hash, 2, CURRENT_STATE_SYNC_VERSION, grove_version)
+            .expect("should start snapshot syncing");
+
+        let mut chunk_queue = VecDeque::from([app_hash.to_vec()]);
+        let mut num_applied_chunks = 0;
+        let mut num_restarts = 0;
+        while let Some(chunk_id) = chunk_queue.pop_front() {
+            let chunk_data = checkpoint_db
+                .fetch_chunk(
+                    chunk_id.as_slice(),
+                    None,
+                    CURRENT_STATE_SYNC_VERSION,
+                    grove_version,
+                )
+                .expect("should fetch chunk from checkpoint");
+            chunk_queue.extend(
+                session
+                    .apply_chunk(
+                        chunk_id.as_slice(),
+                        &chunk_data,
+                        CURRENT_STATE_SYNC_VERSION,
+                        grove_version,
+                    )
+                    .expect("should apply chunk"),
+            );
+            num_applied_chunks += 1;
+
+            if num_applied_chunks % checkpoint_every == 0 {
+                session
+                    .checkpoint(grove_version)
+                    .expect("should checkpoint session");
+            } else if num_applied_chunks % checkpoint_every == 1 && num_applied_chunks > 1 {
+                // The chunk applied since the checkpoint is lost
+                drop(session);
+                session = dest
+                    .resume_syncing_session(app_hash, grove_version)
+                    .expect("should read checkpoint")
+                    .expect("should resume session");
+                chunk_queue = session
+                    .pending_chunk_ids()
+                    .expect("should list pending chunks")
+                    .into();
+                num_restarts += 1;
+            }
+        }
+
+        assert!(num_restarts > 1, "sync should have been resumed");
+        assert!(
+            session.is_sync_completed(),
+            "sync should be completed after all chunks are applied"
+        );
+        dest.commit_session(session, grove_version)
+            .expect("should commit session");
+        assert!(
+            dest.resume_syncing_session(app_hash, grove_version)
+                .expect("should read checkpoint")
+                .is_none(),
+            "committed session should not be resumable"
+        );
+
+        dest
+    }
+
+    /// Makes `TEST_LEAF/items` and `TEST_LEAF/sums` with 500 elements each,
+    /// spanning several Merk chunks, and `TEST_LEAF/inner` holding five empty
+    /// trees.
+    fn make_source_with_large_merks(grove_version: &GroveVersion) -> TempGroveDb {
+        let source = make_test_grovedb(grove_version);
+        for (key, element) in [
+            (b"items".as_slice(), Element::empty_tree()),
+            (b"sums", Element::empty_sum_tree()),
+            (b"inner", Element::empty_tree()),
+        ] {
+            source
+                .insert(
+                    [TEST_LEAF].as_ref(),
+                    key,
+                    element,
+                    None,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("should insert tree");
+        }
+        for n in 0..500u64 {
+            let key = n.to_be_bytes();
+            source
+                .insert(
+                    [TEST_LEAF, b"items"].as_ref(),
+                    &key,
+                    Element::new_item(value(n)),
+                    None,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("should insert item");
+            source
+                .insert(
+                    [TEST_LEAF, b"sums"].as_ref(),
+                    &key,
+                    Element::new_sum_item(n as i64),
+                    None,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("should insert sum item");
+        }
+        for n in 0..5u64 {
+            source
+                .insert(
+                    [TEST_LEAF, b"inner"].as_ref(),
+                    &n.to_be_bytes(),
+                    Element::empty_tree(),
+                    None,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("should insert nested tree");
+        }
+
+        source
+    }
+
+    #[test]
+    fn resumed_session_restores_partially_synced_merks() {
+        let grove_version = GroveVersion::latest();
+        let source = make_source_with_large_merks(grove_version);
+
+        let dest = sync_source_to_destination_with_restarts(&source, 2, grove_version);
+
+        assert_eq!(
+            dest.root_hash(None, grove_version).unwrap().unwrap(),
+            source.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        assert!(dest
+            .verify_grovedb(None, true, false, grove_version)
+            .expect("should verify destination")
+            .is_empty());
+        assert_eq!(
+            dest.get(
+                [TEST_LEAF, b"items"].as_ref(),
+                &499u64.to_be_bytes(),
+                None,
+                grove_version
+            )
+            .unwrap()
+            .expect("should read synced item"),
+            Element::new_item(value(499))
+        );
+    }
+
+    #[test]
+    fn resumed_session_restores_non_merk_trees() {
+        let grove_version = GroveVersion::latest();
+        let source = make_source_with_non_merk_trees(grove_version);
+
+        let dest = sync_source_to_destination_with_restarts(&source, 2, grove_version);
+
+        assert_eq!(
+            dest.root_hash(None, grove_version).unwrap().unwrap(),
+            source.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        assert!(dest
+            .verify_grovedb(None, true, false, grove_version)
+            .expect("should verify destination")
+            .is_empty());
+    }
+
+    #[test]
+    fn resume_syncing_session_needs_a_checkpoint_of_the_snapshot() {
+        let grove_version = GroveVersion::latest();
+        let dest = make_empty_grovedb();
+        let app_hash = [7u8; 32];
+
+        assert!(dest
+            .resume_syncing_session(app_hash, grove_version)
+            .expect("should read checkpoint")
+            .is_none());
+
+        let mut session = dest
+            .start_snapshot_syncing(app_hash, 2, CURRENT_STATE_SYNC_VERSION, grove_version)
+            .expect("should start snapshot syncing");
+        session
+            .checkpoint(grove_version)
+            .expect("should checkpoint session");
+        drop(session);
+
+        assert!(dest
+            .resume_syncing_session([8u8; 32], grove_version)
+            .expect("should read checkpoint")
+            .is_none());
+        let session = dest
+            .resume_syncing_session(app_hash, grove_version)
+            .expect("should read checkpoint")
+            .expect("should resume session");
+        assert_eq!(
+            session
+                .pending_chunk_ids()
+                .expect("should list pending chunks"),
+            vec![app_hash.to_vec()]
+        );
+        drop(session);
+
+        // Starting over discards the checkpoint once the new session commits
+        let mut session = dest
+            .start_snapshot_syncing([8u8; 32], 2, CURRENT_STATE_SYNC_VERSION, grove_version)
+            .expect("should start snapshot syncing");
+        session
+            .checkpoint(grove_version)
+            .expect("should checkpoint session");
+        drop(session);
+        assert!(dest
+            .resume_syncing_session(app_hash, grove_version)
+            .expect("should read checkpoint")
+            .is_none());
+    }
+
+    #[test]
+    fn apply_chunks_applies_fetched_chunks_together() {
+        let grove_version = GroveVersion::latest();
+        let source = make_source_with_large_merks(grove_version);
+        let app_hash = source.root_hash(None, grove_version).unwrap().unwrap();
+
+        let dest = make_empty_grovedb();
+        let mut session = dest
+            .start_snapshot_syncing(app_hash, 64, CURRENT_STATE_SYNC_VERSION, grove_version)
+            .expect("should start snapshot syncing");
+
+        // Every round applies all the chunks requested by the previous one
+        let mut chunk_ids = vec![app_hash.to_vec()];
+        let mut num_rounds = 0;
+        while !chunk_ids.is_empty() {
+            let fetched_chunks: Vec<Vec<u8>> = chunk_ids
+                .iter()
+                .map(|chunk_id| {
+                    source
+                        .fetch_chunk(chunk_id, None, CURRENT_STATE_SYNC_VERSION, grove_version)
+                        .expect("should fetch chunk")
+                })
+                .collect();
+            let chunks: Vec<(&[u8], &[u8])> = chunk_ids
+                .iter()
+                .zip(&fetched_chunks)
+                .map(|(chunk_id, chunk)| (chunk_id.as_slice(), chunk.as_slice()))
+                .collect();
+            chunk_ids = session
+                .apply_chunks(&chunks, CURRENT_STATE_SYNC_VERSION, grove_version)
+                .expect("should apply chunks");
+            num_rounds += 1;
+        }
+
+        assert!(num_rounds > 3, "chunks should have been applied in rounds");
+        assert!(session.is_sync_completed());
+        dest.commit_session(session, grove_version)
+            .expect("should commit session");
+        assert_eq!(
+            dest.root_hash(None, grove_version).unwrap().unwrap(),
+            app_hash
+        );
+        assert!(dest
+            .verify_grovedb(None, true, false, grove_version)
+            .expect("should verify destination")
+            .is_empty());
+    }
+
+    #[test]
+    fn apply_chunks_rejects_tampered_chunk() {
+        let grove_version = GroveVersion::latest();
+        let source = make_source_with_large_merks(grove_version);
+        let app_hash = source.root_hash(None, grove_version).unwrap().unwrap();
+
+        let dest = make_empty_grovedb();
+        let mut session = dest
+            .start_snapshot_syncing(app_hash, 64, CURRENT_STATE_SYNC_VERSION, grove_version)
+            .expect("should start snapshot syncing");
+
+        let mut chunk_ids = vec![app_hash.to_vec()];
+        loop {
+            let mut fetched_chunks: Vec<Vec<u8>> = chunk_ids
+                .iter()
+                .map(|chunk_id| {
+                    source
+                        .fetch_chunk(chunk_id, None, CURRENT_STATE_SYNC_VERSION, grove_version)
+                        .expect("should fetch chunk")
+                })
+                .collect();
+            // Items only appear in chunks of the `items` Merk
+            let tampered = fetched_chunks.iter_mut().any(|chunk| {
+                let mut tampered = false;
+                *chunk = tamper_payloads(chunk, |payload| {
+                    if let Some(start) = payload.windows(9).position(|bytes| bytes == b"value-499")
+                    {
+                        payload[start] = b'V';
+                        tampered = true;
+                    }
+                });
+                tampered
+            });
+            let chunks: Vec<(&[u8], &[u8])> = chunk_ids
+                .iter()
+                .zip(&fetched_chunks)
+                .map(|(chunk_id, chunk)| (chunk_id.as_slice(), chunk.as_slice()))
+                .collect();
+            let result = session.apply_chunks(&chunks, CURRENT_STATE_SYNC_VERSION, grove_version);
+            if !tampered {
+                chunk_ids = result.expect("should apply chunks");
+                assert!(!chunk_ids.is_empty(), "value-499 should be synced");
+                continue;
+            }
+            match result {
+                Err(Error::InternalError(message)) => assert!(
+                    message.contains("chunk doesn't match expected root hash"),
+                    "unexpected error: {message}"
+                ),
+                other => panic!("expected InternalError, got: {:?}", other),
+            }
+            break;
+        }
+    }
+
+    /// Helper: syncs the state of `source` into `dest`, over the state `dest`
+    /// already holds if `incremental`, and returns the number of chunk bytes
+    /// fetched from `source`.
+    fn sync_into(
+        dest: &GroveDb,
+        source: &GroveDb,
+        incremental: bool,
+        grove_version: &GroveVersion,
+    ) -> usize {
+        let app_hash = source.root_hash(None, grove_version).unwrap().unwrap();
+        let mut session = if incremental {
+            dest.start_incremental_syncing(app_hash, 64, CURRENT_STATE_SYNC_VERSION, grove_version)
+        } else {
+            dest.start_snapshot_syncing(app_hash, 64, CURRENT_STATE_SYNC_VERSION, grove_version)
+        }
+        .expect("should start syncing");
+
+        let mut chunk_queue = VecDeque::from([app_hash.to_vec()]);
+        let mut num_fetched_bytes = 0;
+        while let Some(chunk_id) = chunk_queue.pop_front() {
+            let chunk_data = source
+                .fetch_chunk(&chunk_id, None, CURRENT_STATE_SYNC_VERSION, grove_version)
+                .expect("should fetch chunk");
+            num_fetched_bytes += chunk_data.len();
+            chunk_queue.extend(
+                session
+                    .apply_chunk(
+                        &chunk_id,
+                        &chunk_data,
+                        CURRENT_STATE_SYNC_VERSION,
+                        grove_version,
+                    )
+                    .expect("should apply chunk"),
+            );
+        }
+
+        assert!(session.is_sync_completed());
+        dest.commit_session(session, grove_version)
+            .expect("should commit session");
+        num_fetched_bytes
+    }
+
+    /// Number of entries stored in the data of the subtree at `path`.
+    fn num_stored_entries(db: &GroveDb, path: &[&[u8]]) -> usize {
+        let transaction = db.start_transaction();
+        let storage = db
+            .db
+            .get_transactional_storage_context(path.into(), None, &transaction)
+            .unwrap();
+        let mut raw_iter = storage.raw_iter();
+        raw_iter.seek_to_first().unwrap();
+        let mut num_entries = 0;
+        while raw_iter.valid().unwrap() {
+            num_entries += 1;
+            raw_iter.next().unwrap();
+        }
+        num_entries
+    }
+
+    /// Makes the source of `make_source_with_large_merks` with
+    /// `TEST_LEAF/inner/4/nested` holding an item, as the older state.
+    fn make_older_source(grove_version: &GroveVersion) -> TempGroveDb {
+        let source = make_source_with_large_merks(grove_version);
+        let nested_path: &[&[u8]] = &[TEST_LEAF, b"inner", &4u64.to_be_bytes()];
+        source
+            .insert(
+                nested_path,
+                b"nested",
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should insert nested tree");
+        source
+            .insert(
+                [nested_path, &[b"nested"]].concat().as_slice(),
+                b"key",
+                Element::new_item(value(0)),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should insert nested item");
+        source
+    }
+
+    /// Changes some items of `TEST_LEAF/items`, deletes `TEST_LEAF/inner/4`
+    /// with the tree below it, turns `TEST_LEAF/inner/0` into an MmrTree and
+    /// adds `TEST_LEAF/new`, leaving `TEST_LEAF/sums` as it is.
+    fn update_older_source(source: &GroveDb, grove_version: &GroveVersion) {
+        let items_path = [TEST_LEAF, b"items"];
+        for n in [10u64, 500] {
+            source
+                .insert(
+                    items_path.as_ref(),
+                    &n.to_be_bytes(),
+                    Element::new_item(value(n + 1000)),
+                    None,
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("should insert item");
+        }
+        source
+            .delete(
+                items_path.as_ref(),
+                &20u64.to_be_bytes(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should delete item");
+
+        let inner_path = [TEST_LEAF, b"inner"];
+        source
+            .delete(
+                inner_path.as_ref(),
+                &4u64.to_be_bytes(),
+                Some(DeleteOptions {
+                    allow_deleting_non_empty_trees: true,
+                    deleting_non_empty_trees_returns_error: false,
+                    ..Default::default()
+                }),
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should delete tree");
+        source
+            .delete(
+                inner_path.as_ref(),
+                &0u64.to_be_bytes(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should delete tree");
+        source
+            .insert(
+                inner_path.as_ref(),
+                &0u64.to_be_bytes(),
+                Element::empty_mmr_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should insert mmr tree");
+        for n in 0..10 {
+            source
+                .mmr_tree_append(
+                    inner_path.as_ref(),
+                    &0u64.to_be_bytes(),
+                    value(n),
+                    None,
+                    grove_version,
+                )
+                .unwrap()
+                .expect("should append to mmr tree");
+        }
+
+        source
+            .insert(
+                [TEST_LEAF].as_ref(),
+                b"new",
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should insert tree");
+        source
+            .insert(
+                [TEST_LEAF, b"new"].as_ref(),
+                b"key",
+                Element::new_item(value(0)),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("should insert item");
+    }
+
+    #[test]
+    fn incremental_sync_only_fetches_changed_regions() {
+        let grove_version = GroveVersion::latest();
+        let source = make_older_source(grove_version);
+        let dest = make_empty_grovedb();
+        sync_into(&dest, &source, false, grove_version);
+
+        update_older_source(&source, grove_version);
+        let num_full_sync_bytes = sync_into(&make_empty_grovedb(), &source, false, grove_version);
+        let num_incremental_sync_bytes = sync_into(&dest, &source, true, grove_version);
+
+        assert!(
+            num_incremental_sync_bytes * 4 < num_full_sync_bytes,
+            "incremental sync fetched {num_incremental_sync_bytes} bytes, full sync \
+             {num_full_sync_bytes}"
+        );
+        assert_eq!(
+            dest.root_hash(None, grove_version).unwrap().unwrap(),
+            source.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        assert!(dest
+            .verify_grovedb(None, true, false, grove_version)
+            .expect("should verify destination")
+            .is_empty());
+        assert_eq!(
+            dest.get(
+                [TEST_LEAF, b"items"].as_ref(),
+                &10u64.to_be_bytes(),
+                None,
+                grove_version
+            )
+            .unwrap()
+            .expect("should read updated item"),
+            Element::new_item(value(1010))
+        );
+        assert_eq!(
+            dest.mmr_tree_get_value(
+                [TEST_LEAF, b"inner"].as_ref(),
+                &0u64.to_be_bytes(),
+                9,
+                None,
+                grove_version
+            )
+            .unwrap()
+            .expect("should get mmr value"),
+            Some(value(9))
+        );
+
+        // Nodes and subtrees of the older state are gone
+        assert_eq!(num_stored_entries(&dest, &[TEST_LEAF, b"items"]), 500);
+        let deleted_tree_path: &[&[u8]] = &[TEST_LEAF, b"inner", &4u64.to_be_bytes()];
+        assert_eq!(num_stored_entries(&dest, deleted_tree_path), 0);
+        assert_eq!(
+            num_stored_entries(&dest, &[deleted_tree_path, &[b"nested"]].concat()),
+            0
+        );
+        let list_subtrees = |db: &GroveDb| {
+            db.list_subtrees(SubtreePath::empty(), None, None, grove_version)
+                .unwrap()
+                .expect("should list subtrees")
+        };
+        assert_eq!(list_subtrees(&dest), list_subtrees(&source));
+    }
+
+    #[test]
+    fn incremental_sync_of_the_stored_state_only_fetches_the_root_chunk() {
+        let grove_version = GroveVersion::latest();
+        let source = make_older_source(grove_version);
+        let dest = make_empty_grovedb();
+        sync_into(&dest, &source, false, grove_version);
+
+        let app_hash = source.root_hash(None, grove_version).unwrap().unwrap();
+        let root_chunk = source
+            .fetch_chunk(&app_hash, None, CURRENT_STATE_SYNC_VERSION, grove_version)
+            .expect("should fetch root chunk");
+        assert_eq!(
+            sync_into(&dest, &source, true, grove_version),
+            root_chunk.len()
+        );
+        assert_eq!(
+            dest.root_hash(None, grove_version).unwrap().unwrap(),
+            app_hash
+        );
+    }
+
+    #[test]
+    fn incremental_sync_resumes_after_every_chunk() {
+        let grove_version = GroveVersion::latest();
+        let source = make_older_source(grove_version);
+        let dest = make_empty_grovedb();
+        sync_into(&dest, &source, false, grove_version);
+        update_older_source(&source, grove_version);
+
+        let app_hash = source.root_hash(None, grove_version).unwrap().unwrap();
+        let mut session = dest
+            .start_incremental_syncing(app_hash, 64, CURRENT_STATE_SYNC_VERSION, grove_version)
+            .expect("should start incremental syncing");
+        let mut chunk_queue = VecDeque::from([app_hash.to_vec()]);
+        while let Some(chunk_id) = chunk_queue.pop_front() {
+            let chunk_data = source
+                .fetch_chunk(&chunk_id, None, CURRENT_STATE_SYNC_VERSION, grove_version)
+                .expect("should fetch chunk");
+            session
+                .apply_chunk(
+                    &chunk_id,
+                    &chunk_data,
+                    CURRENT_STATE_SYNC_VERSION,
+                    grove_version,
+                )
+                .expect("should apply chunk");
+            session
+                .checkpoint(grove_version)
+                .expect("should checkpoint session");
+            drop(session);
+            session = dest
+                .resume_syncing_session(app_hash, grove_version)
+                .expect("should read checkpoint")
+                .expect("should resume session");
+            chunk_queue = session
+                .pending_chunk_ids()
+                .expect("should list pending chunks")
+                .into();
+        }
+
+        dest.commit_session(session, grove_version)
+            .expect("should commit session");
+        assert_eq!(
+            dest.root_hash(None, grove_version).unwrap().unwrap(),
+            app_hash
+        );
+        assert!(dest
+            .verify_grovedb(None, true, false, grove_version)
+            .expect("should verify destination")
+            .is_empty());
+        assert_eq!(num_stored_entries(&dest, &[TEST_LEAF, b"items"]), 500);
+    }
+
+    #[test]
+    fn apply_chunk_rejects_corrupted_and_misdirected_envelopes() {
+        let grove_version = GroveVersion::latest();
+        let source = make_source_with_large_merks(grove_version);
+        let app_hash = source.root_hash(None, grove_version).unwrap().unwrap();
+
+        let dest = make_empty_grovedb();
+        let mut session = dest
+            .start_snapshot_syncing(app_hash, 64, CURRENT_STATE_SYNC_VERSION, grove_version)
+            .expect("should start snapshot syncing");
+        let root_chunk = source
+            .fetch_chunk(&app_hash, None, CURRENT_STATE_SYNC_VERSION, grove_version)
+            .expect("should fetch root chunk");
+
+        let mut corrupted = root_chunk.clone();
+        let middle = corrupted.len() / 2;
+        corrupted[middle] ^= 0x01;
+        assert!(matches!(
+            session.apply_chunk(
+                &app_hash,
+                &corrupted,
+                CURRENT_STATE_SYNC_VERSION,
+                grove_version
+            ),
+            Err(Error::InvalidChunkEnvelope(_))
+        ));
+
+        let next_chunk_ids = session
+            .apply_chunk(
+                &app_hash,
+                &root_chunk,
+                CURRENT_STATE_SYNC_VERSION,
+                grove_version,
+            )
+            .expect("should apply root chunk");
+        assert_eq!(
+            next_chunk_ids.len(),
+            1,
+            "leaves should be requested together"
+        );
+
+        let leaf_chunks = source
+            .fetch_chunk(
+                &next_chunk_ids[0],
+                None,
+                CURRENT_STATE_SYNC_VERSION,
+                grove_version,
+            )
+            .expect("should fetch leaf chunks");
+        let mut envelopes = ChunkEnvelope::decode_packed(&leaf_chunks).unwrap();
+        envelopes.swap(0, 1);
+        let swapped = pack_nested_bytes(
+            envelopes
+                .iter()
+                .map(|envelope| envelope.encode().unwrap())
+                .collect(),
+        )
+        .unwrap();
+        assert!(matches!(
+            session.apply_chunk(
+                &next_chunk_ids[0],
+                &swapped,
+                CURRENT_STATE_SYNC_VERSION,
+                grove_version
+            ),
+            Err(Error::InvalidChunkEnvelope(
+                ChunkEnvelopeError::ChunkIdMismatch { .. }
+            ))
+        ));
+
+        session
+            .apply_chunk(
+                &next_chunk_ids[0],
+                &leaf_chunks,
+                CURRENT_STATE_SYNC_VERSION,
+                grove_version,
+            )
+            .expect("rejected envelopes should leave the session usable");
+    }
 }
@@ -42,10 +42,10 @@ mod tests {
                 decode_global_chunk_id, decode_vec_ops, encode_global_chunk_id, encode_vec_ops,
                 pack_nested_bytes, path_to_string, unpack_nested_bytes,
             },
-            CURRENT_STATE_SYNC_VERSION,
+            ChunkEnvelope, ChunkEnvelopeError, CURRENT_STATE_SYNC_VERSION,
         },
         tests::make_test_grovedb,
-        Element,
+        Element, Error,
     };
 
     // -----------------------------------------------------------------------
@@ -454,7 +454,7 @@ mod tests {
             .unwrap()
             .expect("should get root hash");
 
-        // Use an unsupported version (0 is not CURRENT_STATE_SYNC_VERSION which is 1)
+        // Use an unsupported version (0 is not CURRENT_STATE_SYNC_VERSION)
         let result = db.fetch_chunk(&root_hash, None, 0, grove_version);
         assert!(
             result.is_err(),
@@ -563,4 +563,189 @@ mod tests {
         assert_eq!(dec_tree_type, tree_type);
         assert_eq!(dec_chunk_ids, chunk_ids);
     }
+
+    // -----------------------------------------------------------------------
+    // ChunkEnvelope
+    // -----------------------------------------------------------------------
+
+    /// An envelope holding a Merk chunk of two proof operators.
+    fn merk_chunk_envelope() -> ChunkEnvelope {
+        let ops = vec![
+            Op::Push(Node::Hash([1u8; 32])),
+            Op::Push(Node::Hash([2u8; 32])),
+        ];
+        let op_bytes = encode_vec_ops(ops).expect("should encode ops");
+        ChunkEnvelope {
+            version: CURRENT_STATE_SYNC_VERSION,
+            chunk_id: vec![7u8; 40],
+            tree_type: TreeType::SumTree,
+            op_count: 2,
+            payload: pack_nested_bytes(vec![op_bytes]).expect("should pack"),
+        }
+    }
+
+    #[test]
+    fn chunk_envelope_round_trip() {
+        let envelope = merk_chunk_envelope();
+        let encoded = envelope.encode().expect("should encode envelope");
+        assert_eq!(ChunkEnvelope::decode(&encoded), Ok(envelope.clone()));
+        assert_eq!(
+            envelope.local_chunks().expect("should split payload").len(),
+            1
+        );
+    }
+
+    #[test]
+    fn chunk_envelope_rejects_every_flipped_byte() {
+        let encoded = merk_chunk_envelope()
+            .encode()
+            .expect("should encode envelope");
+        for index in 0..encoded.len() {
+            let mut corrupted = encoded.clone();
+            corrupted[index] ^= 0x01;
+            assert!(
+                ChunkEnvelope::decode(&corrupted).is_err(),
+                "flipping byte {index} should be detected"
+            );
+        }
+
+        // Content bytes keeping the framing intact are caught by the checksum
+        let mut corrupted = encoded.clone();
+        *corrupted.last_mut().unwrap() ^= 0x01;
+        assert_eq!(
+            ChunkEnvelope::decode(&corrupted),
+            Err(ChunkEnvelopeError::ChecksumMismatch)
+        );
+    }
+
+    #[test]
+    fn chunk_envelope_rejects_truncated_and_trailing_bytes() {
+        let encoded = merk_chunk_envelope()
+            .encode()
+            .expect("should encode envelope");
+        assert_eq!(
+            ChunkEnvelope::decode(&encoded[..1]),
+            Err(ChunkEnvelopeError::Truncated("version"))
+        );
+        assert_eq!(
+            ChunkEnvelope::decode(&encoded[..encoded.len() - 1]),
+            Err(ChunkEnvelopeError::Truncated("checksum"))
+        );
+
+        let mut extended = encoded.clone();
+        extended.extend_from_slice(&[0, 0]);
+        assert_eq!(
+            ChunkEnvelope::decode(&extended),
+            Err(ChunkEnvelopeError::TrailingBytes(2))
+        );
+    }
+
+    #[test]
+    fn chunk_envelope_rejects_other_versions() {
+        let envelope = ChunkEnvelope {
+            version: CURRENT_STATE_SYNC_VERSION + 1,
+            ..merk_chunk_envelope()
+        };
+        let encoded = envelope.encode().expect("should encode envelope");
+        assert_eq!(
+            ChunkEnvelope::decode(&encoded),
+            Err(ChunkEnvelopeError::UnsupportedVersion(
+                CURRENT_STATE_SYNC_VERSION + 1
+            ))
+        );
+    }
+
+    #[test]
+    fn chunk_envelope_rejects_wrong_op_count() {
+        let envelope = ChunkEnvelope {
+            op_count: 3,
+            ..merk_chunk_envelope()
+        };
+        let encoded = envelope.encode().expect("should encode envelope");
+        assert_eq!(
+            ChunkEnvelope::decode(&encoded),
+            Err(ChunkEnvelopeError::OpCountMismatch {
+                declared: 3,
+                actual: 2
+            })
+        );
+
+        // Values of non-Merk trees aren't proof operators
+        let envelope = ChunkEnvelope {
+            tree_type: TreeType::MmrTree,
+            ..merk_chunk_envelope()
+        };
+        let encoded = envelope.encode().expect("should encode envelope");
+        assert_eq!(
+            ChunkEnvelope::decode(&encoded),
+            Err(ChunkEnvelopeError::OpCountMismatch {
+                declared: 2,
+                actual: 0
+            })
+        );
+    }
+
+    #[test]
+    fn chunk_envelope_rejects_unknown_tree_type() {
+        let mut encoded = merk_chunk_envelope()
+            .encode()
+            .expect("should encode envelope");
+        // version, chunk id length and 40 bytes of chunk id come first
+        encoded[2 + 4 + 40] = 0xff;
+        assert_eq!(
+            ChunkEnvelope::decode(&encoded),
+            Err(ChunkEnvelopeError::UnknownTreeType(0xff))
+        );
+    }
+
+    #[test]
+    fn chunk_envelope_check_request() {
+        let envelope = merk_chunk_envelope();
+        assert_eq!(
+            envelope.check_request(&[7u8; 40], TreeType::SumTree),
+            Ok(())
+        );
+        assert!(matches!(
+            envelope.check_request(&[8u8; 40], TreeType::SumTree),
+            Err(ChunkEnvelopeError::ChunkIdMismatch { .. })
+        ));
+        assert_eq!(
+            envelope.check_request(&[7u8; 40], TreeType::NormalTree),
+            Err(ChunkEnvelopeError::TreeTypeMismatch {
+                expected: TreeType::NormalTree,
+                received: TreeType::SumTree
+            })
+        );
+    }
+
+    #[test]
+    fn fetched_chunks_are_enveloped() {
+        let grove_version = GroveVersion::latest();
+        let db = make_test_grovedb(grove_version);
+        let root_hash = db
+            .root_hash(None, grove_version)
+            .unwrap()
+            .expect("should get root hash");
+
+        let chunk_data = db
+            .fetch_chunk(&root_hash, None, CURRENT_STATE_SYNC_VERSION, grove_version)
+            .expect("should fetch root chunk");
+        let envelopes = ChunkEnvelope::decode_packed(&chunk_data).expect("should decode envelopes");
+        assert_eq!(envelopes.len(), 1);
+        assert_eq!(envelopes[0].version, CURRENT_STATE_SYNC_VERSION);
+        assert_eq!(envelopes[0].chunk_id, root_hash.to_vec());
+        assert_eq!(envelopes[0].tree_type, TreeType::NormalTree);
+        let ops = decode_vec_ops(&envelopes[0].local_chunks().unwrap()[0])
+            .expect("should decode root chunk");
+        assert_eq!(envelopes[0].op_count as usize, ops.len());
+
+        let mut corrupted = chunk_data.clone();
+        *corrupted.last_mut().unwrap() ^= 0x01;
+        assert!(matches!(
+            ChunkEnvelope::decode_packed(&corrupted),
+            Err(Error::InvalidChunkEnvelope(
+                ChunkEnvelopeError::ChecksumMismatch
+            ))
+        ));
+    }
 }
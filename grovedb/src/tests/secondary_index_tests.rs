@@ -0,0 +1,302 @@
+//! Tests for secondary indexes maintained by batches.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::{query::QueryItem, Query};
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        batch::QualifiedGroveDbOp,
+        query_result_type::QueryResultType,
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, Error, GroveDb, PathQuery, SecondaryIndex,
+    };
+
+    /// Makes `TEST_LEAF/people` for items valued `city:name` and an empty
+    /// `TEST_LEAF/<index>` tree.
+    fn make_db_with_index_tree(index: &[u8], grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        for key in [b"people".as_slice(), index] {
+            db.insert(
+                [TEST_LEAF].as_ref(),
+                key,
+                Element::empty_tree(),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert a tree");
+        }
+        db
+    }
+
+    fn city(value: &[u8]) -> Option<Vec<u8>> {
+        value
+            .iter()
+            .position(|byte| *byte == b':')
+            .map(|end| value[..end].to_vec())
+    }
+
+    fn people_path() -> Vec<Vec<u8>> {
+        vec![TEST_LEAF.to_vec(), b"people".to_vec()]
+    }
+
+    fn put_person(key: &[u8], value: &[u8]) -> QualifiedGroveDbOp {
+        QualifiedGroveDbOp::insert_or_replace_op(
+            people_path(),
+            key.to_vec(),
+            Element::new_item(value.to_vec()),
+        )
+    }
+
+    fn apply(db: &GroveDb, ops: Vec<QualifiedGroveDbOp>, grove_version: &GroveVersion) {
+        db.apply_batch(ops, None, None, grove_version)
+            .unwrap()
+            .expect("cannot apply the batch");
+    }
+
+    fn index_keys(db: &GroveDb, index: &[u8], grove_version: &GroveVersion) -> Vec<Vec<u8>> {
+        let path_query = PathQuery::new_unsized(
+            vec![TEST_LEAF.to_vec(), index.to_vec()],
+            Query::new_range_full(),
+        );
+        let (elements, _) = db
+            .query_raw(
+                &path_query,
+                true,
+                true,
+                true,
+                QueryResultType::QueryKeyElementPairResultType,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot query the index");
+        elements.to_keys()
+    }
+
+    #[test]
+    fn test_batches_keep_the_index_in_step_with_items() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_index_tree(b"by_city", grove_version);
+        let index_path = vec![TEST_LEAF.to_vec(), b"by_city".to_vec()];
+        db.register_index(SecondaryIndex::new(people_path(), index_path.clone(), city))
+            .expect("cannot register the index");
+
+        apply(
+            &db,
+            vec![
+                put_person(b"alice", b"paris:alice"),
+                put_person(b"bob", b"rome:bob"),
+                put_person(b"carol", b"paris:carol"),
+            ],
+            grove_version,
+        );
+        assert_eq!(
+            index_keys(&db, b"by_city", grove_version),
+            vec![
+                b"\x04romebob".to_vec(),
+                b"\x05parisalice".to_vec(),
+                b"\x05pariscarol".to_vec()
+            ]
+        );
+
+        let mut query = Query::new();
+        query.insert_item(QueryItem::Range(
+            b"\x05paris".to_vec()..b"\x05parit".to_vec(),
+        ));
+        let path_query = PathQuery::new_unsized(index_path.clone(), query);
+        let proof = db
+            .prove_query(&path_query, None, grove_version)
+            .unwrap()
+            .expect("cannot prove the index query");
+        let (root_hash, result_set) = GroveDb::verify_query(&proof, &path_query, grove_version)
+            .expect("cannot verify the proof");
+        assert_eq!(
+            root_hash,
+            db.root_hash(None, grove_version).unwrap().unwrap()
+        );
+        let people: Vec<_> = result_set
+            .into_iter()
+            .map(|(_, _, element)| element)
+            .collect();
+        assert_eq!(
+            people,
+            vec![
+                Some(Element::new_item(b"paris:alice".to_vec())),
+                Some(Element::new_item(b"paris:carol".to_vec()))
+            ]
+        );
+
+        apply(
+            &db,
+            vec![
+                put_person(b"alice", b"rome:alice"),
+                put_person(b"carol", b"paris:carol2"),
+                QualifiedGroveDbOp::delete_op(people_path(), b"bob".to_vec()),
+            ],
+            grove_version,
+        );
+        assert_eq!(
+            index_keys(&db, b"by_city", grove_version),
+            vec![b"\x04romealice".to_vec(), b"\x05pariscarol".to_vec()]
+        );
+        assert_eq!(
+            db.get(
+                index_path.as_slice(),
+                b"\x05pariscarol",
+                None,
+                grove_version
+            )
+            .unwrap()
+            .expect("cannot follow the index entry"),
+            Element::new_item(b"paris:carol2".to_vec())
+        );
+
+        assert!(db.unregister_index(&index_path));
+        apply(&db, vec![put_person(b"dave", b"oslo:dave")], grove_version);
+        assert_eq!(index_keys(&db, b"by_city", grove_version).len(), 2);
+    }
+
+    #[test]
+    fn test_unique_index_rejects_a_taken_key() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_index_tree(b"city_of", grove_version);
+        let index_path = vec![TEST_LEAF.to_vec(), b"city_of".to_vec()];
+        db.register_index(SecondaryIndex::new(people_path(), index_path.clone(), city).unique())
+            .expect("cannot register the index");
+        assert!(db
+            .register_index(SecondaryIndex::new(people_path(), index_path, city))
+            .is_err());
+
+        apply(
+            &db,
+            vec![put_person(b"alice", b"paris:alice")],
+            grove_version,
+        );
+        assert!(db
+            .apply_batch(
+                vec![put_person(b"bob", b"paris:bob")],
+                None,
+                None,
+                grove_version
+            )
+            .unwrap()
+            .is_err());
+        // Rewriting the item keeping its key is fine.
+        apply(
+            &db,
+            vec![put_person(b"alice", b"paris:alice2")],
+            grove_version,
+        );
+
+        apply(
+            &db,
+            vec![
+                QualifiedGroveDbOp::delete_op(people_path(), b"alice".to_vec()),
+                put_person(b"bob", b"paris:bob"),
+            ],
+            grove_version,
+        );
+        assert_eq!(
+            index_keys(&db, b"city_of", grove_version),
+            vec![b"paris".to_vec()]
+        );
+        assert_eq!(
+            db.get(
+                [TEST_LEAF, b"city_of"].as_ref(),
+                b"paris",
+                None,
+                grove_version
+            )
+            .unwrap()
+            .expect("cannot follow the index entry"),
+            Element::new_item(b"paris:bob".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_entries_of_different_extracted_keys_never_collide() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_index_tree(b"by_city", grove_version);
+        let index = SecondaryIndex::new(
+            people_path(),
+            vec![TEST_LEAF.to_vec(), b"by_city".to_vec()],
+            city,
+        );
+        db.register_index(index.clone())
+            .expect("cannot register the index");
+
+        apply(
+            &db,
+            vec![
+                put_person(b"salice", b"pari:salice"),
+                put_person(b"alice", b"paris:alice"),
+            ],
+            grove_version,
+        );
+        assert_eq!(
+            index_keys(&db, b"by_city", grove_version),
+            vec![b"\x04parisalice".to_vec(), b"\x05parisalice".to_vec()]
+        );
+
+        // Entries of one extracted key are found by its prefix alone
+        let prefix = index
+            .entry_key_prefix(b"paris")
+            .expect("the key should be indexable");
+        let mut end = prefix.clone();
+        *end.last_mut().unwrap() += 1;
+        let mut query = Query::new();
+        query.insert_item(QueryItem::Range(prefix..end));
+        let (elements, _) = db
+            .query_item_value(
+                &PathQuery::new_unsized(vec![TEST_LEAF.to_vec(), b"by_city".to_vec()], query),
+                true,
+                true,
+                true,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot query the index");
+        assert_eq!(elements, vec![b"paris:alice".to_vec()]);
+    }
+
+    #[test]
+    fn test_unique_index_rejects_a_key_taken_in_the_same_batch() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_index_tree(b"city_of", grove_version);
+        let index_path = vec![TEST_LEAF.to_vec(), b"city_of".to_vec()];
+        db.register_index(SecondaryIndex::new(people_path(), index_path, city).unique())
+            .expect("cannot register the index");
+
+        let result = db
+            .apply_batch(
+                vec![
+                    put_person(b"alice", b"paris:alice"),
+                    put_person(b"bob", b"paris:bob"),
+                ],
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap();
+        assert!(matches!(
+            result,
+            Err(Error::InvalidBatchOperation(
+                "unique secondary index key is already used by another item"
+            ))
+        ));
+        assert!(index_keys(&db, b"city_of", grove_version).is_empty());
+        assert!(db
+            .get(
+                [TEST_LEAF, b"people"].as_ref(),
+                b"alice",
+                None,
+                grove_version
+            )
+            .unwrap()
+            .is_err());
+    }
+}
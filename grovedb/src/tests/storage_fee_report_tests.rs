@@ -0,0 +1,180 @@
+//! Tests for storage fee reports of batches with epoch based storage flags.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_costs::{
+        storage_cost::{
+            fee_report::StorageFeeReport,
+            removal::{Identifier, UNKNOWN_EPOCH},
+        },
+        OperationCost,
+    };
+    use grovedb_epoch_based_storage_flags::StorageFlags;
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        batch::QualifiedGroveDbOp,
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, GroveDb,
+    };
+
+    const ALICE: Identifier = [1; 32];
+    const BOB: Identifier = [2; 32];
+
+    fn docs_path() -> Vec<Vec<u8>> {
+        vec![TEST_LEAF.to_vec(), b"docs".to_vec()]
+    }
+
+    fn make_db_with_docs(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"docs",
+            Element::empty_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert a tree");
+        db
+    }
+
+    fn put_doc(key: &[u8], value: &[u8], epoch: u16, owner_id: Identifier) -> QualifiedGroveDbOp {
+        QualifiedGroveDbOp::insert_or_replace_op(
+            docs_path(),
+            key.to_vec(),
+            Element::new_item_with_flags(
+                value.to_vec(),
+                StorageFlags::SingleEpochOwned(epoch, owner_id).to_some_element_flags(),
+            ),
+        )
+    }
+
+    fn apply(
+        db: &GroveDb,
+        ops: Vec<QualifiedGroveDbOp>,
+        grove_version: &GroveVersion,
+    ) -> (StorageFeeReport, OperationCost) {
+        let context =
+            db.apply_batch_with_storage_fee_report::<StorageFlags>(ops, None, None, grove_version);
+        let report = context.value.expect("cannot apply the batch");
+        assert_eq!(
+            report.total_added_bytes(),
+            context.cost.storage_cost.added_bytes
+        );
+        assert_eq!(
+            report.total_removed_bytes(),
+            context
+                .cost
+                .storage_cost
+                .removed_bytes
+                .total_removed_bytes()
+        );
+        (report, context.cost)
+    }
+
+    #[test]
+    fn test_added_bytes_are_grouped_by_owner_and_epoch() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_docs(grove_version);
+        let (report, _) = apply(
+            &db,
+            vec![
+                put_doc(b"a", b"first", 1, ALICE),
+                put_doc(b"b", b"other", 1, ALICE),
+                put_doc(b"c", b"third", 2, BOB),
+            ],
+            grove_version,
+        );
+
+        let alice_bytes = report.added_bytes[&(ALICE, 1)];
+        let bob_bytes = report.added_bytes[&(BOB, 2)];
+        assert!(bob_bytes > 0);
+        assert_eq!(alice_bytes, 2 * bob_bytes);
+        assert!(report.added_bytes.keys().all(|section| [
+            (ALICE, 1),
+            (BOB, 2),
+            (Identifier::default(), UNKNOWN_EPOCH)
+        ]
+        .contains(section)));
+        assert!(report.removed_bytes.is_empty());
+    }
+
+    #[test]
+    fn test_removed_bytes_are_refunded_to_their_owner_and_epoch() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_docs(grove_version);
+        let (inserted, _) = apply(
+            &db,
+            vec![
+                put_doc(b"a", b"first", 1, ALICE),
+                put_doc(b"b", b"other", 3, BOB),
+            ],
+            grove_version,
+        );
+
+        let (report, _) = apply(
+            &db,
+            vec![QualifiedGroveDbOp::delete_op(docs_path(), b"b".to_vec())],
+            grove_version,
+        );
+        assert!(inserted.added_bytes.contains_key(&(BOB, 3)));
+        assert_eq!(
+            report.removed_bytes.get(&(BOB, 3)),
+            inserted.added_bytes.get(&(BOB, 3))
+        );
+        assert!(!report.removed_bytes.contains_key(&(ALICE, 1)));
+    }
+
+    #[test]
+    fn test_bytes_added_by_an_update_go_to_the_epoch_of_the_update() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_docs(grove_version);
+        apply(&db, vec![put_doc(b"a", b"first", 1, ALICE)], grove_version);
+
+        let (report, _) = apply(
+            &db,
+            vec![put_doc(b"a", b"a much longer first value", 5, ALICE)],
+            grove_version,
+        );
+        assert!(report.added_bytes[&(ALICE, 5)] > 0);
+        assert!(!report.added_bytes.contains_key(&(ALICE, 1)));
+
+        let element = db
+            .get(docs_path().as_slice(), b"a", None, grove_version)
+            .unwrap()
+            .expect("cannot get the item");
+        let storage_flags = StorageFlags::from_element_flags_ref(
+            element.get_flags().as_ref().expect("expected flags"),
+        )
+        .expect("cannot read the flags")
+        .expect("expected storage flags");
+        assert_eq!(
+            storage_flags
+                .epoch_index_map()
+                .and_then(|epochs| epochs.keys().next_back().copied()),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn test_bytes_of_elements_without_flags_are_not_attributed() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_docs(grove_version);
+        let (report, cost) = apply(
+            &db,
+            vec![QualifiedGroveDbOp::insert_or_replace_op(
+                docs_path(),
+                b"a".to_vec(),
+                Element::new_item(b"no flags".to_vec()),
+            )],
+            grove_version,
+        );
+        assert_eq!(
+            report.added_bytes.keys().collect::<Vec<_>>(),
+            vec![&(Identifier::default(), UNKNOWN_EPOCH)]
+        );
+        assert!(cost.storage_cost.added_bytes > 0);
+    }
+}
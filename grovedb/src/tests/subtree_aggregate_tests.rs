@@ -0,0 +1,244 @@
+//! Tests for aggregating the results of path queries by subtree.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_merk::proofs::Query;
+    use grovedb_version::version::GroveVersion;
+
+    use crate::{
+        query_result_type::{QueryResultType, SubtreeAggregate},
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, GroveDb, PathQuery, QueryItem, SizedQuery,
+    };
+
+    /// Makes `TEST_LEAF/owners` holding:
+    /// - `o0`, a count sum tree of 100 sum items, an item and a big sum tree
+    ///   `big` of a sum item, whose sum is not part of the sum of `o0`,
+    /// - `o1`, an empty count sum tree,
+    /// - `o2`, a sum tree of 10 sum items,
+    /// - `o3`, a tree of 5 items and a count tree `counted` of 3 items.
+    fn make_db_with_owners(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        let insert = |path: &[&[u8]], key: &[u8], element: Element| {
+            db.insert(path, key, element, None, None, grove_version)
+                .unwrap()
+                .expect("cannot insert an element");
+        };
+        insert(&[TEST_LEAF], b"owners", Element::empty_tree());
+        let owners: &[&[u8]] = &[TEST_LEAF, b"owners"];
+        insert(owners, b"o0", Element::empty_count_sum_tree());
+        insert(owners, b"o1", Element::empty_count_sum_tree());
+        insert(owners, b"o2", Element::empty_sum_tree());
+        insert(owners, b"o3", Element::empty_tree());
+        for n in 0u8..100 {
+            insert(
+                &[TEST_LEAF, b"owners", b"o0"],
+                &[n],
+                Element::new_sum_item(n as i64 - 20),
+            );
+        }
+        insert(
+            &[TEST_LEAF, b"owners", b"o0"],
+            b"name",
+            Element::new_item(b"first owner".to_vec()),
+        );
+        insert(
+            &[TEST_LEAF, b"owners", b"o0"],
+            b"big",
+            Element::empty_big_sum_tree(),
+        );
+        insert(
+            &[TEST_LEAF, b"owners", b"o0", b"big"],
+            b"balance",
+            Element::new_sum_item(i64::MAX),
+        );
+        for n in 0u8..10 {
+            insert(
+                &[TEST_LEAF, b"owners", b"o2"],
+                &[n],
+                Element::new_sum_item(1000),
+            );
+        }
+        for n in 0u8..5 {
+            insert(
+                &[TEST_LEAF, b"owners", b"o3"],
+                &[n],
+                Element::new_item(vec![n]),
+            );
+        }
+        insert(
+            &[TEST_LEAF, b"owners", b"o3"],
+            b"counted",
+            Element::empty_count_tree(),
+        );
+        for n in 0u8..3 {
+            insert(
+                &[TEST_LEAF, b"owners", b"o3", b"counted"],
+                &[n],
+                Element::new_item(vec![n]),
+            );
+        }
+        db
+    }
+
+    fn owners_query() -> PathQuery {
+        let mut query = Query::new_range_full();
+        query.set_subquery(Query::new_range_full());
+        PathQuery::new_unsized(vec![TEST_LEAF.to_vec(), b"owners".to_vec()], query)
+    }
+
+    fn owner_path(owner: &[u8]) -> Vec<Vec<u8>> {
+        vec![TEST_LEAF.to_vec(), b"owners".to_vec(), owner.to_vec()]
+    }
+
+    #[test]
+    fn test_subtree_aggregates_of_owners() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_owners(grove_version);
+
+        let aggregates = db
+            .query_subtree_aggregates(&owners_query(), None, grove_version)
+            .unwrap()
+            .expect("cannot query the aggregates");
+        assert_eq!(
+            aggregates,
+            vec![
+                SubtreeAggregate {
+                    path: owner_path(b"o0"),
+                    count: 102,
+                    sum: (0..100).map(|n| n - 20).sum(),
+                    min_key: vec![0],
+                    max_key: b"name".to_vec(),
+                },
+                SubtreeAggregate {
+                    path: owner_path(b"o2"),
+                    count: 10,
+                    sum: 10_000,
+                    min_key: vec![0],
+                    max_key: vec![9],
+                },
+                SubtreeAggregate {
+                    path: owner_path(b"o3"),
+                    count: 8,
+                    sum: 0,
+                    min_key: vec![0],
+                    max_key: b"counted".to_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stored_aggregates_match_the_results() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_owners(grove_version);
+
+        let query_result = db.query_raw(
+            &owners_query(),
+            true,
+            true,
+            true,
+            QueryResultType::QueryPathKeyElementTrioResultType,
+            None,
+            grove_version,
+        );
+        let query_cost = query_result.cost.clone();
+        let (results, _) = query_result.unwrap().expect("cannot run the query");
+        let mut expected: Vec<SubtreeAggregate> = Vec::new();
+        for (path, key, element) in results.to_path_key_elements() {
+            let (count, sum) = element.count_sum_value_or_default();
+            match expected.last_mut() {
+                Some(aggregate) if aggregate.path == path => {
+                    aggregate.count += count;
+                    aggregate.sum += sum as i128;
+                    aggregate.max_key = key;
+                }
+                _ => expected.push(SubtreeAggregate {
+                    path,
+                    count,
+                    sum: sum as i128,
+                    min_key: key.clone(),
+                    max_key: key,
+                }),
+            }
+        }
+
+        let aggregates = db.query_subtree_aggregates(&owners_query(), None, grove_version);
+        // The count sum tree `o0` is not read
+        assert!(aggregates.cost.storage_loaded_bytes < query_cost.storage_loaded_bytes / 2);
+        assert_eq!(
+            aggregates.unwrap().expect("cannot query the aggregates"),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_subtree_aggregates_proof() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_owners(grove_version);
+        let root_hash = db.root_hash(None, grove_version).unwrap().unwrap();
+
+        let proof = db
+            .prove_subtree_aggregates(&owners_query(), grove_version)
+            .unwrap()
+            .expect("cannot prove the aggregates");
+        let (proved_root_hash, aggregates) =
+            GroveDb::verify_subtree_aggregates(&proof, &owners_query(), grove_version)
+                .expect("cannot verify the aggregates");
+        assert_eq!(proved_root_hash, root_hash);
+        assert_eq!(
+            aggregates,
+            db.query_subtree_aggregates(&owners_query(), None, grove_version)
+                .unwrap()
+                .expect("cannot query the aggregates")
+        );
+
+        // The elements of the count sum tree `o0` are not proven, so the proof
+        // grows with the depth of `o0` rather than with its elements
+        let results_proof = |db: &TempGroveDb| {
+            db.prove_query(&owners_query(), None, grove_version)
+                .unwrap()
+                .expect("cannot prove the results")
+        };
+        let small_results_proof = results_proof(&db);
+        for n in 100u8..=255 {
+            db.insert(
+                &[TEST_LEAF, b"owners", b"o0"],
+                &[n],
+                Element::new_sum_item(1),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert an element");
+        }
+        let large_proof = db
+            .prove_subtree_aggregates(&owners_query(), grove_version)
+            .unwrap()
+            .expect("cannot prove the aggregates");
+        let large_results_proof = results_proof(&db);
+        assert!(
+            (large_proof.len() - proof.len()) * 5
+                < large_results_proof.len() - small_results_proof.len()
+        );
+
+        // Aggregates proven for the whole of `o0` don't prove those of a range
+        let mut first_keys = Query::new_range_full();
+        first_keys.set_subquery(Query::new_single_query_item(QueryItem::RangeTo(..vec![50])));
+        let first_keys = PathQuery::new_unsized(owners_query().path, first_keys);
+        assert!(GroveDb::verify_subtree_aggregates(&proof, &first_keys, grove_version).is_err());
+
+        let mut limited = owners_query();
+        limited.query = SizedQuery::new(limited.query.query, Some(10), None);
+        assert!(db
+            .query_subtree_aggregates(&limited, None, grove_version)
+            .unwrap()
+            .is_err());
+        assert!(db
+            .prove_subtree_aggregates(&limited, grove_version)
+            .unwrap()
+            .is_err());
+        assert!(GroveDb::verify_subtree_aggregates(&proof, &limited, grove_version).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+//! Tests for the registry of subtrees and for listing subtrees through it.
+
+#[cfg(test)]
+mod tests {
+    use grovedb_path::SubtreePath;
+    use grovedb_storage::{Storage, StorageContext};
+    use grovedb_version::version::{v3::GROVE_V3, v4::GROVE_V4, GroveVersion};
+    use tempfile::TempDir;
+
+    use crate::{
+        batch::{QualifiedGroveDbOp, SubelementsDeletionBehavior},
+        operations::{delete::DeleteOptions, insert::InsertOptions},
+        tests::{make_test_grovedb, TempGroveDb, TEST_LEAF},
+        Element, GroveDb, RegisteredSubtree, StorageBackend, TreeType,
+    };
+
+    fn insert_tree(db: &GroveDb, path: &[&[u8]], key: &[u8], grove_version: &GroveVersion) {
+        db.insert(path, key, Element::empty_tree(), None, None, grove_version)
+            .unwrap()
+            .expect("cannot insert a tree");
+    }
+
+    /// Makes `TEST_LEAF/a/b/c` and `TEST_LEAF/d`, with an item in each of
+    /// them.
+    fn make_db_with_nested_trees(grove_version: &GroveVersion) -> TempGroveDb {
+        let db = make_test_grovedb(grove_version);
+        insert_tree(&db, &[TEST_LEAF], b"a", grove_version);
+        insert_tree(&db, &[TEST_LEAF, b"a"], b"b", grove_version);
+        insert_tree(&db, &[TEST_LEAF, b"a", b"b"], b"c", grove_version);
+        insert_tree(&db, &[TEST_LEAF], b"d", grove_version);
+        for path in [
+            [TEST_LEAF].as_slice(),
+            &[TEST_LEAF, b"a"],
+            &[TEST_LEAF, b"a", b"b"],
+            &[TEST_LEAF, b"a", b"b", b"c"],
+            &[TEST_LEAF, b"d"],
+        ] {
+            db.insert(
+                path,
+                b"item",
+                Element::new_item(b"value".to_vec()),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert an item");
+        }
+        db
+    }
+
+    fn paths(subtrees: &[RegisteredSubtree]) -> Vec<Vec<&[u8]>> {
+        subtrees
+            .iter()
+            .map(|subtree| subtree.path.iter().map(Vec::as_slice).collect())
+            .collect()
+    }
+
+    fn list(
+        db: &GroveDb,
+        path: &[&[u8]],
+        depth: Option<u16>,
+        grove_version: &GroveVersion,
+    ) -> Vec<RegisteredSubtree> {
+        db.list_subtrees(path.into(), depth, None, grove_version)
+            .unwrap()
+            .expect("cannot list subtrees")
+    }
+
+    #[test]
+    fn test_list_subtrees_breadth_first_down_to_depth() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_nested_trees(grove_version);
+
+        let subtrees = list(&db, &[TEST_LEAF], None, grove_version);
+        assert_eq!(
+            paths(&subtrees),
+            vec![
+                vec![TEST_LEAF, b"a"],
+                vec![TEST_LEAF, b"d"],
+                vec![TEST_LEAF, b"a", b"b"],
+                vec![TEST_LEAF, b"a", b"b", b"c"],
+            ]
+        );
+        for subtree in &subtrees {
+            assert_eq!(
+                subtree.prefix,
+                StorageBackend::build_prefix(subtree.path.as_slice().into()).unwrap()
+            );
+        }
+
+        assert_eq!(
+            paths(&list(&db, &[TEST_LEAF], Some(1), grove_version)),
+            vec![vec![TEST_LEAF, b"a"], vec![TEST_LEAF, b"d"]]
+        );
+        assert_eq!(
+            paths(&list(&db, &[TEST_LEAF], Some(2), grove_version)).len(),
+            3
+        );
+        assert!(list(&db, &[TEST_LEAF], Some(0), grove_version).is_empty());
+        assert!(list(&db, &[TEST_LEAF, b"d"], None, grove_version).is_empty());
+    }
+
+    #[test]
+    fn test_listing_reads_subtrees_and_not_elements() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_nested_trees(grove_version);
+        let cost_of_listing = |db: &GroveDb| {
+            db.list_subtrees([TEST_LEAF].as_ref().into(), None, None, grove_version)
+                .cost
+                .seek_count
+        };
+        let seeks = cost_of_listing(&db);
+
+        for n in 0u16..200 {
+            db.insert(
+                [TEST_LEAF, b"a"].as_ref(),
+                &n.to_be_bytes(),
+                Element::new_item(b"value".to_vec()),
+                None,
+                None,
+                grove_version,
+            )
+            .unwrap()
+            .expect("cannot insert an item");
+        }
+        assert_eq!(cost_of_listing(&db), seeks);
+    }
+
+    #[test]
+    fn test_registry_writes_are_only_charged_from_grove_v4() {
+        let cost_of_inserting_a_tree = |grove_version: &GroveVersion| {
+            let db = make_test_grovedb(grove_version);
+            let cost = db
+                .insert(
+                    [TEST_LEAF].as_ref(),
+                    b"a",
+                    Element::empty_tree(),
+                    None,
+                    None,
+                    grove_version,
+                )
+                .cost_as_result()
+                .expect("cannot insert a tree");
+            // The registry is written whether or not it is charged
+            assert_eq!(
+                paths(&list(&db, &[TEST_LEAF], None, grove_version)),
+                vec![vec![TEST_LEAF, b"a"]]
+            );
+            cost
+        };
+
+        let uncharged = cost_of_inserting_a_tree(&GROVE_V3);
+        let charged = cost_of_inserting_a_tree(&GROVE_V4);
+        // 51 for the entry key behind the prefix of the parent, with its size
+        // 34 for the tree prefix and whether the tree may hold subtrees, with
+        //   their size
+        assert_eq!(charged.seek_count, uncharged.seek_count + 1);
+        assert_eq!(
+            charged.storage_cost.added_bytes,
+            uncharged.storage_cost.added_bytes + 85
+        );
+    }
+
+    #[test]
+    fn test_deletes_unregister_the_subtrees_they_remove() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_nested_trees(grove_version);
+
+        db.delete(
+            [TEST_LEAF].as_ref(),
+            b"a",
+            Some(DeleteOptions {
+                allow_deleting_non_empty_trees: true,
+                deleting_non_empty_trees_returns_error: false,
+                ..Default::default()
+            }),
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot delete a non-empty tree");
+        assert_eq!(
+            paths(&list(&db, &[TEST_LEAF], None, grove_version)),
+            vec![vec![TEST_LEAF, b"d"]]
+        );
+
+        // A tree inserted again at the same path starts without subtrees
+        insert_tree(&db, &[TEST_LEAF], b"a", grove_version);
+        assert_eq!(
+            paths(&list(&db, &[TEST_LEAF], None, grove_version)),
+            vec![vec![TEST_LEAF, b"a"], vec![TEST_LEAF, b"d"]]
+        );
+    }
+
+    #[test]
+    fn test_batches_register_and_unregister_subtrees() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_nested_trees(grove_version);
+
+        db.apply_batch(
+            vec![
+                QualifiedGroveDbOp::insert_or_replace_op(
+                    vec![TEST_LEAF.to_vec(), b"d".to_vec()],
+                    b"e".to_vec(),
+                    Element::empty_sum_tree(),
+                ),
+                QualifiedGroveDbOp::delete_tree_op(
+                    vec![TEST_LEAF.to_vec()],
+                    b"a".to_vec(),
+                    TreeType::NormalTree,
+                    SubelementsDeletionBehavior::DeleteChildren,
+                ),
+            ],
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot apply the batch");
+
+        assert_eq!(
+            paths(&list(&db, &[TEST_LEAF], None, grove_version)),
+            vec![vec![TEST_LEAF, b"d"], vec![TEST_LEAF, b"d", b"e"]]
+        );
+    }
+
+    #[test]
+    fn test_trees_overwritten_by_other_elements_are_unregistered() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_nested_trees(grove_version);
+
+        // Batches don't check for overwritten trees by default
+        db.apply_batch(
+            vec![QualifiedGroveDbOp::insert_or_replace_op(
+                vec![TEST_LEAF.to_vec()],
+                b"a".to_vec(),
+                Element::new_item(b"value".to_vec()),
+            )],
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot apply the batch");
+        assert_eq!(
+            paths(&list(&db, &[TEST_LEAF], None, grove_version)),
+            vec![vec![TEST_LEAF, b"d"]]
+        );
+
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"d",
+            Element::new_item(b"value".to_vec()),
+            Some(InsertOptions {
+                validate_insertion_does_not_override_tree: false,
+                ..Default::default()
+            }),
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot overwrite a tree");
+        assert!(list(&db, &[TEST_LEAF], None, grove_version).is_empty());
+    }
+
+    #[test]
+    fn test_subtrees_of_non_merk_trees_are_not_looked_up() {
+        let grove_version = GroveVersion::latest();
+        let db = make_db_with_nested_trees(grove_version);
+        let cost_of_listing = |db: &GroveDb| {
+            db.list_subtrees([TEST_LEAF].as_ref().into(), None, None, grove_version)
+                .cost
+                .seek_count
+        };
+        let seeks = cost_of_listing(&db);
+
+        db.insert(
+            [TEST_LEAF].as_ref(),
+            b"m",
+            Element::empty_mmr_tree(),
+            None,
+            None,
+            grove_version,
+        )
+        .unwrap()
+        .expect("cannot insert an MMR tree");
+        let seeks_with_mmr_tree = cost_of_listing(&db);
+        insert_tree(&db, &[TEST_LEAF], b"n", grove_version);
+
+        // A Merk tree is looked up for subtrees, costing more than the MMR
+        // tree which is only listed
+        assert!(cost_of_listing(&db) - seeks_with_mmr_tree > seeks_with_mmr_tree - seeks);
+        assert_eq!(
+            paths(&list(&db, &[TEST_LEAF], Some(1), grove_version)),
+            vec![
+                vec![TEST_LEAF, b"a"],
+                vec![TEST_LEAF, b"d"],
+                vec![TEST_LEAF, b"m"],
+                vec![TEST_LEAF, b"n"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_registry_is_built_when_opening_a_database_without_it() {
+        let grove_version = GroveVersion::latest();
+        let tmp_dir = TempDir::new().expect("cannot open tempdir");
+        let registered = {
+            let db = GroveDb::open(tmp_dir.path()).expect("cannot open grovedb");
+            insert_tree(&db, &[], TEST_LEAF, grove_version);
+            insert_tree(&db, &[TEST_LEAF], b"a", grove_version);
+            insert_tree(&db, &[TEST_LEAF, b"a"], b"b", grove_version);
+            let registered = list(&db, &[], None, grove_version);
+            assert_eq!(registered.len(), 3);
+
+            // Drop the marker and an entry, like in a database from before the
+            // registry which never registered anything
+            let tx = db.start_transaction();
+            db.db
+                .get_immediate_storage_context(SubtreePath::empty(), &tx)
+                .unwrap()
+                .delete_meta(b"\0grovedb_subtree_registry", None)
+                .unwrap()
+                .expect("cannot delete the registry marker");
+            db.db
+                .get_immediate_storage_context([TEST_LEAF, b"a"].as_ref().into(), &tx)
+                .unwrap()
+                .delete_meta(b"\0grovedb_subtree/b", None)
+                .unwrap()
+                .expect("cannot delete a registry entry");
+            db.commit_transaction(tx)
+                .unwrap()
+                .expect("cannot commit the transaction");
+            assert_eq!(list(&db, &[], None, grove_version).len(), 2);
+            registered
+        };
+
+        let db = GroveDb::open(tmp_dir.path()).expect("cannot open grovedb again");
+        assert_eq!(list(&db, &[], None, grove_version), registered);
+    }
+}
@@ -2,7 +2,7 @@ pub(crate) mod compat;
 
 use grovedb_storage::Storage;
 
-use crate::{Error, RocksDbStorage, Transaction, TransactionArg};
+use crate::{Error, StorageBackend, Transaction, TransactionArg};
 
 pub(crate) enum TxRef<'a, 'db: 'a> {
     Owned(Transaction<'db>),
@@ -10,7 +10,7 @@ pub(crate) enum TxRef<'a, 'db: 'a> {
 }
 
 impl<'a, 'db> TxRef<'a, 'db> {
-    pub(crate) fn new(db: &'db RocksDbStorage, transaction_arg: TransactionArg<'db, 'a>) -> Self {
+    pub(crate) fn new(db: &'db StorageBackend, transaction_arg: TransactionArg<'db, 'a>) -> Self {
         if let Some(tx) = transaction_arg {
             Self::Borrowed(tx)
         } else {
@@ -21,9 +21,7 @@ impl<'a, 'db> TxRef<'a, 'db> {
     /// Commit the transaction if it wasn't received from outside
     pub(crate) fn commit_local(self) -> Result<(), Error> {
         match self {
-            TxRef::Owned(tx) => tx
-                .commit()
-                .map_err(|e| grovedb_storage::Error::from(e).into()),
+            TxRef::Owned(tx) => tx.commit().map_err(Into::into),
             TxRef::Borrowed(_) => Ok(()),
         }
     }
@@ -8,7 +8,7 @@ use grovedb_merk::{
 };
 use grovedb_path::SubtreePath;
 use grovedb_storage::{
-    rocksdb_storage::{PrefixedRocksDbTransactionContext, RocksDbStorage},
+    backend::{PrefixedBackendTransactionContext, StorageBackend},
     Storage, StorageBatch,
 };
 use grovedb_version::version::GroveVersion;
@@ -28,12 +28,12 @@ pub(crate) trait OpenMerkErrorsCompat {
 }
 
 pub(crate) fn open_merk<'db, 'b, B, C: OpenMerkErrorsCompat>(
-    db: &'db RocksDbStorage,
+    db: &'db StorageBackend,
     path: SubtreePath<'b, B>,
     tx: &'db Transaction,
     batch: Option<&'db StorageBatch>,
     grove_version: &GroveVersion,
-) -> CostResult<Merk<PrefixedRocksDbTransactionContext<'db>>, Error>
+) -> CostResult<Merk<PrefixedBackendTransactionContext<'db>>, Error>
 where
     B: AsRef<[u8]> + 'b,
 {
@@ -82,12 +82,12 @@ where
 /// Opens a subtree with errors returned compatible to now removed
 /// `merk_optional_tx!` macro.
 pub(crate) fn merk_optional_tx<'db, 'b, B>(
-    db: &'db RocksDbStorage,
+    db: &'db StorageBackend,
     path: SubtreePath<'b, B>,
     tx: &'db Transaction,
     batch: Option<&'db StorageBatch>,
     grove_version: &GroveVersion,
-) -> CostResult<Merk<PrefixedRocksDbTransactionContext<'db>>, Error>
+) -> CostResult<Merk<PrefixedBackendTransactionContext<'db>>, Error>
 where
     B: AsRef<[u8]> + 'b,
 {
@@ -120,12 +120,12 @@ where
 /// Opens a subtree with errors returned compatible to now removed
 /// `merk_optional_tx_path_not_empty!` macro.
 pub(crate) fn merk_optional_tx_path_not_empty<'db, 'b, B>(
-    db: &'db RocksDbStorage,
+    db: &'db StorageBackend,
     path: SubtreePath<'b, B>,
     tx: &'db Transaction,
     batch: Option<&'db StorageBatch>,
     grove_version: &GroveVersion,
-) -> CostResult<Merk<PrefixedRocksDbTransactionContext<'db>>, Error>
+) -> CostResult<Merk<PrefixedBackendTransactionContext<'db>>, Error>
 where
     B: AsRef<[u8]> + 'b,
 {
@@ -180,6 +180,40 @@ pub enum Element {
         #[serde_as(as = "Option<Base64>")]
         element_flags: Option<Vec<u8>>,
     },
+    MinMaxTree {
+        #[serde_as(as = "Option<Base64>")]
+        root_key: Option<Key>,
+        min: i64,
+        max: i64,
+        #[serde_as(as = "Option<Base64>")]
+        element_flags: Option<Vec<u8>>,
+    },
+    MinMaxItem {
+        value: i64,
+        #[serde_as(as = "Option<Base64>")]
+        element_flags: Option<Vec<u8>>,
+    },
+    ProvableSumTree {
+        #[serde_as(as = "Option<Base64>")]
+        root_key: Option<Key>,
+        sum: i64,
+        #[serde_as(as = "Option<Base64>")]
+        element_flags: Option<Vec<u8>>,
+    },
+    ProvableBigSumTree {
+        #[serde_as(as = "Option<Base64>")]
+        root_key: Option<Key>,
+        sum: i128,
+        #[serde_as(as = "Option<Base64>")]
+        element_flags: Option<Vec<u8>>,
+    },
+    ItemWithExpiry {
+        #[serde_as(as = "Base64")]
+        value: Vec<u8>,
+        expiry: u64,
+        #[serde_as(as = "Option<Base64>")]
+        element_flags: Option<Vec<u8>>,
+    },
     Reference(Reference),
 }
 
@@ -310,6 +344,9 @@ pub enum TreeFeatureType {
     CountedSummedMerkNode(u64, i64),
     ProvableCountedMerkNode(u64),
     ProvableCountedSummedMerkNode(u64, i64),
+    MinMaxMerkNode(i64, i64),
+    ProvableSummedMerkNode(i64),
+    ProvableBigSummedMerkNode(i128),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -233,7 +233,17 @@ fn get_key_from_node(node: &Node) -> Option<Vec<u8>> {
         Node::KVRefValueHash(key, ..) => Some(key.clone()),
         Node::KVCount(key, ..) => Some(key.clone()),
         Node::KVRefValueHashCount(key, ..) => Some(key.clone()),
-        Node::Hash(_) | Node::KVHash(_) | Node::KVHashCount(..) => None,
+        Node::KVMinMax(key, ..) => Some(key.clone()),
+        Node::KVRefValueHashMinMax(key, ..) => Some(key.clone()),
+        Node::KVDigestMinMax(key, ..) => Some(key.clone()),
+        Node::KVSum(key, ..) => Some(key.clone()),
+        Node::KVRefValueHashSum(key, ..) => Some(key.clone()),
+        Node::KVDigestSum(key, ..) => Some(key.clone()),
+        Node::Hash(_)
+        | Node::KVHash(_)
+        | Node::KVHashCount(..)
+        | Node::KVHashMinMax(..)
+        | Node::KVHashSum(..) => None,
     }
 }
 
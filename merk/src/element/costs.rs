@@ -11,8 +11,9 @@ use crate::{
     },
     tree_type::{
         BIG_SUM_TREE_COST_SIZE, BULK_APPEND_TREE_COST_SIZE, COMMITMENT_TREE_COST_SIZE,
-        COUNT_SUM_TREE_COST_SIZE, COUNT_TREE_COST_SIZE, DENSE_TREE_COST_SIZE, MMR_TREE_COST_SIZE,
-        SUM_ITEM_COST_SIZE, SUM_TREE_COST_SIZE, TREE_COST_SIZE,
+        COUNT_SUM_TREE_COST_SIZE, COUNT_TREE_COST_SIZE, DENSE_TREE_COST_SIZE,
+        MIN_MAX_TREE_COST_SIZE, MMR_TREE_COST_SIZE, PROVABLE_BIG_SUM_TREE_COST_SIZE,
+        PROVABLE_SUM_TREE_COST_SIZE, SUM_ITEM_COST_SIZE, SUM_TREE_COST_SIZE, TREE_COST_SIZE,
     },
     Error,
 };
@@ -70,6 +71,9 @@ impl ElementCostPrivateExtensions for Element {
             Element::CountSumTree(..) => Ok(COUNT_SUM_TREE_COST_SIZE),
             Element::ProvableCountTree(..) => Ok(COUNT_TREE_COST_SIZE),
             Element::ProvableCountSumTree(..) => Ok(COUNT_SUM_TREE_COST_SIZE),
+            Element::MinMaxTree(..) => Ok(MIN_MAX_TREE_COST_SIZE),
+            Element::ProvableSumTree(..) => Ok(PROVABLE_SUM_TREE_COST_SIZE),
+            Element::ProvableBigSumTree(..) => Ok(PROVABLE_BIG_SUM_TREE_COST_SIZE),
             _ => Err(Error::CorruptedCodeExecution(
                 "trying to get tree cost from non tree element",
             )),
@@ -172,6 +176,39 @@ impl ElementCostExtensions for Element {
                     key_len, value_len, node_type,
                 )
             }
+            Element::MinMaxTree(.., flags) => {
+                let flags_len = flags.map_or(0, |flags| {
+                    let flags_len = flags.len() as u32;
+                    flags_len + flags_len.required_space() as u32
+                });
+                let value_len = MIN_MAX_TREE_COST_SIZE + flags_len;
+                let key_len = key.len() as u32;
+                KV::layered_value_byte_cost_size_for_key_and_value_lengths(
+                    key_len, value_len, node_type,
+                )
+            }
+            Element::ProvableSumTree(_, _sum_value, flags) => {
+                let flags_len = flags.map_or(0, |flags| {
+                    let flags_len = flags.len() as u32;
+                    flags_len + flags_len.required_space() as u32
+                });
+                let value_len = PROVABLE_SUM_TREE_COST_SIZE + flags_len;
+                let key_len = key.len() as u32;
+                KV::layered_value_byte_cost_size_for_key_and_value_lengths(
+                    key_len, value_len, node_type,
+                )
+            }
+            Element::ProvableBigSumTree(_, _sum_value, flags) => {
+                let flags_len = flags.map_or(0, |flags| {
+                    let flags_len = flags.len() as u32;
+                    flags_len + flags_len.required_space() as u32
+                });
+                let value_len = PROVABLE_BIG_SUM_TREE_COST_SIZE + flags_len;
+                let key_len = key.len() as u32;
+                KV::layered_value_byte_cost_size_for_key_and_value_lengths(
+                    key_len, value_len, node_type,
+                )
+            }
             Element::CommitmentTree(_, _, flags) => {
                 let flags_len = flags.map_or(0, |flags| {
                     let flags_len = flags.len() as u32;
@@ -283,7 +320,10 @@ impl ElementCostExtensions for Element {
             | Element::CommitmentTree(..)
             | Element::MmrTree(..)
             | Element::BulkAppendTree(..)
-            | Element::DenseAppendOnlyFixedSizeTree(..) => Some(cost),
+            | Element::DenseAppendOnlyFixedSizeTree(..)
+            | Element::MinMaxTree(..)
+            | Element::ProvableSumTree(..)
+            | Element::ProvableBigSumTree(..) => Some(cost),
             _ => None,
         }
     }
@@ -309,6 +349,9 @@ impl ElementCostExtensions for Element {
             Element::CountSumTree(..) => Some(LayeredValueDefinedCost(cost)),
             Element::ProvableCountTree(..) => Some(LayeredValueDefinedCost(cost)),
             Element::ProvableCountSumTree(..) => Some(LayeredValueDefinedCost(cost)),
+            Element::MinMaxTree(..) => Some(LayeredValueDefinedCost(cost)),
+            Element::ProvableSumTree(..) => Some(LayeredValueDefinedCost(cost)),
+            Element::ProvableBigSumTree(..) => Some(LayeredValueDefinedCost(cost)),
             Element::SumItem(..) => Some(SpecializedValueDefinedCost(cost)),
             Element::ItemWithSumItem(item, ..) => {
                 let item_len = item.len() as u32;
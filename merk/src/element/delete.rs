@@ -71,9 +71,10 @@ impl ElementDeleteFromStorageExtensions for Element {
             | (TreeType::CommitmentTree(_), true)
             | (TreeType::MmrTree, true)
             | (TreeType::BulkAppendTree(_), true)
-            | (TreeType::DenseAppendOnlyFixedSizeTree(_), true) => {
-                Op::DeleteLayeredMaybeSpecialized
-            }
+            | (TreeType::DenseAppendOnlyFixedSizeTree(_), true)
+            | (TreeType::MinMaxTree, true)
+            | (TreeType::ProvableSumTree, true)
+            | (TreeType::ProvableBigSumTree, true) => Op::DeleteLayeredMaybeSpecialized,
             (TreeType::SumTree, false)
             | (TreeType::BigSumTree, false)
             | (TreeType::CountTree, false)
@@ -83,7 +84,10 @@ impl ElementDeleteFromStorageExtensions for Element {
             | (TreeType::CommitmentTree(_), false)
             | (TreeType::MmrTree, false)
             | (TreeType::BulkAppendTree(_), false)
-            | (TreeType::DenseAppendOnlyFixedSizeTree(_), false) => Op::DeleteMaybeSpecialized,
+            | (TreeType::DenseAppendOnlyFixedSizeTree(_), false)
+            | (TreeType::MinMaxTree, false)
+            | (TreeType::ProvableSumTree, false)
+            | (TreeType::ProvableBigSumTree, false) => Op::DeleteMaybeSpecialized,
         };
         let batch = [(key, op)];
         // todo not sure we get it again, we need to see if this is necessary
@@ -141,9 +145,10 @@ impl ElementDeleteFromStorageExtensions for Element {
             | (TreeType::CommitmentTree(_), true)
             | (TreeType::MmrTree, true)
             | (TreeType::BulkAppendTree(_), true)
-            | (TreeType::DenseAppendOnlyFixedSizeTree(_), true) => {
-                Op::DeleteLayeredMaybeSpecialized
-            }
+            | (TreeType::DenseAppendOnlyFixedSizeTree(_), true)
+            | (TreeType::MinMaxTree, true)
+            | (TreeType::ProvableSumTree, true)
+            | (TreeType::ProvableBigSumTree, true) => Op::DeleteLayeredMaybeSpecialized,
             (TreeType::SumTree, false)
             | (TreeType::BigSumTree, false)
             | (TreeType::CountTree, false)
@@ -153,7 +158,10 @@ impl ElementDeleteFromStorageExtensions for Element {
             | (TreeType::CommitmentTree(_), false)
             | (TreeType::MmrTree, false)
             | (TreeType::BulkAppendTree(_), false)
-            | (TreeType::DenseAppendOnlyFixedSizeTree(_), false) => Op::DeleteMaybeSpecialized,
+            | (TreeType::DenseAppendOnlyFixedSizeTree(_), false)
+            | (TreeType::MinMaxTree, false)
+            | (TreeType::ProvableSumTree, false)
+            | (TreeType::ProvableBigSumTree, false) => Op::DeleteMaybeSpecialized,
         };
         let batch = [(key, op)];
         // todo not sure we get it again, we need to see if this is necessary
@@ -207,9 +215,10 @@ impl ElementDeleteFromStorageExtensions for Element {
             | (TreeType::CommitmentTree(_), true)
             | (TreeType::MmrTree, true)
             | (TreeType::BulkAppendTree(_), true)
-            | (TreeType::DenseAppendOnlyFixedSizeTree(_), true) => {
-                Op::DeleteLayeredMaybeSpecialized
-            }
+            | (TreeType::DenseAppendOnlyFixedSizeTree(_), true)
+            | (TreeType::MinMaxTree, true)
+            | (TreeType::ProvableSumTree, true)
+            | (TreeType::ProvableBigSumTree, true) => Op::DeleteLayeredMaybeSpecialized,
             (TreeType::SumTree, false)
             | (TreeType::BigSumTree, false)
             | (TreeType::CountTree, false)
@@ -219,7 +228,10 @@ impl ElementDeleteFromStorageExtensions for Element {
             | (TreeType::CommitmentTree(_), false)
             | (TreeType::MmrTree, false)
             | (TreeType::BulkAppendTree(_), false)
-            | (TreeType::DenseAppendOnlyFixedSizeTree(_), false) => Op::DeleteMaybeSpecialized,
+            | (TreeType::DenseAppendOnlyFixedSizeTree(_), false)
+            | (TreeType::MinMaxTree, false)
+            | (TreeType::ProvableSumTree, false)
+            | (TreeType::ProvableBigSumTree, false) => Op::DeleteMaybeSpecialized,
         };
         let entry = (key, op);
         batch_operations.push(entry);
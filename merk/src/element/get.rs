@@ -409,7 +409,10 @@ impl ElementFetchFromStoragePrivateExtensions for Element {
                 .transpose()
         );
         match &element {
-            Some(Element::Item(..)) | Some(Element::Reference(..)) => {
+            Some(Element::Item(..))
+            | Some(Element::Reference(..))
+            | Some(Element::MinMaxItem(..))
+            | Some(Element::ItemWithExpiry(..)) => {
                 // while the loaded item might be a sum item, it is given for free
                 // as it would be very hard to know in advance
                 cost.storage_loaded_bytes = KV::value_byte_cost_size_for_key_and_value_lengths(
@@ -450,7 +453,10 @@ impl ElementFetchFromStoragePrivateExtensions for Element {
             | Some(Element::CommitmentTree(_, _, flags))
             | Some(Element::MmrTree(_, flags))
             | Some(Element::BulkAppendTree(.., flags))
-            | Some(Element::DenseAppendOnlyFixedSizeTree(.., flags)) => {
+            | Some(Element::DenseAppendOnlyFixedSizeTree(.., flags))
+            | Some(Element::MinMaxTree(.., flags))
+            | Some(Element::ProvableSumTree(_, _, flags))
+            | Some(Element::ProvableBigSumTree(_, _, flags)) => {
                 let tree_cost_size = element.as_ref().unwrap().tree_type().unwrap().cost_size();
                 let flags_len = flags.as_ref().map_or(0, |flags| {
                     let flags_len = flags.len() as u32;
@@ -507,7 +513,10 @@ impl ElementFetchFromStoragePrivateExtensions for Element {
             })
         );
         match &element {
-            Element::Item(..) | Element::Reference(..) => {
+            Element::Item(..)
+            | Element::Reference(..)
+            | Element::MinMaxItem(..)
+            | Element::ItemWithExpiry(..) => {
                 // while the loaded item might be a sum item, it is given for free
                 // as it would be very hard to know in advance
                 cost.storage_loaded_bytes = KV::value_byte_cost_size_for_key_and_value_lengths(
@@ -550,7 +559,10 @@ impl ElementFetchFromStoragePrivateExtensions for Element {
             | Element::CommitmentTree(_, _, flags)
             | Element::MmrTree(_, flags)
             | Element::BulkAppendTree(.., flags)
-            | Element::DenseAppendOnlyFixedSizeTree(.., flags) => {
+            | Element::DenseAppendOnlyFixedSizeTree(.., flags)
+            | Element::MinMaxTree(.., flags)
+            | Element::ProvableSumTree(_, _, flags)
+            | Element::ProvableBigSumTree(_, _, flags) => {
                 let tree_cost_size = element.tree_type().unwrap().cost_size();
                 let flags_len = flags.as_ref().map_or(0, |flags| {
                     let flags_len = flags.len() as u32;
@@ -15,7 +15,7 @@ use crate::{
         costs::ElementCostExtensions, exists::ElementExistsInStorageExtensions,
         get::ElementFetchFromStorageExtensions, tree_type::ElementTreeTypeExtensions,
     },
-    BatchEntry, CryptoHash, Error, Merk, MerkOptions, Op, TreeFeatureType,
+    BatchEntry, CryptoHash, Error, Merk, MerkOptions, Op, TreeFeatureType, TreeType,
 };
 
 /// Extension trait for inserting elements into Merk storage.
@@ -170,6 +170,13 @@ impl ElementInsertToStorageExtensions for Element {
             .wrap_with_cost(Default::default());
         }
 
+        if merk.tree_type != TreeType::MinMaxTree && self.is_min_max_item() {
+            return Err(Error::InvalidInputError(
+                "cannot add min max item to non min max tree",
+            ))
+            .wrap_with_cost(Default::default());
+        }
+
         let merk_feature_type =
             cost_return_on_error_into_default!(self.get_feature_type(merk.tree_type));
         let batch_operations = if matches!(self, SumItem(..) | Element::ItemWithSumItem(..)) {
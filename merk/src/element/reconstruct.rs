@@ -58,6 +58,20 @@ impl ElementReconstructExtensions for Element {
                 aggregate_data.as_sum_i64(),
                 f.clone(),
             )),
+            Element::MinMaxTree(.., f) => {
+                let (min, max) = aggregate_data.as_min_max();
+                Some(Element::MinMaxTree(maybe_root_key, min, max, f.clone()))
+            }
+            Element::ProvableSumTree(.., f) => Some(Element::ProvableSumTree(
+                maybe_root_key,
+                aggregate_data.as_sum_i64(),
+                f.clone(),
+            )),
+            Element::ProvableBigSumTree(.., f) => Some(Element::ProvableBigSumTree(
+                maybe_root_key,
+                aggregate_data.as_summed_i128(),
+                f.clone(),
+            )),
             Element::CommitmentTree(tc, cp, f) => {
                 Some(Element::CommitmentTree(*tc, *cp, f.clone()))
             }
@@ -60,6 +60,11 @@ impl ElementTreeTypeExtensions for Element {
             Element::DenseAppendOnlyFixedSizeTree(_, height, _) => {
                 Some((None, TreeType::DenseAppendOnlyFixedSizeTree(height)))
             }
+            Element::MinMaxTree(root_key, ..) => Some((root_key, TreeType::MinMaxTree)),
+            Element::ProvableSumTree(root_key, ..) => Some((root_key, TreeType::ProvableSumTree)),
+            Element::ProvableBigSumTree(root_key, ..) => {
+                Some((root_key, TreeType::ProvableBigSumTree))
+            }
             _ => None,
         }
     }
@@ -92,6 +97,11 @@ impl ElementTreeTypeExtensions for Element {
                 &NONE_ROOT_KEY,
                 TreeType::DenseAppendOnlyFixedSizeTree(*height),
             )),
+            Element::MinMaxTree(root_key, ..) => Some((root_key, TreeType::MinMaxTree)),
+            Element::ProvableSumTree(root_key, ..) => Some((root_key, TreeType::ProvableSumTree)),
+            Element::ProvableBigSumTree(root_key, ..) => {
+                Some((root_key, TreeType::ProvableBigSumTree))
+            }
             _ => None,
         }
     }
@@ -118,6 +128,9 @@ impl ElementTreeTypeExtensions for Element {
             Element::DenseAppendOnlyFixedSizeTree(_, height, flags) => {
                 Some((flags, TreeType::DenseAppendOnlyFixedSizeTree(*height)))
             }
+            Element::MinMaxTree(.., flags) => Some((flags, TreeType::MinMaxTree)),
+            Element::ProvableSumTree(.., flags) => Some((flags, TreeType::ProvableSumTree)),
+            Element::ProvableBigSumTree(.., flags) => Some((flags, TreeType::ProvableBigSumTree)),
             _ => None,
         }
     }
@@ -142,6 +155,9 @@ impl ElementTreeTypeExtensions for Element {
             Element::DenseAppendOnlyFixedSizeTree(_, height, _) => {
                 Some(TreeType::DenseAppendOnlyFixedSizeTree(*height))
             }
+            Element::MinMaxTree(..) => Some(TreeType::MinMaxTree),
+            Element::ProvableSumTree(..) => Some(TreeType::ProvableSumTree),
+            Element::ProvableBigSumTree(..) => Some(TreeType::ProvableBigSumTree),
             _ => None,
         }
     }
@@ -165,6 +181,15 @@ impl ElementTreeTypeExtensions for Element {
             Element::MmrTree(..) => Some(BasicMerkNode),
             Element::BulkAppendTree(..) => Some(BasicMerkNode),
             Element::DenseAppendOnlyFixedSizeTree(..) => Some(BasicMerkNode),
+            Element::MinMaxTree(_, min, max, _) => {
+                Some(TreeFeatureType::MinMaxMerkNode(*min, *max))
+            }
+            Element::ProvableSumTree(_, value, _) => {
+                Some(TreeFeatureType::ProvableSummedMerkNode(*value))
+            }
+            Element::ProvableBigSumTree(_, value, _) => {
+                Some(TreeFeatureType::ProvableBigSummedMerkNode(*value))
+            }
             _ => None,
         }
     }
@@ -189,6 +214,9 @@ impl ElementTreeTypeExtensions for Element {
             Element::DenseAppendOnlyFixedSizeTree(_, height, _) => {
                 MaybeTree::Tree(TreeType::DenseAppendOnlyFixedSizeTree(*height))
             }
+            Element::MinMaxTree(..) => MaybeTree::Tree(TreeType::MinMaxTree),
+            Element::ProvableSumTree(..) => MaybeTree::Tree(TreeType::ProvableSumTree),
+            Element::ProvableBigSumTree(..) => MaybeTree::Tree(TreeType::ProvableBigSumTree),
             _ => MaybeTree::NotTree,
         }
     }
@@ -215,6 +243,16 @@ impl ElementTreeTypeExtensions for Element {
             TreeType::MmrTree => Ok(BasicMerkNode),
             TreeType::BulkAppendTree(_) => Ok(BasicMerkNode),
             TreeType::DenseAppendOnlyFixedSizeTree(_) => Ok(BasicMerkNode),
+            TreeType::MinMaxTree => {
+                let (min, max) = self.min_max_value_or_default();
+                Ok(TreeFeatureType::MinMaxMerkNode(min, max))
+            }
+            TreeType::ProvableSumTree => Ok(TreeFeatureType::ProvableSummedMerkNode(
+                self.sum_value_or_default(),
+            )),
+            TreeType::ProvableBigSumTree => Ok(TreeFeatureType::ProvableBigSummedMerkNode(
+                self.big_sum_value_or_default(),
+            )),
         }
     }
 }
@@ -16,7 +16,11 @@ pub mod merk;
 pub mod debugger;
 
 #[cfg(feature = "minimal")]
-pub use crate::merk::{chunks::ChunkProducer, options::MerkOptions, restore::Restorer};
+pub use crate::merk::{
+    chunks::ChunkProducer,
+    options::MerkOptions,
+    restore::{ChunkExpectation, Restorer, VerifiedChunk},
+};
 
 /// Provides a container type that allows temporarily taking ownership of a
 /// value.
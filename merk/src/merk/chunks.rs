@@ -487,6 +487,14 @@ mod test {
                 Node::KVCount(..) => counts.kv += 1,
                 Node::KVHashCount(..) => counts.kv_hash += 1,
                 Node::KVRefValueHashCount(..) => counts.kv_ref_value_hash += 1,
+                Node::KVMinMax(..) => counts.kv += 1,
+                Node::KVHashMinMax(..) => counts.kv_hash += 1,
+                Node::KVDigestMinMax(..) => counts.kv_digest += 1,
+                Node::KVRefValueHashMinMax(..) => counts.kv_ref_value_hash += 1,
+                Node::KVSum(..) => counts.kv += 1,
+                Node::KVHashSum(..) => counts.kv_hash += 1,
+                Node::KVDigestSum(..) => counts.kv_digest += 1,
+                Node::KVRefValueHashSum(..) => counts.kv_ref_value_hash += 1,
             };
         });
 
@@ -3,10 +3,10 @@ use grovedb_storage::StorageContext;
 use grovedb_version::version::GroveVersion;
 
 use crate::{
-    tree::{kv::ValueDefinedCostType, TreeNode},
+    tree::{kv::ValueDefinedCostType, RefWalker, TreeNode},
     CryptoHash, Error,
     Error::StorageError,
-    Merk, TreeFeatureType,
+    Merk, TreeFeatureType, TreeType,
 };
 
 impl<'db, S> Merk<S>
@@ -234,6 +234,60 @@ where
         }
     }
 
+    /// Gets the key and value at `rank` in a provable count tree, or `None`
+    /// if the tree counts no more than `rank`.
+    ///
+    /// Ranks start at zero with the first key. A key holds as many ranks as
+    /// its node counts, so in a tree of items the rank of a key is its
+    /// position, while a count tree nested in the tree holds one rank for
+    /// every element it counts.
+    pub fn get_by_rank(
+        &self,
+        rank: u64,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Option<(Vec<u8>, Vec<u8>)>, Error> {
+        if !self.tracks_ranks() {
+            return Err(Error::InvalidInputError(
+                "ranks can only be resolved in provable count trees",
+            ))
+            .wrap_with_cost(Default::default());
+        }
+        self.use_tree_mut(|maybe_tree| match maybe_tree {
+            None => Ok(None).wrap_with_cost(Default::default()),
+            Some(tree) => {
+                RefWalker::new(tree, self.source()).key_value_at_rank(rank, grove_version)
+            }
+        })
+    }
+
+    /// Gets the first rank held by `key` in a provable count tree, or `None`
+    /// if the key is not in the tree. See [`Merk::get_by_rank`] for how ranks
+    /// are held.
+    pub fn rank_of_key(
+        &self,
+        key: &[u8],
+        grove_version: &GroveVersion,
+    ) -> CostResult<Option<u64>, Error> {
+        if !self.tracks_ranks() {
+            return Err(Error::InvalidInputError(
+                "ranks can only be resolved in provable count trees",
+            ))
+            .wrap_with_cost(Default::default());
+        }
+        self.use_tree_mut(|maybe_tree| match maybe_tree {
+            None => Ok(None).wrap_with_cost(Default::default()),
+            Some(tree) => RefWalker::new(tree, self.source()).rank_of_key(key, grove_version),
+        })
+    }
+
+    /// Only provable count trees commit to the counts ranks are taken from.
+    pub(crate) fn tracks_ranks(&self) -> bool {
+        matches!(
+            self.tree_type,
+            TreeType::ProvableCountTree | TreeType::ProvableCountSumTree
+        )
+    }
+
     /// See if a node's field exists
     fn has_node_direct(
         &self,
@@ -1,13 +1,17 @@
 use std::collections::LinkedList;
 
-use grovedb_costs::{CostResult, CostsExt};
+use grovedb_costs::{cost_return_on_error, CostResult, CostsExt, OperationCost};
 use grovedb_storage::StorageContext;
 use grovedb_version::version::GroveVersion;
 
 use crate::{
-    proofs::{encode_into, query::QueryItem, Op as ProofOp, Query},
+    proofs::{
+        encode_into,
+        query::{QueryItem, RangeAggregate},
+        Op as ProofOp, Query,
+    },
     tree::RefWalker,
-    Error, Merk,
+    Error, Merk, TreeType,
 };
 
 impl<'db, S> Merk<S>
@@ -139,6 +143,174 @@ where
                 .map_ok(|(proof, _, status, ..)| (proof, status.limit))
         })
     }
+
+    /// Like `prove_unchecked_query_items`, but the keys found are proven by
+    /// their key and value hash only, without their values. Such a proof is
+    /// verified with `QueryProofVerify::execute_keys_proof`.
+    pub fn prove_keys_unchecked_query_items(
+        &self,
+        query_items: &[QueryItem],
+        limit: Option<u16>,
+        left_to_right: bool,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Proof, Error> {
+        self.use_tree_mut(|maybe_tree| {
+            maybe_tree
+                .ok_or(Error::CorruptedCodeExecution(
+                    "Cannot create proof for empty tree",
+                ))
+                .wrap_with_cost(Default::default())
+                .flat_map_ok(|tree| {
+                    let mut ref_walker = RefWalker::new(tree, self.source());
+                    ref_walker.create_keys_proof(query_items, limit, left_to_right, grove_version)
+                })
+                .map_ok(|(proof, _, status, ..)| (proof, status.limit))
+        })
+    }
+
+    /// Like `prove_unchecked_query_items`, but skips the first `offset`
+    /// matches, which are proven by their key and value hash only and do
+    /// not count against `limit`. Also returns how many of the matches to
+    /// skip were not found.
+    pub fn prove_unchecked_query_items_with_offset(
+        &self,
+        query_items: &[QueryItem],
+        limit: Option<u16>,
+        offset: u16,
+        left_to_right: bool,
+        grove_version: &GroveVersion,
+    ) -> CostResult<(Proof, u16), Error> {
+        self.use_tree_mut(|maybe_tree| {
+            maybe_tree
+                .ok_or(Error::CorruptedCodeExecution(
+                    "Cannot create proof for empty tree",
+                ))
+                .wrap_with_cost(Default::default())
+                .flat_map_ok(|tree| {
+                    let mut ref_walker = RefWalker::new(tree, self.source());
+                    ref_walker.create_proof_with_offset(
+                        query_items,
+                        limit,
+                        offset,
+                        left_to_right,
+                        grove_version,
+                    )
+                })
+                .map_ok(|(proof, _, status, ..)| ((proof, status.limit), status.offset))
+        })
+    }
+
+    /// Creates a proof of the aggregate count of the keys in `range`, which
+    /// can be verified with `verify_range_count_proof`. Only provable count
+    /// trees hash their counts, so other tree types are rejected.
+    pub fn prove_count_in_range(
+        &self,
+        range: &QueryItem,
+        grove_version: &GroveVersion,
+    ) -> CostResult<LinkedList<ProofOp>, Error> {
+        if !matches!(
+            self.tree_type,
+            TreeType::ProvableCountTree | TreeType::ProvableCountSumTree
+        ) {
+            return Err(Error::InvalidInputError(
+                "range counts can only be proved in provable count trees",
+            ))
+            .wrap_with_cost(Default::default());
+        }
+        self.use_tree_mut(|maybe_tree| {
+            maybe_tree
+                .ok_or(Error::CorruptedCodeExecution(
+                    "Cannot create proof for empty tree",
+                ))
+                .wrap_with_cost(Default::default())
+                .flat_map_ok(|tree| {
+                    let mut ref_walker = RefWalker::new(tree, self.source());
+                    ref_walker.create_range_aggregate_proof(
+                        RangeAggregate::Count,
+                        range,
+                        None,
+                        None,
+                        grove_version,
+                    )
+                })
+        })
+    }
+
+    /// Creates a proof of the rank of `key`, which can be verified with
+    /// `verify_rank_of_key_proof`. The proof shows whether the key is in the
+    /// tree as well.
+    pub fn prove_rank_of_key(
+        &self,
+        key: &[u8],
+        grove_version: &GroveVersion,
+    ) -> CostResult<LinkedList<ProofOp>, Error> {
+        if !self.tracks_ranks() {
+            return Err(Error::InvalidInputError(
+                "ranks can only be proved in provable count trees",
+            ))
+            .wrap_with_cost(Default::default());
+        }
+        self.prove_count_in_range(&QueryItem::RangeTo(..key.to_vec()), grove_version)
+    }
+
+    /// Creates a proof of the key at `rank`, which can be verified with
+    /// `verify_key_at_rank_proof`. If the tree counts no more than `rank`,
+    /// the proof only shows the count of the whole tree.
+    pub fn prove_key_at_rank(
+        &self,
+        rank: u64,
+        grove_version: &GroveVersion,
+    ) -> CostResult<LinkedList<ProofOp>, Error> {
+        let mut cost = OperationCost::default();
+        if !self.tracks_ranks() {
+            return Err(Error::InvalidInputError(
+                "ranks can only be proved in provable count trees",
+            ))
+            .wrap_with_cost(cost);
+        }
+        let range = match cost_return_on_error!(&mut cost, self.get_by_rank(rank, grove_version)) {
+            Some((key, _)) => QueryItem::RangeTo(..key),
+            None => QueryItem::RangeFull(..),
+        };
+        self.prove_count_in_range(&range, grove_version)
+            .add_cost(cost)
+    }
+
+    /// Creates a proof of the aggregate sum of the keys in `range`, which can
+    /// be verified with `verify_range_sum_proof`. Only provable sum trees
+    /// hash their sums, so other tree types are rejected.
+    pub fn prove_sum_in_range(
+        &self,
+        range: &QueryItem,
+        grove_version: &GroveVersion,
+    ) -> CostResult<LinkedList<ProofOp>, Error> {
+        if !matches!(
+            self.tree_type,
+            TreeType::ProvableSumTree | TreeType::ProvableBigSumTree
+        ) {
+            return Err(Error::InvalidInputError(
+                "range sums can only be proved in provable sum trees",
+            ))
+            .wrap_with_cost(Default::default());
+        }
+        self.use_tree_mut(|maybe_tree| {
+            maybe_tree
+                .ok_or(Error::CorruptedCodeExecution(
+                    "Cannot create proof for empty tree",
+                ))
+                .wrap_with_cost(Default::default())
+                .flat_map_ok(|tree| {
+                    let mut ref_walker = RefWalker::new(tree, self.source());
+                    ref_walker.create_range_aggregate_proof(
+                        RangeAggregate::Sum,
+                        range,
+                        None,
+                        None,
+                        grove_version,
+                    )
+                })
+        })
+    }
 }
 
 type Proof = (LinkedList<ProofOp>, Option<u16>);
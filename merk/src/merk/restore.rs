@@ -31,7 +31,7 @@
 
 use std::collections::BTreeMap;
 
-use grovedb_storage::{Batch, StorageContext};
+use grovedb_storage::{Batch, RawIterator, StorageContext};
 use grovedb_version::version::GroveVersion;
 
 use crate::{
@@ -44,7 +44,7 @@ use crate::{
             error::{ChunkError, ChunkError::InternalError},
             util::{traversal_instruction_as_vec_bytes, vec_bytes_as_traversal_instruction},
         },
-        tree::{execute, Child, Tree as ProofTree},
+        tree::{execute, Tree as ProofTree},
         Node, Op,
     },
     tree::{combine_hash, kv::ValueDefinedCostType, value_hash, RefWalker, TreeNode},
@@ -98,6 +98,8 @@ pub struct Restorer<S> {
     parent_key_value_hash: Option<CryptoHash>,
     // this is used to keep track of parents whose links need to be rewritten
     parent_keys: BTreeMap<Vec<u8>, Vec<u8>>,
+    // whether the storage holds an older version of the tree to reuse
+    incremental: bool,
 }
 
 impl<'db, S: StorageContext<'db>> Restorer<S> {
@@ -115,6 +117,23 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
             chunk_id_to_root_hash,
             parent_key_value_hash,
             parent_keys: BTreeMap::new(),
+            incremental: false,
+        }
+    }
+
+    /// Initializes a chunk restorer over storage holding an older version of
+    /// the tree. Subtrees behind the hash nodes of a chunk are taken from
+    /// storage instead of being requested when a stored node links to them
+    /// with the same hash, and stored nodes left out of the restored tree
+    /// are deleted by [`Restorer::finalize`].
+    pub fn new_incremental(
+        merk: Merk<S>,
+        expected_root_hash: CryptoHash,
+        parent_key_value_hash: Option<CryptoHash>,
+    ) -> Self {
+        Self {
+            incremental: true,
+            ..Self::new(merk, expected_root_hash, parent_key_value_hash)
         }
     }
 
@@ -133,6 +152,14 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
         chunk: Vec<Op>,
         grove_version: &GroveVersion,
     ) -> Result<Vec<Vec<u8>>, Error> {
+        let verified_chunk = self.chunk_expectation(chunk_id)?.verify(chunk)?;
+        self.apply_verified_chunk(verified_chunk, grove_version)
+    }
+
+    /// Returns what the chunk at some chunk id is verified against, so it can
+    /// be verified away from the restorer, e.g. on another thread. Fails if
+    /// the chunk isn't expected.
+    pub fn chunk_expectation(&self, chunk_id: &[u8]) -> Result<ChunkExpectation, Error> {
         let expected_root_hash = self
             .chunk_id_to_root_hash
             .get(chunk_id)
@@ -142,9 +169,31 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
         if chunk_id.is_empty() {
             parent_key_value_hash = self.parent_key_value_hash;
         }
-        let chunk_tree = Self::verify_chunk(chunk, expected_root_hash, &parent_key_value_hash)?;
+        Ok(ChunkExpectation {
+            chunk_id: chunk_id.to_vec(),
+            expected_root_hash: *expected_root_hash,
+            parent_key_value_hash,
+        })
+    }
 
-        let mut root_traversal_instruction = vec_bytes_as_traversal_instruction(chunk_id)?;
+    /// Writes a chunk verified against [`Restorer::chunk_expectation`],
+    /// returns the chunks id's of chunks that can be requested
+    pub fn apply_verified_chunk(
+        &mut self,
+        verified_chunk: VerifiedChunk,
+        grove_version: &GroveVersion,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let VerifiedChunk {
+            chunk_id,
+            expected_root_hash,
+            tree: chunk_tree,
+        } = verified_chunk;
+        // the chunk must still be expected with the hash it was verified against
+        if self.chunk_id_to_root_hash.get(&chunk_id) != Some(&expected_root_hash) {
+            return Err(Error::ChunkRestoringError(ChunkError::UnexpectedChunk));
+        }
+
+        let mut root_traversal_instruction = vec_bytes_as_traversal_instruction(&chunk_id)?;
 
         if root_traversal_instruction.is_empty() {
             self.merk
@@ -154,7 +203,7 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
             // every non root chunk has some associated parent with an placeholder link
             // here we update the placeholder link to represent the true data
             self.rewrite_parent_link(
-                chunk_id,
+                &chunk_id,
                 &root_traversal_instruction,
                 &chunk_tree,
                 grove_version,
@@ -162,11 +211,12 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
         }
 
         // next up, we need to write the chunk and build the map again
-        let chunk_write_result = self.write_chunk(chunk_tree, &mut root_traversal_instruction);
+        let chunk_write_result =
+            self.write_chunk(chunk_tree, &mut root_traversal_instruction, grove_version);
         if chunk_write_result.is_ok() {
             // if we were able to successfully write the chunk, we can remove
             // the chunk expected root hash from our chunk id map
-            self.chunk_id_to_root_hash.remove(chunk_id);
+            self.chunk_id_to_root_hash.remove(&chunk_id);
         }
 
         chunk_write_result
@@ -207,66 +257,6 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
         Ok(chunk_ids)
     }
 
-    /// Verifies the structure of a chunk and ensures the chunk matches the
-    /// expected root hash
-    fn verify_chunk(
-        chunk: Vec<Op>,
-        expected_root_hash: &CryptoHash,
-        parent_key_value_hash_opt: &Option<CryptoHash>,
-    ) -> Result<ProofTree, Error> {
-        let chunk_len = chunk.len();
-        let mut kv_count = 0;
-        let mut hash_count = 0;
-
-        // build tree from ops
-        // ensure only made of KV-like nodes and Hash nodes, and count them
-        let tree = execute(chunk.clone().into_iter().map(Ok), false, |node| {
-            if matches!(
-                node,
-                Node::KVValueHashFeatureType(..)
-                    | Node::KV(..)
-                    | Node::KVValueHash(..)
-                    | Node::KVCount(..)
-            ) {
-                kv_count += 1;
-                Ok(())
-            } else if matches!(node, Node::Hash(..)) {
-                hash_count += 1;
-                Ok(())
-            } else {
-                Err(Error::ChunkRestoringError(ChunkError::InvalidChunkProof(
-                    "expected chunk proof to contain only kv or hash nodes",
-                )))
-            }
-        })
-        .unwrap()?;
-
-        // chunk len must be exactly equal to the kv_count + hash_count +
-        // parent_branch_count + child_branch_count
-        debug_assert_eq!(chunk_len, ((kv_count + hash_count) * 2) - 1);
-
-        // chunk structure verified, next verify root hash
-        match parent_key_value_hash_opt {
-            Some(val_hash) => {
-                let combined_hash = combine_hash(val_hash, &tree.hash().unwrap()).unwrap();
-                if &combined_hash != expected_root_hash {
-                    return Err(Error::ChunkRestoringError(ChunkError::InvalidChunkProof(
-                        "chunk doesn't match expected root hash",
-                    )));
-                }
-            }
-            None => {
-                if &tree.hash().unwrap() != expected_root_hash {
-                    return Err(Error::ChunkRestoringError(ChunkError::InvalidChunkProof(
-                        "chunk doesn't match expected root hash",
-                    )));
-                }
-            }
-        };
-
-        Ok(tree)
-    }
-
     /// Write the verified chunk to storage.
     ///
     /// Note on child heights: `Child::as_link()` produces `Link::Reference`
@@ -283,7 +273,15 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
         &mut self,
         chunk_tree: ProofTree,
         traversal_instruction: &mut Vec<bool>,
+        grove_version: &GroveVersion,
     ) -> Result<Vec<Vec<u8>>, Error> {
+        // subtrees behind hash nodes which are already in storage
+        let stored_links = if self.incremental {
+            self.stored_links(&chunk_tree, traversal_instruction, grove_version)?
+        } else {
+            BTreeMap::new()
+        };
+
         // this contains all the elements we want to write to storage
         let mut batch = self.merk.storage.new_batch();
         let mut new_chunk_ids = Vec::new();
@@ -306,8 +304,14 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
                         // Update tree links. Heights in these links may be
                         // placeholders for Hash-node children (chunk
                         // boundaries); corrected by finalize().
-                        *tree.slot_mut(LEFT) = proof_node.left.as_ref().map(Child::as_link);
-                        *tree.slot_mut(RIGHT) = proof_node.right.as_ref().map(Child::as_link);
+                        *tree.slot_mut(LEFT) =
+                            child_link(proof_node, node_traversal_instruction, LEFT, &stored_links);
+                        *tree.slot_mut(RIGHT) = child_link(
+                            proof_node,
+                            node_traversal_instruction,
+                            RIGHT,
+                            &stored_links,
+                        );
 
                         // encode the node and add it to the batch
                         let bytes = tree.encode();
@@ -326,8 +330,14 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
                         )
                         .unwrap();
 
-                        *tree.slot_mut(LEFT) = proof_node.left.as_ref().map(Child::as_link);
-                        *tree.slot_mut(RIGHT) = proof_node.right.as_ref().map(Child::as_link);
+                        *tree.slot_mut(LEFT) =
+                            child_link(proof_node, node_traversal_instruction, LEFT, &stored_links);
+                        *tree.slot_mut(RIGHT) = child_link(
+                            proof_node,
+                            node_traversal_instruction,
+                            RIGHT,
+                            &stored_links,
+                        );
 
                         let bytes = tree.encode();
                         batch.put(key, &bytes, None, None).map_err(CostsError)
@@ -344,8 +354,14 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
                         )
                         .unwrap();
 
-                        *tree.slot_mut(LEFT) = proof_node.left.as_ref().map(Child::as_link);
-                        *tree.slot_mut(RIGHT) = proof_node.right.as_ref().map(Child::as_link);
+                        *tree.slot_mut(LEFT) =
+                            child_link(proof_node, node_traversal_instruction, LEFT, &stored_links);
+                        *tree.slot_mut(RIGHT) = child_link(
+                            proof_node,
+                            node_traversal_instruction,
+                            RIGHT,
+                            &stored_links,
+                        );
 
                         let bytes = tree.encode();
                         batch.put(key, &bytes, None, None).map_err(CostsError)
@@ -362,8 +378,63 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
                         )
                         .unwrap();
 
-                        *tree.slot_mut(LEFT) = proof_node.left.as_ref().map(Child::as_link);
-                        *tree.slot_mut(RIGHT) = proof_node.right.as_ref().map(Child::as_link);
+                        *tree.slot_mut(LEFT) =
+                            child_link(proof_node, node_traversal_instruction, LEFT, &stored_links);
+                        *tree.slot_mut(RIGHT) = child_link(
+                            proof_node,
+                            node_traversal_instruction,
+                            RIGHT,
+                            &stored_links,
+                        );
+
+                        let bytes = tree.encode();
+                        batch.put(key, &bytes, None, None).map_err(CostsError)
+                    }
+                    Node::KVMinMax(key, value, min, max) => {
+                        // Items in MinMaxTree: value_hash = H(value),
+                        // feature_type = MinMaxMerkNode(min, max)
+                        let vh = value_hash(value.as_slice()).unwrap();
+                        let mut tree = TreeNode::new_with_value_hash(
+                            key.clone(),
+                            value.clone(),
+                            vh,
+                            TreeFeatureType::MinMaxMerkNode(*min, *max),
+                        )
+                        .unwrap();
+
+                        *tree.slot_mut(LEFT) =
+                            child_link(proof_node, node_traversal_instruction, LEFT, &stored_links);
+                        *tree.slot_mut(RIGHT) = child_link(
+                            proof_node,
+                            node_traversal_instruction,
+                            RIGHT,
+                            &stored_links,
+                        );
+
+                        let bytes = tree.encode();
+                        batch.put(key, &bytes, None, None).map_err(CostsError)
+                    }
+                    Node::KVSum(key, value, sum) => {
+                        // Items in provable sum trees: value_hash = H(value),
+                        // feature_type follows the tree being restored
+                        let vh = value_hash(value.as_slice()).unwrap();
+                        let feature_type = provable_sum_feature_type(self.merk.tree_type, *sum)?;
+                        let mut tree = TreeNode::new_with_value_hash(
+                            key.clone(),
+                            value.clone(),
+                            vh,
+                            feature_type,
+                        )
+                        .unwrap();
+
+                        *tree.slot_mut(LEFT) =
+                            child_link(proof_node, node_traversal_instruction, LEFT, &stored_links);
+                        *tree.slot_mut(RIGHT) = child_link(
+                            proof_node,
+                            node_traversal_instruction,
+                            RIGHT,
+                            &stored_links,
+                        );
 
                         let bytes = tree.encode();
                         batch.put(key, &bytes, None, None).map_err(CostsError)
@@ -373,6 +444,10 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
                         // we get the chunk id and add the hash to restorer state
                         let chunk_id =
                             traversal_instruction_as_vec_bytes(node_traversal_instruction);
+                        if stored_links.contains_key(&chunk_id) {
+                            // the parent links to the stored subtree instead
+                            return Ok(());
+                        }
                         new_chunk_ids.push(chunk_id.to_vec());
                         self.chunk_id_to_root_hash.insert(chunk_id.to_vec(), *hash);
                         let parent = parent_key.ok_or(Error::ChunkRestoringError(
@@ -403,6 +478,55 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
         Ok(new_chunk_ids)
     }
 
+    /// Finds the hash nodes of a chunk whose subtree is already in storage,
+    /// which is the case when the stored node with the key of their parent
+    /// links to a subtree with the same hash, and returns the stored links by
+    /// chunk id. Keys are unique in the tree, so the stored subtree holds
+    /// exactly the keys of the subtree behind the hash node and no other
+    /// chunk overwrites them.
+    fn stored_links(
+        &self,
+        chunk_tree: &ProofTree,
+        traversal_instruction: &mut Vec<bool>,
+        grove_version: &GroveVersion,
+    ) -> Result<BTreeMap<Vec<u8>, Link>, Error> {
+        let mut stored_links = BTreeMap::new();
+        chunk_tree.visit_refs_track_traversal_and_parent(
+            traversal_instruction,
+            None,
+            &mut |proof_node, node_traversal_instruction, parent_key| {
+                let (Node::Hash(hash), Some(parent_key), Some(is_left)) = (
+                    &proof_node.node,
+                    parent_key,
+                    node_traversal_instruction.last(),
+                ) else {
+                    return Ok(());
+                };
+                // the stored tree may not even be a merk, in which case there
+                // is nothing to reuse
+                let Ok(Some(stored_parent)) = merk::fetch_node(
+                    &self.merk.storage,
+                    parent_key,
+                    None::<&fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+                    grove_version,
+                ) else {
+                    return Ok(());
+                };
+                if let Some(link) = stored_parent.link(*is_left)
+                    && link.hash() == hash
+                    && link.aggregate_data().parent_tree_type() == self.merk.tree_type
+                {
+                    stored_links.insert(
+                        traversal_instruction_as_vec_bytes(node_traversal_instruction),
+                        link.clone(),
+                    );
+                }
+                Ok(())
+            },
+        )?;
+        Ok(stored_links)
+    }
+
     /// When we process truncated chunks, the parents of Node::Hash have invalid
     /// placeholder for links.
     /// When we get the actual chunk associated with the Node::Hash,
@@ -438,11 +562,15 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
         let updated_key = chunk_tree
             .key()
             .expect("chunk tree must have a key during restore");
-        let updated_sum = chunk_tree.aggregate_data().map_err(|e| {
-            Error::CorruptedData(format!(
-                "chunk tree root node must be KVValueHashFeatureType for aggregate data: {e}"
-            ))
-        })?;
+        let updated_sum = match &chunk_tree.node {
+            // A KVSum node doesn't say which provable sum tree it came from
+            Node::KVSum(_, _, sum) => provable_sum_feature_type(self.merk.tree_type, *sum)?.into(),
+            _ => chunk_tree.aggregate_data().map_err(|e| {
+                Error::CorruptedData(format!(
+                    "chunk tree root node must be KVValueHashFeatureType for aggregate data: {e}"
+                ))
+            })?,
+        };
 
         if let Some(Link::Reference {
             key,
@@ -564,17 +692,87 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
             .map_err(StorageError)
     }
 
-    /// Rebuild restoration state from partial storage state
-    #[allow(dead_code)]
-    fn attempt_state_recovery(&mut self, grove_version: &GroveVersion) -> Result<(), Error> {
-        // TODO: think about the return type some more
-        let (bad_link_map, parent_keys) = self.merk.verify(false, grove_version);
-        if !bad_link_map.is_empty() {
+    /// Deletes the stored nodes which aren't part of the restored tree, as
+    /// storage held an older version of the tree, see
+    /// [`Restorer::new_incremental`]. The keys of the tree are visited in
+    /// order alongside the stored keys, so nothing is collected in memory.
+    fn delete_unreachable_nodes(&mut self, grove_version: &GroveVersion) -> Result<(), Error> {
+        let fetch = |key: &[u8]| {
+            merk::fetch_node(
+                &self.merk.storage,
+                key,
+                None::<&fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+                grove_version,
+            )?
+            .ok_or(Error::CorruptedState("link points to non-existent node"))
+        };
+        // nodes whose key and right subtree are still to be visited
+        let mut stack: Vec<TreeNode> = vec![];
+        let push_left_spine = |stack: &mut Vec<TreeNode>, key: Option<Vec<u8>>| {
+            let mut key = key;
+            while let Some(node_key) = key {
+                let node = fetch(&node_key)?;
+                key = node.link(LEFT).map(|link| link.key().to_vec());
+                stack.push(node);
+            }
+            Ok::<_, Error>(())
+        };
+        let next_key = |stack: &mut Vec<TreeNode>| {
+            let Some(node) = stack.pop() else {
+                return Ok(None);
+            };
+            push_left_spine(stack, node.link(RIGHT).map(|link| link.key().to_vec()))?;
+            Ok::<_, Error>(Some(node.key().to_vec()))
+        };
+
+        let root_key = self
+            .merk
+            .use_tree(|tree| tree.map(|tree| tree.key().to_vec()));
+        push_left_spine(&mut stack, root_key)?;
+        let mut tree_key = next_key(&mut stack)?;
+
+        let mut batch = self.merk.storage.new_batch();
+        let mut stored_entries = self.merk.storage.raw_iter();
+        stored_entries.seek_to_first().unwrap();
+        while stored_entries.valid().unwrap() {
+            let Some(stored_key) = stored_entries.key().unwrap() else {
+                break;
+            };
+            if tree_key.as_deref() == Some(stored_key) {
+                tree_key = next_key(&mut stack)?;
+            } else {
+                batch.delete(stored_key, None);
+            }
+            stored_entries.next().unwrap();
+        }
+        drop(stored_entries);
+
+        // costs intentionally discarded — restorer does not track them
+        self.merk
+            .storage
+            .commit_batch(batch)
+            .value
+            .map_err(StorageError)
+    }
+
+    /// Rebuild restoration state from partial storage state, for a restorer
+    /// of a Merk that an earlier restorer was interrupted on. Returns the ids
+    /// of the chunks still to be processed.
+    pub fn attempt_state_recovery(
+        &mut self,
+        grove_version: &GroveVersion,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        // until the root chunk is written there is nothing to recover and
+        // only the root chunk is expected
+        if self.merk.use_tree(|tree| tree.is_some()) {
+            let (bad_link_map, parent_keys) = self
+                .merk
+                .verify(self.merk.tree_type != TreeType::NormalTree, grove_version);
             self.chunk_id_to_root_hash = bad_link_map;
             self.parent_keys = parent_keys;
         }
 
-        Ok(())
+        Ok(self.chunk_id_to_root_hash.keys().cloned().collect())
     }
 
     /// Consumes the `Restorer` and returns a newly created, fully populated
@@ -607,6 +805,10 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
                 ))
             })?;
 
+        if self.incremental {
+            self.delete_unreachable_nodes(grove_version)?;
+        }
+
         // Heights written during chunk processing may be placeholders (see
         // write_chunk doc comment). Verify them here, and rewrite if needed.
         if self.verify_height(grove_version).is_err() {
@@ -719,14 +921,144 @@ impl<'db, S: StorageContext<'db>> Restorer<S> {
     }
 }
 
+/// Returns the link of a chunk node to its child on the given side, which is
+/// the stored link if the child is a hash node whose subtree is already in
+/// storage.
+fn child_link(
+    proof_node: &ProofTree,
+    traversal_instruction: &[bool],
+    left: bool,
+    stored_links: &BTreeMap<Vec<u8>, Link>,
+) -> Option<Link> {
+    let child = proof_node.child(left)?;
+    if matches!(child.tree.node, Node::Hash(_)) {
+        let child_traversal_instruction = [traversal_instruction, &[left]].concat();
+        if let Some(link) = stored_links.get(&traversal_instruction_as_vec_bytes(
+            &child_traversal_instruction,
+        )) {
+            return Some(link.clone());
+        }
+    }
+    Some(child.as_link())
+}
+
+/// Hash a chunk must match to be accepted by a [`Restorer`], see
+/// [`Restorer::chunk_expectation`]. Verifying a chunk against it doesn't
+/// involve the restorer or its storage.
+#[derive(Debug, Clone)]
+pub struct ChunkExpectation {
+    chunk_id: Vec<u8>,
+    expected_root_hash: CryptoHash,
+    parent_key_value_hash: Option<CryptoHash>,
+}
+
+impl ChunkExpectation {
+    /// Verifies the chunk proof `chunk`, which can then be written with
+    /// [`Restorer::apply_verified_chunk`].
+    pub fn verify(self, chunk: Vec<Op>) -> Result<VerifiedChunk, Error> {
+        let tree = verify_chunk(chunk, &self.expected_root_hash, &self.parent_key_value_hash)?;
+        Ok(VerifiedChunk {
+            chunk_id: self.chunk_id,
+            expected_root_hash: self.expected_root_hash,
+            tree,
+        })
+    }
+}
+
+/// Chunk proof verified against a [`ChunkExpectation`].
+#[derive(Debug)]
+pub struct VerifiedChunk {
+    chunk_id: Vec<u8>,
+    expected_root_hash: CryptoHash,
+    tree: ProofTree,
+}
+
+/// Verifies the structure of a chunk and ensures the chunk matches the
+/// expected root hash
+fn verify_chunk(
+    chunk: Vec<Op>,
+    expected_root_hash: &CryptoHash,
+    parent_key_value_hash_opt: &Option<CryptoHash>,
+) -> Result<ProofTree, Error> {
+    let chunk_len = chunk.len();
+    let mut kv_count = 0;
+    let mut hash_count = 0;
+
+    // build tree from ops
+    // ensure only made of KV-like nodes and Hash nodes, and count them
+    let tree = execute(chunk.clone().into_iter().map(Ok), false, |node| {
+        if matches!(
+            node,
+            Node::KVValueHashFeatureType(..)
+                | Node::KV(..)
+                | Node::KVValueHash(..)
+                | Node::KVCount(..)
+                | Node::KVMinMax(..)
+                | Node::KVSum(..)
+        ) {
+            kv_count += 1;
+            Ok(())
+        } else if matches!(node, Node::Hash(..)) {
+            hash_count += 1;
+            Ok(())
+        } else {
+            Err(Error::ChunkRestoringError(ChunkError::InvalidChunkProof(
+                "expected chunk proof to contain only kv or hash nodes",
+            )))
+        }
+    })
+    .unwrap()?;
+
+    // chunk len must be exactly equal to the kv_count + hash_count +
+    // parent_branch_count + child_branch_count
+    debug_assert_eq!(chunk_len, ((kv_count + hash_count) * 2) - 1);
+
+    // chunk structure verified, next verify root hash
+    match parent_key_value_hash_opt {
+        Some(val_hash) => {
+            let combined_hash = combine_hash(val_hash, &tree.hash().unwrap()).unwrap();
+            if &combined_hash != expected_root_hash {
+                return Err(Error::ChunkRestoringError(ChunkError::InvalidChunkProof(
+                    "chunk doesn't match expected root hash",
+                )));
+            }
+        }
+        None => {
+            if &tree.hash().unwrap() != expected_root_hash {
+                return Err(Error::ChunkRestoringError(ChunkError::InvalidChunkProof(
+                    "chunk doesn't match expected root hash",
+                )));
+            }
+        }
+    };
+
+    Ok(tree)
+}
+
+/// Returns the feature type of a `KVSum` chunk node restored into a tree of
+/// the given type. `KVSum` always carries an i128, so the restoring tree
+/// decides whether it is a regular or a big provable sum.
+fn provable_sum_feature_type(tree_type: TreeType, sum: i128) -> Result<TreeFeatureType, Error> {
+    match tree_type {
+        TreeType::ProvableSumTree => i64::try_from(sum)
+            .map(TreeFeatureType::ProvableSummedMerkNode)
+            .map_err(|_| {
+                Error::ChunkRestoringError(ChunkError::InvalidChunkProof(
+                    "provable sum does not fit in a provable sum tree",
+                ))
+            }),
+        TreeType::ProvableBigSumTree => Ok(TreeFeatureType::ProvableBigSummedMerkNode(sum)),
+        _ => Err(Error::ChunkRestoringError(ChunkError::InvalidChunkProof(
+            "kv sum node found outside of a provable sum tree",
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use grovedb_path::SubtreePath;
     use grovedb_storage::{
-        rocksdb_storage::{
-            test_utils::TempStorage, PrefixedRocksDbImmediateStorageContext,
-            PrefixedRocksDbTransactionContext,
-        },
+        rocksdb_storage::{test_utils::TempStorage, PrefixedRocksDbImmediateStorageContext},
         RawIterator, Storage,
     };
 
@@ -736,7 +1068,7 @@ mod tests {
         proofs::chunk::{
             chunk::tests::traverse_get_node_hash, error::ChunkError::InvalidChunkProof,
         },
-        test_utils::{make_batch_seq, TempMerk},
+        test_utils::{make_batch_seq, make_del_batch_seq, seq_key, TempMerk},
         tree_type::TreeType,
         Error::ChunkRestoringError,
         Merk, PanicSource,
@@ -751,20 +1083,14 @@ mod tests {
             Op::Push(Node::KV(vec![3], vec![3])),
             Op::Parent,
         ];
-        assert!(Restorer::<PrefixedRocksDbTransactionContext>::verify_chunk(
-            non_avl_tree_proof,
-            &[0; 32],
-            &None
-        )
-        .is_err());
+        assert!(verify_chunk(non_avl_tree_proof, &[0; 32], &None).is_err());
     }
 
     #[test]
     fn test_chunk_verification_accepted_and_rejected_node_types() {
         // KV should be accepted (items in normal trees)
         let kv_proof = vec![Op::Push(Node::KV(vec![1], vec![1]))];
-        let result =
-            Restorer::<PrefixedRocksDbTransactionContext>::verify_chunk(kv_proof, &[0; 32], &None);
+        let result = verify_chunk(kv_proof, &[0; 32], &None);
         // KV is now accepted; it will fail with hash mismatch (not invalid node type)
         assert!(
             !matches!(
@@ -778,9 +1104,7 @@ mod tests {
 
         // KVValueHash should be accepted (subtrees in normal trees)
         let kvvh_proof = vec![Op::Push(Node::KVValueHash(vec![0], vec![0], [0; 32]))];
-        let result = Restorer::<PrefixedRocksDbTransactionContext>::verify_chunk(
-            kvvh_proof, &[0; 32], &None,
-        );
+        let result = verify_chunk(kvvh_proof, &[0; 32], &None);
         assert!(
             !matches!(
                 result,
@@ -793,8 +1117,7 @@ mod tests {
 
         // KVCount should be accepted (items in provable count trees)
         let kvc_proof = vec![Op::Push(Node::KVCount(vec![0], vec![0], 1))];
-        let result =
-            Restorer::<PrefixedRocksDbTransactionContext>::verify_chunk(kvc_proof, &[0; 32], &None);
+        let result = verify_chunk(kvc_proof, &[0; 32], &None);
         assert!(
             !matches!(
                 result,
@@ -807,11 +1130,7 @@ mod tests {
 
         // should not accept kvhash
         let invalid_chunk_proof = vec![Op::Push(Node::KVHash([0; 32]))];
-        let verification_result = Restorer::<PrefixedRocksDbTransactionContext>::verify_chunk(
-            invalid_chunk_proof,
-            &[0; 32],
-            &None,
-        );
+        let verification_result = verify_chunk(invalid_chunk_proof, &[0; 32], &None);
         assert!(matches!(
             verification_result,
             Err(ChunkRestoringError(InvalidChunkProof(
@@ -821,11 +1140,7 @@ mod tests {
 
         // should not accept kvdigest
         let invalid_chunk_proof = vec![Op::Push(Node::KVDigest(vec![0], [0; 32]))];
-        let verification_result = Restorer::<PrefixedRocksDbTransactionContext>::verify_chunk(
-            invalid_chunk_proof,
-            &[0; 32],
-            &None,
-        );
+        let verification_result = verify_chunk(invalid_chunk_proof, &[0; 32], &None);
         assert!(matches!(
             verification_result,
             Err(ChunkRestoringError(InvalidChunkProof(
@@ -835,11 +1150,7 @@ mod tests {
 
         // should not accept kvrefvaluehash
         let invalid_chunk_proof = vec![Op::Push(Node::KVRefValueHash(vec![0], vec![0], [0; 32]))];
-        let verification_result = Restorer::<PrefixedRocksDbTransactionContext>::verify_chunk(
-            invalid_chunk_proof,
-            &[0; 32],
-            &None,
-        );
+        let verification_result = verify_chunk(invalid_chunk_proof, &[0; 32], &None);
         assert!(matches!(
             verification_result,
             Err(ChunkRestoringError(InvalidChunkProof(
@@ -1666,13 +1977,203 @@ mod tests {
         assert_eq!(restorer.parent_keys.len(), 0);
 
         // recover state
-        let recovery_attempt = restorer.attempt_state_recovery(grove_version);
-        assert!(recovery_attempt.is_ok());
+        let recovered_chunk_ids = restorer
+            .attempt_state_recovery(grove_version)
+            .expect("should recover state");
         assert_eq!(restorer.chunk_id_to_root_hash.len(), 4);
         assert_eq!(restorer.parent_keys.len(), 4);
+        assert_eq!(
+            recovered_chunk_ids,
+            old_chunk_id_to_root_hash
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        );
 
         // assert equality to old state
         assert_eq!(old_chunk_id_to_root_hash, restorer.chunk_id_to_root_hash);
         assert_eq!(old_parent_keys, restorer.parent_keys);
     }
+
+    #[test]
+    fn test_chunks_verified_on_other_threads() {
+        let grove_version = GroveVersion::latest();
+        let mut merk = TempMerk::new(grove_version);
+        let batch = make_batch_seq(0..15);
+        merk.apply::<_, Vec<_>>(&batch, &[], None, grove_version)
+            .unwrap()
+            .expect("apply failed");
+
+        let storage = TempStorage::new();
+        let tx = storage.start_transaction();
+        let restoration_merk = Merk::open_base(
+            storage
+                .get_immediate_storage_context(SubtreePath::empty(), &tx)
+                .unwrap(),
+            TreeType::NormalTree,
+            None::<&fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+            grove_version,
+        )
+        .unwrap()
+        .unwrap();
+
+        let mut chunk_producer = ChunkProducer::new(&merk).expect("should create chunk producer");
+        let mut restorer = Restorer::new(restoration_merk, merk.root_hash().unwrap(), None);
+
+        let (chunk, _) = chunk_producer.chunk_with_index(1, grove_version).unwrap();
+        let new_chunk_ids = restorer
+            .process_chunk(&[], chunk, grove_version)
+            .expect("should process chunk");
+        assert_eq!(new_chunk_ids.len(), 4);
+
+        let chunks: Vec<_> = new_chunk_ids
+            .iter()
+            .map(|chunk_id| {
+                let (chunk, _) = chunk_producer
+                    .chunk(chunk_id, grove_version)
+                    .expect("should produce chunk");
+                let expectation = restorer
+                    .chunk_expectation(chunk_id)
+                    .expect("chunk should be expected");
+                (expectation, chunk)
+            })
+            .collect();
+        let (stale_expectation, stale_chunk) = chunks[0].clone();
+
+        let verified_chunks: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = chunks
+                .into_iter()
+                .map(|(expectation, chunk)| scope.spawn(move || expectation.verify(chunk)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap().expect("should verify chunk"))
+                .collect()
+        });
+        for verified_chunk in verified_chunks {
+            restorer
+                .apply_verified_chunk(verified_chunk, grove_version)
+                .expect("should apply verified chunk");
+        }
+
+        // a chunk can't be applied twice
+        let stale_chunk = stale_expectation
+            .verify(stale_chunk)
+            .expect("should verify chunk");
+        assert!(matches!(
+            restorer.apply_verified_chunk(stale_chunk, grove_version),
+            Err(ChunkRestoringError(ChunkError::UnexpectedChunk))
+        ));
+
+        let restored_merk = restorer.finalize(grove_version).expect("should finalize");
+        assert_eq!(
+            restored_merk.root_hash().unwrap(),
+            merk.root_hash().unwrap()
+        );
+    }
+
+    /// Restores the tree of `chunk_producer` with `restorer`, requesting the
+    /// chunk ids it returns, and returns the restored merk with the number
+    /// of chunks it took.
+    fn restore_requested_chunks<'db, 'p, S: StorageContext<'db>>(
+        mut restorer: Restorer<S>,
+        chunk_producer: &mut ChunkProducer<'p, PrefixedRocksDbImmediateStorageContext<'p>>,
+        grove_version: &GroveVersion,
+    ) -> (Merk<S>, usize) {
+        let mut chunk_ids = vec![vec![]];
+        let mut num_chunks = 0;
+        while let Some(chunk_id) = chunk_ids.pop() {
+            let (chunk, _) = chunk_producer
+                .chunk(&chunk_id, grove_version)
+                .expect("should produce chunk");
+            chunk_ids.extend(
+                restorer
+                    .process_chunk(&chunk_id, chunk, grove_version)
+                    .expect("should process chunk"),
+            );
+            num_chunks += 1;
+        }
+        let restored_merk = restorer.finalize(grove_version).expect("should finalize");
+        (restored_merk, num_chunks)
+    }
+
+    #[test]
+    fn test_incremental_restoration_reuses_stored_subtrees() {
+        let grove_version = GroveVersion::latest();
+        // the source tree is the older tree with a few changes
+        let source_storage = TempStorage::new();
+        let source_tx = source_storage.start_transaction();
+        let mut source_merk = Merk::open_base(
+            source_storage
+                .get_immediate_storage_context(SubtreePath::empty(), &source_tx)
+                .unwrap(),
+            TreeType::NormalTree,
+            None::<&fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+            grove_version,
+        )
+        .unwrap()
+        .unwrap();
+        for batch in [
+            make_batch_seq(0..1000),
+            make_del_batch_seq(200..210),
+            vec![(
+                seq_key(600).to_vec(),
+                crate::Op::Put(vec![7; 60], TreeFeatureType::BasicMerkNode),
+            )],
+            make_batch_seq(1000..1010),
+        ] {
+            source_merk
+                .apply::<_, Vec<_>>(&batch, &[], None, grove_version)
+                .unwrap()
+                .expect("apply failed");
+        }
+        let source_root_hash = source_merk.root_hash().unwrap();
+        let mut chunk_producer =
+            ChunkProducer::new(&source_merk).expect("should create chunk producer");
+
+        let empty_storage = TempStorage::new();
+        let empty_tx = empty_storage.start_transaction();
+        let empty_merk = Merk::open_base(
+            empty_storage
+                .get_immediate_storage_context(SubtreePath::empty(), &empty_tx)
+                .unwrap(),
+            TreeType::NormalTree,
+            None::<&fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+            grove_version,
+        )
+        .unwrap()
+        .unwrap();
+        let (_, num_chunks_from_empty) = restore_requested_chunks(
+            Restorer::new(empty_merk, source_root_hash, None),
+            &mut chunk_producer,
+            grove_version,
+        );
+
+        let storage = TempStorage::new();
+        let tx = storage.start_transaction();
+        let mut older_merk = Merk::open_base(
+            storage
+                .get_immediate_storage_context(SubtreePath::empty(), &tx)
+                .unwrap(),
+            TreeType::NormalTree,
+            None::<&fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+            grove_version,
+        )
+        .unwrap()
+        .unwrap();
+        older_merk
+            .apply::<_, Vec<_>>(&make_batch_seq(0..1000), &[], None, grove_version)
+            .unwrap()
+            .expect("apply failed");
+        let restorer = Restorer::new_incremental(older_merk, source_root_hash, None);
+        let (restored_merk, num_chunks) =
+            restore_requested_chunks(restorer, &mut chunk_producer, grove_version);
+
+        assert!(
+            num_chunks < num_chunks_from_empty,
+            "{num_chunks} chunks restored the tree, {num_chunks_from_empty} from empty storage"
+        );
+        // nodes of the older tree missing from the source tree were deleted
+        assert_raw_db_entries_eq(&restored_merk, &source_merk, 1000);
+    }
 }
@@ -119,8 +119,18 @@ impl TrunkQueryResult {
             | Node::KVDigestCount(key, ..)
             | Node::KVRefValueHash(key, ..)
             | Node::KVCount(key, ..)
-            | Node::KVRefValueHashCount(key, ..) => Some(key.clone()),
-            Node::Hash(_) | Node::KVHash(_) | Node::KVHashCount(..) => None,
+            | Node::KVRefValueHashCount(key, ..)
+            | Node::KVMinMax(key, ..)
+            | Node::KVRefValueHashMinMax(key, ..)
+            | Node::KVDigestMinMax(key, ..)
+            | Node::KVSum(key, ..)
+            | Node::KVRefValueHashSum(key, ..)
+            | Node::KVDigestSum(key, ..) => Some(key.clone()),
+            Node::Hash(_)
+            | Node::KVHash(_)
+            | Node::KVHashCount(..)
+            | Node::KVHashMinMax(..)
+            | Node::KVHashSum(..) => None,
         }
     }
 
@@ -382,8 +392,18 @@ impl BranchQueryResult {
             | Node::KVDigestCount(key, ..)
             | Node::KVRefValueHash(key, ..)
             | Node::KVCount(key, ..)
-            | Node::KVRefValueHashCount(key, ..) => Some(key.clone()),
-            Node::Hash(_) | Node::KVHash(_) | Node::KVHashCount(..) => None,
+            | Node::KVRefValueHashCount(key, ..)
+            | Node::KVMinMax(key, ..)
+            | Node::KVRefValueHashMinMax(key, ..)
+            | Node::KVDigestMinMax(key, ..)
+            | Node::KVSum(key, ..)
+            | Node::KVRefValueHashSum(key, ..)
+            | Node::KVDigestSum(key, ..) => Some(key.clone()),
+            Node::Hash(_)
+            | Node::KVHash(_)
+            | Node::KVHashCount(..)
+            | Node::KVHashMinMax(..)
+            | Node::KVHashSum(..) => None,
         }
     }
 }
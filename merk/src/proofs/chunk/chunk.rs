@@ -148,6 +148,8 @@ where
     /// - Items in ProvableCountTree: KVCount (includes count in hash)
     /// - Subtrees in regular trees: KVValueHash (combined hash)
     /// - Subtrees in ProvableCountTree: KVValueHashFeatureType (includes count)
+    /// - Items in MinMaxTree: KVMinMax (includes min and max in hash)
+    /// - Items in provable sum trees: KVSum (includes the sum in hash)
     ///
     /// For raw merk values (not GroveDB Elements), defaults to
     /// KVValueHashFeatureType for backward compatibility with
@@ -172,6 +174,10 @@ where
             // GroveDB will post-process if needed
             ProofNodeType::KvRefValueHash => self.to_kv_value_hash_node(),
             ProofNodeType::KvRefValueHashCount => self.to_kv_value_hash_feature_type_node(),
+            ProofNodeType::KvMinMax => self.to_kv_min_max_node(),
+            ProofNodeType::KvRefValueHashMinMax => self.to_kv_value_hash_feature_type_node(),
+            ProofNodeType::KvSum => self.to_kv_sum_node(),
+            ProofNodeType::KvRefValueHashSum => self.to_kv_value_hash_feature_type_node(),
         }
     }
 
@@ -8,6 +8,8 @@ mod merk_integration_tests;
 #[cfg(any(feature = "minimal", feature = "verify"))]
 mod map;
 #[cfg(any(feature = "minimal", feature = "verify"))]
+mod range_aggregate;
+#[cfg(any(feature = "minimal", feature = "verify"))]
 mod verify;
 
 #[cfg(feature = "minimal")]
@@ -19,6 +21,14 @@ use grovedb_version::version::GroveVersion;
 #[cfg(any(feature = "minimal", feature = "verify"))]
 pub use map::{Map, MapBuilder};
 #[cfg(any(feature = "minimal", feature = "verify"))]
+#[cfg(feature = "minimal")]
+pub(crate) use range_aggregate::RangeAggregate;
+#[cfg(any(feature = "minimal", feature = "verify"))]
+pub use range_aggregate::{
+    verify_key_at_rank_proof, verify_range_count_proof, verify_range_sum_proof,
+    verify_rank_of_key_proof,
+};
+#[cfg(any(feature = "minimal", feature = "verify"))]
 pub use verify::{
     boundaries_in_proof, key_exists_as_boundary_in_proof, ProofVerificationResult,
     ProvedKeyOptionalValue, ProvedKeyValue, QueryProofVerify, VerifyOptions, PROOF_VERSION_LATEST,
@@ -84,6 +94,11 @@ where
             Ok(AggregateData::ProvableCountAndSum(count, sum)) => {
                 TreeFeatureType::ProvableCountedSummedMerkNode(count, sum)
             }
+            Ok(AggregateData::MinMax(min, max)) => TreeFeatureType::MinMaxMerkNode(min, max),
+            Ok(AggregateData::ProvableSum(sum)) => TreeFeatureType::ProvableSummedMerkNode(sum),
+            Ok(AggregateData::ProvableBigSum(sum)) => {
+                TreeFeatureType::ProvableBigSummedMerkNode(sum)
+            }
             _ => self.tree().feature_type(),
         };
         Node::KVValueHashFeatureType(
@@ -164,6 +179,83 @@ where
         )
     }
 
+    /// Returns the aggregate `(min, max)` of the root node's subtree, which is
+    /// what a MinMaxTree node hash commits to.
+    fn aggregate_min_max(&self) -> (i64, i64) {
+        self.tree()
+            .aggregate_data()
+            .map(|aggregate_data| aggregate_data.as_min_max())
+            .unwrap_or((i64::MAX, i64::MIN))
+    }
+
+    /// Creates a `Node::KVMinMax` from the key/value pair and aggregate
+    /// min/max of the root node. Used for items in a MinMaxTree.
+    pub(crate) fn to_kv_min_max_node(&self) -> Node {
+        let (min, max) = self.aggregate_min_max();
+        Node::KVMinMax(
+            self.tree().key().to_vec(),
+            self.tree().value_as_slice().to_vec(),
+            min,
+            max,
+        )
+    }
+
+    /// Creates a `Node::KVHashMinMax` from the kv hash and aggregate min/max
+    /// of the root node. Used for non-queried nodes of a MinMaxTree.
+    pub(crate) fn to_kvhash_min_max_node(&self) -> Node {
+        let (min, max) = self.aggregate_min_max();
+        Node::KVHashMinMax(*self.tree().kv_hash(), min, max)
+    }
+
+    /// Creates a `Node::KVDigestMinMax` from the key/value_hash pair and
+    /// aggregate min/max of the root node. Used for boundary nodes of a
+    /// MinMaxTree.
+    pub(crate) fn to_kvdigest_min_max_node(&self) -> Node {
+        let (min, max) = self.aggregate_min_max();
+        Node::KVDigestMinMax(
+            self.tree().key().to_vec(),
+            *self.tree().value_hash(),
+            min,
+            max,
+        )
+    }
+
+    /// Returns the aggregate sum of the root node's subtree, which is what a
+    /// provable sum tree node hash commits to.
+    fn aggregate_provable_sum(&self) -> i128 {
+        self.tree()
+            .aggregate_data()
+            .map(|aggregate_data| aggregate_data.as_summed_i128())
+            .unwrap_or_default()
+    }
+
+    /// Creates a `Node::KVSum` from the key/value pair and aggregate sum of
+    /// the root node. Used for items in a provable sum tree.
+    pub(crate) fn to_kv_sum_node(&self) -> Node {
+        Node::KVSum(
+            self.tree().key().to_vec(),
+            self.tree().value_as_slice().to_vec(),
+            self.aggregate_provable_sum(),
+        )
+    }
+
+    /// Creates a `Node::KVHashSum` from the kv hash and aggregate sum of the
+    /// root node. Used for non-queried nodes of a provable sum tree.
+    pub(crate) fn to_kvhash_sum_node(&self) -> Node {
+        Node::KVHashSum(*self.tree().kv_hash(), self.aggregate_provable_sum())
+    }
+
+    /// Creates a `Node::KVDigestSum` from the key/value_hash pair and
+    /// aggregate sum of the root node. Used for boundary nodes of a provable
+    /// sum tree.
+    pub(crate) fn to_kvdigest_sum_node(&self) -> Node {
+        Node::KVDigestSum(
+            self.tree().key().to_vec(),
+            *self.tree().value_hash(),
+            self.aggregate_provable_sum(),
+        )
+    }
+
     #[cfg(feature = "minimal")]
     pub(crate) fn create_proof(
         &mut self,
@@ -183,6 +275,51 @@ where
         )
     }
 
+    /// Like `create_proof`, but skips the first `offset` matches, proving
+    /// them by their key and value hash only. The skipped matches do not
+    /// count against `limit`.
+    #[cfg(feature = "minimal")]
+    pub(crate) fn create_proof_with_offset(
+        &mut self,
+        query: &[QueryItem],
+        limit: Option<u16>,
+        offset: u16,
+        left_to_right: bool,
+        grove_version: &GroveVersion,
+    ) -> CostResult<ProofAbsenceLimit, Error> {
+        let (proof_query_items, proof_params) =
+            ProofItems::new_with_query_items(query, left_to_right);
+        let proof_status = ProofStatus::new_with_limit_and_offset(limit, offset);
+        self.create_proof_internal(
+            &proof_query_items,
+            &proof_params,
+            proof_status,
+            grove_version,
+        )
+    }
+
+    /// Like `create_proof`, but proves the matched nodes by their key and
+    /// value hash only (`KVDigest` or `KVDigestCount` nodes).
+    #[cfg(feature = "minimal")]
+    pub(crate) fn create_keys_proof(
+        &mut self,
+        query: &[QueryItem],
+        limit: Option<u16>,
+        left_to_right: bool,
+        grove_version: &GroveVersion,
+    ) -> CostResult<ProofAbsenceLimit, Error> {
+        let (proof_query_items, mut proof_params) =
+            ProofItems::new_with_query_items(query, left_to_right);
+        proof_params.keys_only = true;
+        let proof_status = ProofStatus::new_with_limit(limit);
+        self.create_proof_internal(
+            &proof_query_items,
+            &proof_params,
+            proof_status,
+            grove_version,
+        )
+    }
+
     /// Generates a proof for the list of queried items. Returns a tuple
     /// containing the generated proof operators, and a tuple representing if
     /// any keys were queried were less than the left edge or greater than the
@@ -221,7 +358,7 @@ where
         }
 
         let proof_direction = proof_params.left_to_right; // search the opposite path on second pass
-        let (mut proof, left_absence, proof_status) = if proof_params.left_to_right {
+        let (mut proof, left_absence, mut proof_status) = if proof_params.left_to_right {
             cost_return_on_error!(
                 &mut cost,
                 self.create_child_proof(
@@ -247,7 +384,16 @@ where
 
         let mut new_limit = None;
 
-        if let Some(current_limit) = proof_status.limit {
+        // A match still covered by the offset is skipped: it is proved like a
+        // boundary and leaves the limit untouched.
+        let skipped = found_item
+            && !on_boundary_not_found
+            && proof_status.offset > 0
+            && !proof_status.hit_limit();
+
+        if skipped {
+            proof_status.offset -= 1;
+        } else if let Some(current_limit) = proof_status.limit {
             // if after generating proof for the left subtree, the limit becomes 0
             // clear the current node and clear the right batch
             if current_limit == 0 {
@@ -308,17 +454,33 @@ where
                 | TreeFeatureType::ProvableCountedSummedMerkNode(..)
         );
 
+        let is_min_max_tree = matches!(
+            self.tree().feature_type(),
+            TreeFeatureType::MinMaxMerkNode(..)
+        );
+
+        let is_provable_sum_tree = matches!(
+            self.tree().feature_type(),
+            TreeFeatureType::ProvableSummedMerkNode(_)
+                | TreeFeatureType::ProvableBigSummedMerkNode(_)
+        );
+
         // Convert is_provable_count_tree to parent tree type for proof_node_type()
         // Both ProvableCountTree and ProvableCountSumTree use count in hash
         let parent_tree_type = if is_provable_count_tree {
             // Use ProvableCountTree for both since proof handling is the same (count in
             // hash)
             Some(ElementType::ProvableCountTree)
+        } else if is_min_max_tree {
+            Some(ElementType::MinMaxTree)
+        } else if is_provable_sum_tree {
+            // Both provable sum trees hash the sum as an i128
+            Some(ElementType::ProvableSumTree)
         } else {
             None // Regular tree or unknown - treated the same
         };
 
-        let proof_op = if found_item {
+        let proof_op = if found_item && !proof_params.keys_only && !skipped {
             // For query proofs, we need to include the actual key/value data.
             // The node type depends on the element type stored in the value:
             // - Items (simple hash): use KV or KVCount (verifier computes hash -
@@ -353,6 +515,13 @@ where
                 // ProvableCountTree references: generate KVValueHashFeatureType
                 // GroveDB will post-process to KVRefValueHashCount with dereferenced value
                 ProofNodeType::KvRefValueHashCount => self.to_kv_value_hash_feature_type_node(),
+                ProofNodeType::KvMinMax => self.to_kv_min_max_node(),
+                // MinMaxTree references: GroveDB post-processes to KVRefValueHashMinMax
+                ProofNodeType::KvRefValueHashMinMax => self.to_kv_value_hash_feature_type_node(),
+                ProofNodeType::KvSum => self.to_kv_sum_node(),
+                // Provable sum tree references: GroveDB post-processes to
+                // KVRefValueHashSum
+                ProofNodeType::KvRefValueHashSum => self.to_kv_value_hash_feature_type_node(),
             };
 
             if proof_params.left_to_right {
@@ -360,11 +529,21 @@ where
             } else {
                 Op::PushInverted(node)
             }
-        } else if on_boundary_not_found || left_absence.1 || right_absence.0 {
-            // On boundary (proving absence): use KVDigest or KVDigestCount
-            // depending on whether this is a ProvableCountTree
+        } else if on_boundary_not_found
+            || left_absence.1
+            || right_absence.0
+            || (found_item && (proof_params.keys_only || skipped))
+        {
+            // On boundary (proving absence), matched by a keys only proof or
+            // skipped by the offset:
+            // use KVDigest or KVDigestCount depending on whether this is a
+            // ProvableCountTree
             let node = if is_provable_count_tree {
                 self.to_kvdigest_count_node()
+            } else if is_min_max_tree {
+                self.to_kvdigest_min_max_node()
+            } else if is_provable_sum_tree {
+                self.to_kvdigest_sum_node()
             } else {
                 self.to_kvdigest_node()
             };
@@ -379,6 +558,18 @@ where
             } else {
                 Op::PushInverted(self.to_kvhash_count_node())
             }
+        } else if is_min_max_tree {
+            if proof_params.left_to_right {
+                Op::Push(self.to_kvhash_min_max_node())
+            } else {
+                Op::PushInverted(self.to_kvhash_min_max_node())
+            }
+        } else if is_provable_sum_tree {
+            if proof_params.left_to_right {
+                Op::Push(self.to_kvhash_sum_node())
+            } else {
+                Op::PushInverted(self.to_kvhash_sum_node())
+            }
         } else if proof_params.left_to_right {
             Op::Push(self.to_kvhash_node())
         } else {
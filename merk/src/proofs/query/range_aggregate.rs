@@ -0,0 +1,565 @@
+//! Proofs of the aggregate count or sum of the keys in a range of a provable
+//! count or provable sum tree.
+//!
+//! Provable count trees hash the aggregate count of every subtree into its
+//! node hash, and provable sum trees do the same with the aggregate sum. A
+//! range proof walks down to the two ends of the range only: every subtree
+//! whose keys are known from its ancestors to be all in or all out of the
+//! range is proven by the `KVHashCount` (or `KVHashSum`) node of its root and
+//! the hashes of that root's children, while the nodes the walks go through
+//! are proven by `KVDigestCount` (or `KVDigestSum`) nodes. The proof therefore
+//! stays logarithmic in the size of the tree.
+//!
+//! Ranks in provable count trees are proven the same way. The rank of a key
+//! is the count of the keys before it, and the range count proof of those
+//! keys walks down to the key itself, so it also proves whether the key is in
+//! the tree. The same proof made for the key at a rank lets the verifier
+//! find that key again from the counts along the walk.
+
+use std::cmp::Ordering;
+#[cfg(feature = "minimal")]
+use std::collections::LinkedList;
+
+use grovedb_costs::{
+    cost_return_on_error, cost_return_on_error_no_add, CostResult, CostsExt, OperationCost,
+};
+#[cfg(feature = "minimal")]
+use grovedb_version::version::GroveVersion;
+
+use super::QueryItem;
+#[cfg(feature = "minimal")]
+use crate::tree::{kv::ValueDefinedCostType, Fetch, RefWalker};
+use crate::{
+    error::Error,
+    proofs::{
+        tree::{Tree, MAX_PROOF_OPS, MAX_PROOF_STACK_DEPTH},
+        Decoder, Node, Op,
+    },
+    CryptoHash,
+};
+
+/// The aggregate a range proof is made for.
+#[cfg(feature = "minimal")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RangeAggregate {
+    /// The count of a provable count tree.
+    Count,
+    /// The sum of a provable sum tree.
+    Sum,
+}
+
+/// How the keys between two exclusive bounds relate to a range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangeCoverage {
+    Inside,
+    Outside,
+    Partial,
+}
+
+/// Tells whether all keys strictly between `lower` and `upper` are known to
+/// be in `range`, known to be out of it, or neither. `None` bounds are
+/// unbounded.
+fn range_coverage(range: &QueryItem, lower: Option<&[u8]>, upper: Option<&[u8]>) -> RangeCoverage {
+    let (start, _) = range.lower_bound();
+    let (end, _) = range.upper_bound();
+
+    let starts_inside =
+        range.lower_unbounded() || matches!(lower, Some(lower) if Some(lower) >= start);
+    let ends_inside = range.upper_unbounded() || matches!(upper, Some(upper) if Some(upper) <= end);
+    if starts_inside && ends_inside {
+        return RangeCoverage::Inside;
+    }
+
+    let before_start =
+        !range.lower_unbounded() && matches!(upper, Some(upper) if Some(upper) <= start);
+    let after_end = !range.upper_unbounded() && matches!(lower, Some(lower) if Some(lower) >= end);
+    if before_start || after_end {
+        RangeCoverage::Outside
+    } else {
+        RangeCoverage::Partial
+    }
+}
+
+#[cfg(feature = "minimal")]
+impl<S> RefWalker<'_, S>
+where
+    S: Fetch + Sized + Clone,
+{
+    /// Generates the range proof of `aggregate` for the subtree of this node,
+    /// whose keys are strictly between `lower` and `upper`.
+    pub(crate) fn create_range_aggregate_proof(
+        &mut self,
+        aggregate: RangeAggregate,
+        range: &QueryItem,
+        lower: Option<&[u8]>,
+        upper: Option<&[u8]>,
+        grove_version: &GroveVersion,
+    ) -> CostResult<LinkedList<Op>, Error> {
+        let mut cost = OperationCost::default();
+        let mut proof = LinkedList::new();
+
+        if range_coverage(range, lower, upper) != RangeCoverage::Partial {
+            if let Some(link) = self.tree().link(true) {
+                proof.push_back(Op::Push(link.to_hash_node()));
+            }
+            proof.push_back(Op::Push(match aggregate {
+                RangeAggregate::Count => self.to_kvhash_count_node(),
+                RangeAggregate::Sum => self.to_kvhash_sum_node(),
+            }));
+            if self.tree().link(true).is_some() {
+                proof.push_back(Op::Parent);
+            }
+            if let Some(link) = self.tree().link(false) {
+                proof.push_back(Op::Push(link.to_hash_node()));
+                proof.push_back(Op::Child);
+            }
+            return Ok(proof).wrap_with_cost(cost);
+        }
+
+        let key = self.tree().key().to_vec();
+        let mut left_proof = cost_return_on_error!(
+            &mut cost,
+            self.create_child_range_aggregate_proof(
+                true,
+                aggregate,
+                range,
+                lower,
+                Some(&key),
+                grove_version
+            )
+        );
+        let has_left = !left_proof.is_empty();
+        proof.append(&mut left_proof);
+        proof.push_back(Op::Push(match aggregate {
+            RangeAggregate::Count => self.to_kvdigest_count_node(),
+            RangeAggregate::Sum => self.to_kvdigest_sum_node(),
+        }));
+        if has_left {
+            proof.push_back(Op::Parent);
+        }
+
+        let mut right_proof = cost_return_on_error!(
+            &mut cost,
+            self.create_child_range_aggregate_proof(
+                false,
+                aggregate,
+                range,
+                Some(&key),
+                upper,
+                grove_version
+            )
+        );
+        if !right_proof.is_empty() {
+            proof.append(&mut right_proof);
+            proof.push_back(Op::Child);
+        }
+        Ok(proof).wrap_with_cost(cost)
+    }
+
+    fn create_child_range_aggregate_proof(
+        &mut self,
+        left: bool,
+        aggregate: RangeAggregate,
+        range: &QueryItem,
+        lower: Option<&[u8]>,
+        upper: Option<&[u8]>,
+        grove_version: &GroveVersion,
+    ) -> CostResult<LinkedList<Op>, Error> {
+        self.walk(
+            left,
+            None::<&fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+            grove_version,
+        )
+        .flat_map_ok(|child| match child {
+            Some(mut child) => {
+                child.create_range_aggregate_proof(aggregate, range, lower, upper, grove_version)
+            }
+            None => Ok(LinkedList::new()).wrap_with_cost(Default::default()),
+        })
+    }
+
+    /// Returns the key and value of the node holding `rank` in the subtree of
+    /// this node, or `None` if the subtree counts no more than `rank`. Ranks
+    /// start at zero, and every node holds as many of them as it counts.
+    pub(crate) fn key_value_at_rank(
+        &mut self,
+        rank: u64,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Option<(Vec<u8>, Vec<u8>)>, Error> {
+        let left_count = match self.tree().child_aggregate_count_data_as_u64(true) {
+            Ok(count) => count,
+            Err(e) => return Err(e).wrap_with_cost(Default::default()),
+        };
+        let own_count = self.tree().feature_type().count().unwrap_or_default();
+        if rank < left_count {
+            self.child_key_value_at_rank(true, rank, grove_version)
+        } else if rank - left_count < own_count {
+            let tree = self.tree();
+            Ok(Some((tree.key().to_vec(), tree.value_as_slice().to_vec())))
+                .wrap_with_cost(Default::default())
+        } else {
+            self.child_key_value_at_rank(false, rank - left_count - own_count, grove_version)
+        }
+    }
+
+    fn child_key_value_at_rank(
+        &mut self,
+        left: bool,
+        rank: u64,
+        grove_version: &GroveVersion,
+    ) -> CostResult<Option<(Vec<u8>, Vec<u8>)>, Error> {
+        self.walk(
+            left,
+            None::<&fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+            grove_version,
+        )
+        .flat_map_ok(|child| match child {
+            Some(mut child) => child.key_value_at_rank(rank, grove_version),
+            None => Ok(None).wrap_with_cost(Default::default()),
+        })
+    }
+
+    /// Returns the first rank held by `key` in the subtree of this node, or
+    /// `None` if the key is not in the subtree.
+    pub(crate) fn rank_of_key(
+        &mut self,
+        key: &[u8],
+        grove_version: &GroveVersion,
+    ) -> CostResult<Option<u64>, Error> {
+        let left_count = match self.tree().child_aggregate_count_data_as_u64(true) {
+            Ok(count) => count,
+            Err(e) => return Err(e).wrap_with_cost(Default::default()),
+        };
+        match key.cmp(self.tree().key()) {
+            Ordering::Less => self.child_rank_of_key(true, key, grove_version),
+            Ordering::Equal => Ok(Some(left_count)).wrap_with_cost(Default::default()),
+            Ordering::Greater => {
+                let before = left_count + self.tree().feature_type().count().unwrap_or_default();
+                self.child_rank_of_key(false, key, grove_version)
+                    .map_ok(|rank| rank.map(|rank| before + rank))
+            }
+        }
+    }
+
+    fn child_rank_of_key(
+        &mut self,
+        left: bool,
+        key: &[u8],
+        grove_version: &GroveVersion,
+    ) -> CostResult<Option<u64>, Error> {
+        self.walk(
+            left,
+            None::<&fn(&[u8], &GroveVersion) -> Option<ValueDefinedCostType>>,
+            grove_version,
+        )
+        .flat_map_ok(|child| match child {
+            Some(mut child) => child.rank_of_key(key, grove_version),
+            None => Ok(None).wrap_with_cost(Default::default()),
+        })
+    }
+}
+
+/// Verifies an encoded proof of the rank of `key`, made as the range count
+/// proof of the keys before it. Returns the root hash of the tree and the
+/// rank of the key, or `None` if the proof shows the key is not in the tree.
+pub fn verify_rank_of_key_proof(
+    bytes: &[u8],
+    key: &[u8],
+) -> CostResult<(CryptoHash, Option<u64>), Error> {
+    let mut cost = OperationCost::default();
+
+    let tree = cost_return_on_error!(&mut cost, rebuild_proof_tree(bytes));
+    let range = QueryItem::RangeTo(..key.to_vec());
+    let rank = match count_in_range(&tree, &range, None, None)
+        .and_then(|(_, rank)| Ok(key_is_proven(&tree, key)?.then_some(rank)))
+    {
+        Ok(rank) => rank,
+        Err(e) => return Err(e).wrap_with_cost(cost),
+    };
+    tree.hash().map(|hash| Ok((hash, rank))).add_cost(cost)
+}
+
+/// Verifies an encoded proof of the key at `rank`. Returns the root hash of
+/// the tree and the key holding the rank, or `None` if the tree counts no
+/// more than `rank`.
+pub fn verify_key_at_rank_proof(
+    bytes: &[u8],
+    rank: u64,
+) -> CostResult<(CryptoHash, Option<Vec<u8>>), Error> {
+    let mut cost = OperationCost::default();
+
+    let tree = cost_return_on_error!(&mut cost, rebuild_proof_tree(bytes));
+    let key = match key_at_rank(&tree, rank) {
+        Ok(key) => key,
+        Err(e) => return Err(e).wrap_with_cost(cost),
+    };
+    tree.hash().map(|hash| Ok((hash, key))).add_cost(cost)
+}
+
+/// Verifies an encoded range count proof, returning the root hash of the
+/// tree and the aggregate count of the keys in `range`.
+pub fn verify_range_count_proof(
+    bytes: &[u8],
+    range: &QueryItem,
+) -> CostResult<(CryptoHash, u64), Error> {
+    let mut cost = OperationCost::default();
+
+    let tree = cost_return_on_error!(&mut cost, rebuild_proof_tree(bytes));
+    let count = match count_in_range(&tree, range, None, None) {
+        Ok((_, count)) => count,
+        Err(e) => return Err(e).wrap_with_cost(cost),
+    };
+    tree.hash().map(|hash| Ok((hash, count))).add_cost(cost)
+}
+
+/// Verifies an encoded range sum proof, returning the root hash of the tree
+/// and the aggregate sum of the keys in `range`.
+pub fn verify_range_sum_proof(
+    bytes: &[u8],
+    range: &QueryItem,
+) -> CostResult<(CryptoHash, i128), Error> {
+    let mut cost = OperationCost::default();
+
+    let tree = cost_return_on_error!(&mut cost, rebuild_proof_tree(bytes));
+    let sum = match sum_in_range(&tree, range, None, None) {
+        Ok((_, sum)) => sum,
+        Err(e) => return Err(e).wrap_with_cost(cost),
+    };
+    tree.hash().map(|hash| Ok((hash, sum))).add_cost(cost)
+}
+
+/// Rebuilds the tree of a range proof. Unlike `execute`, this does not
+/// expect the tree to be balanced: the walk to one end of the range can go
+/// much deeper than the walk to the other.
+fn rebuild_proof_tree(bytes: &[u8]) -> CostResult<Tree, Error> {
+    let mut cost = OperationCost::default();
+    let mut stack: Vec<Tree> = Vec::new();
+
+    for (op_count, op) in Decoder::new(bytes).enumerate() {
+        if op_count >= MAX_PROOF_OPS || stack.len() > MAX_PROOF_STACK_DEPTH {
+            return Err(Error::InvalidProofError(
+                "Range proof is too large".to_string(),
+            ))
+            .wrap_with_cost(cost);
+        }
+        let (left, child, mut parent) = match cost_return_on_error_no_add!(cost, op) {
+            Op::Push(
+                node @ (Node::Hash(_)
+                | Node::KVHashCount(..)
+                | Node::KVDigestCount(..)
+                | Node::KVHashSum(..)
+                | Node::KVDigestSum(..)),
+            ) => {
+                stack.push(node.into());
+                continue;
+            }
+            Op::Parent if stack.len() >= 2 => {
+                let parent = stack.pop().unwrap();
+                (true, stack.pop().unwrap(), parent)
+            }
+            Op::Child if stack.len() >= 2 => {
+                let child = stack.pop().unwrap();
+                (false, child, stack.pop().unwrap())
+            }
+            op => {
+                return Err(Error::InvalidProofError(format!(
+                    "Unexpected operation in range proof: {:?}",
+                    op
+                )))
+                .wrap_with_cost(cost);
+            }
+        };
+        cost_return_on_error!(&mut cost, parent.attach(left, child));
+        stack.push(parent);
+    }
+
+    match (stack.pop(), stack.is_empty()) {
+        (Some(tree), true) => Ok(tree).wrap_with_cost(cost),
+        _ => Err(Error::InvalidProofError(
+            "Expected range proof to result in exactly one tree".to_string(),
+        ))
+        .wrap_with_cost(cost),
+    }
+}
+
+/// Returns the aggregate count of `tree` and the part of it in `range`, the
+/// keys of `tree` being strictly between `lower` and `upper`.
+fn count_in_range(
+    tree: &Tree,
+    range: &QueryItem,
+    lower: Option<&[u8]>,
+    upper: Option<&[u8]>,
+) -> Result<(u64, u64), Error> {
+    match &tree.node {
+        Node::KVHashCount(_, count) => match range_coverage(range, lower, upper) {
+            RangeCoverage::Inside => Ok((*count, *count)),
+            RangeCoverage::Outside => Ok((*count, 0)),
+            RangeCoverage::Partial => Err(Error::InvalidProofError(
+                "Proof is missing data for the counted range".to_string(),
+            )),
+        },
+        Node::KVDigestCount(key, _, count) => {
+            let (left_count, left_in_range) = match &tree.left {
+                Some(child) => count_in_range(&child.tree, range, lower, Some(key))?,
+                None => (0, 0),
+            };
+            let (right_count, right_in_range) = match &tree.right {
+                Some(child) => count_in_range(&child.tree, range, Some(key), upper)?,
+                None => (0, 0),
+            };
+            let own_count = count
+                .checked_sub(left_count)
+                .and_then(|count| count.checked_sub(right_count))
+                .ok_or_else(|| {
+                    Error::InvalidProofError(
+                        "Node count is lower than the counts of its children".to_string(),
+                    )
+                })?;
+            let in_range = left_in_range + right_in_range;
+            Ok((
+                *count,
+                if range.contains(key) {
+                    in_range + own_count
+                } else {
+                    in_range
+                },
+            ))
+        }
+        node => Err(Error::InvalidProofError(format!(
+            "Unexpected node in range count proof: {}",
+            node
+        ))),
+    }
+}
+
+/// Returns the aggregate count proven for the subtree `tree`.
+fn proven_count(tree: &Tree) -> Result<u64, Error> {
+    match &tree.node {
+        Node::KVHashCount(_, count) | Node::KVDigestCount(_, _, count) => Ok(*count),
+        node => Err(Error::InvalidProofError(format!(
+            "Unexpected node in rank proof: {}",
+            node
+        ))),
+    }
+}
+
+/// Tells whether the walk of a rank proof towards `key` ends at `key`. The
+/// walk must have been checked to reach every node `key` could be at.
+fn key_is_proven(tree: &Tree, key: &[u8]) -> Result<bool, Error> {
+    let mut tree = tree;
+    loop {
+        let Node::KVDigestCount(node_key, ..) = &tree.node else {
+            return Err(Error::InvalidProofError(
+                "Proof is missing data for the ranked key".to_string(),
+            ));
+        };
+        let child = match key.cmp(node_key) {
+            Ordering::Equal => return Ok(true),
+            Ordering::Less => &tree.left,
+            Ordering::Greater => &tree.right,
+        };
+        match child {
+            Some(child) => tree = &child.tree,
+            None => return Ok(false),
+        }
+    }
+}
+
+/// Returns the key holding `rank` in `tree`, or `None` if the tree counts no
+/// more than `rank`.
+fn key_at_rank(tree: &Tree, rank: u64) -> Result<Option<Vec<u8>>, Error> {
+    let mut tree = tree;
+    let mut rank = rank;
+    loop {
+        let count = proven_count(tree)?;
+        if rank >= count {
+            return Ok(None);
+        }
+        let Node::KVDigestCount(key, ..) = &tree.node else {
+            return Err(Error::InvalidProofError(
+                "Proof is missing data for the rank".to_string(),
+            ));
+        };
+        let left_count = match &tree.left {
+            Some(child) => proven_count(&child.tree)?,
+            None => 0,
+        };
+        let right_count = match &tree.right {
+            Some(child) => proven_count(&child.tree)?,
+            None => 0,
+        };
+        let own_count = count
+            .checked_sub(left_count)
+            .and_then(|count| count.checked_sub(right_count))
+            .ok_or_else(|| {
+                Error::InvalidProofError(
+                    "Node count is lower than the counts of its children".to_string(),
+                )
+            })?;
+        let child = if rank < left_count {
+            &tree.left
+        } else if rank - left_count < own_count {
+            return Ok(Some(key.clone()));
+        } else {
+            rank -= left_count + own_count;
+            &tree.right
+        };
+        tree = match child {
+            Some(child) => &child.tree,
+            None => {
+                return Err(Error::InvalidProofError(
+                    "Proof is missing data for the rank".to_string(),
+                ))
+            }
+        };
+    }
+}
+
+/// Returns the aggregate sum of `tree` and the part of it in `range`, the
+/// keys of `tree` being strictly between `lower` and `upper`.
+fn sum_in_range(
+    tree: &Tree,
+    range: &QueryItem,
+    lower: Option<&[u8]>,
+    upper: Option<&[u8]>,
+) -> Result<(i128, i128), Error> {
+    let overflow = || Error::InvalidProofError("Sum overflow in range sum proof".to_string());
+    match &tree.node {
+        Node::KVHashSum(_, sum) => match range_coverage(range, lower, upper) {
+            RangeCoverage::Inside => Ok((*sum, *sum)),
+            RangeCoverage::Outside => Ok((*sum, 0)),
+            RangeCoverage::Partial => Err(Error::InvalidProofError(
+                "Proof is missing data for the summed range".to_string(),
+            )),
+        },
+        Node::KVDigestSum(key, _, sum) => {
+            let (left_sum, left_in_range) = match &tree.left {
+                Some(child) => sum_in_range(&child.tree, range, lower, Some(key))?,
+                None => (0, 0),
+            };
+            let (right_sum, right_in_range) = match &tree.right {
+                Some(child) => sum_in_range(&child.tree, range, Some(key), upper)?,
+                None => (0, 0),
+            };
+            let own_sum = sum
+                .checked_sub(left_sum)
+                .and_then(|sum| sum.checked_sub(right_sum))
+                .ok_or_else(overflow)?;
+            let in_range = left_in_range
+                .checked_add(right_in_range)
+                .ok_or_else(overflow)?;
+            Ok((
+                *sum,
+                if range.contains(key) {
+                    in_range.checked_add(own_sum).ok_or_else(overflow)?
+                } else {
+                    in_range
+                },
+            ))
+        }
+        node => Err(Error::InvalidProofError(format!(
+            "Unexpected node in range sum proof: {}",
+            node
+        ))),
+    }
+}
@@ -98,6 +98,30 @@ pub trait QueryProofVerify {
         proof_version: u16,
     ) -> CostResult<(MerkHash, ProofVerificationResult), Error>;
 
+    /// Verifies an encoded proof made by
+    /// `Merk::prove_unchecked_query_items_with_offset`. The first `offset`
+    /// matches are skipped and must be proven without their values. The
+    /// `offset` of the returned result is how many of them were not found.
+    fn execute_proof_with_offset(
+        &self,
+        bytes: &[u8],
+        limit: Option<u16>,
+        offset: u16,
+        left_to_right: bool,
+        proof_version: u16,
+    ) -> CostResult<(MerkHash, ProofVerificationResult), Error>;
+
+    /// Verifies an encoded proof made by
+    /// `Merk::prove_keys_unchecked_query_items`, in which the keys found are
+    /// proven by their value hash only. The values of the returned result
+    /// set are always `None`.
+    fn execute_keys_proof(
+        &self,
+        bytes: &[u8],
+        limit: Option<u16>,
+        left_to_right: bool,
+    ) -> CostResult<(MerkHash, ProofVerificationResult), Error>;
+
     /// Verifies the encoded proof with the given query and expected hash.
     fn verify_proof(
         &self,
@@ -127,441 +151,564 @@ impl QueryProofVerify for Query {
         left_to_right: bool,
         proof_version: u16,
     ) -> CostResult<(MerkHash, ProofVerificationResult), Error> {
-        #[cfg(feature = "proof_debug")]
-        {
-            println!(
-                "executing proof with limit {:?} going {} using query {}",
-                limit,
-                if left_to_right {
-                    "left to right"
+        execute_query_proof(self, bytes, limit, 0, left_to_right, proof_version, false)
+    }
+
+    fn execute_proof_with_offset(
+        &self,
+        bytes: &[u8],
+        limit: Option<u16>,
+        offset: u16,
+        left_to_right: bool,
+        proof_version: u16,
+    ) -> CostResult<(MerkHash, ProofVerificationResult), Error> {
+        execute_query_proof(
+            self,
+            bytes,
+            limit,
+            offset,
+            left_to_right,
+            proof_version,
+            false,
+        )
+    }
+
+    fn execute_keys_proof(
+        &self,
+        bytes: &[u8],
+        limit: Option<u16>,
+        left_to_right: bool,
+    ) -> CostResult<(MerkHash, ProofVerificationResult), Error> {
+        execute_query_proof(
+            self,
+            bytes,
+            limit,
+            0,
+            left_to_right,
+            PROOF_VERSION_LATEST,
+            true,
+        )
+    }
+
+    /// Verifies the encoded proof with the given query and expected hash
+    fn verify_proof(
+        &self,
+        bytes: &[u8],
+        limit: Option<u16>,
+        left_to_right: bool,
+        expected_hash: MerkHash,
+    ) -> CostResult<ProofVerificationResult, Error> {
+        self.execute_proof(bytes, limit, left_to_right, PROOF_VERSION_LATEST)
+            .map_ok(|(root_hash, verification_result)| {
+                if root_hash == expected_hash {
+                    Ok(verification_result)
                 } else {
-                    "right to left"
-                },
-                self
-            );
-        }
-        let mut cost = OperationCost::default();
-
-        let mut output = Vec::with_capacity(self.len());
-        let mut last_push = None;
-        let mut query = self.directional_iter(left_to_right).peekable();
-        let mut in_range = false;
-        let original_limit = limit;
-        let mut current_limit = limit;
-
-        let mut decoder = Decoder::new(bytes);
-
-        let root_wrapped = execute(decoder.by_ref(), true, |node| {
-            let mut execute_node = |key: &Vec<u8>,
-                                    value: Option<&Vec<u8>>,
-                                    value_hash: CryptoHash,
-                                    child_hash_verified: bool|
-             -> Result<_, Error> {
-                while let Some(item) = query.peek() {
-                    // get next item in query
-                    let query_item = *item;
-                    let (lower_bound, start_non_inclusive) = query_item.lower_bound();
-                    let (upper_bound, end_inclusive) = query_item.upper_bound();
-
-                    // terminate if we encounter a node before the current query item.
-                    // this means a node less than the current query item for left to right.
-                    // and a node greater than the current query item for right to left.
-                    let terminate = if left_to_right {
-                        // if the query item is lower unbounded, then a node cannot be less than it.
-                        // checks that the lower bound of the query item not greater than the key
-                        // if they are equal make sure the start is inclusive
-                        !query_item.lower_unbounded()
-                            && ((lower_bound.expect("confirmed not unbounded") > key.as_slice())
-                                || (start_non_inclusive
-                                    && lower_bound.expect("confirmed not unbounded")
-                                        == key.as_slice()))
-                    } else {
-                        !query_item.upper_unbounded()
-                            && ((upper_bound.expect("confirmed not unbounded") < key.as_slice())
-                                || (!end_inclusive
-                                    && upper_bound.expect("confirmed not unbounded")
-                                        == key.as_slice()))
-                    };
-                    if terminate {
-                        break;
-                    }
+                    Err(Error::InvalidProofError(format!(
+                        "Proof did not match expected hash\n\tExpected: \
+                         {expected_hash:?}\n\tActual: {root_hash:?}"
+                    )))
+                }
+            })
+            .flatten()
+    }
+}
 
-                    if !in_range {
-                        // this is the first data we have encountered for this query item
-                        if left_to_right {
-                            // ensure lower bound of query item is proven
-                            match last_push {
-                                // lower bound is proven - we have an exact match
-                                // ignoring the case when the lower bound is unbounded
-                                // as it's not possible the get an exact key match for
-                                // an unbounded value
-                                _ if Some(key.as_slice()) == query_item.lower_bound().0 => {}
-
-                                // lower bound is proven - this is the leftmost node
-                                // in the tree
-                                None => {}
-
-                                // lower bound is proven - the preceding tree node
-                                // is lower than the bound
-                                Some(Node::KV(..)) => {}
-                                Some(Node::KVDigest(..)) => {}
-                                Some(Node::KVDigestCount(..)) => {}
-                                Some(Node::KVRefValueHash(..)) => {}
-                                Some(Node::KVValueHash(..)) => {}
-                                Some(Node::KVValueHashFeatureType(..)) => {}
-                                Some(Node::KVValueHashFeatureTypeWithChildHash(..)) => {}
-                                Some(Node::KVRefValueHashCount(..)) => {}
-                                Some(Node::KVCount(..)) => {}
-
-                                // cannot verify lower bound - we have an abridged
-                                // tree, so we cannot tell what the preceding key was
-                                Some(_) => {
-                                    return Err(Error::InvalidProofError(
-                                        "Cannot verify lower bound of queried range".to_string(),
-                                    ));
-                                }
+/// Executes a query proof, see `QueryProofVerify::execute_proof`. The first
+/// `offset` matches are skipped. When `keys_only` is set the keys found may
+/// be proven without their value, and are returned without it.
+fn execute_query_proof(
+    query_to_verify: &Query,
+    bytes: &[u8],
+    limit: Option<u16>,
+    offset: u16,
+    left_to_right: bool,
+    proof_version: u16,
+    keys_only: bool,
+) -> CostResult<(MerkHash, ProofVerificationResult), Error> {
+    #[cfg(feature = "proof_debug")]
+    {
+        println!(
+            "executing proof with limit {:?} going {} using query {}",
+            limit,
+            if left_to_right {
+                "left to right"
+            } else {
+                "right to left"
+            },
+            query_to_verify
+        );
+    }
+    let mut cost = OperationCost::default();
+
+    let mut output = Vec::with_capacity(query_to_verify.len());
+    let mut last_push = None;
+    let mut query = query_to_verify.directional_iter(left_to_right).peekable();
+    let mut in_range = false;
+    let original_limit = limit;
+    let mut current_limit = limit;
+    let mut current_offset = offset;
+
+    let mut decoder = Decoder::new(bytes);
+
+    let root_wrapped = execute(decoder.by_ref(), true, |node| {
+        let mut execute_node = |key: &Vec<u8>,
+                                value: Option<&Vec<u8>>,
+                                value_hash: CryptoHash,
+                                child_hash_verified: bool|
+         -> Result<_, Error> {
+            while let Some(item) = query.peek() {
+                // get next item in query
+                let query_item = *item;
+                let (lower_bound, start_non_inclusive) = query_item.lower_bound();
+                let (upper_bound, end_inclusive) = query_item.upper_bound();
+
+                // terminate if we encounter a node before the current query item.
+                // this means a node less than the current query item for left to right.
+                // and a node greater than the current query item for right to left.
+                let terminate = if left_to_right {
+                    // if the query item is lower unbounded, then a node cannot be less than it.
+                    // checks that the lower bound of the query item not greater than the key
+                    // if they are equal make sure the start is inclusive
+                    !query_item.lower_unbounded()
+                        && ((lower_bound.expect("confirmed not unbounded") > key.as_slice())
+                            || (start_non_inclusive
+                                && lower_bound.expect("confirmed not unbounded") == key.as_slice()))
+                } else {
+                    !query_item.upper_unbounded()
+                        && ((upper_bound.expect("confirmed not unbounded") < key.as_slice())
+                            || (!end_inclusive
+                                && upper_bound.expect("confirmed not unbounded") == key.as_slice()))
+                };
+                if terminate {
+                    break;
+                }
+
+                if !in_range {
+                    // this is the first data we have encountered for this query item
+                    if left_to_right {
+                        // ensure lower bound of query item is proven
+                        match last_push {
+                            // lower bound is proven - we have an exact match
+                            // ignoring the case when the lower bound is unbounded
+                            // as it's not possible the get an exact key match for
+                            // an unbounded value
+                            _ if Some(key.as_slice()) == query_item.lower_bound().0 => {}
+
+                            // lower bound is proven - this is the leftmost node
+                            // in the tree
+                            None => {}
+
+                            // lower bound is proven - the preceding tree node
+                            // is lower than the bound
+                            Some(Node::KV(..)) => {}
+                            Some(Node::KVDigest(..)) => {}
+                            Some(Node::KVDigestCount(..)) => {}
+                            Some(Node::KVRefValueHash(..)) => {}
+                            Some(Node::KVValueHash(..)) => {}
+                            Some(Node::KVValueHashFeatureType(..)) => {}
+                            Some(Node::KVValueHashFeatureTypeWithChildHash(..)) => {}
+                            Some(Node::KVRefValueHashCount(..)) => {}
+                            Some(Node::KVCount(..)) => {}
+                            Some(Node::KVMinMax(..)) => {}
+                            Some(Node::KVDigestMinMax(..)) => {}
+                            Some(Node::KVRefValueHashMinMax(..)) => {}
+                            Some(Node::KVSum(..)) => {}
+                            Some(Node::KVDigestSum(..)) => {}
+                            Some(Node::KVRefValueHashSum(..)) => {}
+
+                            // cannot verify lower bound - we have an abridged
+                            // tree, so we cannot tell what the preceding key was
+                            Some(_) => {
+                                return Err(Error::InvalidProofError(
+                                    "Cannot verify lower bound of queried range".to_string(),
+                                ));
                             }
-                        } else {
-                            // ensure upper bound of query item is proven
-                            match last_push {
-                                // upper bound is proven - we have an exact match
-                                // ignoring the case when the upper bound is unbounded
-                                // as it's not possible the get an exact key match for
-                                // an unbounded value
-                                _ if Some(key.as_slice()) == query_item.upper_bound().0 => {}
-
-                                // lower bound is proven - this is the rightmost node
-                                // in the tree
-                                None => {}
-
-                                // upper bound is proven - the preceding tree node
-                                // is greater than the bound
-                                Some(Node::KV(..)) => {}
-                                Some(Node::KVDigest(..)) => {}
-                                Some(Node::KVDigestCount(..)) => {}
-                                Some(Node::KVRefValueHash(..)) => {}
-                                Some(Node::KVValueHash(..)) => {}
-                                Some(Node::KVValueHashFeatureType(..)) => {}
-                                Some(Node::KVValueHashFeatureTypeWithChildHash(..)) => {}
-                                Some(Node::KVRefValueHashCount(..)) => {}
-                                Some(Node::KVCount(..)) => {}
-
-                                // cannot verify upper bound - we have an abridged
-                                // tree so we cannot tell what the previous key was
-                                Some(_) => {
-                                    return Err(Error::InvalidProofError(
-                                        "Cannot verify upper bound of queried range".to_string(),
-                                    ));
-                                }
+                        }
+                    } else {
+                        // ensure upper bound of query item is proven
+                        match last_push {
+                            // upper bound is proven - we have an exact match
+                            // ignoring the case when the upper bound is unbounded
+                            // as it's not possible the get an exact key match for
+                            // an unbounded value
+                            _ if Some(key.as_slice()) == query_item.upper_bound().0 => {}
+
+                            // lower bound is proven - this is the rightmost node
+                            // in the tree
+                            None => {}
+
+                            // upper bound is proven - the preceding tree node
+                            // is greater than the bound
+                            Some(Node::KV(..)) => {}
+                            Some(Node::KVDigest(..)) => {}
+                            Some(Node::KVDigestCount(..)) => {}
+                            Some(Node::KVRefValueHash(..)) => {}
+                            Some(Node::KVValueHash(..)) => {}
+                            Some(Node::KVValueHashFeatureType(..)) => {}
+                            Some(Node::KVValueHashFeatureTypeWithChildHash(..)) => {}
+                            Some(Node::KVRefValueHashCount(..)) => {}
+                            Some(Node::KVCount(..)) => {}
+                            Some(Node::KVMinMax(..)) => {}
+                            Some(Node::KVDigestMinMax(..)) => {}
+                            Some(Node::KVRefValueHashMinMax(..)) => {}
+                            Some(Node::KVSum(..)) => {}
+                            Some(Node::KVDigestSum(..)) => {}
+                            Some(Node::KVRefValueHashSum(..)) => {}
+
+                            // cannot verify upper bound - we have an abridged
+                            // tree so we cannot tell what the previous key was
+                            Some(_) => {
+                                return Err(Error::InvalidProofError(
+                                    "Cannot verify upper bound of queried range".to_string(),
+                                ));
                             }
                         }
                     }
+                }
 
-                    if left_to_right {
-                        if query_item.upper_bound().0.is_some()
-                            && Some(key.as_slice()) >= query_item.upper_bound().0
-                        {
-                            // at or past upper bound of range (or this was an exact
-                            // match on a single-key queryitem), advance to next query
-                            // item
-                            query.next();
-                            in_range = false;
-                        } else {
-                            // have not reached upper bound, we expect more values
-                            // to be proven in the range (and all pushes should be
-                            // unabridged until we reach end of range)
-                            in_range = true;
-                        }
-                    } else if query_item.lower_bound().0.is_some()
-                        && Some(key.as_slice()) <= query_item.lower_bound().0
+                if left_to_right {
+                    if query_item.upper_bound().0.is_some()
+                        && Some(key.as_slice()) >= query_item.upper_bound().0
                     {
-                        // at or before lower bound of range (or this was an exact
+                        // at or past upper bound of range (or this was an exact
                         // match on a single-key queryitem), advance to next query
                         // item
                         query.next();
                         in_range = false;
                     } else {
-                        // have not reached lower bound, we expect more values
+                        // have not reached upper bound, we expect more values
                         // to be proven in the range (and all pushes should be
                         // unabridged until we reach end of range)
                         in_range = true;
                     }
+                } else if query_item.lower_bound().0.is_some()
+                    && Some(key.as_slice()) <= query_item.lower_bound().0
+                {
+                    // at or before lower bound of range (or this was an exact
+                    // match on a single-key queryitem), advance to next query
+                    // item
+                    query.next();
+                    in_range = false;
+                } else {
+                    // have not reached lower bound, we expect more values
+                    // to be proven in the range (and all pushes should be
+                    // unabridged until we reach end of range)
+                    in_range = true;
+                }
 
-                    // this push matches the queried item
-                    if query_item.contains(key) {
-                        if let Some(val) = value {
-                            if let Some(limit) = current_limit {
-                                if limit == 0 {
-                                    return Err(Error::InvalidProofError(format!(
-                                        "Proof returns more data than limit {:?}",
-                                        original_limit
-                                    )));
-                                } else {
-                                    current_limit = Some(limit - 1);
-                                    if current_limit == Some(0) {
-                                        in_range = false;
-                                    }
-                                }
-                            }
-                            #[cfg(feature = "proof_debug")]
-                            {
-                                println!(
-                                    "pushing {}",
-                                    ProvedKeyOptionalValue {
-                                        key: key.clone(),
-                                        value: Some(val.clone()),
-                                        proof: value_hash,
-                                        child_hash_verified,
-                                    }
-                                );
-                            }
-                            // add data to output
-                            output.push(ProvedKeyOptionalValue {
-                                key: key.clone(),
-                                value: Some(val.clone()),
-                                proof: value_hash,
-                                child_hash_verified,
-                            });
-
-                            // continue to next push
-                            break;
-                        } else {
+                // this push matches the queried item
+                if query_item.contains(key) {
+                    if current_offset > 0 {
+                        // skipped by the offset, the proof leaves its value out
+                        if value.is_some() {
                             return Err(Error::InvalidProofError(
-                                "Proof is missing data for query".to_string(),
+                                "Proof includes the value of a match skipped by the offset"
+                                    .to_string(),
                             ));
                         }
+                        current_offset -= 1;
+                        break;
+                    }
+                    if value.is_some() || keys_only {
+                        let value = value.filter(|_| !keys_only).cloned();
+                        if let Some(limit) = current_limit {
+                            if limit == 0 {
+                                return Err(Error::InvalidProofError(format!(
+                                    "Proof returns more data than limit {:?}",
+                                    original_limit
+                                )));
+                            } else {
+                                current_limit = Some(limit - 1);
+                                if current_limit == Some(0) {
+                                    in_range = false;
+                                }
+                            }
+                        }
+                        #[cfg(feature = "proof_debug")]
+                        {
+                            println!(
+                                "pushing {}",
+                                ProvedKeyOptionalValue {
+                                    key: key.clone(),
+                                    value: value.clone(),
+                                    proof: value_hash,
+                                    child_hash_verified,
+                                }
+                            );
+                        }
+                        // add data to output
+                        output.push(ProvedKeyOptionalValue {
+                            key: key.clone(),
+                            value,
+                            proof: value_hash,
+                            child_hash_verified,
+                        });
+
+                        // continue to next push
+                        break;
+                    } else {
+                        return Err(Error::InvalidProofError(
+                            "Proof is missing data for query".to_string(),
+                        ));
                     }
-                    {}
-                    // continue to next queried item
                 }
-                Ok(())
-            };
+                {}
+                // continue to next queried item
+            }
+            Ok(())
+        };
 
-            match node {
-                Node::KV(key, value) => {
-                    #[cfg(feature = "proof_debug")]
-                    {
-                        println!("Processing KV node");
-                    }
-                    execute_node(key, Some(value), value_hash(value).unwrap(), false)?;
+        match node {
+            Node::KV(key, value) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KV node");
                 }
-                Node::KVValueHash(key, value, value_hash) => {
-                    #[cfg(feature = "proof_debug")]
-                    {
-                        println!("Processing KVValueHash node");
-                    }
-                    // KVValueHash exists for elements whose value_hash is a
-                    // combine_hash (subtrees and references). Reject item
-                    // elements to prevent KV→KVValueHash forgery where an
-                    // attacker substitutes a KV node with KVValueHash to inject
-                    // a fake value while keeping the original hash.
-                    // Skipped for V0 backwards compatibility.
-                    if proof_version >= 1 {
-                        let element_type =
-                            ElementType::from_serialized_value(value).map_err(|e| {
-                                Error::InvalidProofError(format!(
-                                    "cannot determine element type in KVValueHash node: {e}"
-                                ))
-                            })?;
-                        if element_type.has_simple_value_hash() {
-                            return Err(Error::InvalidProofError(
-                                "KVValueHash node must not contain an item element".to_string(),
-                            ));
-                        }
-                    }
-                    execute_node(key, Some(value), *value_hash, false)?;
+                execute_node(key, Some(value), value_hash(value).unwrap(), false)?;
+            }
+            Node::KVValueHash(key, value, value_hash) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVValueHash node");
                 }
-                Node::KVDigest(key, value_hash) => {
-                    #[cfg(feature = "proof_debug")]
-                    {
-                        println!("Processing KVDigest node");
+                // KVValueHash exists for elements whose value_hash is a
+                // combine_hash (subtrees and references). Reject item
+                // elements to prevent KV→KVValueHash forgery where an
+                // attacker substitutes a KV node with KVValueHash to inject
+                // a fake value while keeping the original hash.
+                // Skipped for V0 backwards compatibility.
+                if proof_version >= 1 {
+                    let element_type = ElementType::from_serialized_value(value).map_err(|e| {
+                        Error::InvalidProofError(format!(
+                            "cannot determine element type in KVValueHash node: {e}"
+                        ))
+                    })?;
+                    if element_type.has_simple_value_hash() {
+                        return Err(Error::InvalidProofError(
+                            "KVValueHash node must not contain an item element".to_string(),
+                        ));
                     }
-                    execute_node(key, None, *value_hash, false)?;
                 }
-                Node::KVDigestCount(key, value_hash, _count) => {
-                    #[cfg(feature = "proof_debug")]
-                    {
-                        println!("Processing KVDigestCount node");
-                    }
-                    execute_node(key, None, *value_hash, false)?;
+                execute_node(key, Some(value), *value_hash, false)?;
+            }
+            Node::KVDigest(key, value_hash) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVDigest node");
                 }
-                Node::KVRefValueHash(key, value, value_hash) => {
-                    #[cfg(feature = "proof_debug")]
-                    {
-                        println!("Processing KVRefValueHash node");
-                    }
-                    execute_node(key, Some(value), *value_hash, false)?;
+                execute_node(key, None, *value_hash, false)?;
+            }
+            Node::KVDigestCount(key, value_hash, _count) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVDigestCount node");
                 }
-                Node::KVCount(key, value, _count) => {
-                    #[cfg(feature = "proof_debug")]
-                    {
-                        println!("Processing KVCount node");
-                    }
-                    execute_node(key, Some(value), value_hash(value).unwrap(), false)?;
+                execute_node(key, None, *value_hash, false)?;
+            }
+            Node::KVRefValueHash(key, value, value_hash) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVRefValueHash node");
                 }
-                Node::KVValueHashFeatureType(key, value, value_hash, _feature_type) => {
-                    #[cfg(feature = "proof_debug")]
-                    {
-                        println!("Processing KVValueHashFeatureType node");
-                    }
-                    // Same check as KVValueHash — reject item elements.
-                    // Skipped for V0 backwards compatibility.
-                    if proof_version >= 1 {
-                        let element_type =
-                            ElementType::from_serialized_value(value).map_err(|e| {
-                                Error::InvalidProofError(format!(
-                                    "cannot determine element type in KVValueHashFeatureType \
-                                     node: {e}"
-                                ))
-                            })?;
-                        if element_type.has_simple_value_hash() {
-                            return Err(Error::InvalidProofError(
-                                "KVValueHashFeatureType node must not contain an item element"
-                                    .to_string(),
-                            ));
-                        }
-                    }
-                    execute_node(key, Some(value), *value_hash, false)?;
+                execute_node(key, Some(value), *value_hash, false)?;
+            }
+            Node::KVCount(key, value, _count) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVCount node");
                 }
-                Node::KVRefValueHashCount(key, value, value_hash, _count) => {
-                    #[cfg(feature = "proof_debug")]
-                    {
-                        println!("Processing KVRefValueHashCount node");
-                    }
-                    execute_node(key, Some(value), *value_hash, false)?;
+                execute_node(key, Some(value), value_hash(value).unwrap(), false)?;
+            }
+            Node::KVValueHashFeatureType(key, value, value_hash, _feature_type) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVValueHashFeatureType node");
                 }
-                Node::KVValueHashFeatureTypeWithChildHash(
-                    key,
-                    value,
-                    node_value_hash,
-                    _feature_type,
-                    child_hash,
-                ) => {
-                    #[cfg(feature = "proof_debug")]
-                    {
-                        println!("Processing KVValueHashFeatureTypeWithChildHash node");
-                    }
-                    // Same element-type check as KVValueHashFeatureType.
-                    // Skipped for V0 backwards compatibility.
-                    if proof_version >= 1 {
-                        let element_type =
-                            ElementType::from_serialized_value(value).map_err(|e| {
-                                Error::InvalidProofError(format!(
-                                    "cannot determine element type in \
-                                     KVValueHashFeatureTypeWithChildHash node: {e}"
-                                ))
-                            })?;
-                        if element_type.has_simple_value_hash() {
-                            return Err(Error::InvalidProofError(
-                                "KVValueHashFeatureTypeWithChildHash node must not contain \
-                                 an item element"
-                                    .to_string(),
-                            ));
-                        }
-                    }
-                    // Verify value integrity: combine_hash(H(value), child_hash) must
-                    // equal the provided value_hash. This prevents an attacker from
-                    // swapping the serialized element bytes (e.g. changing a CountTree's
-                    // count) while reusing the original value_hash.
-                    let element_value_hash = value_hash(value).unwrap();
-                    let computed_value_hash =
-                        combine_hash(&element_value_hash, child_hash).unwrap();
-                    if computed_value_hash != *node_value_hash {
-                        return Err(Error::InvalidProofError(format!(
-                            "value/child hash mismatch: combine_hash(H(value), child_hash) \
-                             = {} but value_hash = {}",
-                            hex::encode(computed_value_hash),
-                            hex::encode(node_value_hash)
-                        )));
+                // Same check as KVValueHash — reject item elements.
+                // Skipped for V0 backwards compatibility.
+                if proof_version >= 1 {
+                    let element_type = ElementType::from_serialized_value(value).map_err(|e| {
+                        Error::InvalidProofError(format!(
+                            "cannot determine element type in KVValueHashFeatureType \
+                                 node: {e}"
+                        ))
+                    })?;
+                    if element_type.has_simple_value_hash() {
+                        return Err(Error::InvalidProofError(
+                            "KVValueHashFeatureType node must not contain an item element"
+                                .to_string(),
+                        ));
                     }
-                    execute_node(key, Some(value), *node_value_hash, true)?;
                 }
-                Node::Hash(_) | Node::KVHash(_) | Node::KVHashCount(..) => {
-                    if in_range {
-                        return Err(Error::InvalidProofError(format!(
-                            "Proof is missing data for query range. Encountered unexpected node \
-                             type: {}",
-                            node
-                        )));
+                execute_node(key, Some(value), *value_hash, false)?;
+            }
+            Node::KVRefValueHashCount(key, value, value_hash, _count) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVRefValueHashCount node");
+                }
+                execute_node(key, Some(value), *value_hash, false)?;
+            }
+            Node::KVMinMax(key, value, ..) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVMinMax node");
+                }
+                execute_node(key, Some(value), value_hash(value).unwrap(), false)?;
+            }
+            Node::KVDigestMinMax(key, value_hash, ..) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVDigestMinMax node");
+                }
+                execute_node(key, None, *value_hash, false)?;
+            }
+            Node::KVRefValueHashMinMax(key, value, value_hash, ..) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVRefValueHashMinMax node");
+                }
+                execute_node(key, Some(value), *value_hash, false)?;
+            }
+            Node::KVSum(key, value, _) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVSum node");
+                }
+                execute_node(key, Some(value), value_hash(value).unwrap(), false)?;
+            }
+            Node::KVDigestSum(key, value_hash, _) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVDigestSum node");
+                }
+                execute_node(key, None, *value_hash, false)?;
+            }
+            Node::KVRefValueHashSum(key, value, value_hash, _) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVRefValueHashSum node");
+                }
+                execute_node(key, Some(value), *value_hash, false)?;
+            }
+            Node::KVValueHashFeatureTypeWithChildHash(
+                key,
+                value,
+                node_value_hash,
+                _feature_type,
+                child_hash,
+            ) => {
+                #[cfg(feature = "proof_debug")]
+                {
+                    println!("Processing KVValueHashFeatureTypeWithChildHash node");
+                }
+                // Same element-type check as KVValueHashFeatureType.
+                // Skipped for V0 backwards compatibility.
+                if proof_version >= 1 {
+                    let element_type = ElementType::from_serialized_value(value).map_err(|e| {
+                        Error::InvalidProofError(format!(
+                            "cannot determine element type in \
+                                 KVValueHashFeatureTypeWithChildHash node: {e}"
+                        ))
+                    })?;
+                    if element_type.has_simple_value_hash() {
+                        return Err(Error::InvalidProofError(
+                            "KVValueHashFeatureTypeWithChildHash node must not contain \
+                             an item element"
+                                .to_string(),
+                        ));
                     }
                 }
+                // Verify value integrity: combine_hash(H(value), child_hash) must
+                // equal the provided value_hash. This prevents an attacker from
+                // swapping the serialized element bytes (e.g. changing a CountTree's
+                // count) while reusing the original value_hash.
+                let element_value_hash = value_hash(value).unwrap();
+                let computed_value_hash = combine_hash(&element_value_hash, child_hash).unwrap();
+                if computed_value_hash != *node_value_hash {
+                    return Err(Error::InvalidProofError(format!(
+                        "value/child hash mismatch: combine_hash(H(value), child_hash) \
+                         = {} but value_hash = {}",
+                        hex::encode(computed_value_hash),
+                        hex::encode(node_value_hash)
+                    )));
+                }
+                execute_node(key, Some(value), *node_value_hash, true)?;
+            }
+            Node::Hash(_)
+            | Node::KVHash(_)
+            | Node::KVHashCount(..)
+            | Node::KVHashMinMax(..)
+            | Node::KVHashSum(..) => {
+                if in_range {
+                    return Err(Error::InvalidProofError(format!(
+                        "Proof is missing data for query range. Encountered unexpected node \
+                         type: {}",
+                        node
+                    )));
+                }
             }
+        }
 
-            last_push = Some(node.clone());
+        last_push = Some(node.clone());
 
-            Ok(())
-        });
+        Ok(())
+    });
 
-        let root = cost_return_on_error!(&mut cost, root_wrapped);
+    let root = cost_return_on_error!(&mut cost, root_wrapped);
 
-        if decoder.remaining_bytes() > 0 {
-            return Err(Error::InvalidProofError(format!(
-                "Proof has {} unconsumed trailing bytes",
-                decoder.remaining_bytes()
-            )))
-            .wrap_with_cost(cost);
-        }
+    if decoder.remaining_bytes() > 0 {
+        return Err(Error::InvalidProofError(format!(
+            "Proof has {} unconsumed trailing bytes",
+            decoder.remaining_bytes()
+        )))
+        .wrap_with_cost(cost);
+    }
 
-        // we have remaining query items, check absence proof against right edge of
-        // tree
-        if query.peek().is_some() {
-            if current_limit == Some(0) {
-            } else {
-                match last_push {
-                    // last node in tree was less than queried item
-                    Some(Node::KV(..)) => {}
-                    Some(Node::KVDigest(..)) => {}
-                    Some(Node::KVDigestCount(..)) => {}
-                    Some(Node::KVRefValueHash(..)) => {}
-                    Some(Node::KVValueHash(..)) => {}
-                    Some(Node::KVCount(..)) => {}
-                    Some(Node::KVValueHashFeatureType(..)) => {}
-                    Some(Node::KVValueHashFeatureTypeWithChildHash(..)) => {}
-                    Some(Node::KVRefValueHashCount(..)) => {}
-
-                    // proof contains abridged data so we cannot verify absence of
-                    // remaining query items
-                    _ => {
-                        return Err(Error::InvalidProofError(
-                            "Proof is missing data for query".to_string(),
-                        ))
-                        .wrap_with_cost(cost);
-                    }
+    // we have remaining query items, check absence proof against right edge of
+    // tree
+    if query.peek().is_some() {
+        if current_limit == Some(0) {
+        } else {
+            match last_push {
+                // last node in tree was less than queried item
+                Some(Node::KV(..)) => {}
+                Some(Node::KVDigest(..)) => {}
+                Some(Node::KVDigestCount(..)) => {}
+                Some(Node::KVRefValueHash(..)) => {}
+                Some(Node::KVValueHash(..)) => {}
+                Some(Node::KVCount(..)) => {}
+                Some(Node::KVValueHashFeatureType(..)) => {}
+                Some(Node::KVValueHashFeatureTypeWithChildHash(..)) => {}
+                Some(Node::KVRefValueHashCount(..)) => {}
+                Some(Node::KVMinMax(..)) => {}
+                Some(Node::KVDigestMinMax(..)) => {}
+                Some(Node::KVRefValueHashMinMax(..)) => {}
+                Some(Node::KVSum(..)) => {}
+                Some(Node::KVDigestSum(..)) => {}
+                Some(Node::KVRefValueHashSum(..)) => {}
+
+                // proof contains abridged data so we cannot verify absence of
+                // remaining query items
+                _ => {
+                    return Err(Error::InvalidProofError(
+                        "Proof is missing data for query".to_string(),
+                    ))
+                    .wrap_with_cost(cost);
                 }
             }
         }
-
-        Ok((
-            root.hash().unwrap_add_cost(&mut cost),
-            ProofVerificationResult {
-                result_set: output,
-                limit: current_limit,
-            },
-        ))
-        .wrap_with_cost(cost)
     }
 
-    /// Verifies the encoded proof with the given query and expected hash
-    fn verify_proof(
-        &self,
-        bytes: &[u8],
-        limit: Option<u16>,
-        left_to_right: bool,
-        expected_hash: MerkHash,
-    ) -> CostResult<ProofVerificationResult, Error> {
-        self.execute_proof(bytes, limit, left_to_right, PROOF_VERSION_LATEST)
-            .map_ok(|(root_hash, verification_result)| {
-                if root_hash == expected_hash {
-                    Ok(verification_result)
-                } else {
-                    Err(Error::InvalidProofError(format!(
-                        "Proof did not match expected hash\n\tExpected: \
-                         {expected_hash:?}\n\tActual: {root_hash:?}"
-                    )))
-                }
-            })
-            .flatten()
-    }
+    Ok((
+        root.hash().unwrap_add_cost(&mut cost),
+        ProofVerificationResult {
+            result_set: output,
+            limit: current_limit,
+            offset: current_offset,
+        },
+    ))
+    .wrap_with_cost(cost)
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -659,6 +806,8 @@ pub struct ProofVerificationResult {
     pub result_set: Vec<ProvedKeyOptionalValue>,
     /// Limit
     pub limit: Option<u16>,
+    /// Number of the matches to skip that were not found
+    pub offset: u16,
 }
 
 impl fmt::Display for ProofVerificationResult {
@@ -670,12 +819,14 @@ impl fmt::Display for ProofVerificationResult {
         }
         writeln!(f, "  ],")?;
         writeln!(f, "  limit: {:?}", self.limit)?;
+        writeln!(f, "  offset: {}", self.offset)?;
         write!(f, "}}")
     }
 }
 
 /// Checks whether a key exists as a boundary element in the given merk proof
-/// bytes. A boundary element is a `KVDigest` or `KVDigestCount` node — it
+/// bytes. A boundary element is a `KVDigest`, `KVDigestCount`,
+/// `KVDigestMinMax` or `KVDigestSum` node — it
 /// proves the key exists in the tree without revealing the value.
 ///
 /// This is useful for exclusive range queries (e.g. `RangeAfter(10)`) where
@@ -689,7 +840,11 @@ pub fn key_exists_as_boundary_in_proof(proof_bytes: &[u8], key: &[u8]) -> Result
             Op::Push(Node::KVDigest(k, _))
             | Op::PushInverted(Node::KVDigest(k, _))
             | Op::Push(Node::KVDigestCount(k, _, _))
-            | Op::PushInverted(Node::KVDigestCount(k, _, _)) => {
+            | Op::PushInverted(Node::KVDigestCount(k, _, _))
+            | Op::Push(Node::KVDigestMinMax(k, ..))
+            | Op::PushInverted(Node::KVDigestMinMax(k, ..))
+            | Op::Push(Node::KVDigestSum(k, ..))
+            | Op::PushInverted(Node::KVDigestSum(k, ..)) => {
                 if k.as_slice() == key {
                     return Ok(true);
                 }
@@ -701,8 +856,9 @@ pub fn key_exists_as_boundary_in_proof(proof_bytes: &[u8], key: &[u8]) -> Result
 }
 
 /// Returns all boundary keys found in the given merk proof bytes.
-/// Boundary keys appear as `KVDigest` or `KVDigestCount` nodes — they
-/// prove a key exists in the tree without revealing the value.
+/// Boundary keys appear as `KVDigest`, `KVDigestCount`, `KVDigestMinMax` or
+/// `KVDigestSum` nodes — they prove a key exists in the tree without
+/// revealing the value.
 pub fn boundaries_in_proof(proof_bytes: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
     let decoder = Decoder::new(proof_bytes);
     let mut keys = Vec::new();
@@ -712,7 +868,11 @@ pub fn boundaries_in_proof(proof_bytes: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
             Op::Push(Node::KVDigest(k, _))
             | Op::PushInverted(Node::KVDigest(k, _))
             | Op::Push(Node::KVDigestCount(k, _, _))
-            | Op::PushInverted(Node::KVDigestCount(k, _, _)) => {
+            | Op::PushInverted(Node::KVDigestCount(k, _, _))
+            | Op::Push(Node::KVDigestMinMax(k, ..))
+            | Op::PushInverted(Node::KVDigestMinMax(k, ..))
+            | Op::Push(Node::KVDigestSum(k, ..))
+            | Op::PushInverted(Node::KVDigestSum(k, ..)) => {
                 keys.push(k);
             }
             _ => {}
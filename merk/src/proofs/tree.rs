@@ -13,8 +13,8 @@ use grovedb_costs::{
 use super::{Node, Op};
 #[cfg(any(feature = "minimal", feature = "verify"))]
 use crate::tree::{
-    combine_hash, kv_digest_to_kv_hash, kv_hash, node_hash, node_hash_with_count, value_hash,
-    NULL_HASH,
+    combine_hash, kv_digest_to_kv_hash, kv_hash, node_hash, node_hash_with_count,
+    node_hash_with_min_max, node_hash_with_sum, value_hash, NULL_HASH,
 };
 #[cfg(any(feature = "minimal", feature = "verify"))]
 use crate::{
@@ -162,6 +162,25 @@ impl Tree {
                                 *count,
                             )
                         }
+                        TreeFeatureType::MinMaxMerkNode(min, max) => node_hash_with_min_max(
+                            &kv_hash,
+                            &self.child_hash(true),
+                            &self.child_hash(false),
+                            *min,
+                            *max,
+                        ),
+                        TreeFeatureType::ProvableSummedMerkNode(sum) => node_hash_with_sum(
+                            &kv_hash,
+                            &self.child_hash(true),
+                            &self.child_hash(false),
+                            *sum as i128,
+                        ),
+                        TreeFeatureType::ProvableBigSummedMerkNode(sum) => node_hash_with_sum(
+                            &kv_hash,
+                            &self.child_hash(true),
+                            &self.child_hash(false),
+                            *sum,
+                        ),
                         _ => compute_hash(self, kv_hash),
                     }
                 })
@@ -219,6 +238,92 @@ impl Tree {
                     )
                 })
             }
+            Node::KVMinMax(key, value, min, max) => kv_hash(key.as_slice(), value.as_slice())
+                .flat_map(|kv_hash| {
+                    node_hash_with_min_max(
+                        &kv_hash,
+                        &self.child_hash(true),
+                        &self.child_hash(false),
+                        *min,
+                        *max,
+                    )
+                }),
+            Node::KVHashMinMax(kv_hash, min, max) => node_hash_with_min_max(
+                kv_hash,
+                &self.child_hash(true),
+                &self.child_hash(false),
+                *min,
+                *max,
+            ),
+            Node::KVDigestMinMax(key, value_hash, min, max) => {
+                kv_digest_to_kv_hash(key, value_hash).flat_map(|kv_hash| {
+                    node_hash_with_min_max(
+                        &kv_hash,
+                        &self.child_hash(true),
+                        &self.child_hash(false),
+                        *min,
+                        *max,
+                    )
+                })
+            }
+            Node::KVRefValueHashMinMax(key, referenced_value, node_value_hash, min, max) => {
+                let mut cost = OperationCost::default();
+                let referenced_value_hash =
+                    value_hash(referenced_value.as_slice()).unwrap_add_cost(&mut cost);
+                let combined_value_hash = combine_hash(node_value_hash, &referenced_value_hash)
+                    .unwrap_add_cost(&mut cost);
+
+                kv_digest_to_kv_hash(key.as_slice(), &combined_value_hash).flat_map(|kv_hash| {
+                    node_hash_with_min_max(
+                        &kv_hash,
+                        &self.child_hash(true),
+                        &self.child_hash(false),
+                        *min,
+                        *max,
+                    )
+                })
+            }
+            Node::KVSum(key, value, sum) => {
+                kv_hash(key.as_slice(), value.as_slice()).flat_map(|kv_hash| {
+                    node_hash_with_sum(
+                        &kv_hash,
+                        &self.child_hash(true),
+                        &self.child_hash(false),
+                        *sum,
+                    )
+                })
+            }
+            Node::KVHashSum(kv_hash, sum) => node_hash_with_sum(
+                kv_hash,
+                &self.child_hash(true),
+                &self.child_hash(false),
+                *sum,
+            ),
+            Node::KVDigestSum(key, value_hash, sum) => kv_digest_to_kv_hash(key, value_hash)
+                .flat_map(|kv_hash| {
+                    node_hash_with_sum(
+                        &kv_hash,
+                        &self.child_hash(true),
+                        &self.child_hash(false),
+                        *sum,
+                    )
+                }),
+            Node::KVRefValueHashSum(key, referenced_value, node_value_hash, sum) => {
+                let mut cost = OperationCost::default();
+                let referenced_value_hash =
+                    value_hash(referenced_value.as_slice()).unwrap_add_cost(&mut cost);
+                let combined_value_hash = combine_hash(node_value_hash, &referenced_value_hash)
+                    .unwrap_add_cost(&mut cost);
+
+                kv_digest_to_kv_hash(key.as_slice(), &combined_value_hash).flat_map(|kv_hash| {
+                    node_hash_with_sum(
+                        &kv_hash,
+                        &self.child_hash(true),
+                        &self.child_hash(false),
+                        *sum,
+                    )
+                })
+            }
         }
     }
 
@@ -390,9 +495,19 @@ impl Tree {
             | Node::KVDigest(key, ..)
             | Node::KVDigestCount(key, ..)
             | Node::KVCount(key, ..)
-            | Node::KVRefValueHashCount(key, ..) => Some(key.as_slice()),
+            | Node::KVRefValueHashCount(key, ..)
+            | Node::KVMinMax(key, ..)
+            | Node::KVRefValueHashMinMax(key, ..)
+            | Node::KVDigestMinMax(key, ..)
+            | Node::KVSum(key, ..)
+            | Node::KVRefValueHashSum(key, ..)
+            | Node::KVDigestSum(key, ..) => Some(key.as_slice()),
             // These nodes don't have keys, only hashes
-            Node::Hash(_) | Node::KVHash(_) | Node::KVHashCount(..) => None,
+            Node::Hash(_)
+            | Node::KVHash(_)
+            | Node::KVHashCount(..)
+            | Node::KVHashMinMax(..)
+            | Node::KVHashSum(..) => None,
         }
     }
 
@@ -404,6 +519,7 @@ impl Tree {
                 Ok((*feature_type).into())
             }
             Node::KVCount(_, _, count) => Ok(AggregateData::ProvableCount(*count)),
+            Node::KVMinMax(_, _, min, max) => Ok(AggregateData::MinMax(*min, *max)),
             Node::KV(..) | Node::KVValueHash(..) => Ok(AggregateData::NoAggregateData),
             _ => Err(Error::InvalidProofError(
                 "Cannot extract aggregate data from this node type".to_string(),
@@ -164,3 +164,59 @@ pub fn node_hash_with_count(
         ..Default::default()
     })
 }
+
+#[cfg(any(feature = "minimal", feature = "verify"))]
+/// Hashes a node for MinMaxTree, including the aggregate minimum and maximum
+pub fn node_hash_with_min_max(
+    kv: &CryptoHash,
+    left: &CryptoHash,
+    right: &CryptoHash,
+    min: i64,
+    max: i64,
+) -> CostContext<CryptoHash> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(kv);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.update(&min.to_be_bytes());
+    hasher.update(&max.to_be_bytes());
+
+    // 112 bytes of input, so hashes will always be 2
+    let hashes = 2;
+
+    let res = hasher.finalize();
+    let mut hash: CryptoHash = Default::default();
+    hash.copy_from_slice(res.as_bytes());
+    hash.wrap_with_cost(OperationCost {
+        hash_node_calls: hashes,
+        ..Default::default()
+    })
+}
+
+#[cfg(any(feature = "minimal", feature = "verify"))]
+/// Hashes a node for ProvableSumTree and ProvableBigSumTree, including the
+/// aggregate sum. The sum is always hashed as a 16-byte big-endian integer so
+/// that both tree types share one proof format.
+pub fn node_hash_with_sum(
+    kv: &CryptoHash,
+    left: &CryptoHash,
+    right: &CryptoHash,
+    sum: i128,
+) -> CostContext<CryptoHash> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(kv);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.update(&sum.to_be_bytes());
+
+    // 112 bytes of input, so hashes will always be 2
+    let hashes = 2;
+
+    let res = hasher.finalize();
+    let mut hash: CryptoHash = Default::default();
+    hash.copy_from_slice(res.as_bytes());
+    hash.wrap_with_cost(OperationCost {
+        hash_node_calls: hashes,
+        ..Default::default()
+    })
+}
@@ -314,7 +314,8 @@ impl Link {
                 AggregateData::NoAggregateData => key.len() + 36, // 1 + HASH_LENGTH + 2 + 1,
                 AggregateData::Count(_)
                 | AggregateData::Sum(_)
-                | AggregateData::ProvableCount(_) => {
+                | AggregateData::ProvableCount(_)
+                | AggregateData::ProvableSum(_) => {
                     // 1 for key len
                     // key_len for keys
                     // 32 for hash
@@ -327,7 +328,9 @@ impl Link {
                 }
                 AggregateData::BigSum(_)
                 | AggregateData::CountAndSum(..)
-                | AggregateData::ProvableCountAndSum(..) => {
+                | AggregateData::ProvableCountAndSum(..)
+                | AggregateData::MinMax(..)
+                | AggregateData::ProvableBigSum(_) => {
                     // 1 for key len
                     // key_len for keys
                     // 32 for hash
@@ -358,12 +361,15 @@ impl Link {
                 AggregateData::NoAggregateData => tree.key().len() + 36, // 1 + 32 + 2 + 1,
                 AggregateData::Count(_)
                 | AggregateData::Sum(_)
-                | AggregateData::ProvableCount(_) => {
+                | AggregateData::ProvableCount(_)
+                | AggregateData::ProvableSum(_) => {
                     tree.key().len() + 44 // 1 + 32 + 2 + 1 + 8
                 }
                 AggregateData::BigSum(_)
                 | AggregateData::CountAndSum(..)
-                | AggregateData::ProvableCountAndSum(..) => {
+                | AggregateData::ProvableCountAndSum(..)
+                | AggregateData::MinMax(..)
+                | AggregateData::ProvableBigSum(_) => {
                     tree.key().len() + 52 // 1 + 32 + 2 + 1 + 16
                 }
             },
@@ -442,6 +448,19 @@ impl Encode for Link {
                 out.write_varint(*count_value)?;
                 out.write_varint(*sum_value)?;
             }
+            AggregateData::MinMax(min_value, max_value) => {
+                out.write_all(&[7])?;
+                out.write_varint(*min_value)?;
+                out.write_varint(*max_value)?;
+            }
+            AggregateData::ProvableSum(sum_value) => {
+                out.write_all(&[8])?;
+                out.write_varint(*sum_value)?;
+            }
+            AggregateData::ProvableBigSum(big_sum_value) => {
+                out.write_all(&[9])?;
+                out.write_i128::<BigEndian>(*big_sum_value)?;
+            }
         }
 
         Ok(())
@@ -507,6 +526,18 @@ impl Encode for Link {
                     let encoded_count_value = count.encode_var_vec();
                     key.len() + encoded_sum_value.len() + encoded_count_value.len() + 36
                 }
+                AggregateData::MinMax(min, max) => {
+                    let encoded_min_value = min.encode_var_vec();
+                    let encoded_max_value = max.encode_var_vec();
+                    key.len() + encoded_min_value.len() + encoded_max_value.len() + 36
+                }
+                AggregateData::ProvableSum(sum_value) => {
+                    let encoded_sum_value = sum_value.encode_var_vec();
+                    key.len() + encoded_sum_value.len() + 36
+                }
+                AggregateData::ProvableBigSum(_) => {
+                    key.len() + 52 // 1 + 32 + 2 + 1 + 16
+                }
             },
             Link::Modified { .. } => {
                 return Err(ed::Error::IOError(std::io::Error::new(
@@ -550,6 +581,18 @@ impl Encode for Link {
                     let encoded_count_value = count.encode_var_vec();
                     tree.key().len() + encoded_sum_value.len() + encoded_count_value.len() + 36
                 }
+                AggregateData::MinMax(min, max) => {
+                    let encoded_min_value = min.encode_var_vec();
+                    let encoded_max_value = max.encode_var_vec();
+                    tree.key().len() + encoded_min_value.len() + encoded_max_value.len() + 36
+                }
+                AggregateData::ProvableSum(sum_value) => {
+                    let encoded_sum_value = sum_value.encode_var_vec();
+                    tree.key().len() + encoded_sum_value.len() + 36
+                }
+                AggregateData::ProvableBigSum(_) => {
+                    tree.key().len() + 52 // 1 + 32 + 2 + 1 + 16
+                }
             },
         })
     }
@@ -631,6 +674,19 @@ impl Decode for Link {
                     let encoded_sum: i64 = input.read_varint()?;
                     AggregateData::ProvableCountAndSum(encoded_count, encoded_sum)
                 }
+                7 => {
+                    let encoded_min: i64 = input.read_varint()?;
+                    let encoded_max: i64 = input.read_varint()?;
+                    AggregateData::MinMax(encoded_min, encoded_max)
+                }
+                8 => {
+                    let encoded_sum: i64 = input.read_varint()?;
+                    AggregateData::ProvableSum(encoded_sum)
+                }
+                9 => {
+                    let encoded_big_sum: i128 = input.read_i128::<BigEndian>()?;
+                    AggregateData::ProvableBigSum(encoded_big_sum)
+                }
                 byte => return Err(ed::Error::UnexpectedByte(byte)),
             };
         } else {
@@ -853,6 +909,54 @@ mod test {
         );
     }
 
+    #[test]
+    fn encode_link_with_min_max() {
+        let link = Link::Reference {
+            key: vec![1, 2, 3],
+            aggregate_data: AggregateData::MinMax(-3, 7),
+            child_heights: (123, 124),
+            hash: [55; 32],
+        };
+        assert_eq!(link.encoding_length().unwrap(), 41);
+
+        let mut bytes = vec![];
+        link.encode_into(&mut bytes).unwrap();
+
+        assert_eq!(link.encoding_length().unwrap(), bytes.len());
+        assert_eq!(
+            bytes,
+            vec![
+                3, 1, 2, 3, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55,
+                55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 55, 123, 124, 7, 5, 14,
+            ]
+        );
+
+        let decoded = Link::decode(bytes.as_slice()).unwrap();
+        assert_eq!(decoded.aggregate_data(), AggregateData::MinMax(-3, 7));
+    }
+
+    #[test]
+    fn encode_link_with_provable_sums() {
+        for aggregate_data in [
+            AggregateData::ProvableSum(-50),
+            AggregateData::ProvableBigSum(i128::MAX),
+        ] {
+            let link = Link::Reference {
+                key: vec![1, 2, 3],
+                aggregate_data,
+                child_heights: (123, 124),
+                hash: [55; 32],
+            };
+
+            let mut bytes = vec![];
+            link.encode_into(&mut bytes).unwrap();
+            assert_eq!(link.encoding_length().unwrap(), bytes.len());
+
+            let decoded = Link::decode(bytes.as_slice()).unwrap();
+            assert_eq!(decoded.aggregate_data(), aggregate_data);
+        }
+    }
+
     #[test]
     fn encode_link_with_big_sum() {
         let link = Link::Reference {
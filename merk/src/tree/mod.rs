@@ -46,8 +46,8 @@ use grovedb_costs::{
 use grovedb_version::version::GroveVersion;
 #[cfg(any(feature = "minimal", feature = "verify"))]
 pub use hash::{
-    combine_hash, kv_digest_to_kv_hash, kv_hash, node_hash, node_hash_with_count, value_hash,
-    CryptoHash, HASH_LENGTH, NULL_HASH,
+    combine_hash, kv_digest_to_kv_hash, kv_hash, node_hash, node_hash_with_count,
+    node_hash_with_min_max, node_hash_with_sum, value_hash, CryptoHash, HASH_LENGTH, NULL_HASH,
 };
 #[cfg(feature = "minimal")]
 pub use hash::{HASH_BLOCK_SIZE, HASH_BLOCK_SIZE_U32, HASH_LENGTH_U32, HASH_LENGTH_U32_X2};
@@ -470,6 +470,11 @@ impl TreeNode {
                     AggregateData::ProvableCountAndSum(c, s) => {
                         s.encode_var_vec().len() as u32 + c.encode_var_vec().len() as u32
                     }
+                    AggregateData::MinMax(min, max) => {
+                        min.encode_var_vec().len() as u32 + max.encode_var_vec().len() as u32
+                    }
+                    AggregateData::ProvableSum(s) => s.encode_var_vec().len() as u32,
+                    AggregateData::ProvableBigSum(_) => 16_u32,
                 },
             )
         })
@@ -549,6 +554,11 @@ impl TreeNode {
                 AggregateData::CountAndSum(_, s) => Ok(s),
                 AggregateData::ProvableCount(_) => Ok(0),
                 AggregateData::ProvableCountAndSum(_, s) => Ok(s),
+                AggregateData::MinMax(..) => Ok(0),
+                AggregateData::ProvableSum(s) => Ok(s),
+                AggregateData::ProvableBigSum(_) => Err(Error::BigSumTreeUnderNormalSumTree(
+                    "for aggregate data as i64".to_string(),
+                )),
             },
             _ => Ok(0),
         }
@@ -578,6 +588,9 @@ impl TreeNode {
                 AggregateData::CountAndSum(c, _) => Ok(c),
                 AggregateData::ProvableCount(c) => Ok(c),
                 AggregateData::ProvableCountAndSum(c, _) => Ok(c),
+                AggregateData::MinMax(..) => Ok(0),
+                AggregateData::ProvableSum(_) => Ok(0),
+                AggregateData::ProvableBigSum(_) => Ok(0),
             },
             _ => Ok(0),
         }
@@ -586,7 +599,8 @@ impl TreeNode {
     /// Returns the i128 sum from the child link's aggregate data on the given
     /// side. If there is no child, returns 0.
     ///
-    /// Called by `aggregate_data()` for `BigSummedMerkNode` parents.
+    /// Called by `aggregate_data()` for `BigSummedMerkNode` and
+    /// `ProvableBigSummedMerkNode` parents.
     ///
     /// # Cross-type aggregate safety (audit finding #11)
     ///
@@ -605,11 +619,27 @@ impl TreeNode {
                 AggregateData::CountAndSum(_, s) => s as i128,
                 AggregateData::ProvableCount(_) => 0,
                 AggregateData::ProvableCountAndSum(_, s) => s as i128,
+                AggregateData::MinMax(..) => 0,
+                AggregateData::ProvableSum(s) => s as i128,
+                AggregateData::ProvableBigSum(s) => s,
             },
             _ => 0,
         }
     }
 
+    /// Returns the `(min, max)` pair from the child link's aggregate data on
+    /// the given side. If there is no child, returns `(i64::MAX, i64::MIN)`,
+    /// which leaves the parent's own values unchanged when folded in.
+    ///
+    /// Called by `aggregate_data()` for `MinMaxMerkNode` parents.
+    #[inline]
+    pub fn child_aggregate_min_max(&self, left: bool) -> (i64, i64) {
+        match self.link(left) {
+            Some(link) => link.aggregate_data().as_min_max(),
+            _ => (i64::MAX, i64::MIN),
+        }
+    }
+
     /// Computes and returns the hash of the root node.
     #[inline]
     pub fn hash(&self) -> CostContext<CryptoHash> {
@@ -622,7 +652,7 @@ impl TreeNode {
     }
 
     /// Computes and returns the hash of the root node, including aggregate data
-    /// for ProvableCountTree and ProvableCountSumTree.
+    /// for the provable tree types and MinMaxTree.
     #[inline]
     pub fn hash_for_link(&self, tree_type: TreeType) -> CostContext<CryptoHash> {
         match tree_type {
@@ -660,6 +690,41 @@ impl TreeNode {
                     self.hash()
                 }
             }
+            TreeType::MinMaxTree => {
+                let aggregate_data = self
+                    .aggregate_data()
+                    .unwrap_or(AggregateData::NoAggregateData);
+                if let AggregateData::MinMax(min, max) = aggregate_data {
+                    node_hash_with_min_max(
+                        self.inner.kv.hash(),
+                        self.child_hash(true),
+                        self.child_hash(false),
+                        min,
+                        max,
+                    )
+                } else {
+                    // Fallback to regular hash if aggregate data is unexpected
+                    self.hash()
+                }
+            }
+            TreeType::ProvableSumTree | TreeType::ProvableBigSumTree => {
+                // Both provable sum trees hash the sum as an i128
+                let aggregate_data = self
+                    .aggregate_data()
+                    .unwrap_or(AggregateData::NoAggregateData);
+                match aggregate_data {
+                    AggregateData::ProvableSum(_) | AggregateData::ProvableBigSum(_) => {
+                        node_hash_with_sum(
+                            self.inner.kv.hash(),
+                            self.child_hash(true),
+                            self.child_hash(false),
+                            aggregate_data.as_summed_i128(),
+                        )
+                    }
+                    // Fallback to regular hash if aggregate data is unexpected
+                    _ => self.hash(),
+                }
+            }
             _ => self.hash(),
         }
     }
@@ -675,6 +740,10 @@ impl TreeNode {
     /// - `CountedSummedMerkNode`: aggregates both count and sum from children
     /// - `ProvableCountedMerkNode` / `ProvableCountedSummedMerkNode`: same as
     ///   counted variants
+    /// - `MinMaxMerkNode`: folds own min/max with the left and right child
+    ///   min/max
+    /// - `ProvableSummedMerkNode` / `ProvableBigSummedMerkNode`: same as the
+    ///   summed variants
     ///
     /// # Cross-type aggregate safety (audit finding #11)
     ///
@@ -762,6 +831,28 @@ impl TreeNode {
                     aggregated_sum_value,
                 ))
             }
+            TreeFeatureType::MinMaxMerkNode(min, max) => {
+                let (left_min, left_max) = self.child_aggregate_min_max(true);
+                let (right_min, right_max) = self.child_aggregate_min_max(false);
+                Ok(AggregateData::MinMax(
+                    min.min(left_min).min(right_min),
+                    max.max(left_max).max(right_max),
+                ))
+            }
+            TreeFeatureType::ProvableSummedMerkNode(value) => {
+                let left = self.child_aggregate_sum_data_as_i64(true)?;
+                let right = self.child_aggregate_sum_data_as_i64(false)?;
+                value
+                    .checked_add(left)
+                    .and_then(|a| a.checked_add(right))
+                    .ok_or(Overflow("sum is overflowing"))
+                    .map(AggregateData::ProvableSum)
+            }
+            TreeFeatureType::ProvableBigSummedMerkNode(value) => value
+                .checked_add(self.child_aggregate_sum_data_as_i128(true))
+                .and_then(|a| a.checked_add(self.child_aggregate_sum_data_as_i128(false)))
+                .ok_or(Overflow("big sum is overflowing"))
+                .map(AggregateData::ProvableBigSum),
         }
     }
 
@@ -1199,6 +1290,23 @@ impl TreeNode {
                         *count,
                     )
                     .unwrap_add_cost(&mut cost),
+                    AggregateData::MinMax(min, max) => node_hash_with_min_max(
+                        tree.inner.kv.hash(),
+                        tree.child_hash(true),
+                        tree.child_hash(false),
+                        *min,
+                        *max,
+                    )
+                    .unwrap_add_cost(&mut cost),
+                    AggregateData::ProvableSum(_) | AggregateData::ProvableBigSum(_) => {
+                        node_hash_with_sum(
+                            tree.inner.kv.hash(),
+                            tree.child_hash(true),
+                            tree.child_hash(false),
+                            aggregate_data.as_summed_i128(),
+                        )
+                        .unwrap_add_cost(&mut cost)
+                    }
                     _ => tree.hash().unwrap_add_cost(&mut cost),
                 };
                 self.inner.left = Some(Link::Loaded {
@@ -1239,6 +1347,23 @@ impl TreeNode {
                         *count,
                     )
                     .unwrap_add_cost(&mut cost),
+                    AggregateData::MinMax(min, max) => node_hash_with_min_max(
+                        tree.inner.kv.hash(),
+                        tree.child_hash(true),
+                        tree.child_hash(false),
+                        *min,
+                        *max,
+                    )
+                    .unwrap_add_cost(&mut cost),
+                    AggregateData::ProvableSum(_) | AggregateData::ProvableBigSum(_) => {
+                        node_hash_with_sum(
+                            tree.inner.kv.hash(),
+                            tree.child_hash(true),
+                            tree.child_hash(false),
+                            aggregate_data.as_summed_i128(),
+                        )
+                        .unwrap_add_cost(&mut cost)
+                    }
                     _ => tree.hash().unwrap_add_cost(&mut cost),
                 };
                 self.inner.right = Some(Link::Loaded {
@@ -29,6 +29,12 @@ pub enum AggregateData {
     ProvableCount(u64),
     /// A provable combined element count and sum.
     ProvableCountAndSum(u64, i64),
+    /// The minimum and maximum of descendant min max values.
+    MinMax(i64, i64),
+    /// A provable signed 64-bit sum value.
+    ProvableSum(i64),
+    /// A provable signed 128-bit sum value.
+    ProvableBigSum(i128),
 }
 
 #[cfg(feature = "minimal")]
@@ -43,6 +49,9 @@ impl AggregateData {
             AggregateData::CountAndSum(..) => TreeType::CountSumTree,
             AggregateData::ProvableCount(_) => TreeType::ProvableCountTree,
             AggregateData::ProvableCountAndSum(..) => TreeType::ProvableCountSumTree,
+            AggregateData::MinMax(..) => TreeType::MinMaxTree,
+            AggregateData::ProvableSum(_) => TreeType::ProvableSumTree,
+            AggregateData::ProvableBigSum(_) => TreeType::ProvableBigSumTree,
         }
     }
 
@@ -51,7 +60,7 @@ impl AggregateData {
         match self {
             AggregateData::NoAggregateData => 0,
             AggregateData::Sum(s) => *s,
-            AggregateData::BigSum(i) => {
+            AggregateData::BigSum(i) | AggregateData::ProvableBigSum(i) => {
                 if *i > i64::MAX as i128 {
                     i64::MAX
                 } else if *i < i64::MIN as i128 {
@@ -64,6 +73,8 @@ impl AggregateData {
             AggregateData::CountAndSum(_, s) => *s,
             AggregateData::ProvableCount(_) => 0,
             AggregateData::ProvableCountAndSum(_, s) => *s,
+            AggregateData::MinMax(..) => 0,
+            AggregateData::ProvableSum(s) => *s,
         }
     }
 
@@ -77,6 +88,9 @@ impl AggregateData {
             AggregateData::CountAndSum(c, _) => *c,
             AggregateData::ProvableCount(c) => *c,
             AggregateData::ProvableCountAndSum(c, _) => *c,
+            AggregateData::MinMax(..) => 0,
+            AggregateData::ProvableSum(_) => 0,
+            AggregateData::ProvableBigSum(_) => 0,
         }
     }
 
@@ -90,6 +104,18 @@ impl AggregateData {
             AggregateData::CountAndSum(_, s) => *s as i128,
             AggregateData::ProvableCount(_) => 0,
             AggregateData::ProvableCountAndSum(_, s) => *s as i128,
+            AggregateData::MinMax(..) => 0,
+            AggregateData::ProvableSum(s) => *s as i128,
+            AggregateData::ProvableBigSum(i) => *i,
+        }
+    }
+
+    /// Returns the `(min, max)` pair, or `(i64::MAX, i64::MIN)` (the identity
+    /// of the min/max fold) if not a min max variant.
+    pub fn as_min_max(&self) -> (i64, i64) {
+        match self {
+            AggregateData::MinMax(min, max) => (*min, *max),
+            _ => (i64::MAX, i64::MIN),
         }
     }
 }
@@ -107,6 +133,9 @@ impl From<TreeFeatureType> for AggregateData {
             TreeFeatureType::ProvableCountedSummedMerkNode(count, sum) => {
                 AggregateData::ProvableCountAndSum(count, sum)
             }
+            TreeFeatureType::MinMaxMerkNode(min, max) => AggregateData::MinMax(min, max),
+            TreeFeatureType::ProvableSummedMerkNode(val) => AggregateData::ProvableSum(val),
+            TreeFeatureType::ProvableBigSummedMerkNode(val) => AggregateData::ProvableBigSum(val),
         }
     }
 }
@@ -143,6 +172,18 @@ mod tests {
             AggregateData::ProvableCountAndSum(1, 2).parent_tree_type(),
             TreeType::ProvableCountSumTree
         );
+        assert_eq!(
+            AggregateData::MinMax(-4, 9).parent_tree_type(),
+            TreeType::MinMaxTree
+        );
+        assert_eq!(
+            AggregateData::ProvableSum(3).parent_tree_type(),
+            TreeType::ProvableSumTree
+        );
+        assert_eq!(
+            AggregateData::ProvableBigSum(3).parent_tree_type(),
+            TreeType::ProvableBigSumTree
+        );
     }
 
     #[test]
@@ -165,6 +206,12 @@ mod tests {
         assert_eq!(AggregateData::CountAndSum(5, 20).as_sum_i64(), 20);
         assert_eq!(AggregateData::ProvableCount(3).as_sum_i64(), 0);
         assert_eq!(AggregateData::ProvableCountAndSum(1, -7).as_sum_i64(), -7);
+        assert_eq!(AggregateData::MinMax(-4, 9).as_sum_i64(), 0);
+        assert_eq!(AggregateData::ProvableSum(-12).as_sum_i64(), -12);
+        assert_eq!(
+            AggregateData::ProvableBigSum(i128::MAX).as_sum_i64(),
+            i64::MAX
+        );
     }
 
     #[test]
@@ -176,6 +223,8 @@ mod tests {
         assert_eq!(AggregateData::CountAndSum(5, 20).as_count_u64(), 5);
         assert_eq!(AggregateData::ProvableCount(3).as_count_u64(), 3);
         assert_eq!(AggregateData::ProvableCountAndSum(7, -1).as_count_u64(), 7);
+        assert_eq!(AggregateData::MinMax(-4, 9).as_count_u64(), 0);
+        assert_eq!(AggregateData::ProvableSum(4).as_count_u64(), 0);
     }
 
     #[test]
@@ -190,6 +239,22 @@ mod tests {
             AggregateData::ProvableCountAndSum(1, 50).as_summed_i128(),
             50
         );
+        assert_eq!(AggregateData::MinMax(-4, 9).as_summed_i128(), 0);
+        assert_eq!(AggregateData::ProvableSum(-8).as_summed_i128(), -8);
+        assert_eq!(
+            AggregateData::ProvableBigSum(i128::MIN).as_summed_i128(),
+            i128::MIN
+        );
+    }
+
+    #[test]
+    fn aggregate_data_as_min_max_all_variants() {
+        assert_eq!(AggregateData::MinMax(-4, 9).as_min_max(), (-4, 9));
+        assert_eq!(
+            AggregateData::NoAggregateData.as_min_max(),
+            (i64::MAX, i64::MIN)
+        );
+        assert_eq!(AggregateData::Sum(42).as_min_max(), (i64::MAX, i64::MIN));
     }
 
     #[test]
@@ -222,5 +287,17 @@ mod tests {
             AggregateData::from(TreeFeatureType::ProvableCountedSummedMerkNode(1, 2)),
             AggregateData::ProvableCountAndSum(1, 2)
         );
+        assert_eq!(
+            AggregateData::from(TreeFeatureType::MinMaxMerkNode(-4, 9)),
+            AggregateData::MinMax(-4, 9)
+        );
+        assert_eq!(
+            AggregateData::from(TreeFeatureType::ProvableSummedMerkNode(-2)),
+            AggregateData::ProvableSum(-2)
+        );
+        assert_eq!(
+            AggregateData::from(TreeFeatureType::ProvableBigSummedMerkNode(5)),
+            AggregateData::ProvableBigSum(5)
+        );
     }
 }
@@ -28,6 +28,15 @@ pub const COUNT_TREE_COST_SIZE: u32 = SUM_LAYER_COST_SIZE; // 12
 /// The cost of a count sum tree
 pub const COUNT_SUM_TREE_COST_SIZE: u32 = SUM_AND_COUNT_LAYER_COST_SIZE; // 21
 
+/// The cost of a min max tree (a varint for both the min and the max)
+pub const MIN_MAX_TREE_COST_SIZE: u32 = SUM_AND_COUNT_LAYER_COST_SIZE; // 21
+
+/// The cost of a provable sum tree
+pub const PROVABLE_SUM_TREE_COST_SIZE: u32 = SUM_LAYER_COST_SIZE; // 12
+
+/// The cost of a provable big sum tree
+pub const PROVABLE_BIG_SUM_TREE_COST_SIZE: u32 = BIG_SUM_LAYER_COST_SIZE; // 19
+
 /// The cost of a commitment tree (9 bytes total_count (u64 varint worst case)
 /// + 1 byte chunk_power (u8) + 2 bytes overhead)
 pub const COMMITMENT_TREE_COST_SIZE: u32 = 9 + 1 + 2; // 12
@@ -65,6 +74,9 @@ impl CostSize for TreeType {
             TreeType::MmrTree => MMR_TREE_COST_SIZE,
             TreeType::BulkAppendTree(_) => BULK_APPEND_TREE_COST_SIZE,
             TreeType::DenseAppendOnlyFixedSizeTree(_) => DENSE_TREE_COST_SIZE,
+            TreeType::MinMaxTree => MIN_MAX_TREE_COST_SIZE,
+            TreeType::ProvableSumTree => PROVABLE_SUM_TREE_COST_SIZE,
+            TreeType::ProvableBigSumTree => PROVABLE_BIG_SUM_TREE_COST_SIZE,
         }
     }
 }
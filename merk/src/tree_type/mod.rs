@@ -44,6 +44,12 @@ pub enum TreeType {
     BulkAppendTree(u8),
     /// A dense append-only tree with fixed-size entries and a configurable height.
     DenseAppendOnlyFixedSizeTree(u8),
+    /// A tree that tracks the minimum and maximum of descendant min max items.
+    MinMaxTree,
+    /// A sum tree whose running sum is committed into every node hash.
+    ProvableSumTree,
+    /// A big sum tree whose running sum is committed into every node hash.
+    ProvableBigSumTree,
 }
 
 impl TreeType {
@@ -63,6 +69,9 @@ impl TreeType {
             TreeType::MmrTree => 8,
             TreeType::BulkAppendTree(_) => 9,
             TreeType::DenseAppendOnlyFixedSizeTree(_) => 10,
+            TreeType::MinMaxTree => 11,
+            TreeType::ProvableSumTree => 12,
+            TreeType::ProvableBigSumTree => 13,
         }
     }
 }
@@ -83,7 +92,10 @@ impl TryFrom<u8> for TreeType {
             8 => Ok(TreeType::MmrTree),
             9 => Ok(TreeType::BulkAppendTree(0)),
             10 => Ok(TreeType::DenseAppendOnlyFixedSizeTree(0)),
-            n => Err(Error::UnknownTreeType(format!("got {}, max is 10", n))),
+            11 => Ok(TreeType::MinMaxTree),
+            12 => Ok(TreeType::ProvableSumTree),
+            13 => Ok(TreeType::ProvableBigSumTree),
+            n => Err(Error::UnknownTreeType(format!("got {}, max is 13", n))),
         }
     }
 }
@@ -102,6 +114,9 @@ impl fmt::Display for TreeType {
             TreeType::MmrTree => "MMR Tree",
             TreeType::BulkAppendTree(_) => "BulkAppendTree",
             TreeType::DenseAppendOnlyFixedSizeTree(_) => "Dense Tree",
+            TreeType::MinMaxTree => "Min Max Tree",
+            TreeType::ProvableSumTree => "Provable Sum Tree",
+            TreeType::ProvableBigSumTree => "Provable Big Sum Tree",
         };
         write!(f, "{}", s)
     }
@@ -135,6 +150,9 @@ impl TreeType {
             TreeType::MmrTree => false,
             TreeType::BulkAppendTree(_) => false,
             TreeType::DenseAppendOnlyFixedSizeTree(_) => false,
+            TreeType::MinMaxTree => false,
+            TreeType::ProvableSumTree => true,
+            TreeType::ProvableBigSumTree => true,
         }
     }
 
@@ -153,6 +171,9 @@ impl TreeType {
             TreeType::MmrTree => NodeType::NormalNode,
             TreeType::BulkAppendTree(_) => NodeType::NormalNode,
             TreeType::DenseAppendOnlyFixedSizeTree(_) => NodeType::NormalNode,
+            TreeType::MinMaxTree => NodeType::MinMaxNode,
+            TreeType::ProvableSumTree => NodeType::ProvableSumNode,
+            TreeType::ProvableBigSumTree => NodeType::ProvableBigSumNode,
         }
     }
 
@@ -170,6 +191,9 @@ impl TreeType {
             TreeType::MmrTree => TreeFeatureType::BasicMerkNode,
             TreeType::BulkAppendTree(_) => TreeFeatureType::BasicMerkNode,
             TreeType::DenseAppendOnlyFixedSizeTree(_) => TreeFeatureType::BasicMerkNode,
+            TreeType::MinMaxTree => TreeFeatureType::MinMaxMerkNode(i64::MAX, i64::MIN),
+            TreeType::ProvableSumTree => TreeFeatureType::ProvableSummedMerkNode(0),
+            TreeType::ProvableBigSumTree => TreeFeatureType::ProvableBigSummedMerkNode(0),
         }
     }
 
@@ -194,6 +218,9 @@ impl TreeType {
             TreeType::DenseAppendOnlyFixedSizeTree(_) => {
                 Some(ElementType::DenseAppendOnlyFixedSizeTree)
             }
+            TreeType::MinMaxTree => Some(ElementType::MinMaxTree),
+            TreeType::ProvableSumTree => Some(ElementType::ProvableSumTree),
+            TreeType::ProvableBigSumTree => Some(ElementType::ProvableBigSumTree),
         }
     }
 }
@@ -216,6 +243,9 @@ mod tests {
             TreeType::MmrTree,
             TreeType::BulkAppendTree(3),
             TreeType::DenseAppendOnlyFixedSizeTree(8),
+            TreeType::MinMaxTree,
+            TreeType::ProvableSumTree,
+            TreeType::ProvableBigSumTree,
         ];
         for v in &variants {
             let d = v.discriminant();
@@ -227,7 +257,7 @@ mod tests {
 
     #[test]
     fn tree_type_try_from_invalid() {
-        assert!(TreeType::try_from(11u8).is_err());
+        assert!(TreeType::try_from(14u8).is_err());
         assert!(TreeType::try_from(255u8).is_err());
     }
 
@@ -256,6 +286,15 @@ mod tests {
             format!("{}", TreeType::DenseAppendOnlyFixedSizeTree(0)),
             "Dense Tree"
         );
+        assert_eq!(format!("{}", TreeType::MinMaxTree), "Min Max Tree");
+        assert_eq!(
+            format!("{}", TreeType::ProvableSumTree),
+            "Provable Sum Tree"
+        );
+        assert_eq!(
+            format!("{}", TreeType::ProvableBigSumTree),
+            "Provable Big Sum Tree"
+        );
     }
 
     #[test]
@@ -271,6 +310,9 @@ mod tests {
         assert!(TreeType::MmrTree.uses_non_merk_data_storage());
         assert!(TreeType::BulkAppendTree(0).uses_non_merk_data_storage());
         assert!(TreeType::DenseAppendOnlyFixedSizeTree(0).uses_non_merk_data_storage());
+        assert!(!TreeType::MinMaxTree.uses_non_merk_data_storage());
+        assert!(!TreeType::ProvableSumTree.uses_non_merk_data_storage());
+        assert!(!TreeType::ProvableBigSumTree.uses_non_merk_data_storage());
     }
 
     #[test]
@@ -286,6 +328,9 @@ mod tests {
         assert!(!TreeType::MmrTree.allows_sum_item());
         assert!(!TreeType::BulkAppendTree(0).allows_sum_item());
         assert!(!TreeType::DenseAppendOnlyFixedSizeTree(0).allows_sum_item());
+        assert!(!TreeType::MinMaxTree.allows_sum_item());
+        assert!(TreeType::ProvableSumTree.allows_sum_item());
+        assert!(TreeType::ProvableBigSumTree.allows_sum_item());
     }
 
     #[test]
@@ -334,6 +379,18 @@ mod tests {
             TreeType::DenseAppendOnlyFixedSizeTree(0).empty_tree_feature_type(),
             TreeFeatureType::BasicMerkNode
         );
+        assert_eq!(
+            TreeType::MinMaxTree.empty_tree_feature_type(),
+            TreeFeatureType::MinMaxMerkNode(i64::MAX, i64::MIN)
+        );
+        assert_eq!(
+            TreeType::ProvableSumTree.empty_tree_feature_type(),
+            TreeFeatureType::ProvableSummedMerkNode(0)
+        );
+        assert_eq!(
+            TreeType::ProvableBigSumTree.empty_tree_feature_type(),
+            TreeFeatureType::ProvableBigSummedMerkNode(0)
+        );
     }
 
     #[test]
@@ -382,5 +439,17 @@ mod tests {
             TreeType::DenseAppendOnlyFixedSizeTree(0).to_element_type(),
             Some(ElementType::DenseAppendOnlyFixedSizeTree)
         );
+        assert_eq!(
+            TreeType::MinMaxTree.to_element_type(),
+            Some(ElementType::MinMaxTree)
+        );
+        assert_eq!(
+            TreeType::ProvableSumTree.to_element_type(),
+            Some(ElementType::ProvableSumTree)
+        );
+        assert_eq!(
+            TreeType::ProvableBigSumTree.to_element_type(),
+            Some(ElementType::ProvableBigSumTree)
+        );
     }
 }
@@ -0,0 +1,1168 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Storage backend chosen at runtime.
+//!
+//! [`StorageBackend`] wraps a [`RocksDbStorage`], a
+//! [`ReadOnlyRocksDbStorage`], a [`HistoricalRocksDbStorage`] or a
+//! [`MemoryStorage`] and implements [`Storage`] by dispatching to it, so
+//! that upper layers can be written once against a single concrete storage
+//! type. Transactions and contexts are wrapped the same way; mixing a
+//! transaction of one backend with a storage of another is a programming
+//! error.
+
+use std::{
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use grovedb_costs::{
+    storage_cost::key_value_cost::KeyValueStorageCost, ChildrenSizesWithIsSumTree, CostContext,
+    CostResult, CostsExt, OperationCost,
+};
+use grovedb_path::SubtreePath;
+use rocksdb::{
+    DBRawIteratorWithThreadMode, OptimisticTransactionDB, Transaction, WriteBatchWithTransaction,
+    DB,
+};
+
+use crate::{
+    error::Error,
+    memory_storage::{
+        MemoryStorage, MemoryTransaction, MemoryWriteBatch, PrefixedMemoryBatch,
+        PrefixedMemoryImmediateStorageContext, PrefixedMemoryRawIterator,
+        PrefixedMemoryTransactionContext,
+    },
+    prefixed::{self, PrefixedMultiContextBatchPart},
+    rocksdb_storage::{
+        HistoricalRocksDbStorage, PrefixedRocksDbBatch, PrefixedRocksDbHistoricalRawIterator,
+        PrefixedRocksDbHistoricalStorageContext, PrefixedRocksDbImmediateStorageContext,
+        PrefixedRocksDbRawIterator, PrefixedRocksDbReadOnlyStorageContext,
+        PrefixedRocksDbTransactionContext, ReadOnlyRocksDbStorage, ReadOnlyTransaction,
+        RocksDbStorage,
+    },
+    storage::SubtreePrefix,
+    worst_case_costs::WorstKeyLength,
+    Batch, RawIterator, Storage, StorageBatch, StorageContext,
+};
+
+/// Calls the same expression on whichever backend `$value` wraps.
+macro_rules! dispatch {
+    ($value:expr, $inner:ident => $body:expr $(; $extra:ident)*) => {
+        match $value {
+            Self::RocksDb($inner) => $body,
+            Self::Memory($inner) => $body,
+            Self::ReadOnlyRocksDb($inner) => $body,
+            Self::HistoricalRocksDb($inner) => $body,
+            $(Self::$extra($inner) => $body,)*
+        }
+    };
+}
+
+/// Type alias for a RocksDB transaction
+type Tx<'db> = Transaction<'db, OptimisticTransactionDB>;
+
+const MISMATCHED_TRANSACTION: &str = "transaction was started on a different storage backend";
+
+const MISMATCHED_WRITE_BATCH: &str = "write batch was built by a different storage backend";
+
+const READ_ONLY: &str = "attempted to write to read-only storage";
+
+/// Storage backed by either RocksDB, possibly opened read-only or at a past
+/// version, or memory.
+pub enum StorageBackend {
+    /// RocksDB storage
+    RocksDb(RocksDbStorage),
+    /// In-memory storage
+    Memory(MemoryStorage),
+    /// RocksDB storage opened read-only or as a secondary instance
+    ReadOnlyRocksDb(ReadOnlyRocksDbStorage),
+    /// Past version of RocksDB storage
+    HistoricalRocksDb(HistoricalRocksDbStorage),
+}
+
+impl From<RocksDbStorage> for StorageBackend {
+    fn from(storage: RocksDbStorage) -> Self {
+        StorageBackend::RocksDb(storage)
+    }
+}
+
+impl From<MemoryStorage> for StorageBackend {
+    fn from(storage: MemoryStorage) -> Self {
+        StorageBackend::Memory(storage)
+    }
+}
+
+impl From<ReadOnlyRocksDbStorage> for StorageBackend {
+    fn from(storage: ReadOnlyRocksDbStorage) -> Self {
+        StorageBackend::ReadOnlyRocksDb(storage)
+    }
+}
+
+impl From<HistoricalRocksDbStorage> for StorageBackend {
+    fn from(storage: HistoricalRocksDbStorage) -> Self {
+        StorageBackend::HistoricalRocksDb(storage)
+    }
+}
+
+impl StorageBackend {
+    /// A helper method to build a prefix to storage keys or identify a subtree
+    /// in `subtrees` map by tree path. Prefixes don't depend on the backend.
+    pub fn build_prefix<B>(path: SubtreePath<B>) -> CostContext<SubtreePrefix>
+    where
+        B: AsRef<[u8]>,
+    {
+        prefixed::build_prefix(path)
+    }
+
+    /// Clears all data from the storage.
+    pub fn wipe(&self) -> Result<(), Error> {
+        match self {
+            StorageBackend::RocksDb(storage) => storage.wipe(),
+            StorageBackend::Memory(storage) => storage.wipe(),
+            StorageBackend::ReadOnlyRocksDb(_) | StorageBackend::HistoricalRocksDb(_) => {
+                Err(Error::StorageError(READ_ONLY.to_string()))
+            }
+        }
+    }
+
+    /// Returns `true` if data is only kept in memory.
+    pub fn is_in_memory(&self) -> bool {
+        matches!(self, StorageBackend::Memory(_))
+    }
+
+    /// Returns `true` if the storage rejects writes.
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            StorageBackend::ReadOnlyRocksDb(_) | StorageBackend::HistoricalRocksDb(_)
+        )
+    }
+
+    /// Returns the earliest and the latest versions which can be read with
+    /// [`Self::at_version`], `None` without history.
+    pub fn history_version_range(&self) -> Result<Option<(u64, u64)>, Error> {
+        match self {
+            StorageBackend::RocksDb(storage) => storage.history_version_range(),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns a read-only view of the storage as it was once `version` got
+    /// committed. Only RocksDB storage opened with history has past versions.
+    pub fn at_version(&self, version: u64) -> Result<StorageBackend, Error> {
+        match self {
+            StorageBackend::RocksDb(storage) => storage.at_version(version).map(Into::into),
+            _ => Err(Error::StorageError(
+                "storage doesn't keep past versions".to_string(),
+            )),
+        }
+    }
+
+    /// Returns the write batch, with costs and pending costs
+    /// Pending costs are costs that should only be applied after successful
+    /// write of the write batch.
+    pub fn build_write_batch(
+        &self,
+        storage_batch: StorageBatch,
+    ) -> CostResult<(BackendWriteBatch, OperationCost), Error> {
+        match self {
+            StorageBackend::RocksDb(storage) => {
+                storage
+                    .build_write_batch(storage_batch)
+                    .map_ok(|(batch, pending_costs)| {
+                        (BackendWriteBatch::RocksDb(batch), pending_costs)
+                    })
+            }
+            StorageBackend::Memory(storage) => storage
+                .build_write_batch(storage_batch)
+                .map_ok(|(batch, pending_costs)| (BackendWriteBatch::Memory(batch), pending_costs)),
+            StorageBackend::ReadOnlyRocksDb(_) | StorageBackend::HistoricalRocksDb(_) => {
+                Err(Error::StorageError(READ_ONLY.to_string())).wrap_with_cost(Default::default())
+            }
+        }
+    }
+
+    /// Continues the write batch, returning pending costs.
+    pub fn continue_write_batch(
+        &self,
+        db_batch: &mut BackendWriteBatch,
+        storage_batch: StorageBatch,
+    ) -> CostResult<OperationCost, Error> {
+        match (self, db_batch) {
+            (StorageBackend::RocksDb(storage), BackendWriteBatch::RocksDb(batch)) => {
+                storage.continue_write_batch(batch, storage_batch)
+            }
+            (StorageBackend::Memory(storage), BackendWriteBatch::Memory(batch)) => {
+                storage.continue_write_batch(batch, storage_batch)
+            }
+            (StorageBackend::ReadOnlyRocksDb(_) | StorageBackend::HistoricalRocksDb(_), _) => {
+                Err(Error::StorageError(READ_ONLY.to_string())).wrap_with_cost(Default::default())
+            }
+            _ => Err(Error::StorageError(MISMATCHED_WRITE_BATCH.to_string()))
+                .wrap_with_cost(Default::default()),
+        }
+    }
+
+    /// Commits a write batch
+    pub fn commit_db_write_batch(
+        &self,
+        db_batch: BackendWriteBatch,
+        pending_costs: OperationCost,
+        transaction: Option<&BackendTransaction>,
+    ) -> CostResult<(), Error> {
+        match (
+            self,
+            db_batch,
+            transaction.map(|transaction| &transaction.kind),
+        ) {
+            (StorageBackend::RocksDb(storage), BackendWriteBatch::RocksDb(batch), None) => {
+                storage.commit_db_write_batch(batch, pending_costs, None)
+            }
+            (
+                StorageBackend::RocksDb(storage),
+                BackendWriteBatch::RocksDb(batch),
+                Some(BackendTransactionKind::RocksDb(transaction)),
+            ) => storage.commit_db_write_batch(batch, pending_costs, Some(transaction)),
+            (StorageBackend::Memory(storage), BackendWriteBatch::Memory(batch), None) => {
+                storage.commit_db_write_batch(batch, pending_costs, None)
+            }
+            (
+                StorageBackend::Memory(storage),
+                BackendWriteBatch::Memory(batch),
+                Some(BackendTransactionKind::Memory(transaction)),
+            ) => storage.commit_db_write_batch(batch, pending_costs, Some(transaction)),
+            (StorageBackend::ReadOnlyRocksDb(_) | StorageBackend::HistoricalRocksDb(_), ..) => {
+                Err(Error::StorageError(READ_ONLY.to_string())).wrap_with_cost(Default::default())
+            }
+            (_, _, Some(_)) => Err(Error::StorageError(MISMATCHED_TRANSACTION.to_string()))
+                .wrap_with_cost(Default::default()),
+            _ => Err(Error::StorageError(MISMATCHED_WRITE_BATCH.to_string()))
+                .wrap_with_cost(Default::default()),
+        }
+    }
+}
+
+/// Write batch of a [`StorageBackend`].
+pub enum BackendWriteBatch {
+    /// RocksDB write batch
+    RocksDb(WriteBatchWithTransaction<true>),
+    /// In-memory storage write batch
+    Memory(MemoryWriteBatch),
+}
+
+/// Identifier of the next transaction started on a [`StorageBackend`].
+static NEXT_TRANSACTION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Transaction of a [`StorageBackend`].
+pub struct BackendTransaction<'db> {
+    id: u64,
+    kind: BackendTransactionKind<'db>,
+}
+
+/// Transaction of the storage a [`StorageBackend`] runs on.
+enum BackendTransactionKind<'db> {
+    /// RocksDB transaction
+    RocksDb(Tx<'db>),
+    /// In-memory storage transaction
+    Memory(MemoryTransaction<'db>),
+    /// Read-only storage transaction
+    ReadOnlyRocksDb(ReadOnlyTransaction),
+    /// Past version storage transaction
+    HistoricalRocksDb(ReadOnlyTransaction),
+}
+
+impl<'db> BackendTransaction<'db> {
+    fn new(kind: BackendTransactionKind<'db>) -> Self {
+        BackendTransaction {
+            id: NEXT_TRANSACTION_ID.fetch_add(1, Ordering::Relaxed),
+            kind,
+        }
+    }
+
+    /// Identifier of the transaction, no two transactions started in the
+    /// process have the same one.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Consumes and commits the transaction.
+    pub fn commit(self) -> Result<(), Error> {
+        match self.kind {
+            BackendTransactionKind::RocksDb(transaction) => {
+                transaction.commit().map_err(Error::RocksDBError)
+            }
+            BackendTransactionKind::Memory(transaction) => transaction.commit(),
+            BackendTransactionKind::ReadOnlyRocksDb(_)
+            | BackendTransactionKind::HistoricalRocksDb(_) => Ok(()),
+        }
+    }
+
+    /// Rolls back the transaction, reverting its pending writes.
+    pub fn rollback(&self) -> Result<(), Error> {
+        match &self.kind {
+            BackendTransactionKind::RocksDb(transaction) => {
+                transaction.rollback().map_err(Error::RocksDBError)
+            }
+            BackendTransactionKind::Memory(transaction) => transaction.rollback(),
+            BackendTransactionKind::ReadOnlyRocksDb(_)
+            | BackendTransactionKind::HistoricalRocksDb(_) => Ok(()),
+        }
+    }
+
+    /// Sets a savepoint which writes made afterwards can be rolled back to.
+    pub fn set_savepoint(&self) {
+        match &self.kind {
+            BackendTransactionKind::RocksDb(transaction) => transaction.set_savepoint(),
+            BackendTransactionKind::Memory(transaction) => transaction.set_savepoint(),
+            BackendTransactionKind::ReadOnlyRocksDb(_)
+            | BackendTransactionKind::HistoricalRocksDb(_) => {}
+        }
+    }
+
+    /// Reverts the writes made since the last savepoint and removes it.
+    pub fn rollback_to_savepoint(&self) -> Result<(), Error> {
+        match &self.kind {
+            BackendTransactionKind::RocksDb(transaction) => transaction
+                .rollback_to_savepoint()
+                .map_err(Error::RocksDBError),
+            BackendTransactionKind::Memory(transaction) => transaction.rollback_to_savepoint(),
+            BackendTransactionKind::ReadOnlyRocksDb(_)
+            | BackendTransactionKind::HistoricalRocksDb(_) => Ok(()),
+        }
+    }
+}
+
+impl<'db> Storage<'db> for StorageBackend {
+    type BatchTransactionalStorageContext = PrefixedBackendTransactionContext<'db>;
+    type ImmediateStorageContext = PrefixedBackendImmediateStorageContext<'db>;
+    type Transaction = BackendTransaction<'db>;
+
+    fn start_transaction(&'db self) -> Self::Transaction {
+        BackendTransaction::new(match self {
+            StorageBackend::RocksDb(storage) => {
+                BackendTransactionKind::RocksDb(storage.start_transaction())
+            }
+            StorageBackend::Memory(storage) => {
+                BackendTransactionKind::Memory(storage.start_transaction())
+            }
+            StorageBackend::ReadOnlyRocksDb(storage) => {
+                BackendTransactionKind::ReadOnlyRocksDb(storage.start_transaction())
+            }
+            StorageBackend::HistoricalRocksDb(storage) => {
+                BackendTransactionKind::HistoricalRocksDb(storage.start_transaction())
+            }
+        })
+    }
+
+    fn commit_transaction(&self, transaction: Self::Transaction) -> CostResult<(), Error> {
+        match (self, transaction.kind) {
+            (StorageBackend::RocksDb(storage), BackendTransactionKind::RocksDb(transaction)) => {
+                storage.commit_transaction(transaction)
+            }
+            (StorageBackend::Memory(storage), BackendTransactionKind::Memory(transaction)) => {
+                storage.commit_transaction(transaction)
+            }
+            (
+                StorageBackend::ReadOnlyRocksDb(storage),
+                BackendTransactionKind::ReadOnlyRocksDb(transaction),
+            ) => storage.commit_transaction(transaction),
+            (
+                StorageBackend::HistoricalRocksDb(storage),
+                BackendTransactionKind::HistoricalRocksDb(transaction),
+            ) => storage.commit_transaction(transaction),
+            _ => Err(Error::StorageError(MISMATCHED_TRANSACTION.to_string()))
+                .wrap_with_cost(OperationCost::default()),
+        }
+    }
+
+    fn rollback_transaction(&self, transaction: &Self::Transaction) -> Result<(), Error> {
+        match (self, &transaction.kind) {
+            (StorageBackend::RocksDb(storage), BackendTransactionKind::RocksDb(transaction)) => {
+                storage.rollback_transaction(transaction)
+            }
+            (StorageBackend::Memory(storage), BackendTransactionKind::Memory(transaction)) => {
+                storage.rollback_transaction(transaction)
+            }
+            (
+                StorageBackend::ReadOnlyRocksDb(storage),
+                BackendTransactionKind::ReadOnlyRocksDb(transaction),
+            ) => storage.rollback_transaction(transaction),
+            (
+                StorageBackend::HistoricalRocksDb(storage),
+                BackendTransactionKind::HistoricalRocksDb(transaction),
+            ) => storage.rollback_transaction(transaction),
+            _ => Err(Error::StorageError(MISMATCHED_TRANSACTION.to_string())),
+        }
+    }
+
+    fn commit_multi_context_batch(
+        &self,
+        batch: StorageBatch,
+        transaction: Option<&'db Self::Transaction>,
+    ) -> CostResult<(), Error> {
+        match (self, transaction.map(|transaction| &transaction.kind)) {
+            (StorageBackend::RocksDb(storage), None) => {
+                storage.commit_multi_context_batch(batch, None)
+            }
+            (
+                StorageBackend::RocksDb(storage),
+                Some(BackendTransactionKind::RocksDb(transaction)),
+            ) => storage.commit_multi_context_batch(batch, Some(transaction)),
+            (StorageBackend::Memory(storage), None) => {
+                storage.commit_multi_context_batch(batch, None)
+            }
+            (
+                StorageBackend::Memory(storage),
+                Some(BackendTransactionKind::Memory(transaction)),
+            ) => storage.commit_multi_context_batch(batch, Some(transaction)),
+            (StorageBackend::ReadOnlyRocksDb(storage), None) => {
+                storage.commit_multi_context_batch(batch, None)
+            }
+            (
+                StorageBackend::ReadOnlyRocksDb(storage),
+                Some(BackendTransactionKind::ReadOnlyRocksDb(transaction)),
+            ) => storage.commit_multi_context_batch(batch, Some(transaction)),
+            (StorageBackend::HistoricalRocksDb(storage), None) => {
+                storage.commit_multi_context_batch(batch, None)
+            }
+            (
+                StorageBackend::HistoricalRocksDb(storage),
+                Some(BackendTransactionKind::HistoricalRocksDb(transaction)),
+            ) => storage.commit_multi_context_batch(batch, Some(transaction)),
+            _ => Err(Error::StorageError(MISMATCHED_TRANSACTION.to_string()))
+                .wrap_with_cost(OperationCost::default()),
+        }
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        dispatch!(self, storage => storage.flush())
+    }
+
+    fn get_transactional_storage_context<'b, B>(
+        &'db self,
+        path: SubtreePath<'b, B>,
+        batch: Option<&'db StorageBatch>,
+        transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::BatchTransactionalStorageContext>
+    where
+        B: AsRef<[u8]> + 'b,
+    {
+        Self::build_prefix(path).flat_map(|prefix| {
+            self.get_transactional_storage_context_by_subtree_prefix(prefix, batch, transaction)
+        })
+    }
+
+    fn get_transactional_storage_context_by_subtree_prefix(
+        &'db self,
+        prefix: SubtreePrefix,
+        batch: Option<&'db StorageBatch>,
+        transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::BatchTransactionalStorageContext> {
+        match (self, &transaction.kind) {
+            (StorageBackend::RocksDb(storage), BackendTransactionKind::RocksDb(transaction)) => {
+                storage
+                    .get_transactional_storage_context_by_subtree_prefix(prefix, batch, transaction)
+                    .map(PrefixedBackendTransactionContext::RocksDb)
+            }
+            (StorageBackend::Memory(storage), BackendTransactionKind::Memory(transaction)) => {
+                storage
+                    .get_transactional_storage_context_by_subtree_prefix(prefix, batch, transaction)
+                    .map(PrefixedBackendTransactionContext::Memory)
+            }
+            (
+                StorageBackend::ReadOnlyRocksDb(storage),
+                BackendTransactionKind::ReadOnlyRocksDb(transaction),
+            ) => storage
+                .get_transactional_storage_context_by_subtree_prefix(prefix, batch, transaction)
+                .map(PrefixedBackendTransactionContext::ReadOnlyRocksDb),
+            (
+                StorageBackend::HistoricalRocksDb(storage),
+                BackendTransactionKind::HistoricalRocksDb(transaction),
+            ) => storage
+                .get_transactional_storage_context_by_subtree_prefix(prefix, batch, transaction)
+                .map(PrefixedBackendTransactionContext::HistoricalRocksDb),
+            _ => PrefixedBackendTransactionContext::Mismatched(MismatchedStorageContext { prefix })
+                .wrap_with_cost(OperationCost::default()),
+        }
+    }
+
+    fn get_immediate_storage_context<'b, B>(
+        &'db self,
+        path: SubtreePath<'b, B>,
+        transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::ImmediateStorageContext>
+    where
+        B: AsRef<[u8]> + 'b,
+    {
+        Self::build_prefix(path).flat_map(|prefix| {
+            self.get_immediate_storage_context_by_subtree_prefix(prefix, transaction)
+        })
+    }
+
+    fn get_immediate_storage_context_by_subtree_prefix(
+        &'db self,
+        prefix: SubtreePrefix,
+        transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::ImmediateStorageContext> {
+        match (self, &transaction.kind) {
+            (StorageBackend::RocksDb(storage), BackendTransactionKind::RocksDb(transaction)) => {
+                storage
+                    .get_immediate_storage_context_by_subtree_prefix(prefix, transaction)
+                    .map(PrefixedBackendImmediateStorageContext::RocksDb)
+            }
+            (StorageBackend::Memory(storage), BackendTransactionKind::Memory(transaction)) => {
+                storage
+                    .get_immediate_storage_context_by_subtree_prefix(prefix, transaction)
+                    .map(PrefixedBackendImmediateStorageContext::Memory)
+            }
+            (
+                StorageBackend::ReadOnlyRocksDb(storage),
+                BackendTransactionKind::ReadOnlyRocksDb(transaction),
+            ) => storage
+                .get_immediate_storage_context_by_subtree_prefix(prefix, transaction)
+                .map(PrefixedBackendImmediateStorageContext::ReadOnlyRocksDb),
+            (
+                StorageBackend::HistoricalRocksDb(storage),
+                BackendTransactionKind::HistoricalRocksDb(transaction),
+            ) => storage
+                .get_immediate_storage_context_by_subtree_prefix(prefix, transaction)
+                .map(PrefixedBackendImmediateStorageContext::HistoricalRocksDb),
+            _ => PrefixedBackendImmediateStorageContext::Mismatched(MismatchedStorageContext {
+                prefix,
+            })
+            .wrap_with_cost(OperationCost::default()),
+        }
+    }
+
+    fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        dispatch!(self, storage => storage.create_checkpoint(path))
+    }
+
+    fn get_storage_context_cost<L: WorstKeyLength>(path: &[L]) -> OperationCost {
+        prefixed::storage_context_cost(path)
+    }
+}
+
+/// Transactional storage context of a [`StorageBackend`].
+pub enum PrefixedBackendTransactionContext<'db> {
+    /// RocksDB context
+    RocksDb(PrefixedRocksDbTransactionContext<'db>),
+    /// In-memory storage context
+    Memory(PrefixedMemoryTransactionContext<'db>),
+    /// Read-only RocksDB context
+    ReadOnlyRocksDb(PrefixedRocksDbReadOnlyStorageContext<'db>),
+    /// Past version RocksDB context
+    HistoricalRocksDb(PrefixedRocksDbHistoricalStorageContext<'db>),
+    /// Context requested with a transaction of a different backend
+    Mismatched(MismatchedStorageContext),
+}
+
+impl PrefixedBackendTransactionContext<'_> {
+    /// Clears all data in the data namespace for this prefix.
+    pub fn clear(&mut self) -> CostResult<(), Error> {
+        dispatch!(self, context => context.clear(); Mismatched)
+    }
+}
+
+impl<'db> StorageContext<'db> for PrefixedBackendTransactionContext<'db> {
+    type Batch = PrefixedMultiContextBatchPart;
+    type RawIterator = PrefixedBackendRawIterator<'db>;
+
+    fn put<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        children_sizes: ChildrenSizesWithIsSumTree,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.put(key, value, children_sizes, cost_info); Mismatched)
+    }
+
+    fn put_aux<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.put_aux(key, value, cost_info); Mismatched)
+    }
+
+    fn put_root<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.put_root(key, value, cost_info); Mismatched)
+    }
+
+    fn put_meta<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.put_meta(key, value, cost_info); Mismatched)
+    }
+
+    fn delete<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.delete(key, cost_info); Mismatched)
+    }
+
+    fn delete_aux<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.delete_aux(key, cost_info); Mismatched)
+    }
+
+    fn delete_root<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.delete_root(key, cost_info); Mismatched)
+    }
+
+    fn delete_meta<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.delete_meta(key, cost_info); Mismatched)
+    }
+
+    fn get<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        dispatch!(self, context => context.get(key); Mismatched)
+    }
+
+    fn get_aux<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        dispatch!(self, context => context.get_aux(key); Mismatched)
+    }
+
+    fn get_root<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        dispatch!(self, context => context.get_root(key); Mismatched)
+    }
+
+    fn get_meta<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        dispatch!(self, context => context.get_meta(key); Mismatched)
+    }
+
+    fn new_batch(&self) -> Self::Batch {
+        dispatch!(self, context => context.new_batch(); Mismatched)
+    }
+
+    fn commit_batch(&self, batch: Self::Batch) -> CostResult<(), Error> {
+        dispatch!(self, context => context.commit_batch(batch); Mismatched)
+    }
+
+    fn raw_iter(&self) -> Self::RawIterator {
+        match self {
+            Self::RocksDb(context) => PrefixedBackendRawIterator::RocksDb(context.raw_iter()),
+            Self::Memory(context) => PrefixedBackendRawIterator::Memory(context.raw_iter()),
+            Self::ReadOnlyRocksDb(context) => {
+                PrefixedBackendRawIterator::ReadOnlyRocksDb(context.raw_iter())
+            }
+            Self::HistoricalRocksDb(context) => {
+                PrefixedBackendRawIterator::HistoricalRocksDb(context.raw_iter())
+            }
+            Self::Mismatched(context) => PrefixedBackendRawIterator::Mismatched(context.raw_iter()),
+        }
+    }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        match self {
+            Self::RocksDb(context) => PrefixedBackendRawIterator::RocksDb(context.raw_iter_meta()),
+            Self::Memory(context) => PrefixedBackendRawIterator::Memory(context.raw_iter_meta()),
+            Self::ReadOnlyRocksDb(context) => {
+                PrefixedBackendRawIterator::ReadOnlyRocksDb(context.raw_iter_meta())
+            }
+            Self::HistoricalRocksDb(context) => {
+                PrefixedBackendRawIterator::HistoricalRocksDb(context.raw_iter_meta())
+            }
+            Self::Mismatched(context) => {
+                PrefixedBackendRawIterator::Mismatched(context.raw_iter_meta())
+            }
+        }
+    }
+}
+
+/// Immediate storage context of a [`StorageBackend`].
+pub enum PrefixedBackendImmediateStorageContext<'db> {
+    /// RocksDB context
+    RocksDb(PrefixedRocksDbImmediateStorageContext<'db>),
+    /// In-memory storage context
+    Memory(PrefixedMemoryImmediateStorageContext<'db>),
+    /// Read-only RocksDB context
+    ReadOnlyRocksDb(PrefixedRocksDbReadOnlyStorageContext<'db>),
+    /// Past version RocksDB context
+    HistoricalRocksDb(PrefixedRocksDbHistoricalStorageContext<'db>),
+    /// Context requested with a transaction of a different backend
+    Mismatched(MismatchedStorageContext),
+}
+
+impl<'db> StorageContext<'db> for PrefixedBackendImmediateStorageContext<'db> {
+    type Batch = PrefixedBackendBatch<'db>;
+    type RawIterator = PrefixedBackendRawIterator<'db>;
+
+    fn put<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        children_sizes: ChildrenSizesWithIsSumTree,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.put(key, value, children_sizes, cost_info); Mismatched)
+    }
+
+    fn put_aux<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.put_aux(key, value, cost_info); Mismatched)
+    }
+
+    fn put_root<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.put_root(key, value, cost_info); Mismatched)
+    }
+
+    fn put_meta<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.put_meta(key, value, cost_info); Mismatched)
+    }
+
+    fn delete<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.delete(key, cost_info); Mismatched)
+    }
+
+    fn delete_aux<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.delete_aux(key, cost_info); Mismatched)
+    }
+
+    fn delete_root<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.delete_root(key, cost_info); Mismatched)
+    }
+
+    fn delete_meta<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        dispatch!(self, context => context.delete_meta(key, cost_info); Mismatched)
+    }
+
+    fn get<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        dispatch!(self, context => context.get(key); Mismatched)
+    }
+
+    fn get_aux<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        dispatch!(self, context => context.get_aux(key); Mismatched)
+    }
+
+    fn get_root<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        dispatch!(self, context => context.get_root(key); Mismatched)
+    }
+
+    fn get_meta<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        dispatch!(self, context => context.get_meta(key); Mismatched)
+    }
+
+    fn new_batch(&self) -> Self::Batch {
+        match self {
+            Self::RocksDb(context) => PrefixedBackendBatch::RocksDb(context.new_batch()),
+            Self::Memory(context) => PrefixedBackendBatch::Memory(context.new_batch()),
+            Self::ReadOnlyRocksDb(context) => {
+                PrefixedBackendBatch::ReadOnlyRocksDb(context.new_batch())
+            }
+            Self::HistoricalRocksDb(context) => {
+                PrefixedBackendBatch::HistoricalRocksDb(context.new_batch())
+            }
+            Self::Mismatched(context) => PrefixedBackendBatch::Mismatched(context.new_batch()),
+        }
+    }
+
+    fn commit_batch(&self, batch: Self::Batch) -> CostResult<(), Error> {
+        match (self, batch) {
+            (Self::RocksDb(context), PrefixedBackendBatch::RocksDb(batch)) => {
+                context.commit_batch(batch)
+            }
+            (Self::Memory(context), PrefixedBackendBatch::Memory(batch)) => {
+                context.commit_batch(batch)
+            }
+            (Self::ReadOnlyRocksDb(context), PrefixedBackendBatch::ReadOnlyRocksDb(batch)) => {
+                context.commit_batch(batch)
+            }
+            (Self::HistoricalRocksDb(context), PrefixedBackendBatch::HistoricalRocksDb(batch)) => {
+                context.commit_batch(batch)
+            }
+            (Self::Mismatched(context), PrefixedBackendBatch::Mismatched(batch)) => {
+                context.commit_batch(batch)
+            }
+            _ => Err(Error::StorageError(
+                "batch was created by a context of a different storage backend".to_string(),
+            ))
+            .wrap_with_cost(OperationCost::default()),
+        }
+    }
+
+    fn raw_iter(&self) -> Self::RawIterator {
+        match self {
+            Self::RocksDb(context) => PrefixedBackendRawIterator::RocksDb(context.raw_iter()),
+            Self::Memory(context) => PrefixedBackendRawIterator::Memory(context.raw_iter()),
+            Self::ReadOnlyRocksDb(context) => {
+                PrefixedBackendRawIterator::ReadOnlyRocksDb(context.raw_iter())
+            }
+            Self::HistoricalRocksDb(context) => {
+                PrefixedBackendRawIterator::HistoricalRocksDb(context.raw_iter())
+            }
+            Self::Mismatched(context) => PrefixedBackendRawIterator::Mismatched(context.raw_iter()),
+        }
+    }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        match self {
+            Self::RocksDb(context) => PrefixedBackendRawIterator::RocksDb(context.raw_iter_meta()),
+            Self::Memory(context) => PrefixedBackendRawIterator::Memory(context.raw_iter_meta()),
+            Self::ReadOnlyRocksDb(context) => {
+                PrefixedBackendRawIterator::ReadOnlyRocksDb(context.raw_iter_meta())
+            }
+            Self::HistoricalRocksDb(context) => {
+                PrefixedBackendRawIterator::HistoricalRocksDb(context.raw_iter_meta())
+            }
+            Self::Mismatched(context) => {
+                PrefixedBackendRawIterator::Mismatched(context.raw_iter_meta())
+            }
+        }
+    }
+}
+
+/// Storage context of a [`StorageBackend`] requested with a transaction
+/// started on a different backend. Every read and write fails, and its raw
+/// iterators are never valid.
+pub struct MismatchedStorageContext {
+    prefix: SubtreePrefix,
+}
+
+fn mismatched_transaction<T>() -> CostResult<T, Error> {
+    Err(Error::StorageError(MISMATCHED_TRANSACTION.to_string()))
+        .wrap_with_cost(OperationCost::default())
+}
+
+impl MismatchedStorageContext {
+    /// Fails, as the context has no storage to clear.
+    pub fn clear(&mut self) -> CostResult<(), Error> {
+        mismatched_transaction()
+    }
+}
+
+impl StorageContext<'_> for MismatchedStorageContext {
+    type Batch = PrefixedMultiContextBatchPart;
+    type RawIterator = MismatchedRawIterator;
+
+    fn put<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _children_sizes: ChildrenSizesWithIsSumTree,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        mismatched_transaction()
+    }
+
+    fn put_aux<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        mismatched_transaction()
+    }
+
+    fn put_root<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        mismatched_transaction()
+    }
+
+    fn put_meta<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        mismatched_transaction()
+    }
+
+    fn delete<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        mismatched_transaction()
+    }
+
+    fn delete_aux<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        mismatched_transaction()
+    }
+
+    fn delete_root<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        mismatched_transaction()
+    }
+
+    fn delete_meta<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        mismatched_transaction()
+    }
+
+    fn get<K: AsRef<[u8]>>(&self, _key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        mismatched_transaction()
+    }
+
+    fn get_aux<K: AsRef<[u8]>>(&self, _key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        mismatched_transaction()
+    }
+
+    fn get_root<K: AsRef<[u8]>>(&self, _key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        mismatched_transaction()
+    }
+
+    fn get_meta<K: AsRef<[u8]>>(&self, _key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        mismatched_transaction()
+    }
+
+    fn new_batch(&self) -> Self::Batch {
+        PrefixedMultiContextBatchPart {
+            prefix: self.prefix,
+            batch: StorageBatch::new(),
+        }
+    }
+
+    fn commit_batch(&self, _batch: Self::Batch) -> CostResult<(), Error> {
+        mismatched_transaction()
+    }
+
+    fn raw_iter(&self) -> Self::RawIterator {
+        MismatchedRawIterator
+    }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        MismatchedRawIterator
+    }
+}
+
+/// Raw iterator of a [`MismatchedStorageContext`], which is never valid.
+pub struct MismatchedRawIterator;
+
+impl RawIterator for MismatchedRawIterator {
+    fn seek_to_first(&mut self) -> CostContext<()> {
+        ().wrap_with_cost(OperationCost::default())
+    }
+
+    fn seek_to_last(&mut self) -> CostContext<()> {
+        ().wrap_with_cost(OperationCost::default())
+    }
+
+    fn seek<K: AsRef<[u8]>>(&mut self, _key: K) -> CostContext<()> {
+        ().wrap_with_cost(OperationCost::default())
+    }
+
+    fn seek_for_prev<K: AsRef<[u8]>>(&mut self, _key: K) -> CostContext<()> {
+        ().wrap_with_cost(OperationCost::default())
+    }
+
+    fn next(&mut self) -> CostContext<()> {
+        ().wrap_with_cost(OperationCost::default())
+    }
+
+    fn prev(&mut self) -> CostContext<()> {
+        ().wrap_with_cost(OperationCost::default())
+    }
+
+    fn value(&self) -> CostContext<Option<&[u8]>> {
+        None.wrap_with_cost(OperationCost::default())
+    }
+
+    fn key(&self) -> CostContext<Option<&[u8]>> {
+        None.wrap_with_cost(OperationCost::default())
+    }
+
+    fn valid(&self) -> CostContext<bool> {
+        false.wrap_with_cost(OperationCost::default())
+    }
+}
+
+/// Raw iterator of a [`StorageBackend`] context.
+pub enum PrefixedBackendRawIterator<'db> {
+    /// RocksDB raw iterator
+    RocksDb(PrefixedRocksDbRawIterator<DBRawIteratorWithThreadMode<'db, Tx<'db>>>),
+    /// In-memory storage raw iterator
+    Memory(PrefixedMemoryRawIterator<'db>),
+    /// Read-only RocksDB raw iterator
+    ReadOnlyRocksDb(PrefixedRocksDbRawIterator<DBRawIteratorWithThreadMode<'db, DB>>),
+    /// Past version RocksDB raw iterator
+    HistoricalRocksDb(PrefixedRocksDbHistoricalRawIterator<'db>),
+    /// Raw iterator of a mismatched context, which is never valid
+    Mismatched(MismatchedRawIterator),
+}
+
+impl RawIterator for PrefixedBackendRawIterator<'_> {
+    fn seek_to_first(&mut self) -> CostContext<()> {
+        dispatch!(self, iterator => iterator.seek_to_first(); Mismatched)
+    }
+
+    fn seek_to_last(&mut self) -> CostContext<()> {
+        dispatch!(self, iterator => iterator.seek_to_last(); Mismatched)
+    }
+
+    fn seek<K: AsRef<[u8]>>(&mut self, key: K) -> CostContext<()> {
+        dispatch!(self, iterator => iterator.seek(key); Mismatched)
+    }
+
+    fn seek_for_prev<K: AsRef<[u8]>>(&mut self, key: K) -> CostContext<()> {
+        dispatch!(self, iterator => iterator.seek_for_prev(key); Mismatched)
+    }
+
+    fn next(&mut self) -> CostContext<()> {
+        dispatch!(self, iterator => iterator.next(); Mismatched)
+    }
+
+    fn prev(&mut self) -> CostContext<()> {
+        dispatch!(self, iterator => iterator.prev(); Mismatched)
+    }
+
+    fn value(&self) -> CostContext<Option<&[u8]>> {
+        dispatch!(self, iterator => iterator.value(); Mismatched)
+    }
+
+    fn key(&self) -> CostContext<Option<&[u8]>> {
+        dispatch!(self, iterator => iterator.key(); Mismatched)
+    }
+
+    fn valid(&self) -> CostContext<bool> {
+        dispatch!(self, iterator => iterator.valid(); Mismatched)
+    }
+}
+
+/// Batch of a [`StorageBackend`] immediate context.
+pub enum PrefixedBackendBatch<'db> {
+    /// RocksDB batch
+    RocksDb(PrefixedRocksDbBatch<'db>),
+    /// In-memory storage batch
+    Memory(PrefixedMemoryBatch),
+    /// Read-only RocksDB batch, which can be filled but never committed
+    ReadOnlyRocksDb(PrefixedMultiContextBatchPart),
+    /// Past version RocksDB batch, which can be filled but never committed
+    HistoricalRocksDb(PrefixedMultiContextBatchPart),
+    /// Batch of a mismatched context, which can be filled but never committed
+    Mismatched(PrefixedMultiContextBatchPart),
+}
+
+impl Batch for PrefixedBackendBatch<'_> {
+    fn put<K: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: &[u8],
+        children_sizes: ChildrenSizesWithIsSumTree,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> Result<(), grovedb_costs::error::Error> {
+        dispatch!(self, batch => batch.put(key, value, children_sizes, cost_info); Mismatched)
+    }
+
+    fn put_aux<K: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> Result<(), grovedb_costs::error::Error> {
+        dispatch!(self, batch => batch.put_aux(key, value, cost_info); Mismatched)
+    }
+
+    fn put_root<K: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> Result<(), grovedb_costs::error::Error> {
+        dispatch!(self, batch => batch.put_root(key, value, cost_info); Mismatched)
+    }
+
+    fn delete<K: AsRef<[u8]>>(&mut self, key: K, cost_info: Option<KeyValueStorageCost>) {
+        dispatch!(self, batch => batch.delete(key, cost_info); Mismatched)
+    }
+
+    fn delete_aux<K: AsRef<[u8]>>(&mut self, key: K, cost_info: Option<KeyValueStorageCost>) {
+        dispatch!(self, batch => batch.delete_aux(key, cost_info); Mismatched)
+    }
+
+    fn delete_root<K: AsRef<[u8]>>(&mut self, key: K, cost_info: Option<KeyValueStorageCost>) {
+        dispatch!(self, batch => batch.delete_root(key, cost_info); Mismatched)
+    }
+}
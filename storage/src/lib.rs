@@ -30,7 +30,13 @@
 
 #![deny(missing_docs)]
 
+#[cfg(all(feature = "rocksdb_storage", feature = "memory_storage"))]
+pub mod backend;
 pub mod error;
+#[cfg(feature = "memory_storage")]
+pub mod memory_storage;
+#[cfg(any(feature = "rocksdb_storage", feature = "memory_storage"))]
+mod prefixed;
 #[cfg(feature = "rocksdb_storage")]
 pub mod rocksdb_storage;
 mod storage;
@@ -0,0 +1,45 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! GroveDB storage layer kept entirely in memory.
+//!
+//! Mirrors the RocksDB backend record for record: the same subtree prefixes,
+//! the same four namespaces (data, aux, roots and meta) and the same cost
+//! accounting, but without touching the filesystem. Useful for tests, fuzzing
+//! and short-lived nodes that only need to verify data.
+mod storage;
+pub mod storage_context;
+#[cfg(test)]
+mod tests;
+
+pub use storage_context::{
+    PrefixedMemoryBatch, PrefixedMemoryImmediateStorageContext, PrefixedMemoryRawIterator,
+    PrefixedMemoryTransactionContext,
+};
+
+pub use self::storage::{MemoryStorage, MemoryTransaction, MemoryWriteBatch};
@@ -0,0 +1,628 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Implementation for a storage abstraction kept in memory.
+
+use std::{
+    collections::BTreeMap,
+    ops::Bound,
+    path::Path,
+    sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use grovedb_costs::{
+    cost_return_on_error, cost_return_on_error_no_add,
+    storage_cost::{
+        key_value_cost::KeyValueStorageCost, removal::StorageRemovedBytes::BasicStorageRemoval,
+    },
+    CostContext, CostResult, CostsExt, OperationCost,
+};
+use grovedb_path::SubtreePath;
+use integer_encoding::VarInt;
+
+use super::{PrefixedMemoryImmediateStorageContext, PrefixedMemoryTransactionContext};
+use crate::{
+    error::Error::{self, CostError},
+    prefixed,
+    storage::{AbstractBatchOperation, SubtreePrefix},
+    worst_case_costs::WorstKeyLength,
+    Storage, StorageBatch,
+};
+
+/// Namespace of a record, the in-memory counterpart of RocksDB column
+/// families.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Column {
+    /// Subtrees data
+    Data,
+    /// Auxiliary data
+    Aux,
+    /// Subtrees roots data
+    Roots,
+    /// Metadata
+    Meta,
+}
+
+/// One ordered map of records per [`Column`].
+#[derive(Debug, Clone)]
+pub(crate) struct Columns<V> {
+    data: BTreeMap<Vec<u8>, V>,
+    aux: BTreeMap<Vec<u8>, V>,
+    roots: BTreeMap<Vec<u8>, V>,
+    meta: BTreeMap<Vec<u8>, V>,
+}
+
+impl<V> Default for Columns<V> {
+    fn default() -> Self {
+        Columns {
+            data: BTreeMap::new(),
+            aux: BTreeMap::new(),
+            roots: BTreeMap::new(),
+            meta: BTreeMap::new(),
+        }
+    }
+}
+
+impl<V> Columns<V> {
+    fn column(&self, column: Column) -> &BTreeMap<Vec<u8>, V> {
+        match column {
+            Column::Data => &self.data,
+            Column::Aux => &self.aux,
+            Column::Roots => &self.roots,
+            Column::Meta => &self.meta,
+        }
+    }
+
+    fn column_mut(&mut self, column: Column) -> &mut BTreeMap<Vec<u8>, V> {
+        match column {
+            Column::Data => &mut self.data,
+            Column::Aux => &mut self.aux,
+            Column::Roots => &mut self.roots,
+            Column::Meta => &mut self.meta,
+        }
+    }
+
+    fn into_records(self) -> impl Iterator<Item = (Column, Vec<u8>, V)> {
+        let Columns {
+            data,
+            aux,
+            roots,
+            meta,
+        } = self;
+        let tag = |column| move |(key, value)| (column, key, value);
+        data.into_iter()
+            .map(tag(Column::Data))
+            .chain(aux.into_iter().map(tag(Column::Aux)))
+            .chain(roots.into_iter().map(tag(Column::Roots)))
+            .chain(meta.into_iter().map(tag(Column::Meta)))
+    }
+}
+
+/// Deferred write of a record, a `None` value stands for a deletion.
+pub(crate) type Write = (Column, Vec<u8>, Option<Vec<u8>>);
+
+/// Writes of a multi-context batch waiting to be committed, the in-memory
+/// counterpart of a RocksDB write batch.
+#[derive(Default)]
+pub struct MemoryWriteBatch {
+    writes: Vec<Write>,
+}
+
+/// Storage which keeps all records in memory.
+///
+/// Transactions buffer their writes and publish them on commit, as the
+/// RocksDB backend does, but conflicting commits of concurrent transactions
+/// are not detected: the last one wins. See the [`Storage`] trait
+/// documentation for the single-writer requirement.
+#[derive(Default)]
+pub struct MemoryStorage {
+    db: RwLock<Columns<Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    /// Create empty in-memory storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A helper method to build a prefix to storage keys or identify a subtree
+    /// in `subtrees` map by tree path. Yields the same prefixes as
+    /// [`RocksDbStorage::build_prefix`](crate::rocksdb_storage::RocksDbStorage::build_prefix).
+    pub fn build_prefix<B>(path: SubtreePath<B>) -> CostContext<SubtreePrefix>
+    where
+        B: AsRef<[u8]>,
+    {
+        prefixed::build_prefix(path)
+    }
+
+    /// Clears all data from every namespace.
+    pub fn wipe(&self) -> Result<(), Error> {
+        *self.write_db() = Columns::default();
+        Ok(())
+    }
+
+    fn read_db(&self) -> RwLockReadGuard<'_, Columns<Vec<u8>>> {
+        self.db.read().expect("memory storage lock is poisoned")
+    }
+
+    fn write_db(&self) -> RwLockWriteGuard<'_, Columns<Vec<u8>>> {
+        self.db.write().expect("memory storage lock is poisoned")
+    }
+
+    fn get_committed(&self, column: Column, key: &[u8]) -> Option<Vec<u8>> {
+        self.read_db().column(column).get(key).cloned()
+    }
+
+    fn apply(&self, writes: impl IntoIterator<Item = Write>) {
+        let mut db = self.write_db();
+        for (column, key, value) in writes {
+            match value {
+                Some(value) => db.column_mut(column).insert(key, value),
+                None => db.column_mut(column).remove(&key),
+            };
+        }
+    }
+
+    /// Returns the write batch, with costs and pending costs
+    /// Pending costs are costs that should only be applied after successful
+    /// write of the write batch.
+    pub fn build_write_batch(
+        &self,
+        storage_batch: StorageBatch,
+    ) -> CostResult<(MemoryWriteBatch, OperationCost), Error> {
+        let mut db_batch = MemoryWriteBatch::default();
+        self.continue_write_batch(&mut db_batch, storage_batch)
+            .map_ok(|operation_cost| (db_batch, operation_cost))
+    }
+
+    /// Continues the write batch, returning pending costs.
+    ///
+    /// Costs are the same as the RocksDB backend would report for the batch,
+    /// including the estimation of freed bytes from **committed** state for
+    /// deletions without `cost_info` (see
+    /// [`RocksDbStorage::continue_write_batch`](crate::rocksdb_storage::RocksDbStorage::continue_write_batch)).
+    pub fn continue_write_batch(
+        &self,
+        db_batch: &mut MemoryWriteBatch,
+        storage_batch: StorageBatch,
+    ) -> CostResult<OperationCost, Error> {
+        let mut cost = OperationCost::default();
+        let mut pending_costs = OperationCost::default();
+        let writes = &mut db_batch.writes;
+        writes.reserve(storage_batch.len());
+
+        for op in storage_batch.into_iter() {
+            match op {
+                AbstractBatchOperation::Put {
+                    key,
+                    value,
+                    children_sizes,
+                    cost_info,
+                } => {
+                    cost.seek_count += 1;
+                    cost_return_on_error_no_add!(
+                        cost,
+                        pending_costs
+                            .add_key_value_storage_costs(
+                                key.len() as u32,
+                                value.len() as u32,
+                                children_sizes,
+                                cost_info
+                            )
+                            .map_err(CostError)
+                    );
+                    writes.push((Column::Data, key, Some(value)));
+                }
+                AbstractBatchOperation::PutAux {
+                    key,
+                    value,
+                    cost_info,
+                } => {
+                    cost.seek_count += 1;
+                    cost_return_on_error_no_add!(
+                        cost,
+                        pending_costs
+                            .add_key_value_storage_costs(
+                                key.len() as u32,
+                                value.len() as u32,
+                                None,
+                                cost_info
+                            )
+                            .map_err(CostError)
+                    );
+                    writes.push((Column::Aux, key, Some(value)));
+                }
+                AbstractBatchOperation::PutRoot {
+                    key,
+                    value,
+                    cost_info,
+                } => {
+                    cost.seek_count += 1;
+                    // We only add costs for put root if they are set, otherwise it is free
+                    if cost_info.is_some() {
+                        cost_return_on_error_no_add!(
+                            cost,
+                            pending_costs
+                                .add_key_value_storage_costs(
+                                    key.len() as u32,
+                                    value.len() as u32,
+                                    None,
+                                    cost_info
+                                )
+                                .map_err(CostError)
+                        );
+                    }
+                    writes.push((Column::Roots, key, Some(value)));
+                }
+                AbstractBatchOperation::PutMeta {
+                    key,
+                    value,
+                    cost_info,
+                } => {
+                    cost.seek_count += 1;
+                    cost_return_on_error_no_add!(
+                        cost,
+                        pending_costs
+                            .add_key_value_storage_costs(
+                                key.len() as u32,
+                                value.len() as u32,
+                                None,
+                                cost_info
+                            )
+                            .map_err(CostError)
+                    );
+                    writes.push((Column::Meta, key, Some(value)));
+                }
+                AbstractBatchOperation::Delete { key, cost_info } => {
+                    self.add_deletion_costs(
+                        Column::Data,
+                        &key,
+                        cost_info,
+                        &mut cost,
+                        &mut pending_costs,
+                    );
+                    writes.push((Column::Data, key, None));
+                }
+                AbstractBatchOperation::DeleteAux { key, cost_info } => {
+                    self.add_deletion_costs(
+                        Column::Aux,
+                        &key,
+                        cost_info,
+                        &mut cost,
+                        &mut pending_costs,
+                    );
+                    writes.push((Column::Aux, key, None));
+                }
+                AbstractBatchOperation::DeleteRoot { key, cost_info } => {
+                    self.add_deletion_costs(
+                        Column::Roots,
+                        &key,
+                        cost_info,
+                        &mut cost,
+                        &mut pending_costs,
+                    );
+                    writes.push((Column::Roots, key, None));
+                }
+                AbstractBatchOperation::DeleteMeta { key, cost_info } => {
+                    self.add_deletion_costs(
+                        Column::Meta,
+                        &key,
+                        cost_info,
+                        &mut cost,
+                        &mut pending_costs,
+                    );
+                    writes.push((Column::Meta, key, None));
+                }
+            }
+        }
+        Ok(pending_costs).wrap_with_cost(cost)
+    }
+
+    /// Commits a write batch
+    pub fn commit_db_write_batch(
+        &self,
+        db_batch: MemoryWriteBatch,
+        pending_costs: OperationCost,
+        transaction: Option<&MemoryTransaction>,
+    ) -> CostResult<(), Error> {
+        match transaction {
+            None => self.apply(db_batch.writes),
+            Some(transaction) => transaction.apply(db_batch.writes),
+        }
+        Ok(()).wrap_with_cost(pending_costs)
+    }
+
+    fn add_deletion_costs(
+        &self,
+        column: Column,
+        key: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+        cost: &mut OperationCost,
+        pending_costs: &mut OperationCost,
+    ) {
+        if let Some(key_value_removed_bytes) = cost_info {
+            cost.seek_count += 1;
+            pending_costs.storage_cost.removed_bytes +=
+                key_value_removed_bytes.combined_removed_bytes();
+        } else {
+            cost.seek_count += 2;
+            let value_len = self
+                .get_committed(column, key)
+                .map(|x| x.len() as u32)
+                .unwrap_or(0);
+            cost.storage_loaded_bytes += value_len as u64;
+
+            let key_len = key.len() as u32;
+            pending_costs.storage_cost.removed_bytes += BasicStorageRemoval(
+                key_len
+                    + value_len
+                    + key_len.required_space() as u32
+                    + value_len.required_space() as u32,
+            );
+        }
+    }
+}
+
+/// Transaction over [`MemoryStorage`].
+///
+/// Writes are buffered until [`commit`](Self::commit) publishes them, reads
+/// see them on top of the committed records.
+pub struct MemoryTransaction<'db> {
+    storage: &'db MemoryStorage,
+    pending: RwLock<Columns<Option<Vec<u8>>>>,
+    savepoints: Mutex<Vec<Columns<Option<Vec<u8>>>>>,
+}
+
+impl<'db> MemoryTransaction<'db> {
+    fn new(storage: &'db MemoryStorage) -> Self {
+        MemoryTransaction {
+            storage,
+            pending: RwLock::new(Columns::default()),
+            savepoints: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Publishes the transaction writes to the storage.
+    pub fn commit(self) -> Result<(), Error> {
+        let pending = self
+            .pending
+            .into_inner()
+            .expect("memory storage lock is poisoned");
+        self.storage.apply(pending.into_records());
+        Ok(())
+    }
+
+    /// Discards the transaction writes.
+    pub fn rollback(&self) -> Result<(), Error> {
+        *self.write_pending() = Columns::default();
+        Ok(())
+    }
+
+    /// Remembers the current writes so that later ones can be discarded
+    /// with [`rollback_to_savepoint`](Self::rollback_to_savepoint).
+    pub fn set_savepoint(&self) {
+        let pending = self.read_pending().clone();
+        self.savepoints
+            .lock()
+            .expect("memory storage lock is poisoned")
+            .push(pending);
+    }
+
+    /// Discards the writes made since the last savepoint and removes it.
+    pub fn rollback_to_savepoint(&self) -> Result<(), Error> {
+        let savepoint = self
+            .savepoints
+            .lock()
+            .expect("memory storage lock is poisoned")
+            .pop()
+            .ok_or_else(|| Error::StorageError("no savepoint to roll back to".to_owned()))?;
+        *self.write_pending() = savepoint;
+        Ok(())
+    }
+
+    fn read_pending(&self) -> RwLockReadGuard<'_, Columns<Option<Vec<u8>>>> {
+        self.pending
+            .read()
+            .expect("memory storage lock is poisoned")
+    }
+
+    fn write_pending(&self) -> RwLockWriteGuard<'_, Columns<Option<Vec<u8>>>> {
+        self.pending
+            .write()
+            .expect("memory storage lock is poisoned")
+    }
+
+    pub(crate) fn get(&self, column: Column, key: &[u8]) -> Option<Vec<u8>> {
+        match self.read_pending().column(column).get(key) {
+            Some(value) => value.clone(),
+            None => self.storage.get_committed(column, key),
+        }
+    }
+
+    pub(crate) fn put(&self, column: Column, key: Vec<u8>, value: Vec<u8>) {
+        self.write_pending()
+            .column_mut(column)
+            .insert(key, Some(value));
+    }
+
+    pub(crate) fn delete(&self, column: Column, key: Vec<u8>) {
+        self.write_pending().column_mut(column).insert(key, None);
+    }
+
+    pub(crate) fn apply(&self, writes: impl IntoIterator<Item = Write>) {
+        let mut pending = self.write_pending();
+        for (column, key, value) in writes {
+            pending.column_mut(column).insert(key, value);
+        }
+    }
+
+    /// Returns the first record of `column` with a key after `from`, as seen
+    /// by the transaction.
+    pub(crate) fn seek_forward(
+        &self,
+        column: Column,
+        from: Bound<&[u8]>,
+    ) -> Option<(Vec<u8>, Vec<u8>)> {
+        let pending = self.read_pending();
+        let db = self.storage.read_db();
+        let (pending, committed) = (pending.column(column), db.column(column));
+        let range = (from, Bound::Unbounded);
+
+        let committed_next = committed
+            .range::<[u8], _>(range)
+            .find(|(key, _)| !pending.contains_key(key.as_slice()));
+        let pending_next = pending
+            .range::<[u8], _>(range)
+            .find_map(|(key, value)| value.as_ref().map(|value| (key, value)));
+
+        match (committed_next, pending_next) {
+            (Some(committed), Some(pending)) => Some(committed.min(pending)),
+            (committed, pending) => committed.or(pending),
+        }
+        .map(|(key, value)| (key.clone(), value.clone()))
+    }
+
+    /// Returns the last record of `column` with a key before `to`, as seen by
+    /// the transaction.
+    pub(crate) fn seek_backward(
+        &self,
+        column: Column,
+        to: Bound<&[u8]>,
+    ) -> Option<(Vec<u8>, Vec<u8>)> {
+        let pending = self.read_pending();
+        let db = self.storage.read_db();
+        let (pending, committed) = (pending.column(column), db.column(column));
+        let range = (Bound::Unbounded, to);
+
+        let committed_prev = committed
+            .range::<[u8], _>(range)
+            .rev()
+            .find(|(key, _)| !pending.contains_key(key.as_slice()));
+        let pending_prev = pending
+            .range::<[u8], _>(range)
+            .rev()
+            .find_map(|(key, value)| value.as_ref().map(|value| (key, value)));
+
+        match (committed_prev, pending_prev) {
+            (Some(committed), Some(pending)) => Some(committed.max(pending)),
+            (committed, pending) => committed.or(pending),
+        }
+        .map(|(key, value)| (key.clone(), value.clone()))
+    }
+}
+
+impl<'db> Storage<'db> for MemoryStorage {
+    type BatchTransactionalStorageContext = PrefixedMemoryTransactionContext<'db>;
+    type ImmediateStorageContext = PrefixedMemoryImmediateStorageContext<'db>;
+    type Transaction = MemoryTransaction<'db>;
+
+    fn start_transaction(&'db self) -> Self::Transaction {
+        MemoryTransaction::new(self)
+    }
+
+    fn commit_transaction(&self, transaction: Self::Transaction) -> CostResult<(), Error> {
+        // All transaction costs were provided on method calls.
+        transaction.commit().wrap_with_cost(Default::default())
+    }
+
+    fn rollback_transaction(&self, transaction: &Self::Transaction) -> Result<(), Error> {
+        transaction.rollback()
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        // Nothing is ever persisted
+        Ok(())
+    }
+
+    fn get_transactional_storage_context<'b, B>(
+        &'db self,
+        path: SubtreePath<'b, B>,
+        batch: Option<&'db StorageBatch>,
+        transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::BatchTransactionalStorageContext>
+    where
+        B: AsRef<[u8]> + 'b,
+    {
+        Self::build_prefix(path)
+            .map(|prefix| PrefixedMemoryTransactionContext::new(transaction, prefix, batch))
+    }
+
+    fn get_transactional_storage_context_by_subtree_prefix(
+        &'db self,
+        prefix: SubtreePrefix,
+        batch: Option<&'db StorageBatch>,
+        transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::BatchTransactionalStorageContext> {
+        PrefixedMemoryTransactionContext::new(transaction, prefix, batch)
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn get_immediate_storage_context<'b, B>(
+        &'db self,
+        path: SubtreePath<'b, B>,
+        transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::ImmediateStorageContext>
+    where
+        B: AsRef<[u8]> + 'b,
+    {
+        Self::build_prefix(path)
+            .map(|prefix| PrefixedMemoryImmediateStorageContext::new(transaction, prefix))
+    }
+
+    fn get_immediate_storage_context_by_subtree_prefix(
+        &'db self,
+        prefix: SubtreePrefix,
+        transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::ImmediateStorageContext> {
+        PrefixedMemoryImmediateStorageContext::new(transaction, prefix)
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn commit_multi_context_batch(
+        &self,
+        batch: StorageBatch,
+        transaction: Option<&'db Self::Transaction>,
+    ) -> CostResult<(), Error> {
+        let mut cost = OperationCost::default();
+        let (db_batch, pending_costs) =
+            cost_return_on_error!(&mut cost, self.build_write_batch(batch));
+
+        self.commit_db_write_batch(db_batch, pending_costs, transaction)
+            .add_cost(cost)
+    }
+
+    fn get_storage_context_cost<L: WorstKeyLength>(path: &[L]) -> OperationCost {
+        prefixed::storage_context_cost(path)
+    }
+
+    fn create_checkpoint<P: AsRef<Path>>(&self, _path: P) -> Result<(), Error> {
+        Err(Error::StorageError(
+            "checkpoints are not supported by in-memory storage".to_string(),
+        ))
+    }
+}
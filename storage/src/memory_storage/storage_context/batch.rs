@@ -0,0 +1,134 @@
+//! Prefixed storage batch implementation for the in-memory backend.
+
+use grovedb_costs::{
+    storage_cost::key_value_cost::KeyValueStorageCost, ChildrenSizesWithIsSumTree, OperationCost,
+};
+use integer_encoding::VarInt;
+
+use crate::{
+    memory_storage::storage::{Column, Write},
+    prefixed::make_prefixed_key,
+    storage::SubtreePrefix,
+    Batch,
+};
+
+/// Batch of writes to be applied to an in-memory transaction at once.
+/// Handles prefixes and accumulates costs the same way
+/// [`PrefixedRocksDbBatch`](crate::rocksdb_storage::PrefixedRocksDbBatch)
+/// does, so both backends report equal costs.
+pub struct PrefixedMemoryBatch {
+    pub(crate) prefix: SubtreePrefix,
+    pub(crate) writes: Vec<Write>,
+    pub(crate) cost_acc: OperationCost,
+}
+
+impl PrefixedMemoryBatch {
+    fn add_deletion(
+        &mut self,
+        column: Column,
+        key: Vec<u8>,
+        cost_info: Option<KeyValueStorageCost>,
+    ) {
+        self.cost_acc.seek_count += 1;
+
+        if let Some(removed_bytes) = cost_info {
+            self.cost_acc.storage_cost.removed_bytes += removed_bytes.combined_removed_bytes();
+        }
+
+        self.writes.push((column, key, None));
+    }
+}
+
+impl Batch for PrefixedMemoryBatch {
+    fn put<K: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: &[u8],
+        children_sizes: ChildrenSizesWithIsSumTree,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> Result<(), grovedb_costs::error::Error> {
+        let prefixed_key = make_prefixed_key(&self.prefix, key);
+
+        // Update the key_storage_cost based on the prefixed key
+        let updated_cost_info = cost_info.map(|mut key_value_storage_cost| {
+            if key_value_storage_cost.new_node {
+                // key is new, storage_cost needs to be created for it
+                key_value_storage_cost.key_storage_cost.added_bytes +=
+                    (prefixed_key.len() + prefixed_key.len().required_space()) as u32;
+            }
+            key_value_storage_cost
+        });
+
+        self.cost_acc.seek_count += 1;
+        self.cost_acc.add_key_value_storage_costs(
+            prefixed_key.len() as u32,
+            value.len() as u32,
+            children_sizes,
+            updated_cost_info,
+        )?;
+
+        self.writes
+            .push((Column::Data, prefixed_key, Some(value.to_vec())));
+        Ok(())
+    }
+
+    fn put_aux<K: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> Result<(), grovedb_costs::error::Error> {
+        let prefixed_key = make_prefixed_key(&self.prefix, key);
+
+        self.cost_acc.seek_count += 1;
+        self.cost_acc.add_key_value_storage_costs(
+            prefixed_key.len() as u32,
+            value.len() as u32,
+            None,
+            cost_info,
+        )?;
+
+        self.writes
+            .push((Column::Aux, prefixed_key, Some(value.to_vec())));
+        Ok(())
+    }
+
+    fn put_root<K: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> Result<(), grovedb_costs::error::Error> {
+        let prefixed_key = make_prefixed_key(&self.prefix, key);
+
+        self.cost_acc.seek_count += 1;
+        // put root only pays if cost info is set
+        if cost_info.is_some() {
+            self.cost_acc.add_key_value_storage_costs(
+                prefixed_key.len() as u32,
+                value.len() as u32,
+                None,
+                cost_info,
+            )?;
+        }
+
+        self.writes
+            .push((Column::Roots, prefixed_key, Some(value.to_vec())));
+        Ok(())
+    }
+
+    fn delete<K: AsRef<[u8]>>(&mut self, key: K, cost_info: Option<KeyValueStorageCost>) {
+        let prefixed_key = make_prefixed_key(&self.prefix, key);
+        self.add_deletion(Column::Data, prefixed_key, cost_info);
+    }
+
+    fn delete_aux<K: AsRef<[u8]>>(&mut self, key: K, cost_info: Option<KeyValueStorageCost>) {
+        let prefixed_key = make_prefixed_key(&self.prefix, key);
+        self.add_deletion(Column::Aux, prefixed_key, cost_info);
+    }
+
+    fn delete_root<K: AsRef<[u8]>>(&mut self, key: K, cost_info: Option<KeyValueStorageCost>) {
+        let prefixed_key = make_prefixed_key(&self.prefix, key);
+        self.add_deletion(Column::Roots, prefixed_key, cost_info);
+    }
+}
@@ -0,0 +1,195 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Storage context implementation with a transaction.
+
+use grovedb_costs::{
+    storage_cost::key_value_cost::KeyValueStorageCost, ChildrenSizesWithIsSumTree, CostResult,
+    CostsExt,
+};
+
+use super::{PrefixedMemoryBatch, PrefixedMemoryRawIterator};
+use crate::{
+    error::Error,
+    memory_storage::{storage::Column, MemoryTransaction},
+    prefixed::make_prefixed_key,
+    storage::SubtreePrefix,
+    StorageContext,
+};
+
+/// Storage context with a prefix applied to be used in a subtree, writing
+/// straight to the transaction.
+pub struct PrefixedMemoryImmediateStorageContext<'db> {
+    transaction: &'db MemoryTransaction<'db>,
+    prefix: SubtreePrefix,
+}
+
+impl<'db> PrefixedMemoryImmediateStorageContext<'db> {
+    /// Create a new prefixed immediate context instance
+    pub fn new(transaction: &'db MemoryTransaction<'db>, prefix: SubtreePrefix) -> Self {
+        PrefixedMemoryImmediateStorageContext {
+            transaction,
+            prefix,
+        }
+    }
+
+    fn put_cf<K: AsRef<[u8]>>(
+        &self,
+        column: Column,
+        key: K,
+        value: &[u8],
+    ) -> CostResult<(), Error> {
+        self.transaction
+            .put(column, make_prefixed_key(&self.prefix, key), value.to_vec());
+        Ok(()).wrap_with_cost(Default::default())
+    }
+
+    fn delete_cf<K: AsRef<[u8]>>(&self, column: Column, key: K) -> CostResult<(), Error> {
+        self.transaction
+            .delete(column, make_prefixed_key(&self.prefix, key));
+        Ok(()).wrap_with_cost(Default::default())
+    }
+
+    fn get_cf<K: AsRef<[u8]>>(&self, column: Column, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        Ok(self
+            .transaction
+            .get(column, &make_prefixed_key(&self.prefix, key)))
+        .wrap_with_cost(Default::default())
+    }
+}
+
+impl<'db> StorageContext<'db> for PrefixedMemoryImmediateStorageContext<'db> {
+    type Batch = PrefixedMemoryBatch;
+    type RawIterator = PrefixedMemoryRawIterator<'db>;
+
+    fn put<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        _children_sizes: ChildrenSizesWithIsSumTree,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.put_cf(Column::Data, key, value)
+    }
+
+    fn put_aux<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.put_cf(Column::Aux, key, value)
+    }
+
+    fn put_root<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.put_cf(Column::Roots, key, value)
+    }
+
+    fn put_meta<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.put_cf(Column::Meta, key, value)
+    }
+
+    fn delete<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.delete_cf(Column::Data, key)
+    }
+
+    fn delete_aux<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.delete_cf(Column::Aux, key)
+    }
+
+    fn delete_root<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.delete_cf(Column::Roots, key)
+    }
+
+    fn delete_meta<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.delete_cf(Column::Meta, key)
+    }
+
+    fn get<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_cf(Column::Data, key)
+    }
+
+    fn get_aux<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_cf(Column::Aux, key)
+    }
+
+    fn get_root<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_cf(Column::Roots, key)
+    }
+
+    fn get_meta<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_cf(Column::Meta, key)
+    }
+
+    fn new_batch(&self) -> Self::Batch {
+        PrefixedMemoryBatch {
+            prefix: self.prefix,
+            writes: Vec::new(),
+            cost_acc: Default::default(),
+        }
+    }
+
+    fn commit_batch(&self, batch: Self::Batch) -> CostResult<(), Error> {
+        self.transaction.apply(batch.writes);
+        Ok(()).wrap_with_cost(batch.cost_acc)
+    }
+
+    fn raw_iter(&self) -> Self::RawIterator {
+        PrefixedMemoryRawIterator::new(self.transaction, Column::Data, self.prefix)
+    }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        PrefixedMemoryRawIterator::new(self.transaction, Column::Meta, self.prefix)
+    }
+}
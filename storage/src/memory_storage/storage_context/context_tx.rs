@@ -0,0 +1,257 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Storage context batch implementation with a transaction.
+
+use grovedb_costs::{
+    cost_return_on_error, storage_cost::key_value_cost::KeyValueStorageCost,
+    ChildrenSizesWithIsSumTree, CostResult, CostsExt, OperationCost,
+};
+
+use super::PrefixedMemoryRawIterator;
+use crate::{
+    error::Error,
+    memory_storage::{storage::Column, MemoryTransaction},
+    prefixed::{make_prefixed_key, PrefixedMultiContextBatchPart},
+    storage::SubtreePrefix,
+    RawIterator, StorageBatch, StorageContext,
+};
+
+/// Storage context with a prefix applied to be used in a subtree to be used in
+/// transaction.
+pub struct PrefixedMemoryTransactionContext<'db> {
+    transaction: &'db MemoryTransaction<'db>,
+    prefix: SubtreePrefix,
+    batch: Option<&'db StorageBatch>,
+}
+
+impl<'db> PrefixedMemoryTransactionContext<'db> {
+    /// Create a new prefixed transaction context instance
+    pub fn new(
+        transaction: &'db MemoryTransaction<'db>,
+        prefix: SubtreePrefix,
+        batch: Option<&'db StorageBatch>,
+    ) -> Self {
+        PrefixedMemoryTransactionContext {
+            transaction,
+            prefix,
+            batch,
+        }
+    }
+
+    /// Clears all data in the data namespace for this prefix. Auxiliary, roots,
+    /// and meta namespaces are **not** affected.
+    pub fn clear(&mut self) -> CostResult<(), Error> {
+        let mut cost = OperationCost::default();
+
+        let mut iter = self.raw_iter();
+        iter.seek_to_first().unwrap_add_cost(&mut cost);
+
+        while iter.valid().unwrap_add_cost(&mut cost) {
+            if let Some(key) = iter.key().unwrap_add_cost(&mut cost) {
+                cost_return_on_error!(&mut cost, self.delete(key, None));
+            }
+            iter.next().unwrap_add_cost(&mut cost);
+        }
+        Ok(()).wrap_with_cost(cost)
+    }
+
+    /// Returns the batch writes are deferred to, failing as the RocksDB
+    /// context does when there is none.
+    fn batch(&self, operation: &str) -> Result<&'db StorageBatch, Error> {
+        self.batch.ok_or_else(|| {
+            Error::StorageError(format!(
+                "attempted {operation} operation on transactional context without a batch"
+            ))
+        })
+    }
+
+    fn get_cf(&self, column: Column, key: &[u8]) -> CostResult<Option<Vec<u8>>, Error> {
+        let value = self
+            .transaction
+            .get(column, &make_prefixed_key(&self.prefix, key));
+        let cost = OperationCost {
+            seek_count: 1,
+            storage_loaded_bytes: value.as_ref().map(|x| x.len() as u64).unwrap_or(0),
+            ..Default::default()
+        };
+        Ok(value).wrap_with_cost(cost)
+    }
+}
+
+impl<'db> StorageContext<'db> for PrefixedMemoryTransactionContext<'db> {
+    type Batch = PrefixedMultiContextBatchPart;
+    type RawIterator = PrefixedMemoryRawIterator<'db>;
+
+    fn put<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        children_sizes: ChildrenSizesWithIsSumTree,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.batch("put")
+            .map(|batch| {
+                batch.put(
+                    make_prefixed_key(&self.prefix, key),
+                    value.to_vec(),
+                    children_sizes,
+                    cost_info,
+                )
+            })
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn put_aux<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.batch("put_aux")
+            .map(|batch| {
+                batch.put_aux(
+                    make_prefixed_key(&self.prefix, key),
+                    value.to_vec(),
+                    cost_info,
+                )
+            })
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn put_root<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.batch("put_root")
+            .map(|batch| {
+                batch.put_root(
+                    make_prefixed_key(&self.prefix, key),
+                    value.to_vec(),
+                    cost_info,
+                )
+            })
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn put_meta<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.batch("put_meta")
+            .map(|batch| {
+                batch.put_meta(
+                    make_prefixed_key(&self.prefix, key),
+                    value.to_vec(),
+                    cost_info,
+                )
+            })
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn delete<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.batch("delete")
+            .map(|batch| batch.delete(make_prefixed_key(&self.prefix, key), cost_info))
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn delete_aux<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.batch("delete_aux")
+            .map(|batch| batch.delete_aux(make_prefixed_key(&self.prefix, key), cost_info))
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn delete_root<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.batch("delete_root")
+            .map(|batch| batch.delete_root(make_prefixed_key(&self.prefix, key), cost_info))
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn delete_meta<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        self.batch("delete_meta")
+            .map(|batch| batch.delete_meta(make_prefixed_key(&self.prefix, key), cost_info))
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn get<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_cf(Column::Data, key.as_ref())
+    }
+
+    fn get_aux<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_cf(Column::Aux, key.as_ref())
+    }
+
+    fn get_root<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_cf(Column::Roots, key.as_ref())
+    }
+
+    fn get_meta<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_cf(Column::Meta, key.as_ref())
+    }
+
+    fn new_batch(&self) -> Self::Batch {
+        PrefixedMultiContextBatchPart {
+            prefix: self.prefix,
+            batch: StorageBatch::new(),
+        }
+    }
+
+    fn commit_batch(&self, batch: Self::Batch) -> CostResult<(), Error> {
+        self.batch("commit_batch")
+            .map(|existing_batch| existing_batch.merge(batch.batch))
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn raw_iter(&self) -> Self::RawIterator {
+        PrefixedMemoryRawIterator::new(self.transaction, Column::Data, self.prefix)
+    }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        PrefixedMemoryRawIterator::new(self.transaction, Column::Meta, self.prefix)
+    }
+}
@@ -0,0 +1,182 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Prefixed storage_cost raw iterator implementation for the in-memory
+//! backend.
+
+use std::ops::Bound;
+
+use grovedb_costs::{CostContext, CostsExt, OperationCost};
+
+use crate::{
+    memory_storage::{storage::Column, MemoryTransaction},
+    prefixed::make_prefixed_key,
+    storage::SubtreePrefix,
+    RawIterator,
+};
+
+/// 256 bytes for the key and 32 bytes for the prefix
+const MAX_PREFIXED_KEY_LENGTH: u64 = 256 + 32;
+
+/// Raw iterator over prefixed storage_cost.
+///
+/// Unlike a RocksDB iterator it doesn't pin a snapshot: every move looks the
+/// neighbouring record up in the transaction, so the iterator only holds a
+/// copy of the record it points at. Costs are the same as for
+/// [`PrefixedRocksDbRawIterator`](crate::rocksdb_storage::PrefixedRocksDbRawIterator).
+pub struct PrefixedMemoryRawIterator<'db> {
+    prefix: SubtreePrefix,
+    column: Column,
+    transaction: &'db MemoryTransaction<'db>,
+    current: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+impl<'db> PrefixedMemoryRawIterator<'db> {
+    pub(crate) fn new(
+        transaction: &'db MemoryTransaction<'db>,
+        column: Column,
+        prefix: SubtreePrefix,
+    ) -> Self {
+        PrefixedMemoryRawIterator {
+            prefix,
+            column,
+            transaction,
+            current: None,
+        }
+    }
+
+    fn seek_forward(&mut self, from: Bound<&[u8]>) -> CostContext<()> {
+        self.current = self.transaction.seek_forward(self.column, from);
+        ().wrap_with_cost(OperationCost::with_seek_count(1))
+    }
+
+    fn seek_backward(&mut self, to: Bound<&[u8]>) -> CostContext<()> {
+        self.current = self.transaction.seek_backward(self.column, to);
+        ().wrap_with_cost(OperationCost::with_seek_count(1))
+    }
+}
+
+impl RawIterator for PrefixedMemoryRawIterator<'_> {
+    fn seek_to_first(&mut self) -> CostContext<()> {
+        let prefix = self.prefix;
+        self.seek_forward(Bound::Included(prefix.as_slice()))
+    }
+
+    /// Seeks to the last key with this prefix the way the RocksDB iterator
+    /// does, by incrementing the prefix and seeking backwards from it.
+    fn seek_to_last(&mut self) -> CostContext<()> {
+        let mut prefix_vec = self.prefix.to_vec();
+        for i in (0..prefix_vec.len()).rev() {
+            prefix_vec[i] = prefix_vec[i].wrapping_add(1);
+            if prefix_vec[i] != 0 {
+                // if it is == 0 then we need to go to next bit
+                break;
+            }
+        }
+        self.seek_backward(Bound::Included(prefix_vec.as_slice()))
+    }
+
+    fn seek<K: AsRef<[u8]>>(&mut self, key: K) -> CostContext<()> {
+        let prefixed_key = make_prefixed_key(&self.prefix, key);
+        self.seek_forward(Bound::Included(prefixed_key.as_slice()))
+    }
+
+    fn seek_for_prev<K: AsRef<[u8]>>(&mut self, key: K) -> CostContext<()> {
+        let prefixed_key = make_prefixed_key(&self.prefix, key);
+        self.seek_backward(Bound::Included(prefixed_key.as_slice()))
+    }
+
+    fn next(&mut self) -> CostContext<()> {
+        match self.current.take() {
+            Some((key, _)) => self.seek_forward(Bound::Excluded(key.as_slice())),
+            None => ().wrap_with_cost(OperationCost::with_seek_count(1)),
+        }
+    }
+
+    fn prev(&mut self) -> CostContext<()> {
+        match self.current.take() {
+            Some((key, _)) => self.seek_backward(Bound::Excluded(key.as_slice())),
+            None => ().wrap_with_cost(OperationCost::with_seek_count(1)),
+        }
+    }
+
+    fn value(&self) -> CostContext<Option<&[u8]>> {
+        let mut cost = OperationCost::default();
+
+        let value = if self.valid().unwrap_add_cost(&mut cost) {
+            self.current.as_ref().map(|(_, value)| {
+                cost.storage_loaded_bytes += value.len() as u64;
+                value.as_slice()
+            })
+        } else {
+            None
+        };
+
+        value.wrap_with_cost(cost)
+    }
+
+    fn key(&self) -> CostContext<Option<&[u8]>> {
+        let mut cost = OperationCost::default();
+
+        let value = match &self.current {
+            Some((k, _)) if k.starts_with(&self.prefix) => {
+                // Even if we truncate prefix, loaded cost should be maximum for the whole
+                // function
+                cost.storage_loaded_bytes += k.len() as u64;
+                Some(k.split_at(self.prefix.len()).1)
+            }
+            _ => {
+                // Outside of the prefix, or past the last record, a fixed cost is charged
+                // so that iteration costs don't depend on neighbouring subtrees, see
+                // the RocksDB raw iterator.
+                cost.storage_loaded_bytes += MAX_PREFIXED_KEY_LENGTH;
+                None
+            }
+        };
+
+        value.wrap_with_cost(cost)
+    }
+
+    fn valid(&self) -> CostContext<bool> {
+        let mut cost = OperationCost::default();
+
+        let valid = match &self.current {
+            Some((k, _)) if k.starts_with(&self.prefix) => {
+                cost.storage_loaded_bytes += k.len() as u64;
+                true
+            }
+            _ => {
+                // Same fixed boundary cost as for `key`
+                cost.storage_loaded_bytes += MAX_PREFIXED_KEY_LENGTH;
+                false
+            }
+        };
+
+        valid.wrap_with_cost(cost)
+    }
+}
@@ -0,0 +1,613 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Tests
+
+use super::MemoryStorage;
+
+mod immediate_storage {
+    use super::*;
+    use crate::{Batch, RawIterator, Storage, StorageContext};
+
+    #[test]
+    fn test_namespaces_are_separate() {
+        let storage = MemoryStorage::new();
+        let tx = storage.start_transaction();
+        let context_ayya = storage
+            .get_immediate_storage_context([b"ayya"].as_ref().into(), &tx)
+            .unwrap();
+        let context_ayyb = storage
+            .get_immediate_storage_context([b"ayyb"].as_ref().into(), &tx)
+            .unwrap();
+
+        context_ayya
+            .put(b"key", b"data", None, None)
+            .unwrap()
+            .expect("cannot insert data");
+        context_ayya
+            .put_aux(b"key", b"aux", None)
+            .unwrap()
+            .expect("cannot insert into aux");
+        context_ayya
+            .put_root(b"key", b"root", None)
+            .unwrap()
+            .expect("cannot insert into roots");
+        context_ayya
+            .put_meta(b"key", b"meta", None)
+            .unwrap()
+            .expect("cannot insert into meta");
+
+        assert_eq!(
+            context_ayya.get(b"key").unwrap().unwrap(),
+            Some(b"data".to_vec())
+        );
+        assert_eq!(
+            context_ayya.get_aux(b"key").unwrap().unwrap(),
+            Some(b"aux".to_vec())
+        );
+        assert_eq!(
+            context_ayya.get_root(b"key").unwrap().unwrap(),
+            Some(b"root".to_vec())
+        );
+        assert_eq!(
+            context_ayya.get_meta(b"key").unwrap().unwrap(),
+            Some(b"meta".to_vec())
+        );
+        assert!(context_ayyb.get(b"key").unwrap().unwrap().is_none());
+
+        context_ayya
+            .delete_aux(b"key", None)
+            .unwrap()
+            .expect("cannot delete from aux");
+        assert!(context_ayya.get_aux(b"key").unwrap().unwrap().is_none());
+        assert_eq!(
+            context_ayya.get(b"key").unwrap().unwrap(),
+            Some(b"data".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_transaction_isolation() {
+        let storage = MemoryStorage::new();
+        let tx = storage.start_transaction();
+        let other_tx = storage.start_transaction();
+        let context = storage
+            .get_immediate_storage_context([b"ayya"].as_ref().into(), &tx)
+            .unwrap();
+        let other_context = storage
+            .get_immediate_storage_context([b"ayya"].as_ref().into(), &other_tx)
+            .unwrap();
+
+        context
+            .put_aux(b"key1", b"value1", None)
+            .unwrap()
+            .expect("cannot insert into aux");
+        assert!(other_context.get_aux(b"key1").unwrap().unwrap().is_none());
+
+        storage
+            .commit_transaction(tx)
+            .unwrap()
+            .expect("cannot commit transaction");
+        assert_eq!(
+            other_context.get_aux(b"key1").unwrap().unwrap(),
+            Some(b"value1".to_vec())
+        );
+
+        other_context
+            .delete_aux(b"key1", None)
+            .unwrap()
+            .expect("cannot delete from aux");
+        storage
+            .rollback_transaction(&other_tx)
+            .expect("cannot rollback transaction");
+        assert_eq!(
+            other_context.get_aux(b"key1").unwrap().unwrap(),
+            Some(b"value1".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_rollback_to_savepoint() {
+        let storage = MemoryStorage::new();
+        let tx = storage.start_transaction();
+        let context = storage
+            .get_immediate_storage_context([b"ayya"].as_ref().into(), &tx)
+            .unwrap();
+
+        context
+            .put_aux(b"key1", b"value1", None)
+            .unwrap()
+            .expect("cannot insert into aux");
+        tx.set_savepoint();
+        context
+            .put_aux(b"key1", b"value2", None)
+            .unwrap()
+            .expect("cannot insert into aux");
+        context
+            .put_aux(b"key2", b"value2", None)
+            .unwrap()
+            .expect("cannot insert into aux");
+
+        tx.rollback_to_savepoint()
+            .expect("cannot roll back to the savepoint");
+        assert_eq!(
+            context.get_aux(b"key1").unwrap().unwrap(),
+            Some(b"value1".to_vec())
+        );
+        assert!(context.get_aux(b"key2").unwrap().unwrap().is_none());
+        assert!(tx.rollback_to_savepoint().is_err());
+    }
+
+    #[test]
+    fn test_batch() {
+        let storage = MemoryStorage::new();
+        let tx = storage.start_transaction();
+        let context = storage
+            .get_immediate_storage_context([b"ayya"].as_ref().into(), &tx)
+            .unwrap();
+
+        context
+            .put(b"key1", b"value1", None, None)
+            .unwrap()
+            .expect("cannot insert data");
+
+        let mut batch = context.new_batch();
+        batch
+            .put(b"key2", b"value2", None, None)
+            .expect("cannot add to batch");
+        batch
+            .put_aux(b"key3", b"value3", None)
+            .expect("cannot add to batch");
+        batch.delete(b"key1", None);
+
+        assert_eq!(
+            context.get(b"key1").unwrap().unwrap(),
+            Some(b"value1".to_vec())
+        );
+        assert!(context.get(b"key2").unwrap().unwrap().is_none());
+
+        let cost = context.commit_batch(batch).cost;
+        assert_eq!(cost.seek_count, 3);
+
+        assert!(context.get(b"key1").unwrap().unwrap().is_none());
+        assert_eq!(
+            context.get(b"key2").unwrap().unwrap(),
+            Some(b"value2".to_vec())
+        );
+        assert_eq!(
+            context.get_aux(b"key3").unwrap().unwrap(),
+            Some(b"value3".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_raw_iterator() {
+        let storage = MemoryStorage::new();
+        let tx = storage.start_transaction();
+        let context = storage
+            .get_immediate_storage_context([b"someprefix"].as_ref().into(), &tx)
+            .unwrap();
+
+        for (key, value) in [
+            (b"key1", b"value1"),
+            (b"key0", b"value0"),
+            (b"key3", b"value3"),
+            (b"key2", b"value2"),
+        ] {
+            context
+                .put(key, value, None, None)
+                .unwrap()
+                .expect("expected successful insertion");
+        }
+
+        // Neighbouring subtrees must not leak into the iteration
+        for path in [b"anothersomeprefix".as_ref(), b"zanothersomeprefix"] {
+            let neighbour = storage
+                .get_immediate_storage_context([path].as_ref().into(), &tx)
+                .unwrap();
+            neighbour
+                .put(b"key1", b"value1", None, None)
+                .unwrap()
+                .expect("expected successful insertion");
+            neighbour
+                .put(b"key5", b"value5", None, None)
+                .unwrap()
+                .expect("expected successful insertion");
+        }
+
+        storage
+            .commit_transaction(tx)
+            .unwrap()
+            .expect("cannot commit transaction");
+
+        let tx = storage.start_transaction();
+        let context = storage
+            .get_immediate_storage_context([b"someprefix"].as_ref().into(), &tx)
+            .unwrap();
+
+        // Uncommitted changes are merged with committed data
+        context
+            .delete(b"key1", None)
+            .unwrap()
+            .expect("unable to delete an item");
+        context
+            .put(b"key4", b"value4", None, None)
+            .unwrap()
+            .expect("unable to insert an item");
+
+        let expected: Vec<(Vec<u8>, Vec<u8>)> = [
+            (b"key0", b"value0"),
+            (b"key2", b"value2"),
+            (b"key3", b"value3"),
+            (b"key4", b"value4"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_vec(), value.to_vec()))
+        .collect();
+
+        let mut iter = context.raw_iter();
+        let mut forward = Vec::new();
+        iter.seek_to_first().unwrap();
+        while iter.valid().unwrap() {
+            forward.push((
+                iter.key().unwrap().unwrap().to_vec(),
+                iter.value().unwrap().unwrap().to_vec(),
+            ));
+            iter.next().unwrap();
+        }
+        assert_eq!(forward, expected);
+
+        let mut iter = context.raw_iter();
+        let mut backward = Vec::new();
+        iter.seek_to_last().unwrap();
+        while iter.valid().unwrap() {
+            backward.push((
+                iter.key().unwrap().unwrap().to_vec(),
+                iter.value().unwrap().unwrap().to_vec(),
+            ));
+            iter.prev().unwrap();
+        }
+        backward.reverse();
+        assert_eq!(backward, expected);
+
+        let mut iter = context.raw_iter();
+        iter.seek(b"key1").unwrap();
+        assert_eq!(iter.key().unwrap(), Some(b"key2".as_ref()));
+        iter.seek_for_prev(b"key1").unwrap();
+        assert_eq!(iter.key().unwrap(), Some(b"key0".as_ref()));
+        iter.seek(b"key5").unwrap();
+        assert!(!iter.valid().unwrap());
+        assert!(iter.key().unwrap().is_none());
+    }
+}
+
+mod batch_transaction {
+    use super::*;
+    use crate::{RawIterator, Storage, StorageBatch, StorageContext};
+
+    #[test]
+    fn test_transaction_properties() {
+        let storage = MemoryStorage::new();
+        let other_transaction = storage.start_transaction();
+        let transaction = storage.start_transaction();
+
+        let batch = StorageBatch::new();
+        let context_tx = storage
+            .get_transactional_storage_context(
+                [b"ayya"].as_ref().into(),
+                Some(&batch),
+                &transaction,
+            )
+            .unwrap();
+        context_tx
+            .put(b"key1", b"value1", None, None)
+            .unwrap()
+            .expect("cannot insert data");
+        context_tx
+            .put_meta(b"key2", b"value2", None)
+            .unwrap()
+            .expect("cannot insert metadata");
+
+        // Batch data isn't visible until the batch is committed
+        assert!(context_tx.get(b"key1").unwrap().unwrap().is_none());
+
+        storage
+            .commit_multi_context_batch(batch, Some(&transaction))
+            .unwrap()
+            .expect("cannot commit batch");
+
+        let context_tx = storage
+            .get_transactional_storage_context([b"ayya"].as_ref().into(), None, &transaction)
+            .unwrap();
+        let context_other = storage
+            .get_transactional_storage_context([b"ayya"].as_ref().into(), None, &other_transaction)
+            .unwrap();
+
+        // Visible in the transaction...
+        assert_eq!(
+            context_tx.get(b"key1").unwrap().unwrap(),
+            Some(b"value1".to_vec())
+        );
+        assert_eq!(
+            context_tx.get_meta(b"key2").unwrap().unwrap(),
+            Some(b"value2".to_vec())
+        );
+        // ...but not outside of it
+        assert!(context_other.get(b"key1").unwrap().unwrap().is_none());
+
+        storage
+            .commit_transaction(transaction)
+            .unwrap()
+            .expect("cannot commit transaction");
+
+        assert_eq!(
+            context_other.get(b"key1").unwrap().unwrap(),
+            Some(b"value1".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_get_costs() {
+        let storage = MemoryStorage::new();
+        let transaction = storage.start_transaction();
+
+        let batch = StorageBatch::new();
+        let context = storage
+            .get_transactional_storage_context(
+                [b"ayya"].as_ref().into(),
+                Some(&batch),
+                &transaction,
+            )
+            .unwrap();
+        context
+            .put(b"key1", b"value1", None, None)
+            .unwrap()
+            .expect("cannot insert data");
+        storage
+            .commit_multi_context_batch(batch, Some(&transaction))
+            .unwrap()
+            .expect("cannot commit batch");
+
+        let context = storage
+            .get_transactional_storage_context([b"ayya"].as_ref().into(), None, &transaction)
+            .unwrap();
+        let found = context.get(b"key1");
+        assert_eq!(found.cost.seek_count, 1);
+        assert_eq!(found.cost.storage_loaded_bytes, 6);
+        let missing = context.get(b"key2");
+        assert_eq!(missing.cost.seek_count, 1);
+        assert_eq!(missing.cost.storage_loaded_bytes, 0);
+    }
+
+    #[test]
+    fn test_transactional_clear_removes_only_target_subtree() {
+        let storage = MemoryStorage::new();
+        let transaction = storage.start_transaction();
+
+        let batch = StorageBatch::new();
+        for path in [b"ayya".as_ref(), b"ayyb"] {
+            let context = storage
+                .get_transactional_storage_context(
+                    [path].as_ref().into(),
+                    Some(&batch),
+                    &transaction,
+                )
+                .unwrap();
+            context
+                .put(b"key1", b"value1", None, None)
+                .unwrap()
+                .expect("cannot insert data");
+            context
+                .put(b"key2", b"value2", None, None)
+                .unwrap()
+                .expect("cannot insert data");
+        }
+        storage
+            .commit_multi_context_batch(batch, Some(&transaction))
+            .unwrap()
+            .expect("cannot commit batch");
+
+        let batch = StorageBatch::new();
+        let mut context = storage
+            .get_transactional_storage_context(
+                [b"ayya"].as_ref().into(),
+                Some(&batch),
+                &transaction,
+            )
+            .unwrap();
+        context.clear().unwrap().expect("cannot clear subtree");
+        storage
+            .commit_multi_context_batch(batch, Some(&transaction))
+            .unwrap()
+            .expect("cannot commit batch");
+
+        let cleared = storage
+            .get_transactional_storage_context([b"ayya"].as_ref().into(), None, &transaction)
+            .unwrap();
+        let mut iter = cleared.raw_iter();
+        iter.seek_to_first().unwrap();
+        assert!(!iter.valid().unwrap());
+
+        let untouched = storage
+            .get_transactional_storage_context([b"ayyb"].as_ref().into(), None, &transaction)
+            .unwrap();
+        assert_eq!(
+            untouched.get(b"key2").unwrap().unwrap(),
+            Some(b"value2".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_write_operations_error_when_batch_is_none() {
+        let storage = MemoryStorage::new();
+        let transaction = storage.start_transaction();
+        let context = storage
+            .get_transactional_storage_context([b"ayya"].as_ref().into(), None, &transaction)
+            .unwrap();
+
+        assert!(context.put(b"key", b"value", None, None).unwrap().is_err());
+        assert!(context.put_aux(b"key", b"value", None).unwrap().is_err());
+        assert!(context.delete_meta(b"key", None).unwrap().is_err());
+    }
+}
+
+mod storage_management {
+    use super::*;
+    use crate::{Storage, StorageBatch, StorageContext};
+
+    #[test]
+    fn test_wipe_and_unsupported_checkpoints() {
+        let storage = MemoryStorage::new();
+        let batch = StorageBatch::new();
+        let transaction = storage.start_transaction();
+        let context = storage
+            .get_transactional_storage_context(
+                [b"ayya"].as_ref().into(),
+                Some(&batch),
+                &transaction,
+            )
+            .unwrap();
+        context
+            .put(b"key1", b"value1", None, None)
+            .unwrap()
+            .expect("cannot insert data");
+        context
+            .put_root(b"root", b"value", None)
+            .unwrap()
+            .expect("cannot insert root");
+        storage
+            .commit_multi_context_batch(batch, None)
+            .unwrap()
+            .expect("cannot commit batch");
+        drop(transaction);
+
+        assert!(storage.create_checkpoint("/nonexistent").is_err());
+
+        storage.wipe().expect("cannot wipe storage");
+        let transaction = storage.start_transaction();
+        let context = storage
+            .get_transactional_storage_context([b"ayya"].as_ref().into(), None, &transaction)
+            .unwrap();
+        assert!(context.get(b"key1").unwrap().unwrap().is_none());
+        assert!(context.get_root(b"root").unwrap().unwrap().is_none());
+    }
+}
+
+/// Both backends must report the same costs, otherwise fees would depend on
+/// where the data is kept.
+#[cfg(feature = "rocksdb_storage")]
+mod rocksdb_parity {
+    use grovedb_costs::OperationCost;
+
+    use super::*;
+    use crate::{
+        rocksdb_storage::test_utils::TempStorage, RawIterator, Storage, StorageBatch,
+        StorageContext,
+    };
+
+    /// Runs the same sequence of operations against a backend and collects
+    /// the cost of every step.
+    macro_rules! run_workload {
+        ($storage:expr) => {{
+            let storage = $storage;
+            let mut costs: Vec<OperationCost> = Vec::new();
+            let transaction = storage.start_transaction();
+
+            let batch = StorageBatch::new();
+            for path in [b"ayya".as_ref(), b"ayyb", b"zzz"] {
+                let context = storage
+                    .get_transactional_storage_context(
+                        [path].as_ref().into(),
+                        Some(&batch),
+                        &transaction,
+                    )
+                    .unwrap();
+                for (key, value) in [
+                    (b"a".as_ref(), b"value1".as_ref()),
+                    (b"b", b"value22"),
+                    (b"c", b"value333"),
+                ] {
+                    context
+                        .put(key, value, None, None)
+                        .unwrap()
+                        .expect("cannot insert data");
+                }
+                context
+                    .put_aux(b"aux", b"aux_value", None)
+                    .unwrap()
+                    .expect("cannot insert aux");
+            }
+            costs.push(
+                storage
+                    .commit_multi_context_batch(batch, Some(&transaction))
+                    .cost,
+            );
+
+            let batch = StorageBatch::new();
+            let context = storage
+                .get_transactional_storage_context(
+                    [b"ayyb"].as_ref().into(),
+                    Some(&batch),
+                    &transaction,
+                )
+                .unwrap();
+            costs.push(context.get(b"b").cost);
+            costs.push(context.get_aux(b"aux").cost);
+            context.delete(b"b", None).unwrap().expect("cannot delete");
+            context
+                .delete_aux(b"aux", None)
+                .unwrap()
+                .expect("cannot delete");
+
+            let mut iter = context.raw_iter();
+            let mut iteration_cost = iter.seek_to_first().cost;
+            while iter.valid().unwrap_add_cost(&mut iteration_cost) {
+                iter.key().unwrap_add_cost(&mut iteration_cost);
+                iter.value().unwrap_add_cost(&mut iteration_cost);
+                iter.next().unwrap_add_cost(&mut iteration_cost);
+            }
+            costs.push(iteration_cost);
+            drop(iter);
+
+            costs.push(
+                storage
+                    .commit_multi_context_batch(batch, Some(&transaction))
+                    .cost,
+            );
+            costs
+        }};
+    }
+
+    #[test]
+    fn test_costs_match_rocksdb() {
+        let rocksdb = TempStorage::new();
+        let memory = MemoryStorage::new();
+
+        assert_eq!(run_workload!(&*rocksdb), run_workload!(&memory));
+    }
+}
@@ -0,0 +1,206 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Subtree prefixes and prefixed batches shared by the storage backends.
+//!
+//! Every backend lays out data the same way: a record of a subtree is stored
+//! under `prefix || key`, where the prefix is a Blake3 hash of the subtree
+//! path. Keeping this in one place guarantees that the same subtree maps to
+//! the same keys (and therefore the same costs) regardless of the backend.
+
+use grovedb_costs::{
+    storage_cost::key_value_cost::KeyValueStorageCost, ChildrenSizesWithIsSumTree, CostContext,
+    CostsExt, OperationCost,
+};
+use grovedb_path::SubtreePath;
+use integer_encoding::VarInt;
+
+use crate::{storage::SubtreePrefix, worst_case_costs::WorstKeyLength, Batch, StorageBatch};
+
+const BLAKE_BLOCK_LEN: usize = 64;
+
+fn blake_block_count(len: usize) -> usize {
+    if len == 0 {
+        1
+    } else {
+        1 + (len - 1) / BLAKE_BLOCK_LEN
+    }
+}
+
+fn build_prefix_body<B>(path: SubtreePath<B>) -> (Vec<u8>, usize)
+where
+    B: AsRef<[u8]>,
+{
+    let segments_iter = path.into_reverse_iter();
+    let mut segments_count: usize = 0;
+    let mut res = Vec::new();
+    let mut lengths = Vec::new();
+
+    for s in segments_iter {
+        segments_count += 1;
+        res.extend_from_slice(s);
+        lengths.push(u8::try_from(s.len()).expect(
+            "path segment length must not exceed 255 bytes; \
+             this is enforced at insert time via validate_key_length",
+        ));
+    }
+
+    // Note: this uses native-endian encoding. Changing to big-endian would
+    // be a breaking change since the output is hashed with Blake3 to produce
+    // subtree prefixes stored in RocksDB — existing databases would become
+    // unreadable. In practice GroveDB only targets little-endian platforms
+    // (x86_64, aarch64), so this is not a portability concern.
+    res.extend(segments_count.to_ne_bytes());
+    res.extend(lengths);
+    (res, segments_count)
+}
+
+/// Builds the prefix of a subtree identified by `path`.
+pub(crate) fn build_prefix<B>(path: SubtreePath<B>) -> CostContext<SubtreePrefix>
+where
+    B: AsRef<[u8]>,
+{
+    let (body, segments_count) = build_prefix_body(path);
+    if segments_count == 0 {
+        SubtreePrefix::default().wrap_with_cost(OperationCost::default())
+    } else {
+        let blocks_count = blake_block_count(body.len());
+        SubtreePrefix::from(blake3::hash(&body))
+            .wrap_with_cost(OperationCost::with_hash_node_calls(blocks_count as u32))
+    }
+}
+
+fn worst_case_body_size<L: WorstKeyLength>(path: &[L]) -> usize {
+    // body = segment_bytes + segments_count.to_ne_bytes() + lengths
+    // segments_count.to_ne_bytes() contributes size_of::<usize>() bytes
+    path.iter().map(|a| a.max_length() as usize).sum::<usize>()
+        + std::mem::size_of::<usize>()
+        + path.len()
+}
+
+/// Worst case cost of building a storage context prefix for `path`.
+pub(crate) fn storage_context_cost<L: WorstKeyLength>(path: &[L]) -> OperationCost {
+    if path.is_empty() {
+        OperationCost::default()
+    } else {
+        let body_size = worst_case_body_size(path);
+        // the block size of blake3 is 64
+        let blocks_num = blake_block_count(body_size) as u32;
+        OperationCost::with_hash_node_calls(blocks_num)
+    }
+}
+
+/// Make prefixed key
+pub fn make_prefixed_key<K: AsRef<[u8]>>(prefix: &SubtreePrefix, key: K) -> Vec<u8> {
+    let mut prefix_vec = prefix.to_vec();
+    prefix_vec.extend_from_slice(key.as_ref());
+    prefix_vec
+}
+
+/// Batch with no backing storage_cost (it is not a backend batch, but our own
+/// way to represent a set of operations) that eventually will be merged into
+/// multi-context batch.
+pub struct PrefixedMultiContextBatchPart {
+    pub(crate) prefix: SubtreePrefix,
+    pub(crate) batch: StorageBatch,
+}
+
+/// Implementation of a batch outside a transaction for multi-context
+/// batch.
+impl Batch for PrefixedMultiContextBatchPart {
+    fn put<K: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: &[u8],
+        children_sizes: ChildrenSizesWithIsSumTree,
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> Result<(), grovedb_costs::error::Error> {
+        let prefixed_key = make_prefixed_key(&self.prefix, key);
+
+        // Update the key_storage_cost based on the prefixed key
+        let updated_cost_info = cost_info.map(|mut key_value_storage_cost| {
+            if key_value_storage_cost.new_node {
+                // key is new, storage_cost needs to be created for it
+                key_value_storage_cost.key_storage_cost.added_bytes +=
+                    (prefixed_key.len() + prefixed_key.len().required_space()) as u32;
+            }
+            key_value_storage_cost
+        });
+
+        self.batch.put(
+            prefixed_key,
+            value.to_vec(),
+            children_sizes,
+            updated_cost_info,
+        );
+        Ok(())
+    }
+
+    fn put_aux<K: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> Result<(), grovedb_costs::error::Error> {
+        self.batch.put_aux(
+            make_prefixed_key(&self.prefix, key),
+            value.to_vec(),
+            cost_info,
+        );
+        Ok(())
+    }
+
+    fn put_root<K: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: &[u8],
+        cost_info: Option<KeyValueStorageCost>,
+    ) -> Result<(), grovedb_costs::error::Error> {
+        self.batch.put_root(
+            make_prefixed_key(&self.prefix, key),
+            value.to_vec(),
+            cost_info,
+        );
+        Ok(())
+    }
+
+    fn delete<K: AsRef<[u8]>>(&mut self, key: K, cost_info: Option<KeyValueStorageCost>) {
+        self.batch
+            .delete(make_prefixed_key(&self.prefix, key), cost_info);
+    }
+
+    fn delete_aux<K: AsRef<[u8]>>(&mut self, key: K, cost_info: Option<KeyValueStorageCost>) {
+        self.batch
+            .delete_aux(make_prefixed_key(&self.prefix, key), cost_info);
+    }
+
+    fn delete_root<K: AsRef<[u8]>>(&mut self, key: K, cost_info: Option<KeyValueStorageCost>) {
+        self.batch
+            .delete_root(make_prefixed_key(&self.prefix, key), cost_info);
+    }
+}
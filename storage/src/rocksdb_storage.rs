@@ -27,6 +27,9 @@
 // DEALINGS IN THE SOFTWARE.
 
 //! GroveDB storage layer implemented over RocksDB backend.
+mod history;
+mod options;
+mod read_only_storage;
 mod storage;
 pub mod storage_context;
 pub mod test_utils;
@@ -35,8 +38,15 @@ mod tests;
 
 pub use rocksdb::{Error, WriteBatchWithTransaction};
 pub use storage_context::{
-    PrefixedRocksDbBatch, PrefixedRocksDbImmediateStorageContext, PrefixedRocksDbRawIterator,
+    PrefixedRocksDbBatch, PrefixedRocksDbHistoricalRawIterator,
+    PrefixedRocksDbHistoricalStorageContext, PrefixedRocksDbImmediateStorageContext,
+    PrefixedRocksDbRawIterator, PrefixedRocksDbReadOnlyStorageContext,
     PrefixedRocksDbTransactionContext,
 };
 
-pub use self::storage::RocksDbStorage;
+pub use self::{
+    history::HistoricalRocksDbStorage,
+    options::{ColumnFamilyOptions, CompressionType, RocksDbOptions},
+    read_only_storage::{ReadOnlyRocksDbStorage, ReadOnlyTransaction},
+    storage::RocksDbStorage,
+};
@@ -0,0 +1,531 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//! Undo log keeping past committed versions of a RocksDB storage readable.
+//!
+//! With history enabled every committed write batch becomes a new version.
+//! Before a key is overwritten or deleted its committed value is saved in the
+//! meta column family, indexed both by key and by version. The value a key had
+//! at a retained version is then the saved value of the first later version
+//! that changed it, or the current value if none did. Records are also
+//! indexed by subtree and version, so reading a past subtree only merges in
+//! the changes made to that subtree.
+
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    path::Path,
+    sync::Arc,
+};
+
+use grovedb_costs::{CostContext, CostResult, CostsExt, OperationCost};
+use grovedb_path::SubtreePath;
+use rocksdb::{
+    ColumnFamily, DBRawIteratorWithThreadMode, WriteBatchWithTransaction,
+    DEFAULT_COLUMN_FAMILY_NAME,
+};
+
+use super::{
+    storage::{Db, SubtreePrefix, AUX_CF_NAME, META_CF_NAME, ROOTS_CF_NAME},
+    PrefixedRocksDbHistoricalStorageContext, ReadOnlyTransaction,
+};
+use crate::{
+    error::Error::{self, RocksDBError},
+    prefixed,
+    storage::AbstractBatchOperation,
+    worst_case_costs::WorstKeyLength,
+    Storage, StorageBatch,
+};
+
+/// Every history record lives under this prefix in the meta column family.
+/// Regular meta keys start with a 32 bytes subtree prefix, so they can't
+/// collide with it in practice.
+const HISTORY_PREFIX: &[u8] = b"\0grovedb_history/";
+const LATEST_VERSION_KEY: &[u8] = b"\0grovedb_history/latest";
+const EARLIEST_VERSION_KEY: &[u8] = b"\0grovedb_history/earliest";
+/// `prefix | column | key length | key | version` to saved value
+const BY_KEY_PREFIX: &[u8] = b"\0grovedb_history/k";
+/// `prefix | version | column | key` to nothing, used for pruning
+const BY_VERSION_PREFIX: &[u8] = b"\0grovedb_history/v";
+/// `prefix | column | subtree prefix | version | key without subtree prefix`
+/// to nothing, used to find which keys of a subtree changed since a version
+const BY_SUBTREE_PREFIX: &[u8] = b"\0grovedb_history/s";
+
+/// Column family a journaled key belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Column {
+    Data,
+    Aux,
+    Roots,
+    Meta,
+}
+
+/// Returns the column and the key `operation` writes to.
+pub(crate) fn written_key(operation: &AbstractBatchOperation) -> (Column, Vec<u8>) {
+    match operation {
+        AbstractBatchOperation::Put { key, .. } | AbstractBatchOperation::Delete { key, .. } => {
+            (Column::Data, key.clone())
+        }
+        AbstractBatchOperation::PutAux { key, .. }
+        | AbstractBatchOperation::DeleteAux { key, .. } => (Column::Aux, key.clone()),
+        AbstractBatchOperation::PutRoot { key, .. }
+        | AbstractBatchOperation::DeleteRoot { key, .. } => (Column::Roots, key.clone()),
+        AbstractBatchOperation::PutMeta { key, .. }
+        | AbstractBatchOperation::DeleteMeta { key, .. } => (Column::Meta, key.clone()),
+    }
+}
+
+impl Column {
+    fn to_byte(self) -> u8 {
+        match self {
+            Column::Data => 0,
+            Column::Aux => 1,
+            Column::Roots => 2,
+            Column::Meta => 3,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, Error> {
+        match byte {
+            0 => Ok(Column::Data),
+            1 => Ok(Column::Aux),
+            2 => Ok(Column::Roots),
+            3 => Ok(Column::Meta),
+            _ => Err(corrupted("unknown column")),
+        }
+    }
+
+    fn handle(self, db: &Db) -> &ColumnFamily {
+        let name = match self {
+            Column::Data => DEFAULT_COLUMN_FAMILY_NAME,
+            Column::Aux => AUX_CF_NAME,
+            Column::Roots => ROOTS_CF_NAME,
+            Column::Meta => META_CF_NAME,
+        };
+        db.cf_handle(name).expect("column family must exist")
+    }
+}
+
+fn corrupted(reason: &str) -> Error {
+    Error::StorageError(format!("corrupted history record: {reason}"))
+}
+
+fn cf_meta(db: &Db) -> &ColumnFamily {
+    Column::Meta.handle(db)
+}
+
+fn by_key_prefix(column: Column, key: &[u8]) -> Vec<u8> {
+    let mut record_key = Vec::with_capacity(BY_KEY_PREFIX.len() + 5 + key.len() + 8);
+    record_key.extend_from_slice(BY_KEY_PREFIX);
+    record_key.push(column.to_byte());
+    record_key.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    record_key.extend_from_slice(key);
+    record_key
+}
+
+fn by_key(column: Column, key: &[u8], version: u64) -> Vec<u8> {
+    let mut record_key = by_key_prefix(column, key);
+    record_key.extend_from_slice(&version.to_be_bytes());
+    record_key
+}
+
+fn by_version(version: u64, column: Column, key: &[u8]) -> Vec<u8> {
+    let mut record_key = Vec::with_capacity(BY_VERSION_PREFIX.len() + 9 + key.len());
+    record_key.extend_from_slice(BY_VERSION_PREFIX);
+    record_key.extend_from_slice(&version.to_be_bytes());
+    record_key.push(column.to_byte());
+    record_key.extend_from_slice(key);
+    record_key
+}
+
+fn by_subtree_prefix(column: Column, prefix: &[u8]) -> Vec<u8> {
+    let mut record_key = Vec::with_capacity(BY_SUBTREE_PREFIX.len() + 1 + prefix.len() + 8);
+    record_key.extend_from_slice(BY_SUBTREE_PREFIX);
+    record_key.push(column.to_byte());
+    record_key.extend_from_slice(prefix);
+    record_key
+}
+
+/// Returns the by-subtree record key of a change of `key`, or `None` if the
+/// key is too short to belong to a subtree.
+fn by_subtree(version: u64, column: Column, key: &[u8]) -> Option<Vec<u8>> {
+    let (prefix, key) = key.split_at_checked(size_of::<SubtreePrefix>())?;
+    let mut record_key = by_subtree_prefix(column, prefix);
+    record_key.extend_from_slice(&version.to_be_bytes());
+    record_key.extend_from_slice(key);
+    Some(record_key)
+}
+
+/// Splits a by-version record key into its version, column and key.
+fn parse_by_version(record_key: &[u8]) -> Result<(u64, Column, &[u8]), Error> {
+    let rest = &record_key[BY_VERSION_PREFIX.len()..];
+    if rest.len() < 9 {
+        return Err(corrupted("by-version key is too short"));
+    }
+    let (version, rest) = rest.split_at(8);
+    let version = u64::from_be_bytes(version.try_into().expect("8 bytes"));
+    Ok((version, Column::from_byte(rest[0])?, &rest[1..]))
+}
+
+/// Splits a by-subtree record key into its version and the key without its
+/// subtree prefix.
+fn parse_by_subtree(record_key: &[u8]) -> Result<(u64, &[u8]), Error> {
+    let rest = record_key
+        .get(BY_SUBTREE_PREFIX.len() + 1 + size_of::<SubtreePrefix>()..)
+        .filter(|rest| rest.len() >= 8)
+        .ok_or_else(|| corrupted("by-subtree key is too short"))?;
+    let (version, key) = rest.split_at(8);
+    Ok((
+        u64::from_be_bytes(version.try_into().expect("8 bytes")),
+        key,
+    ))
+}
+
+fn encode_value(value: Option<Vec<u8>>) -> Vec<u8> {
+    match value {
+        None => vec![0],
+        Some(value) => {
+            let mut encoded = Vec::with_capacity(value.len() + 1);
+            encoded.push(1);
+            encoded.extend(value);
+            encoded
+        }
+    }
+}
+
+fn decode_value(encoded: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+    match encoded.split_first() {
+        Some((0, [])) => Ok(None),
+        Some((1, value)) => Ok(Some(value.to_vec())),
+        _ => Err(corrupted("invalid saved value")),
+    }
+}
+
+fn get_version(db: &Db, key: &[u8]) -> Result<Option<u64>, Error> {
+    db.get_cf(cf_meta(db), key)
+        .map_err(RocksDBError)?
+        .map(|bytes| {
+            bytes
+                .as_slice()
+                .try_into()
+                .map(u64::from_be_bytes)
+                .map_err(|_| corrupted("invalid version"))
+        })
+        .transpose()
+}
+
+/// Starts versioning at version 0 unless the storage already is versioned.
+pub(crate) fn enable(db: &Db) -> Result<(), Error> {
+    if get_version(db, LATEST_VERSION_KEY)?.is_none() {
+        let mut batch = WriteBatchWithTransaction::<true>::default();
+        batch.put_cf(cf_meta(db), LATEST_VERSION_KEY, 0u64.to_be_bytes());
+        batch.put_cf(cf_meta(db), EARLIEST_VERSION_KEY, 0u64.to_be_bytes());
+        db.write(batch).map_err(RocksDBError)?;
+    }
+    Ok(())
+}
+
+/// Drops all history. Writes made while history is disabled aren't
+/// journaled, so history left over from a previous run can't be trusted.
+pub(crate) fn disable(db: &Db) -> Result<(), Error> {
+    if get_version(db, LATEST_VERSION_KEY)?.is_some() {
+        let mut upper = HISTORY_PREFIX.to_vec();
+        *upper.last_mut().expect("prefix is not empty") += 1;
+        db.delete_range_cf(cf_meta(db), HISTORY_PREFIX, upper.as_slice())
+            .map_err(RocksDBError)?;
+    }
+    Ok(())
+}
+
+/// Returns the earliest and the latest retained versions.
+pub(crate) fn version_range(db: &Db) -> Result<Option<(u64, u64)>, Error> {
+    let Some(latest) = get_version(db, LATEST_VERSION_KEY)? else {
+        return Ok(None);
+    };
+    let earliest = get_version(db, EARLIEST_VERSION_KEY)?.unwrap_or(0);
+    Ok(Some((earliest, latest)))
+}
+
+/// Adds the undo records of `written` keys to `db_batch`, making it the next
+/// version once committed, and prunes versions falling out of `retention`.
+///
+/// Saved values are read from committed state rather than from a
+/// transaction, so journaling several batches of the same transaction saves
+/// the same values and bumps the version only once.
+pub(crate) fn journal(
+    db: &Db,
+    retention: u64,
+    db_batch: &mut WriteBatchWithTransaction<true>,
+    written: Vec<(Column, Vec<u8>)>,
+) -> Result<(), Error> {
+    if written.is_empty() {
+        return Ok(());
+    }
+    let retention = retention.max(1);
+    let (earliest, latest) = version_range(db)?.unwrap_or((0, 0));
+    let version = latest + 1;
+
+    if retention > 1 {
+        for (column, key) in written {
+            let old_value = db.get_cf(column.handle(db), &key).map_err(RocksDBError)?;
+            db_batch.put_cf(
+                cf_meta(db),
+                by_key(column, &key, version),
+                encode_value(old_value),
+            );
+            db_batch.put_cf(cf_meta(db), by_version(version, column, &key), []);
+            if let Some(record_key) = by_subtree(version, column, &key) {
+                db_batch.put_cf(cf_meta(db), record_key, []);
+            }
+        }
+    }
+
+    // Reading a version needs the records of every later version, so records
+    // up to the earliest retained version itself can go.
+    let new_earliest = earliest.max((version + 1).saturating_sub(retention));
+    if new_earliest > earliest {
+        let mut iter = db.raw_iterator_cf(cf_meta(db));
+        iter.seek(by_version(earliest + 1, Column::Data, &[]));
+        while let Some(record_key) = iter.key() {
+            if !record_key.starts_with(BY_VERSION_PREFIX) {
+                break;
+            }
+            let (record_version, column, key) = parse_by_version(record_key)?;
+            if record_version > new_earliest {
+                break;
+            }
+            db_batch.delete_cf(cf_meta(db), by_key(column, key, record_version));
+            if let Some(record_key) = by_subtree(record_version, column, key) {
+                db_batch.delete_cf(cf_meta(db), record_key);
+            }
+            db_batch.delete_cf(cf_meta(db), record_key);
+            iter.next();
+        }
+        iter.status().map_err(RocksDBError)?;
+    }
+
+    db_batch.put_cf(cf_meta(db), LATEST_VERSION_KEY, version.to_be_bytes());
+    db_batch.put_cf(
+        cf_meta(db),
+        EARLIEST_VERSION_KEY,
+        new_earliest.to_be_bytes(),
+    );
+    Ok(())
+}
+
+/// Storage over a past version of a [`RocksDbStorage`](super::RocksDbStorage)
+/// with history enabled, obtained with
+/// [`RocksDbStorage::at_version`](super::RocksDbStorage::at_version).
+///
+/// Reads see committed data as it was at that version, every write fails.
+/// A version can be pruned while it's being read if enough new versions get
+/// committed meanwhile, check it's still retained after reading.
+pub struct HistoricalRocksDbStorage {
+    db: Arc<Db>,
+    version: u64,
+}
+
+impl HistoricalRocksDbStorage {
+    pub(crate) fn new(db: Arc<Db>, version: u64) -> Self {
+        HistoricalRocksDbStorage { db, version }
+    }
+
+    /// Returns the version this storage reads.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the earliest and the latest versions retained by the
+    /// underlying storage.
+    pub fn version_range(&self) -> Result<Option<(u64, u64)>, Error> {
+        version_range(&self.db)
+    }
+
+    /// Gets the value `key` of `column` had at this version.
+    pub(crate) fn get(&self, column: Column, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        // Current value first: a commit landing in between leaves a record
+        // with the value read here, so either way the result is consistent
+        let current = self
+            .db
+            .get_cf(column.handle(&self.db), key)
+            .map_err(RocksDBError)?;
+
+        let record_prefix = by_key_prefix(column, key);
+        let mut iter = self.db.raw_iterator_cf(cf_meta(&self.db));
+        iter.seek(by_key(column, key, self.version + 1));
+        match (iter.key(), iter.value()) {
+            (Some(record_key), Some(saved))
+                if record_key.len() == record_prefix.len() + 8
+                    && record_key.starts_with(&record_prefix) =>
+            {
+                decode_value(saved)
+            }
+            _ => {
+                iter.status().map_err(RocksDBError)?;
+                Ok(current)
+            }
+        }
+    }
+
+    /// Returns a raw iterator over the current records of `column`.
+    pub(crate) fn live_iter(&self, column: Column) -> DBRawIteratorWithThreadMode<'_, Db> {
+        self.db.raw_iterator_cf(column.handle(&self.db))
+    }
+
+    /// Collects the keys of `column` under `prefix` changed after this
+    /// version along with the values they had at this version, `None` for
+    /// keys that didn't exist yet.
+    ///
+    /// Only the by-subtree records of `prefix` are read. A live iterator
+    /// created before calling this sees no change missing from the result.
+    pub(crate) fn changes(
+        &self,
+        column: Column,
+        prefix: &SubtreePrefix,
+    ) -> Result<BTreeMap<Vec<u8>, Option<Vec<u8>>>, Error> {
+        let record_prefix = by_subtree_prefix(column, prefix);
+        let mut changes = BTreeMap::new();
+
+        let mut iter = self.db.raw_iterator_cf(cf_meta(&self.db));
+        let mut start = record_prefix.clone();
+        start.extend_from_slice(&(self.version + 1).to_be_bytes());
+        iter.seek(start);
+        while let Some(record_key) = iter.key() {
+            if !record_key.starts_with(&record_prefix) {
+                break;
+            }
+            let (version, key) = parse_by_subtree(record_key)?;
+            let key = [prefix.as_slice(), key].concat();
+            // Records come by version, the first one of a key saved its value
+            // at this version
+            if let Entry::Vacant(entry) = changes.entry(key) {
+                let saved = self
+                    .db
+                    .get_cf(cf_meta(&self.db), by_key(column, entry.key(), version))
+                    .map_err(RocksDBError)?
+                    .ok_or_else(|| corrupted("missing saved value"))?;
+                entry.insert(decode_value(&saved)?);
+            }
+            iter.next();
+        }
+        iter.status().map_err(RocksDBError)?;
+
+        Ok(changes)
+    }
+}
+
+impl<'db> Storage<'db> for HistoricalRocksDbStorage {
+    type BatchTransactionalStorageContext = PrefixedRocksDbHistoricalStorageContext<'db>;
+    type ImmediateStorageContext = PrefixedRocksDbHistoricalStorageContext<'db>;
+    type Transaction = ReadOnlyTransaction;
+
+    fn start_transaction(&'db self) -> Self::Transaction {
+        ReadOnlyTransaction
+    }
+
+    fn commit_transaction(&self, _transaction: Self::Transaction) -> CostResult<(), Error> {
+        Ok(()).wrap_with_cost(OperationCost::default())
+    }
+
+    fn rollback_transaction(&self, _transaction: &Self::Transaction) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn get_transactional_storage_context<'b, B>(
+        &'db self,
+        path: SubtreePath<'b, B>,
+        _batch: Option<&'db StorageBatch>,
+        _transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::BatchTransactionalStorageContext>
+    where
+        B: AsRef<[u8]> + 'b,
+    {
+        prefixed::build_prefix(path)
+            .map(|prefix| PrefixedRocksDbHistoricalStorageContext::new(self, prefix))
+    }
+
+    fn get_transactional_storage_context_by_subtree_prefix(
+        &'db self,
+        prefix: SubtreePrefix,
+        _batch: Option<&'db StorageBatch>,
+        _transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::BatchTransactionalStorageContext> {
+        PrefixedRocksDbHistoricalStorageContext::new(self, prefix)
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn get_immediate_storage_context<'b, B>(
+        &'db self,
+        path: SubtreePath<'b, B>,
+        _transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::ImmediateStorageContext>
+    where
+        B: AsRef<[u8]> + 'b,
+    {
+        prefixed::build_prefix(path)
+            .map(|prefix| PrefixedRocksDbHistoricalStorageContext::new(self, prefix))
+    }
+
+    fn get_immediate_storage_context_by_subtree_prefix(
+        &'db self,
+        prefix: SubtreePrefix,
+        _transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::ImmediateStorageContext> {
+        PrefixedRocksDbHistoricalStorageContext::new(self, prefix)
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    /// Empty batches are accepted since read operations may commit batches
+    /// they didn't write to, anything else fails.
+    fn commit_multi_context_batch(
+        &self,
+        batch: StorageBatch,
+        _transaction: Option<&'db Self::Transaction>,
+    ) -> CostResult<(), Error> {
+        if batch.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::StorageError(
+                "attempted to commit a batch to a past version".to_string(),
+            ))
+        }
+        .wrap_with_cost(OperationCost::default())
+    }
+
+    fn get_storage_context_cost<L: WorstKeyLength>(path: &[L]) -> OperationCost {
+        prefixed::storage_context_cost(path)
+    }
+
+    fn create_checkpoint<P: AsRef<Path>>(&self, _path: P) -> Result<(), Error> {
+        Err(Error::StorageError(
+            "checkpoints of past versions are not supported".to_string(),
+        ))
+    }
+}
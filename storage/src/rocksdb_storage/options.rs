@@ -0,0 +1,332 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//! Tuning options for RocksDB storage.
+
+use rocksdb::{BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType};
+
+use super::storage::{AUX_CF_NAME, META_CF_NAME, ROOTS_CF_NAME};
+
+/// Compression applied to SST files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionType {
+    /// No compression
+    None,
+    /// Snappy, RocksDB's default
+    Snappy,
+    /// LZ4
+    Lz4,
+    /// Zstandard
+    Zstd,
+}
+
+impl From<CompressionType> for DBCompressionType {
+    fn from(value: CompressionType) -> Self {
+        match value {
+            CompressionType::None => DBCompressionType::None,
+            CompressionType::Snappy => DBCompressionType::Snappy,
+            CompressionType::Lz4 => DBCompressionType::Lz4,
+            CompressionType::Zstd => DBCompressionType::Zstd,
+        }
+    }
+}
+
+/// Options of a single column family. Anything left unset falls back to the
+/// database-wide value of [`RocksDbOptions`].
+#[derive(Debug, Clone, Default)]
+pub struct ColumnFamilyOptions {
+    compression: Option<CompressionType>,
+    bloom_filter_bits_per_key: Option<f64>,
+    write_buffer_size: Option<usize>,
+    max_write_buffer_number: Option<i32>,
+}
+
+impl ColumnFamilyOptions {
+    /// Sets the compression of the column family.
+    pub fn compression(mut self, compression: CompressionType) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Enables a bloom filter with the given bits per key.
+    pub fn bloom_filter_bits_per_key(mut self, bits_per_key: f64) -> Self {
+        self.bloom_filter_bits_per_key = Some(bits_per_key);
+        self
+    }
+
+    /// Sets the size of a single memtable in bytes.
+    pub fn write_buffer_size(mut self, size: usize) -> Self {
+        self.write_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets how many memtables can be kept in memory before stalling writes.
+    pub fn max_write_buffer_number(mut self, number: i32) -> Self {
+        self.max_write_buffer_number = Some(number);
+        self
+    }
+}
+
+/// Options used to open [`RocksDbStorage`](super::RocksDbStorage).
+///
+/// The defaults match what GroveDb has always used. Creating missing files
+/// and atomic flushes across column families cannot be turned off, as GroveDb
+/// relies on the column families being flushed together.
+#[derive(Debug, Clone)]
+pub struct RocksDbOptions {
+    parallelism: i32,
+    allow_mmap_reads: bool,
+    allow_mmap_writes: bool,
+    max_open_files: Option<i32>,
+    block_cache_size: Option<usize>,
+    max_total_wal_size: Option<u64>,
+    wal_ttl_seconds: Option<u64>,
+    wal_size_limit_mb: Option<u64>,
+    manual_wal_flush: bool,
+    history_retention: Option<u64>,
+    default_cf: ColumnFamilyOptions,
+    aux_cf: ColumnFamilyOptions,
+    roots_cf: ColumnFamilyOptions,
+    meta_cf: ColumnFamilyOptions,
+}
+
+impl Default for RocksDbOptions {
+    fn default() -> Self {
+        RocksDbOptions {
+            parallelism: num_cpus::get() as i32,
+            allow_mmap_reads: true,
+            allow_mmap_writes: true,
+            max_open_files: None,
+            block_cache_size: None,
+            max_total_wal_size: None,
+            wal_ttl_seconds: None,
+            wal_size_limit_mb: None,
+            manual_wal_flush: false,
+            history_retention: None,
+            default_cf: ColumnFamilyOptions::default(),
+            aux_cf: ColumnFamilyOptions::default(),
+            roots_cf: ColumnFamilyOptions::default(),
+            meta_cf: ColumnFamilyOptions::default(),
+        }
+    }
+}
+
+impl RocksDbOptions {
+    /// Sets the number of background threads for flushes and compactions.
+    pub fn parallelism(mut self, threads: i32) -> Self {
+        self.parallelism = threads;
+        self
+    }
+
+    /// Allows or forbids memory mapped reads.
+    pub fn allow_mmap_reads(mut self, allow: bool) -> Self {
+        self.allow_mmap_reads = allow;
+        self
+    }
+
+    /// Allows or forbids memory mapped writes.
+    pub fn allow_mmap_writes(mut self, allow: bool) -> Self {
+        self.allow_mmap_writes = allow;
+        self
+    }
+
+    /// Limits the number of files kept open, `-1` keeps all of them open.
+    pub fn max_open_files(mut self, max_open_files: i32) -> Self {
+        self.max_open_files = Some(max_open_files);
+        self
+    }
+
+    /// Sets the size in bytes of the LRU block cache shared by all column
+    /// families.
+    pub fn block_cache_size(mut self, size: usize) -> Self {
+        self.block_cache_size = Some(size);
+        self
+    }
+
+    /// Sets the compression of every column family which doesn't override
+    /// it.
+    pub fn compression(mut self, compression: CompressionType) -> Self {
+        self.default_cf.compression = Some(compression);
+        self
+    }
+
+    /// Enables bloom filters in every column family which doesn't override
+    /// them.
+    pub fn bloom_filter_bits_per_key(mut self, bits_per_key: f64) -> Self {
+        self.default_cf.bloom_filter_bits_per_key = Some(bits_per_key);
+        self
+    }
+
+    /// Sets the total size of WAL files after which column families are
+    /// flushed to release the oldest ones.
+    pub fn max_total_wal_size(mut self, size: u64) -> Self {
+        self.max_total_wal_size = Some(size);
+        self
+    }
+
+    /// Sets how long archived WAL files are kept.
+    pub fn wal_ttl_seconds(mut self, seconds: u64) -> Self {
+        self.wal_ttl_seconds = Some(seconds);
+        self
+    }
+
+    /// Sets the size limit of archived WAL files in megabytes.
+    pub fn wal_size_limit_mb(mut self, size: u64) -> Self {
+        self.wal_size_limit_mb = Some(size);
+        self
+    }
+
+    /// Buffers WAL writes until the storage is flushed instead of writing
+    /// them on every commit.
+    pub fn manual_wal_flush(mut self, manual: bool) -> Self {
+        self.manual_wal_flush = manual;
+        self
+    }
+
+    /// Keeps the last `versions` committed states readable, the latest one
+    /// included, see [`RocksDbStorage::at_version`](super::RocksDbStorage::at_version).
+    ///
+    /// Only writes committed through batches are versioned. Opening the
+    /// storage without history drops the history kept so far.
+    pub fn history_retention(mut self, versions: u64) -> Self {
+        self.history_retention = Some(versions);
+        self
+    }
+
+    pub(crate) fn retained_versions(&self) -> Option<u64> {
+        self.history_retention
+    }
+
+    pub(crate) fn buffers_wal_writes(&self) -> bool {
+        self.manual_wal_flush
+    }
+
+    /// Sets the options of the default column family holding subtrees data,
+    /// which are also the fallback of the other column families.
+    pub fn default_cf(mut self, options: ColumnFamilyOptions) -> Self {
+        self.default_cf = options;
+        self
+    }
+
+    /// Sets the options of the auxiliary data column family.
+    pub fn aux_cf(mut self, options: ColumnFamilyOptions) -> Self {
+        self.aux_cf = options;
+        self
+    }
+
+    /// Sets the options of the subtree roots column family.
+    pub fn roots_cf(mut self, options: ColumnFamilyOptions) -> Self {
+        self.roots_cf = options;
+        self
+    }
+
+    /// Sets the options of the metadata column family.
+    pub fn meta_cf(mut self, options: ColumnFamilyOptions) -> Self {
+        self.meta_cf = options;
+        self
+    }
+
+    /// Builds the database options together with the descriptors of GroveDb
+    /// column families.
+    pub(crate) fn build(&self) -> (rocksdb::Options, [ColumnFamilyDescriptor; 3]) {
+        let cache = self.block_cache_size.map(Cache::new_lru_cache);
+
+        let mut opts = rocksdb::Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        opts.set_atomic_flush(true);
+        opts.increase_parallelism(self.parallelism);
+        opts.set_allow_mmap_writes(self.allow_mmap_writes);
+        opts.set_allow_mmap_reads(self.allow_mmap_reads);
+        if let Some(max_open_files) = self.max_open_files {
+            opts.set_max_open_files(max_open_files);
+        }
+        if let Some(size) = self.max_total_wal_size {
+            opts.set_max_total_wal_size(size);
+        }
+        if let Some(seconds) = self.wal_ttl_seconds {
+            opts.set_wal_ttl_seconds(seconds);
+        }
+        if let Some(size) = self.wal_size_limit_mb {
+            opts.set_wal_size_limit_mb(size);
+        }
+        opts.set_manual_wal_flush(self.manual_wal_flush);
+
+        let cf_opts = |cf: &ColumnFamilyOptions| {
+            let mut cf_opts = opts.clone();
+            apply_column_family_options(&mut cf_opts, cf, &self.default_cf, cache.as_ref());
+            cf_opts
+        };
+        let descriptors = [
+            ColumnFamilyDescriptor::new(AUX_CF_NAME, cf_opts(&self.aux_cf)),
+            ColumnFamilyDescriptor::new(ROOTS_CF_NAME, cf_opts(&self.roots_cf)),
+            ColumnFamilyDescriptor::new(META_CF_NAME, cf_opts(&self.meta_cf)),
+        ];
+        apply_column_family_options(
+            &mut opts,
+            &self.default_cf,
+            &self.default_cf,
+            cache.as_ref(),
+        );
+
+        (opts, descriptors)
+    }
+}
+
+fn apply_column_family_options(
+    opts: &mut rocksdb::Options,
+    cf: &ColumnFamilyOptions,
+    fallback: &ColumnFamilyOptions,
+    cache: Option<&Cache>,
+) {
+    if let Some(compression) = cf.compression.or(fallback.compression) {
+        opts.set_compression_type(compression.into());
+    }
+    if let Some(size) = cf.write_buffer_size.or(fallback.write_buffer_size) {
+        opts.set_write_buffer_size(size);
+    }
+    if let Some(number) = cf
+        .max_write_buffer_number
+        .or(fallback.max_write_buffer_number)
+    {
+        opts.set_max_write_buffer_number(number);
+    }
+
+    let bloom_filter_bits_per_key = cf
+        .bloom_filter_bits_per_key
+        .or(fallback.bloom_filter_bits_per_key);
+    if cache.is_some() || bloom_filter_bits_per_key.is_some() {
+        let mut table_opts = BlockBasedOptions::default();
+        if let Some(cache) = cache {
+            table_opts.set_block_cache(cache);
+        }
+        if let Some(bits_per_key) = bloom_filter_bits_per_key {
+            table_opts.set_bloom_filter(bits_per_key, false);
+        }
+        opts.set_block_based_table_factory(&table_opts);
+    }
+}
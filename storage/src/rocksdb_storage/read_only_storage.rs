@@ -0,0 +1,220 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Read-only access to a RocksDB database used by another process.
+
+use std::path::Path;
+
+use grovedb_costs::{CostContext, CostResult, CostsExt, OperationCost};
+use grovedb_path::SubtreePath;
+use lazy_static::lazy_static;
+
+use super::{
+    storage::{ReadOnlyDb, SubtreePrefix, READ_ONLY_CHECKPOINTS_OPTS},
+    PrefixedRocksDbReadOnlyStorageContext, RocksDbOptions,
+};
+use crate::{
+    error::Error::{self, RocksDBError},
+    prefixed,
+    worst_case_costs::WorstKeyLength,
+    Storage, StorageBatch,
+};
+
+lazy_static! {
+    static ref SECONDARY_OPTS: rocksdb::Options = {
+        let mut opts = READ_ONLY_CHECKPOINTS_OPTS.clone();
+        // Secondary instances must keep all files open to follow the primary
+        opts.set_max_open_files(-1);
+        opts
+    };
+}
+
+/// Storage over a RocksDB database opened without write access, either
+/// read-only or as a secondary instance tailing a primary one.
+///
+/// A read-only instance sees the database as it was when opened, a secondary
+/// one moves forward on each
+/// [`try_catch_up_with_primary`](Self::try_catch_up_with_primary). Every
+/// write fails with a storage error.
+pub struct ReadOnlyRocksDbStorage {
+    db: ReadOnlyDb,
+    is_secondary: bool,
+}
+
+impl ReadOnlyRocksDbStorage {
+    /// Opens the database at `path` read-only.
+    pub fn read_only_rocksdb_with_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let db = ReadOnlyDb::open_cf_descriptors_read_only(
+            &READ_ONLY_CHECKPOINTS_OPTS,
+            &path,
+            RocksDbOptions::default().build().1,
+            false,
+        )
+        .map_err(RocksDBError)?;
+
+        Ok(ReadOnlyRocksDbStorage {
+            db,
+            is_secondary: false,
+        })
+    }
+
+    /// Opens the database at `primary_path` as a secondary instance, keeping
+    /// its own info logs in `secondary_path`.
+    pub fn secondary_rocksdb_with_paths<P: AsRef<Path>, S: AsRef<Path>>(
+        primary_path: P,
+        secondary_path: S,
+    ) -> Result<Self, Error> {
+        let db = ReadOnlyDb::open_cf_descriptors_as_secondary(
+            &SECONDARY_OPTS,
+            primary_path.as_ref(),
+            secondary_path.as_ref(),
+            RocksDbOptions::default().build().1,
+        )
+        .map_err(RocksDBError)?;
+
+        Ok(ReadOnlyRocksDbStorage {
+            db,
+            is_secondary: true,
+        })
+    }
+
+    /// Returns `true` if the database was opened as a secondary instance.
+    pub fn is_secondary(&self) -> bool {
+        self.is_secondary
+    }
+
+    /// Replays the primary's latest changes on a secondary instance.
+    pub fn try_catch_up_with_primary(&self) -> Result<(), Error> {
+        if !self.is_secondary {
+            return Err(Error::StorageError(
+                "only secondary instances can catch up with the primary".to_string(),
+            ));
+        }
+        self.db.try_catch_up_with_primary().map_err(RocksDBError)
+    }
+}
+
+/// Transaction over [`ReadOnlyRocksDbStorage`].
+///
+/// There is nothing to isolate without writes: reads go straight to the
+/// database and committing or rolling back does nothing.
+#[derive(Debug, Default)]
+pub struct ReadOnlyTransaction;
+
+impl<'db> Storage<'db> for ReadOnlyRocksDbStorage {
+    type BatchTransactionalStorageContext = PrefixedRocksDbReadOnlyStorageContext<'db>;
+    type ImmediateStorageContext = PrefixedRocksDbReadOnlyStorageContext<'db>;
+    type Transaction = ReadOnlyTransaction;
+
+    fn start_transaction(&'db self) -> Self::Transaction {
+        ReadOnlyTransaction
+    }
+
+    fn commit_transaction(&self, _transaction: Self::Transaction) -> CostResult<(), Error> {
+        Ok(()).wrap_with_cost(OperationCost::default())
+    }
+
+    fn rollback_transaction(&self, _transaction: &Self::Transaction) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn get_transactional_storage_context<'b, B>(
+        &'db self,
+        path: SubtreePath<'b, B>,
+        _batch: Option<&'db StorageBatch>,
+        _transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::BatchTransactionalStorageContext>
+    where
+        B: AsRef<[u8]> + 'b,
+    {
+        prefixed::build_prefix(path)
+            .map(|prefix| PrefixedRocksDbReadOnlyStorageContext::new(&self.db, prefix))
+    }
+
+    fn get_transactional_storage_context_by_subtree_prefix(
+        &'db self,
+        prefix: SubtreePrefix,
+        _batch: Option<&'db StorageBatch>,
+        _transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::BatchTransactionalStorageContext> {
+        PrefixedRocksDbReadOnlyStorageContext::new(&self.db, prefix)
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    fn get_immediate_storage_context<'b, B>(
+        &'db self,
+        path: SubtreePath<'b, B>,
+        _transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::ImmediateStorageContext>
+    where
+        B: AsRef<[u8]> + 'b,
+    {
+        prefixed::build_prefix(path)
+            .map(|prefix| PrefixedRocksDbReadOnlyStorageContext::new(&self.db, prefix))
+    }
+
+    fn get_immediate_storage_context_by_subtree_prefix(
+        &'db self,
+        prefix: SubtreePrefix,
+        _transaction: &'db Self::Transaction,
+    ) -> CostContext<Self::ImmediateStorageContext> {
+        PrefixedRocksDbReadOnlyStorageContext::new(&self.db, prefix)
+            .wrap_with_cost(OperationCost::default())
+    }
+
+    /// Empty batches are accepted since read operations may commit batches
+    /// they didn't write to, anything else fails.
+    fn commit_multi_context_batch(
+        &self,
+        batch: StorageBatch,
+        _transaction: Option<&'db Self::Transaction>,
+    ) -> CostResult<(), Error> {
+        if batch.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::StorageError(
+                "attempted to commit a batch to read-only storage".to_string(),
+            ))
+        }
+        .wrap_with_cost(OperationCost::default())
+    }
+
+    fn get_storage_context_cost<L: WorstKeyLength>(path: &[L]) -> OperationCost {
+        prefixed::storage_context_cost(path)
+    }
+
+    fn create_checkpoint<P: AsRef<Path>>(&self, _path: P) -> Result<(), Error> {
+        Err(Error::StorageError(
+            "checkpoints are not supported by read-only storage".to_string(),
+        ))
+    }
+}
@@ -28,7 +28,7 @@
 
 //! Implementation for a storage abstraction over RocksDB.
 
-use std::path::Path;
+use std::{path::Path, sync::Arc};
 
 use error::Error;
 use grovedb_costs::{
@@ -40,30 +40,25 @@ use grovedb_path::SubtreePath;
 use integer_encoding::VarInt;
 use lazy_static::lazy_static;
 use rocksdb::{
-    checkpoint::Checkpoint, ColumnFamily, ColumnFamilyDescriptor, OptimisticTransactionDB,
-    Transaction, WriteBatchWithTransaction, DEFAULT_COLUMN_FAMILY_NAME,
+    checkpoint::Checkpoint, ColumnFamily, OptimisticTransactionDB, Transaction,
+    WriteBatchWithTransaction, DEFAULT_COLUMN_FAMILY_NAME,
 };
 
-use super::{PrefixedRocksDbImmediateStorageContext, PrefixedRocksDbTransactionContext};
+use super::{
+    history, HistoricalRocksDbStorage, PrefixedRocksDbImmediateStorageContext,
+    PrefixedRocksDbTransactionContext, RocksDbOptions,
+};
 use crate::{
     error,
     error::Error::{CostError, RocksDBError},
+    prefixed,
     storage::AbstractBatchOperation,
     worst_case_costs::WorstKeyLength,
     Storage, StorageBatch,
 };
 
-const BLAKE_BLOCK_LEN: usize = 64;
 pub type SubtreePrefix = [u8; 32];
 
-fn blake_block_count(len: usize) -> usize {
-    if len == 0 {
-        1
-    } else {
-        1 + (len - 1) / BLAKE_BLOCK_LEN
-    }
-}
-
 /// Name of column family used to store auxiliary data
 pub(crate) const AUX_CF_NAME: &str = "aux";
 /// Name of column family used to store subtrees roots data
@@ -72,20 +67,7 @@ pub(crate) const ROOTS_CF_NAME: &str = "roots";
 pub(crate) const META_CF_NAME: &str = "meta";
 
 lazy_static! {
-    static ref DEFAULT_OPTS: rocksdb::Options = {
-        let mut opts = rocksdb::Options::default();
-        opts.create_if_missing(true);
-        opts.increase_parallelism(num_cpus::get() as i32);
-        opts.set_allow_mmap_writes(true);
-        opts.set_allow_mmap_reads(true);
-        opts.create_missing_column_families(true);
-        opts.set_atomic_flush(true);
-        opts
-    };
-}
-
-lazy_static! {
-    static ref READ_ONLY_CHECKPOINTS_OPTS: rocksdb::Options = {
+    pub(crate) static ref READ_ONLY_CHECKPOINTS_OPTS: rocksdb::Options = {
         let mut opts = rocksdb::Options::default();
         // Absolutely do NOT create or modify anything
         opts.create_if_missing(false);
@@ -112,6 +94,10 @@ pub(crate) type Db = OptimisticTransactionDB;
 /// Type alias for a transaction
 pub(crate) type Tx<'db> = Transaction<'db, Db>;
 
+/// Type alias for a database opened read-only or as a secondary instance,
+/// which is only possible without transaction support
+pub(crate) type ReadOnlyDb = rocksdb::DB;
+
 /// Storage which uses RocksDB as its backend.
 ///
 /// Uses `OptimisticTransactionDB` for transaction support. Optimistic
@@ -124,24 +110,37 @@ pub(crate) type Tx<'db> = Transaction<'db, Db>;
 /// See the [`Storage`] trait documentation for the single-writer
 /// requirement.
 pub struct RocksDbStorage {
-    db: OptimisticTransactionDB,
+    db: Arc<OptimisticTransactionDB>,
+    /// Number of versions kept readable if history is enabled
+    history_retention: Option<u64>,
+    /// Whether WAL writes are buffered until the storage is flushed
+    manual_wal_flush: bool,
 }
 const DEFAULT_LOG_SIZE_FOR_CHECKPOINT_FLUSH: u64 = u64::MAX; // Never flush
 
 impl RocksDbStorage {
     /// Create RocksDb storage with default parameters using `path`.
     pub fn default_rocksdb_with_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
-        let db = Db::open_cf_descriptors(
-            &DEFAULT_OPTS,
-            &path,
-            [
-                ColumnFamilyDescriptor::new(AUX_CF_NAME, DEFAULT_OPTS.clone()),
-                ColumnFamilyDescriptor::new(ROOTS_CF_NAME, DEFAULT_OPTS.clone()),
-                ColumnFamilyDescriptor::new(META_CF_NAME, DEFAULT_OPTS.clone()),
-            ],
-        )
-        .map_err(RocksDBError)?;
-        Ok(RocksDbStorage { db })
+        Self::rocksdb_with_path_and_options(path, &RocksDbOptions::default())
+    }
+
+    /// Create RocksDb storage tuned with `options` using `path`.
+    pub fn rocksdb_with_path_and_options<P: AsRef<Path>>(
+        path: P,
+        options: &RocksDbOptions,
+    ) -> Result<Self, Error> {
+        let (opts, column_families) = options.build();
+        let db = Db::open_cf_descriptors(&opts, &path, column_families).map_err(RocksDBError)?;
+        let history_retention = options.retained_versions();
+        match history_retention {
+            Some(_) => history::enable(&db)?,
+            None => history::disable(&db)?,
+        }
+        Ok(RocksDbStorage {
+            db: Arc::new(db),
+            history_retention,
+            manual_wal_flush: options.buffers_wal_writes(),
+        })
     }
 
     /// Create RocksDb storage with checkpoint parameters using `path` in read
@@ -150,42 +149,20 @@ impl RocksDbStorage {
         let db = Db::open_cf_descriptors(
             &READ_ONLY_CHECKPOINTS_OPTS,
             &path,
-            [
-                ColumnFamilyDescriptor::new(AUX_CF_NAME, DEFAULT_OPTS.clone()),
-                ColumnFamilyDescriptor::new(ROOTS_CF_NAME, DEFAULT_OPTS.clone()),
-                ColumnFamilyDescriptor::new(META_CF_NAME, DEFAULT_OPTS.clone()),
-            ],
+            RocksDbOptions::default().build().1,
         )
         .map_err(RocksDBError)?;
-        Ok(RocksDbStorage { db })
+        Ok(RocksDbStorage {
+            db: Arc::new(db),
+            history_retention: None,
+            manual_wal_flush: false,
+        })
     }
 
-    fn build_prefix_body<B>(path: SubtreePath<B>) -> (Vec<u8>, usize)
-    where
-        B: AsRef<[u8]>,
-    {
-        let segments_iter = path.into_reverse_iter();
-        let mut segments_count: usize = 0;
-        let mut res = Vec::new();
-        let mut lengths = Vec::new();
-
-        for s in segments_iter {
-            segments_count += 1;
-            res.extend_from_slice(s);
-            lengths.push(u8::try_from(s.len()).expect(
-                "path segment length must not exceed 255 bytes; \
-                 this is enforced at insert time via validate_key_length",
-            ));
-        }
-
-        // Note: this uses native-endian encoding. Changing to big-endian would
-        // be a breaking change since the output is hashed with Blake3 to produce
-        // subtree prefixes stored in RocksDB — existing databases would become
-        // unreadable. In practice GroveDB only targets little-endian platforms
-        // (x86_64, aarch64), so this is not a portability concern.
-        res.extend(segments_count.to_ne_bytes());
-        res.extend(lengths);
-        (res, segments_count)
+    /// Returns the value of the integer RocksDB property `name` of the
+    /// default column family, like `rocksdb.block-cache-capacity`.
+    pub fn property_int_value(&self, name: &str) -> Result<Option<u64>, Error> {
+        self.db.property_int_value(name).map_err(RocksDBError)
     }
 
     /// A helper method to build a prefix to rocksdb keys or identify a subtree
@@ -194,22 +171,32 @@ impl RocksDbStorage {
     where
         B: AsRef<[u8]>,
     {
-        let (body, segments_count) = Self::build_prefix_body(path);
-        if segments_count == 0 {
-            SubtreePrefix::default().wrap_with_cost(OperationCost::default())
-        } else {
-            let blocks_count = blake_block_count(body.len());
-            SubtreePrefix::from(blake3::hash(&body))
-                .wrap_with_cost(OperationCost::with_hash_node_calls(blocks_count as u32))
+        prefixed::build_prefix(path)
+    }
+
+    /// Returns the earliest and the latest versions which can be read with
+    /// [`Self::at_version`], `None` if history is disabled.
+    pub fn history_version_range(&self) -> Result<Option<(u64, u64)>, Error> {
+        if self.history_retention.is_none() {
+            return Ok(None);
         }
+        history::version_range(&self.db)
     }
 
-    fn worst_case_body_size<L: WorstKeyLength>(path: &[L]) -> usize {
-        // body = segment_bytes + segments_count.to_ne_bytes() + lengths
-        // segments_count.to_ne_bytes() contributes size_of::<usize>() bytes
-        path.iter().map(|a| a.max_length() as usize).sum::<usize>()
-            + std::mem::size_of::<usize>()
-            + path.len()
+    /// Returns a read-only view of the storage as it was once `version` got
+    /// committed.
+    pub fn at_version(&self, version: u64) -> Result<HistoricalRocksDbStorage, Error> {
+        match self.history_version_range()? {
+            Some((earliest, latest)) if (earliest..=latest).contains(&version) => {
+                Ok(HistoricalRocksDbStorage::new(self.db.clone(), version))
+            }
+            Some((earliest, latest)) => Err(Error::StorageError(format!(
+                "version {version} is not retained, available versions are {earliest} to {latest}"
+            ))),
+            None => Err(Error::StorageError(
+                "storage was opened without history".to_string(),
+            )),
+        }
     }
 
     /// Returns the write batch, with costs and pending costs
@@ -268,8 +255,13 @@ impl RocksDbStorage {
         // Until batch is committed these costs are pending (should not be added in case
         // of early termination).
         let mut pending_costs = OperationCost::default();
+        // Keys to journal, history reads don't count towards costs
+        let mut written = self.history_retention.map(|_| Vec::new());
 
         for op in storage_batch.into_iter() {
+            if let Some(written) = written.as_mut() {
+                written.push(history::written_key(&op));
+            }
             match op {
                 AbstractBatchOperation::Put {
                     key,
@@ -470,6 +462,13 @@ impl RocksDbStorage {
                 }
             }
         }
+
+        if let (Some(retention), Some(written)) = (self.history_retention, written) {
+            cost_return_on_error_no_add!(
+                cost,
+                history::journal(&self.db, retention, db_batch, written)
+            );
+        }
         Ok(pending_costs).wrap_with_cost(cost)
     }
 
@@ -506,6 +505,10 @@ impl RocksDbStorage {
         ] {
             self.wipe_column_family(cf_name)?;
         }
+        if self.history_retention.is_some() {
+            // History was wiped with the meta column family, start over
+            history::enable(&self.db)?;
+        }
         Ok(())
     }
 
@@ -565,6 +568,9 @@ impl<'db> Storage<'db> for RocksDbStorage {
     }
 
     fn flush(&self) -> Result<(), Error> {
+        if self.manual_wal_flush {
+            self.db.flush_wal(true).map_err(RocksDBError)?;
+        }
         self.db.flush().map_err(RocksDBError)
     }
 
@@ -628,14 +634,7 @@ impl<'db> Storage<'db> for RocksDbStorage {
     }
 
     fn get_storage_context_cost<L: WorstKeyLength>(path: &[L]) -> OperationCost {
-        if path.is_empty() {
-            OperationCost::default()
-        } else {
-            let body_size = Self::worst_case_body_size(path);
-            // the block size of blake3 is 64
-            let blocks_num = blake_block_count(body_size) as u32;
-            OperationCost::with_hash_node_calls(blocks_num)
-        }
+        prefixed::storage_context_cost(path)
     }
 
     fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
@@ -29,20 +29,17 @@
 //! Implementation of prefixed storage context.
 
 mod batch;
+mod context_historical;
 pub mod context_immediate;
+mod context_read_only;
 mod context_tx;
 mod raw_iterator;
 
 pub use batch::PrefixedRocksDbBatch;
+pub use context_historical::PrefixedRocksDbHistoricalStorageContext;
 pub use context_immediate::PrefixedRocksDbImmediateStorageContext;
+pub use context_read_only::PrefixedRocksDbReadOnlyStorageContext;
 pub use context_tx::PrefixedRocksDbTransactionContext;
-pub use raw_iterator::PrefixedRocksDbRawIterator;
+pub use raw_iterator::{PrefixedRocksDbHistoricalRawIterator, PrefixedRocksDbRawIterator};
 
-use super::storage::SubtreePrefix;
-
-/// Make prefixed key
-pub fn make_prefixed_key<K: AsRef<[u8]>>(prefix: &SubtreePrefix, key: K) -> Vec<u8> {
-    let mut prefix_vec = prefix.to_vec();
-    prefix_vec.extend_from_slice(key.as_ref());
-    prefix_vec
-}
+pub use crate::prefixed::make_prefixed_key;
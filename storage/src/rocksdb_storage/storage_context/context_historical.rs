@@ -0,0 +1,190 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//! Storage context over a past version of a database with history enabled.
+
+use error::Error;
+use grovedb_costs::{
+    storage_cost::key_value_cost::KeyValueStorageCost, ChildrenSizesWithIsSumTree, CostResult,
+    CostsExt,
+};
+
+use super::{
+    context_read_only::{get_cost, read_only_error},
+    make_prefixed_key, PrefixedRocksDbHistoricalRawIterator,
+};
+use crate::{
+    error,
+    prefixed::PrefixedMultiContextBatchPart,
+    rocksdb_storage::{
+        history::{Column, HistoricalRocksDbStorage},
+        storage::SubtreePrefix,
+    },
+    StorageBatch, StorageContext,
+};
+
+/// Storage context with a prefix applied to be used in a subtree as it was at
+/// a past version. Reads are charged as in a transactional context while
+/// every write fails.
+pub struct PrefixedRocksDbHistoricalStorageContext<'db> {
+    storage: &'db HistoricalRocksDbStorage,
+    prefix: SubtreePrefix,
+}
+
+impl<'db> PrefixedRocksDbHistoricalStorageContext<'db> {
+    /// Create a new prefixed historical context instance
+    pub fn new(storage: &'db HistoricalRocksDbStorage, prefix: SubtreePrefix) -> Self {
+        PrefixedRocksDbHistoricalStorageContext { storage, prefix }
+    }
+
+    /// Past versions cannot be cleared, always fails.
+    pub fn clear(&mut self) -> CostResult<(), Error> {
+        read_only_error("clear")
+    }
+
+    fn get_in<K: AsRef<[u8]>>(&self, column: Column, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.storage
+            .get(column, &make_prefixed_key(&self.prefix, key))
+            .wrap_fn_cost(get_cost)
+    }
+
+    fn raw_iter_in(&self, column: Column) -> PrefixedRocksDbHistoricalRawIterator<'db> {
+        // The live iterator comes first so that it misses no collected change
+        let live = self.storage.live_iter(column);
+        let changes = self.storage.changes(column, &self.prefix).ok();
+        PrefixedRocksDbHistoricalRawIterator::new(self.prefix, live, changes)
+    }
+}
+
+impl<'db> StorageContext<'db> for PrefixedRocksDbHistoricalStorageContext<'db> {
+    type Batch = PrefixedMultiContextBatchPart;
+    type RawIterator = PrefixedRocksDbHistoricalRawIterator<'db>;
+
+    fn put<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _children_sizes: ChildrenSizesWithIsSumTree,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("put")
+    }
+
+    fn put_aux<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("put_aux")
+    }
+
+    fn put_root<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("put_root")
+    }
+
+    fn put_meta<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("put_meta")
+    }
+
+    fn delete<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("delete")
+    }
+
+    fn delete_aux<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("delete_aux")
+    }
+
+    fn delete_root<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("delete_root")
+    }
+
+    fn delete_meta<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("delete_meta")
+    }
+
+    fn get<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_in(Column::Data, key)
+    }
+
+    fn get_aux<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_in(Column::Aux, key)
+    }
+
+    fn get_root<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_in(Column::Roots, key)
+    }
+
+    fn get_meta<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.get_in(Column::Meta, key)
+    }
+
+    fn new_batch(&self) -> Self::Batch {
+        PrefixedMultiContextBatchPart {
+            prefix: self.prefix,
+            batch: StorageBatch::new(),
+        }
+    }
+
+    fn commit_batch(&self, _batch: Self::Batch) -> CostResult<(), Error> {
+        read_only_error("commit_batch")
+    }
+
+    fn raw_iter(&self) -> Self::RawIterator {
+        self.raw_iter_in(Column::Data)
+    }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        self.raw_iter_in(Column::Meta)
+    }
+}
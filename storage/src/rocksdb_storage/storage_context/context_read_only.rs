@@ -0,0 +1,236 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Storage context over a database opened read-only or as a secondary
+//! instance.
+
+use error::Error;
+use grovedb_costs::{
+    storage_cost::key_value_cost::KeyValueStorageCost, ChildrenSizesWithIsSumTree, CostResult,
+    CostsExt, OperationCost,
+};
+use rocksdb::{ColumnFamily, DBRawIteratorWithThreadMode};
+
+use super::{make_prefixed_key, PrefixedRocksDbRawIterator};
+use crate::{
+    error,
+    error::Error::RocksDBError,
+    prefixed::PrefixedMultiContextBatchPart,
+    rocksdb_storage::storage::{
+        ReadOnlyDb, SubtreePrefix, AUX_CF_NAME, META_CF_NAME, ROOTS_CF_NAME,
+    },
+    StorageBatch, StorageContext,
+};
+
+/// Storage context with a prefix applied to be used in a subtree of a
+/// read-only database. Reads are charged as in a transactional context while
+/// every write fails.
+pub struct PrefixedRocksDbReadOnlyStorageContext<'db> {
+    storage: &'db ReadOnlyDb,
+    prefix: SubtreePrefix,
+}
+
+impl<'db> PrefixedRocksDbReadOnlyStorageContext<'db> {
+    /// Create a new prefixed read-only context instance
+    pub fn new(storage: &'db ReadOnlyDb, prefix: SubtreePrefix) -> Self {
+        PrefixedRocksDbReadOnlyStorageContext { storage, prefix }
+    }
+
+    /// Subtrees of a read-only database cannot be cleared, always fails.
+    pub fn clear(&mut self) -> CostResult<(), Error> {
+        read_only_error("clear")
+    }
+
+    /// Get auxiliary data column family
+    fn cf_aux(&self) -> &'db ColumnFamily {
+        self.storage
+            .cf_handle(AUX_CF_NAME)
+            .expect("aux column family must exist")
+    }
+
+    /// Get trees roots data column family
+    fn cf_roots(&self) -> &'db ColumnFamily {
+        self.storage
+            .cf_handle(ROOTS_CF_NAME)
+            .expect("roots column family must exist")
+    }
+
+    /// Get metadata column family
+    fn cf_meta(&self) -> &'db ColumnFamily {
+        self.storage
+            .cf_handle(META_CF_NAME)
+            .expect("meta column family must exist")
+    }
+}
+
+pub(super) fn read_only_error(operation: &str) -> CostResult<(), Error> {
+    Err(Error::StorageError(format!(
+        "attempted {operation} operation on read-only storage"
+    )))
+    .wrap_with_cost(OperationCost::default())
+}
+
+pub(super) fn get_cost(value: &Result<Option<Vec<u8>>, Error>) -> OperationCost {
+    OperationCost {
+        seek_count: 1,
+        storage_loaded_bytes: value
+            .as_ref()
+            .ok()
+            .and_then(Option::as_ref)
+            .map(|x| x.len() as u64)
+            .unwrap_or(0),
+        ..Default::default()
+    }
+}
+
+impl<'db> StorageContext<'db> for PrefixedRocksDbReadOnlyStorageContext<'db> {
+    type Batch = PrefixedMultiContextBatchPart;
+    type RawIterator = PrefixedRocksDbRawIterator<DBRawIteratorWithThreadMode<'db, ReadOnlyDb>>;
+
+    fn put<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _children_sizes: ChildrenSizesWithIsSumTree,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("put")
+    }
+
+    fn put_aux<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("put_aux")
+    }
+
+    fn put_root<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("put_root")
+    }
+
+    fn put_meta<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _value: &[u8],
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("put_meta")
+    }
+
+    fn delete<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("delete")
+    }
+
+    fn delete_aux<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("delete_aux")
+    }
+
+    fn delete_root<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("delete_root")
+    }
+
+    fn delete_meta<K: AsRef<[u8]>>(
+        &self,
+        _key: K,
+        _cost_info: Option<KeyValueStorageCost>,
+    ) -> CostResult<(), Error> {
+        read_only_error("delete_meta")
+    }
+
+    fn get<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.storage
+            .get(make_prefixed_key(&self.prefix, key))
+            .map_err(RocksDBError)
+            .wrap_fn_cost(get_cost)
+    }
+
+    fn get_aux<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.storage
+            .get_cf(self.cf_aux(), make_prefixed_key(&self.prefix, key))
+            .map_err(RocksDBError)
+            .wrap_fn_cost(get_cost)
+    }
+
+    fn get_root<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.storage
+            .get_cf(self.cf_roots(), make_prefixed_key(&self.prefix, key))
+            .map_err(RocksDBError)
+            .wrap_fn_cost(get_cost)
+    }
+
+    fn get_meta<K: AsRef<[u8]>>(&self, key: K) -> CostResult<Option<Vec<u8>>, Error> {
+        self.storage
+            .get_cf(self.cf_meta(), make_prefixed_key(&self.prefix, key))
+            .map_err(RocksDBError)
+            .wrap_fn_cost(get_cost)
+    }
+
+    fn new_batch(&self) -> Self::Batch {
+        PrefixedMultiContextBatchPart {
+            prefix: self.prefix,
+            batch: StorageBatch::new(),
+        }
+    }
+
+    fn commit_batch(&self, _batch: Self::Batch) -> CostResult<(), Error> {
+        read_only_error("commit_batch")
+    }
+
+    fn raw_iter(&self) -> Self::RawIterator {
+        PrefixedRocksDbRawIterator {
+            prefix: self.prefix,
+            raw_iterator: self.storage.raw_iterator(),
+        }
+    }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        PrefixedRocksDbRawIterator {
+            prefix: self.prefix,
+            raw_iterator: self.storage.raw_iterator_cf(self.cf_meta()),
+        }
+    }
+}
@@ -35,10 +35,11 @@ use grovedb_costs::{
 };
 use rocksdb::{ColumnFamily, DBRawIteratorWithThreadMode};
 
-use super::{batch::PrefixedMultiContextBatchPart, make_prefixed_key, PrefixedRocksDbRawIterator};
+use super::{make_prefixed_key, PrefixedRocksDbRawIterator};
 use crate::{
     error,
     error::Error::RocksDBError,
+    prefixed::PrefixedMultiContextBatchPart,
     rocksdb_storage::storage::{Db, SubtreePrefix, Tx, AUX_CF_NAME, META_CF_NAME, ROOTS_CF_NAME},
     RawIterator, StorageBatch, StorageContext,
 };
@@ -385,4 +386,11 @@ impl<'db> StorageContext<'db> for PrefixedRocksDbTransactionContext<'db> {
             raw_iterator: self.transaction.raw_iterator(),
         }
     }
+
+    fn raw_iter_meta(&self) -> Self::RawIterator {
+        PrefixedRocksDbRawIterator {
+            prefix: self.prefix,
+            raw_iterator: self.transaction.raw_iterator_cf(self.cf_meta()),
+        }
+    }
 }
@@ -28,25 +28,41 @@
 
 //! Prefixed storage_cost raw iterator implementation for RocksDB backend.
 
+use std::{collections::BTreeMap, ops::Bound};
+
 use grovedb_costs::{CostContext, CostsExt, OperationCost};
-use rocksdb::DBRawIteratorWithThreadMode;
+use rocksdb::{DBAccess, DBRawIteratorWithThreadMode};
 
 use super::make_prefixed_key;
 use crate::{
-    rocksdb_storage::storage::{Db, SubtreePrefix, Tx},
+    rocksdb_storage::storage::{Db, SubtreePrefix},
     RawIterator,
 };
 
 /// 256 bytes for the key and 32 bytes for the prefix
 const MAX_PREFIXED_KEY_LENGTH: u64 = 256 + 32;
 
+/// Returns the prefix following `prefix`, which bounds the keys starting with
+/// it from above.
+fn prefix_upper_bound(prefix: &SubtreePrefix) -> Vec<u8> {
+    let mut prefix_vec = prefix.to_vec();
+    for i in (0..prefix_vec.len()).rev() {
+        prefix_vec[i] = prefix_vec[i].wrapping_add(1);
+        if prefix_vec[i] != 0 {
+            // if it is == 0 then we need to go to next bit
+            break;
+        }
+    }
+    prefix_vec
+}
+
 /// Raw iterator over prefixed storage_cost.
 pub struct PrefixedRocksDbRawIterator<I> {
     pub(super) prefix: SubtreePrefix,
     pub(super) raw_iterator: I,
 }
 
-impl RawIterator for PrefixedRocksDbRawIterator<DBRawIteratorWithThreadMode<'_, Db>> {
+impl<D: DBAccess> RawIterator for PrefixedRocksDbRawIterator<DBRawIteratorWithThreadMode<'_, D>> {
     fn seek_to_first(&mut self) -> CostContext<()> {
         self.raw_iterator.seek(self.prefix);
         ().wrap_with_cost(OperationCost::with_seek_count(1))
@@ -58,15 +74,8 @@ impl RawIterator for PrefixedRocksDbRawIterator<DBRawIteratorWithThreadMode<'_,
     /// this cannot happen in practice: prefixes are 32-byte Blake3 hashes,
     /// so an all-0xFF prefix has probability 1/2^256 — effectively zero.
     fn seek_to_last(&mut self) -> CostContext<()> {
-        let mut prefix_vec = self.prefix.to_vec();
-        for i in (0..prefix_vec.len()).rev() {
-            prefix_vec[i] = prefix_vec[i].wrapping_add(1);
-            if prefix_vec[i] != 0 {
-                // if it is == 0 then we need to go to next bit
-                break;
-            }
-        }
-        self.raw_iterator.seek_for_prev(prefix_vec);
+        self.raw_iterator
+            .seek_for_prev(prefix_upper_bound(&self.prefix));
         ().wrap_with_cost(OperationCost::with_seek_count(1))
     }
 
@@ -171,55 +180,181 @@ impl RawIterator for PrefixedRocksDbRawIterator<DBRawIteratorWithThreadMode<'_,
     }
 }
 
-impl<'a> RawIterator for PrefixedRocksDbRawIterator<DBRawIteratorWithThreadMode<'a, Tx<'a>>> {
-    fn seek_to_first(&mut self) -> CostContext<()> {
-        self.raw_iterator.seek(self.prefix);
-        ().wrap_with_cost(OperationCost::with_seek_count(1))
+/// Raw iterator over a subtree as it was at a past version.
+///
+/// A live iterator over the subtree is merged lazily with the subtree's
+/// changes since that version, which are collected when the iterator is
+/// created. Like a RocksDB iterator hitting an error, it is left empty if
+/// they can't be read. Costs are the same as for
+/// [`PrefixedRocksDbRawIterator`].
+pub struct PrefixedRocksDbHistoricalRawIterator<'db> {
+    prefix: SubtreePrefix,
+    live: DBRawIteratorWithThreadMode<'db, Db>,
+    /// Past values of the keys changed since the version, `None` for keys
+    /// that didn't exist then. The live records of these keys are skipped.
+    changes: Option<BTreeMap<Vec<u8>, Option<Vec<u8>>>>,
+    current: Option<HistoricalPosition>,
+}
+
+enum HistoricalPosition {
+    /// On the live record the live iterator points at
+    Live,
+    /// On the past value of a changed key
+    Changed(Vec<u8>),
+}
+
+impl<'db> PrefixedRocksDbHistoricalRawIterator<'db> {
+    pub(super) fn new(
+        prefix: SubtreePrefix,
+        live: DBRawIteratorWithThreadMode<'db, Db>,
+        changes: Option<BTreeMap<Vec<u8>, Option<Vec<u8>>>>,
+    ) -> Self {
+        PrefixedRocksDbHistoricalRawIterator {
+            prefix,
+            live,
+            changes,
+            current: None,
+        }
     }
 
-    /// See the Db impl above for why the all-0xFF overflow case is not a
-    /// concern (Blake3 hash output, 1/2^256 probability).
-    fn seek_to_last(&mut self) -> CostContext<()> {
-        let mut prefix_vec = self.prefix.to_vec();
-        for i in (0..prefix_vec.len()).rev() {
-            prefix_vec[i] = prefix_vec[i].wrapping_add(1);
-            if prefix_vec[i] != 0 {
-                // if it is == 0 then we need to go to next bit
-                break;
+    /// Moves the live iterator forward past changed keys and positions the
+    /// iterator on the least of its record and the next past value in
+    /// `range`.
+    fn settle_forward(&mut self, range: (Bound<&[u8]>, Bound<&[u8]>)) -> CostContext<()> {
+        let Some(changes) = self.changes.take() else {
+            return ().wrap_with_cost(OperationCost::with_seek_count(1));
+        };
+        while self
+            .live
+            .key()
+            .is_some_and(|key| key.starts_with(&self.prefix) && changes.contains_key(key))
+        {
+            self.live.next();
+        }
+        let live = self.live.key().filter(|key| key.starts_with(&self.prefix));
+        let changed = changes
+            .range::<[u8], _>(range)
+            .find(|(_, value)| value.is_some())
+            .map(|(key, _)| key);
+        self.current = match (live, changed) {
+            (Some(live), Some(changed)) if changed.as_slice() < live => {
+                Some(HistoricalPosition::Changed(changed.clone()))
             }
+            (Some(_), _) => Some(HistoricalPosition::Live),
+            (None, changed) => changed.cloned().map(HistoricalPosition::Changed),
+        };
+        self.changes = Some(changes);
+        ().wrap_with_cost(OperationCost::with_seek_count(1))
+    }
+
+    /// Moves the live iterator backward past changed keys and positions the
+    /// iterator on the greatest of its record and the previous past value
+    /// in `range`.
+    fn settle_backward(&mut self, range: (Bound<&[u8]>, Bound<&[u8]>)) -> CostContext<()> {
+        let Some(changes) = self.changes.take() else {
+            return ().wrap_with_cost(OperationCost::with_seek_count(1));
+        };
+        while self
+            .live
+            .key()
+            .is_some_and(|key| key.starts_with(&self.prefix) && changes.contains_key(key))
+        {
+            self.live.prev();
         }
-        self.raw_iterator.seek_for_prev(prefix_vec);
+        let live = self.live.key().filter(|key| key.starts_with(&self.prefix));
+        let changed = changes
+            .range::<[u8], _>(range)
+            .rev()
+            .find(|(_, value)| value.is_some())
+            .map(|(key, _)| key);
+        self.current = match (live, changed) {
+            (Some(live), Some(changed)) if changed.as_slice() > live => {
+                Some(HistoricalPosition::Changed(changed.clone()))
+            }
+            (Some(_), _) => Some(HistoricalPosition::Live),
+            (None, changed) => changed.cloned().map(HistoricalPosition::Changed),
+        };
+        self.changes = Some(changes);
         ().wrap_with_cost(OperationCost::with_seek_count(1))
     }
 
+    /// Returns the prefixed key the iterator is positioned on.
+    fn current_key(&self) -> Option<&[u8]> {
+        match self.current.as_ref()? {
+            HistoricalPosition::Live => self.live.key(),
+            HistoricalPosition::Changed(key) => Some(key),
+        }
+    }
+
+    fn current_value(&self) -> Option<&[u8]> {
+        match self.current.as_ref()? {
+            HistoricalPosition::Live => self.live.value(),
+            HistoricalPosition::Changed(key) => self
+                .changes
+                .as_ref()
+                .and_then(|changes| changes.get(key))
+                .and_then(|value| value.as_deref()),
+        }
+    }
+}
+
+impl RawIterator for PrefixedRocksDbHistoricalRawIterator<'_> {
+    fn seek_to_first(&mut self) -> CostContext<()> {
+        let prefix = self.prefix;
+        self.live.seek(prefix);
+        self.settle_forward((Bound::Included(&prefix), Bound::Unbounded))
+    }
+
+    fn seek_to_last(&mut self) -> CostContext<()> {
+        let upper = prefix_upper_bound(&self.prefix);
+        self.live.seek_for_prev(&upper);
+        if self.live.key() == Some(upper.as_slice()) {
+            self.live.prev();
+        }
+        self.settle_backward((Bound::Unbounded, Bound::Excluded(&upper)))
+    }
+
     fn seek<K: AsRef<[u8]>>(&mut self, key: K) -> CostContext<()> {
-        self.raw_iterator.seek(make_prefixed_key(&self.prefix, key));
-        ().wrap_with_cost(OperationCost::with_seek_count(1))
+        let key = make_prefixed_key(&self.prefix, key);
+        self.live.seek(&key);
+        self.settle_forward((Bound::Included(&key), Bound::Unbounded))
     }
 
     fn seek_for_prev<K: AsRef<[u8]>>(&mut self, key: K) -> CostContext<()> {
-        self.raw_iterator
-            .seek_for_prev(make_prefixed_key(&self.prefix, key));
-        ().wrap_with_cost(OperationCost::with_seek_count(1))
+        let key = make_prefixed_key(&self.prefix, key);
+        self.live.seek_for_prev(&key);
+        self.settle_backward((Bound::Unbounded, Bound::Included(&key)))
     }
 
     fn next(&mut self) -> CostContext<()> {
-        self.raw_iterator.next();
-        ().wrap_with_cost(OperationCost::with_seek_count(1))
+        let Some(key) = self.current_key().map(|key| key.to_vec()) else {
+            return ().wrap_with_cost(OperationCost::with_seek_count(1));
+        };
+        match self.current {
+            Some(HistoricalPosition::Live) => self.live.next(),
+            // The live iterator may be anywhere around a past value
+            _ => self.live.seek(&key),
+        }
+        self.settle_forward((Bound::Excluded(&key), Bound::Unbounded))
     }
 
     fn prev(&mut self) -> CostContext<()> {
-        self.raw_iterator.prev();
-        ().wrap_with_cost(OperationCost::with_seek_count(1))
+        let Some(key) = self.current_key().map(|key| key.to_vec()) else {
+            return ().wrap_with_cost(OperationCost::with_seek_count(1));
+        };
+        match self.current {
+            Some(HistoricalPosition::Live) => self.live.prev(),
+            _ => self.live.seek_for_prev(&key),
+        }
+        self.settle_backward((Bound::Unbounded, Bound::Excluded(&key)))
     }
 
     fn value(&self) -> CostContext<Option<&[u8]>> {
         let mut cost = OperationCost::default();
 
         let value = if self.valid().unwrap_add_cost(&mut cost) {
-            self.raw_iterator
-                .value()
-                .inspect(|v| cost.storage_loaded_bytes += v.len() as u64)
+            self.current_value()
+                .inspect(|value| cost.storage_loaded_bytes += value.len() as u64)
         } else {
             None
         };
@@ -230,29 +365,13 @@ impl<'a> RawIterator for PrefixedRocksDbRawIterator<DBRawIteratorWithThreadMode<
     fn key(&self) -> CostContext<Option<&[u8]>> {
         let mut cost = OperationCost::default();
 
-        let value = match self.raw_iterator.key() {
+        let value = match self.current_key() {
             Some(k) => {
-                // Even if we truncate prefix, loaded cost should be maximum for the whole
-                // function
-                if k.starts_with(&self.prefix) {
-                    cost.storage_loaded_bytes += k.len() as u64;
-                    Some(k.split_at(self.prefix.len()).1)
-                } else {
-                    // we can think of the underlying storage layer as stacked blocks
-                    // and a block is a collection of key value pairs with the
-                    // same prefix.
-                    // if we are at the last key in a block and we try to
-                    // check for the next key, we should not add the next block's first key
-                    // len() as that will make cost depend on the ordering of blocks.
-                    // instead we should add a fixed sized cost for such boundary checks
-                    cost.storage_loaded_bytes += MAX_PREFIXED_KEY_LENGTH;
-                    None
-                }
+                cost.storage_loaded_bytes += k.len() as u64;
+                Some(k.split_at(self.prefix.len()).1)
             }
             None => {
-                // if we are at the last key in the last block we should also add
-                // a fixed sized cost rather than nothing, as a change in block ordering
-                // could move the last block to some other position.
+                // Past either end of the subtree, see `PrefixedRocksDbRawIterator`
                 cost.storage_loaded_bytes += MAX_PREFIXED_KEY_LENGTH;
                 None
             }
@@ -264,31 +383,17 @@ impl<'a> RawIterator for PrefixedRocksDbRawIterator<DBRawIteratorWithThreadMode<
     fn valid(&self) -> CostContext<bool> {
         let mut cost = OperationCost::default();
 
-        self.raw_iterator
-            .key()
-            .map(|k| {
-                if k.starts_with(&self.prefix) {
-                    cost.storage_loaded_bytes += k.len() as u64;
-                    true
-                } else {
-                    // we can think of the underlying storage layer as stacked blocks
-                    // and a block is a collection of key value pairs with the
-                    // same prefix.
-                    // if we are at the last key in a block and we try to
-                    // check for the next key, we should not add the next block's first key
-                    // len() as that will make cost depend on the ordering of blocks.
-                    // instead we should add a fixed sized cost for such boundary checks
-                    cost.storage_loaded_bytes += MAX_PREFIXED_KEY_LENGTH;
-                    false
-                }
-            })
-            .unwrap_or_else(|| {
-                // if we are at the last key in the last block we should also add
-                // a fixed sized cost rather than nothing, as a change in block ordering
-                // could move the last block to some other position.
+        let valid = match self.current_key() {
+            Some(k) => {
+                cost.storage_loaded_bytes += k.len() as u64;
+                true
+            }
+            None => {
                 cost.storage_loaded_bytes += MAX_PREFIXED_KEY_LENGTH;
                 false
-            })
-            .wrap_with_cost(cost)
+            }
+        };
+
+        valid.wrap_with_cost(cost)
     }
 }
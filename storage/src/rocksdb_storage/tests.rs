@@ -543,6 +543,56 @@ mod immediate_storage {
             assert!(expected_iter.next().is_none());
         }
     }
+
+    #[test]
+    fn test_raw_iterator_over_meta() {
+        let storage = TempStorage::new();
+        let tx = storage.start_transaction();
+        let context = storage
+            .get_immediate_storage_context([b"someprefix"].as_ref().into(), &tx)
+            .unwrap();
+        context
+            .put(b"key0", b"value0", None, None)
+            .unwrap()
+            .expect("expected successful insertion");
+        context
+            .put_meta(b"meta1", b"value1", None)
+            .unwrap()
+            .expect("expected successful insertion");
+        context
+            .put_meta(b"meta0", b"value0", None)
+            .unwrap()
+            .expect("expected successful insertion");
+        storage
+            .get_immediate_storage_context([b"zanothersomeprefix"].as_ref().into(), &tx)
+            .unwrap()
+            .put_meta(b"meta2", b"value2", None)
+            .unwrap()
+            .expect("expected successful insertion");
+
+        // Only the metadata of the subtree is iterated over, without its data
+        let mut iter = context.raw_iter_meta();
+        let mut entries = Vec::new();
+        iter.seek_to_first().unwrap();
+        while iter.valid().unwrap() {
+            entries.push((
+                iter.key().unwrap().unwrap().to_vec(),
+                iter.value().unwrap().unwrap().to_vec(),
+            ));
+            iter.next().unwrap();
+        }
+        assert_eq!(
+            entries,
+            vec![
+                (b"meta0".to_vec(), b"value0".to_vec()),
+                (b"meta1".to_vec(), b"value1".to_vec()),
+            ]
+        );
+
+        let mut iter = context.raw_iter_meta();
+        iter.seek(b"meta1").unwrap();
+        assert_eq!(iter.key().unwrap(), Some(b"meta1".as_ref()));
+    }
 }
 
 mod batch_no_transaction {
@@ -1568,3 +1618,312 @@ mod transactional_context_without_batch {
         );
     }
 }
+
+mod read_only_storage {
+    use tempfile::TempDir;
+
+    use crate::{
+        rocksdb_storage::{ReadOnlyRocksDbStorage, RocksDbStorage},
+        RawIterator, Storage, StorageBatch, StorageContext,
+    };
+
+    fn put_committed(storage: &RocksDbStorage, key: &[u8], value: &[u8]) {
+        let batch = StorageBatch::new();
+        let transaction = storage.start_transaction();
+        storage
+            .get_transactional_storage_context(
+                [b"ayya"].as_ref().into(),
+                Some(&batch),
+                &transaction,
+            )
+            .unwrap()
+            .put(key, value, None, None)
+            .unwrap()
+            .expect("cannot insert data");
+        storage
+            .commit_multi_context_batch(batch, Some(&transaction))
+            .unwrap()
+            .expect("cannot commit batch");
+        storage
+            .commit_transaction(transaction)
+            .unwrap()
+            .expect("cannot commit transaction");
+    }
+
+    #[test]
+    fn test_read_only_sees_data_and_rejects_writes() {
+        let primary_dir = TempDir::new().expect("cannot create tempdir");
+        let primary = RocksDbStorage::default_rocksdb_with_path(primary_dir.path())
+            .expect("cannot open rocksdb storage");
+        put_committed(&primary, b"key1", b"value1");
+        put_committed(&primary, b"key2", b"value2");
+        primary.flush().expect("cannot flush");
+
+        let read_only = ReadOnlyRocksDbStorage::read_only_rocksdb_with_path(primary_dir.path())
+            .expect("cannot open read-only storage");
+        assert!(!read_only.is_secondary());
+        assert!(read_only.try_catch_up_with_primary().is_err());
+
+        let transaction = read_only.start_transaction();
+        let batch = StorageBatch::new();
+        let context = read_only
+            .get_transactional_storage_context(
+                [b"ayya"].as_ref().into(),
+                Some(&batch),
+                &transaction,
+            )
+            .unwrap();
+
+        let get = context.get(b"key1");
+        assert_eq!(get.cost.seek_count, 1);
+        assert_eq!(get.cost.storage_loaded_bytes, 6);
+        assert_eq!(
+            get.value.expect("cannot get data"),
+            Some(b"value1".to_vec())
+        );
+
+        let mut iter = context.raw_iter();
+        iter.seek_to_last().unwrap();
+        assert_eq!(iter.key().unwrap(), Some(b"key2".as_ref()));
+
+        assert!(context
+            .put(b"key3", b"value3", None, None)
+            .unwrap()
+            .is_err());
+        assert!(context.delete_aux(b"key1", None).unwrap().is_err());
+        assert!(read_only
+            .commit_multi_context_batch(StorageBatch::new(), Some(&transaction))
+            .unwrap()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_secondary_catches_up_with_primary() {
+        let primary_dir = TempDir::new().expect("cannot create tempdir");
+        let secondary_dir = TempDir::new().expect("cannot create tempdir");
+        let primary = RocksDbStorage::default_rocksdb_with_path(primary_dir.path())
+            .expect("cannot open rocksdb storage");
+        put_committed(&primary, b"key1", b"value1");
+
+        let secondary = ReadOnlyRocksDbStorage::secondary_rocksdb_with_paths(
+            primary_dir.path(),
+            secondary_dir.path(),
+        )
+        .expect("cannot open secondary storage");
+        assert!(secondary.is_secondary());
+
+        put_committed(&primary, b"key2", b"value2");
+        primary.flush().expect("cannot flush");
+
+        secondary
+            .try_catch_up_with_primary()
+            .expect("cannot catch up with primary");
+        let transaction = secondary.start_transaction();
+        let context = secondary
+            .get_transactional_storage_context([b"ayya"].as_ref().into(), None, &transaction)
+            .unwrap();
+        assert_eq!(
+            context.get(b"key1").unwrap().expect("cannot get data"),
+            Some(b"value1".to_vec())
+        );
+        assert_eq!(
+            context.get(b"key2").unwrap().expect("cannot get data"),
+            Some(b"value2".to_vec())
+        );
+    }
+}
+
+mod history {
+    use tempfile::TempDir;
+
+    use crate::{
+        rocksdb_storage::{RocksDbOptions, RocksDbStorage},
+        RawIterator, Storage, StorageBatch, StorageContext,
+    };
+
+    /// Commits puts, `None` values being deletions, as a single version.
+    fn commit(storage: &RocksDbStorage, writes: &[(&[u8], Option<&[u8]>)]) {
+        let batch = StorageBatch::new();
+        let transaction = storage.start_transaction();
+        let context = storage
+            .get_transactional_storage_context(
+                [b"ayya"].as_ref().into(),
+                Some(&batch),
+                &transaction,
+            )
+            .unwrap();
+        for (key, value) in writes {
+            match value {
+                Some(value) => context.put(key, value, None, None),
+                None => context.delete(key, None),
+            }
+            .unwrap()
+            .expect("cannot write data");
+        }
+        storage
+            .commit_multi_context_batch(batch, Some(&transaction))
+            .unwrap()
+            .expect("cannot commit batch");
+        storage
+            .commit_transaction(transaction)
+            .unwrap()
+            .expect("cannot commit transaction");
+    }
+
+    /// Returns the key and the value `iter` points at, if valid.
+    fn current_entry<I: RawIterator>(iter: &I) -> Option<(Vec<u8>, Vec<u8>)> {
+        iter.valid().unwrap().then(|| {
+            (
+                iter.key().unwrap().expect("valid iterator").to_vec(),
+                iter.value().unwrap().expect("valid iterator").to_vec(),
+            )
+        })
+    }
+
+    fn entries_at_version(storage: &RocksDbStorage, version: u64) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let past = storage.at_version(version).expect("version is retained");
+        let transaction = past.start_transaction();
+        let context = past
+            .get_transactional_storage_context([b"ayya"].as_ref().into(), None, &transaction)
+            .unwrap();
+
+        let mut entries = Vec::new();
+        let mut iter = context.raw_iter();
+        iter.seek_to_first().unwrap();
+        while let Some(entry) = current_entry(&iter) {
+            entries.push(entry);
+            iter.next().unwrap();
+        }
+
+        // Point reads agree with iteration
+        for key in [b"key1", b"key2", b"key3"] {
+            assert_eq!(
+                context.get(key).unwrap().expect("cannot get data"),
+                entries
+                    .iter()
+                    .find(|(k, _)| k.as_slice() == key)
+                    .map(|(_, v)| v.clone())
+            );
+        }
+        entries
+    }
+
+    #[test]
+    fn test_past_versions_are_readable_until_pruned() {
+        let tmp_dir = TempDir::new().expect("cannot create tempdir");
+        let storage = RocksDbStorage::rocksdb_with_path_and_options(
+            tmp_dir.path(),
+            &RocksDbOptions::default().history_retention(3),
+        )
+        .expect("cannot open rocksdb storage");
+        assert_eq!(storage.history_version_range().unwrap(), Some((0, 0)));
+
+        commit(&storage, &[(b"key1", Some(b"a")), (b"key2", Some(b"b"))]);
+        commit(&storage, &[(b"key1", Some(b"c")), (b"key2", None)]);
+        commit(&storage, &[(b"key3", Some(b"d"))]);
+        assert_eq!(storage.history_version_range().unwrap(), Some((1, 3)));
+        assert!(storage.at_version(0).is_err());
+        assert!(storage.at_version(4).is_err());
+
+        assert_eq!(
+            entries_at_version(&storage, 1),
+            vec![
+                (b"key1".to_vec(), b"a".to_vec()),
+                (b"key2".to_vec(), b"b".to_vec())
+            ]
+        );
+        assert_eq!(
+            entries_at_version(&storage, 2),
+            vec![(b"key1".to_vec(), b"c".to_vec())]
+        );
+        assert_eq!(
+            entries_at_version(&storage, 3),
+            vec![
+                (b"key1".to_vec(), b"c".to_vec()),
+                (b"key3".to_vec(), b"d".to_vec())
+            ]
+        );
+
+        let past = storage.at_version(2).unwrap();
+        let transaction = past.start_transaction();
+        assert!(past
+            .get_transactional_storage_context([b"ayya"].as_ref().into(), None, &transaction)
+            .unwrap()
+            .put(b"key1", b"e", None, None)
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn test_past_version_iterator_merges_current_records_with_changes() {
+        let tmp_dir = TempDir::new().expect("cannot create tempdir");
+        let storage = RocksDbStorage::rocksdb_with_path_and_options(
+            tmp_dir.path(),
+            &RocksDbOptions::default().history_retention(3),
+        )
+        .expect("cannot open rocksdb storage");
+
+        commit(
+            &storage,
+            &[
+                (b"key1", Some(b"a")),
+                (b"key3", Some(b"b")),
+                (b"key5", Some(b"c")),
+            ],
+        );
+        commit(
+            &storage,
+            &[
+                (b"key2", Some(b"d")),
+                (b"key3", None),
+                (b"key5", Some(b"e")),
+            ],
+        );
+
+        let past = storage.at_version(1).expect("version is retained");
+        let transaction = past.start_transaction();
+        let context = past
+            .get_transactional_storage_context([b"ayya"].as_ref().into(), None, &transaction)
+            .unwrap();
+        let mut iter = context.raw_iter();
+        iter.seek_to_last().unwrap();
+        let mut entries = Vec::new();
+        while let Some(entry) = current_entry(&iter) {
+            entries.push(entry);
+            iter.prev().unwrap();
+        }
+        assert_eq!(
+            entries,
+            vec![
+                (b"key5".to_vec(), b"c".to_vec()),
+                (b"key3".to_vec(), b"b".to_vec()),
+                (b"key1".to_vec(), b"a".to_vec()),
+            ]
+        );
+
+        iter.seek(b"key2").unwrap();
+        assert_eq!(
+            current_entry(&iter),
+            Some((b"key3".to_vec(), b"b".to_vec()))
+        );
+        iter.next().unwrap();
+        assert_eq!(
+            current_entry(&iter),
+            Some((b"key5".to_vec(), b"c".to_vec()))
+        );
+        iter.prev().unwrap();
+        iter.prev().unwrap();
+        assert_eq!(
+            current_entry(&iter),
+            Some((b"key1".to_vec(), b"a".to_vec()))
+        );
+        iter.seek_for_prev(b"key4").unwrap();
+        assert_eq!(
+            current_entry(&iter),
+            Some((b"key3".to_vec(), b"b".to_vec()))
+        );
+        iter.next().unwrap();
+        iter.next().unwrap();
+        assert_eq!(current_entry(&iter), None);
+    }
+}
@@ -234,6 +234,9 @@ pub trait StorageContext<'db> {
 
     /// Get raw iterator over storage_cost
     fn raw_iter(&self) -> Self::RawIterator;
+
+    /// Get raw iterator over GroveDB metadata storage_cost
+    fn raw_iter_meta(&self) -> Self::RawIterator;
 }
 
 /// Database batch (not to be confused with multi-tree operations batch).
@@ -355,6 +358,25 @@ impl StorageBatch {
         self.len() == 0
     }
 
+    /// Calls `f` with the prefixed key, the value and the storage cost info
+    /// of every deferred `put` of subtree data.
+    pub fn for_each_data_put(
+        &self,
+        mut f: impl FnMut(&[u8], &[u8], Option<&KeyValueStorageCost>),
+    ) {
+        for operation in self.operations.borrow().data.values() {
+            if let AbstractBatchOperation::Put {
+                key,
+                value,
+                cost_info,
+                ..
+            } = operation
+            {
+                f(key, value, cost_info.as_ref());
+            }
+        }
+    }
+
     /// Add deferred `put` operation
     pub(crate) fn put(
         &self,